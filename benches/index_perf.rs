@@ -42,6 +42,7 @@
 
 use coding_agent_search::indexer::{IndexOptions, run_index};
 use coding_agent_search::search::tantivy::index_dir;
+use coding_agent_search::search::vector_index::Quantization;
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use std::fs;
 use std::io::Write;
@@ -96,6 +97,7 @@ fn bench_index_full(c: &mut Criterion) {
         semantic: false,
         build_hnsw: false,
         embedder: "fastembed".to_string(),
+        quantization: Quantization::F32,
         progress: None,
     };
 
@@ -134,6 +136,7 @@ fn bench_streaming_vs_batch(c: &mut Criterion) {
             semantic: false,
             build_hnsw: false,
             embedder: "fastembed".to_string(),
+            quantization: Quantization::F32,
             progress: None,
         };
 
@@ -200,6 +203,7 @@ fn bench_channel_overhead(c: &mut Criterion) {
         semantic: false,
         build_hnsw: false,
         embedder: "fastembed".to_string(),
+        quantization: Quantization::F32,
         progress: None,
     };
 
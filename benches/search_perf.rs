@@ -309,6 +309,7 @@ fn bench_vector_index_search_50k_filtered(c: &mut Criterion) {
         roles: None,
         created_from: None,
         created_to: None,
+        message_ids: None,
     };
 
     c.bench_function("vector_index_search_50k_filtered", |b| {
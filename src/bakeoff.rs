@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
+use crate::search::vector_index::{Quantization, VectorEntry, VectorIndex};
+
 /// Hard eligibility cutoff: models must be released on/after this date.
 /// Format: YYYY-MM-DD
 pub const ELIGIBILITY_CUTOFF: &str = "2025-11-01";
@@ -637,6 +640,35 @@ impl EvaluationHarness {
         Ok(report)
     }
 
+    /// Compare vector index quantizations using an embedder's corpus vectors.
+    ///
+    /// See [`evaluate_quantizations`]; `ndcg_k` is used as the recall cutoff.
+    pub fn evaluate_quantization<E: crate::search::embedder::Embedder>(
+        &self,
+        embedder: &E,
+        corpus: &EvaluationCorpus,
+    ) -> Result<Vec<QuantizationReport>, String> {
+        let doc_embeddings: Vec<Vec<f32>> = corpus
+            .documents
+            .iter()
+            .map(|d| embedder.embed(&d.content))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        let query_embeddings: Vec<Vec<f32>> = corpus
+            .queries
+            .iter()
+            .map(|q| embedder.embed(&q.query))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+
+        evaluate_quantizations(
+            &doc_embeddings,
+            &query_embeddings,
+            self.config.ndcg_k,
+            self.config.timing_iterations,
+        )
+    }
+
     /// Run a full bake-off comparison with baseline and candidates.
     pub fn run_comparison<E: crate::search::embedder::Embedder>(
         &self,
@@ -755,6 +787,174 @@ pub fn format_comparison_table(comparison: &BakeoffComparison) -> String {
     output
 }
 
+// ==================== Quantization Bake-off ====================
+
+/// Recall, latency and size of one vector index quantization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuantizationReport {
+    /// Storage format ("f32", "f16", "int8" or "binary").
+    pub quantization: String,
+    /// Whether top candidates were rescored against an f16 companion.
+    pub rescored: bool,
+    /// Mean fraction of the exact f32 top-k that this index returned.
+    pub recall_at_k: f64,
+    /// Median search latency in microseconds.
+    pub latency_us_p50: u64,
+    /// 95th percentile search latency in microseconds.
+    pub latency_us_p95: u64,
+    /// Size of the scanned CVVI file in bytes.
+    pub index_bytes: u64,
+    /// Size of the f16 rescoring companion in bytes (0 when not rescored).
+    pub rescore_bytes: u64,
+}
+
+/// Quantizations compared by [`evaluate_quantizations`], with and without rescoring.
+const QUANTIZATION_VARIANTS: [(Quantization, bool); 6] = [
+    (Quantization::F32, false),
+    (Quantization::F16, false),
+    (Quantization::Int8, false),
+    (Quantization::Int8, true),
+    (Quantization::Binary, false),
+    (Quantization::Binary, true),
+];
+
+/// Measure the recall/latency/size tradeoff of each vector index quantization.
+///
+/// Every variant indexes `vectors`; recall@k is measured against an exact
+/// f32 search for each query, latency is timed over `timing_iterations`
+/// searches per query, and sizes are the serialized CVVI bytes.
+pub fn evaluate_quantizations(
+    vectors: &[Vec<f32>],
+    queries: &[Vec<f32>],
+    k: usize,
+    timing_iterations: usize,
+) -> Result<Vec<QuantizationReport>, String> {
+    let dimension = vectors.first().map(Vec::len).ok_or("Empty corpus")?;
+    let entries: Vec<VectorEntry> = vectors
+        .iter()
+        .enumerate()
+        .map(|(idx, vector)| VectorEntry {
+            message_id: idx as u64,
+            created_at_ms: 0,
+            agent_id: 0,
+            workspace_id: 0,
+            source_id: 0,
+            role: 0,
+            chunk_idx: 0,
+            content_hash: [0; 32],
+            vector: vector.clone(),
+        })
+        .collect();
+
+    let exact = VectorIndex::build(
+        "bakeoff",
+        "1",
+        dimension,
+        Quantization::F32,
+        entries.clone(),
+    )
+    .map_err(|e| e.to_string())?;
+    let truth: Vec<HashSet<u64>> = queries
+        .iter()
+        .map(|query| {
+            exact
+                .search_top_k(query, k, None)
+                .map(|hits| hits.iter().map(|h| h.message_id).collect())
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut reports = Vec::with_capacity(QUANTIZATION_VARIANTS.len());
+    for (quantization, rescored) in QUANTIZATION_VARIANTS {
+        let built = if rescored {
+            VectorIndex::build_with_rescore(
+                "bakeoff",
+                "1",
+                dimension,
+                quantization,
+                entries.clone(),
+            )
+        } else {
+            VectorIndex::build("bakeoff", "1", dimension, quantization, entries.clone())
+        };
+        let index = built.map_err(|e| e.to_string())?;
+        let index_bytes = serialized_len(&index)?;
+        let rescore_bytes = match index.rescore_index() {
+            Some(rescore) => serialized_len(rescore)?,
+            None => 0,
+        };
+
+        let mut recall_sum = 0.0;
+        let mut latencies = Vec::with_capacity(queries.len() * timing_iterations.max(1));
+        for (query, expected) in queries.iter().zip(&truth) {
+            let mut hits = Vec::new();
+            for _ in 0..timing_iterations.max(1) {
+                let start = Instant::now();
+                hits = index
+                    .search_top_k(query, k, None)
+                    .map_err(|e| e.to_string())?;
+                latencies.push(start.elapsed().as_micros() as u64);
+            }
+            recall_sum += if expected.is_empty() {
+                1.0
+            } else {
+                let found = hits
+                    .iter()
+                    .filter(|h| expected.contains(&h.message_id))
+                    .count();
+                found as f64 / expected.len() as f64
+            };
+        }
+        latencies.sort_unstable();
+
+        reports.push(QuantizationReport {
+            quantization: quantization.as_str().to_string(),
+            rescored,
+            recall_at_k: if queries.is_empty() {
+                1.0
+            } else {
+                recall_sum / queries.len() as f64
+            },
+            latency_us_p50: percentile(&latencies, 50),
+            latency_us_p95: percentile(&latencies, 95),
+            index_bytes,
+            rescore_bytes,
+        });
+    }
+
+    Ok(reports)
+}
+
+fn serialized_len(index: &VectorIndex) -> Result<u64, String> {
+    let mut buf = Vec::new();
+    index.write_to(&mut buf).map_err(|e| e.to_string())?;
+    Ok(buf.len() as u64)
+}
+
+/// Format quantization reports as a markdown table.
+pub fn format_quantization_table(reports: &[QuantizationReport]) -> String {
+    let mut output = String::new();
+
+    output.push_str("# Quantization Bake-off\n\n");
+    output.push_str("| Quantization | Rescored | Recall@k | P50 (µs) | P95 (µs) | Index (bytes) | Rescore (bytes) |\n");
+    output.push_str("|--------------|----------|----------|----------|----------|---------------|-----------------|\n");
+
+    for r in reports {
+        output.push_str(&format!(
+            "| {} | {} | {:.3} | {} | {} | {} | {} |\n",
+            r.quantization,
+            if r.rescored { "✓" } else { "✗" },
+            r.recall_at_k,
+            r.latency_us_p50,
+            r.latency_us_p95,
+            r.index_bytes,
+            r.rescore_bytes
+        ));
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1149,4 +1349,54 @@ mod tests {
         assert!(table.contains("⭐")); // Winner marker
         assert!(table.contains("Recommendation"));
     }
+
+    fn synthetic_vectors(count: usize, dimension: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                let mut vector: Vec<f32> = (0..dimension)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6364136223846793005)
+                            .wrapping_add(1442695040888963407);
+                        ((state >> 33) as f32 / (1u64 << 31) as f32) - 0.5
+                    })
+                    .collect();
+                let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+                vector.iter_mut().for_each(|v| *v /= norm);
+                vector
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quantization_bakeoff_reports_tradeoff() {
+        let vectors = synthetic_vectors(300, 64, 7);
+        let queries = synthetic_vectors(8, 64, 99);
+        let reports = evaluate_quantizations(&vectors, &queries, 10, 1).expect("bakeoff");
+
+        assert_eq!(reports.len(), QUANTIZATION_VARIANTS.len());
+        let find = |name: &str, rescored: bool| {
+            reports
+                .iter()
+                .find(|r| r.quantization == name && r.rescored == rescored)
+                .expect("variant present")
+        };
+
+        let f32_report = find("f32", false);
+        let int8_rescored = find("int8", true);
+        let binary_rescored = find("binary", true);
+        assert!((f32_report.recall_at_k - 1.0).abs() < 1e-9);
+        assert!(int8_rescored.recall_at_k >= 0.95);
+        assert!(binary_rescored.recall_at_k > find("binary", false).recall_at_k - 1e-9);
+
+        assert!(find("binary", false).index_bytes < find("int8", false).index_bytes);
+        assert!(find("int8", false).index_bytes < find("f16", false).index_bytes);
+        assert!(find("f16", false).index_bytes < f32_report.index_bytes);
+        assert_eq!(find("int8", false).rescore_bytes, 0);
+        assert_eq!(int8_rescored.rescore_bytes, find("f16", false).index_bytes);
+
+        let table = format_quantization_table(&reports);
+        assert!(table.contains("| binary | ✓ |"));
+    }
 }
@@ -2,7 +2,15 @@
 //!
 //! Provides persistent storage for bookmarked search results with user notes
 //! and tags. Uses a separate `SQLite` database file to avoid schema conflicts.
-
+//!
+//! Bookmarks are anchored to the bookmarked message by content hash and
+//! timestamp, so they can be re-resolved against the main index after a
+//! reindex: a bookmark whose session was compacted or moved is relocated when
+//! its message can still be found, and reported as stale otherwise.
+//! [`resolve_bookmarks`] does this in memory for display;
+//! [`BookmarkStore::resolve_against`] also writes the result back.
+
+use crate::search::canonicalize::content_hash_hex;
 use anyhow::{Context, Result};
 use rusqlite::{Connection, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
@@ -34,6 +42,62 @@ pub struct Bookmark {
     pub updated_at: i64,
     /// Original search snippet (for context)
     pub snippet: String,
+    /// SHA-256 of the bookmarked message content (empty if not anchored yet)
+    #[serde(default)]
+    pub content_hash: String,
+    /// Timestamp of the bookmarked message (unix millis), used to find moved sessions
+    #[serde(default)]
+    pub message_created_at: Option<i64>,
+    /// Result of the last re-resolution against the index
+    #[serde(default)]
+    pub status: BookmarkStatus,
+    /// When the bookmark was last re-resolved (unix millis)
+    #[serde(default)]
+    pub resolved_at: Option<i64>,
+}
+
+/// Whether a bookmark still points at the message it was created for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookmarkStatus {
+    /// Not yet checked against the index
+    #[default]
+    Unverified,
+    /// Found at its recorded `source_path`/`line_number`
+    Ok,
+    /// Found after updating `source_path`/`line_number` (session moved or compacted)
+    Relocated,
+    /// The bookmarked message no longer exists in the index
+    Stale,
+}
+
+impl BookmarkStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookmarkStatus::Unverified => "unverified",
+            BookmarkStatus::Ok => "ok",
+            BookmarkStatus::Relocated => "relocated",
+            BookmarkStatus::Stale => "stale",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "ok" => BookmarkStatus::Ok,
+            "relocated" => BookmarkStatus::Relocated,
+            "stale" => BookmarkStatus::Stale,
+            _ => BookmarkStatus::Unverified,
+        }
+    }
+}
+
+/// Counts from one [`resolve_bookmarks`] / [`BookmarkStore::resolve_against`] pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResolveSummary {
+    pub checked: usize,
+    pub ok: usize,
+    pub relocated: usize,
+    pub stale: usize,
 }
 
 impl Bookmark {
@@ -58,6 +122,10 @@ impl Bookmark {
             created_at: now,
             updated_at: now,
             snippet: String::new(),
+            content_hash: String::new(),
+            message_created_at: None,
+            status: BookmarkStatus::Unverified,
+            resolved_at: None,
         }
     }
 
@@ -85,6 +153,13 @@ impl Bookmark {
         self
     }
 
+    /// Anchor the bookmark to the full content of the bookmarked message
+    pub fn with_anchor(mut self, content: &str, created_at: Option<i64>) -> Self {
+        self.content_hash = content_hash_hex(content);
+        self.message_created_at = created_at;
+        self
+    }
+
     /// Get tags as a vector
     pub fn tag_list(&self) -> Vec<&str> {
         self.tags
@@ -125,6 +200,7 @@ impl BookmarkStore {
 
         // Create schema if needed
         conn.execute_batch(SCHEMA)?;
+        add_missing_columns(&conn)?;
 
         Ok(Self { conn })
     }
@@ -138,8 +214,7 @@ impl BookmarkStore {
     /// Add a new bookmark
     pub fn add(&self, bookmark: &Bookmark) -> Result<i64> {
         self.conn.execute(
-            "INSERT INTO bookmarks (title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            INSERT_SQL,
             params![
                 bookmark.title,
                 bookmark.source_path,
@@ -151,6 +226,10 @@ impl BookmarkStore {
                 bookmark.created_at,
                 bookmark.updated_at,
                 bookmark.snippet,
+                bookmark.content_hash,
+                bookmark.message_created_at,
+                bookmark.status.as_str(),
+                bookmark.resolved_at,
             ],
         )?;
 
@@ -187,7 +266,8 @@ impl BookmarkStore {
     pub fn get(&self, id: i64) -> Result<Option<Bookmark>> {
         self.conn
             .query_row(
-                "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet
+                "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet,
+                    content_hash, message_created_at, status, resolved_at
                  FROM bookmarks WHERE id = ?1",
                 [id],
                 row_to_bookmark,
@@ -200,7 +280,8 @@ impl BookmarkStore {
     pub fn list(&self, tag_filter: Option<&str>) -> Result<Vec<Bookmark>> {
         let mut bookmarks = Vec::new();
 
-        let sql = "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet
+        let sql = "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet,
+                    content_hash, message_created_at, status, resolved_at
                    FROM bookmarks ORDER BY created_at DESC";
 
         let mut stmt = self.conn.prepare(sql)?;
@@ -231,7 +312,8 @@ impl BookmarkStore {
         let pattern = format!("%{escaped}%");
 
         let mut stmt = self.conn.prepare(
-            "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet
+            "SELECT id, title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet,
+                    content_hash, message_created_at, status, resolved_at
              FROM bookmarks
              WHERE LOWER(title) LIKE ?1 ESCAPE '\\' OR LOWER(note) LIKE ?1 ESCAPE '\\' OR LOWER(snippet) LIKE ?1 ESCAPE '\\'
              ORDER BY created_at DESC",
@@ -274,6 +356,40 @@ impl BookmarkStore {
         Ok(exists)
     }
 
+    /// Re-resolve all bookmarks against the main index database
+    /// (`agent_search.db`) and save the result.
+    ///
+    /// See [`resolve_bookmarks`] for which bookmarks are checked. Relocated
+    /// bookmarks get their `source_path`/`line_number` rewritten; bookmarks
+    /// whose message is gone are marked stale.
+    pub fn resolve_against(
+        &self,
+        index: &Connection,
+        indexed_at: Option<i64>,
+    ) -> Result<ResolveSummary> {
+        let mut bookmarks = self.list(None)?;
+        let (summary, checked) = resolve_due(index, &mut bookmarks, indexed_at)?;
+        let tx = self.conn.unchecked_transaction()?;
+        for bookmark in checked.into_iter().map(|i| &bookmarks[i]) {
+            tx.execute(
+                "UPDATE bookmarks SET source_path = ?1, line_number = ?2, content_hash = ?3,
+                        message_created_at = ?4, status = ?5, resolved_at = ?6
+                 WHERE id = ?7",
+                params![
+                    bookmark.source_path,
+                    bookmark.line_number.map(|n| n as i64),
+                    bookmark.content_hash,
+                    bookmark.message_created_at,
+                    bookmark.status.as_str(),
+                    bookmark.resolved_at,
+                    bookmark.id
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
+
     /// Export all bookmarks to JSON
     pub fn export_json(&self) -> Result<String> {
         let bookmarks = self.list(None)?;
@@ -293,10 +409,7 @@ impl BookmarkStore {
                 "SELECT EXISTS(SELECT 1 FROM bookmarks WHERE source_path = ?1 AND line_number IS ?2)",
             )?;
 
-            let mut insert_stmt = tx.prepare(
-                "INSERT INTO bookmarks (title, source_path, line_number, agent, workspace, note, tags, created_at, updated_at, snippet)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            )?;
+            let mut insert_stmt = tx.prepare(INSERT_SQL)?;
 
             for mut bookmark in bookmarks {
                 // Check for duplicates
@@ -318,6 +431,10 @@ impl BookmarkStore {
                         bookmark.created_at,
                         bookmark.updated_at,
                         bookmark.snippet,
+                        bookmark.content_hash,
+                        bookmark.message_created_at,
+                        bookmark.status.as_str(),
+                        bookmark.resolved_at,
                     ])?;
                     imported += 1;
                 }
@@ -344,17 +461,243 @@ fn row_to_bookmark(row: &rusqlite::Row) -> rusqlite::Result<Bookmark> {
         created_at: row.get(8)?,
         updated_at: row.get(9)?,
         snippet: row.get(10)?,
+        content_hash: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+        message_created_at: row.get(12)?,
+        status: BookmarkStatus::parse(&row.get::<_, Option<String>>(13)?.unwrap_or_default()),
+        resolved_at: row.get(14)?,
     })
 }
 
+/// Columns added after the original schema; `CREATE TABLE IF NOT EXISTS`
+/// does not add them to existing bookmark databases.
+const ANCHOR_COLUMNS: &[(&str, &str)] = &[
+    ("content_hash", "TEXT DEFAULT ''"),
+    ("message_created_at", "INTEGER"),
+    ("status", "TEXT DEFAULT 'unverified'"),
+    ("resolved_at", "INTEGER"),
+];
+
+fn add_missing_columns(conn: &Connection) -> Result<()> {
+    let mut stmt = conn.prepare("PRAGMA table_info(bookmarks)")?;
+    let existing: Vec<String> = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<_, _>>()?;
+    for (name, decl) in ANCHOR_COLUMNS {
+        if !existing.iter().any(|c| c == name) {
+            conn.execute_batch(&format!("ALTER TABLE bookmarks ADD COLUMN {name} {decl};"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Re-resolve `bookmarks` against the main index database in memory only.
+///
+/// Only bookmarks never resolved, or resolved before `indexed_at` (the index's
+/// `last_indexed_at`, unix millis), are checked; pass `None` to check all.
+/// Nothing is written to the bookmark store, so listing bookmarks never
+/// rewrites them; use [`BookmarkStore::resolve_against`] to persist.
+pub fn resolve_bookmarks(
+    index: &Connection,
+    bookmarks: &mut [Bookmark],
+    indexed_at: Option<i64>,
+) -> Result<ResolveSummary> {
+    Ok(resolve_due(index, bookmarks, indexed_at)?.0)
+}
+
+/// Resolve the bookmarks that are due, returning the summary and their positions.
+fn resolve_due(
+    index: &Connection,
+    bookmarks: &mut [Bookmark],
+    indexed_at: Option<i64>,
+) -> Result<(ResolveSummary, Vec<usize>)> {
+    let mut summary = ResolveSummary::default();
+    let mut checked = Vec::new();
+    let now = current_timestamp();
+    for (i, bookmark) in bookmarks.iter_mut().enumerate() {
+        let due = match (bookmark.resolved_at, indexed_at) {
+            (None, _) | (_, None) => true,
+            (Some(resolved), Some(indexed)) => resolved < indexed,
+        };
+        if !due {
+            continue;
+        }
+        summary.checked += 1;
+        checked.push(i);
+
+        let (status, located) = match locate(index, bookmark)? {
+            Some(loc)
+                if loc.source_path == bookmark.source_path
+                    && loc.line_number == bookmark.line_number =>
+            {
+                // Keep the relocated flag so callers still see the bookmark moved.
+                let status = if bookmark.status == BookmarkStatus::Relocated {
+                    BookmarkStatus::Relocated
+                } else {
+                    BookmarkStatus::Ok
+                };
+                (status, Some(loc))
+            }
+            Some(loc) => (BookmarkStatus::Relocated, Some(loc)),
+            None => (BookmarkStatus::Stale, None),
+        };
+        match status {
+            BookmarkStatus::Ok => summary.ok += 1,
+            BookmarkStatus::Relocated => summary.relocated += 1,
+            _ => summary.stale += 1,
+        }
+
+        if let Some(loc) = located {
+            bookmark.source_path = loc.source_path;
+            bookmark.line_number = loc.line_number;
+            bookmark.content_hash = loc.content_hash;
+            bookmark.message_created_at = loc.message_created_at;
+        }
+        bookmark.status = status;
+        bookmark.resolved_at = Some(now);
+    }
+    Ok((summary, checked))
+}
+
+/// Where a bookmark's message was found (if anywhere) in the main index
+struct Located {
+    source_path: String,
+    line_number: Option<usize>,
+    content_hash: String,
+    message_created_at: Option<i64>,
+}
+
+/// Find the bookmarked message in the main index database.
+///
+/// Lookup order: the recorded path and line; any line of the recorded session
+/// (compaction shifts message indices); any message with the same timestamp
+/// and content hash (the session file moved). Unanchored bookmarks adopt the
+/// message at their recorded location.
+fn locate(index: &Connection, bookmark: &Bookmark) -> Result<Option<Located>> {
+    let Some(line) = bookmark.line_number else {
+        let exists: bool = index.query_row(
+            "SELECT EXISTS(SELECT 1 FROM conversations WHERE source_path = ?1)",
+            [&bookmark.source_path],
+            |row| row.get(0),
+        )?;
+        return Ok(exists.then(|| Located {
+            source_path: bookmark.source_path.clone(),
+            line_number: None,
+            content_hash: bookmark.content_hash.clone(),
+            message_created_at: bookmark.message_created_at,
+        }));
+    };
+
+    let at_line: Option<(String, Option<i64>)> = index
+        .query_row(
+            "SELECT m.content, m.created_at FROM messages m
+             JOIN conversations c ON c.id = m.conversation_id
+             WHERE c.source_path = ?1 AND m.idx = ?2
+             LIMIT 1",
+            params![bookmark.source_path, line as i64 - 1],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    if let Some((content, created_at)) = &at_line {
+        let hash = content_hash_hex(content);
+        if bookmark.content_hash.is_empty() || hash == bookmark.content_hash {
+            return Ok(Some(Located {
+                source_path: bookmark.source_path.clone(),
+                line_number: Some(line),
+                content_hash: hash,
+                message_created_at: *created_at,
+            }));
+        }
+    }
+    if bookmark.content_hash.is_empty() {
+        return Ok(None);
+    }
+
+    let mut same_session = index.prepare(
+        "SELECT c.source_path, m.idx, m.content, m.created_at FROM messages m
+         JOIN conversations c ON c.id = m.conversation_id
+         WHERE c.source_path = ?1
+         ORDER BY m.idx",
+    )?;
+    if let Some(found) = first_hash_match(
+        &mut same_session,
+        params![bookmark.source_path],
+        &bookmark.content_hash,
+    )? {
+        return Ok(Some(found));
+    }
+
+    if let Some(ts) = bookmark.message_created_at {
+        let mut same_time = index.prepare(
+            "SELECT c.source_path, m.idx, m.content, m.created_at FROM messages m
+             JOIN conversations c ON c.id = m.conversation_id
+             WHERE m.created_at = ?1
+             ORDER BY c.source_path, m.idx",
+        )?;
+        return first_hash_match(&mut same_time, params![ts], &bookmark.content_hash);
+    }
+    Ok(None)
+}
+
+fn first_hash_match(
+    stmt: &mut rusqlite::Statement<'_>,
+    params: impl rusqlite::Params,
+    content_hash: &str,
+) -> Result<Option<Located>> {
+    let mut rows = stmt.query(params)?;
+    while let Some(row) = rows.next()? {
+        let content: String = row.get(2)?;
+        if content_hash_hex(&content) == content_hash {
+            return Ok(Some(Located {
+                source_path: row.get(0)?,
+                line_number: Some(row.get::<_, i64>(1)? as usize + 1),
+                content_hash: content_hash.to_string(),
+                message_created_at: row.get(3)?,
+            }));
+        }
+    }
+    Ok(None)
+}
+
 /// Get the default bookmarks database path
 pub fn default_bookmarks_path() -> PathBuf {
-    directories::ProjectDirs::from("com", "coding-agent-search", "coding-agent-search").map_or_else(
-        || PathBuf::from("bookmarks.db"),
-        |dirs| dirs.data_dir().join("bookmarks.db"),
-    )
+    bookmarks_path_in(&crate::default_data_dir())
+}
+
+/// Bookmarks database path for `data_dir`.
+///
+/// Older releases kept bookmarks in the platform data dir even when
+/// `CASS_DATA_DIR` pointed elsewhere. Until `data_dir` has its own
+/// `bookmarks.db`, that file keeps being used so existing bookmarks stay visible.
+pub fn bookmarks_path_in(data_dir: &Path) -> PathBuf {
+    let path = data_dir.join("bookmarks.db");
+    match legacy_bookmarks_path() {
+        Some(legacy) => with_legacy_fallback(path, legacy),
+        None => path,
+    }
 }
 
+/// Where releases before the data-dir move stored bookmarks.
+fn legacy_bookmarks_path() -> Option<PathBuf> {
+    directories::ProjectDirs::from("com", "coding-agent-search", "coding-agent-search")
+        .map(|dirs| dirs.data_dir().join("bookmarks.db"))
+}
+
+/// `path`, or `legacy` when only the legacy file exists.
+fn with_legacy_fallback(path: PathBuf, legacy: PathBuf) -> PathBuf {
+    if path != legacy && !path.exists() && legacy.is_file() {
+        tracing::debug!(
+            legacy = %legacy.display(),
+            "using bookmarks from the pre-data-dir location"
+        );
+        return legacy;
+    }
+    path
+}
+
+const INSERT_SQL: &str = "INSERT INTO bookmarks (title, source_path, line_number, agent, workspace, note, tags,
+                       created_at, updated_at, snippet, content_hash, message_created_at, status, resolved_at)
+     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)";
+
 /// SQL schema for bookmarks database
 const SCHEMA: &str = r"
 CREATE TABLE IF NOT EXISTS bookmarks (
@@ -368,7 +711,11 @@ CREATE TABLE IF NOT EXISTS bookmarks (
     tags TEXT DEFAULT '',
     created_at INTEGER NOT NULL,
     updated_at INTEGER NOT NULL,
-    snippet TEXT DEFAULT ''
+    snippet TEXT DEFAULT '',
+    content_hash TEXT DEFAULT '',
+    message_created_at INTEGER,
+    status TEXT DEFAULT 'unverified',
+    resolved_at INTEGER
 );
 
 CREATE INDEX IF NOT EXISTS idx_bookmarks_source ON bookmarks(source_path, line_number);
@@ -398,6 +745,37 @@ mod tests {
         (store, dir)
     }
 
+    #[test]
+    fn test_legacy_bookmarks_used_until_data_dir_has_its_own() {
+        let legacy_dir = tempdir().unwrap();
+        let data_dir = tempdir().unwrap();
+        let legacy = legacy_dir.path().join("bookmarks.db");
+        let path = data_dir.path().join("bookmarks.db");
+
+        // Nothing anywhere: the data dir is used.
+        assert_eq!(with_legacy_fallback(path.clone(), legacy.clone()), path);
+
+        {
+            let store = BookmarkStore::open(&legacy).unwrap();
+            store
+                .add(&Bookmark::new(
+                    "Old",
+                    "/path/a.jsonl",
+                    "claude_code",
+                    "/work",
+                ))
+                .unwrap();
+        }
+        let resolved = with_legacy_fallback(path.clone(), legacy.clone());
+        assert_eq!(resolved, legacy);
+        let store = BookmarkStore::open(&resolved).unwrap();
+        assert_eq!(store.list(None).unwrap()[0].title, "Old");
+
+        // Once the data dir has a bookmarks.db, it wins.
+        BookmarkStore::open(&path).unwrap();
+        assert_eq!(with_legacy_fallback(path.clone(), legacy), path);
+    }
+
     #[test]
     fn test_create_bookmark() {
         let bookmark = Bookmark::new("Test", "/path/file.rs", "claude_code", "/workspace")
@@ -525,4 +903,161 @@ mod tests {
         assert_eq!(imported, 2);
         assert_eq!(store2.count().unwrap(), 2);
     }
+
+    fn index_db(messages: &[(&str, i64, &str, i64)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE conversations (id INTEGER PRIMARY KEY, source_path TEXT NOT NULL);
+             CREATE TABLE messages (id INTEGER PRIMARY KEY, conversation_id INTEGER NOT NULL,
+                                    idx INTEGER NOT NULL, content TEXT NOT NULL, created_at INTEGER);",
+        )
+        .unwrap();
+        for (path, idx, content, created_at) in messages {
+            let conv_id: i64 = conn
+                .query_row(
+                    "SELECT id FROM conversations WHERE source_path = ?1",
+                    [path],
+                    |row| row.get(0),
+                )
+                .optional()
+                .unwrap()
+                .unwrap_or_else(|| {
+                    conn.execute(
+                        "INSERT INTO conversations (source_path) VALUES (?1)",
+                        [path],
+                    )
+                    .unwrap();
+                    conn.last_insert_rowid()
+                });
+            conn.execute(
+                "INSERT INTO messages (conversation_id, idx, content, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![conv_id, idx, content, created_at],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_resolve_ok_and_adopts_anchor() {
+        let (store, _dir) = test_store();
+        let index = index_db(&[("/s.jsonl", 0, "hello", 100), ("/s.jsonl", 1, "world", 200)]);
+        let id = store
+            .add(&Bookmark::new("B", "/s.jsonl", "codex", "/w").with_line(2))
+            .unwrap();
+
+        let summary = store.resolve_against(&index, None).unwrap();
+        assert_eq!(summary.ok, 1);
+        let b = store.get(id).unwrap().unwrap();
+        assert_eq!(b.status, BookmarkStatus::Ok);
+        assert_eq!(b.content_hash, content_hash_hex("world"));
+        assert_eq!(b.message_created_at, Some(200));
+        assert!(b.resolved_at.is_some());
+    }
+
+    #[test]
+    fn test_resolve_relocates_compacted_and_moved_sessions() {
+        let (store, _dir) = test_store();
+        let shifted = store
+            .add(
+                &Bookmark::new("Shifted", "/s.jsonl", "codex", "/w")
+                    .with_line(3)
+                    .with_anchor("target", Some(300)),
+            )
+            .unwrap();
+        let moved = store
+            .add(
+                &Bookmark::new("Moved", "/old.jsonl", "codex", "/w")
+                    .with_line(1)
+                    .with_anchor("elsewhere", Some(500)),
+            )
+            .unwrap();
+        // Compaction dropped the first message; /old.jsonl now lives at /new.jsonl.
+        let index = index_db(&[
+            ("/s.jsonl", 0, "second", 200),
+            ("/s.jsonl", 1, "target", 300),
+            ("/new.jsonl", 0, "elsewhere", 500),
+        ]);
+
+        let summary = store.resolve_against(&index, None).unwrap();
+        assert_eq!(summary.relocated, 2);
+        let b = store.get(shifted).unwrap().unwrap();
+        assert_eq!(b.status, BookmarkStatus::Relocated);
+        assert_eq!(b.line_number, Some(2));
+        let b = store.get(moved).unwrap().unwrap();
+        assert_eq!(b.source_path, "/new.jsonl");
+        assert_eq!(b.line_number, Some(1));
+    }
+
+    #[test]
+    fn test_resolve_marks_stale_and_skips_fresh() {
+        let (store, _dir) = test_store();
+        let id = store
+            .add(
+                &Bookmark::new("Gone", "/s.jsonl", "codex", "/w")
+                    .with_line(1)
+                    .with_anchor("deleted", Some(100)),
+            )
+            .unwrap();
+        let index = index_db(&[("/s.jsonl", 0, "replaced", 100)]);
+
+        let summary = store.resolve_against(&index, None).unwrap();
+        assert_eq!(summary.stale, 1);
+        let b = store.get(id).unwrap().unwrap();
+        assert_eq!(b.status, BookmarkStatus::Stale);
+        assert_eq!(b.source_path, "/s.jsonl");
+
+        // Resolved after the last index run: nothing to re-check.
+        let summary = store.resolve_against(&index, Some(0)).unwrap();
+        assert_eq!(summary.checked, 0);
+    }
+
+    #[test]
+    fn test_resolve_bookmarks_does_not_write_to_store() {
+        let (store, _dir) = test_store();
+        let id = store
+            .add(
+                &Bookmark::new("Moved", "/old.jsonl", "codex", "/w")
+                    .with_line(1)
+                    .with_anchor("elsewhere", Some(500)),
+            )
+            .unwrap();
+        let index = index_db(&[("/new.jsonl", 0, "elsewhere", 500)]);
+
+        let mut bookmarks = store.list(None).unwrap();
+        let summary = resolve_bookmarks(&index, &mut bookmarks, None).unwrap();
+        assert_eq!(summary.relocated, 1);
+        assert_eq!(bookmarks[0].source_path, "/new.jsonl");
+        assert_eq!(bookmarks[0].status, BookmarkStatus::Relocated);
+
+        let stored = store.get(id).unwrap().unwrap();
+        assert_eq!(stored.source_path, "/old.jsonl");
+        assert_eq!(stored.status, BookmarkStatus::Unverified);
+        assert!(stored.resolved_at.is_none());
+    }
+
+    #[test]
+    fn test_open_migrates_old_schema() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("old.db");
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE bookmarks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, title TEXT NOT NULL,
+                    source_path TEXT NOT NULL, line_number INTEGER, agent TEXT NOT NULL,
+                    workspace TEXT NOT NULL, note TEXT DEFAULT '', tags TEXT DEFAULT '',
+                    created_at INTEGER NOT NULL, updated_at INTEGER NOT NULL, snippet TEXT DEFAULT '');
+                 INSERT INTO bookmarks (title, source_path, agent, workspace, created_at, updated_at)
+                 VALUES ('Old', '/a.rs', 'codex', '/w', 1, 1);",
+            )
+            .unwrap();
+        }
+
+        let store = BookmarkStore::open(&path).unwrap();
+        let all = store.list(None).unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].status, BookmarkStatus::Unverified);
+        assert!(all[0].content_hash.is_empty());
+    }
 }
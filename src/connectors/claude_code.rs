@@ -1,12 +1,14 @@
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufRead, Read};
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::connectors::{
-    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    Connector, CursorMap, DetectionResult, FileCursor, NormalizedConversation, NormalizedMessage,
+    ScanContext, ScanItem, ScanIter, read_jsonl_from,
 };
 
 pub struct ClaudeCodeConnector;
@@ -43,6 +45,31 @@ impl Connector for ClaudeCodeConnector {
     }
 
     fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let mut convs = Vec::new();
+        for path in Self::session_files(ctx) {
+            if let Some(conversation) =
+                Self::parse_session_file(&path, None)?.and_then(|item| item.conversation)
+            {
+                convs.push(conversation);
+            }
+        }
+        Ok(convs)
+    }
+
+    fn scan_incremental<'a>(
+        &'a self,
+        ctx: &'a ScanContext,
+        cursors: &'a CursorMap,
+    ) -> Result<ScanIter<'a>> {
+        Ok(Box::new(Self::session_files(ctx).into_iter().filter_map(
+            move |path| Self::parse_session_file(&path, cursors.get(&path)).transpose(),
+        )))
+    }
+}
+
+impl ClaudeCodeConnector {
+    /// Roots to walk for this scan context.
+    fn scan_roots(ctx: &ScanContext) -> Vec<PathBuf> {
         // Use data_root only if it looks like a Claude projects directory (for testing)
         // Otherwise use the default projects_root
         let looks_like_root = |path: &PathBuf| {
@@ -52,7 +79,7 @@ impl Connector for ClaudeCodeConnector {
                     .is_some_and(|n| n.to_str().unwrap_or("").contains("claude"))
         };
 
-        let roots: Vec<PathBuf> = if ctx.use_default_detection() {
+        if ctx.use_default_detection() {
             if looks_like_root(&ctx.data_dir) {
                 vec![ctx.data_dir.clone()]
             } else {
@@ -61,12 +88,14 @@ impl Connector for ClaudeCodeConnector {
         } else {
             // Explicit roots (remote mirrors, etc.) - trust the configuration
             ctx.scan_roots.iter().map(|r| r.path.clone()).collect()
-        };
+        }
+    }
 
-        let mut convs = Vec::new();
-        let mut file_count = 0;
+    /// Session files under the scan roots that changed since `ctx.since_ts`.
+    fn session_files(ctx: &ScanContext) -> Vec<PathBuf> {
+        let mut files = Vec::new();
 
-        for root in roots {
+        for root in Self::scan_roots(ctx) {
             let scan_target = if root.is_file() {
                 root.parent().unwrap_or(&root).to_path_buf()
             } else {
@@ -89,258 +118,316 @@ impl Connector for ClaudeCodeConnector {
                 if !crate::connectors::file_modified_since(entry.path(), ctx.since_ts) {
                     continue;
                 }
-                file_count += 1;
-                if file_count <= 3 {
+                if files.len() < 3 {
                     tracing::debug!(path = %entry.path().display(), "claude_code found file");
                 }
+                files.push(entry.into_path());
+            }
+        }
 
-                let mut messages = Vec::new();
-                let mut started_at: Option<i64> = None;
-                let mut ended_at: Option<i64> = None;
-                // Track workspace from first entry's cwd field
-                let mut workspace: Option<PathBuf> = None;
-                let mut session_id: Option<String> = None;
-                let mut git_branch: Option<String> = None;
-                let mut json_title: Option<String> = None;
-
-                if ext == Some("jsonl") {
-                    let file = std::fs::File::open(entry.path())
-                        .with_context(|| format!("open {}", entry.path().display()))?;
-                    let reader = std::io::BufReader::new(file);
-
-                    for line_res in std::io::BufRead::lines(reader) {
-                        let line = match line_res {
-                            Ok(l) => l,
-                            Err(_) => continue,
-                        };
-                        if line.trim().is_empty() {
-                            continue;
-                        }
-                        let val: Value = match serde_json::from_str(&line) {
-                            Ok(v) => v,
-                            Err(_) => continue, // Skip malformed lines
-                        };
-
-                        // Extract session metadata from first available entry
-                        if workspace.is_none() {
-                            workspace = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
-                        }
-                        if session_id.is_none() {
-                            session_id = val
-                                .get("sessionId")
-                                .and_then(|v| v.as_str())
-                                .map(String::from);
-                        }
-                        if git_branch.is_none() {
-                            git_branch = val
-                                .get("gitBranch")
-                                .and_then(|v| v.as_str())
-                                .map(String::from);
-                        }
-
-                        // Filter to user/assistant entries only (skip summary, file-history-snapshot, etc.)
-                        let entry_type = val.get("type").and_then(|v| v.as_str());
-                        let role_hint = val
-                            .get("message")
-                            .and_then(|m| m.get("role"))
-                            .and_then(|v| v.as_str())
-                            .or_else(|| val.get("role").and_then(|v| v.as_str()));
-                        let is_user_assistant = matches!(entry_type, Some("user" | "assistant"))
-                            || (entry_type == Some("message")
-                                && matches!(role_hint, Some("user" | "assistant")));
-                        if !is_user_assistant {
-                            continue;
-                        }
-
-                        // Parse ISO-8601 timestamp using shared utility
-                        let created = val
-                            .get("timestamp")
-                            .and_then(crate::connectors::parse_timestamp);
-
-                        // NOTE: Do NOT filter individual messages by timestamp here!
-                        // The file-level check in file_modified_since() is sufficient.
-                        // Filtering messages would cause older messages to be lost when
-                        // the file is re-indexed after new messages are added.
-
-                        started_at = started_at.or(created);
-                        // Track the latest timestamp seen (robust against out-of-order logs)
-                        ended_at = match (ended_at, created) {
-                            (Some(curr), Some(ts)) => Some(curr.max(ts)),
-                            (None, Some(ts)) => Some(ts),
-                            (Some(curr), None) => Some(curr),
-                            (None, None) => None,
-                        };
-
-                        // Role from message.role, top-level role, or entry type
-                        let role = role_hint.or(entry_type).unwrap_or("agent");
-
-                        // Content from message.content or top-level content (may be string or array)
-                        let content_val = val
-                            .get("message")
-                            .and_then(|m| m.get("content"))
-                            .or_else(|| val.get("content"));
-                        let content_str = content_val
-                            .map(crate::connectors::flatten_content)
-                            .unwrap_or_default();
-
-                        // Skip entries with empty content
-                        if content_str.trim().is_empty() {
-                            continue;
-                        }
-
-                        // Extract model name for author field
-                        let author = val
-                            .get("message")
-                            .and_then(|m| m.get("model"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from);
-
-                        messages.push(NormalizedMessage {
-                            idx: 0, // will be re-assigned after filtering
-                            role: role.to_string(),
-                            author,
-                            created_at: created,
-                            content: content_str,
-                            extra: val,
-                            snippets: Vec::new(),
-                        });
-                    }
-                    // Re-assign sequential indices after filtering
-                    super::reindex_messages(&mut messages);
-                } else {
-                    // Safety check: Don't read files larger than 100MB to avoid OOM
-                    if let Ok(metadata) = fs::metadata(entry.path())
-                        && metadata.len() > 100 * 1024 * 1024
-                    {
-                        tracing::debug!(
-                            path = %entry.path().display(),
-                            size_bytes = metadata.len(),
-                            "skipping large file (>100MB)"
-                        );
-                        continue;
-                    }
-
-                    let content_string = fs::read_to_string(entry.path())
-                        .with_context(|| format!("read {}", entry.path().display()))?;
-                    // JSON or Claude format files
-                    let val: Value = match serde_json::from_str(&content_string) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            tracing::debug!(path = %entry.path().display(), error = %e, "claude_code skipping malformed JSON");
-                            continue;
-                        }
-                    };
-
-                    // Extract title from root object if present
-                    json_title = val.get("title").and_then(|t| t.as_str()).map(String::from);
-
-                    if let Some(arr) = val.get("messages").and_then(|m| m.as_array()) {
-                        for item in arr {
-                            let role = item
-                                .get("role")
-                                .or_else(|| item.get("type"))
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("agent");
-
-                            // Use parse_timestamp for consistent handling of both i64 and ISO-8601
-                            let created = item
-                                .get("timestamp")
-                                .or_else(|| item.get("time"))
-                                .and_then(crate::connectors::parse_timestamp);
-
-                            // NOTE: Do NOT filter individual messages by timestamp.
-                            // File-level check is sufficient for incremental indexing.
-
-                            started_at = started_at.or(created);
-                            // Track the latest timestamp seen
-                            ended_at = match (ended_at, created) {
-                                (Some(curr), Some(ts)) => Some(curr.max(ts)),
-                                (None, Some(ts)) => Some(ts),
-                                (Some(curr), None) => Some(curr),
-                                (None, None) => None,
-                            };
-
-                            // Use flatten_content for consistent handling of both string and array content
-                            let content_str = item
-                                .get("content")
-                                .or_else(|| item.get("text"))
-                                .map(crate::connectors::flatten_content)
-                                .unwrap_or_default();
-
-                            // Skip entries with empty content
-                            if content_str.trim().is_empty() {
-                                continue;
-                            }
-
-                            messages.push(NormalizedMessage {
-                                idx: 0, // will be re-assigned after filtering
-                                role: role.to_string(),
-                                author: None,
-                                created_at: created,
-                                content: content_str,
-                                extra: item.clone(),
-                                snippets: Vec::new(),
-                            });
-                        }
-                    }
-                    // Re-assign sequential indices after filtering
-                    super::reindex_messages(&mut messages);
-                }
-                if messages.is_empty() {
-                    if file_count <= 3 {
-                        tracing::debug!(path = %entry.path().display(), "claude_code no messages extracted");
-                    }
+        files
+    }
+
+    /// Parse one session file. JSONL files resume from `cursor` when it still applies.
+    fn parse_session_file(path: &Path, cursor: Option<&FileCursor>) -> Result<Option<ScanItem>> {
+        let ext = path.extension().and_then(|s| s.to_str());
+        let mut next_cursor = None;
+        let mut appended = false;
+
+        let session = if ext == Some("jsonl") {
+            let mut session = JsonlSession::default();
+            let read = read_jsonl_from(path, cursor, |val| session.push_entry(val))
+                .with_context(|| format!("open {}", path.display()))?;
+            if read.resumed {
+                // Only the tail was parsed; recover the header from the head of the file.
+                session.inherit_header(JsonlSession::read_head(path, read.cursor.offset)?);
+                appended = true;
+            }
+            let start_idx = read.cursor.next_idx;
+            for (i, msg) in session.messages.iter_mut().enumerate() {
+                msg.idx = start_idx + i as i64;
+            }
+            next_cursor = Some(FileCursor {
+                next_idx: start_idx + session.messages.len() as i64,
+                ..read.cursor
+            });
+            session
+        } else {
+            match Self::parse_json_file(path)? {
+                Some(session) => session,
+                None => return Ok(None),
+            }
+        };
+
+        if session.messages.is_empty() {
+            tracing::debug!(path = %path.display(), "claude_code no messages extracted");
+            // Still move past an appended tail of non-message entries.
+            return Ok(next_cursor
+                .filter(|next| appended && Some(next) != cursor)
+                .map(|next| ScanItem::cursor_only(path.to_path_buf(), next)));
+        }
+        tracing::debug!(path = %path.display(), messages = session.messages.len(), "claude_code extracted messages");
+
+        // Extract title: use explicit JSON title, or fallback to first user message
+        let title = session.title.clone().or_else(|| {
+            // Fallback to workspace directory name
+            session
+                .workspace
+                .as_ref()
+                .and_then(|p| p.file_name())
+                .and_then(|n| n.to_str())
+                .map(String::from)
+        });
+
+        Ok(Some(ScanItem {
+            source_path: path.to_path_buf(),
+            conversation: Some(NormalizedConversation {
+                agent_slug: "claude_code".into(),
+                external_id: path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .map(std::string::ToString::to_string),
+                title,
+                workspace: session.workspace, // Now populated from cwd field!
+                source_path: path.to_path_buf(),
+                started_at: session.started_at,
+                ended_at: session.ended_at,
+                metadata: serde_json::json!({
+                    "source": "claude_code",
+                    "sessionId": session.session_id,
+                    "gitBranch": session.git_branch
+                }),
+                messages: session.messages,
+            }),
+            cursor: next_cursor,
+            appended,
+        }))
+    }
+
+    /// Parse a whole-file JSON (or `.claude`) session export.
+    fn parse_json_file(path: &Path) -> Result<Option<JsonlSession>> {
+        // Safety check: Don't read files larger than 100MB to avoid OOM
+        if let Ok(metadata) = fs::metadata(path)
+            && metadata.len() > 100 * 1024 * 1024
+        {
+            tracing::debug!(
+                path = %path.display(),
+                size_bytes = metadata.len(),
+                "skipping large file (>100MB)"
+            );
+            return Ok(None);
+        }
+
+        let content_string =
+            fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        // JSON or Claude format files
+        let val: Value = match serde_json::from_str(&content_string) {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::debug!(path = %path.display(), error = %e, "claude_code skipping malformed JSON");
+                return Ok(None);
+            }
+        };
+
+        let mut session = JsonlSession {
+            // Extract title from root object if present
+            title: val.get("title").and_then(|t| t.as_str()).map(String::from),
+            ..Default::default()
+        };
+
+        if let Some(arr) = val.get("messages").and_then(|m| m.as_array()) {
+            for item in arr {
+                let role = item
+                    .get("role")
+                    .or_else(|| item.get("type"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("agent");
+
+                // Use parse_timestamp for consistent handling of both i64 and ISO-8601
+                let created = item
+                    .get("timestamp")
+                    .or_else(|| item.get("time"))
+                    .and_then(crate::connectors::parse_timestamp);
+
+                // NOTE: Do NOT filter individual messages by timestamp.
+                // File-level check is sufficient for incremental indexing.
+                session.observe_timestamp(created);
+
+                // Use flatten_content for consistent handling of both string and array content
+                let content_str = item
+                    .get("content")
+                    .or_else(|| item.get("text"))
+                    .map(crate::connectors::flatten_content)
+                    .unwrap_or_default();
+
+                // Skip entries with empty content
+                if content_str.trim().is_empty() {
                     continue;
                 }
-                tracing::debug!(path = %entry.path().display(), messages = messages.len(), "claude_code extracted messages");
-
-                // Extract title: use explicit JSON title, or fallback to first user message
-                let title = json_title.or_else(|| {
-                    messages
-                        .iter()
-                        .find(|m| m.role == "user")
-                        .map(|m| {
-                            m.content
-                                .lines()
-                                .next()
-                                .unwrap_or(&m.content)
-                                .chars()
-                                .take(100)
-                                .collect::<String>()
-                        })
-                        .or_else(|| {
-                            // Fallback to workspace directory name
-                            workspace
-                                .as_ref()
-                                .and_then(|p| p.file_name())
-                                .and_then(|n| n.to_str())
-                                .map(String::from)
-                        })
-                });
 
-                convs.push(NormalizedConversation {
-                    agent_slug: "claude_code".into(),
-                    external_id: entry
-                        .path()
-                        .file_name()
-                        .and_then(|s| s.to_str())
-                        .map(std::string::ToString::to_string),
-                    title,
-                    workspace, // Now populated from cwd field!
-                    source_path: entry.path().to_path_buf(),
-                    started_at,
-                    ended_at,
-                    metadata: serde_json::json!({
-                        "source": "claude_code",
-                        "sessionId": session_id,
-                        "gitBranch": git_branch
-                    }),
-                    messages,
+                session.push_message(NormalizedMessage {
+                    idx: 0, // will be re-assigned after filtering
+                    role: role.to_string(),
+                    author: None,
+                    created_at: created,
+                    content: content_str,
+                    extra: item.clone(),
+                    snippets: Vec::new(),
                 });
             }
         }
+        // Re-assign sequential indices after filtering
+        super::reindex_messages(&mut session.messages);
+        Ok(Some(session))
+    }
+}
 
-        Ok(convs)
+/// Messages and session metadata accumulated from Claude Code entries.
+#[derive(Default)]
+struct JsonlSession {
+    messages: Vec<NormalizedMessage>,
+    started_at: Option<i64>,
+    ended_at: Option<i64>,
+    // Session metadata from the first entries that carry it
+    workspace: Option<PathBuf>,
+    session_id: Option<String>,
+    git_branch: Option<String>,
+    /// Explicit title, or the first line of the first user message.
+    title: Option<String>,
+}
+
+impl JsonlSession {
+    /// Parse entries from the start of `path` up to `limit` bytes, stopping once the
+    /// first user message (and with it the session header) has been seen.
+    fn read_head(path: &Path, limit: u64) -> Result<Self> {
+        let file = fs::File::open(path).with_context(|| format!("open {}", path.display()))?;
+        let reader = std::io::BufReader::new(file.take(limit));
+        let mut head = Self::default();
+        for line in reader.split(b'\n').map_while(std::result::Result::ok) {
+            if let Ok(val) = serde_json::from_slice::<Value>(&line) {
+                head.push_entry(val);
+            }
+            if head.title.is_some() {
+                break;
+            }
+        }
+        Ok(head)
+    }
+
+    /// Take header fields from the head of the file over those seen in the tail.
+    fn inherit_header(&mut self, head: Self) {
+        self.started_at = head.started_at.or(self.started_at);
+        self.workspace = head.workspace.or(self.workspace.take());
+        self.session_id = head.session_id.or(self.session_id.take());
+        self.git_branch = head.git_branch.or(self.git_branch.take());
+        self.title = head.title.or(self.title.take());
+    }
+
+    fn observe_timestamp(&mut self, created: Option<i64>) {
+        self.started_at = self.started_at.or(created);
+        // Track the latest timestamp seen (robust against out-of-order logs)
+        self.ended_at = match (self.ended_at, created) {
+            (Some(curr), Some(ts)) => Some(curr.max(ts)),
+            (None, Some(ts)) => Some(ts),
+            (Some(curr), None) => Some(curr),
+            (None, None) => None,
+        };
+    }
+
+    fn push_message(&mut self, msg: NormalizedMessage) {
+        if self.title.is_none() && msg.role == "user" {
+            self.title = Some(
+                msg.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&msg.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>(),
+            );
+        }
+        self.messages.push(msg);
+    }
+
+    /// Handle one JSONL entry.
+    fn push_entry(&mut self, val: Value) {
+        // Extract session metadata from first available entry
+        if self.workspace.is_none() {
+            self.workspace = val.get("cwd").and_then(|v| v.as_str()).map(PathBuf::from);
+        }
+        if self.session_id.is_none() {
+            self.session_id = val
+                .get("sessionId")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+        }
+        if self.git_branch.is_none() {
+            self.git_branch = val
+                .get("gitBranch")
+                .and_then(|v| v.as_str())
+                .map(String::from);
+        }
+
+        // Filter to user/assistant entries only (skip summary, file-history-snapshot, etc.)
+        let entry_type = val.get("type").and_then(|v| v.as_str());
+        let role_hint = val
+            .get("message")
+            .and_then(|m| m.get("role"))
+            .and_then(|v| v.as_str())
+            .or_else(|| val.get("role").and_then(|v| v.as_str()));
+        let is_user_assistant = matches!(entry_type, Some("user" | "assistant"))
+            || (entry_type == Some("message") && matches!(role_hint, Some("user" | "assistant")));
+        if !is_user_assistant {
+            return;
+        }
+
+        // Parse ISO-8601 timestamp using shared utility
+        let created = val
+            .get("timestamp")
+            .and_then(crate::connectors::parse_timestamp);
+
+        // NOTE: Do NOT filter individual messages by timestamp here!
+        // The file-level check in file_modified_since() is sufficient.
+        // Filtering messages would cause older messages to be lost when
+        // the file is re-indexed after new messages are added.
+        self.observe_timestamp(created);
+
+        // Role from message.role, top-level role, or entry type
+        let role = role_hint.or(entry_type).unwrap_or("agent").to_string();
+
+        // Content from message.content or top-level content (may be string or array)
+        let content_val = val
+            .get("message")
+            .and_then(|m| m.get("content"))
+            .or_else(|| val.get("content"));
+        let content_str = content_val
+            .map(crate::connectors::flatten_content)
+            .unwrap_or_default();
+
+        // Tool output (exit status) belongs to the assistant message that made the call
+        crate::connectors::fold_tool_results(&mut self.messages, &val);
+
+        // Skip entries with empty content
+        if content_str.trim().is_empty() {
+            return;
+        }
+
+        // Extract model name for author field
+        let author = val
+            .get("message")
+            .and_then(|m| m.get("model"))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        self.push_message(NormalizedMessage {
+            idx: 0, // assigned once the file has been read
+            role,
+            author,
+            created_at: created,
+            content: content_str,
+            extra: val,
+            snippets: Vec::new(),
+        });
     }
 }
 
@@ -984,6 +1071,32 @@ mod tests {
         assert_eq!(convs[0].messages[0].extra["customField"], "customValue");
     }
 
+    #[test]
+    fn scan_folds_tool_results_into_calling_message() {
+        let dir = TempDir::new().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+
+        let content = [
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo build"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","is_error":true,"content":"Exit code 101"}]}}"#,
+        ]
+        .join("\n");
+        fs::write(claude_dir.join("session.jsonl"), content).unwrap();
+
+        let connector = ClaudeCodeConnector::new();
+        let ctx = ScanContext::local_default(claude_dir.clone(), None);
+        let convs = connector.scan(&ctx).unwrap();
+
+        // The tool_result-only entry is not indexed, but its outcome is kept
+        assert_eq!(convs[0].messages.len(), 1);
+        let events = crate::connectors::extract_tool_events(&convs[0].messages[0].extra);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].command.as_deref(), Some("cargo build"));
+        assert_eq!(events[0].exit_code, Some(101));
+        assert_eq!(events[0].failed, Some(true));
+    }
+
     #[test]
     fn scan_tracks_started_and_ended_timestamps() {
         let dir = TempDir::new().unwrap();
@@ -1464,4 +1577,113 @@ mod tests {
             "second line should be extractable regardless of BOM"
         );
     }
+
+    // =========================================================================
+    // Incremental scan tests
+    // =========================================================================
+
+    fn incremental_items(claude_dir: &Path, cursors: &CursorMap) -> Vec<ScanItem> {
+        let connector = ClaudeCodeConnector::new();
+        let ctx = ScanContext::local_default(claude_dir.to_path_buf(), None);
+        connector
+            .scan_incremental(&ctx, cursors)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn scan_incremental_parses_only_appended_lines() {
+        let dir = TempDir::new().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+
+        let session_file = claude_dir.join("session.jsonl");
+        fs::write(
+            &session_file,
+            r#"{"type":"user","cwd":"/work/app","sessionId":"s-1","gitBranch":"main","timestamp":"2025-12-01T10:00:00Z","message":{"role":"user","content":"Fix the login bug"}}
+{"type":"assistant","timestamp":"2025-12-01T10:00:01Z","message":{"role":"assistant","content":"Looking at auth.rs"}}
+"#,
+        )
+        .unwrap();
+
+        let first = incremental_items(&claude_dir, &CursorMap::new());
+        assert_eq!(first.len(), 1);
+        assert!(!first[0].appended);
+        let cursor = first[0].cursor.expect("jsonl files carry a cursor");
+        assert_eq!(cursor.next_idx, 2);
+        assert_eq!(cursor.offset, fs::metadata(&session_file).unwrap().len());
+
+        let mut f = fs::OpenOptions::new()
+            .append(true)
+            .open(&session_file)
+            .unwrap();
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"type":"user","timestamp":"2025-12-01T10:05:00Z","message":{"role":"user","content":"Now add a test"}}
+"#,
+        )
+        .unwrap();
+
+        let cursors = CursorMap::from([(session_file.clone(), cursor)]);
+        let second = incremental_items(&claude_dir, &cursors);
+        assert_eq!(second.len(), 1);
+        let item = &second[0];
+        assert!(item.appended);
+        let conv = item.conversation.as_ref().unwrap();
+        assert_eq!(conv.messages.len(), 1);
+        assert_eq!(conv.messages[0].idx, 2);
+        assert_eq!(conv.messages[0].content, "Now add a test");
+        // Header comes from the head of the file, not the appended tail
+        assert_eq!(conv.title.as_deref(), Some("Fix the login bug"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/work/app")));
+        assert_eq!(conv.metadata["sessionId"], "s-1");
+        assert_eq!(conv.external_id.as_deref(), Some("session.jsonl"));
+        assert_eq!(item.cursor.unwrap().next_idx, 3);
+
+        // Nothing new after the cursor: no item
+        let cursors = CursorMap::from([(session_file, item.cursor.unwrap())]);
+        assert!(incremental_items(&claude_dir, &cursors).is_empty());
+    }
+
+    #[test]
+    fn scan_incremental_advances_cursor_past_non_message_tail() {
+        let dir = TempDir::new().unwrap();
+        let claude_dir = dir.path().join(".claude");
+        fs::create_dir_all(&claude_dir).unwrap();
+
+        let session_file = claude_dir.join("session.jsonl");
+        fs::write(
+            &session_file,
+            r#"{"type":"user","cwd":"/work/app","sessionId":"s-1","timestamp":"2025-12-01T10:00:00Z","message":{"role":"user","content":"Fix the login bug"}}
+"#,
+        )
+        .unwrap();
+        let first = incremental_items(&claude_dir, &CursorMap::new());
+        let cursor = first[0].cursor.unwrap();
+
+        // A summary entry carries no message, but the cursor must still move past it.
+        let mut f = fs::OpenOptions::new()
+            .append(true)
+            .open(&session_file)
+            .unwrap();
+        std::io::Write::write_all(
+            &mut f,
+            br#"{"type":"summary","summary":"Login bug","leafUuid":"u-1"}
+"#,
+        )
+        .unwrap();
+
+        let cursors = CursorMap::from([(session_file.clone(), cursor)]);
+        let second = incremental_items(&claude_dir, &cursors);
+        assert_eq!(second.len(), 1);
+        assert!(second[0].conversation.is_none());
+        assert_eq!(second[0].source_path, session_file);
+        let advanced = second[0].cursor.unwrap();
+        assert_eq!(advanced.offset, fs::metadata(&session_file).unwrap().len());
+        assert_eq!(advanced.next_idx, cursor.next_idx);
+
+        let cursors = CursorMap::from([(session_file, advanced)]);
+        assert!(incremental_items(&claude_dir, &cursors).is_empty());
+    }
 }
@@ -36,12 +36,9 @@ impl ClineConnector {
             base.join("Library/Application Support/Cursor/User/globalStorage"),
             base.join("AppData/Roaming/Cursor/User/globalStorage"),
         ];
-        let extensions = ["saoudrizwan.claude-dev", "rooveterinaryinc.roo-cline"];
-
+        // Roo Code (`rooveterinaryinc.roo-cline`) has its own connector.
         for root in code_roots.iter().chain(cursor_roots.iter()) {
-            for ext in &extensions {
-                roots.push(root.join(ext));
-            }
+            roots.push(root.join("saoudrizwan.claude-dev"));
         }
 
         roots
@@ -69,7 +66,7 @@ impl ClineConnector {
         if path
             .file_name()
             .and_then(|n| n.to_str())
-            .is_some_and(|n| n.contains("claude-dev") || n.contains("roo-cline"))
+            .is_some_and(|n| n.contains("claude-dev"))
         {
             return true;
         }
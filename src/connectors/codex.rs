@@ -1,4 +1,5 @@
 use std::fs;
+use std::io::{BufRead, Read};
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
@@ -6,7 +7,8 @@ use serde_json::Value;
 use walkdir::WalkDir;
 
 use crate::connectors::{
-    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    Connector, CursorMap, DetectionResult, FileCursor, NormalizedConversation, NormalizedMessage,
+    ScanContext, ScanItem, ScanIter, read_jsonl_from,
 };
 
 pub struct CodexConnector;
@@ -75,6 +77,33 @@ impl Connector for CodexConnector {
     }
 
     fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let mut convs = Vec::new();
+        for (file, sessions_dir) in Self::session_files(ctx) {
+            if let Some(conversation) =
+                Self::parse_rollout(&file, &sessions_dir, None)?.and_then(|item| item.conversation)
+            {
+                convs.push(conversation);
+            }
+        }
+        Ok(convs)
+    }
+
+    fn scan_incremental<'a>(
+        &'a self,
+        ctx: &'a ScanContext,
+        cursors: &'a CursorMap,
+    ) -> Result<ScanIter<'a>> {
+        Ok(Box::new(Self::session_files(ctx).into_iter().filter_map(
+            move |(file, sessions_dir)| {
+                Self::parse_rollout(&file, &sessions_dir, cursors.get(&file)).transpose()
+            },
+        )))
+    }
+}
+
+impl CodexConnector {
+    /// Rollout files changed since `ctx.since_ts`, paired with their sessions directory.
+    fn session_files(ctx: &ScanContext) -> Vec<(PathBuf, PathBuf)> {
         // Use data_root only if it IS a Codex home directory (for testing).
         // Check for `.codex` in path OR explicit directory name ending in "codex".
         // AND ensure it has a "sessions" subdirectory.
@@ -97,11 +126,7 @@ impl Connector for CodexConnector {
             ctx.scan_roots.iter().map(|r| r.path.clone()).collect()
         };
 
-        if roots.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        let mut convs = Vec::new();
+        let mut out = Vec::new();
 
         for mut home in roots {
             if home.is_file() {
@@ -111,256 +136,323 @@ impl Connector for CodexConnector {
                 continue;
             }
 
-            let files = Self::rollout_files(&home);
-
-            for file in files {
-                let source_path = file.clone();
+            let sessions_dir = Self::sessions_dir(&home);
+            for file in Self::rollout_files(&home) {
                 // Skip files not modified since last scan (incremental indexing)
-                if !crate::connectors::file_modified_since(&file, ctx.since_ts) {
-                    continue;
+                if crate::connectors::file_modified_since(&file, ctx.since_ts) {
+                    out.push((file, sessions_dir.clone()));
                 }
-                // Use relative path from sessions dir as external_id for uniqueness
-                // e.g., "2025/11/20/rollout-1" instead of just "rollout-1"
-                let sessions_dir = Self::sessions_dir(&home);
-                let external_id = source_path
-                    .strip_prefix(&sessions_dir)
-                    .ok()
-                    .and_then(|rel| {
-                        rel.with_extension("")
-                            .to_str()
-                            .map(std::string::ToString::to_string)
-                    })
-                    .or_else(|| {
-                        source_path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .map(std::string::ToString::to_string)
-                    });
-                let ext = file.extension().and_then(|e| e.to_str());
-                let mut messages = Vec::new();
-                let mut started_at = None;
-                let mut ended_at = None;
-                let mut session_cwd: Option<PathBuf> = None;
-
-                if ext == Some("jsonl") {
-                    let f = std::fs::File::open(&file)
-                        .with_context(|| format!("open rollout {}", file.display()))?;
-                    let reader = std::io::BufReader::new(f);
-
-                    // Modern envelope format: each line has {type, timestamp, payload}
-                    for line_res in std::io::BufRead::lines(reader) {
-                        let line = match line_res {
-                            Ok(l) => l,
-                            Err(_) => continue,
-                        };
-                        if line.trim().is_empty() {
-                            continue;
-                        }
-                        let val: Value = match serde_json::from_str(&line) {
-                            Ok(v) => v,
-                            Err(_) => continue,
-                        };
-
-                        let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
-                        let created = val
-                            .get("timestamp")
-                            .and_then(crate::connectors::parse_timestamp);
-
-                        // NOTE: Do NOT filter individual messages by timestamp here!
-                        // The file-level check in file_modified_since() is sufficient.
-                        // Filtering messages would cause older messages to be lost when
-                        // the file is re-indexed after new messages are added.
-
-                        match entry_type {
-                            "session_meta" => {
-                                // Extract workspace from session metadata
-                                if let Some(payload) = val.get("payload") {
-                                    session_cwd = payload
-                                        .get("cwd")
-                                        .and_then(|v| v.as_str())
-                                        .map(PathBuf::from);
-                                }
-                                started_at = started_at.or(created);
-                            }
-                            "response_item" => {
-                                // Main message entries with nested payload
-                                if let Some(payload) = val.get("payload") {
-                                    let role = payload
-                                        .get("role")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("agent");
-
-                                    let content_str = payload
-                                        .get("content")
-                                        .map(crate::connectors::flatten_content)
-                                        .unwrap_or_default();
-
-                                    if content_str.trim().is_empty() {
-                                        continue;
-                                    }
-
-                                    started_at = started_at.or(created);
-                                    ended_at = created.or(ended_at);
-
-                                    messages.push(NormalizedMessage {
-                                        idx: 0, // will be re-assigned after filtering
-                                        role: role.to_string(),
-                                        author: None,
-                                        created_at: created,
-                                        content: content_str,
-                                        extra: val,
-                                        snippets: Vec::new(),
-                                    });
-                                }
-                            }
-                            "event_msg" => {
-                                // Event messages - filter by payload type
-                                if let Some(payload) = val.get("payload") {
-                                    let event_type = payload.get("type").and_then(|v| v.as_str());
-
-                                    match event_type {
-                                        Some("user_message") => {
-                                            let text = payload
-                                                .get("message")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("");
-                                            if !text.is_empty() {
-                                                started_at = started_at.or(created);
-                                                ended_at = created.or(ended_at);
-                                                messages.push(NormalizedMessage {
-                                                    idx: 0, // will be re-assigned after filtering
-                                                    role: "user".to_string(),
-                                                    author: None,
-                                                    created_at: created,
-                                                    content: text.to_string(),
-                                                    extra: val,
-                                                    snippets: Vec::new(),
-                                                });
-                                            }
-                                        }
-                                        Some("agent_reasoning") => {
-                                            // Include reasoning - valuable for search
-                                            let text = payload
-                                                .get("text")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("");
-                                            if !text.is_empty() {
-                                                started_at = started_at.or(created);
-                                                ended_at = created.or(ended_at);
-                                                messages.push(NormalizedMessage {
-                                                    idx: 0, // will be re-assigned after filtering
-                                                    role: "assistant".to_string(),
-                                                    author: Some("reasoning".to_string()),
-                                                    created_at: created,
-                                                    content: text.to_string(),
-                                                    extra: val,
-                                                    snippets: Vec::new(),
-                                                });
-                                            }
-                                        }
-                                        _ => {} // Skip token_count, turn_aborted, etc.
-                                    }
-                                }
-                            }
-                            _ => {} // Skip turn_context and unknown types
-                        }
+            }
+        }
+
+        out
+    }
+
+    /// Parse one rollout file. JSONL rollouts resume from `cursor` when it still applies.
+    fn parse_rollout(
+        file: &Path,
+        sessions_dir: &Path,
+        cursor: Option<&FileCursor>,
+    ) -> Result<Option<ScanItem>> {
+        // Use relative path from sessions dir as external_id for uniqueness
+        // e.g., "2025/11/20/rollout-1" instead of just "rollout-1"
+        let external_id = file
+            .strip_prefix(sessions_dir)
+            .ok()
+            .and_then(|rel| {
+                rel.with_extension("")
+                    .to_str()
+                    .map(std::string::ToString::to_string)
+            })
+            .or_else(|| {
+                file.file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(std::string::ToString::to_string)
+            });
+        let ext = file.extension().and_then(|e| e.to_str());
+        let mut session = RolloutSession::default();
+        let mut next_cursor = None;
+        let mut appended = false;
+
+        if ext == Some("jsonl") {
+            // Modern envelope format: each line has {type, timestamp, payload}
+            let read = read_jsonl_from(file, cursor, |val| session.push_entry(val))
+                .with_context(|| format!("open rollout {}", file.display()))?;
+            if read.resumed {
+                // Only the tail was parsed; recover the header from the head of the file.
+                session.inherit_header(RolloutSession::read_head(file, read.cursor.offset)?);
+                appended = true;
+            }
+            let start_idx = read.cursor.next_idx;
+            for (i, msg) in session.messages.iter_mut().enumerate() {
+                msg.idx = start_idx + i as i64;
+            }
+            next_cursor = Some(FileCursor {
+                next_idx: start_idx + session.messages.len() as i64,
+                ..read.cursor
+            });
+        } else if ext == Some("json") {
+            let content = fs::read_to_string(file)
+                .with_context(|| format!("read rollout {}", file.display()))?;
+            // Legacy format: single JSON object with {session, items}
+            let val: Value = match serde_json::from_str(&content) {
+                Ok(v) => v,
+                Err(_) => return Ok(None),
+            };
+
+            // Extract workspace from session.cwd
+            session.cwd = val
+                .get("session")
+                .and_then(|s| s.get("cwd"))
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from);
+
+            // Parse items array
+            if let Some(items) = val.get("items").and_then(|v| v.as_array()) {
+                for item in items {
+                    let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("agent");
+
+                    let content_str = item
+                        .get("content")
+                        .map(crate::connectors::flatten_content)
+                        .unwrap_or_default();
+
+                    if content_str.trim().is_empty() {
+                        continue;
                     }
-                    // Re-assign sequential indices after filtering
-                    super::reindex_messages(&mut messages);
-                } else if ext == Some("json") {
-                    let content = fs::read_to_string(&file)
-                        .with_context(|| format!("read rollout {}", file.display()))?;
-                    // Legacy format: single JSON object with {session, items}
-                    let val: Value = match serde_json::from_str(&content) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-
-                    // Extract workspace from session.cwd
-                    session_cwd = val
-                        .get("session")
-                        .and_then(|s| s.get("cwd"))
+
+                    let created = item
+                        .get("timestamp")
+                        .and_then(crate::connectors::parse_timestamp);
+
+                    // NOTE: Do NOT filter individual messages by timestamp.
+                    // File-level check is sufficient for incremental indexing.
+
+                    session.push_message(NormalizedMessage {
+                        idx: 0, // will be re-assigned after filtering
+                        role: role.to_string(),
+                        author: None,
+                        created_at: created,
+                        content: content_str,
+                        extra: item.clone(),
+                        snippets: Vec::new(),
+                    });
+                }
+            }
+            // Re-assign sequential indices after filtering
+            super::reindex_messages(&mut session.messages);
+        }
+
+        if session.messages.is_empty() {
+            // Still move past an appended tail of non-message entries.
+            return Ok(next_cursor
+                .filter(|next| appended && Some(next) != cursor)
+                .map(|next| ScanItem::cursor_only(file.to_path_buf(), next)));
+        }
+
+        // Title from first user message, else the first message
+        let title = session.user_title.or(session.first_title);
+
+        Ok(Some(ScanItem {
+            source_path: file.to_path_buf(),
+            conversation: Some(NormalizedConversation {
+                agent_slug: "codex".to_string(),
+                external_id,
+                title,
+                workspace: session.cwd, // Now populated from session_meta/session.cwd!
+                source_path: file.to_path_buf(),
+                started_at: session.started_at,
+                ended_at: session.ended_at,
+                metadata: serde_json::json!({"source": if ext == Some("json") { "rollout_json" } else { "rollout" }}),
+                messages: session.messages,
+            }),
+            cursor: next_cursor,
+            appended,
+        }))
+    }
+}
+
+/// Messages and session metadata accumulated from rollout entries.
+#[derive(Default)]
+struct RolloutSession {
+    messages: Vec<NormalizedMessage>,
+    started_at: Option<i64>,
+    ended_at: Option<i64>,
+    cwd: Option<PathBuf>,
+    /// First line of the first user message.
+    user_title: Option<String>,
+    /// First line of the first message of any role.
+    first_title: Option<String>,
+}
+
+impl RolloutSession {
+    /// Parse entries from the start of `path` up to `limit` bytes, stopping once the
+    /// first user message (and with it the session header) has been seen.
+    fn read_head(path: &Path, limit: u64) -> Result<Self> {
+        let file =
+            fs::File::open(path).with_context(|| format!("open rollout {}", path.display()))?;
+        let reader = std::io::BufReader::new(file.take(limit));
+        let mut head = Self::default();
+        for line in reader.split(b'\n').map_while(std::result::Result::ok) {
+            if let Ok(val) = serde_json::from_slice::<Value>(&line) {
+                head.push_entry(val);
+            }
+            if head.user_title.is_some() {
+                break;
+            }
+        }
+        Ok(head)
+    }
+
+    /// Take header fields from the head of the file over those seen in the tail.
+    fn inherit_header(&mut self, head: Self) {
+        self.started_at = head.started_at.or(self.started_at);
+        self.cwd = head.cwd.or(self.cwd.take());
+        self.user_title = head.user_title.or(self.user_title.take());
+        self.first_title = head.first_title.or(self.first_title.take());
+    }
+
+    fn push_message(&mut self, msg: NormalizedMessage) {
+        let first_line = || -> String {
+            msg.content
+                .lines()
+                .next()
+                .unwrap_or(&msg.content)
+                .chars()
+                .take(100)
+                .collect()
+        };
+        if self.user_title.is_none() && msg.role == "user" {
+            self.user_title = Some(first_line());
+        }
+        if self.first_title.is_none() {
+            self.first_title = Some(first_line());
+        }
+        self.started_at = self.started_at.or(msg.created_at);
+        self.ended_at = msg.created_at.or(self.ended_at);
+        self.messages.push(msg);
+    }
+
+    /// Handle one JSONL envelope entry.
+    fn push_entry(&mut self, val: Value) {
+        let entry_type = val.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let created = val
+            .get("timestamp")
+            .and_then(crate::connectors::parse_timestamp);
+
+        // NOTE: Do NOT filter individual messages by timestamp here!
+        // The file-level check in file_modified_since() is sufficient.
+        // Filtering messages would cause older messages to be lost when
+        // the file is re-indexed after new messages are added.
+
+        match entry_type {
+            "session_meta" => {
+                // Extract workspace from session metadata
+                if let Some(payload) = val.get("payload") {
+                    self.cwd = payload
+                        .get("cwd")
                         .and_then(|v| v.as_str())
                         .map(PathBuf::from);
-
-                    // Parse items array
-                    if let Some(items) = val.get("items").and_then(|v| v.as_array()) {
-                        for item in items {
-                            let role = item.get("role").and_then(|v| v.as_str()).unwrap_or("agent");
-
-                            let content_str = item
-                                .get("content")
-                                .map(crate::connectors::flatten_content)
-                                .unwrap_or_default();
-
-                            if content_str.trim().is_empty() {
-                                continue;
-                            }
-
-                            let created = item
-                                .get("timestamp")
-                                .and_then(crate::connectors::parse_timestamp);
-
-                            // NOTE: Do NOT filter individual messages by timestamp.
-                            // File-level check is sufficient for incremental indexing.
-
-                            started_at = started_at.or(created);
-                            ended_at = created.or(ended_at);
-
-                            messages.push(NormalizedMessage {
-                                idx: 0, // will be re-assigned after filtering
-                                role: role.to_string(),
-                                author: None,
-                                created_at: created,
-                                content: content_str,
-                                extra: item.clone(),
-                                snippets: Vec::new(),
-                            });
-                        }
+                }
+                self.started_at = self.started_at.or(created);
+            }
+            "response_item" => {
+                // Main message entries with nested payload
+                let Some(payload) = val.get("payload") else {
+                    return;
+                };
+                match payload.get("type").and_then(|v| v.as_str()) {
+                    Some("function_call" | "custom_tool_call" | "local_shell_call") => {
+                        let content = tool_call_marker(payload);
+                        self.push_message(NormalizedMessage {
+                            idx: 0, // assigned once the file has been read
+                            role: "assistant".to_string(),
+                            author: None,
+                            created_at: created,
+                            content,
+                            extra: val,
+                            snippets: Vec::new(),
+                        });
+                        return;
+                    }
+                    Some("function_call_output" | "custom_tool_call_output") => {
+                        crate::connectors::fold_tool_results(&mut self.messages, payload);
+                        return;
                     }
-                    // Re-assign sequential indices after filtering
-                    super::reindex_messages(&mut messages);
+                    _ => {}
                 }
-
-                if messages.is_empty() {
-                    continue;
+                let role = payload
+                    .get("role")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("agent")
+                    .to_string();
+
+                let content_str = payload
+                    .get("content")
+                    .map(crate::connectors::flatten_content)
+                    .unwrap_or_default();
+
+                if content_str.trim().is_empty() {
+                    return;
                 }
 
-                // Extract title from first user message
-                let title = messages
-                    .iter()
-                    .find(|m| m.role == "user")
-                    .map(|m| {
-                        m.content
-                            .lines()
-                            .next()
-                            .unwrap_or(&m.content)
-                            .chars()
-                            .take(100)
-                            .collect::<String>()
-                    })
-                    .or_else(|| {
-                        messages
-                            .first()
-                            .and_then(|m| m.content.lines().next())
-                            .map(|s| s.chars().take(100).collect())
-                    });
-
-                convs.push(NormalizedConversation {
-                    agent_slug: "codex".to_string(),
-                    external_id,
-                    title,
-                    workspace: session_cwd, // Now populated from session_meta/session.cwd!
-                    source_path: source_path.clone(),
-                    started_at,
-                    ended_at,
-                    metadata: serde_json::json!({"source": if ext == Some("json") { "rollout_json" } else { "rollout" }}),
-                    messages,
+                self.push_message(NormalizedMessage {
+                    idx: 0, // assigned once the file has been read
+                    role,
+                    author: None,
+                    created_at: created,
+                    content: content_str,
+                    extra: val,
+                    snippets: Vec::new(),
                 });
             }
+            "event_msg" => {
+                // Event messages - filter by payload type
+                let Some(payload) = val.get("payload") else {
+                    return;
+                };
+                let (role, author, text) = match payload.get("type").and_then(|v| v.as_str()) {
+                    Some("user_message") => ("user", None, payload.get("message")),
+                    // Include reasoning - valuable for search
+                    Some("agent_reasoning") => {
+                        ("assistant", Some("reasoning"), payload.get("text"))
+                    }
+                    _ => return, // Skip token_count, turn_aborted, etc.
+                };
+                let text = text.and_then(|v| v.as_str()).unwrap_or("").to_string();
+                if text.is_empty() {
+                    return;
+                }
+                self.push_message(NormalizedMessage {
+                    idx: 0, // assigned once the file has been read
+                    role: role.to_string(),
+                    author: author.map(String::from),
+                    created_at: created,
+                    content: text,
+                    extra: val,
+                    snippets: Vec::new(),
+                });
+            }
+            _ => {} // Skip turn_context and unknown types
         }
+    }
+}
 
-        Ok(convs)
+/// `[Tool: shell - cargo test]` marker text for a tool call item, matching the
+/// form other connectors use so tool calls stay searchable.
+fn tool_call_marker(payload: &Value) -> String {
+    let name = payload
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("local_shell");
+    let details: Vec<String> = crate::connectors::extract_tool_events(payload)
+        .into_iter()
+        .filter_map(|e| e.command.or(e.path))
+        .collect();
+    if details.is_empty() {
+        format!("[Tool: {name}]")
+    } else {
+        format!("[Tool: {name} - {}]", details.join(", "))
     }
 }
 
@@ -1686,4 +1778,159 @@ not valid json at all
                 .contains("Here are the results")
         );
     }
+
+    #[test]
+    fn function_calls_become_tool_messages_with_exit_status() {
+        let dir = TempDir::new().unwrap();
+        let codex_dir = dir.path().join(".codex");
+        let sessions = codex_dir.join("sessions");
+        fs::create_dir_all(&sessions).unwrap();
+
+        let lines = [
+            json!({"type": "response_item", "payload": {
+                "type": "function_call", "name": "shell", "call_id": "call_1",
+                "arguments": "{\"command\":[\"bash\",\"-lc\",\"cargo test\"]}"
+            }}),
+            json!({"type": "response_item", "payload": {
+                "type": "function_call_output", "call_id": "call_1",
+                "output": "{\"output\":\"test failed\",\"metadata\":{\"exit_code\":101}}"
+            }}),
+            json!({"type": "response_item", "payload": {
+                "role": "assistant", "content": [{"type": "text", "text": "Tests fail."}]
+            }}),
+        ];
+        let content: String = lines.iter().map(|l| format!("{l}\n")).collect();
+        fs::write(sessions.join("rollout-calls.jsonl"), content).unwrap();
+
+        let connector = CodexConnector::new();
+        let ctx = ScanContext::local_default(codex_dir.clone(), None);
+        let convs = connector.scan(&ctx).unwrap();
+        assert_eq!(convs.len(), 1);
+        let messages = &convs[0].messages;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, "[Tool: shell - cargo test]");
+
+        let events = crate::connectors::extract_tool_events(&messages[0].extra);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].command.as_deref(), Some("cargo test"));
+        assert_eq!(events[0].exit_code, Some(101));
+        assert_eq!(events[0].failed, Some(true));
+    }
+
+    // =====================================================
+    // scan_incremental() Tests
+    // =====================================================
+
+    #[test]
+    fn scan_incremental_resumes_rollout_from_cursor() {
+        let dir = TempDir::new().unwrap();
+        let codex_dir = dir.path().join(".codex");
+        let sessions = codex_dir.join("sessions");
+        fs::create_dir_all(&sessions).unwrap();
+
+        let rollout = sessions.join("rollout-inc.jsonl");
+        fs::write(
+            &rollout,
+            r#"{"type":"session_meta","timestamp":"2025-12-01T10:00:00Z","payload":{"cwd":"/work/cli"}}
+{"type":"response_item","timestamp":"2025-12-01T10:00:01Z","payload":{"role":"user","content":"Add a --json flag"}}
+"#,
+        )
+        .unwrap();
+
+        let connector = CodexConnector::new();
+        let ctx = ScanContext::local_default(codex_dir.clone(), None);
+        let first: Vec<ScanItem> = connector
+            .scan_incremental(&ctx, &CursorMap::new())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(first.len(), 1);
+        let cursor = first[0].cursor.unwrap();
+        assert_eq!(cursor.next_idx, 1);
+
+        let mut content = fs::read_to_string(&rollout).unwrap();
+        content.push_str(
+            r#"{"type":"event_msg","timestamp":"2025-12-01T10:00:02Z","payload":{"type":"agent_reasoning","text":"Need a flag in clap"}}
+{"type":"response_item","timestamp":"2025-12-01T10:00:03Z","payload":{"role":"assistant","content":"Added --json"}}
+"#,
+        );
+        fs::write(&rollout, content).unwrap();
+
+        let cursors = CursorMap::from([(rollout.clone(), cursor)]);
+        let second: Vec<ScanItem> = connector
+            .scan_incremental(&ctx, &cursors)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(second.len(), 1);
+        let conv = second[0].conversation.as_ref().unwrap();
+        assert!(second[0].appended);
+        assert_eq!(
+            conv.messages.iter().map(|m| m.idx).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(conv.messages[0].author.as_deref(), Some("reasoning"));
+        assert_eq!(conv.title.as_deref(), Some("Add a --json flag"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/work/cli")));
+        assert_eq!(conv.external_id.as_deref(), Some("rollout-inc"));
+
+        // A full scan assigns the same indices to the same messages
+        let full = connector.scan(&ctx).unwrap();
+        assert_eq!(full[0].messages[1].content, conv.messages[0].content);
+        assert_eq!(full[0].messages[1].idx, conv.messages[0].idx);
+    }
+
+    #[test]
+    fn scan_incremental_advances_cursor_past_non_message_tail() {
+        let dir = TempDir::new().unwrap();
+        let codex_dir = dir.path().join(".codex");
+        let sessions = codex_dir.join("sessions");
+        fs::create_dir_all(&sessions).unwrap();
+
+        let rollout = sessions.join("rollout-meta.jsonl");
+        fs::write(
+            &rollout,
+            r#"{"type":"session_meta","timestamp":"2025-12-01T10:00:00Z","payload":{"cwd":"/work/cli"}}
+{"type":"response_item","timestamp":"2025-12-01T10:00:01Z","payload":{"role":"user","content":"Add a --json flag"}}
+"#,
+        )
+        .unwrap();
+
+        let connector = CodexConnector::new();
+        let ctx = ScanContext::local_default(codex_dir.clone(), None);
+        let first: Vec<ScanItem> = connector
+            .scan_incremental(&ctx, &CursorMap::new())
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        let cursor = first[0].cursor.unwrap();
+
+        // Only a token-count event is appended: no messages, but the cursor moves on.
+        let mut content = fs::read_to_string(&rollout).unwrap();
+        content.push_str(
+            r#"{"type":"event_msg","timestamp":"2025-12-01T10:00:02Z","payload":{"type":"token_count","info":null}}
+"#,
+        );
+        fs::write(&rollout, content).unwrap();
+
+        let cursors = CursorMap::from([(rollout.clone(), cursor)]);
+        let second: Vec<ScanItem> = connector
+            .scan_incremental(&ctx, &cursors)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(second.len(), 1);
+        assert!(second[0].conversation.is_none());
+        assert_eq!(second[0].source_path, rollout);
+        let advanced = second[0].cursor.unwrap();
+        assert_eq!(advanced.offset, fs::metadata(&rollout).unwrap().len());
+        assert_eq!(advanced.next_idx, cursor.next_idx);
+
+        // Resuming from the advanced cursor yields nothing.
+        let cursors = CursorMap::from([(rollout, advanced)]);
+        assert_eq!(
+            connector.scan_incremental(&ctx, &cursors).unwrap().count(),
+            0
+        );
+    }
 }
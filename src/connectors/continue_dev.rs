@@ -0,0 +1,345 @@
+//! Continue.dev connector for chat session files.
+//!
+//! Continue (https://continue.dev) is an open-source IDE assistant for VS Code and
+//! JetBrains. It stores chat sessions under `~/.continue/sessions/`
+//! (`CONTINUE_GLOBAL_DIR` relocates `~/.continue`):
+//!   - `sessions.json` — index of `{sessionId, title, dateCreated, workspaceDirectory}`
+//!   - `{sessionId}.json` — `{sessionId, title, workspaceDirectory, history: [...]}`
+//!
+//! Each history item wraps a chat message as `{message: {role, content}, contextItems}`.
+//! Messages carry no timestamps; the index's `dateCreated` and the session file's
+//! modification time bound the conversation instead.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    file_modified_since, flatten_content, parse_timestamp,
+};
+
+/// Title Continue assigns before a session has been summarized.
+const PLACEHOLDER_TITLE: &str = "New Session";
+
+pub struct ContinueConnector;
+
+impl Default for ContinueConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContinueConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Get the Continue sessions directory.
+    fn sessions_root() -> Option<PathBuf> {
+        let global = match dotenvy::var("CONTINUE_GLOBAL_DIR") {
+            Ok(dir) => PathBuf::from(dir),
+            Err(_) => dirs::home_dir()?.join(".continue"),
+        };
+        Some(global.join("sessions"))
+    }
+}
+
+impl Connector for ContinueConnector {
+    fn detect(&self) -> DetectionResult {
+        if let Some(root) = Self::sessions_root()
+            && root.exists()
+        {
+            return DetectionResult {
+                detected: true,
+                evidence: vec![format!("found {}", root.display())],
+                root_paths: vec![root],
+            };
+        }
+        DetectionResult::not_found()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let roots: Vec<PathBuf> = if ctx.use_default_detection() {
+            if looks_like_continue_storage(&ctx.data_dir) {
+                vec![ctx.data_dir.clone()]
+            } else {
+                Self::sessions_root().into_iter().collect()
+            }
+        } else {
+            ctx.scan_roots
+                .iter()
+                .filter_map(|sr| {
+                    let nested = sr.path.join(".continue/sessions");
+                    if nested.exists() {
+                        Some(nested)
+                    } else if looks_like_continue_storage(&sr.path) {
+                        Some(sr.path.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        let mut convs = Vec::new();
+        for root in roots {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            let index = load_session_index(&root);
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_file()
+                    || path.extension().and_then(|e| e.to_str()) != Some("json")
+                    || path.file_name().is_some_and(|n| n == "sessions.json")
+                {
+                    continue;
+                }
+                // Skip files not modified since last scan (incremental indexing)
+                if !file_modified_since(&path, ctx.since_ts) {
+                    continue;
+                }
+
+                match parse_continue_session(&path, &index) {
+                    Ok(Some(conv)) => convs.push(conv),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::debug!(path = %path.display(), error = %e, "continue parse error");
+                    }
+                }
+            }
+        }
+
+        Ok(convs)
+    }
+}
+
+/// Check if a directory looks like Continue session storage.
+fn looks_like_continue_storage(path: &Path) -> bool {
+    path.to_string_lossy().to_lowercase().contains("continue")
+        && (path.join("sessions.json").exists()
+            || path.file_name().is_some_and(|n| n == "sessions"))
+}
+
+/// Load `sessions.json`, keyed by session id.
+fn load_session_index(root: &Path) -> HashMap<String, Value> {
+    let Ok(data) = fs::read_to_string(root.join("sessions.json")) else {
+        return HashMap::new();
+    };
+    let Ok(Value::Array(items)) = serde_json::from_str::<Value>(&data) else {
+        return HashMap::new();
+    };
+    items
+        .into_iter()
+        .filter_map(|item| {
+            let id = item.get("sessionId")?.as_str()?.to_string();
+            Some((id, item))
+        })
+        .collect()
+}
+
+/// Parse a Continue session file into a NormalizedConversation.
+fn parse_continue_session(
+    path: &Path,
+    index: &HashMap<String, Value>,
+) -> Result<Option<NormalizedConversation>> {
+    let data = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let val: Value =
+        serde_json::from_str(&data).with_context(|| format!("parse {}", path.display()))?;
+
+    let session_id = val
+        .get("sessionId")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(String::from));
+    let indexed = session_id.as_ref().and_then(|id| index.get(id));
+    let field = |key: &str| {
+        val.get(key)
+            .or_else(|| indexed.and_then(|i| i.get(key)))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(String::from)
+    };
+
+    let mut messages = Vec::new();
+    if let Some(history) = val.get("history").and_then(|h| h.as_array()) {
+        for item in history {
+            // Older releases stored the message inline rather than under `message`
+            let msg = item.get("message").unwrap_or(item);
+            let Some(role) = msg.get("role").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let content = msg.get("content").map(flatten_content).unwrap_or_default();
+            if content.trim().is_empty() {
+                continue;
+            }
+            // Reasoning is kept as assistant output, like other connectors do
+            let (role, author) = match role {
+                "thinking" => ("assistant", Some("thinking".to_string())),
+                other => (other, None),
+            };
+            messages.push(NormalizedMessage {
+                idx: 0, // Will be reassigned after collection
+                role: role.to_string(),
+                author,
+                created_at: None,
+                content,
+                extra: item.clone(),
+                snippets: Vec::new(),
+            });
+        }
+    }
+
+    super::reindex_messages(&mut messages);
+    if messages.is_empty() {
+        return Ok(None);
+    }
+
+    let workspace = field("workspaceDirectory")
+        .map(|dir| crate::connectors::file_uri_to_path(&dir).unwrap_or_else(|| PathBuf::from(dir)));
+
+    let title = field("title")
+        .filter(|t| t != PLACEHOLDER_TITLE)
+        .or_else(|| {
+            messages.iter().find(|m| m.role == "user").map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+        });
+
+    let started_at = indexed
+        .and_then(|i| i.get("dateCreated"))
+        .or_else(|| val.get("dateCreated"))
+        .and_then(parse_timestamp);
+    // Continue rewrites the session file after every exchange
+    let ended_at = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|mt| mt.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .or(started_at);
+
+    Ok(Some(NormalizedConversation {
+        agent_slug: "continue".into(),
+        external_id: session_id.clone(),
+        title,
+        workspace,
+        source_path: path.to_path_buf(),
+        started_at: started_at.or(ended_at),
+        ended_at,
+        metadata: serde_json::json!({
+            "source": "continue",
+            "sessionId": session_id,
+            "mode": val.get("mode").and_then(|v| v.as_str()),
+        }),
+        messages,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_session(dir: &Path, id: &str, body: &Value) -> PathBuf {
+        let path = dir.join(format!("{id}.json"));
+        fs::write(&path, serde_json::to_string(body).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn looks_like_continue_storage_requires_continue_path() {
+        let tmp = TempDir::new().unwrap();
+        let sessions = tmp.path().join(".continue/sessions");
+        fs::create_dir_all(&sessions).unwrap();
+        assert!(looks_like_continue_storage(&sessions));
+
+        let other = tmp.path().join("other/sessions");
+        fs::create_dir_all(&other).unwrap();
+        assert!(!looks_like_continue_storage(&other));
+    }
+
+    #[test]
+    fn parse_maps_thinking_to_assistant_and_skips_empty() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_session(
+            tmp.path(),
+            "s1",
+            &serde_json::json!({
+                "sessionId": "s1",
+                "title": "New Session",
+                "workspaceDirectory": "file:///home/dev/my%20app",
+                "history": [
+                    {"message": {"role": "user", "content": [{"type": "text", "text": "Why is CI red?\nDetails"}]}, "contextItems": []},
+                    {"message": {"role": "thinking", "content": "Check the workflow"}},
+                    {"message": {"role": "assistant", "content": ""}},
+                    {"message": {"role": "assistant", "content": "The lint job fails"}}
+                ]
+            }),
+        );
+
+        let conv = parse_continue_session(&path, &HashMap::new())
+            .unwrap()
+            .unwrap();
+        assert_eq!(conv.messages.len(), 3);
+        assert_eq!(conv.messages[1].role, "assistant");
+        assert_eq!(conv.messages[1].author.as_deref(), Some("thinking"));
+        assert_eq!(conv.messages[2].idx, 2);
+        // Placeholder title falls back to the first user line
+        assert_eq!(conv.title.as_deref(), Some("Why is CI red?"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/home/dev/my app")));
+        assert!(conv.ended_at.is_some());
+    }
+
+    #[test]
+    fn parse_uses_index_for_missing_fields() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_session(
+            tmp.path(),
+            "s2",
+            &serde_json::json!({
+                "sessionId": "s2",
+                "history": [{"message": {"role": "user", "content": "hello"}}]
+            }),
+        );
+        let index = HashMap::from([(
+            "s2".to_string(),
+            serde_json::json!({
+                "sessionId": "s2",
+                "title": "Greeting",
+                "dateCreated": "1714000000000",
+                "workspaceDirectory": "/work/site"
+            }),
+        )]);
+
+        let conv = parse_continue_session(&path, &index).unwrap().unwrap();
+        assert_eq!(conv.title.as_deref(), Some("Greeting"));
+        assert_eq!(conv.started_at, Some(1_714_000_000_000));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/work/site")));
+    }
+
+    #[test]
+    fn parse_returns_none_without_messages() {
+        let tmp = TempDir::new().unwrap();
+        let path = write_session(
+            tmp.path(),
+            "empty",
+            &serde_json::json!({"sessionId": "empty", "history": []}),
+        );
+        assert!(
+            parse_continue_session(&path, &HashMap::new())
+                .unwrap()
+                .is_none()
+        );
+    }
+}
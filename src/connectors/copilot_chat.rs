@@ -0,0 +1,371 @@
+//! GitHub Copilot Chat connector for VS Code chat session files.
+//!
+//! The Copilot Chat extension persists each chat panel session as JSON in the
+//! editor's user storage:
+//!   - `<editor>/User/workspaceStorage/{hash}/chatSessions/{sessionId}.json`
+//!   - `<editor>/User/workspaceStorage/{hash}/workspace.json` — `{folder: "file:///..."}`
+//!   - `<editor>/User/globalStorage/emptyWindowChatSessions/{sessionId}.json`
+//!
+//! A session holds `requests[]`; each request pairs the user's `message.text` with
+//! a `response` array of markdown parts. Tool invocations and inline references in
+//! the response are skipped, only text parts with a `value` are kept.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    file_modified_since, file_uri_to_path, parse_timestamp,
+};
+
+pub struct CopilotChatConnector;
+
+impl Default for CopilotChatConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CopilotChatConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// VS Code `User` directories (stable and Insiders) across platforms.
+    fn candidate_roots() -> Vec<PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        let mut roots = Vec::new();
+        for editor in ["Code", "Code - Insiders"] {
+            for base in [
+                home.join(".config"),
+                home.join("Library/Application Support"),
+                home.join("AppData/Roaming"),
+            ] {
+                roots.push(base.join(editor).join("User"));
+            }
+        }
+        roots
+    }
+
+    fn user_roots() -> Vec<PathBuf> {
+        Self::candidate_roots()
+            .into_iter()
+            .filter(|r| r.join("workspaceStorage").exists())
+            .collect()
+    }
+}
+
+impl Connector for CopilotChatConnector {
+    fn detect(&self) -> DetectionResult {
+        let roots: Vec<PathBuf> = Self::user_roots()
+            .into_iter()
+            .filter(|r| !session_files(r).is_empty())
+            .collect();
+        if roots.is_empty() {
+            return DetectionResult::not_found();
+        }
+        DetectionResult {
+            detected: true,
+            evidence: roots
+                .iter()
+                .map(|r| format!("found chat sessions in {}", r.display()))
+                .collect(),
+            root_paths: roots,
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let roots: Vec<PathBuf> = if ctx.use_default_detection() {
+            if looks_like_copilot_storage(&ctx.data_dir) {
+                vec![ctx.data_dir.clone()]
+            } else {
+                Self::user_roots()
+            }
+        } else {
+            ctx.scan_roots
+                .iter()
+                .map(|sr| sr.path.clone())
+                .filter(|p| looks_like_copilot_storage(p))
+                .collect()
+        };
+
+        let mut convs = Vec::new();
+        for root in roots {
+            for file in session_files(&root) {
+                // Skip files not modified since last scan (incremental indexing)
+                if !file_modified_since(&file, ctx.since_ts) {
+                    continue;
+                }
+                match parse_chat_session(&file) {
+                    Ok(Some(conv)) => convs.push(conv),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::debug!(path = %file.display(), error = %e, "copilot chat parse error");
+                    }
+                }
+            }
+        }
+
+        Ok(convs)
+    }
+}
+
+/// Check if a path is a VS Code `User` dir or one of the chat session dirs inside it.
+fn looks_like_copilot_storage(path: &Path) -> bool {
+    path.join("workspaceStorage").is_dir()
+        || path.join("chatSessions").is_dir()
+        || path.join("emptyWindowChatSessions").is_dir()
+        || path
+            .file_name()
+            .is_some_and(|n| n == "chatSessions" || n == "emptyWindowChatSessions")
+        || (path.file_name().is_some_and(|n| n == "workspaceStorage") && path.is_dir())
+}
+
+/// Collect `*.json` session files under any of the accepted root shapes.
+fn session_files(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let name = root.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    if name == "chatSessions" || name == "emptyWindowChatSessions" {
+        dirs.push(root.to_path_buf());
+    } else {
+        // `User/` -> `User/workspaceStorage/`, otherwise assume the root is one already
+        let storage = if root.join("workspaceStorage").is_dir() {
+            root.join("workspaceStorage")
+        } else {
+            root.to_path_buf()
+        };
+        dirs.push(storage.join("chatSessions"));
+        if let Ok(entries) = fs::read_dir(&storage) {
+            dirs.extend(entries.flatten().map(|e| e.path().join("chatSessions")));
+        }
+        dirs.push(root.join("globalStorage/emptyWindowChatSessions"));
+    }
+
+    let mut files: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|d| fs::read_dir(d).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Workspace folder recorded in `workspaceStorage/{hash}/workspace.json`.
+fn workspace_folder(session_file: &Path) -> Option<PathBuf> {
+    let hash_dir = session_file.parent()?.parent()?;
+    let data = fs::read_to_string(hash_dir.join("workspace.json")).ok()?;
+    let val: Value = serde_json::from_str(&data).ok()?;
+    let folder = val.get("folder").and_then(|v| v.as_str())?;
+    file_uri_to_path(folder)
+}
+
+/// Join the markdown parts of a response, skipping tool and reference parts.
+fn response_text(response: &Value) -> String {
+    match response {
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part.get("value").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join(""),
+        Value::String(s) => s.clone(),
+        _ => String::new(),
+    }
+}
+
+/// Parse a chat session file into a NormalizedConversation.
+fn parse_chat_session(path: &Path) -> Result<Option<NormalizedConversation>> {
+    let data = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let val: Value =
+        serde_json::from_str(&data).with_context(|| format!("parse {}", path.display()))?;
+
+    let mut messages = Vec::new();
+    if let Some(requests) = val.get("requests").and_then(|r| r.as_array()) {
+        for request in requests {
+            let ts = request.get("timestamp").and_then(parse_timestamp);
+            let prompt = request
+                .get("message")
+                .and_then(|m| m.get("text"))
+                .and_then(|t| t.as_str())
+                .unwrap_or("");
+            if !prompt.trim().is_empty() {
+                messages.push(NormalizedMessage {
+                    idx: 0, // Will be reassigned after collection
+                    role: "user".into(),
+                    author: None,
+                    created_at: ts,
+                    content: prompt.to_string(),
+                    extra: serde_json::json!({
+                        "requestId": request.get("requestId"),
+                        "variableData": request.get("variableData"),
+                    }),
+                    snippets: Vec::new(),
+                });
+            }
+
+            let reply = request
+                .get("response")
+                .map(response_text)
+                .unwrap_or_default();
+            if !reply.trim().is_empty() {
+                messages.push(NormalizedMessage {
+                    idx: 0,
+                    role: "assistant".into(),
+                    author: request
+                        .get("modelId")
+                        .and_then(|v| v.as_str())
+                        .map(String::from),
+                    created_at: ts,
+                    content: reply,
+                    extra: serde_json::json!({
+                        "requestId": request.get("requestId"),
+                        "result": request.get("result"),
+                    }),
+                    snippets: Vec::new(),
+                });
+            }
+        }
+    }
+
+    super::reindex_messages(&mut messages);
+    if messages.is_empty() {
+        return Ok(None);
+    }
+
+    let session_id = val
+        .get("sessionId")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(String::from));
+
+    let title = val
+        .get("customTitle")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .map(String::from)
+        .or_else(|| {
+            messages.iter().find(|m| m.role == "user").map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+        });
+
+    let started_at = val
+        .get("creationDate")
+        .and_then(parse_timestamp)
+        .or_else(|| messages.iter().filter_map(|m| m.created_at).min());
+    let ended_at = val
+        .get("lastMessageDate")
+        .and_then(parse_timestamp)
+        .or_else(|| messages.iter().filter_map(|m| m.created_at).max());
+
+    Ok(Some(NormalizedConversation {
+        agent_slug: "copilot".into(),
+        external_id: session_id.clone(),
+        title,
+        workspace: workspace_folder(path),
+        source_path: path.to_path_buf(),
+        started_at,
+        ended_at,
+        metadata: serde_json::json!({
+            "source": "copilot_chat",
+            "sessionId": session_id,
+            "location": val.get("initialLocation").and_then(|v| v.as_str()),
+            "requester": val.get("requesterUsername").and_then(|v| v.as_str()),
+        }),
+        messages,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn response_text_keeps_markdown_parts_only() {
+        let response = json!([
+            {"value": "Use `cargo fmt`", "supportThemeIcons": false},
+            {"kind": "inlineReference", "inlineReference": {"path": "/x"}},
+            {"value": " before committing."}
+        ]);
+        assert_eq!(
+            response_text(&response),
+            "Use `cargo fmt` before committing."
+        );
+        assert_eq!(response_text(&json!(null)), "");
+    }
+
+    #[test]
+    fn parse_reads_requests_and_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let hash_dir = tmp.path().join("workspaceStorage/abc123");
+        let chat_dir = hash_dir.join("chatSessions");
+        fs::create_dir_all(&chat_dir).unwrap();
+        fs::write(
+            hash_dir.join("workspace.json"),
+            r#"{"folder":"file:///home/dev/shop"}"#,
+        )
+        .unwrap();
+        let file = chat_dir.join("sess-1.json");
+        fs::write(
+            &file,
+            json!({
+                "version": 3,
+                "sessionId": "sess-1",
+                "creationDate": 1_710_000_000_000i64,
+                "lastMessageDate": 1_710_000_090_000i64,
+                "initialLocation": "panel",
+                "requests": [
+                    {"requestId": "r1", "timestamp": 1_710_000_000_000i64,
+                     "message": {"text": "Explain the cart reducer\nplease"},
+                     "response": [{"value": "It folds actions into state."}],
+                     "modelId": "copilot/gpt-4o"},
+                    {"requestId": "r2", "timestamp": 1_710_000_090_000i64,
+                     "message": {"text": "   "}, "response": []}
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let conv = parse_chat_session(&file).unwrap().unwrap();
+        assert_eq!(conv.agent_slug, "copilot");
+        assert_eq!(conv.external_id.as_deref(), Some("sess-1"));
+        assert_eq!(conv.title.as_deref(), Some("Explain the cart reducer"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/home/dev/shop")));
+        assert_eq!(conv.messages.len(), 2);
+        assert_eq!(conv.messages[1].author.as_deref(), Some("copilot/gpt-4o"));
+        assert_eq!(conv.ended_at, Some(1_710_000_090_000));
+    }
+
+    #[test]
+    fn session_files_accepts_user_and_chat_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let user = tmp.path().join("User");
+        let chat = user.join("workspaceStorage/h1/chatSessions");
+        let empty = user.join("globalStorage/emptyWindowChatSessions");
+        fs::create_dir_all(&chat).unwrap();
+        fs::create_dir_all(&empty).unwrap();
+        fs::write(chat.join("a.json"), "{}").unwrap();
+        fs::write(empty.join("b.json"), "{}").unwrap();
+        fs::write(chat.join("notes.txt"), "").unwrap();
+
+        assert_eq!(session_files(&user).len(), 2);
+        assert_eq!(session_files(&chat).len(), 1);
+        assert!(looks_like_copilot_storage(&user));
+        assert!(looks_like_copilot_storage(&chat));
+        assert!(!looks_like_copilot_storage(tmp.path()));
+    }
+}
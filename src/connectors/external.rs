@@ -0,0 +1,652 @@
+//! External (subprocess) connectors declared in `connectors.toml`.
+//!
+//! An external connector is any executable that emits one
+//! [`NormalizedConversation`] JSON object per line on stdout. This lets
+//! in-house agents be indexed without adding a Rust module to cass.
+//!
+//! # Configuration
+//!
+//! Stored next to `sources.toml` (e.g. `~/.config/cass/connectors.toml`):
+//!
+//! ```toml
+//! [[connectors]]
+//! name = "acme"                      # default agent slug
+//! command = "/usr/local/bin/cass-acme"
+//! args = ["--jsonl"]
+//! roots = ["~/.acme/sessions"]       # detection + watch roots
+//! env = { ACME_PROFILE = "work" }
+//! timeout_secs = 300                 # kill the child after this long (default 300)
+//! ```
+//!
+//! # Protocol (version 1)
+//!
+//! cass writes a single JSON line to the child's stdin describing the scan:
+//!
+//! ```json
+//! {"protocol":1,"connector":"acme","data_dir":"...","since_ts":1700000000000,
+//!  "scan_roots":[{"path":"/home/me/.acme/sessions","origin":{...},"platform":null}]}
+//! ```
+//!
+//! The same fields are exported as `CASS_CONNECTOR_PROTOCOL`, `CASS_CONNECTOR_NAME`,
+//! `CASS_DATA_DIR`, `CASS_SINCE_TS` (omitted on full scans) and `CASS_SCAN_ROOTS`
+//! (platform path-list separator) for shell scripts. The child should emit only
+//! conversations changed since `since_ts`; cass additionally drops conversations
+//! whose `source_path` file is older than `since_ts`, like the built-in connectors.
+//! Provenance (`metadata.cass.origin`) is always injected by the indexer, so any
+//! value emitted by the child is overwritten.
+//!
+//! A child that runs longer than `timeout_secs` is killed and that connector's
+//! scan fails; other connectors in `connectors.toml` are still indexed.
+
+use super::{
+    Connector, DetectionResult, NormalizedConversation, ScanContext, ScanRoot, file_modified_since,
+    reindex_messages,
+};
+use crate::sources::config::{ConfigError, Platform, SourcesConfig};
+use crate::sources::provenance::Origin;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// Protocol version sent to external connectors.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Scan timeout used when a connector does not set `timeout_secs`.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+/// Root configuration for external connectors.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExternalConnectorsConfig {
+    #[serde(default)]
+    pub connectors: Vec<ExternalConnectorConfig>,
+}
+
+/// A single external connector definition.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExternalConnectorConfig {
+    /// Connector name; used as the agent slug when a conversation omits one.
+    pub name: String,
+    /// Executable to launch (absolute path or looked up on `PATH`).
+    pub command: String,
+    /// Extra arguments passed to the executable.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Directories holding the agent's sessions. Used for detection, to route
+    /// `cass index --watch` events, and passed to the child as scan roots.
+    #[serde(default)]
+    pub roots: Vec<String>,
+    /// Extra environment variables for the child process.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Seconds a scan may run before the child is killed (default 300).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl ExternalConnectorConfig {
+    /// Configured roots with `~` expanded.
+    pub fn root_paths(&self) -> Vec<PathBuf> {
+        self.roots.iter().map(|r| expand_tilde(r)).collect()
+    }
+
+    /// Scan timeout for this connector.
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    /// Resolve the executable, if it exists.
+    pub fn resolve_command(&self) -> Option<PathBuf> {
+        let path = expand_tilde(&self.command);
+        if path.components().count() > 1 {
+            return path.is_file().then_some(path);
+        }
+        which::which(&self.command).ok()
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(ConfigError::Validation(format!(
+                "Invalid connector name {:?}: use letters, digits, '-' or '_'",
+                self.name
+            )));
+        }
+        if self.command.trim().is_empty() {
+            return Err(ConfigError::Validation(format!(
+                "Connector '{}' has no command",
+                self.name
+            )));
+        }
+        if self.timeout_secs == Some(0) {
+            return Err(ConfigError::Validation(format!(
+                "Connector '{}' has timeout_secs = 0",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl ExternalConnectorsConfig {
+    /// Load configuration from the default location.
+    ///
+    /// Returns an empty config if the file doesn't exist.
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::config_path()?)
+    }
+
+    /// Load configuration from a specific path.
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)?;
+        let config: Self = toml::from_str(&content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// `connectors.toml`, next to `sources.toml`.
+    pub fn config_path() -> Result<PathBuf, ConfigError> {
+        Ok(SourcesConfig::config_path()?.with_file_name("connectors.toml"))
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut seen = std::collections::HashSet::new();
+        for connector in &self.connectors {
+            connector.validate()?;
+            if !seen.insert(&connector.name) {
+                return Err(ConfigError::Validation(format!(
+                    "Duplicate connector name: {}",
+                    connector.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Scan request written to the child's stdin.
+#[derive(Debug, Serialize)]
+struct ScanRequest<'a> {
+    protocol: u32,
+    connector: &'a str,
+    data_dir: &'a Path,
+    since_ts: Option<i64>,
+    scan_roots: Vec<ScanRootRequest<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScanRootRequest<'a> {
+    path: &'a Path,
+    origin: &'a Origin,
+    platform: Option<Platform>,
+}
+
+/// One configured external connector.
+pub struct ExternalConnector {
+    config: ExternalConnectorConfig,
+}
+
+impl ExternalConnector {
+    pub fn new(config: ExternalConnectorConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    /// Scan roots this connector should receive for `ctx`.
+    ///
+    /// With default detection, the connector's own configured roots. With explicit
+    /// roots, local roots must fall under a configured root (so watch events for one
+    /// connector don't launch the others); remote mirror roots go to every connector.
+    fn roots_for(&self, ctx: &ScanContext) -> Option<Vec<ScanRoot>> {
+        let configured = self.config.root_paths();
+        if ctx.use_default_detection() {
+            return Some(
+                configured
+                    .into_iter()
+                    .filter(|p| p.exists())
+                    .map(ScanRoot::local)
+                    .collect(),
+            );
+        }
+        let roots: Vec<ScanRoot> = ctx
+            .scan_roots
+            .iter()
+            .filter(|root| {
+                root.origin.source_id != "local"
+                    || configured
+                        .iter()
+                        .any(|c| root.path.starts_with(c) || c.starts_with(&root.path))
+            })
+            .cloned()
+            .collect();
+        (!roots.is_empty()).then_some(roots)
+    }
+
+    fn run(
+        &self,
+        program: &Path,
+        ctx: &ScanContext,
+        roots: &[ScanRoot],
+    ) -> Result<Vec<NormalizedConversation>> {
+        let name = &self.config.name;
+        let request = ScanRequest {
+            protocol: PROTOCOL_VERSION,
+            connector: name,
+            data_dir: &ctx.data_dir,
+            since_ts: ctx.since_ts,
+            scan_roots: roots
+                .iter()
+                .map(|r| ScanRootRequest {
+                    path: &r.path,
+                    origin: &r.origin,
+                    platform: r.platform,
+                })
+                .collect(),
+        };
+
+        let mut cmd = Command::new(program);
+        cmd.args(&self.config.args)
+            .envs(&self.config.env)
+            .env("CASS_CONNECTOR_PROTOCOL", PROTOCOL_VERSION.to_string())
+            .env("CASS_CONNECTOR_NAME", name)
+            .env("CASS_DATA_DIR", &ctx.data_dir)
+            .env(
+                "CASS_SCAN_ROOTS",
+                std::env::join_paths(roots.iter().map(|r| &r.path)).unwrap_or_default(),
+            )
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        match ctx.since_ts {
+            Some(ts) => cmd.env("CASS_SINCE_TS", ts.to_string()),
+            None => cmd.env_remove("CASS_SINCE_TS"),
+        };
+
+        let timeout = self.config.timeout();
+        let deadline = Instant::now() + timeout;
+        let mut child = ChildGuard(
+            cmd.spawn()
+                .with_context(|| format!("launching external connector '{name}'"))?,
+        );
+
+        if let Some(mut stdin) = child.stdin.take() {
+            let line = serde_json::to_string(&request)?;
+            // A child that ignores stdin may exit before reading it; that's fine.
+            let _ = writeln!(stdin, "{line}");
+        }
+
+        // Drain stderr on a side thread so a chatty child can't block on a full pipe.
+        let stderr_handle = child.stderr.take().map(|mut stderr| {
+            std::thread::spawn(move || {
+                let mut buf = String::new();
+                let _ = stderr.read_to_string(&mut buf);
+                buf
+            })
+        });
+
+        // Read stdout on a side thread too, so a child that stops writing without
+        // exiting can't hold the scan past its deadline. The thread is left behind
+        // on timeout if a grandchild keeps the pipe open.
+        // Lines are passed on as bytes so one that isn't UTF-8 can be skipped
+        // like any other invalid line; a read error ends the stream.
+        let (line_tx, line_rx) = mpsc::channel::<Vec<u8>>();
+        let stdout = child.stdout.take();
+        std::thread::spawn(move || {
+            if let Some(stdout) = stdout {
+                for line in BufReader::new(stdout).split(b'\n') {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if line_tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut convs = Vec::new();
+        let mut invalid = 0usize;
+        let mut line_no = 0usize;
+        loop {
+            let line =
+                match line_rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                    Ok(line) => line,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => return Err(self.timed_out(timeout)),
+                };
+            line_no += 1;
+            let Ok(line) = String::from_utf8(line) else {
+                invalid += 1;
+                tracing::warn!(
+                    connector = %name,
+                    line = line_no,
+                    "skipping conversation line that is not valid UTF-8"
+                );
+                continue;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<NormalizedConversation>(&line) {
+                Ok(conv) => {
+                    if let Some(conv) = self.normalize(conv, ctx.since_ts) {
+                        convs.push(conv);
+                    }
+                }
+                Err(e) => {
+                    invalid += 1;
+                    tracing::warn!(
+                        connector = %name,
+                        line = line_no,
+                        "skipping invalid conversation line: {e}"
+                    );
+                }
+            }
+        }
+
+        // stdout is closed; the child may still be running (e.g. it closed stdout early).
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                return Err(self.timed_out(timeout));
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let stderr = stderr_handle
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+        if !status.success() {
+            let tail: Vec<&str> = stderr.lines().rev().take(5).collect();
+            let tail: Vec<&str> = tail.into_iter().rev().collect();
+            bail!(
+                "external connector '{name}' exited with {status}: {}",
+                tail.join(" | ")
+            );
+        }
+        if !stderr.trim().is_empty() {
+            tracing::debug!(connector = %name, stderr = %stderr.trim(), "external connector stderr");
+        }
+        tracing::debug!(
+            connector = %name,
+            conversations = convs.len(),
+            invalid,
+            "external connector scan"
+        );
+        Ok(convs)
+    }
+
+    /// Describe a scan that ran past its deadline (the [`ChildGuard`] kills the child).
+    fn timed_out(&self, timeout: Duration) -> anyhow::Error {
+        anyhow::anyhow!(
+            "external connector '{}' timed out after {}s (raise timeout_secs in connectors.toml)",
+            self.config.name,
+            timeout.as_secs()
+        )
+    }
+
+    /// Fill defaults and apply the same incremental filter as built-in connectors.
+    fn normalize(
+        &self,
+        mut conv: NormalizedConversation,
+        since_ts: Option<i64>,
+    ) -> Option<NormalizedConversation> {
+        if conv.source_path.as_os_str().is_empty() {
+            tracing::warn!(
+                connector = %self.config.name,
+                "skipping conversation without source_path"
+            );
+            return None;
+        }
+        if !file_modified_since(&conv.source_path, since_ts) {
+            return None;
+        }
+        if conv.agent_slug.trim().is_empty() {
+            conv.agent_slug = self.config.name.clone();
+        }
+        if conv.metadata.is_null() {
+            conv.metadata = serde_json::json!({});
+        }
+        reindex_messages(&mut conv.messages);
+        Some(conv)
+    }
+}
+
+impl Connector for ExternalConnector {
+    fn detect(&self) -> DetectionResult {
+        let Some(program) = self.config.resolve_command() else {
+            return DetectionResult::not_found();
+        };
+        let root_paths: Vec<PathBuf> = self
+            .config
+            .root_paths()
+            .into_iter()
+            .filter(|p| p.exists())
+            .collect();
+        if !self.config.roots.is_empty() && root_paths.is_empty() {
+            return DetectionResult::not_found();
+        }
+        DetectionResult {
+            detected: true,
+            evidence: vec![format!(
+                "external connector '{}' ({})",
+                self.config.name,
+                program.display()
+            )],
+            root_paths,
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let Some(program) = self.config.resolve_command() else {
+            bail!(
+                "external connector '{}': command not found: {}",
+                self.config.name,
+                self.config.command
+            );
+        };
+        let Some(roots) = self.roots_for(ctx) else {
+            return Ok(Vec::new());
+        };
+        self.run(&program, ctx, &roots)
+    }
+}
+
+/// Kills (if still running) and reaps the child when a scan returns, including
+/// on early error returns, so no connector process outlives its scan.
+struct ChildGuard(Child);
+
+impl std::ops::Deref for ChildGuard {
+    type Target = Child;
+
+    fn deref(&self) -> &Child {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for ChildGuard {
+    fn deref_mut(&mut self) -> &mut Child {
+        &mut self.0
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        if !matches!(self.0.try_wait(), Ok(Some(_))) {
+            let _ = self.0.kill();
+        }
+        let _ = self.0.wait();
+    }
+}
+
+/// All configured external connectors, registered with the indexer as one connector.
+pub struct ExternalConnectors {
+    connectors: Vec<ExternalConnector>,
+}
+
+impl ExternalConnectors {
+    /// Load connectors from `connectors.toml`; a broken config disables them with a warning.
+    pub fn load() -> Self {
+        if dotenvy::var("CASS_IGNORE_CONNECTORS_CONFIG").is_ok() {
+            return Self::from_config(ExternalConnectorsConfig::default());
+        }
+        match ExternalConnectorsConfig::load() {
+            Ok(config) => Self::from_config(config),
+            Err(e) => {
+                tracing::warn!("external connectors disabled: {e}");
+                Self::from_config(ExternalConnectorsConfig::default())
+            }
+        }
+    }
+
+    pub fn from_config(config: ExternalConnectorsConfig) -> Self {
+        Self {
+            connectors: config
+                .connectors
+                .into_iter()
+                .map(ExternalConnector::new)
+                .collect(),
+        }
+    }
+}
+
+impl Connector for ExternalConnectors {
+    fn detect(&self) -> DetectionResult {
+        let mut result = DetectionResult::not_found();
+        for connector in &self.connectors {
+            let detection = connector.detect();
+            if detection.detected {
+                result.detected = true;
+                result.evidence.extend(detection.evidence);
+                result.root_paths.extend(detection.root_paths);
+            }
+        }
+        result
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let mut convs = Vec::new();
+        let mut failures = Vec::new();
+        for connector in &self.connectors {
+            if connector.config.resolve_command().is_none() {
+                continue;
+            }
+            match connector.scan(ctx) {
+                Ok(found) => convs.extend(found),
+                Err(e) => {
+                    tracing::warn!(connector = connector.name(), "external scan failed: {e:#}");
+                    failures.push(format!("{e:#}"));
+                }
+            }
+        }
+        // Only surface an error when nothing could be scanned at all.
+        if convs.is_empty() && !failures.is_empty() {
+            bail!(failures.join("; "));
+        }
+        Ok(convs)
+    }
+}
+
+fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    if path == "~"
+        && let Some(home) = dirs::home_dir()
+    {
+        return home;
+    }
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_parses_and_rejects_duplicates() {
+        let config: ExternalConnectorsConfig = toml::from_str(
+            r#"
+            [[connectors]]
+            name = "acme"
+            command = "cass-acme"
+            roots = ["~/.acme"]
+            env = { ACME_PROFILE = "work" }
+            "#,
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.connectors[0].env["ACME_PROFILE"], "work");
+        assert!(config.connectors[0].args.is_empty());
+
+        let mut dup = config.clone();
+        dup.connectors.push(config.connectors[0].clone());
+        assert!(dup.validate().is_err());
+
+        assert_eq!(
+            config.connectors[0].timeout(),
+            Duration::from_secs(DEFAULT_TIMEOUT_SECS)
+        );
+        let mut no_time = config.clone();
+        no_time.connectors[0].timeout_secs = Some(0);
+        assert!(no_time.validate().is_err());
+
+        let mut bad = config;
+        bad.connectors[0].name = "acme agent".into();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn roots_for_routes_local_roots_to_owning_connector() {
+        let connector = ExternalConnector::new(ExternalConnectorConfig {
+            name: "acme".into(),
+            command: "cass-acme".into(),
+            args: Vec::new(),
+            roots: vec!["/data/acme".into()],
+            env: BTreeMap::new(),
+            timeout_secs: None,
+        });
+
+        let own = ScanContext::with_roots(
+            PathBuf::from("/data/acme"),
+            vec![ScanRoot::local(PathBuf::from("/data/acme"))],
+            None,
+        );
+        assert_eq!(connector.roots_for(&own).map(|r| r.len()), Some(1));
+
+        let other = ScanContext::with_roots(
+            PathBuf::from("/data/other"),
+            vec![ScanRoot::local(PathBuf::from("/data/other"))],
+            None,
+        );
+        assert!(connector.roots_for(&other).is_none());
+
+        let remote = ScanContext::with_roots(
+            PathBuf::from("/mirror"),
+            vec![ScanRoot::remote(
+                PathBuf::from("/mirror"),
+                Origin::remote("laptop"),
+                None,
+            )],
+            None,
+        );
+        assert_eq!(connector.roots_for(&remote).map(|r| r.len()), Some(1));
+    }
+}
@@ -395,12 +395,18 @@ pub mod claude_code;
 pub mod clawdbot;
 pub mod cline;
 pub mod codex;
+pub mod continue_dev;
+pub mod copilot_chat;
 pub mod cursor;
+pub mod external;
 pub mod factory;
 pub mod gemini;
+pub mod native;
 pub mod opencode;
 pub mod pi_agent;
+pub mod roo_code;
 pub mod vibe;
+pub mod windsurf;
 
 /// High-level detection status for a connector.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -625,6 +631,153 @@ pub struct NormalizedSnippet {
 pub trait Connector {
     fn detect(&self) -> DetectionResult;
     fn scan(&self, ctx: &ScanContext) -> anyhow::Result<Vec<NormalizedConversation>>;
+
+    /// Scan lazily, resuming append-only session files from previously stored cursors.
+    ///
+    /// Connectors backed by JSONL logs override this so that only lines appended since
+    /// the cursor in `cursors` are parsed; each yielded item then carries just the new
+    /// messages (with indices continuing from the cursor) plus the cursor to persist once
+    /// the item has been ingested. The default wraps [`Connector::scan`].
+    fn scan_incremental<'a>(
+        &'a self,
+        ctx: &'a ScanContext,
+        cursors: &'a CursorMap,
+    ) -> anyhow::Result<ScanIter<'a>> {
+        let _ = cursors;
+        Ok(Box::new(self.scan(ctx)?.into_iter().map(|conversation| {
+            Ok::<_, anyhow::Error>(ScanItem::full(conversation))
+        })))
+    }
+}
+
+/// Resume position inside an append-only JSONL session file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCursor {
+    /// Inode of the file when the cursor was taken (0 where the platform has none).
+    pub inode: u64,
+    /// Byte offset just past the last consumed line.
+    pub offset: u64,
+    /// Index assigned to the next message parsed from this file.
+    pub next_idx: i64,
+}
+
+/// Stored cursors keyed by session file path.
+pub type CursorMap = HashMap<PathBuf, FileCursor>;
+
+/// Iterator returned by [`Connector::scan_incremental`].
+pub type ScanIter<'a> = Box<dyn Iterator<Item = anyhow::Result<ScanItem>> + 'a>;
+
+/// One session file yielded by an incremental scan.
+#[derive(Debug, Clone)]
+pub struct ScanItem {
+    /// `None` when the file only gained lines without messages; the cursor still moves.
+    pub conversation: Option<NormalizedConversation>,
+    /// Session file the cursor belongs to.
+    pub source_path: PathBuf,
+    /// Cursor to persist for `source_path` after ingesting this item.
+    pub cursor: Option<FileCursor>,
+    /// True when `conversation` only holds messages appended after a stored cursor.
+    pub appended: bool,
+}
+
+impl ScanItem {
+    /// A fully parsed conversation with no resume cursor.
+    pub fn full(conversation: NormalizedConversation) -> Self {
+        Self {
+            source_path: conversation.source_path.clone(),
+            conversation: Some(conversation),
+            cursor: None,
+            appended: false,
+        }
+    }
+
+    /// A cursor advanced past appended lines that held no messages.
+    pub fn cursor_only(source_path: PathBuf, cursor: FileCursor) -> Self {
+        Self {
+            conversation: None,
+            source_path,
+            cursor: Some(cursor),
+            appended: true,
+        }
+    }
+}
+
+/// Result of [`read_jsonl_from`].
+#[derive(Debug, Clone, Copy)]
+pub struct JsonlRead {
+    /// Cursor positioned after the last consumed line. `next_idx` is carried over from
+    /// the resumed cursor (or 0), callers advance it by the messages they emitted.
+    pub cursor: FileCursor,
+    /// Whether reading resumed from the supplied cursor rather than byte 0.
+    pub resumed: bool,
+}
+
+/// Read JSON values from a JSONL file, starting at `cursor` when it still applies.
+///
+/// A cursor is honoured only if the inode is unchanged and the file has not shrunk;
+/// otherwise (rotation, rewrite, truncation) the file is read from the start. Blank,
+/// malformed and non-UTF-8 lines are skipped. A final line without a trailing newline
+/// is consumed only if it parses, so a half-written entry is picked up by the next scan.
+pub fn read_jsonl_from(
+    path: &Path,
+    cursor: Option<&FileCursor>,
+    mut on_value: impl FnMut(serde_json::Value),
+) -> std::io::Result<JsonlRead> {
+    use std::io::{BufRead, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(path)?;
+    let meta = file.metadata()?;
+    let inode = file_inode(&meta);
+    let resume = cursor
+        .copied()
+        .filter(|c| c.inode == inode && c.offset <= meta.len());
+    let (mut offset, next_idx) = resume.map_or((0, 0), |c| (c.offset, c.next_idx));
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset))?;
+    }
+
+    let mut reader = std::io::BufReader::new(file);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        let read = reader.read_until(b'\n', &mut buf)?;
+        if read == 0 {
+            break;
+        }
+        let value = std::str::from_utf8(&buf)
+            .ok()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .and_then(|line| serde_json::from_str::<serde_json::Value>(line).ok());
+        if buf.last() != Some(&b'\n') && value.is_none() {
+            // Partial trailing line: leave it for the next scan.
+            break;
+        }
+        offset += read as u64;
+        if let Some(value) = value {
+            on_value(value);
+        }
+    }
+
+    Ok(JsonlRead {
+        cursor: FileCursor {
+            inode,
+            offset,
+            next_idx,
+        },
+        resumed: resume.is_some(),
+    })
+}
+
+#[cfg(unix)]
+fn file_inode(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.ino()
+}
+
+#[cfg(not(unix))]
+fn file_inode(_meta: &std::fs::Metadata) -> u64 {
+    0
 }
 
 /// Re-assign sequential indices to messages starting from 0.
@@ -792,6 +945,617 @@ fn extract_content_part(item: &serde_json::Value) -> Option<String> {
     None
 }
 
+/// Convert a `file://` URI, as stored by VS Code-based editors, to a filesystem path.
+///
+/// Percent-escapes are decoded and Windows drive URIs (`file:///c%3A/src`) lose the
+/// leading slash. Other schemes (e.g. `vscode-remote://`) yield `None`.
+pub fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // Skip the authority (`file://host/path`); local URIs have an empty one.
+    let path = &rest[rest.find('/')?..];
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(b) => {
+                decoded.push(b);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    let decoded = String::from_utf8(decoded).ok()?;
+
+    let b = decoded.as_bytes();
+    if b.len() >= 3 && b[0] == b'/' && b[1].is_ascii_alphabetic() && b[2] == b':' {
+        return Some(PathBuf::from(&decoded[1..]));
+    }
+    Some(PathBuf::from(decoded))
+}
+
+/// Canonical role label used for role filtering (`--role`, `role:`).
+///
+/// Mirrors the mapping applied when messages are persisted, so "assistant"
+/// and "agent" are interchangeable.
+pub fn canonical_role(role: &str) -> String {
+    let lower = role.trim().to_lowercase();
+    match lower.as_str() {
+        "assistant" | "agent" => "agent".to_string(),
+        _ => lower,
+    }
+}
+
+/// Maximum JSON nesting depth inspected when collecting tool names.
+const TOOL_NAME_MAX_DEPTH: usize = 8;
+
+/// Extract the (lowercased, deduplicated) tool names invoked by a message.
+///
+/// Looks at structured tool calls in the raw message JSON (`tool_use`,
+/// `function_call`, OpenAI-style `tool_calls[].function.name`) and at the
+/// `[Tool: Name ...]` markers emitted by [`flatten_content`].
+pub fn extract_tool_names(content: &str, extra: &serde_json::Value) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_tool_names(extra, &mut names, 0);
+    for marker in content.split("[Tool: ").skip(1) {
+        let end = marker.find([']', ' ']).unwrap_or(marker.len());
+        push_tool_name(&mut names, &marker[..end]);
+    }
+    names
+}
+
+fn collect_tool_names(val: &serde_json::Value, names: &mut Vec<String>, depth: usize) {
+    if depth > TOOL_NAME_MAX_DEPTH {
+        return;
+    }
+    match val {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_tool_names(item, names, depth + 1);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            let item_type = map.get("type").and_then(|v| v.as_str());
+            if matches!(
+                item_type,
+                Some("tool_use" | "server_tool_use" | "function_call" | "tool_call")
+            ) && let Some(name) = map.get("name").and_then(|v| v.as_str())
+            {
+                push_tool_name(names, name);
+            }
+            if let Some(name) = map
+                .get("function")
+                .and_then(|f| f.get("name"))
+                .and_then(|v| v.as_str())
+            {
+                push_tool_name(names, name);
+            }
+            for (key, child) in map {
+                // Tool inputs are arbitrary user data; don't mine them for names.
+                if key == "input" || key == "arguments" {
+                    continue;
+                }
+                collect_tool_names(child, names, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_tool_name(names: &mut Vec<String>, name: &str) {
+    let name = name.trim().to_lowercase();
+    if !name.is_empty() && !names.contains(&name) {
+        names.push(name);
+    }
+}
+
+// -------------------------------------------------------------------------
+// Tool events: files read/edited and shell commands run
+// -------------------------------------------------------------------------
+
+/// Key under which connectors attach tool results (exit status) to the raw
+/// JSON of the message that issued the matching call. See [`fold_tool_results`].
+pub const TOOL_RESULTS_KEY: &str = "cass_tool_results";
+
+/// How many earlier messages [`fold_tool_results`] searches for the issuing call.
+const TOOL_RESULT_LOOKBACK: usize = 64;
+
+const COMMAND_TOOLS: &[&str] = &[
+    "bash",
+    "shell",
+    "local_shell",
+    "exec_command",
+    "container.exec",
+    "run_command",
+    "run_terminal_cmd",
+    "run_shell_command",
+    "execute_command",
+    "terminal",
+];
+const EDIT_TOOLS: &[&str] = &[
+    "edit",
+    "multiedit",
+    "write",
+    "notebookedit",
+    "write_file",
+    "edit_file",
+    "create_file",
+    "replace",
+    "replace_in_file",
+    "write_to_file",
+    "search_replace",
+    "apply_diff",
+    "insert_content",
+];
+const READ_TOOLS: &[&str] = &[
+    "read",
+    "read_file",
+    "view",
+    "view_file",
+    "open_file",
+    "notebookread",
+];
+const PATH_KEYS: &[&str] = &[
+    "file_path",
+    "path",
+    "filePath",
+    "target_file",
+    "notebook_path",
+    "absolute_path",
+    "filename",
+];
+
+/// What a tool call did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolEventKind {
+    /// A file was read
+    Read,
+    /// A file was created, edited or deleted
+    Edit,
+    /// A shell command was run
+    Command,
+}
+
+impl ToolEventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ToolEventKind::Read => "read",
+            ToolEventKind::Edit => "edit",
+            ToolEventKind::Command => "command",
+        }
+    }
+}
+
+/// A structured event extracted from a message's tool calls.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ToolEvent {
+    pub kind: ToolEventKind,
+    /// Lowercased tool name (`bash`, `edit`, `apply_patch`, ...)
+    pub tool: String,
+    /// File path for read/edit events, as given to the tool
+    pub path: Option<String>,
+    /// Command line for command events
+    pub command: Option<String>,
+    pub exit_code: Option<i64>,
+    /// `Some(true)` when the call's result reported an error; `None` if no result was seen
+    pub failed: Option<bool>,
+}
+
+/// A tool invocation found in raw message JSON.
+struct RawToolCall {
+    id: Option<String>,
+    name: String,
+    input: serde_json::Value,
+}
+
+/// Outcome of a tool call, as attached under [`TOOL_RESULTS_KEY`].
+struct RawToolResult {
+    id: String,
+    is_error: bool,
+    exit_code: Option<i64>,
+}
+
+/// Extract file-touch and command events from a message's raw JSON.
+///
+/// Understands Anthropic `tool_use` blocks, OpenAI `tool_calls` /
+/// `function_call`, Codex `function_call` / `local_shell_call` /
+/// `custom_tool_call` items (including `apply_patch` file headers), and the
+/// `tool_name` / `tool_input` shape used by Cursor-style exports. Exit status
+/// comes from results folded in by [`fold_tool_results`] or from `tool_result`
+/// blocks in the same message.
+pub fn extract_tool_events(extra: &serde_json::Value) -> Vec<ToolEvent> {
+    let mut calls = Vec::new();
+    collect_tool_calls(extra, &mut calls, 0);
+    if calls.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results = Vec::new();
+    collect_tool_results(extra, &mut results, 0);
+    if let Some(attached) = extra.get(TOOL_RESULTS_KEY).and_then(|v| v.as_array()) {
+        results.extend(attached.iter().filter_map(|r| {
+            Some(RawToolResult {
+                id: r.get("id")?.as_str()?.to_string(),
+                is_error: r.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false),
+                exit_code: r.get("exit_code").and_then(|v| v.as_i64()),
+            })
+        }));
+    }
+
+    let mut events: Vec<ToolEvent> = Vec::new();
+    for call in calls {
+        let outcome = call
+            .id
+            .as_deref()
+            .and_then(|id| results.iter().find(|r| r.id == id));
+        let exit_code = outcome.and_then(|r| r.exit_code);
+        let failed = outcome.map(|r| r.is_error || exit_code.is_some_and(|c| c != 0));
+        for (kind, path, command) in classify_tool_call(&call) {
+            let event = ToolEvent {
+                kind,
+                tool: call.name.clone(),
+                path,
+                command,
+                exit_code: if kind == ToolEventKind::Command {
+                    exit_code
+                } else {
+                    None
+                },
+                failed,
+            };
+            if !events.contains(&event) {
+                events.push(event);
+            }
+        }
+    }
+    events
+}
+
+/// Attach the tool results carried by `entry` to the earlier messages that
+/// issued the matching calls (under [`TOOL_RESULTS_KEY`]).
+///
+/// Connectors call this for entries that only carry tool output (Claude Code
+/// `tool_result` user turns, Codex `function_call_output` items) so exit
+/// status survives even though the entry itself is not indexed. Returns true
+/// if `entry` carried any tool result.
+pub fn fold_tool_results(messages: &mut [NormalizedMessage], entry: &serde_json::Value) -> bool {
+    let mut results = Vec::new();
+    collect_tool_results(entry, &mut results, 0);
+    for result in &results {
+        let target = messages
+            .iter_mut()
+            .rev()
+            .take(TOOL_RESULT_LOOKBACK)
+            .find(|msg| {
+                let mut calls = Vec::new();
+                collect_tool_calls(&msg.extra, &mut calls, 0);
+                calls.iter().any(|c| c.id.as_deref() == Some(&result.id))
+            });
+        let Some(serde_json::Value::Object(map)) = target.map(|msg| &mut msg.extra) else {
+            continue;
+        };
+        let attached = map
+            .entry(TOOL_RESULTS_KEY)
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let serde_json::Value::Array(items) = attached {
+            items.push(serde_json::json!({
+                "id": result.id,
+                "is_error": result.is_error,
+                "exit_code": result.exit_code,
+            }));
+        }
+    }
+    !results.is_empty()
+}
+
+/// Parse an exit code from tool output text ("Exit code 1", "exit code: 2",
+/// "Process exited with code 127").
+pub fn parse_exit_code(text: &str) -> Option<i64> {
+    let head: String = text.chars().take(400).collect::<String>().to_lowercase();
+    for marker in ["exit code", "exited with code", "exit status"] {
+        if let Some(pos) = head.find(marker) {
+            let rest = head[pos + marker.len()..].trim_start_matches([':', ' ', '=']);
+            let digits: String = rest
+                .chars()
+                .enumerate()
+                .take_while(|(i, c)| c.is_ascii_digit() || (*i == 0 && *c == '-'))
+                .map(|(_, c)| c)
+                .collect();
+            if let Ok(code) = digits.parse() {
+                return Some(code);
+            }
+        }
+    }
+    None
+}
+
+fn collect_tool_calls(val: &serde_json::Value, calls: &mut Vec<RawToolCall>, depth: usize) {
+    if depth > TOOL_NAME_MAX_DEPTH {
+        return;
+    }
+    match val {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_tool_calls(item, calls, depth + 1);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            let str_field = |key: &str| map.get(key).and_then(|v| v.as_str());
+            let id = str_field("call_id")
+                .or_else(|| str_field("id"))
+                .map(String::from);
+            let item_type = str_field("type");
+            if matches!(
+                item_type,
+                Some(
+                    "tool_use"
+                        | "server_tool_use"
+                        | "function_call"
+                        | "tool_call"
+                        | "custom_tool_call"
+                        | "local_shell_call"
+                )
+            ) {
+                let name = str_field("name").unwrap_or(if item_type == Some("local_shell_call") {
+                    "local_shell"
+                } else {
+                    ""
+                });
+                let input = map
+                    .get("input")
+                    .or_else(|| map.get("arguments"))
+                    .or_else(|| map.get("action"));
+                push_tool_call(calls, id.clone(), name, input);
+            }
+            if let Some(function) = map.get("function").and_then(|f| f.as_object())
+                && let Some(name) = function.get("name").and_then(|v| v.as_str())
+            {
+                push_tool_call(calls, id.clone(), name, function.get("arguments"));
+            }
+            if let Some(call) = map.get("function_call").and_then(|f| f.as_object())
+                && let Some(name) = call.get("name").and_then(|v| v.as_str())
+            {
+                push_tool_call(calls, id.clone(), name, call.get("arguments"));
+            }
+            if let Some(name) = str_field("tool_name") {
+                push_tool_call(calls, id, name, map.get("tool_input"));
+            }
+            for (key, child) in map {
+                if matches!(
+                    key.as_str(),
+                    "input"
+                        | "arguments"
+                        | "action"
+                        | "function"
+                        | "function_call"
+                        | "tool_input"
+                        | TOOL_RESULTS_KEY
+                ) {
+                    continue;
+                }
+                collect_tool_calls(child, calls, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_tool_call(
+    calls: &mut Vec<RawToolCall>,
+    id: Option<String>,
+    name: &str,
+    input: Option<&serde_json::Value>,
+) {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return;
+    }
+    // Function-call arguments arrive as a JSON-encoded string; custom tools
+    // (e.g. Codex `apply_patch`) pass raw text.
+    let input = match input {
+        Some(serde_json::Value::String(s)) => {
+            serde_json::from_str(s).unwrap_or_else(|_| serde_json::Value::String(s.clone()))
+        }
+        Some(v) => v.clone(),
+        None => serde_json::Value::Null,
+    };
+    calls.push(RawToolCall { id, name, input });
+}
+
+fn collect_tool_results(val: &serde_json::Value, results: &mut Vec<RawToolResult>, depth: usize) {
+    if depth > TOOL_NAME_MAX_DEPTH {
+        return;
+    }
+    match val {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_tool_results(item, results, depth + 1);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            let item_type = map.get("type").and_then(|v| v.as_str());
+            let id = match item_type {
+                Some("tool_result") => map.get("tool_use_id"),
+                Some("function_call_output" | "custom_tool_call_output") => map.get("call_id"),
+                _ if map.get("role").and_then(|v| v.as_str()) == Some("tool") => {
+                    map.get("tool_call_id")
+                }
+                _ => None,
+            }
+            .and_then(|v| v.as_str());
+            if let Some(id) = id {
+                let output = map.get("content").or_else(|| map.get("output"));
+                results.push(tool_result_from_output(
+                    id,
+                    map.get("is_error").and_then(|v| v.as_bool()),
+                    output,
+                ));
+                return;
+            }
+            for (key, child) in map {
+                if key == "input" || key == "arguments" || key == TOOL_RESULTS_KEY {
+                    continue;
+                }
+                collect_tool_results(child, results, depth + 1);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn tool_result_from_output(
+    id: &str,
+    is_error: Option<bool>,
+    output: Option<&serde_json::Value>,
+) -> RawToolResult {
+    let output = match output {
+        // Codex wraps shell output as a JSON string with metadata.exit_code
+        Some(serde_json::Value::String(s)) => {
+            serde_json::from_str(s).unwrap_or_else(|_| serde_json::Value::String(s.clone()))
+        }
+        Some(v) => v.clone(),
+        None => serde_json::Value::Null,
+    };
+    let exit_code = output
+        .get("metadata")
+        .and_then(|m| m.get("exit_code"))
+        .or_else(|| output.get("exit_code"))
+        .and_then(|v| v.as_i64())
+        .or_else(|| {
+            let text = match &output {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Array(_) => flatten_content(&output),
+                other => other
+                    .get("output")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            };
+            parse_exit_code(&text)
+        });
+    RawToolResult {
+        id: id.to_string(),
+        is_error: is_error.unwrap_or(false),
+        exit_code,
+    }
+}
+
+type ClassifiedEvent = (ToolEventKind, Option<String>, Option<String>);
+
+fn classify_tool_call(call: &RawToolCall) -> Vec<ClassifiedEvent> {
+    let name = call.name.as_str();
+    let input = &call.input;
+
+    if name == "apply_patch" {
+        let patch = input
+            .as_str()
+            .or_else(|| input.get("input").and_then(|v| v.as_str()))
+            .or_else(|| input.get("patch").and_then(|v| v.as_str()))
+            .unwrap_or_default();
+        return patch_file_events(patch);
+    }
+
+    // Anthropic text editor tool: one name, sub-command decides read vs edit
+    if name.starts_with("str_replace") || name == "text_editor" {
+        let kind = match input.get("command").and_then(|v| v.as_str()) {
+            Some("view") => ToolEventKind::Read,
+            _ => ToolEventKind::Edit,
+        };
+        return input_path(input)
+            .map(|p| vec![(kind, Some(p), None)])
+            .unwrap_or_default();
+    }
+
+    let kind = if COMMAND_TOOLS.contains(&name) {
+        ToolEventKind::Command
+    } else if EDIT_TOOLS.contains(&name) {
+        ToolEventKind::Edit
+    } else if READ_TOOLS.contains(&name) {
+        ToolEventKind::Read
+    } else {
+        return Vec::new();
+    };
+
+    if kind != ToolEventKind::Command {
+        return input_path(input)
+            .map(|p| vec![(kind, Some(p), None)])
+            .unwrap_or_default();
+    }
+
+    let Some(argv) = input.get("command").or_else(|| input.get("cmd")) else {
+        return Vec::new();
+    };
+    match argv {
+        serde_json::Value::String(s) if !s.trim().is_empty() => {
+            vec![(kind, None, Some(s.trim().to_string()))]
+        }
+        serde_json::Value::Array(parts) => {
+            let parts: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+            // Codex runs `apply_patch` through its shell tool
+            if parts.first() == Some(&"apply_patch") {
+                return patch_file_events(parts.get(1).copied().unwrap_or_default());
+            }
+            let command = match parts.as_slice() {
+                [shell, flag, script, ..]
+                    if matches!(*shell, "bash" | "sh" | "zsh") && matches!(*flag, "-c" | "-lc") =>
+                {
+                    script.trim().to_string()
+                }
+                _ => parts.join(" "),
+            };
+            if command.is_empty() {
+                Vec::new()
+            } else {
+                vec![(kind, None, Some(command))]
+            }
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn input_path(input: &serde_json::Value) -> Option<String> {
+    PATH_KEYS
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+}
+
+/// File edits named by the `*** Add/Update/Delete File:` headers of an apply_patch body.
+fn patch_file_events(patch: &str) -> Vec<ClassifiedEvent> {
+    patch
+        .lines()
+        .filter_map(|line| {
+            [
+                "*** Add File: ",
+                "*** Update File: ",
+                "*** Delete File: ",
+                "*** Move to: ",
+            ]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+        })
+        .map(|path| {
+            (
+                ToolEventKind::Edit,
+                Some(path.trim().to_string()),
+                None::<String>,
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1060,6 +1824,176 @@ mod tests {
         assert!(super::flatten_content(&val).is_empty());
     }
 
+    #[test]
+    fn file_uri_to_path_decodes_local_uris() {
+        assert_eq!(
+            file_uri_to_path("file:///home/dev/my%20app"),
+            Some(PathBuf::from("/home/dev/my app"))
+        );
+        assert_eq!(
+            file_uri_to_path("file:///c%3A/Users/dev/repo"),
+            Some(PathBuf::from("c:/Users/dev/repo"))
+        );
+        assert_eq!(
+            file_uri_to_path("file://host/share/x"),
+            Some(PathBuf::from("/share/x"))
+        );
+        assert_eq!(file_uri_to_path("/plain/path"), None);
+        assert_eq!(file_uri_to_path("vscode-remote://ssh/x"), None);
+    }
+
+    #[test]
+    fn canonical_role_merges_assistant_and_agent() {
+        assert_eq!(canonical_role("assistant"), "agent");
+        assert_eq!(canonical_role(" Agent "), "agent");
+        assert_eq!(canonical_role("USER"), "user");
+        assert_eq!(canonical_role("tool"), "tool");
+    }
+
+    #[test]
+    fn extract_tool_names_from_structured_and_markers() {
+        let extra = serde_json::json!({
+            "message": {
+                "content": [
+                    {"type": "tool_use", "name": "Bash", "input": {"command": "ls", "name": "Ignored"}},
+                    {"type": "text", "text": "done"}
+                ]
+            },
+            "tool_calls": [{"function": {"name": "read_file", "arguments": "{}"}}]
+        });
+        let names = extract_tool_names("[Tool: Edit - src/lib.rs]\n[Tool: Bash]", &extra);
+        assert_eq!(names, vec!["bash", "read_file", "edit"]);
+    }
+
+    #[test]
+    fn extract_tool_names_empty_for_plain_messages() {
+        let names = extract_tool_names("just text", &serde_json::json!({"role": "user"}));
+        assert!(names.is_empty());
+    }
+
+    fn tool_msg(idx: i64, role: &str, extra: serde_json::Value) -> NormalizedMessage {
+        NormalizedMessage {
+            idx,
+            role: role.to_string(),
+            author: None,
+            created_at: None,
+            content: String::new(),
+            extra,
+            snippets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn extract_tool_events_classifies_reads_edits_and_commands() {
+        let extra = serde_json::json!({
+            "message": {
+                "content": [
+                    {"type": "tool_use", "id": "t1", "name": "Read", "input": {"file_path": "/repo/src/lib.rs"}},
+                    {"type": "tool_use", "id": "t2", "name": "Edit", "input": {"file_path": "/repo/src/main.rs"}},
+                    {"type": "tool_use", "id": "t3", "name": "Bash", "input": {"command": "cargo test"}},
+                    {"type": "tool_use", "id": "t4", "name": "Grep", "input": {"pattern": "fn"}}
+                ]
+            }
+        });
+        let events = extract_tool_events(&extra);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].kind, ToolEventKind::Read);
+        assert_eq!(events[0].path.as_deref(), Some("/repo/src/lib.rs"));
+        assert_eq!(events[1].kind, ToolEventKind::Edit);
+        assert_eq!(events[2].kind, ToolEventKind::Command);
+        assert_eq!(events[2].command.as_deref(), Some("cargo test"));
+        assert_eq!(events[2].failed, None);
+    }
+
+    #[test]
+    fn extract_tool_events_handles_codex_shell_and_apply_patch() {
+        let shell = serde_json::json!({
+            "type": "function_call",
+            "name": "shell",
+            "call_id": "c1",
+            "arguments": "{\"command\":[\"bash\",\"-lc\",\"npm test\"]}"
+        });
+        let events = extract_tool_events(&shell);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].command.as_deref(), Some("npm test"));
+
+        let patch = serde_json::json!({
+            "type": "function_call",
+            "name": "shell",
+            "call_id": "c2",
+            "arguments": serde_json::json!({
+                "command": ["apply_patch", "*** Begin Patch\n*** Update File: src/a.rs\n@@\n*** Add File: src/b.rs\n+x\n*** End Patch"]
+            }).to_string()
+        });
+        let paths: Vec<_> = extract_tool_events(&patch)
+            .into_iter()
+            .map(|e| (e.kind, e.path.unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (ToolEventKind::Edit, "src/a.rs".to_string()),
+                (ToolEventKind::Edit, "src/b.rs".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn fold_tool_results_attaches_exit_status_to_calling_message() {
+        let mut messages = vec![tool_msg(
+            0,
+            "assistant",
+            serde_json::json!({
+                "message": {"content": [
+                    {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo build"}}
+                ]}
+            }),
+        )];
+        let result_entry = serde_json::json!({
+            "type": "user",
+            "message": {"content": [
+                {"type": "tool_result", "tool_use_id": "t1", "is_error": true, "content": "Exit code 101\nerror[E0425]"}
+            ]}
+        });
+        assert!(fold_tool_results(&mut messages, &result_entry));
+        assert!(!fold_tool_results(
+            &mut messages,
+            &serde_json::json!({"type": "user", "message": {"content": "hi"}})
+        ));
+
+        let events = extract_tool_events(&messages[0].extra);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].exit_code, Some(101));
+        assert_eq!(events[0].failed, Some(true));
+    }
+
+    #[test]
+    fn fold_tool_results_reads_codex_output_metadata() {
+        let mut messages = vec![tool_msg(
+            0,
+            "assistant",
+            serde_json::json!({"type": "function_call", "name": "shell", "call_id": "c1",
+                "arguments": "{\"command\":[\"ls\"]}"}),
+        )];
+        let output = serde_json::json!({
+            "type": "function_call_output",
+            "call_id": "c1",
+            "output": "{\"output\":\"\",\"metadata\":{\"exit_code\":0}}"
+        });
+        assert!(fold_tool_results(&mut messages, &output));
+        let events = extract_tool_events(&messages[0].extra);
+        assert_eq!(events[0].exit_code, Some(0));
+        assert_eq!(events[0].failed, Some(false));
+    }
+
+    #[test]
+    fn parse_exit_code_variants() {
+        assert_eq!(parse_exit_code("Exit code 1\nboom"), Some(1));
+        assert_eq!(parse_exit_code("Process exited with code 127"), Some(127));
+        assert_eq!(parse_exit_code("exit status: 2"), Some(2));
+        assert_eq!(parse_exit_code("all good"), None);
+    }
+
     #[test]
     fn flatten_content_number_returns_empty() {
         let val = serde_json::json!(42);
@@ -1584,4 +2518,84 @@ mod tests {
         // Different paths should (likely) have different hash
         assert_ne!(hash_of(&key1), hash_of(&key3));
     }
+
+    // =========================================================================
+    // read_jsonl_from tests
+    // =========================================================================
+
+    fn read_all(path: &Path, cursor: Option<&FileCursor>) -> (Vec<i64>, JsonlRead) {
+        let mut seen = Vec::new();
+        let read = read_jsonl_from(path, cursor, |v| seen.push(v["n"].as_i64().unwrap())).unwrap();
+        (seen, read)
+    }
+
+    #[test]
+    fn read_jsonl_from_resumes_at_cursor() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        std::fs::write(&path, "{\"n\":1}\nnot json\n\n{\"n\":2}\n").unwrap();
+
+        let (seen, first) = read_all(&path, None);
+        assert_eq!(seen, vec![1, 2]);
+        assert!(!first.resumed);
+        assert_eq!(first.cursor.offset, std::fs::metadata(&path).unwrap().len());
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut f, b"{\"n\":3}\n").unwrap();
+        let cursor = FileCursor {
+            next_idx: 7,
+            ..first.cursor
+        };
+        let (seen, second) = read_all(&path, Some(&cursor));
+        assert_eq!(seen, vec![3]);
+        assert!(second.resumed);
+        assert_eq!(second.cursor.next_idx, 7);
+    }
+
+    #[test]
+    fn read_jsonl_from_leaves_partial_line_for_next_scan() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        std::fs::write(&path, "{\"n\":1}\n{\"n\":").unwrap();
+
+        let (seen, read) = read_all(&path, None);
+        assert_eq!(seen, vec![1]);
+        assert_eq!(read.cursor.offset, 8);
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut f, b"2}\n").unwrap();
+        let (seen, _) = read_all(&path, Some(&read.cursor));
+        assert_eq!(seen, vec![2]);
+    }
+
+    #[test]
+    fn read_jsonl_from_restarts_on_truncation_or_replacement() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("s.jsonl");
+        std::fs::write(&path, "{\"n\":1}\n{\"n\":2}\n").unwrap();
+        let (_, read) = read_all(&path, None);
+
+        // Truncated below the cursor: start over
+        std::fs::write(&path, "{\"n\":9}\n").unwrap();
+        let (seen, again) = read_all(&path, Some(&read.cursor));
+        assert_eq!(seen, vec![9]);
+        assert!(!again.resumed);
+        assert_eq!(again.cursor.next_idx, 0);
+
+        // Replaced by a different file (new inode): start over
+        let replacement = tmp.path().join("s.jsonl.new");
+        std::fs::write(&replacement, "{\"n\":4}\n{\"n\":5}\n{\"n\":6}\n").unwrap();
+        std::fs::rename(&replacement, &path).unwrap();
+        let (seen, replaced) = read_all(&path, Some(&again.cursor));
+        if cfg!(unix) {
+            assert_eq!(seen, vec![4, 5, 6]);
+            assert!(!replaced.resumed);
+        }
+    }
 }
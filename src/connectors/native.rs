@@ -0,0 +1,101 @@
+//! Connector for conversations pulled from a remote cass by native sync.
+//!
+//! `cass sources sync` spools them, already normalized, at:
+//! - <data_dir>/remotes/<source>/mirror/cass-native/*.ndjson
+//!
+//! Each line is a [`NormalizedConversation`]. Only mirror roots are scanned;
+//! there is nothing to detect locally.
+
+use anyhow::Result;
+
+use crate::connectors::{Connector, DetectionResult, NormalizedConversation, ScanContext};
+use crate::sources::native::{SPOOL_DIR, read_spool};
+
+pub struct NativeConnector;
+
+impl Default for NativeConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NativeConnector {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Connector for NativeConnector {
+    fn detect(&self) -> DetectionResult {
+        DetectionResult::not_found()
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let mut convs = Vec::new();
+        for root in &ctx.scan_roots {
+            if root.path.file_name().is_some_and(|name| name == SPOOL_DIR) {
+                convs.extend(read_spool(&root.path, ctx.since_ts)?);
+            }
+        }
+        Ok(convs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connectors::ScanRoot;
+    use crate::sources::provenance::Origin;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn scans_only_spool_roots() {
+        let tmp = TempDir::new().unwrap();
+        let spool = tmp.path().join(SPOOL_DIR);
+        std::fs::create_dir_all(&spool).unwrap();
+        let conv = serde_json::json!({
+            "agent_slug": "claude_code",
+            "external_id": "abc",
+            "title": "Fix the build",
+            "workspace": "/home/me/project",
+            "source_path": "/home/me/.claude/projects/p/abc.jsonl",
+            "started_at": 1_700_000_000_000_i64,
+            "ended_at": null,
+            "metadata": {},
+            "messages": [{
+                "idx": 4, "role": "user", "author": null, "created_at": null,
+                "content": "why does it fail?", "extra": {}, "snippets": []
+            }]
+        });
+        std::fs::write(
+            spool.join(format!("{:020}-{:020}.ndjson", 0, 12)),
+            format!("{conv}\n"),
+        )
+        .unwrap();
+        // Agent logs elsewhere in the mirror are left to their own connectors.
+        std::fs::write(tmp.path().join("other.ndjson"), format!("{conv}\n")).unwrap();
+
+        let origin = Origin::remote("buildbox");
+        let ctx = ScanContext::with_roots(
+            tmp.path().to_path_buf(),
+            vec![
+                ScanRoot::remote(spool.clone(), origin.clone(), None),
+                ScanRoot::remote(tmp.path().to_path_buf(), origin, None),
+            ],
+            None,
+        );
+        let convs = NativeConnector::new().scan(&ctx).unwrap();
+        assert_eq!(convs.len(), 1);
+        assert_eq!(convs[0].agent_slug, "claude_code");
+        assert_eq!(convs[0].messages[0].idx, 4);
+        assert_eq!(
+            convs[0].source_path,
+            PathBuf::from("/home/me/.claude/projects/p/abc.jsonl")
+        );
+
+        let local = ScanContext::local_default(tmp.path().to_path_buf(), None);
+        assert!(NativeConnector::new().scan(&local).unwrap().is_empty());
+        assert!(!NativeConnector::new().detect().detected);
+    }
+}
@@ -0,0 +1,360 @@
+//! Roo Code connector for VS Code extension task storage.
+//!
+//! Roo Code (https://roocode.com) is a Cline fork published as
+//! `rooveterinaryinc.roo-cline`. Every task gets its own directory in the editor's
+//! global storage:
+//!   - `<editor>/User/globalStorage/rooveterinaryinc.roo-cline/tasks/{taskId}/`
+//!     - `api_conversation_history.json` — Anthropic-style `{role, content, ts}` messages
+//!     - `ui_messages.json` — webview events `{ts, type: "say"|"ask", say|ask, text}`
+//!
+//! The API history is preferred because it carries explicit roles; the UI log is
+//! the fallback for tasks that never reached the model.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    file_modified_since, flatten_content, parse_timestamp,
+};
+
+/// Extension id of Roo Code in VS Code-based editors.
+pub const EXTENSION_ID: &str = "rooveterinaryinc.roo-cline";
+
+pub struct RooCodeConnector;
+
+impl Default for RooCodeConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RooCodeConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extension storage directories for VS Code, Cursor and Windsurf.
+    fn candidate_roots() -> Vec<PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        let mut roots = Vec::new();
+        for editor in ["Code", "Cursor", "Windsurf"] {
+            for base in [
+                home.join(".config"),
+                home.join("Library/Application Support"),
+                home.join("AppData/Roaming"),
+            ] {
+                roots.push(
+                    base.join(editor)
+                        .join("User/globalStorage")
+                        .join(EXTENSION_ID),
+                );
+            }
+        }
+        roots
+    }
+
+    fn storage_roots() -> Vec<PathBuf> {
+        Self::candidate_roots()
+            .into_iter()
+            .filter(|r| r.exists())
+            .collect()
+    }
+}
+
+impl Connector for RooCodeConnector {
+    fn detect(&self) -> DetectionResult {
+        let roots = Self::storage_roots();
+        if roots.is_empty() {
+            return DetectionResult::not_found();
+        }
+        DetectionResult {
+            detected: true,
+            evidence: roots
+                .iter()
+                .map(|r| format!("found {}", r.display()))
+                .collect(),
+            root_paths: roots,
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let roots: Vec<PathBuf> = if ctx.use_default_detection() {
+            if looks_like_roo_storage(&ctx.data_dir) {
+                vec![ctx.data_dir.clone()]
+            } else {
+                Self::storage_roots()
+            }
+        } else {
+            ctx.scan_roots
+                .iter()
+                .map(|sr| sr.path.clone())
+                .filter(|p| looks_like_roo_storage(p))
+                .collect()
+        };
+
+        let mut convs = Vec::new();
+        for root in roots {
+            // Current releases nest tasks under `tasks/`; early ones did not
+            let tasks_dir = if root.join("tasks").is_dir() {
+                root.join("tasks")
+            } else {
+                root
+            };
+            let Ok(entries) = fs::read_dir(&tasks_dir) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let task_dir = entry.path();
+                if !task_dir.is_dir() {
+                    continue;
+                }
+                match parse_roo_task(&task_dir, ctx.since_ts) {
+                    Ok(Some(conv)) => convs.push(conv),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::debug!(path = %task_dir.display(), error = %e, "roo_code parse error");
+                    }
+                }
+            }
+        }
+
+        Ok(convs)
+    }
+}
+
+/// Check if a path is (or contains) Roo Code extension storage.
+fn looks_like_roo_storage(path: &Path) -> bool {
+    path.to_string_lossy().contains("roo-cline")
+        || (path
+            .file_name()
+            .is_some_and(|n| n.to_string_lossy().contains("roo"))
+            && path.join("tasks").is_dir())
+}
+
+/// Parse one task directory into a NormalizedConversation.
+fn parse_roo_task(
+    task_dir: &Path,
+    since_ts: Option<i64>,
+) -> Result<Option<NormalizedConversation>> {
+    let api_path = task_dir.join("api_conversation_history.json");
+    let ui_path = task_dir.join("ui_messages.json");
+    let (file, from_api) = if api_path.exists() {
+        (api_path, true)
+    } else if ui_path.exists() {
+        (ui_path, false)
+    } else {
+        return Ok(None);
+    };
+
+    // Skip files not modified since last scan (incremental indexing)
+    if !file_modified_since(&file, since_ts) {
+        return Ok(None);
+    }
+
+    let data = fs::read_to_string(&file).with_context(|| format!("read {}", file.display()))?;
+    let val: Value =
+        serde_json::from_str(&data).with_context(|| format!("parse {}", file.display()))?;
+    let Some(items) = val.as_array() else {
+        return Ok(None);
+    };
+
+    let mut messages = if from_api {
+        api_messages(items)
+    } else {
+        ui_messages(items)
+    };
+    super::reindex_messages(&mut messages);
+    if messages.is_empty() {
+        return Ok(None);
+    }
+
+    let first_user = messages.iter().find(|m| m.role == "user");
+    let workspace = first_user.and_then(|m| environment_cwd(&m.content));
+    let title = first_user.map(|m| {
+        let text = tagged_section(&m.content, "task").unwrap_or(&m.content);
+        text.trim()
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .take(100)
+            .collect::<String>()
+    });
+
+    Ok(Some(NormalizedConversation {
+        agent_slug: "roo_code".into(),
+        external_id: task_dir
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(String::from),
+        title,
+        workspace,
+        source_path: task_dir.to_path_buf(),
+        started_at: messages.iter().filter_map(|m| m.created_at).min(),
+        ended_at: messages.iter().filter_map(|m| m.created_at).max(),
+        metadata: serde_json::json!({
+            "source": "roo_code",
+            "file": if from_api { "api_conversation_history" } else { "ui_messages" },
+        }),
+        messages,
+    }))
+}
+
+/// Messages from `api_conversation_history.json`.
+fn api_messages(items: &[Value]) -> Vec<NormalizedMessage> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let role = item.get("role").and_then(|v| v.as_str())?;
+            let content = item.get("content").map(flatten_content).unwrap_or_default();
+            if content.trim().is_empty() {
+                return None;
+            }
+            Some(NormalizedMessage {
+                idx: 0, // Will be reassigned after collection
+                role: role.to_string(),
+                author: None,
+                created_at: item.get("ts").and_then(parse_timestamp),
+                content,
+                extra: item.clone(),
+                snippets: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// Messages from `ui_messages.json`.
+///
+/// The first `say: "text"` event is the task the user typed; later ones are model
+/// output. Follow-ups arrive as `user_feedback`. Tool and API bookkeeping events
+/// (`api_req_started`, `command`, ...) are skipped.
+fn ui_messages(items: &[Value]) -> Vec<NormalizedMessage> {
+    let mut messages = Vec::new();
+    let mut seen_task = false;
+    for item in items {
+        let kind = item.get("type").and_then(|v| v.as_str());
+        let say = item.get("say").and_then(|v| v.as_str());
+        let ask = item.get("ask").and_then(|v| v.as_str());
+        let role = match (kind, say, ask) {
+            (Some("say"), Some("text"), _) if !seen_task => {
+                seen_task = true;
+                "user"
+            }
+            (Some("say"), Some("user_feedback"), _) => "user",
+            (Some("say"), Some("text" | "completion_result"), _) => "assistant",
+            (Some("ask"), _, Some("followup" | "completion_result")) => "assistant",
+            _ => continue,
+        };
+        let text = item.get("text").and_then(|v| v.as_str()).unwrap_or("");
+        // Follow-up questions are JSON-encoded `{question, suggest}`
+        let content = serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|v| v.get("question").and_then(|q| q.as_str()).map(String::from))
+            .unwrap_or_else(|| text.to_string());
+        if content.trim().is_empty() {
+            continue;
+        }
+        messages.push(NormalizedMessage {
+            idx: 0, // Will be reassigned after collection
+            role: role.to_string(),
+            author: None,
+            created_at: item.get("ts").and_then(parse_timestamp),
+            content,
+            extra: item.clone(),
+            snippets: Vec::new(),
+        });
+    }
+    messages
+}
+
+/// Text between `<tag>` and `</tag>`, if present.
+fn tagged_section<'a>(text: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = text.find(&open)? + open.len();
+    let end = text[start..].find(&close)? + start;
+    Some(&text[start..end])
+}
+
+/// Workspace from the `<environment_details>` block Roo appends to user turns:
+/// `# Current Workspace Directory (/path/to/project) Files`.
+fn environment_cwd(text: &str) -> Option<PathBuf> {
+    [
+        "# Current Workspace Directory (",
+        "# Current Working Directory (",
+    ]
+    .iter()
+    .find_map(|marker| {
+        let start = text.find(marker)? + marker.len();
+        let end = text[start..].find(") Files")? + start;
+        Some(PathBuf::from(&text[start..end]))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn environment_cwd_extracts_workspace() {
+        let text = "<task>fix it</task>\n<environment_details>\n# Current Workspace Directory (/home/dev/api) Files\nsrc/\n</environment_details>";
+        assert_eq!(environment_cwd(text), Some(PathBuf::from("/home/dev/api")));
+        assert_eq!(tagged_section(text, "task"), Some("fix it"));
+        assert_eq!(environment_cwd("no details"), None);
+    }
+
+    #[test]
+    fn ui_messages_classify_task_feedback_and_replies() {
+        let items = vec![
+            json!({"ts": 1, "type": "say", "say": "text", "text": "Add a health check"}),
+            json!({"ts": 2, "type": "say", "say": "api_req_started", "text": "{\"tokensIn\":10}"}),
+            json!({"ts": 3, "type": "say", "say": "text", "text": "I'll add /healthz"}),
+            json!({"ts": 4, "type": "ask", "ask": "followup", "text": "{\"question\":\"Which port?\",\"suggest\":[]}"}),
+            json!({"ts": 5, "type": "say", "say": "user_feedback", "text": "8080"}),
+            json!({"ts": 6, "type": "ask", "ask": "command", "text": "cargo test"}),
+        ];
+        let msgs = ui_messages(&items);
+        let roles: Vec<_> = msgs.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, vec!["user", "assistant", "assistant", "user"]);
+        assert_eq!(msgs[2].content, "Which port?");
+    }
+
+    #[test]
+    fn parse_prefers_api_history_and_strips_task_tags() {
+        let tmp = TempDir::new().unwrap();
+        let task = tmp.path().join("task-1");
+        fs::create_dir_all(&task).unwrap();
+        fs::write(
+            task.join("api_conversation_history.json"),
+            json!([
+                {"role": "user", "ts": 1_700_000_000_000i64, "content": [
+                    {"type": "text", "text": "<task>\nRename the crate\n</task>"},
+                    {"type": "text", "text": "<environment_details>\n# Current Workspace Directory (/src/demo) Files\n</environment_details>"}
+                ]},
+                {"role": "assistant", "ts": 1_700_000_005_000i64, "content": [{"type": "text", "text": "Renamed."}]}
+            ])
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(task.join("ui_messages.json"), "[]").unwrap();
+
+        let conv = parse_roo_task(&task, None).unwrap().unwrap();
+        assert_eq!(conv.agent_slug, "roo_code");
+        assert_eq!(conv.external_id.as_deref(), Some("task-1"));
+        assert_eq!(conv.title.as_deref(), Some("Rename the crate"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/src/demo")));
+        assert_eq!(conv.messages.len(), 2);
+        assert_eq!(conv.started_at, Some(1_700_000_000_000));
+        assert_eq!(conv.ended_at, Some(1_700_000_005_000));
+        assert_eq!(conv.metadata["file"], "api_conversation_history");
+    }
+}
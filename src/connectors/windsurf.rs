@@ -0,0 +1,358 @@
+//! Windsurf (Cascade) connector for trajectory JSON files.
+//!
+//! Windsurf (https://windsurf.com) keeps Cascade conversations under
+//! `~/.codeium/windsurf/cascade/` (`windsurf-next` for the preview build). The
+//! live store is `{cascadeId}.pb`, an undocumented protobuf that this connector
+//! does not decode; it indexes the JSON trajectories that sit alongside it, which
+//! Windsurf writes when a conversation is exported or shared:
+//!   - `{cascadeId}.json` — `{trajectory: {cascadeId, steps: [...]}}` or `{steps: [...]}`
+//!
+//! Conversations that were never exported are not indexed. `detect` reports how
+//! many live conversations have no exported trajectory so `cass diag` can say so.
+//!
+//! Steps are typed `CORTEX_STEP_TYPE_*`. `USER_INPUT` steps carry the prompt and
+//! `PLANNER_RESPONSE` steps the model's reply; tool steps are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::connectors::{
+    Connector, DetectionResult, NormalizedConversation, NormalizedMessage, ScanContext,
+    file_modified_since, file_uri_to_path, parse_timestamp,
+};
+
+pub struct WindsurfConnector;
+
+impl Default for WindsurfConnector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WindsurfConnector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Cascade directories for the stable and preview builds.
+    fn cascade_roots() -> Vec<PathBuf> {
+        let Some(home) = dirs::home_dir() else {
+            return Vec::new();
+        };
+        ["windsurf", "windsurf-next"]
+            .iter()
+            .map(|build| home.join(".codeium").join(build).join("cascade"))
+            .filter(|r| r.exists())
+            .collect()
+    }
+}
+
+impl Connector for WindsurfConnector {
+    fn detect(&self) -> DetectionResult {
+        let roots = Self::cascade_roots();
+        if roots.is_empty() {
+            return DetectionResult::not_found();
+        }
+        DetectionResult {
+            detected: true,
+            evidence: roots.iter().map(|r| root_evidence(r)).collect(),
+            root_paths: roots,
+        }
+    }
+
+    fn scan(&self, ctx: &ScanContext) -> Result<Vec<NormalizedConversation>> {
+        let roots: Vec<PathBuf> = if ctx.use_default_detection() {
+            if looks_like_cascade_dir(&ctx.data_dir) {
+                vec![ctx.data_dir.clone()]
+            } else {
+                Self::cascade_roots()
+            }
+        } else {
+            ctx.scan_roots
+                .iter()
+                .filter_map(|sr| {
+                    let nested = sr.path.join(".codeium/windsurf/cascade");
+                    if nested.exists() {
+                        Some(nested)
+                    } else if looks_like_cascade_dir(&sr.path) {
+                        Some(sr.path.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        let mut convs = Vec::new();
+        for root in roots {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                // Skip files not modified since last scan (incremental indexing)
+                if !file_modified_since(&path, ctx.since_ts) {
+                    continue;
+                }
+                match parse_trajectory(&path) {
+                    Ok(Some(conv)) => convs.push(conv),
+                    Ok(None) => {}
+                    Err(e) => {
+                        tracing::debug!(path = %path.display(), error = %e, "windsurf parse error");
+                    }
+                }
+            }
+        }
+
+        Ok(convs)
+    }
+}
+
+/// Detection evidence for a Cascade directory, including how many live `.pb`
+/// conversations have no exported `.json` trajectory and are therefore skipped.
+fn root_evidence(root: &Path) -> String {
+    let mut exported = std::collections::HashSet::new();
+    let mut live = Vec::new();
+    for entry in fs::read_dir(root).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()).map(String::from) else {
+            continue;
+        };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => {
+                exported.insert(stem);
+            }
+            Some("pb") => live.push(stem),
+            _ => {}
+        }
+    }
+    let unexported = live.iter().filter(|id| !exported.contains(*id)).count();
+    let mut evidence = format!(
+        "found {} ({} exported trajectories)",
+        root.display(),
+        exported.len()
+    );
+    if unexported > 0 {
+        evidence.push_str(&format!(
+            "; {unexported} live .pb conversations are not indexed until exported from Windsurf"
+        ));
+    }
+    evidence
+}
+
+/// Check if a directory looks like a Windsurf Cascade store.
+fn looks_like_cascade_dir(path: &Path) -> bool {
+    path.file_name().is_some_and(|n| n == "cascade")
+        && path.to_string_lossy().to_lowercase().contains("windsurf")
+}
+
+/// Text of a user input step (`userResponse`, or the joined `items[].text`).
+fn user_input_text(step: &Value) -> Option<String> {
+    let input = step.get("userInput")?;
+    if let Some(text) = input.get("userResponse").and_then(|v| v.as_str()) {
+        return Some(text.to_string());
+    }
+    let items = input.get("items")?.as_array()?;
+    Some(
+        items
+            .iter()
+            .filter_map(|i| i.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join(""),
+    )
+}
+
+/// Text of a planner response step.
+fn planner_text(step: &Value) -> Option<String> {
+    let planner = step.get("plannerResponse")?;
+    planner
+        .get("response")
+        .or_else(|| planner.get("modifiedResponse"))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+}
+
+/// Workspace from trajectory metadata (`workspace`/`cwd` or a folder URI).
+fn trajectory_workspace(trajectory: &Value) -> Option<PathBuf> {
+    for key in ["workspace", "cwd"] {
+        if let Some(dir) = trajectory.get(key).and_then(|v| v.as_str()) {
+            return Some(file_uri_to_path(dir).unwrap_or_else(|| PathBuf::from(dir)));
+        }
+    }
+    trajectory
+        .get("metadata")
+        .and_then(|m| m.get("workspaces"))
+        .and_then(|w| w.as_array())
+        .and_then(|w| w.first())
+        .and_then(|w| w.get("workspaceFolderAbsoluteUri"))
+        .and_then(|v| v.as_str())
+        .and_then(file_uri_to_path)
+}
+
+/// Parse a Cascade trajectory export into a NormalizedConversation.
+fn parse_trajectory(path: &Path) -> Result<Option<NormalizedConversation>> {
+    let data = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let val: Value =
+        serde_json::from_str(&data).with_context(|| format!("parse {}", path.display()))?;
+    let trajectory = val.get("trajectory").unwrap_or(&val);
+    let Some(steps) = trajectory.get("steps").and_then(|s| s.as_array()) else {
+        return Ok(None);
+    };
+
+    let mut messages = Vec::new();
+    for step in steps {
+        let kind = step.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let (role, content) = if kind.ends_with("USER_INPUT") {
+            ("user", user_input_text(step))
+        } else if kind.ends_with("PLANNER_RESPONSE") {
+            ("assistant", planner_text(step))
+        } else {
+            continue;
+        };
+        let Some(content) = content.filter(|c| !c.trim().is_empty()) else {
+            continue;
+        };
+        messages.push(NormalizedMessage {
+            idx: 0, // Will be reassigned after collection
+            role: role.to_string(),
+            author: None,
+            created_at: step
+                .get("metadata")
+                .and_then(|m| m.get("createdAt"))
+                .and_then(parse_timestamp),
+            content,
+            extra: step.clone(),
+            snippets: Vec::new(),
+        });
+    }
+
+    super::reindex_messages(&mut messages);
+    if messages.is_empty() {
+        return Ok(None);
+    }
+
+    let cascade_id = trajectory
+        .get("cascadeId")
+        .or_else(|| trajectory.get("trajectoryId"))
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| path.file_stem().and_then(|s| s.to_str()).map(String::from));
+
+    let title = val
+        .get("summary")
+        .or_else(|| trajectory.get("summary"))
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .map(String::from)
+        .or_else(|| {
+            messages.iter().find(|m| m.role == "user").map(|m| {
+                m.content
+                    .lines()
+                    .next()
+                    .unwrap_or(&m.content)
+                    .chars()
+                    .take(100)
+                    .collect::<String>()
+            })
+        });
+
+    Ok(Some(NormalizedConversation {
+        agent_slug: "windsurf".into(),
+        external_id: cascade_id.clone(),
+        title,
+        workspace: trajectory_workspace(trajectory),
+        source_path: path.to_path_buf(),
+        started_at: messages.iter().filter_map(|m| m.created_at).min(),
+        ended_at: messages.iter().filter_map(|m| m.created_at).max(),
+        metadata: serde_json::json!({
+            "source": "windsurf",
+            "cascadeId": cascade_id,
+        }),
+        messages,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn looks_like_cascade_dir_requires_windsurf_path() {
+        assert!(looks_like_cascade_dir(Path::new(
+            "/home/u/.codeium/windsurf/cascade"
+        )));
+        assert!(looks_like_cascade_dir(Path::new(
+            "/home/u/.codeium/windsurf-next/cascade"
+        )));
+        assert!(!looks_like_cascade_dir(Path::new("/home/u/other/cascade")));
+    }
+
+    #[test]
+    fn root_evidence_counts_unexported_live_conversations() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["a.pb", "a.json", "b.pb", "c.pb", "notes.txt"] {
+            fs::write(tmp.path().join(name), b"").unwrap();
+        }
+        let evidence = root_evidence(tmp.path());
+        assert!(evidence.contains("(1 exported trajectories)"), "{evidence}");
+        assert!(
+            evidence.contains("2 live .pb conversations are not indexed"),
+            "{evidence}"
+        );
+
+        fs::remove_file(tmp.path().join("b.pb")).unwrap();
+        fs::remove_file(tmp.path().join("c.pb")).unwrap();
+        assert!(!root_evidence(tmp.path()).contains("not indexed"));
+    }
+
+    #[test]
+    fn parse_keeps_user_and_planner_steps() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("c1.json");
+        fs::write(
+            &file,
+            json!({
+                "trajectory": {
+                    "cascadeId": "c1",
+                    "metadata": {"workspaces": [{"workspaceFolderAbsoluteUri": "file:///home/dev/blog"}]},
+                    "steps": [
+                        {"type": "CORTEX_STEP_TYPE_USER_INPUT",
+                         "metadata": {"createdAt": "2025-03-01T10:00:00Z"},
+                         "userInput": {"items": [{"text": "Add RSS "}, {"text": "feed"}]}},
+                        {"type": "CORTEX_STEP_TYPE_VIEW_FILE", "viewFile": {"absolutePathUri": "file:///x"}},
+                        {"type": "CORTEX_STEP_TYPE_PLANNER_RESPONSE",
+                         "metadata": {"createdAt": "2025-03-01T10:00:30Z"},
+                         "plannerResponse": {"response": "Added feed.xml route."}}
+                    ]
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let conv = parse_trajectory(&file).unwrap().unwrap();
+        assert_eq!(conv.agent_slug, "windsurf");
+        assert_eq!(conv.external_id.as_deref(), Some("c1"));
+        assert_eq!(conv.title.as_deref(), Some("Add RSS feed"));
+        assert_eq!(conv.workspace, Some(PathBuf::from("/home/dev/blog")));
+        assert_eq!(conv.messages.len(), 2);
+        assert_eq!(conv.messages[1].role, "assistant");
+        assert!(conv.started_at < conv.ended_at);
+    }
+
+    #[test]
+    fn parse_returns_none_without_steps() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("other.json");
+        fs::write(&file, r#"{"settings": true}"#).unwrap();
+        assert!(parse_trajectory(&file).unwrap().is_none());
+    }
+}
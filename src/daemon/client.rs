@@ -1,25 +1,28 @@
 //! Daemon client for connecting to the semantic model daemon.
 //!
 //! This client connects via Unix Domain Socket and provides methods for
-//! embedding and reranking. It implements the `DaemonClient` trait from
-//! `search::daemon_client` for integration with the fallback wrappers.
+//! embedding, reranking and (protocol v2+) streamed search. It implements the
+//! `DaemonClient` trait from `search::daemon_client` for integration with the
+//! fallback wrappers.
 
 use std::io::{Read, Write};
 use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 use parking_lot::Mutex;
 use tracing::{debug, info};
 
 use super::protocol::{
-    ErrorCode, FramedMessage, HealthStatus, PROTOCOL_VERSION, Request, Response, decode_message,
+    ErrorCode, FramedMessage, HealthStatus, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, Request,
+    Response, SEARCH_PROTOCOL_VERSION, SearchRequest, SearchSummary, decode_message,
     default_socket_path, encode_message,
 };
 use crate::search::daemon_client::{DaemonClient, DaemonError};
+use crate::search::query::{SearchHit, SearchResult};
 
 /// Configuration for the daemon client.
 #[derive(Debug, Clone)]
@@ -88,6 +91,8 @@ pub struct UdsDaemonClient {
     available: AtomicBool,
     request_counter: AtomicU64,
     last_health_check: Mutex<Option<Instant>>,
+    /// Protocol version the daemon last answered in (0 = not yet known).
+    peer_version: AtomicU32,
 }
 
 impl UdsDaemonClient {
@@ -99,6 +104,7 @@ impl UdsDaemonClient {
             available: AtomicBool::new(false),
             request_counter: AtomicU64::new(0),
             last_health_check: Mutex::new(None),
+            peer_version: AtomicU32::new(0),
         }
     }
 
@@ -231,6 +237,13 @@ impl UdsDaemonClient {
 
     /// Send a request and receive a response.
     fn send_request(&self, request: Request) -> Result<Response, DaemonError> {
+        let mut stream = self.get_connection()?;
+        self.write_request(&mut stream, request)?;
+        self.read_response(&mut stream)
+    }
+
+    /// Frame and send one request.
+    fn write_request(&self, stream: &mut UnixStream, request: Request) -> Result<(), DaemonError> {
         let request_id = format!(
             "cass-{}",
             self.request_counter.fetch_add(1, Ordering::Relaxed)
@@ -240,14 +253,14 @@ impl UdsDaemonClient {
         let encoded = encode_message(&msg)
             .map_err(|e| DaemonError::Failed(format!("failed to encode request: {}", e)))?;
 
-        let mut stream = self.get_connection()?;
-
-        // Send request
         stream.write_all(&encoded).map_err(|e| {
             self.available.store(false, Ordering::SeqCst);
             DaemonError::Unavailable(format!("failed to send request: {}", e))
-        })?;
+        })
+    }
 
+    /// Read one response frame, mapping daemon error responses to `DaemonError`.
+    fn read_response(&self, stream: &mut UnixStream) -> Result<Response, DaemonError> {
         // Read length prefix
         let mut len_buf = [0u8; 4];
         stream.read_exact(&mut len_buf).map_err(|e| {
@@ -283,13 +296,14 @@ impl UdsDaemonClient {
         let response: FramedMessage<Response> = decode_message(&payload)
             .map_err(|e| DaemonError::Failed(format!("failed to decode response: {}", e)))?;
 
-        // Check version compatibility
-        if response.version != PROTOCOL_VERSION {
+        // The daemon answers in min(ours, theirs); anything outside our range is foreign.
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&response.version) {
             return Err(DaemonError::Failed(format!(
-                "protocol version mismatch: expected {}, got {}",
-                PROTOCOL_VERSION, response.version
+                "protocol version mismatch: expected {}..={}, got {}",
+                MIN_PROTOCOL_VERSION, PROTOCOL_VERSION, response.version
             )));
         }
+        self.peer_version.store(response.version, Ordering::SeqCst);
 
         // Handle error responses
         match response.payload {
@@ -309,6 +323,60 @@ impl UdsDaemonClient {
         }
     }
 
+    /// Protocol version negotiated with the daemon, if a response has been seen.
+    pub fn peer_version(&self) -> Option<u32> {
+        match self.peer_version.load(Ordering::SeqCst) {
+            0 => None,
+            version => Some(version),
+        }
+    }
+
+    /// Run a search on the daemon, handing each streamed batch of hits to `on_hits`.
+    ///
+    /// Fails with `Unavailable` if the daemon predates protocol v2.
+    pub fn search_stream(
+        &self,
+        request: &SearchRequest,
+        on_hits: &mut dyn FnMut(Vec<SearchHit>),
+    ) -> Result<SearchSummary, DaemonError> {
+        if self.peer_version().is_none() {
+            self.health()?;
+        }
+        let peer = self.peer_version.load(Ordering::SeqCst);
+        if peer < SEARCH_PROTOCOL_VERSION {
+            return Err(DaemonError::Unavailable(format!(
+                "daemon speaks protocol v{peer}; search needs v{SEARCH_PROTOCOL_VERSION}"
+            )));
+        }
+
+        let mut stream = self.get_connection()?;
+        self.write_request(&mut stream, Request::Search(request.clone()))?;
+        let mut expected_seq = 0;
+        let outcome = loop {
+            match self.read_response(&mut stream) {
+                Ok(Response::SearchChunk(chunk)) if chunk.seq == expected_seq => {
+                    expected_seq += 1;
+                    on_hits(chunk.hits.into_iter().map(SearchHit::from).collect());
+                    if let Some(summary) = chunk.done {
+                        break Ok(summary);
+                    }
+                }
+                Ok(other) => {
+                    break Err(DaemonError::Failed(format!(
+                        "unexpected search frame: {:?}",
+                        other
+                    )));
+                }
+                Err(err) => break Err(err),
+            }
+        };
+        if outcome.is_err() {
+            // Unread frames may still be queued on this connection; start fresh next time.
+            *self.connection.lock() = None;
+        }
+        outcome
+    }
+
     /// Check daemon health.
     pub fn health(&self) -> Result<HealthStatus, DaemonError> {
         match self.send_request(Request::Health)? {
@@ -484,6 +552,42 @@ impl DaemonClient for UdsDaemonClient {
             ))),
         }
     }
+
+    fn search(
+        &self,
+        request: &SearchRequest,
+        request_id: &str,
+    ) -> Result<SearchResult, DaemonError> {
+        debug!(
+            request_id = request_id,
+            mode = ?request.mode,
+            limit = request.limit,
+            "Daemon search request"
+        );
+
+        let mut hits = Vec::new();
+        let summary = self.search_stream(request, &mut |chunk| hits.extend(chunk))?;
+        if summary.total_hits != hits.len() {
+            return Err(DaemonError::Failed(format!(
+                "hit count mismatch: expected {}, got {}",
+                summary.total_hits,
+                hits.len()
+            )));
+        }
+        debug!(
+            request_id = request_id,
+            elapsed_ms = summary.elapsed_ms,
+            hits = hits.len(),
+            "Daemon search completed"
+        );
+        Ok(SearchResult {
+            hits,
+            wildcard_fallback: summary.wildcard_fallback,
+            cache_stats: summary.cache_stats,
+            suggestions: summary.suggestions.into_iter().map(Into::into).collect(),
+            ann_stats: summary.ann_stats,
+        })
+    }
 }
 
 /// Connect to an existing daemon or spawn a new one.
@@ -1,8 +1,11 @@
 //! Daemon server core for the semantic model daemon.
 //!
 //! This module provides the server that listens on a Unix Domain Socket
-//! and handles embedding/reranking requests using loaded models.
+//! and handles embedding/reranking requests using loaded models, plus search
+//! requests served from `SearchClient`s it keeps open per index.
 
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::io::{Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
@@ -10,16 +13,21 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
 use super::models::ModelManager;
 use super::protocol::{
-    EmbedResponse, ErrorCode, ErrorResponse, FramedMessage, HealthStatus, ModelInfo,
-    PROTOCOL_VERSION, Request, RerankResponse, Response, StatusResponse, decode_message,
-    default_socket_path, encode_message,
+    DEFAULT_SEARCH_CHUNK, EmbedResponse, ErrorCode, ErrorResponse, FramedMessage, HealthStatus,
+    MIN_PROTOCOL_VERSION, ModelInfo, PROTOCOL_VERSION, Request, RerankResponse, Response,
+    SEARCH_PROTOCOL_VERSION, SearchChunk, SearchRequest, SearchSummary, StatusResponse, WireHit,
+    WireSuggestion, decode_message, default_socket_path, encode_message, negotiate_version,
 };
 use super::resource::ResourceMonitor;
+use crate::CliError;
+use crate::search::query::{
+    FieldMask, SearchClient, SearchClientOptions, SearchFilters, SearchMode, SearchResult,
+};
 
 /// Configuration for the daemon server.
 #[derive(Debug, Clone)]
@@ -103,6 +111,19 @@ impl DaemonConfig {
     }
 }
 
+/// A search index held open across requests.
+///
+/// Shared as an `Arc` so searches run outside the `searchers` map lock; the
+/// client guards its own state.
+struct ResidentSearch {
+    client: SearchClient,
+    /// Embedder the semantic context was loaded for ("" = best available).
+    ///
+    /// Semantic searches hold the read side while they run, so a request for a
+    /// different model waits instead of swapping the context underneath them.
+    semantic_model: RwLock<Option<String>>,
+}
+
 /// Daemon server state.
 pub struct ModelDaemon {
     config: DaemonConfig,
@@ -113,6 +134,8 @@ pub struct ModelDaemon {
     active_connections: AtomicU64,
     shutdown: AtomicBool,
     last_activity: RwLock<Instant>,
+    /// Open search clients keyed by (index path, db path).
+    searchers: Mutex<HashMap<(PathBuf, PathBuf), Arc<ResidentSearch>>>,
 }
 
 impl ModelDaemon {
@@ -127,6 +150,7 @@ impl ModelDaemon {
             active_connections: AtomicU64::new(0),
             shutdown: AtomicBool::new(false),
             last_activity: RwLock::new(Instant::now()),
+            searchers: Mutex::new(HashMap::new()),
         }
     }
 
@@ -279,16 +303,17 @@ impl ModelDaemon {
             stream.read_exact(&mut payload)?;
 
             // Decode and handle request
-            let response = match decode_message::<Request>(&payload) {
+            let responses = match decode_message::<Request>(&payload) {
                 Ok(msg) => {
                     self.total_requests.fetch_add(1, Ordering::Relaxed);
                     self.touch_activity();
-                    let response = self.handle_request(msg.request_id.clone(), msg.payload);
-                    FramedMessage::new(msg.request_id, response)
+                    self.respond(msg)
                 }
                 Err(e) => {
                     warn!(error = %e, "Failed to decode request");
-                    FramedMessage::new(
+                    // Oldest version so any peer can read the error.
+                    vec![FramedMessage::with_version(
+                        MIN_PROTOCOL_VERSION,
                         "error",
                         Response::Error(ErrorResponse {
                             code: ErrorCode::InvalidInput,
@@ -296,22 +321,197 @@ impl ModelDaemon {
                             retryable: false,
                             retry_after_ms: None,
                         }),
-                    )
+                    )]
                 }
             };
 
-            // Send response
-            let encoded =
-                encode_message(&response).map_err(|e| std::io::Error::other(e.to_string()))?;
-            stream.write_all(&encoded)?;
+            // Send response frames (search results stream as several)
+            let mut shutdown = false;
+            for response in &responses {
+                let encoded =
+                    encode_message(response).map_err(|e| std::io::Error::other(e.to_string()))?;
+                stream.write_all(&encoded)?;
+                shutdown |= matches!(response.payload, Response::Shutdown { .. });
+            }
 
             // Check if this was a shutdown request
-            if matches!(response.payload, Response::Shutdown { .. }) {
+            if shutdown {
                 return Ok(());
             }
         }
     }
 
+    /// Answer one request frame in the protocol version negotiated with its sender.
+    fn respond(&self, msg: FramedMessage<Request>) -> Vec<FramedMessage<Response>> {
+        let Some(version) = negotiate_version(msg.version) else {
+            warn!(peer_version = msg.version, "Unsupported protocol version");
+            return vec![FramedMessage::with_version(
+                MIN_PROTOCOL_VERSION,
+                msg.request_id,
+                Response::Error(ErrorResponse {
+                    code: ErrorCode::VersionMismatch,
+                    message: format!(
+                        "protocol v{} is not supported (supported: v{}..=v{})",
+                        msg.version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+                    ),
+                    retryable: false,
+                    retry_after_ms: None,
+                }),
+            )];
+        };
+
+        let responses = match msg.payload {
+            Request::Search(request) if version >= SEARCH_PROTOCOL_VERSION => {
+                self.search_frames(&msg.request_id, request)
+            }
+            request => vec![self.handle_request(msg.request_id.clone(), request)],
+        };
+        responses
+            .into_iter()
+            .map(|response| FramedMessage::with_version(version, msg.request_id.clone(), response))
+            .collect()
+    }
+
+    /// Run a search and split the hits into `SearchChunk` frames.
+    ///
+    /// This is chunked delivery, not incremental streaming: the search runs to
+    /// completion first, then its hits are cut into frames. The last frame
+    /// carries the summary; failures become a single `Error` frame so the client
+    /// can fall back to searching in-process.
+    fn search_frames(&self, request_id: &str, request: SearchRequest) -> Vec<Response> {
+        let start = Instant::now();
+        debug!(
+            request_id = %request_id,
+            mode = ?request.mode,
+            limit = request.limit,
+            index = %request.index_path.display(),
+            "Processing search request"
+        );
+
+        let chunk_size = match request.chunk_size {
+            0 => DEFAULT_SEARCH_CHUNK,
+            n => n,
+        };
+        let result = match self.run_search(request) {
+            Ok(result) => result,
+            Err(err) => return vec![Response::Error(err)],
+        };
+
+        let summary = SearchSummary {
+            total_hits: result.hits.len(),
+            wildcard_fallback: result.wildcard_fallback,
+            cache_stats: result.cache_stats,
+            suggestions: result
+                .suggestions
+                .iter()
+                .map(WireSuggestion::from)
+                .collect(),
+            ann_stats: result.ann_stats,
+            elapsed_ms: start.elapsed().as_millis() as u64,
+        };
+        let mut chunks: Vec<SearchChunk> = result
+            .hits
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(seq, hits)| SearchChunk {
+                seq: seq as u32,
+                hits: hits.iter().map(WireHit::from).collect(),
+                done: None,
+            })
+            .collect();
+        match chunks.last_mut() {
+            Some(last) => last.done = Some(summary),
+            None => chunks.push(SearchChunk {
+                seq: 0,
+                hits: Vec::new(),
+                done: Some(summary),
+            }),
+        }
+        chunks.into_iter().map(Response::SearchChunk).collect()
+    }
+
+    /// Search the resident client for the request's index, opening it on first use.
+    ///
+    /// The `searchers` lock is only held to look up or insert the client, so
+    /// concurrent searches don't queue behind each other.
+    fn run_search(&self, request: SearchRequest) -> Result<SearchResult, ErrorResponse> {
+        let resident = self.resident_search(&request)?;
+
+        let _model_guard = if matches!(request.mode, SearchMode::Semantic | SearchMode::Hybrid) {
+            let model = request.model.clone().unwrap_or_default();
+            loop {
+                let loaded = resident.semantic_model.read();
+                if loaded.as_deref() == Some(model.as_str()) {
+                    break Some(loaded);
+                }
+                drop(loaded);
+
+                let mut loaded = resident.semantic_model.write();
+                if loaded.as_deref() != Some(model.as_str()) {
+                    let options = crate::SemanticSearchOptions {
+                        model: request.model.clone(),
+                        ..Default::default()
+                    };
+                    crate::attach_semantic_context(
+                        &resident.client,
+                        &request.data_dir,
+                        &request.db_path,
+                        &options,
+                    )
+                    .map_err(search_error)?;
+                    *loaded = Some(model.clone());
+                }
+            }
+        } else {
+            None
+        };
+
+        crate::search_with_mode(
+            &resident.client,
+            request.mode,
+            &request.query,
+            &SearchFilters::from(request.filters),
+            request.limit,
+            request.offset,
+            request.sparse_threshold,
+            FieldMask::from_bits(request.field_mask),
+            request.approximate,
+        )
+        .map_err(search_error)
+    }
+
+    /// Fetch the resident client for the request's index, opening it if needed.
+    fn resident_search(
+        &self,
+        request: &SearchRequest,
+    ) -> Result<Arc<ResidentSearch>, ErrorResponse> {
+        let key = (request.index_path.clone(), request.db_path.clone());
+        if let Some(resident) = self.searchers.lock().get(&key) {
+            return Ok(Arc::clone(resident));
+        }
+
+        // Open without the map lock; if another request won the race, use its client.
+        // Reload keeps results current as `cass index` commits new segments.
+        let client = crate::open_cli_search_client(
+            &request.index_path,
+            &request.db_path,
+            SearchClientOptions::default(),
+        )
+        .map_err(search_error)?;
+        let mut searchers = self.searchers.lock();
+        let resident = match searchers.entry(key) {
+            Entry::Occupied(entry) => Arc::clone(entry.get()),
+            Entry::Vacant(entry) => {
+                info!(index = %request.index_path.display(), "Opened resident search index");
+                Arc::clone(entry.insert(Arc::new(ResidentSearch {
+                    client,
+                    semantic_model: RwLock::new(None),
+                })))
+            }
+        };
+        Ok(resident)
+    }
+
     /// Handle a single request.
     fn handle_request(&self, request_id: String, request: Request) -> Response {
         let start = Instant::now();
@@ -412,6 +612,14 @@ impl ModelDaemon {
                     message: "daemon shutting down".to_string(),
                 }
             }
+
+            // Only reached when the sender negotiated a pre-search version.
+            Request::Search(_) => Response::Error(ErrorResponse {
+                code: ErrorCode::VersionMismatch,
+                message: format!("search requires protocol v{SEARCH_PROTOCOL_VERSION}"),
+                retryable: false,
+                retry_after_ms: None,
+            }),
         }
     }
 
@@ -421,15 +629,80 @@ impl ModelDaemon {
     }
 }
 
+/// Map a CLI search failure to a protocol error (the client then searches locally).
+fn search_error(err: CliError) -> ErrorResponse {
+    ErrorResponse {
+        code: ErrorCode::Internal,
+        message: format!("{}: {}", err.kind, err.message),
+        retryable: err.retryable,
+        retry_after_ms: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::connectors::{NormalizedConversation, NormalizedMessage};
+    use crate::daemon::protocol::WireFilters;
+    use crate::search::tantivy::TantivyIndex;
     use std::path::PathBuf;
+    use tempfile::TempDir;
 
     fn test_data_dir() -> PathBuf {
         PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
     }
 
+    fn test_daemon() -> ModelDaemon {
+        ModelDaemon::new(DaemonConfig::default(), ModelManager::new(&test_data_dir()))
+    }
+
+    /// Index `count` single-message conversations mentioning "cargo".
+    fn build_index(dir: &Path, count: usize) {
+        let mut index = TantivyIndex::open_or_create(dir).unwrap();
+        for i in 0..count {
+            index
+                .add_conversation(&NormalizedConversation {
+                    agent_slug: "codex".into(),
+                    external_id: None,
+                    title: Some(format!("session {i}")),
+                    workspace: Some(PathBuf::from("/tmp/workspace")),
+                    source_path: dir.join(format!("rollout-{i}.jsonl")),
+                    started_at: Some(1_700_000_000_000 + i as i64),
+                    ended_at: None,
+                    metadata: serde_json::json!({}),
+                    messages: vec![NormalizedMessage {
+                        idx: 0,
+                        role: "user".into(),
+                        author: None,
+                        created_at: Some(1_700_000_000_000 + i as i64),
+                        content: format!("run cargo test number {i}"),
+                        extra: serde_json::json!({}),
+                        snippets: Vec::new(),
+                    }],
+                })
+                .unwrap();
+        }
+        index.commit().unwrap();
+    }
+
+    fn search_request(dir: &Path, chunk_size: usize) -> SearchRequest {
+        SearchRequest {
+            data_dir: dir.to_path_buf(),
+            index_path: dir.to_path_buf(),
+            db_path: dir.join("missing.db"),
+            query: "cargo".to_string(),
+            filters: WireFilters::default(),
+            mode: SearchMode::Lexical,
+            limit: 10,
+            offset: 0,
+            sparse_threshold: 3,
+            field_mask: FieldMask::FULL.bits(),
+            approximate: false,
+            model: None,
+            chunk_size,
+        }
+    }
+
     #[test]
     fn test_config_defaults() {
         let config = DaemonConfig::default();
@@ -486,4 +759,143 @@ mod tests {
         // With idle_timeout = 0, should never trigger idle shutdown
         assert!(!daemon.should_shutdown_idle());
     }
+
+    #[test]
+    fn test_v1_peer_gets_v1_response() {
+        let daemon = test_daemon();
+        let responses =
+            daemon.respond(FramedMessage::with_version(1, "v1-health", Request::Health));
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].version, 1);
+        assert_eq!(responses[0].request_id, "v1-health");
+        assert!(matches!(responses[0].payload, Response::Health(_)));
+
+        let responses = daemon.respond(FramedMessage::new("v2-health", Request::Health));
+        assert_eq!(responses[0].version, PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn test_unsupported_version_rejected() {
+        let daemon = test_daemon();
+        let responses = daemon.respond(FramedMessage::with_version(0, "v0", Request::Status));
+        match &responses[0].payload {
+            Response::Error(err) => assert_eq!(err.code, ErrorCode::VersionMismatch),
+            other => panic!("expected VersionMismatch error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_search_streams_chunks_with_summary_last() {
+        let dir = TempDir::new().unwrap();
+        build_index(dir.path(), 5);
+        let daemon = test_daemon();
+
+        let frames = daemon.search_frames("search-1", search_request(dir.path(), 2));
+        assert_eq!(frames.len(), 3);
+        let mut streamed = 0;
+        for (i, frame) in frames.iter().enumerate() {
+            let Response::SearchChunk(chunk) = frame else {
+                panic!("expected SearchChunk, got {frame:?}");
+            };
+            assert_eq!(chunk.seq as usize, i);
+            assert_eq!(chunk.done.is_some(), i == frames.len() - 1);
+            streamed += chunk.hits.len();
+        }
+        let Response::SearchChunk(last) = &frames[2] else {
+            unreachable!()
+        };
+        assert_eq!(last.done.as_ref().unwrap().total_hits, streamed);
+        assert_eq!(streamed, 5);
+
+        // The client stays resident for the next request.
+        assert_eq!(daemon.searchers.lock().len(), 1);
+        let frames = daemon.search_frames("search-2", search_request(dir.path(), 0));
+        assert_eq!(frames.len(), 1);
+        assert_eq!(daemon.searchers.lock().len(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_searches_share_resident_client() {
+        let dir = TempDir::new().unwrap();
+        build_index(dir.path(), 5);
+        let daemon = test_daemon();
+        let request = search_request(dir.path(), 0);
+        let resident = daemon.resident_search(&request).unwrap();
+
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..4)
+                .map(|i| {
+                    let daemon = &daemon;
+                    let path = dir.path();
+                    scope.spawn(move || {
+                        daemon.search_frames(&format!("par-{i}"), search_request(path, 2))
+                    })
+                })
+                .collect();
+            for worker in workers {
+                let frames = worker.join().unwrap();
+                assert_eq!(frames.len(), 3);
+            }
+        });
+
+        // The map lock is free again and every search reused the same client.
+        assert_eq!(daemon.searchers.lock().len(), 1);
+        assert!(Arc::ptr_eq(
+            &resident,
+            &daemon.resident_search(&request).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_search_missing_index_returns_error_frame() {
+        let dir = TempDir::new().unwrap();
+        let daemon = test_daemon();
+        let frames = daemon.search_frames("missing", search_request(dir.path(), 0));
+        assert!(matches!(frames.as_slice(), [Response::Error(_)]));
+        assert!(daemon.searchers.lock().is_empty());
+    }
+
+    #[test]
+    fn test_search_over_socket() {
+        use crate::daemon::client::{DaemonClientConfig, UdsDaemonClient};
+
+        let dir = TempDir::new().unwrap();
+        build_index(dir.path(), 3);
+        let socket_path = dir.path().join("daemon.sock");
+        let daemon = Arc::new(ModelDaemon::new(
+            DaemonConfig {
+                socket_path: socket_path.clone(),
+                ..DaemonConfig::default()
+            },
+            ModelManager::new(&test_data_dir()),
+        ));
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let server = {
+            let daemon = daemon.clone();
+            std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                daemon.handle_connection(stream).unwrap();
+            })
+        };
+
+        let client = UdsDaemonClient::new(DaemonClientConfig {
+            socket_path,
+            auto_spawn: false,
+            ..DaemonClientConfig::default()
+        });
+        client.connect().unwrap();
+        let mut batches = 0;
+        let summary = client
+            .search_stream(&search_request(dir.path(), 1), &mut |hits| {
+                assert_eq!(hits.len(), 1);
+                batches += 1;
+            })
+            .unwrap();
+        assert_eq!(client.peer_version(), Some(PROTOCOL_VERSION));
+        assert_eq!(summary.total_hits, 3);
+        assert_eq!(batches, 3);
+
+        client.shutdown().unwrap();
+        server.join().unwrap();
+    }
 }
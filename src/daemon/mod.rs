@@ -6,6 +6,7 @@
 //! - Shares the socket with xf (wire-compatible protocol)
 //! - First-come spawns, others connect
 //! - Supports graceful fallback to direct inference
+//! - Serves `cass search` from resident index readers (protocol v2+)
 //!
 //! ## Architecture
 //!
@@ -4,12 +4,44 @@
 //! allowing both tools to share a daemon if both are installed.
 //!
 //! Protocol uses MessagePack for efficient binary serialization over Unix Domain Sockets.
+//!
+//! ## Versioning
+//!
+//! Every frame carries the sender's protocol version. The daemon answers each
+//! request in `min(client, daemon)` (see [`negotiate_version`]), so a v1 peer
+//! (xf, or an older cass) keeps working against a newer daemon and vice versa.
+//! Version 2 added [`Request::Search`] and the chunked [`Response::SearchChunk`];
+//! clients only send `Search` once the daemon has answered in version 2 or later.
+//! The daemon finishes the search before sending any chunk, so chunking bounds
+//! frame size rather than delivering the first hits early.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
 
-/// Protocol version for compatibility checks.
-/// Both cass and xf must use the same version to share a daemon.
-pub const PROTOCOL_VERSION: u32 = 1;
+use crate::search::ann_index::AnnSearchStats;
+use crate::search::query::{
+    CacheStats, MatchType, QuerySuggestion, SearchFilters, SearchHit, SearchMode, SuggestionKind,
+};
+use crate::sources::provenance::SourceFilter;
+
+/// Protocol version spoken by this build.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest protocol version still accepted from a peer (embed/rerank only).
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// First protocol version that understands [`Request::Search`].
+pub const SEARCH_PROTOCOL_VERSION: u32 = 2;
+
+/// Hits per [`Response::SearchChunk`] frame when the request does not say.
+pub const DEFAULT_SEARCH_CHUNK: usize = 64;
+
+/// Pick the version to answer a peer in, or `None` if the peer is too old.
+pub fn negotiate_version(peer: u32) -> Option<u32> {
+    (peer >= MIN_PROTOCOL_VERSION).then(|| peer.min(PROTOCOL_VERSION))
+}
 
 /// Default socket path (shared between cass and xf).
 pub fn default_socket_path() -> std::path::PathBuf {
@@ -53,6 +85,9 @@ pub enum Request {
 
     /// Request graceful shutdown.
     Shutdown,
+
+    /// Run a search against a resident index (protocol v2+).
+    Search(SearchRequest),
 }
 
 /// Response types from the daemon.
@@ -75,6 +110,213 @@ pub enum Response {
 
     /// Error response.
     Error(ErrorResponse),
+
+    /// One frame of a streamed search result; the last frame carries `done`.
+    SearchChunk(SearchChunk),
+}
+
+/// Search request served from the daemon's warm `SearchClient`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchRequest {
+    /// Client data dir (vector index and models for semantic modes).
+    pub data_dir: PathBuf,
+    /// Tantivy index directory.
+    pub index_path: PathBuf,
+    /// SQLite database path.
+    pub db_path: PathBuf,
+    pub query: String,
+    pub filters: WireFilters,
+    pub mode: SearchMode,
+    pub limit: usize,
+    pub offset: usize,
+    /// Hit count below which lexical search retries with implicit wildcards.
+    pub sparse_threshold: usize,
+    /// `FieldMask` bits.
+    pub field_mask: u8,
+    /// Use the HNSW index for semantic/hybrid modes.
+    pub approximate: bool,
+    /// Embedder override (as `--model`).
+    pub model: Option<String>,
+    /// Hits per streamed frame (0 = [`DEFAULT_SEARCH_CHUNK`]).
+    pub chunk_size: usize,
+}
+
+/// A streamed slice of search hits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchChunk {
+    /// Frame sequence number, starting at 0.
+    pub seq: u32,
+    pub hits: Vec<WireHit>,
+    /// Present on the final frame only.
+    pub done: Option<SearchSummary>,
+}
+
+/// Result metadata sent with the final search frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchSummary {
+    /// Hits streamed across all frames.
+    pub total_hits: usize,
+    pub wildcard_fallback: bool,
+    pub cache_stats: CacheStats,
+    pub suggestions: Vec<WireSuggestion>,
+    pub ann_stats: Option<AnnSearchStats>,
+    /// Time spent searching inside the daemon.
+    pub elapsed_ms: u64,
+}
+
+/// Search filters in wire form (no skipped fields, so the MessagePack array
+/// layout is stable).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WireFilters {
+    pub agents: Vec<String>,
+    pub workspaces: Vec<String>,
+    pub created_from: Option<i64>,
+    pub created_to: Option<i64>,
+    /// `SourceFilter` display form ("all", "local", "remote" or a source id).
+    pub source_filter: String,
+    pub session_paths: Vec<String>,
+    pub roles: Vec<String>,
+    pub tools: Vec<String>,
+    pub tags: Vec<String>,
+    pub files: Vec<String>,
+}
+
+/// A search hit in wire form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireHit {
+    pub title: String,
+    pub snippet: String,
+    pub content: String,
+    pub content_hash: u64,
+    pub score: f32,
+    pub source_path: String,
+    pub agent: String,
+    pub workspace: String,
+    pub workspace_original: Option<String>,
+    pub created_at: Option<i64>,
+    pub line_number: Option<usize>,
+    pub match_type: MatchType,
+    pub source_id: String,
+    pub origin_kind: String,
+    pub origin_host: Option<String>,
+}
+
+/// A did-you-mean suggestion in wire form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WireSuggestion {
+    pub kind: SuggestionKind,
+    pub message: String,
+    pub suggested_query: Option<String>,
+    pub suggested_filters: Option<WireFilters>,
+    pub shortcut: Option<u8>,
+}
+
+fn sorted(set: &HashSet<String>) -> Vec<String> {
+    let mut values: Vec<String> = set.iter().cloned().collect();
+    values.sort();
+    values
+}
+
+impl From<&SearchFilters> for WireFilters {
+    fn from(filters: &SearchFilters) -> Self {
+        Self {
+            agents: sorted(&filters.agents),
+            workspaces: sorted(&filters.workspaces),
+            created_from: filters.created_from,
+            created_to: filters.created_to,
+            source_filter: filters.source_filter.to_string(),
+            session_paths: sorted(&filters.session_paths),
+            roles: sorted(&filters.roles),
+            tools: sorted(&filters.tools),
+            tags: sorted(&filters.tags),
+            files: sorted(&filters.files),
+        }
+    }
+}
+
+impl From<WireFilters> for SearchFilters {
+    fn from(wire: WireFilters) -> Self {
+        Self {
+            agents: wire.agents.into_iter().collect(),
+            workspaces: wire.workspaces.into_iter().collect(),
+            created_from: wire.created_from,
+            created_to: wire.created_to,
+            source_filter: SourceFilter::parse(&wire.source_filter),
+            session_paths: wire.session_paths.into_iter().collect(),
+            roles: wire.roles.into_iter().collect(),
+            tools: wire.tools.into_iter().collect(),
+            tags: wire.tags.into_iter().collect(),
+            files: wire.files.into_iter().collect(),
+        }
+    }
+}
+
+impl From<&SearchHit> for WireHit {
+    fn from(hit: &SearchHit) -> Self {
+        Self {
+            title: hit.title.clone(),
+            snippet: hit.snippet.clone(),
+            content: hit.content.clone(),
+            content_hash: hit.content_hash,
+            score: hit.score,
+            source_path: hit.source_path.clone(),
+            agent: hit.agent.clone(),
+            workspace: hit.workspace.clone(),
+            workspace_original: hit.workspace_original.clone(),
+            created_at: hit.created_at,
+            line_number: hit.line_number,
+            match_type: hit.match_type,
+            source_id: hit.source_id.clone(),
+            origin_kind: hit.origin_kind.clone(),
+            origin_host: hit.origin_host.clone(),
+        }
+    }
+}
+
+impl From<WireHit> for SearchHit {
+    fn from(wire: WireHit) -> Self {
+        Self {
+            title: wire.title,
+            snippet: wire.snippet,
+            content: wire.content,
+            content_hash: wire.content_hash,
+            score: wire.score,
+            source_path: wire.source_path,
+            agent: wire.agent,
+            workspace: wire.workspace,
+            workspace_original: wire.workspace_original,
+            created_at: wire.created_at,
+            line_number: wire.line_number,
+            match_type: wire.match_type,
+            source_id: wire.source_id,
+            origin_kind: wire.origin_kind,
+            origin_host: wire.origin_host,
+        }
+    }
+}
+
+impl From<&QuerySuggestion> for WireSuggestion {
+    fn from(suggestion: &QuerySuggestion) -> Self {
+        Self {
+            kind: suggestion.kind.clone(),
+            message: suggestion.message.clone(),
+            suggested_query: suggestion.suggested_query.clone(),
+            suggested_filters: suggestion.suggested_filters.as_ref().map(WireFilters::from),
+            shortcut: suggestion.shortcut,
+        }
+    }
+}
+
+impl From<WireSuggestion> for QuerySuggestion {
+    fn from(wire: WireSuggestion) -> Self {
+        Self {
+            kind: wire.kind,
+            message: wire.message,
+            suggested_query: wire.suggested_query,
+            suggested_filters: wire.suggested_filters.map(SearchFilters::from),
+            shortcut: wire.shortcut,
+        }
+    }
 }
 
 /// Health status of the daemon.
@@ -189,8 +431,13 @@ pub struct FramedMessage<T> {
 
 impl<T> FramedMessage<T> {
     pub fn new(request_id: impl Into<String>, payload: T) -> Self {
+        Self::with_version(PROTOCOL_VERSION, request_id, payload)
+    }
+
+    /// Frame a message in a negotiated (possibly older) protocol version.
+    pub fn with_version(version: u32, request_id: impl Into<String>, payload: T) -> Self {
         Self {
-            version: PROTOCOL_VERSION,
+            version,
             request_id: request_id.into(),
             payload,
         }
@@ -397,4 +644,121 @@ mod tests {
             panic!("expected Rerank response");
         }
     }
+
+    #[test]
+    fn test_negotiate_version() {
+        assert_eq!(negotiate_version(0), None);
+        assert_eq!(negotiate_version(1), Some(1));
+        assert_eq!(negotiate_version(PROTOCOL_VERSION), Some(PROTOCOL_VERSION));
+        assert_eq!(
+            negotiate_version(PROTOCOL_VERSION + 5),
+            Some(PROTOCOL_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_v1_request_still_decodes() {
+        let msg = FramedMessage::with_version(1, "req-v1", Request::Status);
+        let encoded = encode_message(&msg).unwrap();
+        let decoded: FramedMessage<Request> = decode_message(&encoded[4..]).unwrap();
+        assert_eq!(decoded.version, 1);
+        assert!(matches!(decoded.payload, Request::Status));
+    }
+
+    #[test]
+    fn test_encode_decode_search_request() {
+        let mut filters = SearchFilters::default();
+        filters.agents.insert("codex".to_string());
+        filters.agents.insert("claude_code".to_string());
+        filters.source_filter = SourceFilter::Remote;
+        filters.created_from = Some(1_700_000_000_000);
+
+        let msg = FramedMessage::new(
+            "req-search",
+            Request::Search(SearchRequest {
+                data_dir: PathBuf::from("/data"),
+                index_path: PathBuf::from("/data/index"),
+                db_path: PathBuf::from("/data/agent_search.db"),
+                query: "cargo build".to_string(),
+                filters: WireFilters::from(&filters),
+                mode: SearchMode::Hybrid,
+                limit: 20,
+                offset: 5,
+                sparse_threshold: 3,
+                field_mask: 0b0101,
+                approximate: true,
+                model: Some("hash".to_string()),
+                chunk_size: 0,
+            }),
+        );
+        let encoded = encode_message(&msg).unwrap();
+        let decoded: FramedMessage<Request> = decode_message(&encoded[4..]).unwrap();
+
+        let Request::Search(req) = decoded.payload else {
+            panic!("expected Search request");
+        };
+        assert_eq!(req.query, "cargo build");
+        assert_eq!(req.mode, SearchMode::Hybrid);
+        assert_eq!((req.limit, req.offset), (20, 5));
+        assert_eq!(req.model.as_deref(), Some("hash"));
+        assert_eq!(req.filters.agents, vec!["claude_code", "codex"]);
+
+        let back = SearchFilters::from(req.filters);
+        assert_eq!(back.agents, filters.agents);
+        assert_eq!(back.source_filter, SourceFilter::Remote);
+        assert_eq!(back.created_from, filters.created_from);
+    }
+
+    #[test]
+    fn test_encode_decode_search_chunk() {
+        let hit = WireHit {
+            title: "Fix build".to_string(),
+            snippet: "cargo **build**".to_string(),
+            content: "run cargo build".to_string(),
+            content_hash: 42,
+            score: 1.5,
+            source_path: "/tmp/session.jsonl".to_string(),
+            agent: "codex".to_string(),
+            workspace: "/repo".to_string(),
+            workspace_original: None,
+            created_at: Some(1_700_000_000_000),
+            line_number: Some(3),
+            match_type: MatchType::Prefix,
+            source_id: "local".to_string(),
+            origin_kind: "local".to_string(),
+            origin_host: None,
+        };
+        let msg = FramedMessage::new(
+            "resp-search",
+            Response::SearchChunk(SearchChunk {
+                seq: 1,
+                hits: vec![hit],
+                done: Some(SearchSummary {
+                    total_hits: 65,
+                    wildcard_fallback: true,
+                    cache_stats: CacheStats {
+                        reload_ms_total: 12,
+                        ..CacheStats::default()
+                    },
+                    suggestions: Vec::new(),
+                    ann_stats: None,
+                    elapsed_ms: 4,
+                }),
+            }),
+        );
+        let encoded = encode_message(&msg).unwrap();
+        let decoded: FramedMessage<Response> = decode_message(&encoded[4..]).unwrap();
+
+        let Response::SearchChunk(chunk) = decoded.payload else {
+            panic!("expected SearchChunk response");
+        };
+        assert_eq!(chunk.seq, 1);
+        let hit = SearchHit::from(chunk.hits[0].clone());
+        assert_eq!(hit.content_hash, 42);
+        assert_eq!(hit.match_type, MatchType::Prefix);
+        let summary = chunk.done.expect("final chunk carries summary");
+        assert_eq!(summary.total_hits, 65);
+        assert!(summary.wildcard_fallback);
+        assert_eq!(summary.cache_stats.reload_ms_total, 12);
+    }
 }
@@ -0,0 +1,712 @@
+//! Session comparison for `cass diff`.
+//!
+//! Aligns the message sequences of two indexed sessions (for example two agents
+//! given the same task, or a retried session) and summarizes how they differ:
+//! where the conversations diverged, which files each touched (from stored
+//! snippets), token and duration differences, and the tool calls unique to each.
+//!
+//! Alignment is a longest-common-subsequence over `(role, normalized content)`.
+//! Unmatched messages between two matches are paired up as "changed" when their
+//! roles agree, so a rephrased prompt reads as one edit rather than a removal
+//! plus an insertion.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Result;
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+
+use crate::connectors::extract_tool_names;
+use crate::export::{escape_markdown, truncate_text};
+use crate::model::types::{Conversation, Message};
+use crate::storage::sqlite::SqliteStorage;
+
+/// Upper bound on LCS table cells; larger gaps fall back to positional pairing.
+const MAX_ALIGN_CELLS: usize = 4_000_000;
+
+/// Characters kept in message previews.
+const PREVIEW_CHARS: usize = 160;
+
+/// One side of a comparison, as loaded from the database.
+#[derive(Debug, Clone)]
+pub struct DiffSession {
+    pub conversation: Conversation,
+    pub messages: Vec<Message>,
+}
+
+/// Load the session indexed from `source_path`, with snippets attached.
+///
+/// When several sources indexed the same path, the first conversation wins.
+pub fn load_session(storage: &SqliteStorage, source_path: &str) -> Result<Option<DiffSession>> {
+    let Some(conversation) = storage
+        .list_conversations_for_path(source_path)?
+        .into_iter()
+        .next()
+    else {
+        return Ok(None);
+    };
+    let messages = match conversation.id {
+        Some(id) => storage.fetch_messages_with_snippets(id)?,
+        None => Vec::new(),
+    };
+    Ok(Some(DiffSession {
+        conversation,
+        messages,
+    }))
+}
+
+/// Per-session totals shown side by side.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSummary {
+    pub source_path: String,
+    pub agent: String,
+    pub title: Option<String>,
+    pub workspace: Option<String>,
+    pub started_at: Option<i64>,
+    pub ended_at: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub messages: usize,
+    pub tokens: u64,
+    /// True when no usage data was recorded and `tokens` is a 4-chars-per-token estimate
+    pub tokens_estimated: bool,
+    pub files_touched: Vec<String>,
+    pub tool_calls: BTreeMap<String, usize>,
+}
+
+/// How a row of the alignment relates the two sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlignKind {
+    Same,
+    Changed,
+    OnlyA,
+    OnlyB,
+}
+
+/// A message referenced from an alignment row.
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageRef {
+    pub idx: i64,
+    pub role: String,
+    pub preview: String,
+}
+
+/// One row of the aligned message sequences.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlignedPair {
+    pub kind: AlignKind,
+    pub a: Option<MessageRef>,
+    pub b: Option<MessageRef>,
+}
+
+/// First point where the sessions stop matching.
+#[derive(Debug, Clone, Serialize)]
+pub struct Divergence {
+    /// Alignment row where the difference starts
+    pub row: usize,
+    pub a_idx: Option<i64>,
+    pub b_idx: Option<i64>,
+}
+
+/// Items present in both sessions or only one of them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SetComparison {
+    pub common: Vec<String>,
+    pub only_a: Vec<String>,
+    pub only_b: Vec<String>,
+}
+
+impl SetComparison {
+    fn of<'a>(
+        a: impl IntoIterator<Item = &'a String>,
+        b: impl IntoIterator<Item = &'a String>,
+    ) -> Self {
+        let a: BTreeSet<&String> = a.into_iter().collect();
+        let b: BTreeSet<&String> = b.into_iter().collect();
+        Self {
+            common: a.intersection(&b).map(|s| s.to_string()).collect(),
+            only_a: a.difference(&b).map(|s| s.to_string()).collect(),
+            only_b: b.difference(&a).map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// Differences `b - a`.
+#[derive(Debug, Clone, Serialize)]
+pub struct Delta {
+    pub messages: i64,
+    pub tokens: i64,
+    pub duration_ms: Option<i64>,
+}
+
+/// Full comparison of two sessions.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiff {
+    pub a: SessionSummary,
+    pub b: SessionSummary,
+    /// Number of leading messages the sessions share
+    pub common_prefix: usize,
+    pub divergence: Option<Divergence>,
+    pub identical: bool,
+    pub delta: Delta,
+    pub files: SetComparison,
+    pub tools: SetComparison,
+    pub alignment: Vec<AlignedPair>,
+}
+
+impl SessionDiff {
+    /// Compare two loaded sessions.
+    pub fn compute(a: &DiffSession, b: &DiffSession) -> Self {
+        let sa = summarize(a);
+        let sb = summarize(b);
+        let alignment = align(&a.messages, &b.messages);
+
+        let common_prefix = alignment
+            .iter()
+            .take_while(|p| p.kind == AlignKind::Same)
+            .count();
+        let divergence = alignment
+            .iter()
+            .position(|p| p.kind != AlignKind::Same)
+            .map(|row| Divergence {
+                row,
+                a_idx: alignment[row].a.as_ref().map(|m| m.idx),
+                b_idx: alignment[row].b.as_ref().map(|m| m.idx),
+            });
+
+        let delta = Delta {
+            messages: sb.messages as i64 - sa.messages as i64,
+            tokens: sb.tokens as i64 - sa.tokens as i64,
+            duration_ms: sa.duration_ms.zip(sb.duration_ms).map(|(x, y)| y - x),
+        };
+        let files = SetComparison::of(&sa.files_touched, &sb.files_touched);
+        let tools = SetComparison::of(sa.tool_calls.keys(), sb.tool_calls.keys());
+
+        Self {
+            identical: divergence.is_none(),
+            a: sa,
+            b: sb,
+            common_prefix,
+            divergence,
+            delta,
+            files,
+            tools,
+            alignment,
+        }
+    }
+}
+
+fn role_name(msg: &Message) -> String {
+    msg.role.to_string().to_lowercase()
+}
+
+fn summarize(session: &DiffSession) -> SessionSummary {
+    let conv = &session.conversation;
+    let msgs = &session.messages;
+
+    let started_at = conv
+        .started_at
+        .or_else(|| msgs.iter().filter_map(|m| m.created_at).min());
+    let ended_at = conv
+        .ended_at
+        .or_else(|| msgs.iter().filter_map(|m| m.created_at).max());
+
+    let mut files = BTreeSet::new();
+    let mut tool_calls: BTreeMap<String, usize> = BTreeMap::new();
+    let mut usage_tokens = 0u64;
+    let mut has_usage = false;
+    let mut chars = 0usize;
+    for msg in msgs {
+        for snippet in &msg.snippets {
+            if let Some(path) = &snippet.file_path {
+                files.insert(path.to_string_lossy().to_string());
+            }
+        }
+        for name in extract_tool_names(&msg.content, &msg.extra_json) {
+            *tool_calls.entry(name).or_default() += 1;
+        }
+        if let Some(tokens) = usage_tokens_in(&msg.extra_json, 0) {
+            usage_tokens += tokens;
+            has_usage = true;
+        }
+        chars += msg.content.chars().count();
+    }
+
+    let (tokens, tokens_estimated) = match conv.approx_tokens {
+        Some(t) if t > 0 => (t as u64, false),
+        _ if has_usage => (usage_tokens, false),
+        _ => ((chars / 4) as u64, true),
+    };
+
+    SessionSummary {
+        source_path: conv.source_path.to_string_lossy().to_string(),
+        agent: conv.agent_slug.clone(),
+        title: conv.title.clone(),
+        workspace: conv
+            .workspace
+            .as_ref()
+            .map(|w| w.to_string_lossy().to_string()),
+        started_at,
+        ended_at,
+        duration_ms: started_at.zip(ended_at).map(|(s, e)| e - s),
+        messages: msgs.len(),
+        tokens,
+        tokens_estimated,
+        files_touched: files.into_iter().collect(),
+        tool_calls,
+    }
+}
+
+/// Sum the first provider `usage` block found in a message's raw JSON.
+///
+/// Understands Anthropic (`input_tokens`/`output_tokens`) and OpenAI
+/// (`prompt_tokens`/`completion_tokens`) shapes.
+fn usage_tokens_in(val: &serde_json::Value, depth: usize) -> Option<u64> {
+    const KEYS: [&str; 4] = [
+        "input_tokens",
+        "output_tokens",
+        "prompt_tokens",
+        "completion_tokens",
+    ];
+    if depth > 4 {
+        return None;
+    }
+    let obj = val.as_object()?;
+    if let Some(usage) = obj.get("usage").and_then(|u| u.as_object()) {
+        let total: u64 = KEYS
+            .iter()
+            .filter_map(|k| usage.get(*k).and_then(|v| v.as_u64()))
+            .sum();
+        if KEYS.iter().any(|k| usage.contains_key(*k)) {
+            return Some(total);
+        }
+    }
+    obj.values().find_map(|v| usage_tokens_in(v, depth + 1))
+}
+
+/// Comparison key: role plus whitespace-normalized content.
+fn align_key(msg: &Message) -> (String, String) {
+    (
+        role_name(msg),
+        msg.content.split_whitespace().collect::<Vec<_>>().join(" "),
+    )
+}
+
+fn message_ref(msg: &Message) -> MessageRef {
+    let first_line = msg
+        .content
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim();
+    MessageRef {
+        idx: msg.idx,
+        role: role_name(msg),
+        preview: truncate_text(first_line, PREVIEW_CHARS),
+    }
+}
+
+/// Align two message sequences.
+fn align(a: &[Message], b: &[Message]) -> Vec<AlignedPair> {
+    let ka: Vec<_> = a.iter().map(align_key).collect();
+    let kb: Vec<_> = b.iter().map(align_key).collect();
+
+    // Common prefix and suffix are matched directly; only the middle needs LCS
+    let prefix = ka.iter().zip(&kb).take_while(|(x, y)| x == y).count();
+    let suffix = ka[prefix..]
+        .iter()
+        .rev()
+        .zip(kb[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let same = |i: usize, j: usize| AlignedPair {
+        kind: AlignKind::Same,
+        a: Some(message_ref(&a[i])),
+        b: Some(message_ref(&b[j])),
+    };
+
+    let mut rows: Vec<AlignedPair> = (0..prefix).map(|i| same(i, i)).collect();
+
+    let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+    let matches = lcs_matches(&ka[prefix..a_end], &kb[prefix..b_end]);
+    let (mut i, mut j) = (prefix, prefix);
+    for (mi, mj) in matches
+        .into_iter()
+        .map(|(x, y)| (x + prefix, y + prefix))
+        .chain(std::iter::once((a_end, b_end)))
+    {
+        push_gap(&mut rows, &a[i..mi], &b[j..mj]);
+        if mi < a_end {
+            rows.push(same(mi, mj));
+        }
+        i = mi + 1;
+        j = mj + 1;
+    }
+
+    rows.extend((0..suffix).map(|k| same(a_end + k, b_end + k)));
+    rows
+}
+
+/// Index pairs of a longest common subsequence, or none when the table would be too large.
+fn lcs_matches<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len(), b.len());
+    if n == 0 || m == 0 || (n + 1).saturating_mul(m + 1) > MAX_ALIGN_CELLS {
+        return Vec::new();
+    }
+    let width = m + 1;
+    let mut table = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if a[i] == b[j] {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                table[(i + 1) * width + j].max(table[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if a[i] == b[j] {
+            out.push((i, j));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    out
+}
+
+/// Emit rows for unmatched runs, pairing same-role messages as changes.
+fn push_gap(rows: &mut Vec<AlignedPair>, a: &[Message], b: &[Message]) {
+    for k in 0..a.len().max(b.len()) {
+        match (a.get(k), b.get(k)) {
+            (Some(x), Some(y)) if x.role == y.role => rows.push(AlignedPair {
+                kind: AlignKind::Changed,
+                a: Some(message_ref(x)),
+                b: Some(message_ref(y)),
+            }),
+            (x, y) => {
+                if let Some(x) = x {
+                    rows.push(AlignedPair {
+                        kind: AlignKind::OnlyA,
+                        a: Some(message_ref(x)),
+                        b: None,
+                    });
+                }
+                if let Some(y) = y {
+                    rows.push(AlignedPair {
+                        kind: AlignKind::OnlyB,
+                        a: None,
+                        b: Some(message_ref(y)),
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn format_ts(ts: Option<i64>) -> String {
+    ts.and_then(|t| Utc.timestamp_millis_opt(t).single())
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Human-readable duration such as `1h 02m` or `45s`.
+pub fn format_duration(ms: i64) -> String {
+    let sign = if ms < 0 { "-" } else { "" };
+    let secs = ms.unsigned_abs() / 1000;
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{sign}{h}h {m:02}m")
+    } else if m > 0 {
+        format!("{sign}{m}m {s:02}s")
+    } else {
+        format!("{sign}{s}s")
+    }
+}
+
+fn signed(n: i64) -> String {
+    if n > 0 {
+        format!("+{n}")
+    } else {
+        n.to_string()
+    }
+}
+
+fn tokens_label(s: &SessionSummary) -> String {
+    if s.tokens_estimated {
+        format!("~{}", s.tokens)
+    } else {
+        s.tokens.to_string()
+    }
+}
+
+/// Render the comparison as Markdown.
+pub fn render_markdown(diff: &SessionDiff) -> String {
+    let (a, b) = (&diff.a, &diff.b);
+    let mut out = String::from("# Session Diff\n\n");
+
+    let cell = escape_markdown;
+    out.push_str("| | A | B | Δ |\n|---|---|---|---|\n");
+    out.push_str(&format!(
+        "| Session | {} | {} | |\n",
+        cell(&a.source_path),
+        cell(&b.source_path)
+    ));
+    out.push_str(&format!(
+        "| Agent | {} | {} | |\n",
+        cell(&a.agent),
+        cell(&b.agent)
+    ));
+    out.push_str(&format!(
+        "| Title | {} | {} | |\n",
+        cell(a.title.as_deref().unwrap_or("-")),
+        cell(b.title.as_deref().unwrap_or("-"))
+    ));
+    out.push_str(&format!(
+        "| Started | {} | {} | |\n",
+        format_ts(a.started_at),
+        format_ts(b.started_at)
+    ));
+    out.push_str(&format!(
+        "| Duration | {} | {} | {} |\n",
+        a.duration_ms
+            .map_or_else(|| "-".to_string(), format_duration),
+        b.duration_ms
+            .map_or_else(|| "-".to_string(), format_duration),
+        diff.delta
+            .duration_ms
+            .map(|d| if d > 0 {
+                format!("+{}", format_duration(d))
+            } else {
+                format_duration(d)
+            })
+            .unwrap_or_default()
+    ));
+    out.push_str(&format!(
+        "| Messages | {} | {} | {} |\n",
+        a.messages,
+        b.messages,
+        signed(diff.delta.messages)
+    ));
+    out.push_str(&format!(
+        "| Tokens | {} | {} | {} |\n\n",
+        tokens_label(a),
+        tokens_label(b),
+        signed(diff.delta.tokens)
+    ));
+    if a.tokens_estimated || b.tokens_estimated {
+        out.push_str("_~ marks token counts estimated from message length._\n\n");
+    }
+
+    out.push_str("## Divergence\n\n");
+    match &diff.divergence {
+        None => out.push_str("The message sequences are identical.\n\n"),
+        Some(d) => {
+            out.push_str(&format!(
+                "The sessions share their first {} message{}; they diverge at row {}",
+                diff.common_prefix,
+                if diff.common_prefix == 1 { "" } else { "s" },
+                d.row + 1
+            ));
+            let at = |idx: Option<i64>| idx.map_or("-".to_string(), |i| format!("#{i}"));
+            out.push_str(&format!(" (A {}, B {}).\n\n", at(d.a_idx), at(d.b_idx)));
+        }
+    }
+
+    let list_section = |out: &mut String, heading: &str, cmp: &SetComparison, code: bool| {
+        out.push_str(&format!("## {heading}\n\n"));
+        if cmp.common.is_empty() && cmp.only_a.is_empty() && cmp.only_b.is_empty() {
+            out.push_str("None recorded.\n\n");
+            return;
+        }
+        for (label, items) in [
+            ("Only in A", &cmp.only_a),
+            ("Only in B", &cmp.only_b),
+            ("In both", &cmp.common),
+        ] {
+            if items.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("**{label}:**\n"));
+            for item in items {
+                if code {
+                    out.push_str(&format!("- `{}`\n", item.replace('`', "")));
+                } else {
+                    out.push_str(&format!("- {}\n", escape_markdown(item)));
+                }
+            }
+            out.push('\n');
+        }
+    };
+    list_section(&mut out, "Files Touched", &diff.files, true);
+    list_section(&mut out, "Tool Calls", &diff.tools, false);
+
+    out.push_str("## Messages\n\n| # | A | B |\n|---|---|---|\n");
+    for (row, pair) in diff.alignment.iter().enumerate() {
+        let marker = match pair.kind {
+            AlignKind::Same => " ",
+            AlignKind::Changed => "~",
+            AlignKind::OnlyA => "-",
+            AlignKind::OnlyB => "+",
+        };
+        let side = |m: &Option<MessageRef>| {
+            m.as_ref().map_or(String::new(), |m| {
+                format!("**{}** {}", m.role, escape_markdown(&m.preview))
+            })
+        };
+        out.push_str(&format!(
+            "| {marker}{} | {} | {} |\n",
+            row + 1,
+            side(&pair.a),
+            side(&pair.b)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::types::{MessageRole, Snippet};
+    use std::path::PathBuf;
+
+    fn msg(idx: i64, role: MessageRole, content: &str) -> Message {
+        Message {
+            id: None,
+            idx,
+            role,
+            author: None,
+            created_at: Some(1_700_000_000_000 + idx * 1000),
+            content: content.to_string(),
+            extra_json: serde_json::Value::Null,
+            snippets: Vec::new(),
+        }
+    }
+
+    fn session(path: &str, messages: Vec<Message>) -> DiffSession {
+        DiffSession {
+            conversation: Conversation {
+                id: Some(1),
+                agent_slug: "codex".into(),
+                workspace: None,
+                external_id: None,
+                title: Some("t".into()),
+                source_path: PathBuf::from(path),
+                started_at: None,
+                ended_at: None,
+                approx_tokens: None,
+                metadata_json: serde_json::Value::Null,
+                messages: Vec::new(),
+                source_id: "local".into(),
+                origin_host: None,
+            },
+            messages,
+        }
+    }
+
+    #[test]
+    fn identical_sessions_have_no_divergence() {
+        let msgs = vec![
+            msg(0, MessageRole::User, "fix the build"),
+            msg(1, MessageRole::Agent, "done"),
+        ];
+        let diff = SessionDiff::compute(&session("/a", msgs.clone()), &session("/b", msgs));
+        assert!(diff.identical);
+        assert_eq!(diff.common_prefix, 2);
+        assert!(diff.divergence.is_none());
+        assert_eq!(diff.delta.messages, 0);
+    }
+
+    #[test]
+    fn alignment_pairs_changes_and_keeps_later_matches() {
+        let a = vec![
+            msg(0, MessageRole::User, "fix the build"),
+            msg(1, MessageRole::Agent, "I will edit Cargo.toml"),
+            msg(2, MessageRole::Tool, "ok"),
+            msg(3, MessageRole::Agent, "All green"),
+        ];
+        let b = vec![
+            msg(0, MessageRole::User, "fix   the build"),
+            msg(1, MessageRole::Agent, "I will bump the linker"),
+            msg(2, MessageRole::Agent, "All green"),
+            msg(3, MessageRole::User, "thanks"),
+        ];
+        let diff = SessionDiff::compute(&session("/a", a), &session("/b", b));
+        let kinds: Vec<_> = diff.alignment.iter().map(|p| p.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AlignKind::Same,
+                AlignKind::Changed,
+                AlignKind::OnlyA,
+                AlignKind::Same,
+                AlignKind::OnlyB
+            ]
+        );
+        assert_eq!(diff.common_prefix, 1);
+        let d = diff.divergence.unwrap();
+        assert_eq!((d.row, d.a_idx, d.b_idx), (1, Some(1), Some(1)));
+    }
+
+    #[test]
+    fn summaries_collect_files_tools_and_usage() {
+        let mut a1 = msg(0, MessageRole::Agent, "[Tool: Bash - cargo test]");
+        a1.snippets.push(Snippet {
+            id: None,
+            file_path: Some(PathBuf::from("src/lib.rs")),
+            start_line: None,
+            end_line: None,
+            language: None,
+            snippet_text: None,
+        });
+        a1.extra_json =
+            serde_json::json!({"message": {"usage": {"input_tokens": 100, "output_tokens": 20}}});
+        let mut b1 = msg(0, MessageRole::Agent, "[Tool: Edit]");
+        b1.snippets.push(Snippet {
+            id: None,
+            file_path: Some(PathBuf::from("src/main.rs")),
+            start_line: None,
+            end_line: None,
+            language: None,
+            snippet_text: None,
+        });
+
+        let diff = SessionDiff::compute(&session("/a", vec![a1]), &session("/b", vec![b1]));
+        assert_eq!(diff.a.tokens, 120);
+        assert!(!diff.a.tokens_estimated);
+        assert!(diff.b.tokens_estimated);
+        assert_eq!(diff.files.only_a, vec!["src/lib.rs"]);
+        assert_eq!(diff.files.only_b, vec!["src/main.rs"]);
+        assert_eq!(diff.tools.only_a, vec!["Bash"]);
+        assert_eq!(diff.tools.only_b, vec!["Edit"]);
+        assert_eq!(diff.a.duration_ms, Some(0));
+    }
+
+    #[test]
+    fn markdown_lists_divergence_and_rows() {
+        let a = vec![msg(0, MessageRole::User, "add tests")];
+        let b = vec![
+            msg(0, MessageRole::User, "add tests"),
+            msg(1, MessageRole::Agent, "added *three*"),
+        ];
+        let md = render_markdown(&SessionDiff::compute(&session("/a", a), &session("/b", b)));
+        assert!(md.contains("# Session Diff"));
+        assert!(md.contains("share their first 1 message;"));
+        assert!(md.contains("| +2 |  | **agent** added \\*three\\* |"));
+    }
+
+    #[test]
+    fn format_duration_units() {
+        assert_eq!(format_duration(45_000), "45s");
+        assert_eq!(format_duration(125_000), "2m 05s");
+        assert_eq!(format_duration(3_720_000), "1h 02m");
+        assert_eq!(format_duration(-5_000), "-5s");
+    }
+}
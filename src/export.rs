@@ -91,7 +91,7 @@ pub fn export_results(hits: &[SearchHit], format: ExportFormat, options: &Export
 }
 
 /// Escape special Markdown characters to prevent formatting issues or injection.
-fn escape_markdown(text: &str) -> String {
+pub(crate) fn escape_markdown(text: &str) -> String {
     text.replace('\\', "\\\\")
         .replace('|', "\\|")
         .replace('*', "\\*")
@@ -332,7 +332,7 @@ fn export_plain_text(hits: &[SearchHit], options: &ExportOptions) -> String {
 /// Truncate text to max length (in characters), adding ellipsis if needed.
 ///
 /// When max_len <= 3, truncates without ellipsis to avoid exceeding max_len.
-fn truncate_text(text: &str, max_len: usize) -> String {
+pub(crate) fn truncate_text(text: &str, max_len: usize) -> String {
     if max_len == 0 {
         return text.to_string();
     }
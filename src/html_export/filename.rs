@@ -403,6 +403,8 @@ pub fn agent_slug(agent: &str) -> String {
         "factory" | "droid" => "factory".to_string(),
         "opencode" => "opencode".to_string(),
         "cline" => "cline".to_string(),
+        "roocode" | "roo" => "roocode".to_string(),
+        "continue" | "continuedev" => "continue".to_string(),
         "amp" => "amp".to_string(),
         "copilot" | "githubcopilot" => "copilot".to_string(),
         "cody" | "sourcegraph" => "cody".to_string(),
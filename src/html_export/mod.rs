@@ -16,6 +16,7 @@
 //! ├── styles.rs        # CSS (critical inline + Tailwind CDN fallback)
 //! ├── scripts.rs       # JS (decryption, search, theme toggle)
 //! ├── renderer.rs      # Conversation -> HTML rendering
+//! ├── site.rs          # Multi-session folder export (index + session pages)
 //! ├── filename.rs      # Smart filename generation
 //! └── encryption.rs    # Web Crypto compatible encryption
 //! ```
@@ -34,6 +35,7 @@ mod encryption;
 mod filename;
 mod renderer;
 mod scripts;
+mod site;
 mod styles;
 mod template;
 
@@ -50,6 +52,7 @@ pub use renderer::{
     render_message, render_message_groups,
 };
 pub use scripts::{ScriptBundle, generate_scripts};
+pub use site::{SESSIONS_DIR, SiteFile, SiteSession, render_site};
 pub use styles::{StyleBundle, generate_styles};
 pub use template::{ExportOptions, HtmlExporter, HtmlTemplate, TemplateError, TemplateMetadata};
 
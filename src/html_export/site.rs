@@ -0,0 +1,752 @@
+//! Multi-session static site export.
+//!
+//! Renders a set of sessions into a self-contained folder:
+//!
+//! ```text
+//! index.html              # Sortable session table with client-side search
+//! sessions/<name>.html    # One page per session, cross-linked by workspace
+//! ```
+//!
+//! Every page is built from the single-session template, so styles, scripts
+//! and encryption behave exactly like `cass export-html`. When encryption is
+//! enabled all pages (including the index) are encrypted with one password,
+//! and the password entered on the first page is reused for the rest of the
+//! browser session.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::Instant;
+
+use chrono::{TimeZone, Utc};
+use tracing::info;
+
+use super::filename;
+use super::renderer::{self, MessageGroup};
+use super::template::{ExportOptions, HtmlExporter, TemplateError, TemplateMetadata, html_escape};
+
+/// Directory (relative to the site root) holding the per-session pages.
+pub const SESSIONS_DIR: &str = "sessions";
+
+/// Per-session text embedded in the index search data is capped at this many chars.
+const SEARCH_TEXT_LIMIT: usize = 100_000;
+
+/// A session to include in a site export.
+#[derive(Debug, Clone)]
+pub struct SiteSession {
+    /// Display title
+    pub title: String,
+    /// Agent slug (claude_code, codex, ...)
+    pub agent: String,
+    /// Workspace path; sessions sharing one are cross-linked
+    pub workspace: Option<String>,
+    /// Original session file
+    pub source_path: String,
+    /// Session start (ms since epoch)
+    pub started_at: Option<i64>,
+    /// Session end (ms since epoch)
+    pub ended_at: Option<i64>,
+    /// User tags
+    pub tags: Vec<String>,
+    /// Number of exported messages
+    pub message_count: usize,
+    /// Message groups as produced by `group_messages_for_export()`
+    pub groups: Vec<MessageGroup>,
+    /// Plain text matched by the index search (usually the message content)
+    pub search_text: String,
+}
+
+/// One rendered page of a site export.
+#[derive(Debug, Clone)]
+pub struct SiteFile {
+    /// Path relative to the site root, using `/` separators
+    pub path: String,
+    /// Session title, or the site title for the index
+    pub title: String,
+    /// Complete HTML document
+    pub html: String,
+}
+
+/// Render the index page and one page per session.
+///
+/// The index is always the first file. `password` is required when
+/// `options.encrypt` is set.
+pub fn render_site(
+    sessions: &[SiteSession],
+    site_title: &str,
+    options: &ExportOptions,
+    password: Option<&str>,
+) -> Result<Vec<SiteFile>, TemplateError> {
+    if sessions.is_empty() {
+        return Err(TemplateError::InvalidInput(
+            "no sessions to export".to_string(),
+        ));
+    }
+    if options.encrypt && password.is_none() {
+        return Err(TemplateError::EncryptionRequired);
+    }
+
+    let started = Instant::now();
+    let pages = page_names(sessions, options.encrypt);
+    let siblings = workspace_siblings(sessions);
+
+    let mut files = Vec::with_capacity(sessions.len() + 1);
+    files.push(SiteFile {
+        path: "index.html".to_string(),
+        title: site_title.to_string(),
+        html: render_index(sessions, &pages, site_title, options, password)?,
+    });
+    for (index, session) in sessions.iter().enumerate() {
+        let html =
+            render_session_page(index, sessions, &pages, &siblings[index], options, password)?;
+        files.push(SiteFile {
+            path: format!("{SESSIONS_DIR}/{}", pages[index]),
+            title: session.title.clone(),
+            html,
+        });
+    }
+
+    info!(
+        component = "site",
+        operation = "render_site_complete",
+        sessions = sessions.len(),
+        encrypt = options.encrypt,
+        duration_ms = started.elapsed().as_millis(),
+        "Rendered multi-session site"
+    );
+    Ok(files)
+}
+
+/// File names for the session pages, unique within the site.
+///
+/// Encrypted sites use neutral names so titles and workspaces don't leak
+/// through the file listing.
+fn page_names(sessions: &[SiteSession], neutral: bool) -> Vec<String> {
+    let mut seen = HashSet::new();
+    sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| {
+            let base = if neutral {
+                format!("session-{:03}.html", i + 1)
+            } else {
+                filename::generate_full_filename(
+                    &session.agent,
+                    session.workspace.as_deref().map(Path::new),
+                    session.started_at,
+                    Some(&session.title),
+                    None,
+                )
+            };
+            let stem = base.strip_suffix(".html").unwrap_or(&base).to_string();
+            let mut name = base;
+            let mut n = 2;
+            while !seen.insert(name.clone()) {
+                name = format!("{stem}-{n}.html");
+                n += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// For each session, all sessions in its workspace ordered oldest first
+/// (empty for sessions without a workspace).
+fn workspace_siblings(sessions: &[SiteSession]) -> Vec<Vec<usize>> {
+    let mut by_workspace: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, session) in sessions.iter().enumerate() {
+        if let Some(workspace) = session.workspace.as_deref() {
+            by_workspace.entry(workspace).or_default().push(i);
+        }
+    }
+    for members in by_workspace.values_mut() {
+        members.sort_by_key(|&i| (sessions[i].started_at.unwrap_or(i64::MIN), i));
+    }
+    sessions
+        .iter()
+        .map(|session| {
+            session
+                .workspace
+                .as_deref()
+                .and_then(|w| by_workspace.get(w))
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn render_index(
+    sessions: &[SiteSession],
+    pages: &[String],
+    site_title: &str,
+    options: &ExportOptions,
+    password: Option<&str>,
+) -> Result<String, TemplateError> {
+    // The index has its own session search; the message search targets a
+    // single conversation.
+    let exporter = HtmlExporter::with_options(ExportOptions {
+        title: Some(site_title.to_string()),
+        include_search: false,
+        agent_name: None,
+        ..options.clone()
+    });
+
+    let rows: Vec<String> = sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| render_index_row(i, session, &pages[i]))
+        .collect();
+
+    let search_data: Vec<String> = sessions.iter().map(search_entry).collect();
+    let search_json = serde_json::to_string(&search_data)
+        .map_err(|e| TemplateError::RenderFailed(e.to_string()))?
+        // Keep the payload from closing its <script> element early.
+        .replace("</", "<\\/");
+
+    let content = format!(
+        r#"<section class="site-index" aria-label="Exported sessions">
+    <div class="site-search-bar">
+        <input type="search" id="site-search" class="search-input" placeholder="Search sessions..." aria-label="Search sessions">
+        <span id="site-search-count" class="search-count"></span>
+    </div>
+    <table id="site-table" class="site-table">
+        <thead>
+            <tr>
+                <th scope="col" data-sort="title" aria-sort="none">Title</th>
+                <th scope="col" data-sort="agent" aria-sort="none">Agent</th>
+                <th scope="col" data-sort="workspace" aria-sort="none">Workspace</th>
+                <th scope="col" data-sort="started" data-type="number" aria-sort="none">Started</th>
+                <th scope="col" data-sort="messages" data-type="number" aria-sort="none">Messages</th>
+                <th scope="col" data-sort="tags" aria-sort="none">Tags</th>
+            </tr>
+        </thead>
+        <tbody>
+{rows}
+        </tbody>
+    </table>
+    <script type="application/json" id="site-search-data">{search_json}</script>
+</section>"#,
+        rows = rows.join("\n"),
+    );
+
+    let mut template = exporter.create_template(site_title);
+    template.content = exporter.seal_content(content, password)?;
+    template.metadata = TemplateMetadata {
+        message_count: sessions.iter().map(|s| s.message_count).sum(),
+        ..TemplateMetadata::default()
+    };
+    template.critical_css.push_str(SITE_CSS);
+    template.inline_js.push_str(SITE_JS);
+    Ok(template.render(exporter.options()))
+}
+
+fn render_index_row(index: usize, session: &SiteSession, page: &str) -> String {
+    let workspace = session.workspace.as_deref().unwrap_or("");
+    let tags: Vec<String> = session
+        .tags
+        .iter()
+        .map(|tag| format!(r#"<span class="site-tag">{}</span>"#, html_escape(tag)))
+        .collect();
+    format!(
+        r#"            <tr data-index="{index}" data-title="{title}" data-agent="{agent}" data-workspace="{workspace}" data-started="{started}" data-messages="{messages}" data-tags="{tag_list}">
+                <td><a href="{dir}/{page}">{title}</a></td>
+                <td>{agent_name}</td>
+                <td title="{workspace}">{workspace_name}</td>
+                <td>{started_label}</td>
+                <td class="num">{messages}</td>
+                <td>{tags}</td>
+            </tr>"#,
+        title = html_escape(&session.title),
+        agent = html_escape(&session.agent),
+        workspace = html_escape(workspace),
+        started = session.started_at.unwrap_or(0),
+        messages = session.message_count,
+        tag_list = html_escape(&session.tags.join(" ")),
+        dir = SESSIONS_DIR,
+        page = html_escape(page),
+        agent_name = html_escape(renderer::agent_display_name(&session.agent)),
+        workspace_name = html_escape(workspace_name(workspace)),
+        started_label = session
+            .started_at
+            .and_then(format_timestamp)
+            .unwrap_or_default(),
+        tags = tags.join(" "),
+    )
+}
+
+fn render_session_page(
+    index: usize,
+    sessions: &[SiteSession],
+    pages: &[String],
+    siblings: &[usize],
+    options: &ExportOptions,
+    password: Option<&str>,
+) -> Result<String, TemplateError> {
+    let session = &sessions[index];
+    let exporter = HtmlExporter::with_options(ExportOptions {
+        title: Some(session.title.clone()),
+        agent_name: Some(session.agent.clone()),
+        ..options.clone()
+    });
+
+    let rendered = renderer::render_message_groups(&session.groups, &exporter.render_options())
+        .map_err(|e| TemplateError::RenderFailed(e.to_string()))?;
+    let nav = render_session_nav(index, sessions, pages, siblings);
+
+    // The header is never encrypted, so encrypted pages carry a neutral
+    // title there and the real one inside the encrypted content.
+    let (title, heading) = if options.encrypt {
+        (
+            format!("Session {} of {}", index + 1, sessions.len()),
+            format!(
+                "<h2 class=\"site-session-title\">{}</h2>\n",
+                html_escape(&session.title)
+            ),
+        )
+    } else {
+        (session.title.clone(), String::new())
+    };
+
+    let mut template = exporter.create_template(&title);
+    template.content = exporter.seal_content(format!("{nav}\n{heading}{rendered}"), password)?;
+    if !options.encrypt {
+        template.metadata = TemplateMetadata {
+            timestamp: session.started_at.and_then(format_timestamp),
+            agent: Some(session.agent.clone()),
+            message_count: session.message_count,
+            duration: format_duration(session.started_at, session.ended_at),
+            project: session.workspace.clone(),
+        };
+    }
+    template.critical_css.push_str(SITE_CSS);
+    template.inline_js.push_str(SITE_JS);
+    Ok(template.render(exporter.options()))
+}
+
+fn render_session_nav(
+    index: usize,
+    sessions: &[SiteSession],
+    pages: &[String],
+    siblings: &[usize],
+) -> String {
+    let mut items = vec![
+        r#"<a class="site-nav-home" href="../index.html">&larr; All sessions</a>"#.to_string(),
+    ];
+
+    if let Some(pos) = siblings.iter().position(|&i| i == index) {
+        if let Some(&prev) = pos.checked_sub(1).and_then(|p| siblings.get(p)) {
+            items.push(format!(
+                r#"<a rel="prev" href="{}" title="{}">&larr; Previous in workspace</a>"#,
+                html_escape(&pages[prev]),
+                html_escape(&sessions[prev].title)
+            ));
+        }
+        if let Some(&next) = siblings.get(pos + 1) {
+            items.push(format!(
+                r#"<a rel="next" href="{}" title="{}">Next in workspace &rarr;</a>"#,
+                html_escape(&pages[next]),
+                html_escape(&sessions[next].title)
+            ));
+        }
+
+        let others: Vec<String> = siblings
+            .iter()
+            .filter(|&&i| i != index)
+            .map(|&i| {
+                format!(
+                    r#"<li><a href="{}">{}</a> <span class="site-meta">{}</span></li>"#,
+                    html_escape(&pages[i]),
+                    html_escape(&sessions[i].title),
+                    sessions[i]
+                        .started_at
+                        .and_then(format_timestamp)
+                        .unwrap_or_default()
+                )
+            })
+            .collect();
+        if !others.is_empty() {
+            let workspace = sessions[index].workspace.as_deref().unwrap_or("");
+            items.push(format!(
+                r#"<details class="site-related"><summary>{} other session{} in {}</summary><ul>{}</ul></details>"#,
+                others.len(),
+                if others.len() == 1 { "" } else { "s" },
+                html_escape(workspace_name(workspace)),
+                others.join("")
+            ));
+        }
+    }
+
+    format!(
+        r#"<nav class="site-nav" aria-label="Session navigation">
+{}
+</nav>"#,
+        items.join("\n")
+    )
+}
+
+/// Lowercased text the index search matches against for one session.
+fn search_entry(session: &SiteSession) -> String {
+    let text = [
+        session.title.as_str(),
+        session.agent.as_str(),
+        session.workspace.as_deref().unwrap_or(""),
+        session.tags.join(" ").as_str(),
+        session.search_text.as_str(),
+    ]
+    .join("\n")
+    .to_lowercase();
+    text.chars().take(SEARCH_TEXT_LIMIT).collect()
+}
+
+fn workspace_name(workspace: &str) -> &str {
+    Path::new(workspace)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(workspace)
+}
+
+fn format_timestamp(ts: i64) -> Option<String> {
+    Utc.timestamp_millis_opt(ts)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+}
+
+fn format_duration(start: Option<i64>, end: Option<i64>) -> Option<String> {
+    match (start, end) {
+        (Some(start), Some(end)) if end > start => {
+            let mins = (end - start) / 60_000;
+            if mins >= 60 {
+                Some(format!("{}h {}m", mins / 60, mins % 60))
+            } else if mins > 0 {
+                Some(format!("{}m", mins))
+            } else {
+                Some("< 1m".to_string())
+            }
+        }
+        _ => None,
+    }
+}
+
+const SITE_CSS: &str = r#"
+/* Multi-session site: index table and session navigation */
+.site-nav {
+    display: flex;
+    flex-wrap: wrap;
+    align-items: center;
+    gap: 0.5rem 1.25rem;
+    margin-bottom: 1.5rem;
+    padding-bottom: 1rem;
+    border-bottom: 1px solid var(--border);
+    font-size: var(--text-sm);
+}
+.site-nav a,
+.site-table a {
+    color: var(--primary);
+    text-decoration: none;
+}
+.site-nav a:hover,
+.site-table a:hover {
+    text-decoration: underline;
+}
+.site-related {
+    width: 100%;
+    color: var(--muted-foreground);
+}
+.site-related summary {
+    cursor: pointer;
+}
+.site-related ul {
+    margin: 0.5rem 0 0;
+    padding-left: 1.25rem;
+}
+.site-meta {
+    color: var(--muted-foreground);
+    font-size: var(--text-xs);
+}
+.site-search-bar {
+    display: flex;
+    align-items: center;
+    gap: 0.75rem;
+    margin-bottom: 1rem;
+}
+.site-table {
+    width: 100%;
+    border-collapse: collapse;
+    font-size: var(--text-sm);
+}
+.site-table th,
+.site-table td {
+    padding: 0.5rem 0.75rem;
+    border-bottom: 1px solid var(--border);
+    text-align: left;
+    vertical-align: top;
+}
+.site-table th[data-sort] {
+    cursor: pointer;
+    user-select: none;
+    white-space: nowrap;
+    color: var(--muted-foreground);
+}
+.site-table th[aria-sort="ascending"]::after {
+    content: " \25B2";
+}
+.site-table th[aria-sort="descending"]::after {
+    content: " \25BC";
+}
+.site-table td.num {
+    text-align: right;
+    font-variant-numeric: tabular-nums;
+}
+.site-table tr[hidden] {
+    display: none;
+}
+.site-tag {
+    display: inline-block;
+    margin: 0 0.25rem 0.25rem 0;
+    padding: 0 0.4rem;
+    border: 1px solid var(--border);
+    border-radius: var(--radius-sm);
+    font-size: var(--text-xs);
+}
+"#;
+
+const SITE_JS: &str = r#"
+// Multi-session site: index sort/search and password reuse across pages.
+// Handlers are delegated so they keep working after decryption swaps content.
+const Site = {
+    storageKey: 'cass-site-password',
+    data: null,
+
+    init() {
+        document.addEventListener('input', (e) => {
+            if (e.target && e.target.id === 'site-search') this.filter(e.target.value);
+        });
+        document.addEventListener('click', (e) => {
+            const th = e.target.closest ? e.target.closest('#site-table th[data-sort]') : null;
+            if (th) this.sort(th);
+        });
+        this.initPassword();
+    },
+
+    initPassword() {
+        const form = $('#password-form');
+        const input = $('#password-input');
+        if (!form || !input || typeof Crypto === 'undefined') return;
+
+        form.addEventListener('submit', () => {
+            try { sessionStorage.setItem(this.storageKey, input.value); } catch (e) {}
+        });
+
+        let saved = null;
+        try { saved = sessionStorage.getItem(this.storageKey); } catch (e) {}
+        if (!saved) return;
+        input.value = saved;
+        Crypto.decrypt().then(() => {
+            if (!$('#password-modal').hidden) {
+                input.value = '';
+                try { sessionStorage.removeItem(this.storageKey); } catch (e) {}
+            }
+        });
+    },
+
+    searchData() {
+        if (!this.data) {
+            const el = $('#site-search-data');
+            try { this.data = el ? JSON.parse(el.textContent) : []; } catch (e) { this.data = []; }
+        }
+        return this.data;
+    },
+
+    filter(value) {
+        const terms = value.toLowerCase().split(/\s+/).filter(Boolean);
+        const data = this.searchData();
+        let shown = 0;
+        $$('#site-table tbody tr').forEach((row) => {
+            const text = data[Number(row.dataset.index)] || '';
+            const match = terms.every((t) => text.includes(t));
+            row.hidden = !match;
+            if (match) shown++;
+        });
+        const count = $('#site-search-count');
+        if (count) count.textContent = terms.length ? `${shown} of ${data.length}` : '';
+    },
+
+    sort(th) {
+        const key = th.dataset.sort;
+        const numeric = th.dataset.type === 'number';
+        const dir = th.getAttribute('aria-sort') === 'ascending' ? 'descending' : 'ascending';
+        $$('#site-table th[data-sort]').forEach((h) => h.setAttribute('aria-sort', 'none'));
+        th.setAttribute('aria-sort', dir);
+
+        const tbody = $('#site-table tbody');
+        const sign = dir === 'ascending' ? 1 : -1;
+        const rows = Array.from(tbody.rows);
+        rows.sort((a, b) => {
+            const x = a.dataset[key] || '';
+            const y = b.dataset[key] || '';
+            const cmp = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+            return cmp * sign || Number(a.dataset.index) - Number(b.dataset.index);
+        });
+        rows.forEach((row) => tbody.appendChild(row));
+    }
+};
+
+if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', () => Site.init());
+} else {
+    Site.init();
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::html_export::Message;
+
+    fn session(title: &str, workspace: Option<&str>, started_at: i64) -> SiteSession {
+        let message = Message {
+            role: "user".to_string(),
+            content: format!("Body of {title}"),
+            timestamp: None,
+            tool_call: None,
+            index: Some(0),
+            author: None,
+        };
+        SiteSession {
+            title: title.to_string(),
+            agent: "claude_code".to_string(),
+            workspace: workspace.map(str::to_string),
+            source_path: format!("/sessions/{title}.jsonl"),
+            started_at: Some(started_at),
+            ended_at: Some(started_at + 5 * 60_000),
+            tags: vec!["auth".to_string()],
+            message_count: 1,
+            groups: vec![MessageGroup::user(message)],
+            search_text: format!("Body of {title}"),
+        }
+    }
+
+    fn options() -> ExportOptions {
+        ExportOptions {
+            include_cdn: false,
+            ..ExportOptions::default()
+        }
+    }
+
+    fn file<'a>(files: &'a [SiteFile], title: &str) -> &'a SiteFile {
+        files
+            .iter()
+            .find(|f| f.title == title)
+            .expect("page for title")
+    }
+
+    #[test]
+    fn test_render_site_index_links_every_session() {
+        let sessions = vec![
+            session("Fix login", Some("/work/app"), 1_700_000_000_000),
+            session("Add tests", Some("/work/lib"), 1_700_000_100_000),
+        ];
+        let files = render_site(&sessions, "My sessions", &options(), None).unwrap();
+
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "index.html");
+        let index = &files[0].html;
+        assert!(index.contains(r#"id="site-table""#));
+        assert!(index.contains(r#"id="site-search-data""#));
+        assert!(index.contains("body of fix login"));
+        for page in &files[1..] {
+            assert!(page.path.starts_with("sessions/"));
+            assert!(index.contains(&format!(r#"href="{}""#, page.path)));
+        }
+    }
+
+    #[test]
+    fn test_render_site_cross_links_same_workspace() {
+        let sessions = vec![
+            session("Newest", Some("/work/app"), 1_700_000_200_000),
+            session("Oldest", Some("/work/app"), 1_700_000_000_000),
+            session("Elsewhere", Some("/work/lib"), 1_700_000_100_000),
+        ];
+        let files = render_site(&sessions, "Sessions", &options(), None).unwrap();
+        let page_name = |title: &str| {
+            file(&files, title)
+                .path
+                .trim_start_matches("sessions/")
+                .to_string()
+        };
+
+        let oldest = &file(&files, "Oldest").html;
+        assert!(oldest.contains(r#"href="../index.html""#));
+        assert!(oldest.contains(&format!(r#"rel="next" href="{}""#, page_name("Newest"))));
+        assert!(!oldest.contains(r#"rel="prev""#));
+        assert!(!oldest.contains(&page_name("Elsewhere")));
+
+        let newest = &file(&files, "Newest").html;
+        assert!(newest.contains(&format!(r#"rel="prev" href="{}""#, page_name("Oldest"))));
+        assert!(newest.contains("1 other session in app"));
+
+        let elsewhere = &file(&files, "Elsewhere").html;
+        assert!(!elsewhere.contains("rel=\"prev\"") && !elsewhere.contains("rel=\"next\""));
+    }
+
+    #[test]
+    fn test_render_site_dedupes_page_names() {
+        let sessions = vec![
+            session("Same", Some("/work/app"), 1_700_000_000_000),
+            session("Same", Some("/work/app"), 1_700_000_000_000),
+        ];
+        let files = render_site(&sessions, "Sessions", &options(), None).unwrap();
+        assert_ne!(files[1].path, files[2].path);
+        assert!(files[2].path.ends_with("-2.html"));
+    }
+
+    #[test]
+    fn test_render_site_escapes_search_data() {
+        let mut s = session("Tricky", None, 1_700_000_000_000);
+        s.search_text = "</script><script>alert(1)</script>".to_string();
+        let files = render_site(&[s], "Sessions", &options(), None).unwrap();
+        assert!(!files[0].html.contains("</script><script>alert(1)"));
+        assert!(files[0].html.contains(r"<\/script>"));
+    }
+
+    #[test]
+    fn test_render_site_requires_sessions_and_password() {
+        assert!(matches!(
+            render_site(&[], "Sessions", &options(), None),
+            Err(TemplateError::InvalidInput(_))
+        ));
+
+        let encrypted = ExportOptions {
+            encrypt: true,
+            ..options()
+        };
+        let sessions = vec![session("Secret", None, 1_700_000_000_000)];
+        assert!(matches!(
+            render_site(&sessions, "Sessions", &encrypted, None),
+            Err(TemplateError::EncryptionRequired)
+        ));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_render_site_encrypted_hides_titles_and_content() {
+        let encrypted = ExportOptions {
+            encrypt: true,
+            ..options()
+        };
+        let sessions = vec![
+            session("Secret plan", Some("/work/private-app"), 1_700_000_000_000),
+            session("Other plan", Some("/work/private-app"), 1_700_000_100_000),
+        ];
+        let files = render_site(&sessions, "Sessions", &encrypted, Some("hunter2")).unwrap();
+
+        assert_eq!(files[1].path, "sessions/session-001.html");
+        assert_eq!(files[2].path, "sessions/session-002.html");
+        for f in &files {
+            assert!(f.html.contains(r#"id="encrypted-content""#), "{}", f.path);
+            assert!(f.html.contains("cass-site-password"));
+            assert!(!f.html.contains("Secret plan"), "{}", f.path);
+            assert!(!f.html.to_lowercase().contains("body of"), "{}", f.path);
+            assert!(!f.html.contains("private-app"), "{}", f.path);
+        }
+    }
+}
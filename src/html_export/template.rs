@@ -620,20 +620,8 @@ impl HtmlExporter {
             "Starting HTML export"
         );
 
-        let render_options = renderer::RenderOptions {
-            show_timestamps: self.options.show_timestamps,
-            show_tool_calls: self.options.show_tool_calls,
-            syntax_highlighting: self.options.syntax_highlighting,
-            agent_slug: self
-                .options
-                .agent_name
-                .as_ref()
-                .map(|name| filename::agent_slug(name)),
-            ..renderer::RenderOptions::default()
-        };
-
         let render_started = Instant::now();
-        let rendered = renderer::render_message_groups(groups, &render_options)
+        let rendered = renderer::render_message_groups(groups, &self.render_options())
             .map_err(|e| TemplateError::RenderFailed(e.to_string()))?;
         debug!(
             component = "renderer",
@@ -644,34 +632,7 @@ impl HtmlExporter {
             "Message groups rendered"
         );
 
-        let content = if self.options.encrypt {
-            let password = match password {
-                Some(pw) => pw,
-                None => {
-                    warn!(
-                        component = "encryption",
-                        operation = "encrypt_payload",
-                        "Encryption requested but no password provided"
-                    );
-                    return Err(TemplateError::EncryptionRequired);
-                }
-            };
-            debug!(
-                component = "encryption",
-                operation = "encrypt_payload",
-                plaintext_bytes = rendered.len(),
-                "Encrypting rendered HTML"
-            );
-            let encrypted = encryption::encrypt_content(
-                &rendered,
-                password,
-                &encryption::EncryptionParams::default(),
-            )
-            .map_err(|e| TemplateError::RenderFailed(e.to_string()))?;
-            encryption::render_encrypted_placeholder(&encrypted)
-        } else {
-            rendered
-        };
+        let content = self.seal_content(rendered, password)?;
 
         let styles_started = Instant::now();
         let styles = styles::generate_styles(&self.options);
@@ -716,6 +677,56 @@ impl HtmlExporter {
     }
 }
 
+impl HtmlExporter {
+    /// Renderer options derived from the export options.
+    pub(crate) fn render_options(&self) -> renderer::RenderOptions {
+        renderer::RenderOptions {
+            show_timestamps: self.options.show_timestamps,
+            show_tool_calls: self.options.show_tool_calls,
+            syntax_highlighting: self.options.syntax_highlighting,
+            agent_slug: self
+                .options
+                .agent_name
+                .as_ref()
+                .map(|name| filename::agent_slug(name)),
+            ..renderer::RenderOptions::default()
+        }
+    }
+
+    /// Encrypt rendered content into the decryption placeholder when
+    /// encryption is enabled; otherwise return it unchanged.
+    pub(crate) fn seal_content(
+        &self,
+        rendered: String,
+        password: Option<&str>,
+    ) -> Result<String, TemplateError> {
+        if !self.options.encrypt {
+            return Ok(rendered);
+        }
+        let Some(password) = password else {
+            warn!(
+                component = "encryption",
+                operation = "encrypt_payload",
+                "Encryption requested but no password provided"
+            );
+            return Err(TemplateError::EncryptionRequired);
+        };
+        debug!(
+            component = "encryption",
+            operation = "encrypt_payload",
+            plaintext_bytes = rendered.len(),
+            "Encrypting rendered HTML"
+        );
+        let encrypted = encryption::encrypt_content(
+            &rendered,
+            password,
+            &encryption::EncryptionParams::default(),
+        )
+        .map_err(|e| TemplateError::RenderFailed(e.to_string()))?;
+        Ok(encryption::render_encrypted_placeholder(&encrypted))
+    }
+}
+
 impl Default for HtmlExporter {
     fn default() -> Self {
         Self::new()
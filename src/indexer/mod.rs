@@ -14,14 +14,18 @@ use notify::{RecursiveMode, Watcher, recommended_watcher};
 
 use crate::connectors::NormalizedConversation;
 use crate::connectors::{
-    Connector, ScanRoot, aider::AiderConnector, amp::AmpConnector, chatgpt::ChatGptConnector,
-    claude_code::ClaudeCodeConnector, clawdbot::ClawdbotConnector, cline::ClineConnector,
-    codex::CodexConnector, cursor::CursorConnector, factory::FactoryConnector,
-    gemini::GeminiConnector, opencode::OpenCodeConnector, pi_agent::PiAgentConnector,
-    vibe::VibeConnector,
+    Connector, CursorMap, FileCursor, ScanContext, ScanRoot, aider::AiderConnector,
+    amp::AmpConnector, chatgpt::ChatGptConnector, claude_code::ClaudeCodeConnector,
+    clawdbot::ClawdbotConnector, cline::ClineConnector, codex::CodexConnector,
+    continue_dev::ContinueConnector, copilot_chat::CopilotChatConnector, cursor::CursorConnector,
+    external::ExternalConnectors, factory::FactoryConnector, gemini::GeminiConnector,
+    native::NativeConnector, opencode::OpenCodeConnector, pi_agent::PiAgentConnector,
+    roo_code::RooCodeConnector, vibe::VibeConnector, windsurf::WindsurfConnector,
 };
 use crate::search::tantivy::{TantivyIndex, index_dir, schema_hash_matches};
-use crate::search::vector_index::{ROLE_ASSISTANT, ROLE_SYSTEM, ROLE_TOOL, ROLE_USER};
+use crate::search::vector_index::{
+    Quantization, ROLE_ASSISTANT, ROLE_SYSTEM, ROLE_TOOL, ROLE_USER,
+};
 
 use crate::sources::config::{Platform, SourcesConfig};
 use crate::sources::provenance::{Origin, Source};
@@ -36,6 +40,9 @@ type BatchClassificationMap =
 #[derive(Debug, Clone)]
 pub enum ReindexCommand {
     Full,
+    /// Rescan the remote mirror roots under these paths from scratch, e.g.
+    /// after a scheduled sync copied files in with their original mtimes.
+    Rescan(Vec<PathBuf>),
 }
 
 #[derive(Debug)]
@@ -336,6 +343,8 @@ pub struct IndexOptions {
     pub build_hnsw: bool,
     /// Embedder ID to use for semantic indexing (hash, fastembed).
     pub embedder: String,
+    /// Vector storage format for the semantic index (int8/binary add an f16 rescoring file).
+    pub quantization: Quantization,
     pub progress: Option<Arc<IndexingProgress>>,
 }
 
@@ -358,6 +367,8 @@ pub enum IndexMessage {
         is_discovered: bool,
         /// Message count in this batch (for stats)
         message_count: usize,
+        /// Scan cursors to persist once the batch has been ingested
+        cursors: Vec<(PathBuf, FileCursor)>,
     },
     /// A scan error occurred (non-fatal, logged but continues)
     ScanError {
@@ -377,6 +388,9 @@ pub enum IndexMessage {
 /// too large defeats the purpose of backpressure.
 const STREAMING_CHANNEL_SIZE: usize = 32;
 
+/// Maximum conversations per batch sent while a connector is still scanning.
+const STREAMING_BATCH_SIZE: usize = 64;
+
 /// Check if streaming indexing is enabled via environment variable.
 ///
 /// Set `CASS_STREAMING_INDEX=0` to disable streaming and use batch mode.
@@ -387,11 +401,88 @@ pub fn streaming_index_enabled() -> bool {
         .unwrap_or(true)
 }
 
+/// Check if per-file scan cursors are honoured on incremental runs.
+///
+/// Set `CASS_SCAN_CURSORS=0` to re-parse every modified session file from the start.
+/// Cursors are still recorded so they can be re-enabled at any time.
+pub fn scan_cursors_enabled() -> bool {
+    dotenvy::var("CASS_SCAN_CURSORS")
+        .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
+        .unwrap_or(true)
+}
+
+/// Load stored scan cursors for an incremental run.
+///
+/// Full scans (`since_ts == None`, including rebuilds that must re-feed Tantivy)
+/// start every file from byte 0, so no cursors are returned for them.
+fn load_scan_cursors(storage: &SqliteStorage, since_ts: Option<i64>) -> CursorMap {
+    if since_ts.is_none() || !scan_cursors_enabled() {
+        return CursorMap::new();
+    }
+    storage.load_scan_cursors().unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "failed to load scan cursors; parsing files from the start");
+        CursorMap::new()
+    })
+}
+
+/// Drain [`Connector::scan_incremental`], handing conversations to `on_batch` in chunks
+/// of at most `batch_size`, together with the cursors to persist once they are ingested.
+///
+/// Per-session failures are logged and skipped so one unreadable file does not drop
+/// the rest of the scan; failing to start the scan is returned as an error.
+fn scan_incremental_batches(
+    conn: &dyn Connector,
+    ctx: &ScanContext,
+    cursors: &CursorMap,
+    batch_size: usize,
+    mut on_batch: impl FnMut(Vec<NormalizedConversation>, Vec<(PathBuf, FileCursor)>),
+) -> Result<()> {
+    let mut convs = Vec::new();
+    let mut batch_cursors = Vec::new();
+
+    for item in conn.scan_incremental(ctx, cursors)? {
+        let item = match item {
+            Ok(item) => item,
+            Err(e) => {
+                tracing::warn!(error = %e, "skipping unreadable session");
+                continue;
+            }
+        };
+        if item.appended {
+            tracing::debug!(
+                path = %item.source_path.display(),
+                messages = item.conversation.as_ref().map_or(0, |c| c.messages.len()),
+                "incremental_append"
+            );
+        }
+        if let Some(cursor) = item.cursor {
+            batch_cursors.push((item.source_path, cursor));
+        }
+        let Some(conversation) = item.conversation else {
+            continue;
+        };
+        convs.push(conversation);
+        if convs.len() >= batch_size {
+            on_batch(
+                std::mem::take(&mut convs),
+                std::mem::take(&mut batch_cursors),
+            );
+        }
+    }
+
+    if !convs.is_empty() || !batch_cursors.is_empty() {
+        on_batch(convs, batch_cursors);
+    }
+    Ok(())
+}
+
 /// Spawn a producer thread that scans a connector and sends batches through the channel.
 ///
 /// Each connector runs in its own thread, scanning local and remote roots.
 /// Conversations are sent through the channel as they're discovered, providing
-/// backpressure when the consumer (indexer) falls behind.
+/// backpressure when the consumer (indexer) falls behind. Session files with a
+/// stored cursor in `cursors` are resumed rather than re-parsed.
+#[allow(clippy::too_many_arguments)]
 fn spawn_connector_producer(
     name: &'static str,
     factory: fn() -> Box<dyn Connector + Send>,
@@ -399,6 +490,7 @@ fn spawn_connector_producer(
     data_dir: PathBuf,
     remote_roots: Vec<ScanRoot>,
     since_ts: Option<i64>,
+    cursors: Arc<CursorMap>,
     progress: Option<Arc<IndexingProgress>>,
 ) -> JoinHandle<()> {
     thread::spawn(move || {
@@ -416,47 +508,49 @@ fn spawn_connector_producer(
             is_discovered = true;
 
             // Scan local sources
-            let ctx = crate::connectors::ScanContext::local_default(data_dir.clone(), since_ts);
-            match conn.scan(&ctx) {
-                Ok(mut local_convs) => {
+            let ctx = ScanContext::local_default(data_dir.clone(), since_ts);
+            let local_origin = Origin::local();
+            let result = scan_incremental_batches(
+                &*conn,
+                &ctx,
+                &cursors,
+                STREAMING_BATCH_SIZE,
+                |mut local_convs, batch_cursors| {
                     // Inject local provenance
-                    let local_origin = Origin::local();
                     for conv in &mut local_convs {
                         inject_provenance(conv, &local_origin);
                     }
 
-                    if !local_convs.is_empty() {
-                        // Count messages for stats
-                        let message_count: usize =
-                            local_convs.iter().map(|c| c.messages.len()).sum();
-                        // Send batch through channel (blocking if full - backpressure!)
-                        let _ = tx.send(IndexMessage::Batch {
-                            connector_name: name,
-                            conversations: local_convs,
-                            is_discovered,
-                            message_count,
-                        });
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(connector = name, "local scan failed: {}", e);
-                    let _ = tx.send(IndexMessage::ScanError {
+                    // Count messages for stats
+                    let message_count: usize = local_convs.iter().map(|c| c.messages.len()).sum();
+                    // Send batch through channel (blocking if full - backpressure!)
+                    let _ = tx.send(IndexMessage::Batch {
                         connector_name: name,
-                        error: e.to_string(),
+                        conversations: local_convs,
+                        is_discovered,
+                        message_count,
+                        cursors: batch_cursors,
                     });
-                }
+                },
+            );
+            if let Err(e) = result {
+                tracing::warn!(connector = name, "local scan failed: {}", e);
+                let _ = tx.send(IndexMessage::ScanError {
+                    connector_name: name,
+                    error: e.to_string(),
+                });
             }
         }
 
         // Scan remote sources
         for root in &remote_roots {
-            let ctx = crate::connectors::ScanContext::with_roots(
-                root.path.clone(),
-                vec![root.clone()],
-                since_ts,
-            );
-            match conn.scan(&ctx) {
-                Ok(mut remote_convs) => {
+            let ctx = ScanContext::with_roots(root.path.clone(), vec![root.clone()], since_ts);
+            let result = scan_incremental_batches(
+                &*conn,
+                &ctx,
+                &cursors,
+                STREAMING_BATCH_SIZE,
+                |mut remote_convs, batch_cursors| {
                     for conv in &mut remote_convs {
                         inject_provenance(conv, &root.origin);
                         apply_workspace_rewrite(conv, root);
@@ -470,25 +564,23 @@ fn spawn_connector_producer(
                         is_discovered = true;
                     }
 
-                    if !remote_convs.is_empty() {
-                        // Count messages for stats
-                        let message_count: usize =
-                            remote_convs.iter().map(|c| c.messages.len()).sum();
-                        let _ = tx.send(IndexMessage::Batch {
-                            connector_name: name,
-                            conversations: remote_convs,
-                            is_discovered,
-                            message_count,
-                        });
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!(
-                        connector = name,
-                        root = %root.path.display(),
-                        "remote scan failed: {}", e
-                    );
-                }
+                    // Count messages for stats
+                    let message_count: usize = remote_convs.iter().map(|c| c.messages.len()).sum();
+                    let _ = tx.send(IndexMessage::Batch {
+                        connector_name: name,
+                        conversations: remote_convs,
+                        is_discovered,
+                        message_count,
+                        cursors: batch_cursors,
+                    });
+                },
+            );
+            if let Err(e) = result {
+                tracing::warn!(
+                    connector = name,
+                    root = %root.path.display(),
+                    "remote scan failed: {}", e
+                );
             }
         }
 
@@ -541,6 +633,7 @@ fn run_streaming_consumer(
                 conversations,
                 is_discovered,
                 message_count,
+                cursors,
             }) => {
                 let batch_size = conversations.len();
                 total_conversations += batch_size;
@@ -579,6 +672,11 @@ fn run_streaming_consumer(
                 // Ingest the batch
                 ingest_batch(storage, t_index, &conversations, progress, needs_rebuild)?;
 
+                // Advance scan cursors only after their messages are stored
+                if let Err(e) = storage.save_scan_cursors(&cursors) {
+                    tracing::warn!(connector = connector_name, error = %e, "failed to save scan cursors");
+                }
+
                 // Periodic commit to make results visible incrementally (every 5s)
                 if last_commit.elapsed() >= Duration::from_secs(5) {
                     if let Err(e) = t_index.commit() {
@@ -718,6 +816,8 @@ fn run_streaming_index(
         }
     }
 
+    let cursors = Arc::new(load_scan_cursors(storage, since_ts));
+
     // Create bounded channel for backpressure
     let (tx, rx) = bounded::<IndexMessage>(STREAMING_CHANNEL_SIZE);
 
@@ -732,6 +832,7 @@ fn run_streaming_index(
                 opts.data_dir.clone(),
                 remote_roots.clone(),
                 since_ts,
+                Arc::clone(&cursors),
                 opts.progress.clone(),
             )
         })
@@ -962,6 +1063,13 @@ pub fn run_index(
         needs_rebuild = true;
     }
 
+    // A migration rewrote denormalized rows (e.g. moved sessions to another agent).
+    if storage.take_rebuild_request()? {
+        tracing::info!("schema migration requested a rebuild; forcing full reindex");
+        storage.rebuild_daily_stats()?;
+        needs_rebuild = true;
+    }
+
     if needs_rebuild && let Some(p) = &opts.progress {
         p.is_rebuilding.store(true, Ordering::Relaxed);
     }
@@ -1041,76 +1149,28 @@ pub fn run_index(
 
     t_index.commit()?;
 
-    // Semantic indexing (if enabled)
-    if opts.semantic {
-        tracing::info!(embedder = %opts.embedder, "starting semantic indexing");
-
-        let semantic_indexer = SemanticIndexer::new(&opts.embedder, Some(&opts.data_dir))?;
-
-        // Fetch all messages with metadata from SQLite
-        let raw_messages = storage.fetch_messages_for_embedding()?;
+    // Semantic indexing (if enabled): embeds only new and edited messages
+    // unless the index is missing, incompatible or a full rebuild was requested.
+    let semantic_indexer = if opts.semantic {
         tracing::info!(
-            message_count = raw_messages.len(),
-            "fetched messages for embedding"
+            embedder = %opts.embedder,
+            quantization = opts.quantization.as_str(),
+            "starting semantic indexing"
         );
 
-        // Convert to EmbeddingInput format
-        let embedding_inputs: Vec<EmbeddingInput> = raw_messages
-            .into_iter()
-            .map(|msg| {
-                let role_u8 = match msg.role.as_str() {
-                    "user" => ROLE_USER,
-                    "agent" | "assistant" => ROLE_ASSISTANT,
-                    "system" => ROLE_SYSTEM,
-                    "tool" => ROLE_TOOL,
-                    _ => ROLE_USER, // default to user for unknown roles
-                };
-
-                EmbeddingInput {
-                    message_id: msg.message_id as u64,
-                    created_at_ms: msg.created_at.unwrap_or(0),
-                    agent_id: msg.agent_id as u32,
-                    workspace_id: msg.workspace_id.unwrap_or(0) as u32,
-                    source_id: msg.source_id_hash,
-                    role: role_u8,
-                    chunk_idx: 0,
-                    content: msg.content,
-                }
-            })
-            .collect();
-
-        // Generate embeddings
-        let embedded_messages = semantic_indexer.embed_messages(&embedding_inputs)?;
-        tracing::info!(
-            embedded_count = embedded_messages.len(),
-            "generated embeddings"
-        );
-
-        if !embedded_messages.is_empty() {
-            let vector_index = semantic_indexer.build_index(embedded_messages)?;
-            let index_path = semantic_indexer.save_index(&vector_index, &opts.data_dir)?;
-            tracing::info!(
-                path = %index_path.display(),
-                embedder = semantic_indexer.embedder_id(),
-                "saved semantic vector index"
-            );
-
-            // Build HNSW index for approximate nearest neighbor search (if enabled)
-            if opts.build_hnsw {
-                let hnsw_path = semantic_indexer.build_hnsw_index(
-                    &vector_index,
-                    &opts.data_dir,
-                    None, // Use default M
-                    None, // Use default ef_construction
-                )?;
-                tracing::info!(
-                    path = %hnsw_path.display(),
-                    embedder = semantic_indexer.embedder_id(),
-                    "saved HNSW index for approximate search"
-                );
-            }
-        }
-    }
+        let semantic_indexer = SemanticIndexer::new(&opts.embedder, Some(&opts.data_dir))?
+            .with_quantization(opts.quantization);
+        let inputs = semantic_inputs(&storage)?;
+        update_semantic_index(
+            &semantic_indexer,
+            &inputs,
+            &opts,
+            opts.full || opts.force_rebuild,
+        )?;
+        Some(semantic_indexer)
+    } else {
+        None
+    };
 
     // Update last_scan_ts after successful scan and commit
     storage.set_last_scan_ts(scan_start_ts)?;
@@ -1159,40 +1219,53 @@ pub fn run_index(
             watch_roots.clone(),
             event_channel,
             stale_detector,
-            move |paths, roots, is_rebuild| {
-                if is_rebuild {
-                    if let Ok(mut g) = state.lock() {
-                        g.clear();
-                        let _ = save_watch_state(&opts_clone.data_dir, &g);
+            move |paths, roots, trigger| {
+                let indexed = match trigger {
+                    WatchTrigger::Rebuild => {
+                        if let Ok(mut g) = state.lock() {
+                            g.clear();
+                            let _ = save_watch_state(&opts_clone.data_dir, &g);
+                        }
+                        // Reset stale detector on rebuild
+                        detector_clone.reset();
+                        // For rebuild, trigger reindex on all active roots
+                        let all_root_paths: Vec<PathBuf> =
+                            roots.iter().map(|(_, root)| root.path.clone()).collect();
+                        reindex_paths(
+                            &opts_clone,
+                            all_root_paths,
+                            roots,
+                            state.clone(),
+                            storage.clone(),
+                            t_index.clone(),
+                            true,
+                        )
                     }
-                    // Reset stale detector on rebuild
-                    detector_clone.reset();
-                    // For rebuild, trigger reindex on all active roots
-                    let all_root_paths: Vec<PathBuf> =
-                        roots.iter().map(|(_, root)| root.path.clone()).collect();
-                    let indexed = reindex_paths(
+                    WatchTrigger::Changes => reindex_paths(
                         &opts_clone,
-                        all_root_paths,
+                        paths,
                         roots,
                         state.clone(),
                         storage.clone(),
                         t_index.clone(),
-                        true,
-                    );
-                    // Record result to stale detector
-                    detector_clone.record_scan(indexed.unwrap_or(0));
-                } else {
-                    let indexed = reindex_paths(
+                        false,
+                    ),
+                    // Mirrors may have gained roots since the watcher started,
+                    // so these are looked up afresh rather than taken from `roots`.
+                    WatchTrigger::Rescan => rescan_remote_roots(
                         &opts_clone,
-                        paths,
-                        roots,
+                        &paths,
                         state.clone(),
                         storage.clone(),
                         t_index.clone(),
-                        false,
-                    );
-                    // Record result to stale detector
-                    detector_clone.record_scan(indexed.unwrap_or(0));
+                    ),
+                };
+                // Record result to stale detector
+                let indexed = indexed.unwrap_or(0);
+                detector_clone.record_scan(indexed);
+                if indexed > 0 {
+                    refresh_semantic_index(&opts_clone, semantic_indexer.as_ref(), &storage);
+                    evaluate_saved_search_alerts(&opts_clone);
                 }
             },
         )?;
@@ -1201,6 +1274,112 @@ pub fn run_index(
     Ok(())
 }
 
+/// Load every message as semantic indexing input.
+fn semantic_inputs(storage: &SqliteStorage) -> Result<Vec<EmbeddingInput>> {
+    let raw_messages = storage.fetch_messages_for_embedding()?;
+    tracing::info!(
+        message_count = raw_messages.len(),
+        "fetched messages for embedding"
+    );
+
+    Ok(raw_messages
+        .into_iter()
+        .map(|msg| {
+            let role_u8 = match msg.role.as_str() {
+                "user" => ROLE_USER,
+                "agent" | "assistant" => ROLE_ASSISTANT,
+                "system" => ROLE_SYSTEM,
+                "tool" => ROLE_TOOL,
+                _ => ROLE_USER, // default to user for unknown roles
+            };
+
+            EmbeddingInput {
+                message_id: msg.message_id as u64,
+                created_at_ms: msg.created_at.unwrap_or(0),
+                agent_id: msg.agent_id as u32,
+                workspace_id: msg.workspace_id.unwrap_or(0) as u32,
+                source_id: msg.source_id_hash,
+                role: role_u8,
+                chunk_idx: 0,
+                content: msg.content,
+            }
+        })
+        .collect())
+}
+
+/// Set while a background vector segment compaction is running.
+static SEMANTIC_COMPACTION_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Apply `inputs` to the semantic index and compact its segments when due.
+///
+/// Compaction runs inline for one-shot indexing and on a background thread
+/// in watch mode, so new batches are not held up by it.
+fn update_semantic_index(
+    semantic_indexer: &SemanticIndexer,
+    inputs: &[EmbeddingInput],
+    opts: &IndexOptions,
+    rebuild: bool,
+) -> Result<()> {
+    let update = semantic_indexer.update_index(inputs, &opts.data_dir, opts.build_hnsw, rebuild)?;
+    tracing::info!(
+        embedder = semantic_indexer.embedder_id(),
+        embedded_messages = update.embedded_messages,
+        tombstoned_messages = update.tombstoned_messages,
+        rebuilt = update.rebuilt,
+        "updated semantic vector index"
+    );
+
+    if !update.needs_compaction {
+        return Ok(());
+    }
+    if !opts.watch {
+        semantic::compact_index(&opts.data_dir, semantic_indexer.embedder_id())?;
+        return Ok(());
+    }
+    if SEMANTIC_COMPACTION_RUNNING.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let data_dir = opts.data_dir.clone();
+    let embedder_id = semantic_indexer.embedder_id().to_string();
+    thread::spawn(move || {
+        if let Err(e) = semantic::compact_index(&data_dir, &embedder_id) {
+            tracing::warn!("vector segment compaction failed: {e}");
+        }
+        SEMANTIC_COMPACTION_RUNNING.store(false, Ordering::SeqCst);
+    });
+    Ok(())
+}
+
+/// Embed the messages a watch batch added or changed (best-effort).
+fn refresh_semantic_index(
+    opts: &IndexOptions,
+    semantic_indexer: Option<&SemanticIndexer>,
+    storage: &Mutex<SqliteStorage>,
+) {
+    let Some(semantic_indexer) = semantic_indexer else {
+        return;
+    };
+    // Only hold the storage lock while reading; embedding can take a while.
+    let inputs = match storage.lock() {
+        Ok(storage) => semantic_inputs(&storage),
+        Err(_) => Err(anyhow::anyhow!("storage lock poisoned")),
+    };
+    let result =
+        inputs.and_then(|inputs| update_semantic_index(semantic_indexer, &inputs, opts, false));
+    if let Err(e) = result {
+        tracing::warn!("semantic index refresh failed: {e}");
+    }
+}
+
+/// Run alerting saved searches against the freshly committed batch (best-effort).
+fn evaluate_saved_search_alerts(opts: &IndexOptions) {
+    match crate::saved_searches::evaluate_alerts(&opts.data_dir, &opts.db_path) {
+        Ok(0) => {}
+        Ok(matches) => tracing::info!(matches, "saved search alerts emitted"),
+        Err(e) => tracing::warn!("saved search evaluation failed: {e}"),
+    }
+}
+
 fn ingest_batch(
     storage: &mut SqliteStorage,
     t_index: &mut TantivyIndex,
@@ -1236,6 +1415,14 @@ pub fn get_connector_factories() -> Vec<(&'static str, fn() -> Box<dyn Connector
         ("chatgpt", || Box::new(ChatGptConnector::new())),
         ("pi_agent", || Box::new(PiAgentConnector::new())),
         ("factory", || Box::new(FactoryConnector::new())),
+        ("continue", || Box::new(ContinueConnector::new())),
+        ("windsurf", || Box::new(WindsurfConnector::new())),
+        ("copilot", || Box::new(CopilotChatConnector::new())),
+        ("roo_code", || Box::new(RooCodeConnector::new())),
+        // Subprocess connectors declared in connectors.toml
+        ("external", || Box::new(ExternalConnectors::load())),
+        // Conversations pulled from a remote cass (`transport = "native"`)
+        ("native", || Box::new(NativeConnector::new())),
     ]
 }
 
@@ -1247,11 +1434,9 @@ pub fn get_connector_factories() -> Vec<(&'static str, fn() -> Box<dyn Connector
 fn build_watch_roots(remote_roots: Vec<ScanRoot>) -> Vec<(ConnectorKind, ScanRoot)> {
     let factories = get_connector_factories();
     let mut roots = Vec::new();
-    let mut all_kinds = Vec::new();
 
     for (name, factory) in factories {
         if let Some(kind) = ConnectorKind::from_slug(name) {
-            all_kinds.push(kind);
             let conn = factory();
             let detection = conn.detect();
             if detection.detected {
@@ -1262,16 +1447,23 @@ fn build_watch_roots(remote_roots: Vec<ScanRoot>) -> Vec<(ConnectorKind, ScanRoo
         }
     }
 
-    // Add remote roots for ALL connectors
-    for remote_root in remote_roots {
-        for kind in &all_kinds {
-            roots.push((*kind, remote_root.clone()));
-        }
-    }
-
+    roots.extend(remote_watch_roots(remote_roots));
     roots
 }
 
+/// Pair each remote root with ALL connectors, since we don't know which
+/// agents' sessions a mirror holds.
+fn remote_watch_roots(remote_roots: Vec<ScanRoot>) -> Vec<(ConnectorKind, ScanRoot)> {
+    let all_kinds: Vec<ConnectorKind> = get_connector_factories()
+        .into_iter()
+        .filter_map(|(name, _)| ConnectorKind::from_slug(name))
+        .collect();
+    remote_roots
+        .into_iter()
+        .flat_map(|root| all_kinds.iter().map(move |kind| (*kind, root.clone())))
+        .collect()
+}
+
 impl ConnectorKind {
     fn from_slug(slug: &str) -> Option<Self> {
         match slug {
@@ -1288,6 +1480,12 @@ impl ConnectorKind {
             "chatgpt" => Some(Self::ChatGpt),
             "pi_agent" => Some(Self::PiAgent),
             "factory" => Some(Self::Factory),
+            "continue" => Some(Self::Continue),
+            "windsurf" => Some(Self::Windsurf),
+            "copilot" => Some(Self::Copilot),
+            "roo_code" => Some(Self::RooCode),
+            "external" => Some(Self::External),
+            "native" => Some(Self::Native),
             _ => None,
         }
     }
@@ -1309,11 +1507,30 @@ impl ConnectorKind {
             Self::ChatGpt => Box::new(ChatGptConnector::new()),
             Self::PiAgent => Box::new(PiAgentConnector::new()),
             Self::Factory => Box::new(FactoryConnector::new()),
+            Self::Continue => Box::new(ContinueConnector::new()),
+            Self::Windsurf => Box::new(WindsurfConnector::new()),
+            Self::Copilot => Box::new(CopilotChatConnector::new()),
+            Self::RooCode => Box::new(RooCodeConnector::new()),
+            Self::External => Box::new(ExternalConnectors::load()),
+            Self::Native => Box::new(NativeConnector::new()),
         }
     }
 }
 
-fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send + 'static>(
+/// Why the watch loop is asking for a reindex.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchTrigger {
+    /// Files changed under the watched roots.
+    Changes,
+    /// Full rebuild of every root.
+    Rebuild,
+    /// Rescan of the remote mirror roots under the given paths.
+    Rescan,
+}
+
+fn watch_sources<
+    F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], WatchTrigger) + Send + 'static,
+>(
     watch_once_paths: Option<Vec<PathBuf>>,
     roots: Vec<(ConnectorKind, ScanRoot)>,
     event_channel: Option<(Sender<IndexerEvent>, Receiver<IndexerEvent>)>,
@@ -1322,7 +1539,7 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
 ) -> Result<()> {
     if let Some(paths) = watch_once_paths {
         if !paths.is_empty() {
-            callback(paths, &roots, false);
+            callback(paths, &roots, WatchTrigger::Changes);
         }
         return Ok(());
     }
@@ -1361,7 +1578,7 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
             let now = Instant::now();
             let elapsed = now.duration_since(first_event.unwrap_or(now));
             if elapsed >= max_wait {
-                callback(std::mem::take(&mut pending), &roots, false);
+                callback(std::mem::take(&mut pending), &roots, WatchTrigger::Changes);
                 first_event = None;
                 continue;
             }
@@ -1381,9 +1598,16 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
                     ReindexCommand::Full => {
                         // Flush pending first, then do full rebuild
                         if !pending.is_empty() {
-                            callback(std::mem::take(&mut pending), &roots, false);
+                            callback(std::mem::take(&mut pending), &roots, WatchTrigger::Changes);
                         }
-                        callback(vec![], &roots, true);
+                        callback(vec![], &roots, WatchTrigger::Rebuild);
+                        first_event = None;
+                    }
+                    ReindexCommand::Rescan(paths) => {
+                        if !pending.is_empty() {
+                            callback(std::mem::take(&mut pending), &roots, WatchTrigger::Changes);
+                        }
+                        callback(paths, &roots, WatchTrigger::Rescan);
                         first_event = None;
                     }
                 },
@@ -1391,7 +1615,7 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
                 // Process pending events if any
                 if !pending.is_empty() {
-                    callback(std::mem::take(&mut pending), &roots, false);
+                    callback(std::mem::take(&mut pending), &roots, WatchTrigger::Changes);
                     first_event = None;
                 }
 
@@ -1422,7 +1646,7 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
                                     "stale state detected, triggering automatic full rebuild"
                                 );
                                 // Trigger full rebuild
-                                callback(vec![], &roots, true);
+                                callback(vec![], &roots, WatchTrigger::Rebuild);
                             }
                             StaleAction::None => {
                                 // Stale detection disabled, should not reach here
@@ -1438,21 +1662,27 @@ fn watch_sources<F: Fn(Vec<PathBuf>, &[(ConnectorKind, ScanRoot)], bool) + Send
 }
 
 fn reset_storage(storage: &mut SqliteStorage) -> Result<()> {
+    // `tags` and `session_tags` are user data and intentionally survive the reset;
+    // insert_conversation re-attaches them to the re-created conversation rows.
     // Wrap in transaction to ensure atomic reset - if any DELETE fails,
     // all changes are rolled back to prevent inconsistent state
     storage.raw().execute_batch(
         "BEGIN TRANSACTION;
          DELETE FROM fts_messages;
          DELETE FROM snippets;
+         DELETE FROM message_tools;
+         DELETE FROM tool_events;
          DELETE FROM messages;
          DELETE FROM conversations;
          DELETE FROM agents;
          DELETE FROM workspaces;
-         DELETE FROM tags;
          DELETE FROM conversation_tags;
+         DELETE FROM scan_cursors;
          DELETE FROM meta WHERE key = 'last_scan_ts';
          COMMIT;",
     )?;
+    // Message rowids start over, so cursors native sync clients hold are void.
+    storage.reset_sync_epoch()?;
     Ok(())
 }
 
@@ -1509,26 +1739,36 @@ fn reindex_paths(
         };
 
         // Use explicit root context
-        let ctx = crate::connectors::ScanContext::with_roots(
-            root.path.clone(),
-            vec![root.clone()],
-            since_ts,
-        );
+        let ctx = ScanContext::with_roots(root.path.clone(), vec![root.clone()], since_ts);
 
-        // SCAN PHASE: IO-heavy, no locks held
-        let mut convs = match conn.scan(&ctx) {
-            Ok(c) => c,
-            Err(e) => {
-                tracing::debug!(
-                    "watch scan failed for {:?} at {}: {}",
-                    kind,
-                    root.path.display(),
-                    e
-                );
-                Vec::new()
-            }
+        let cursors = {
+            let storage = storage
+                .lock()
+                .map_err(|_| anyhow::anyhow!("storage lock poisoned"))?;
+            load_scan_cursors(&storage, since_ts)
         };
 
+        // SCAN PHASE: IO-heavy, no locks held
+        let mut convs = Vec::new();
+        let mut new_cursors = Vec::new();
+        if let Err(e) = scan_incremental_batches(
+            &*conn,
+            &ctx,
+            &cursors,
+            usize::MAX,
+            |batch, batch_cursors| {
+                convs.extend(batch);
+                new_cursors.extend(batch_cursors);
+            },
+        ) {
+            tracing::debug!(
+                "watch scan failed for {:?} at {}: {}",
+                kind,
+                root.path.display(),
+                e
+            );
+        }
+
         // Provenance injection and path rewriting
         for conv in &mut convs {
             inject_provenance(conv, &root.origin);
@@ -1554,6 +1794,9 @@ fn reindex_paths(
                 .map_err(|_| anyhow::anyhow!("index lock poisoned"))?;
 
             ingest_batch(&mut storage, &mut t_index, &convs, &opts.progress, false)?;
+            if let Err(e) = storage.save_scan_cursors(&new_cursors) {
+                tracing::warn!(?kind, error = %e, "failed to save scan cursors");
+            }
 
             // Commit to Tantivy immediately to ensure index consistency before advancing watch state.
             t_index.commit()?;
@@ -1584,6 +1827,65 @@ fn reindex_paths(
     Ok(total_indexed)
 }
 
+/// Rescan, from scratch, the remote mirror roots that lie under (or contain)
+/// any of `paths`, returning the number of conversations indexed.
+///
+/// Synced files keep their remote mtimes, so an incremental scan could miss
+/// them; limiting the full scan to the affected mirrors keeps it cheap.
+fn rescan_remote_roots(
+    opts: &IndexOptions,
+    paths: &[PathBuf],
+    state: Arc<Mutex<HashMap<ConnectorKind, i64>>>,
+    storage: Arc<Mutex<SqliteStorage>>,
+    t_index: Arc<Mutex<TantivyIndex>>,
+) -> Result<usize> {
+    let remote_roots: Vec<ScanRoot> = {
+        let storage = storage
+            .lock()
+            .map_err(|_| anyhow::anyhow!("storage lock poisoned"))?;
+        build_scan_roots(&storage, &opts.data_dir)
+    }
+    .into_iter()
+    .filter(|root| {
+        root.origin.is_remote()
+            && paths
+                .iter()
+                .any(|path| root.path.starts_with(path) || path.starts_with(&root.path))
+    })
+    .collect();
+    if remote_roots.is_empty() {
+        return Ok(0);
+    }
+
+    let root_paths = remote_roots.iter().map(|root| root.path.clone()).collect();
+    let roots = remote_watch_roots(remote_roots);
+    reindex_paths(opts, root_paths, &roots, state, storage, t_index, true)
+}
+
+/// Reindex the remote mirror roots under `paths` without a full scan of
+/// every source, for callers outside watch mode such as `cass sources daemon`.
+///
+/// Requires an existing index; run `cass index` first.
+pub fn reindex_remote_roots(opts: &IndexOptions, paths: &[PathBuf]) -> Result<usize> {
+    let index_path = index_dir(&opts.data_dir)?;
+    if !opts.db_path.exists() || !index_path.join("meta.json").exists() {
+        return Err(anyhow::anyhow!(
+            "no index at {}; run 'cass index' first",
+            opts.data_dir.display()
+        ));
+    }
+    let storage = SqliteStorage::open(&opts.db_path)?;
+    sync_sources_config_to_db(&storage);
+    let t_index = TantivyIndex::open_or_create(&index_path)?;
+    rescan_remote_roots(
+        opts,
+        paths,
+        Arc::new(Mutex::new(load_watch_state(&opts.data_dir))),
+        Arc::new(Mutex::new(storage)),
+        Arc::new(Mutex::new(t_index)),
+    )
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum ConnectorKind {
     #[serde(rename = "cx", alias = "Codex")]
@@ -1612,6 +1914,18 @@ enum ConnectorKind {
     PiAgent,
     #[serde(rename = "fa", alias = "Factory")]
     Factory,
+    #[serde(rename = "ct", alias = "Continue")]
+    Continue,
+    #[serde(rename = "ws", alias = "Windsurf")]
+    Windsurf,
+    #[serde(rename = "cp", alias = "Copilot")]
+    Copilot,
+    #[serde(rename = "rc", alias = "RooCode")]
+    RooCode,
+    #[serde(rename = "ex", alias = "External")]
+    External,
+    #[serde(rename = "nt", alias = "Native")]
+    Native,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
@@ -1824,6 +2138,19 @@ pub fn build_scan_roots(storage: &SqliteStorage, data_dir: &Path) -> Vec<ScanRoo
                         }
                     }
                 }
+
+                // Conversations pulled by native sync, kept even if the source
+                // has since switched back to mirroring files.
+                let spool = data_dir
+                    .join("remotes")
+                    .join(&source.name)
+                    .join("mirror")
+                    .join(crate::sources::native::SPOOL_DIR);
+                if spool.is_dir() {
+                    let mut scan_root = ScanRoot::remote(spool, origin, platform);
+                    scan_root.workspace_rewrites = workspace_rewrites;
+                    roots.push(scan_root);
+                }
             }
             return roots;
         }
@@ -2100,6 +2427,43 @@ pub mod persist {
         }
     }
 
+    /// Header-only copy of `conv` (no messages) with user tags injected into
+    /// `metadata.cass.tags`, for passing to `TantivyIndex::add_messages`.
+    ///
+    /// Returns `None` when there are no tags, so untagged sessions avoid the clone.
+    pub fn with_session_tags(
+        conv: &NormalizedConversation,
+        tags: &[String],
+    ) -> Option<NormalizedConversation> {
+        if tags.is_empty() {
+            return None;
+        }
+        let mut metadata = conv.metadata.clone();
+        if !metadata.is_object() {
+            metadata = serde_json::json!({});
+        }
+        let cass = metadata
+            .as_object_mut()?
+            .entry("cass")
+            .or_insert_with(|| serde_json::json!({}));
+        if !cass.is_object() {
+            *cass = serde_json::json!({});
+        }
+        cass.as_object_mut()?
+            .insert("tags".into(), serde_json::json!(tags));
+        Some(NormalizedConversation {
+            agent_slug: conv.agent_slug.clone(),
+            external_id: conv.external_id.clone(),
+            title: conv.title.clone(),
+            workspace: conv.workspace.clone(),
+            source_path: conv.source_path.clone(),
+            started_at: conv.started_at,
+            ended_at: conv.ended_at,
+            metadata,
+            messages: Vec::new(),
+        })
+    }
+
     pub fn persist_conversation(
         storage: &mut SqliteStorage,
         t_index: &mut TantivyIndex,
@@ -2136,7 +2500,9 @@ pub mod persist {
                 .filter(|m| inserted_indices.contains(&m.idx))
                 .cloned()
                 .collect();
-            t_index.add_messages(conv, &new_msgs)?;
+            let tags = storage.session_tags(&internal_conv.source_path.to_string_lossy())?;
+            let tagged = with_session_tags(conv, &tags);
+            t_index.add_messages(tagged.as_ref().unwrap_or(conv), &new_msgs)?;
         }
         Ok(())
     }
@@ -2211,11 +2577,23 @@ pub mod persist {
         // Execute batched insert (single transaction)
         let outcomes = storage.insert_conversations_batched(&refs)?;
 
+        // User tags live in SQLite only; carry them into Tantivy for `tag:` filters.
+        let tag_map = storage.session_tag_map()?;
+
         // Add newly inserted messages to Tantivy index
-        for (conv, outcome) in convs.iter().zip(outcomes.iter()) {
+        for ((conv, outcome), (_, _, internal)) in
+            convs.iter().zip(outcomes.iter()).zip(prepared.iter())
+        {
+            let tagged = tag_map
+                .get(&(
+                    internal.source_id.clone(),
+                    internal.source_path.to_string_lossy().into_owned(),
+                ))
+                .and_then(|tags| with_session_tags(conv, tags));
+            let header = tagged.as_ref().unwrap_or(conv);
             if force_tantivy_reindex {
                 // Rebuild path: the Tantivy index is known-empty, so index all messages.
-                t_index.add_messages(conv, &conv.messages)?;
+                t_index.add_messages(header, &conv.messages)?;
             } else if !outcome.inserted_indices.is_empty() {
                 let new_msgs: Vec<_> = conv
                     .messages
@@ -2223,7 +2601,7 @@ pub mod persist {
                     .filter(|m| outcome.inserted_indices.contains(&m.idx))
                     .cloned()
                     .collect();
-                t_index.add_messages(conv, &new_msgs)?;
+                t_index.add_messages(header, &new_msgs)?;
             }
         }
 
@@ -2366,6 +2744,8 @@ mod tests {
             .query_row("SELECT COUNT(*) FROM messages", [], |r| r.get(0))
             .unwrap();
         assert_eq!(msg_count, 1);
+        let epoch = storage.sync_epoch().unwrap();
+        assert!(epoch.is_some());
 
         reset_storage(&mut storage).unwrap();
 
@@ -2378,6 +2758,10 @@ mod tests {
             storage.schema_version().unwrap(),
             crate::storage::sqlite::CURRENT_SCHEMA_VERSION
         );
+        // Rowids restart, so native sync clients must see a new epoch.
+        let new_epoch = storage.sync_epoch().unwrap();
+        assert!(new_epoch.is_some());
+        assert_ne!(new_epoch, epoch);
     }
 
     #[test]
@@ -2567,6 +2951,7 @@ mod tests {
             semantic: false,
             build_hnsw: false,
             embedder: "fastembed".to_string(),
+            quantization: Quantization::F32,
             progress: None,
             watch_once_paths: None,
         };
@@ -2684,6 +3069,7 @@ CREATE VIRTUAL TABLE fts_messages USING fts5(
             semantic: false,
             build_hnsw: false,
             embedder: "fastembed".to_string(),
+            quantization: Quantization::F32,
             progress: Some(progress.clone()),
         };
 
@@ -1,17 +1,26 @@
+use std::collections::{HashMap, HashSet};
 use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use anyhow::{Result, bail};
 use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 
 use crate::search::ann_index::{DEFAULT_EF_CONSTRUCTION, DEFAULT_M, HnswIndex, hnsw_index_path};
-use crate::search::canonicalize::{canonicalize_for_embedding, content_hash};
+use crate::search::canonicalize::{canonicalize_for_embedding_chunks, content_hash};
 use crate::search::embedder::Embedder;
 use crate::search::fastembed_embedder::FastEmbedder;
 use crate::search::hash_embedder::HashEmbedder;
 use crate::search::vector_index::{
     Quantization, ROLE_USER, VectorEntry, VectorIndex, vector_index_path,
 };
+use crate::search::vector_segments::{
+    CompactionOutcome, append_segment, clear_segments, compact, load_segmented,
+};
+
+/// Serializes index updates and compaction, which may run on a background
+/// thread in watch mode, so HNSW graph ids never race a renumbering.
+static INDEX_UPDATE_LOCK: Mutex<()> = Mutex::new(());
 
 #[derive(Debug, Clone)]
 pub struct EmbeddingInput {
@@ -69,9 +78,23 @@ impl EmbeddedMessage {
     }
 }
 
+/// What [`SemanticIndexer::update_index`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SemanticUpdate {
+    /// Messages that were (re-)embedded.
+    pub embedded_messages: usize,
+    /// Messages whose earlier rows were tombstoned (edited or deleted).
+    pub tombstoned_messages: usize,
+    /// Whether the index was rebuilt from scratch instead of appended to.
+    pub rebuilt: bool,
+    /// Whether the delta segments should now be compacted.
+    pub needs_compaction: bool,
+}
+
 pub struct SemanticIndexer {
     embedder: Box<dyn Embedder>,
     batch_size: usize,
+    quantization: Quantization,
 }
 
 impl SemanticIndexer {
@@ -93,6 +116,7 @@ impl SemanticIndexer {
         Ok(Self {
             embedder,
             batch_size: 32,
+            quantization: Quantization::F32,
         })
     }
 
@@ -108,6 +132,16 @@ impl SemanticIndexer {
         self.batch_size
     }
 
+    /// Store vectors as `quantization`; int8 and binary also get an f16 rescoring companion.
+    pub fn with_quantization(mut self, quantization: Quantization) -> Self {
+        self.quantization = quantization;
+        self
+    }
+
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
     pub fn embedder_id(&self) -> &str {
         self.embedder.id()
     }
@@ -134,8 +168,10 @@ impl SemanticIndexer {
 
         struct Prepared<'a> {
             msg: &'a EmbeddingInput,
+            chunk_idx: u8,
             canonical: String,
             hash: [u8; 32],
+            last_chunk: bool,
         }
 
         let mut embeddings = Vec::with_capacity(messages.len());
@@ -178,33 +214,40 @@ impl SemanticIndexer {
                     workspace_id: prepared.msg.workspace_id,
                     source_id: prepared.msg.source_id,
                     role: prepared.msg.role,
-                    chunk_idx: prepared.msg.chunk_idx,
+                    chunk_idx: prepared.chunk_idx,
                     content_hash: prepared.hash,
                     embedding: vector,
                 });
             }
 
-            pb.inc(batch.len() as u64);
+            pb.inc(batch.iter().filter(|p| p.last_chunk).count() as u64);
             batch.clear();
             Ok(())
         };
 
         for msg in messages {
-            let canonical = canonicalize_for_embedding(&msg.content);
-            if canonical.is_empty() {
+            // Long messages become several overlapping chunks, numbered from
+            // the input's chunk_idx; search collapses them back by message_id.
+            let chunks = canonicalize_for_embedding_chunks(&msg.content);
+            if chunks.is_empty() {
                 pb.inc(1);
                 continue;
             }
 
-            let hash = content_hash(&canonical);
-            batch.push(Prepared {
-                msg,
-                canonical,
-                hash,
-            });
+            let chunk_count = chunks.len();
+            for (offset, canonical) in chunks.into_iter().enumerate() {
+                let hash = content_hash(&canonical);
+                batch.push(Prepared {
+                    msg,
+                    chunk_idx: msg.chunk_idx.saturating_add(offset as u8),
+                    canonical,
+                    hash,
+                    last_chunk: offset + 1 == chunk_count,
+                });
 
-            if batch.len() >= self.batch_size {
-                flush_batch(&mut batch, &mut embeddings, &pb, self.embedder.as_ref())?;
+                if batch.len() >= self.batch_size {
+                    flush_batch(&mut batch, &mut embeddings, &pb, self.embedder.as_ref())?;
+                }
             }
         }
 
@@ -224,15 +267,189 @@ impl SemanticIndexer {
             .into_iter()
             .map(|embedded| embedded.into_vector_entry());
 
-        VectorIndex::build(
+        VectorIndex::build_with_rescore(
             self.embedder_id(),
             "1.0",
             self.embedder_dimension(),
-            Quantization::F32,
+            self.quantization,
             entries,
         )
     }
 
+    /// Bring the on-disk index in line with `messages` (every message to index).
+    ///
+    /// Without a usable index, or with `rebuild`, everything is embedded into a
+    /// new base. Otherwise only new and edited messages are embedded; they and
+    /// tombstones for edited or deleted messages go into an appended delta
+    /// segment, and the HNSW graph (when `build_hnsw` or one already exists)
+    /// takes the new rows as inserts.
+    pub fn update_index(
+        &self,
+        messages: &[EmbeddingInput],
+        data_dir: &Path,
+        build_hnsw: bool,
+        rebuild: bool,
+    ) -> Result<SemanticUpdate> {
+        let _guard = INDEX_UPDATE_LOCK
+            .lock()
+            .map_err(|_| anyhow::anyhow!("semantic index lock poisoned"))?;
+
+        let index_path = vector_index_path(data_dir, self.embedder_id());
+        let existing = if rebuild || !index_path.is_file() {
+            None
+        } else {
+            match load_segmented(&index_path) {
+                Ok(index) if self.matches_index(&index) => Some(index),
+                Ok(index) => {
+                    tracing::info!(
+                        quantization = index.header().quantization.as_str(),
+                        requested = self.quantization.as_str(),
+                        "vector index format changed; rebuilding"
+                    );
+                    None
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, "failed to load vector index; rebuilding");
+                    None
+                }
+            }
+        };
+
+        let Some(existing) = existing else {
+            let embedded = self.embed_messages(messages)?;
+            if embedded.is_empty() {
+                return Ok(SemanticUpdate::default());
+            }
+            let embedded_messages = distinct_messages(&embedded);
+            let index = self.build_index(embedded)?;
+            self.save_index(&index, data_dir)?;
+            let hnsw_path = hnsw_index_path(data_dir, self.embedder_id());
+            if build_hnsw {
+                self.build_hnsw_index(&index, data_dir, None, None)?;
+            } else if hnsw_path.is_file() {
+                // Graph ids refer to the old rows; incremental inserts would corrupt it.
+                std::fs::remove_file(&hnsw_path)?;
+            }
+            return Ok(SemanticUpdate {
+                embedded_messages,
+                rebuilt: true,
+                ..SemanticUpdate::default()
+            });
+        };
+
+        // Every chunk's hash, so an edit past the first chunk is still seen.
+        let mut known: HashMap<u64, Vec<(u8, [u8; 32])>> = HashMap::new();
+        for row in existing.rows() {
+            known
+                .entry(row.message_id)
+                .or_default()
+                .push((row.chunk_idx, row.content_hash));
+        }
+        drop(existing);
+        for hashes in known.values_mut() {
+            hashes.sort_unstable_by_key(|(chunk_idx, _)| *chunk_idx);
+        }
+
+        let mut pending = Vec::new();
+        let mut tombstones = Vec::new();
+        let mut seen = HashSet::with_capacity(messages.len());
+        for msg in messages {
+            seen.insert(msg.message_id);
+            match known.get(&msg.message_id) {
+                None => pending.push(msg.clone()),
+                Some(hashes) => {
+                    let current = canonicalize_for_embedding_chunks(&msg.content);
+                    let unchanged = current.len() == hashes.len()
+                        && current
+                            .iter()
+                            .zip(hashes)
+                            .all(|(chunk, (_, hash))| content_hash(chunk) == *hash);
+                    if !unchanged {
+                        tombstones.push(msg.message_id);
+                        pending.push(msg.clone());
+                    }
+                }
+            }
+        }
+        let mut deleted: Vec<u64> = known
+            .keys()
+            .filter(|id| !seen.contains(id))
+            .copied()
+            .collect();
+        deleted.sort_unstable();
+        tombstones.extend(deleted);
+
+        let hnsw_path = hnsw_index_path(data_dir, self.embedder_id());
+        let embedded = self.embed_messages(&pending)?;
+        if embedded.is_empty() && tombstones.is_empty() {
+            if build_hnsw && !hnsw_path.is_file() {
+                self.rebuild_hnsw_index(&index_path, data_dir)?;
+            }
+            return Ok(SemanticUpdate::default());
+        }
+
+        let embedded_messages = distinct_messages(&embedded);
+        let tombstoned_messages = tombstones.len();
+        let delta = self.build_index(embedded)?;
+        let append = append_segment(&index_path, &delta, tombstones)?;
+        tracing::info!(
+            embedded_messages,
+            tombstoned_messages,
+            first_graph_id = append.first_graph_id,
+            "appended vector index segment"
+        );
+
+        let mut needs_compaction = append.needs_compaction;
+        if build_hnsw || hnsw_path.is_file() {
+            let inserted = match HnswIndex::load(&hnsw_path) {
+                Ok(mut hnsw) if hnsw.len() == append.first_graph_id => {
+                    hnsw.insert_segment(&delta, append.first_graph_id)?;
+                    hnsw.save(&hnsw_path)?;
+                    true
+                }
+                Ok(hnsw) => {
+                    tracing::info!(
+                        nodes = hnsw.len(),
+                        expected = append.first_graph_id,
+                        "HNSW index out of step with vector segments; rebuilding"
+                    );
+                    false
+                }
+                Err(err) => {
+                    if hnsw_path.is_file() {
+                        tracing::warn!(error = %err, "failed to load HNSW index; rebuilding");
+                    }
+                    false
+                }
+            };
+            if !inserted {
+                self.rebuild_hnsw_index(&index_path, data_dir)?;
+                needs_compaction = false;
+            }
+        }
+
+        Ok(SemanticUpdate {
+            embedded_messages,
+            tombstoned_messages,
+            rebuilt: false,
+            needs_compaction,
+        })
+    }
+
+    /// Build a fresh HNSW graph; deltas are folded first so graph ids are contiguous.
+    fn rebuild_hnsw_index(&self, index_path: &Path, data_dir: &Path) -> Result<PathBuf> {
+        compact(index_path)?;
+        let index = VectorIndex::load(index_path)?;
+        self.build_hnsw_index(&index, data_dir, None, None)
+    }
+
+    fn matches_index(&self, index: &VectorIndex) -> bool {
+        let header = index.header();
+        header.embedder_id == self.embedder_id()
+            && header.dimension as usize == self.embedder_dimension()
+            && header.quantization == self.quantization
+    }
+
     pub fn save_index(&self, index: &VectorIndex, data_dir: &Path) -> Result<PathBuf> {
         let header = index.header();
         if header.embedder_id != self.embedder_id() {
@@ -255,6 +472,8 @@ impl SemanticIndexer {
             std::fs::create_dir_all(parent)?;
         }
         index.save(&index_path)?;
+        // The new base supersedes every delta written against the old one.
+        clear_segments(&index_path)?;
         Ok(index_path)
     }
 
@@ -302,9 +521,41 @@ impl SemanticIndexer {
     }
 }
 
+/// Fold the delta segments of `embedder_id`'s index into a new base.
+///
+/// When tombstoned rows were dropped the graph ids change, so an existing
+/// HNSW index is rebuilt. Returns `None` when there were no deltas.
+pub fn compact_index(data_dir: &Path, embedder_id: &str) -> Result<Option<CompactionOutcome>> {
+    let _guard = INDEX_UPDATE_LOCK
+        .lock()
+        .map_err(|_| anyhow::anyhow!("semantic index lock poisoned"))?;
+
+    let index_path = vector_index_path(data_dir, embedder_id);
+    let outcome = compact(&index_path)?;
+    let hnsw_path = hnsw_index_path(data_dir, embedder_id);
+    if let Some(outcome) = outcome
+        && outcome.dropped_rows > 0
+        && hnsw_path.is_file()
+    {
+        let index = VectorIndex::load(&index_path)?;
+        let hnsw = HnswIndex::build_from_vector_index(&index, DEFAULT_M, DEFAULT_EF_CONSTRUCTION)?;
+        hnsw.save(&hnsw_path)?;
+    }
+    Ok(outcome)
+}
+
+fn distinct_messages(embedded: &[EmbeddedMessage]) -> usize {
+    embedded
+        .iter()
+        .map(|e| e.message_id)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::search::vector_index::rescore_index_path;
     use tempfile::tempdir;
 
     #[test]
@@ -334,6 +585,38 @@ mod tests {
         assert_eq!(embeddings.len(), messages.len());
     }
 
+    #[test]
+    fn test_long_message_embedded_as_chunks() {
+        let indexer = SemanticIndexer::new("hash", None).unwrap();
+        let long: String = (0..1200)
+            .map(|i| format!("alpha{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let messages = vec![
+            EmbeddingInput::new(1, format!("{long} zebra giraffe tail marker")),
+            EmbeddingInput::new(2, "Goodbye world"),
+        ];
+
+        let embeddings = indexer.embed_messages(&messages).unwrap();
+        let chunk_ids: Vec<u8> = embeddings
+            .iter()
+            .filter(|e| e.message_id == 1)
+            .map(|e| e.chunk_idx)
+            .collect();
+        assert!(chunk_ids.len() > 1);
+        assert_eq!(chunk_ids, (0..chunk_ids.len() as u8).collect::<Vec<_>>());
+        assert_eq!(embeddings.last().unwrap().message_id, 2);
+        assert_eq!(embeddings.last().unwrap().chunk_idx, 0);
+
+        // Rows for one message collapse to a single hit scored by its best chunk.
+        let index = indexer.build_index(embeddings).unwrap();
+        let query = indexer.embedder.embed("zebra giraffe tail marker").unwrap();
+        let hits = index.search_top_k_collapsed(&query, 10, None).unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].message_id, 1);
+        assert_eq!(hits[0].chunk_idx as usize, chunk_ids.len() - 1);
+    }
+
     #[test]
     fn test_build_and_save_index() {
         let indexer = SemanticIndexer::new("hash", None).unwrap();
@@ -356,4 +639,100 @@ mod tests {
             indexer.embedder_dimension() as u32
         );
     }
+
+    #[test]
+    fn test_quantized_index_saves_rescore_companion() {
+        let indexer = SemanticIndexer::new("hash", None)
+            .unwrap()
+            .with_quantization(Quantization::Int8);
+        let messages = vec![
+            EmbeddingInput::new(1, "Hello world"),
+            EmbeddingInput::new(2, "Goodbye world"),
+        ];
+
+        let embeddings = indexer.embed_messages(&messages).unwrap();
+        let index = indexer.build_index(embeddings).unwrap();
+
+        let tmp = tempdir().unwrap();
+        let path = indexer.save_index(&index, tmp.path()).unwrap();
+        assert!(rescore_index_path(&path).is_file());
+
+        let loaded = VectorIndex::load(&path).unwrap();
+        assert_eq!(loaded.header().quantization, Quantization::Int8);
+        assert!(loaded.rescore_index().is_some());
+    }
+
+    #[test]
+    fn test_update_index_appends_delta_segments() {
+        let indexer = SemanticIndexer::new("hash", None).unwrap();
+        let tmp = tempdir().unwrap();
+        let mut messages = vec![
+            EmbeddingInput::new(1, "Hello world"),
+            EmbeddingInput::new(2, "Goodbye world"),
+        ];
+
+        let first = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert!(first.rebuilt);
+        assert_eq!(first.embedded_messages, 2);
+
+        // Nothing changed: nothing is embedded or appended.
+        let unchanged = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert_eq!(unchanged, SemanticUpdate::default());
+
+        // One new message, one edited, one deleted.
+        messages[0] = EmbeddingInput::new(1, "Hello brave new world");
+        messages.remove(1);
+        messages.push(EmbeddingInput::new(3, "Kubernetes deployment rollout"));
+        let update = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert!(!update.rebuilt);
+        assert_eq!(update.embedded_messages, 2);
+        assert_eq!(update.tombstoned_messages, 2);
+
+        let path = vector_index_path(tmp.path(), indexer.embedder_id());
+        let merged = load_segmented(&path).unwrap();
+        let mut ids: Vec<u64> = merged.rows().iter().map(|r| r.message_id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, vec![1, 3]);
+
+        let outcome = compact_index(tmp.path(), indexer.embedder_id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(outcome.rows, 2);
+        assert_eq!(outcome.dropped_rows, 2);
+    }
+
+    #[test]
+    fn test_update_index_reembeds_edits_past_first_chunk() {
+        let indexer = SemanticIndexer::new("hash", None).unwrap();
+        let tmp = tempdir().unwrap();
+        let long: String = (0..1200)
+            .map(|i| format!("alpha{i}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut messages = vec![EmbeddingInput::new(1, format!("{long} original ending"))];
+
+        let first = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert!(first.rebuilt);
+
+        let unchanged = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert_eq!(unchanged, SemanticUpdate::default());
+
+        // Only the tail changes; the first chunk hashes the same as before.
+        messages[0] = EmbeddingInput::new(1, format!("{long} rewritten ending"));
+        let update = indexer
+            .update_index(&messages, tmp.path(), false, false)
+            .unwrap();
+        assert_eq!(update.embedded_messages, 1);
+        assert_eq!(update.tombstoned_messages, 1);
+    }
 }
@@ -2,13 +2,17 @@ pub mod bakeoff;
 pub mod bookmarks;
 pub mod connectors;
 pub mod daemon;
+pub mod diff;
 pub mod encryption;
 pub mod export;
 pub mod html_export;
 pub mod indexer;
+pub mod mcp;
 pub mod model;
 pub mod pages;
+pub mod saved_searches;
 pub mod search;
+pub mod serve;
 pub mod sources;
 pub mod storage;
 pub mod ui;
@@ -16,10 +20,12 @@ pub mod update_check;
 
 use anyhow::Result;
 use base64::prelude::*;
+use bookmarks::BookmarkStatus;
 use chrono::Utc;
 use clap::{Arg, ArgAction, Command, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint};
 use indexer::IndexOptions;
 use reqwest::Client;
+use search::query::SearchGranularity;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
@@ -105,6 +111,10 @@ pub enum Commands {
         #[arg(long, default_value_t = false)]
         reset_state: bool,
 
+        /// Print the effective key bindings (defaults plus `tui.toml` overrides) and exit
+        #[arg(long, default_value_t = false)]
+        print_keymap: bool,
+
         /// Override data dir (matches index --data-dir)
         #[arg(long)]
         data_dir: Option<PathBuf>,
@@ -140,6 +150,11 @@ pub enum Commands {
         #[arg(long, default_value = "fastembed")]
         embedder: String,
 
+        /// Vector storage for the semantic index (requires --semantic).
+        /// int8 and binary shrink the scanned vectors and keep an f16 file for rescoring.
+        #[arg(long, value_enum, default_value_t = IndexQuantization::F32)]
+        quantization: IndexQuantization,
+
         /// Override data dir (index + db). Defaults to platform data dir.
         #[arg(long)]
         data_dir: Option<PathBuf>,
@@ -176,6 +191,22 @@ pub enum Commands {
         /// Filter by workspace path (can be specified multiple times)
         #[arg(long)]
         workspace: Vec<String>,
+        /// Filter by message role: user, agent (alias: assistant), tool, system.
+        /// Can be repeated; also available inline as `role:user`.
+        #[arg(long)]
+        role: Vec<String>,
+        /// Filter to messages that invoked a tool (e.g. Bash, Edit; case-insensitive).
+        /// Can be repeated; also available inline as `tool:Bash`.
+        #[arg(long)]
+        tool: Vec<String>,
+        /// Filter to sessions carrying a user tag (see `cass tag add`).
+        /// Can be repeated; also available inline as `tag:NAME`.
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Filter to messages whose tool calls read or edited a file (path as
+        /// recorded or workspace-relative). Can be repeated; also inline as `file:PATH`.
+        #[arg(long)]
+        file: Vec<String>,
         /// Max results
         #[arg(long, default_value_t = 10)]
         limit: usize,
@@ -258,6 +289,13 @@ pub enum Commands {
         /// Search mode: lexical (default), semantic, or hybrid
         #[arg(long, value_enum)]
         mode: Option<crate::search::query::SearchMode>,
+        /// Result granularity: message (one hit per message, default) or session
+        /// (one hit per conversation, ranked by combined match strength and recency)
+        #[arg(long, value_enum, default_value_t = SearchGranularity::Message)]
+        granularity: SearchGranularity,
+        /// Evidence messages to include per session with `--granularity session`
+        #[arg(long, default_value_t = crate::search::query::DEFAULT_SESSION_EVIDENCE)]
+        evidence: usize,
 
         /// Use approximate nearest neighbor (ANN) search with HNSW for faster semantic/hybrid queries.
         /// Trades slight accuracy loss for O(log n) search complexity instead of O(n).
@@ -285,12 +323,13 @@ pub enum Commands {
         #[arg(long)]
         reranker: Option<String>,
 
-        /// Use daemon for warm model inference (faster repeated queries).
-        /// If daemon is unavailable, falls back to direct inference.
+        /// Use daemon for warm model inference and index reads (faster repeated queries).
+        /// Spawns the daemon if it is not running; falls back to direct search on failure.
+        /// Without this flag, an already-running daemon is still used when reachable.
         #[arg(long, default_value_t = false)]
         daemon: bool,
 
-        /// Disable daemon usage even if available (force direct inference).
+        /// Disable daemon usage even if available (force in-process search and inference).
         #[arg(long, default_value_t = false)]
         no_daemon: bool,
 
@@ -389,6 +428,36 @@ pub enum Commands {
         #[arg(long, visible_alias = "robot")]
         json: bool,
     },
+    /// Serve search, view, expand, context, timeline and stats as MCP tools over stdio
+    Mcp {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Serve search, view, expand, timeline, stats, health, sources and related as a local HTTP/JSON API
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = crate::serve::DEFAULT_BIND)]
+        bind: String,
+        /// Require `Authorization: Bearer <TOKEN>` on every request (mandatory off loopback)
+        #[arg(long, env = "CASS_SERVE_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+        /// Allow browser requests from this origin (repeatable; `*` allows any)
+        #[arg(long = "cors-origin", value_name = "ORIGIN")]
+        cors_origin: Vec<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Run the warm model + search daemon (spawned automatically by `search --daemon`)
+    Daemon {
+        /// Unix socket path (default: $CASS_DAEMON_SOCKET or /tmp/semantic-daemon-$USER.sock)
+        #[arg(long)]
+        socket: Option<PathBuf>,
+        /// Override data dir (models for embedding and reranking)
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
     /// View a source file at a specific line (follow up on search results)
     View {
         /// Path to the source file
@@ -451,6 +520,30 @@ pub enum Commands {
         #[arg(long, default_value_t = 5)]
         limit: usize,
     },
+    /// Find sessions related to a source file: edits, reads, snippets, symbol mentions and similar code
+    Related {
+        /// File to look up (absolute, or a trailing part like `src/lib.rs`)
+        #[arg(long)]
+        file: String,
+        /// Line of interest: boosts snippets covering it and seeds semantic matching
+        #[arg(long)]
+        line: Option<usize>,
+        /// Function, type or other identifier to match lexically
+        #[arg(long)]
+        symbol: Option<String>,
+        /// Maximum sessions (default: 10)
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Skip semantic similarity even when a vector index is available
+        #[arg(long)]
+        no_semantic: bool,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
     /// Export a conversation to markdown or other formats
     Export {
         /// Path to session file
@@ -465,16 +558,128 @@ pub enum Commands {
         #[arg(long)]
         include_tools: bool,
     },
+    /// Compare two sessions: alignment, divergence point, files, tokens, duration and tool calls
+    Diff {
+        /// Path to the first session file (source_path from search results)
+        a: PathBuf,
+        /// Path to the second session file
+        b: PathBuf,
+        /// Output format (default: tui on a terminal, markdown otherwise)
+        #[arg(long, value_enum)]
+        format: Option<DiffFormat>,
+        /// Output file for markdown/json (stdout if not specified)
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (same as --format json)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Files read or edited by agents' tool calls (`--touched PATH` lists the sessions)
+    Files {
+        /// Sessions that read or edited this file (absolute, or a trailing part like `src/lib.rs`)
+        #[arg(long)]
+        touched: Option<String>,
+        /// Only count edits, not reads
+        #[arg(long)]
+        edited: bool,
+        /// Filter by agent slug
+        #[arg(long)]
+        agent: Option<String>,
+        /// Filter by workspace path (substring)
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Only events from the last N days
+        #[arg(long)]
+        days: Option<u32>,
+        /// Max files (or sessions with --touched)
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Shell commands run by agents' tool calls, newest first
+    #[command(name = "commands")]
+    CommandLog {
+        /// Only commands that failed (non-zero exit code or tool error)
+        #[arg(long)]
+        failed: bool,
+        /// Only commands containing this text (case-insensitive)
+        #[arg(long)]
+        grep: Option<String>,
+        /// Filter by agent slug
+        #[arg(long)]
+        agent: Option<String>,
+        /// Filter by workspace path (substring)
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Only commands from the last N days
+        #[arg(long)]
+        days: Option<u32>,
+        /// Max commands
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
     /// Export session as beautiful, self-contained HTML (with optional encryption)
     #[command(name = "export-html")]
     ExportHtml {
-        /// Path to session file
-        session: PathBuf,
+        /// Path to session file (omit when selecting several sessions with
+        /// --query/--workspace/--tag/--agent/--since/--until)
+        #[arg(required_unless_present_any = ["query", "workspace", "tag", "agent", "since", "until"])]
+        session: Option<PathBuf>,
 
         /// Output directory (default: current directory)
         #[arg(long)]
         output_dir: Option<PathBuf>,
 
+        /// Export every session matching this search query into a folder with an index page
+        #[arg(long, conflicts_with = "session")]
+        query: Option<String>,
+
+        /// Select sessions from this workspace (repeatable)
+        #[arg(long, conflicts_with = "session")]
+        workspace: Vec<String>,
+
+        /// Select sessions carrying this tag (repeatable, matches any)
+        #[arg(long, conflicts_with = "session")]
+        tag: Vec<String>,
+
+        /// Select sessions from this agent (repeatable)
+        #[arg(long, conflicts_with = "session")]
+        agent: Vec<String>,
+
+        /// Select sessions started at or after this time (ISO date or relative, e.g. 7d)
+        #[arg(long, conflicts_with = "session")]
+        since: Option<String>,
+
+        /// Select sessions started at or before this time
+        #[arg(long, conflicts_with = "session")]
+        until: Option<String>,
+
+        /// Maximum number of sessions in a multi-session export
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+
+        /// Index page title for a multi-session export
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Override data dir (multi-session export)
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+
         /// Custom filename (default: auto-generated from session metadata)
         #[arg(long)]
         filename: Option<String>,
@@ -574,6 +779,14 @@ pub enum Commands {
         #[arg(long)]
         verify: Option<PathBuf>,
 
+        /// Append new conversations and new messages of archived ones to an existing archive instead of re-exporting it
+        #[arg(long, value_name = "DIR")]
+        update: Option<PathBuf>,
+
+        /// Archive password for --update (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+
         /// Filter by agent (comma-separated)
         #[arg(long, value_delimiter = ',')]
         agents: Option<Vec<String>>,
@@ -677,7 +890,19 @@ pub enum Commands {
         /// Show example config file
         #[arg(long)]
         example_config: bool,
+
+        #[command(subcommand)]
+        command: Option<PagesCommand>,
     },
+    /// Manage user tags on sessions (kept across `cass index --full`)
+    #[command(subcommand)]
+    Tag(TagCommand),
+    /// Manage bookmarked search results (re-resolved after reindex)
+    #[command(subcommand)]
+    Bookmarks(BookmarksCommand),
+    /// Manage named saved searches (`cass saved run NAME`; alerts via `cass index --watch`)
+    #[command(subcommand)]
+    Saved(SavedCommand),
     /// Manage remote sources (P5.x)
     #[command(subcommand)]
     Sources(SourcesCommand),
@@ -689,6 +914,254 @@ pub enum Commands {
     Import(ImportCommand),
 }
 
+/// Subcommands for saved searches
+#[derive(Subcommand, Debug, Clone)]
+pub enum SavedCommand {
+    /// Save (or replace) a named search
+    Add {
+        /// Name of the saved search (letters, digits, `-`, `_`, `.`)
+        name: String,
+        /// The query string (inline filters such as `role:user` are kept)
+        query: String,
+        /// Filter by agent slug (can be specified multiple times)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Filter by workspace path (can be specified multiple times)
+        #[arg(long)]
+        workspace: Vec<String>,
+        /// Filter by message role (can be specified multiple times)
+        #[arg(long)]
+        role: Vec<String>,
+        /// Filter to messages that invoked a tool (can be specified multiple times)
+        #[arg(long)]
+        tool: Vec<String>,
+        /// Filter to sessions carrying a user tag (can be specified multiple times)
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Filter by source: 'local', 'remote', 'all', or a specific source ID
+        #[arg(long)]
+        source: Option<String>,
+        /// Only match messages from the last N days (relative to each run)
+        #[arg(long)]
+        days: Option<u32>,
+        /// Search mode: lexical (default), semantic, or hybrid
+        #[arg(long, value_enum)]
+        mode: Option<crate::search::query::SearchMode>,
+        /// Max results per run
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Append new matches as JSON lines to FILE when `cass index --watch` ingests data
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        alert_jsonl: Option<PathBuf>,
+        /// Shell command run with new matches (JSON lines) on stdin during `cass index --watch`
+        #[arg(long)]
+        hook: Option<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// List saved searches
+    List {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Delete a saved search
+    Remove {
+        /// Name of the saved search
+        name: String,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Run a saved search and record its `created_at` high-water mark
+    Run {
+        /// Name of the saved search
+        name: String,
+        /// Only results created after the previous run's high-water mark
+        #[arg(long)]
+        new: bool,
+        /// Override the saved result limit
+        #[arg(long)]
+        limit: Option<usize>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+}
+
+/// Subcommands for tagging sessions
+#[derive(Subcommand, Debug, Clone)]
+pub enum TagCommand {
+    /// Add tags to a session (searchable with `tag:NAME` or `--tag NAME`)
+    Add {
+        /// Path to the session file (source_path from search results)
+        session: PathBuf,
+        /// Tags to add (letters, digits, `-`, `_`, `.`, `/`; case-insensitive)
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Remove tags from a session
+    Remove {
+        /// Path to the session file
+        session: PathBuf,
+        /// Tags to remove
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// List tags on a session, or all tags with session counts
+    List {
+        /// Path to the session file (omit to list every tag)
+        session: Option<PathBuf>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+}
+
+/// Subcommands for bookmarks
+#[derive(Subcommand, Debug, Clone)]
+pub enum BookmarksCommand {
+    /// List bookmarks (newest first)
+    List {
+        /// Only bookmarks carrying this tag
+        #[arg(long)]
+        tag: Option<String>,
+        /// Only bookmarks whose message no longer exists in the index
+        #[arg(long)]
+        stale: bool,
+        /// Max bookmarks to return (0 = all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+    /// Bookmark a session, or one message of it with `--line`
+    Add {
+        /// Path to the session file (source_path from search results)
+        source_path: PathBuf,
+        /// Line number from search results (`line_number`)
+        #[arg(long, short = 'n')]
+        line: Option<usize>,
+        /// Bookmark title (defaults to the session title)
+        #[arg(long)]
+        title: Option<String>,
+        /// Free-form note
+        #[arg(long)]
+        note: Option<String>,
+        /// Comma-separated tags
+        #[arg(long)]
+        tags: Option<String>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+    /// Remove a bookmark by id
+    Remove {
+        /// Bookmark id (from `cass bookmarks list`)
+        id: i64,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+    /// Export all bookmarks as JSON (importable by the TUI)
+    Export {
+        /// Write to FILE instead of stdout
+        #[arg(long, short = 'o', value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Print a JSON summary when writing to `--output`
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Summary format when writing to `--output`: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+    /// Re-resolve bookmarks against the index and save relocated paths and stale flags
+    ///
+    /// `list` and `search` re-resolve in memory only; this writes the result
+    /// back to bookmarks.db.
+    Resolve {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+    /// Search bookmark titles, notes and snippets
+    Search {
+        /// Text to look for
+        query: String,
+        /// Max bookmarks to return (0 = all)
+        #[arg(long, default_value_t = 0)]
+        limit: usize,
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+        /// Output as JSON (`--robot` also works)
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+        /// Robot output format: json | jsonl | compact | sessions | toon
+        #[arg(long, value_enum)]
+        robot_format: Option<RobotFormat>,
+    },
+}
+
 /// Subcommands for importing external data
 #[derive(Subcommand, Debug, Clone)]
 pub enum ImportCommand {
@@ -737,6 +1210,10 @@ pub enum SourcesCommand {
         /// Paths to sync (can be specified multiple times)
         #[arg(long = "path", short = 'p')]
         paths: Vec<String>,
+        /// Pull normalized conversations from cass on the host instead of
+        /// mirroring paths (the host must run cass and have an index)
+        #[arg(long, conflicts_with_all = ["preset", "paths"])]
+        native: bool,
         /// Skip connectivity test
         #[arg(long)]
         no_test: bool,
@@ -779,12 +1256,42 @@ pub enum SourcesCommand {
         #[arg(long, visible_alias = "robot")]
         json: bool,
     },
-    /// Manage path mappings for a source (P6.3)
-    #[command(subcommand)]
-    Mappings(MappingsAction),
-    /// Auto-discover SSH hosts from ~/.ssh/config
-    Discover {
-        /// Platform preset for default paths (macos-defaults, linux-defaults)
+    /// Sync sources on their `sync_schedule` (hourly/daily) in the foreground.
+    ///
+    /// Each due source is synced, then only its mirror roots are reindexed.
+    /// Failed syncs are retried with exponential backoff; outcomes are
+    /// recorded in sync_status.json. `cass index --watch` runs the same
+    /// scheduler alongside the file watcher.
+    Daemon {
+        /// Seconds between checks for due sources
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+        /// Sync whatever is due once and exit (for cron/systemd timers)
+        #[arg(long)]
+        once: bool,
+        /// Don't reindex after syncing
+        #[arg(long)]
+        no_index: bool,
+        /// Output one JSON line per sync
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Answer one native sync pull over stdin/stdout.
+    ///
+    /// `cass sources sync` runs this on hosts with `transport = "native"`
+    /// over ssh: it reads a JSON pull request with a cursor and replies with
+    /// the conversations indexed here since then, as JSON lines.
+    ServeStdio {
+        /// Override data dir
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+    },
+    /// Manage path mappings for a source (P6.3)
+    #[command(subcommand)]
+    Mappings(MappingsAction),
+    /// Auto-discover SSH hosts from ~/.ssh/config
+    Discover {
+        /// Platform preset for default paths (macos-defaults, linux-defaults)
         #[arg(long, default_value = "linux-defaults")]
         preset: String,
         /// Skip hosts that are already configured as sources
@@ -1058,6 +1565,17 @@ pub enum ConvExportFormat {
     Html,
 }
 
+/// Session diff output format (for diff command)
+#[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
+pub enum DiffFormat {
+    /// Interactive side-by-side terminal view
+    Tui,
+    /// Markdown report with summary and aligned message table
+    Markdown,
+    /// Structured JSON for agents and scripts
+    Json,
+}
+
 /// Timeline grouping options
 #[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
 pub enum TimelineGrouping {
@@ -1070,6 +1588,32 @@ pub enum TimelineGrouping {
     None,
 }
 
+/// Vector storage format for `cass index --semantic`.
+#[derive(Copy, Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum IndexQuantization {
+    /// Full precision (largest, exact)
+    #[default]
+    F32,
+    /// Half precision (~50% of f32)
+    F16,
+    /// Scalar int8 with per-dimension scales (~25% of f32), rescored against f16
+    Int8,
+    /// One bit per dimension (~3% of f32), rescored against f16
+    Binary,
+}
+
+impl IndexQuantization {
+    fn to_quantization(self) -> crate::search::vector_index::Quantization {
+        use crate::search::vector_index::Quantization;
+        match self {
+            IndexQuantization::F32 => Quantization::F32,
+            IndexQuantization::F16 => Quantization::F16,
+            IndexQuantization::Int8 => Quantization::Int8,
+            IndexQuantization::Binary => Quantization::Binary,
+        }
+    }
+}
+
 /// Deployment target for pages export.
 #[derive(Copy, Clone, Debug, ValueEnum, PartialEq, Eq)]
 pub enum PagesDeployTarget {
@@ -1101,6 +1645,115 @@ impl PagesDeployTarget {
     }
 }
 
+/// Subcommands of `cass pages`.
+#[derive(Subcommand, Debug, Clone)]
+pub enum PagesCommand {
+    /// Manage the key slots of an encrypted archive without re-exporting
+    #[command(subcommand)]
+    Keys(PagesKeysCommand),
+}
+
+/// Where to push an archive's site/ after changing it.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct PagesRedeployArgs {
+    /// Redeploy the updated site/ to github or cloudflare afterwards
+    #[arg(long, value_enum)]
+    pub redeploy: Option<PagesDeployTarget>,
+
+    /// GitHub repo or Cloudflare project to redeploy to
+    #[arg(long, alias = "repo")]
+    pub project: Option<String>,
+
+    /// Cloudflare production branch (default: main)
+    #[arg(long)]
+    pub branch: Option<String>,
+
+    /// Cloudflare account ID (or CLOUDFLARE_ACCOUNT_ID env)
+    #[arg(long)]
+    pub account_id: Option<String>,
+
+    /// Cloudflare API token (or CLOUDFLARE_API_TOKEN env)
+    #[arg(long)]
+    pub api_token: Option<String>,
+}
+
+/// Key slot management for `cass pages keys`.
+///
+/// Adding and revoking slots only rewrites config.json; rotating re-encrypts
+/// the payload under a fresh key. Each regenerates integrity.json and the
+/// bundle's private/integrity-fingerprint.txt.
+#[derive(Subcommand, Debug, Clone)]
+pub enum PagesKeysCommand {
+    /// List the archive's key slots
+    List {
+        /// Archive directory (bundle root or its site/ directory)
+        #[arg(long)]
+        archive: PathBuf,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Add a password slot, or a recovery secret slot with --recovery
+    Add {
+        /// Archive directory (bundle root or its site/ directory)
+        #[arg(long)]
+        archive: PathBuf,
+        /// Add a recovery secret slot instead of a password slot
+        #[arg(long)]
+        recovery: bool,
+        /// A password that already unlocks the archive (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// Password for the new slot (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_NEW_PASSWORD", hide_env_values = true)]
+        new_password: Option<String>,
+        #[command(flatten)]
+        deploy: PagesRedeployArgs,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Remove a key slot (never the last one or the one you unlock with)
+    Revoke {
+        /// Archive directory (bundle root or its site/ directory)
+        #[arg(long)]
+        archive: PathBuf,
+        /// Slot ID to revoke (see `cass pages keys list`)
+        slot: u8,
+        /// A password from another slot (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        #[command(flatten)]
+        deploy: PagesRedeployArgs,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+    /// Re-encrypt the payload under a new key, replacing every slot
+    ///
+    /// All existing passwords and recovery secrets stop working. A new
+    /// recovery secret is generated if the archive had one.
+    Rotate {
+        /// Archive directory (bundle root or its site/ directory)
+        #[arg(long)]
+        archive: PathBuf,
+        /// A password that currently unlocks the archive (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+        /// The only password after rotation (prompted if omitted)
+        #[arg(long, env = "CASS_PAGES_NEW_PASSWORD", hide_env_values = true)]
+        new_password: Option<String>,
+        /// Don't generate a new recovery secret
+        #[arg(long)]
+        no_recovery: bool,
+        #[command(flatten)]
+        deploy: PagesRedeployArgs,
+        /// Output as JSON
+        #[arg(long, visible_alias = "robot")]
+        json: bool,
+    },
+}
+
 /// Aggregation field types for --aggregate flag
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AggregateField {
@@ -1268,6 +1921,10 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         "offset",
         "agent",
         "workspace",
+        "role",
+        "tool",
+        "tag",
+        "file",
         "fields",
         "max-tokens",
         "request-id",
@@ -1298,6 +1955,8 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         "source",
         "sessions-from",
         "mode",
+        "granularity",
+        "evidence",
         "highlight",
         "timeout",
         "explain",
@@ -1321,6 +1980,12 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         "reranker",
         "daemon",
         "no-daemon",
+        "socket",
+        "bind",
+        "token",
+        "symbol",
+        "no-semantic",
+        "cors-origin",
         "preview",
         "port",
         "config",
@@ -1334,6 +1999,7 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         // Missing flags added
         "approximate",
         "build-hnsw",
+        "quantization",
         "export-only",
         "verify",
         "scan-secrets",
@@ -1344,6 +2010,31 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         "i-understand-unencrypted-risks",
         "include-attachments",
         "no-open",
+        "stale",
+        "note",
+        "title",
+        "tags",
+        "new",
+        "alert-jsonl",
+        "hook",
+        "touched",
+        "edited",
+        "failed",
+        "grep",
+        "print-keymap",
+        "interval",
+        "once",
+        "archive",
+        "recovery",
+        "new-password",
+        "no-recovery",
+        "redeploy",
+        "project",
+        "branch",
+        "account-id",
+        "api-token",
+        "update",
+        "native",
     ];
 
     // Subcommand aliases for common mistakes
@@ -1384,6 +2075,9 @@ fn normalize_args(raw: Vec<String>) -> (Vec<String>, Option<String>) {
         ("docs", "robot-docs"),
         ("help-robot", "robot-docs"),
         ("robotdocs", "robot-docs"),
+        // Bookmarks aliases
+        ("bookmark", "bookmarks"),
+        ("bm", "bookmarks"),
     ];
 
     // Short flags that should remain as single-dash
@@ -1900,6 +2594,9 @@ fn heuristic_parse_recovery(
                 "--offset",
                 "--agent",
                 "--workspace",
+                "--role",
+                "--tool",
+                "--tag",
                 "--fields",
                 "--max-tokens",
                 "--request-id",
@@ -2148,6 +2845,7 @@ async fn execute_cli(
     let command = cli.command.clone().unwrap_or(Commands::Tui {
         once: false,
         reset_state: false,
+        print_keymap: false,
         data_dir: None,
     });
 
@@ -2161,6 +2859,22 @@ async fn execute_cli(
         return Ok(());
     }
 
+    // Printing the keymap needs no terminal, so handle it before the TTY guard.
+    if let Commands::Tui {
+        print_keymap: true, ..
+    } = command
+    {
+        let config = ui::config::TuiConfig::load().map_err(|e| CliError {
+            code: 9,
+            kind: "config",
+            message: format!("{e:#}"),
+            hint: Some("Fix the listed entries in tui.toml or set CASS_TUI_CONFIG".to_string()),
+            retryable: false,
+        })?;
+        print!("{}", config.keymap_report());
+        return Ok(());
+    }
+
     // Block TUI in non-TTY contexts unless TUI_HEADLESS is set (for testing)
     if matches!(command, Commands::Tui { .. })
         && !stdout_is_tty
@@ -2286,6 +3000,7 @@ async fn execute_cli(
                     semantic,
                     build_hnsw,
                     embedder,
+                    quantization,
                     json,
                     idempotency_key,
                 } => {
@@ -2299,131 +3014,14 @@ async fn execute_cli(
                         semantic,
                         build_hnsw,
                         embedder,
+                        quantization.to_quantization(),
                         progress,
                         json,
                         idempotency_key,
                     )?;
                 }
-                Commands::Search {
-                    query,
-                    agent,
-                    workspace,
-                    limit,
-                    offset,
-                    json,
-                    robot_format,
-                    robot_meta,
-                    fields,
-                    max_content_length,
-                    max_tokens,
-                    request_id,
-                    cursor,
-                    display,
-                    data_dir,
-                    days,
-                    today,
-                    yesterday,
-                    week,
-                    since,
-                    until,
-                    aggregate,
-                    explain,
-                    dry_run,
-                    timeout,
-                    highlight,
-                    source,
-                    sessions_from,
-                    mode,
-                    approximate,
-                    model,
-                    rerank,
-                    reranker,
-                    daemon,
-                    no_daemon,
-                    two_tier,
-                    fast_only,
-                    quality_only,
-                } => {
-                    // Validate mutually exclusive two-tier flags
-                    let tier_count = [two_tier, fast_only, quality_only]
-                        .iter()
-                        .filter(|&&b| b)
-                        .count();
-                    if tier_count > 1 {
-                        return Err(CliError::usage(
-                            "Cannot specify multiple tier flags",
-                            Some(
-                                "Use only one of --two-tier, --fast-only, or --quality-only"
-                                    .to_string(),
-                            ),
-                        ));
-                    }
-
-                    // Validate mutually exclusive flags
-                    if daemon && no_daemon {
-                        return Err(CliError::usage(
-                            "Cannot specify both --daemon and --no-daemon",
-                            Some(
-                                "Use --daemon to enable daemon or --no-daemon to disable it"
-                                    .to_string(),
-                            ),
-                        ));
-                    }
-
-                    // Warn about reranker without rerank flag
-                    if reranker.is_some() && !rerank {
-                        eprintln!(
-                            "Warning: --reranker specified but --rerank not enabled; reranker will be ignored"
-                        );
-                    }
-
-                    // Build semantic options from new flags
-                    let semantic_opts = SemanticSearchOptions {
-                        model: model.clone(),
-                        rerank,
-                        reranker: reranker.clone(),
-                        use_daemon: daemon && !no_daemon,
-                        approximate,
-                    };
-
-                    run_cli_search(
-                        &query,
-                        &agent,
-                        &workspace,
-                        &limit,
-                        &offset,
-                        &json,
-                        robot_format,
-                        robot_meta,
-                        fields,
-                        max_content_length,
-                        max_tokens,
-                        request_id.clone(),
-                        cursor.clone(),
-                        display,
-                        &data_dir,
-                        cli.db.clone(),
-                        wrap,
-                        progress,
-                        robot_mode,
-                        TimeFilter::new(
-                            days,
-                            today,
-                            yesterday,
-                            week,
-                            since.as_deref(),
-                            until.as_deref(),
-                        ),
-                        aggregate,
-                        explain,
-                        dry_run,
-                        timeout,
-                        highlight,
-                        source,
-                        sessions_from,
-                        mode,
-                        semantic_opts,
-                    )?;
+                search @ Commands::Search { .. } => {
+                    run_search_command(search, cli.db.clone(), wrap, progress, robot_mode)?;
                 }
                 Commands::Stats {
                     data_dir,
@@ -2465,6 +3063,8 @@ async fn execute_cli(
                 Commands::Pages {
                     export_only,
                     verify,
+                    update,
+                    password,
                     agents,
                     workspaces,
                     since,
@@ -2491,7 +3091,55 @@ async fn execute_cli(
                     config,
                     validate_config,
                     example_config,
+                    command,
                 } => {
+                    if let Some(PagesCommand::Keys(cmd)) = command {
+                        return run_pages_keys(cmd, robot_mode);
+                    }
+
+                    // Handle --update (incremental append to an existing archive)
+                    if let Some(archive) = update {
+                        let db_path = cli.db.clone().unwrap_or_else(|| {
+                            directories::ProjectDirs::from(
+                                "com",
+                                "dicklesworthstone",
+                                "coding-agent-search",
+                            )
+                            .map(|dirs| dirs.data_dir().join("agent_search.db"))
+                            .unwrap_or_else(default_db_path)
+                        });
+                        // Only used for archives that predate the recorded filter
+                        let fallback_filter = crate::pages::export::ExportFilter {
+                            agents,
+                            workspaces: workspaces
+                                .map(|ws| ws.into_iter().map(PathBuf::from).collect()),
+                            since: since
+                                .as_deref()
+                                .and_then(crate::ui::time_parser::parse_time_input)
+                                .and_then(chrono::DateTime::from_timestamp_millis),
+                            until: until
+                                .as_deref()
+                                .and_then(crate::ui::time_parser::parse_time_input)
+                                .and_then(chrono::DateTime::from_timestamp_millis),
+                            path_mode,
+                        };
+                        let deploy = PagesRedeployArgs {
+                            redeploy: target,
+                            project,
+                            branch,
+                            account_id,
+                            api_token,
+                        };
+                        return run_pages_update(
+                            &archive,
+                            &db_path,
+                            password,
+                            fallback_filter,
+                            deploy,
+                            json || robot_mode,
+                        );
+                    }
+
                     // Handle --example-config (show example config and exit)
                     if example_config {
                         println!("{}", crate::pages::config_input::example_config());
@@ -2998,6 +3646,20 @@ async fn execute_cli(
                 Commands::Introspect { json } => {
                     run_introspect(json)?;
                 }
+                Commands::Mcp { data_dir } => {
+                    run_mcp(data_dir, cli.db.clone())?;
+                }
+                Commands::Serve {
+                    bind,
+                    token,
+                    cors_origin,
+                    data_dir,
+                } => {
+                    run_serve(&bind, token, cors_origin, data_dir, cli.db.clone())?;
+                }
+                Commands::Daemon { socket, data_dir } => {
+                    run_daemon(socket, data_dir)?;
+                }
                 Commands::Health {
                     data_dir,
                     json,
@@ -3023,6 +3685,26 @@ async fn execute_cli(
                 } => {
                     run_context(&path, &data_dir, cli.db.clone(), json, limit)?;
                 }
+                Commands::Related {
+                    file,
+                    line,
+                    symbol,
+                    limit,
+                    no_semantic,
+                    data_dir,
+                    json,
+                } => {
+                    run_related(
+                        &file,
+                        line,
+                        symbol.as_deref(),
+                        limit,
+                        no_semantic,
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
                 Commands::Export {
                     path,
                     format,
@@ -3031,9 +3713,77 @@ async fn execute_cli(
                 } => {
                     run_export(&path, format, output.as_deref(), include_tools)?;
                 }
+                Commands::Diff {
+                    a,
+                    b,
+                    format,
+                    output,
+                    data_dir,
+                    json,
+                } => {
+                    run_diff(
+                        &a,
+                        &b,
+                        format,
+                        output.as_deref(),
+                        &data_dir,
+                        cli.db.clone(),
+                        json,
+                    )?;
+                }
+                Commands::Files {
+                    touched,
+                    edited,
+                    agent,
+                    workspace,
+                    days,
+                    limit,
+                    data_dir,
+                    json,
+                } => {
+                    let filter = crate::storage::sqlite::ToolEventFilter {
+                        kind: edited.then_some(crate::connectors::ToolEventKind::Edit),
+                        path: touched,
+                        agent,
+                        workspace,
+                        since_ms: days.map(days_ago_millis),
+                        ..Default::default()
+                    };
+                    run_files(filter, limit, &data_dir, cli.db.clone(), json)?;
+                }
+                Commands::CommandLog {
+                    failed,
+                    grep,
+                    agent,
+                    workspace,
+                    days,
+                    limit,
+                    data_dir,
+                    json,
+                } => {
+                    let filter = crate::storage::sqlite::ToolEventFilter {
+                        kind: Some(crate::connectors::ToolEventKind::Command),
+                        failed: failed.then_some(true),
+                        command_contains: grep,
+                        agent,
+                        workspace,
+                        since_ms: days.map(days_ago_millis),
+                        ..Default::default()
+                    };
+                    run_commands(filter, limit, &data_dir, cli.db.clone(), json)?;
+                }
                 Commands::ExportHtml {
                     session,
                     output_dir,
+                    query,
+                    workspace,
+                    tag,
+                    agent,
+                    since,
+                    until,
+                    limit,
+                    title,
+                    data_dir,
                     filename,
                     encrypt,
                     password,
@@ -3047,22 +3797,50 @@ async fn execute_cli(
                     open,
                     json,
                 } => {
-                    run_export_html(
-                        &session,
-                        output_dir.as_deref(),
-                        filename.as_deref(),
-                        encrypt,
-                        password.as_deref(),
-                        password_stdin,
-                        include_tools,
-                        show_timestamps,
-                        !no_cdns,
-                        &theme,
-                        dry_run,
-                        explain,
-                        open,
-                        json,
-                    )?;
+                    if let Some(session) = session {
+                        run_export_html(
+                            &session,
+                            output_dir.as_deref(),
+                            filename.as_deref(),
+                            encrypt,
+                            password.as_deref(),
+                            password_stdin,
+                            include_tools,
+                            show_timestamps,
+                            !no_cdns,
+                            &theme,
+                            dry_run,
+                            explain,
+                            open,
+                            json,
+                        )?;
+                    } else {
+                        let selection = ExportSiteSelection {
+                            query,
+                            workspaces: workspace,
+                            tags: tag,
+                            agents: agent,
+                            since,
+                            until,
+                            limit,
+                        };
+                        run_export_html_site(
+                            &selection,
+                            title.as_deref(),
+                            output_dir.as_deref(),
+                            &data_dir,
+                            cli.db.clone(),
+                            encrypt,
+                            password.as_deref(),
+                            password_stdin,
+                            include_tools,
+                            show_timestamps,
+                            !no_cdns,
+                            dry_run || explain,
+                            open,
+                            json,
+                        )?;
+                    }
                 }
                 Commands::Expand {
                     path,
@@ -3094,8 +3872,17 @@ async fn execute_cli(
                         source,
                     )?;
                 }
+                Commands::Tag(subcmd) => {
+                    run_tag_command(subcmd, cli.db.clone())?;
+                }
+                Commands::Bookmarks(subcmd) => {
+                    run_bookmarks_command(subcmd, cli.db.clone())?;
+                }
+                Commands::Saved(subcmd) => {
+                    run_saved_command(subcmd, cli.db.clone())?;
+                }
                 Commands::Sources(subcmd) => {
-                    run_sources_command(subcmd)?;
+                    run_sources_command(subcmd, cli.db.clone())?;
                 }
                 Commands::Models(subcmd) => {
                     let subcmd = subcmd.clone();
@@ -3471,14 +4258,24 @@ fn describe_command(cli: &Cli) -> String {
         Some(Commands::ApiVersion { .. }) => "api-version".to_string(),
         Some(Commands::State { .. }) => "state".to_string(),
         Some(Commands::Introspect { .. }) => "introspect".to_string(),
+        Some(Commands::Mcp { .. }) => "mcp".to_string(),
+        Some(Commands::Serve { .. }) => "serve".to_string(),
+        Some(Commands::Daemon { .. }) => "daemon".to_string(),
         Some(Commands::RobotDocs { topic }) => format!("robot-docs:{topic:?}"),
         Some(Commands::Health { .. }) => "health".to_string(),
         Some(Commands::Doctor { .. }) => "doctor".to_string(),
         Some(Commands::Context { .. }) => "context".to_string(),
+        Some(Commands::Related { .. }) => "related".to_string(),
         Some(Commands::Export { .. }) => "export".to_string(),
+        Some(Commands::Diff { .. }) => "diff".to_string(),
+        Some(Commands::Files { .. }) => "files".to_string(),
+        Some(Commands::CommandLog { .. }) => "commands".to_string(),
         Some(Commands::ExportHtml { .. }) => "export-html".to_string(),
         Some(Commands::Expand { .. }) => "expand".to_string(),
         Some(Commands::Timeline { .. }) => "timeline".to_string(),
+        Some(Commands::Tag(..)) => "tag".to_string(),
+        Some(Commands::Bookmarks(..)) => "bookmarks".to_string(),
+        Some(Commands::Saved(..)) => "saved".to_string(),
         Some(Commands::Sources(..)) => "sources".to_string(),
         Some(Commands::Models(..)) => "models".to_string(),
         Some(Commands::Pages { .. }) => "pages".to_string(),
@@ -3511,15 +4308,56 @@ fn is_robot_mode(command: &Commands) -> bool {
         Commands::View { json, .. } => *json || env_robot_mode,
         Commands::Capabilities { json, .. } => *json || env_robot_mode,
         Commands::Introspect { json, .. } => *json || env_robot_mode,
+        // stdout carries JSON-RPC, so logs stay quiet like any robot command.
+        Commands::Mcp { .. } => true,
         Commands::Context { json, .. } => *json || env_robot_mode,
+        Commands::Related { json, .. } => *json || env_robot_mode,
         Commands::Expand { json, .. } => *json || env_robot_mode,
         Commands::ExportHtml { json, .. } => *json || env_robot_mode,
+        Commands::Diff { json, format, .. } => {
+            *json || matches!(format, Some(DiffFormat::Json)) || env_robot_mode
+        }
         Commands::Timeline { json, .. } => *json || env_robot_mode,
+        Commands::Files { json, .. } | Commands::CommandLog { json, .. } => *json || env_robot_mode,
+        Commands::Tag(cmd) => match cmd {
+            TagCommand::Add { json, .. }
+            | TagCommand::Remove { json, .. }
+            | TagCommand::List { json, .. } => *json || env_robot_mode,
+        },
+        Commands::Bookmarks(cmd) => match cmd {
+            BookmarksCommand::List {
+                json, robot_format, ..
+            }
+            | BookmarksCommand::Add {
+                json, robot_format, ..
+            }
+            | BookmarksCommand::Remove {
+                json, robot_format, ..
+            }
+            | BookmarksCommand::Search {
+                json, robot_format, ..
+            }
+            | BookmarksCommand::Export {
+                json, robot_format, ..
+            }
+            | BookmarksCommand::Resolve {
+                json, robot_format, ..
+            } => *json || robot_format.is_some() || env_robot_mode,
+        },
+        Commands::Saved(cmd) => match cmd {
+            SavedCommand::Run {
+                json, robot_format, ..
+            } => *json || robot_format.is_some() || env_robot_mode,
+            SavedCommand::Add { json, .. }
+            | SavedCommand::List { json, .. }
+            | SavedCommand::Remove { json, .. } => *json || env_robot_mode,
+        },
         Commands::Sources(cmd) => match cmd {
             // Only `sources list` honors env-based structured output today.
             SourcesCommand::List { json, .. } => *json || env_robot_mode,
             SourcesCommand::Doctor { json, .. }
             | SourcesCommand::Sync { json, .. }
+            | SourcesCommand::Daemon { json, .. }
             | SourcesCommand::Discover { json, .. }
             | SourcesCommand::Setup { json, .. } => *json,
             _ => false,
@@ -3527,6 +4365,15 @@ fn is_robot_mode(command: &Commands) -> bool {
         Commands::Import(cmd) => match cmd {
             ImportCommand::Chatgpt { json, .. } => *json || env_robot_mode,
         },
+        Commands::Pages {
+            command: Some(PagesCommand::Keys(cmd)),
+            ..
+        } => match cmd {
+            PagesKeysCommand::List { json, .. }
+            | PagesKeysCommand::Add { json, .. }
+            | PagesKeysCommand::Revoke { json, .. }
+            | PagesKeysCommand::Rotate { json, .. } => *json || env_robot_mode,
+        },
         _ => false,
     }
 }
@@ -3779,6 +4626,9 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  cass search <query> [OPTIONS]".to_string(),
             "    --agent A         Filter by agent (codex, claude_code, gemini, vibe, opencode, amp, cline)".to_string(),
             "    --workspace W     Filter by workspace path".to_string(),
+            "    --role R          Filter by message role (user, agent, tool, system); inline: role:user".to_string(),
+            "    --tool T          Filter to messages that invoked tool T (e.g. Bash); inline: tool:Bash".to_string(),
+            "    --tag G           Filter to sessions tagged G (see cass tag); inline: tag:G".to_string(),
             "    --limit N         Max results (default: 10)".to_string(),
             "    --offset N        Pagination offset (default: 0)".to_string(),
             "    --json | --robot  JSON output for automation".to_string(),
@@ -3795,12 +4645,47 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "    --until DATE      Filter to date (YYYY-MM-DD)".to_string(),
             "    --aggregate F1,F2 Server-side aggregation by fields (agent,workspace,date,match_type)".to_string(),
             "                      Returns buckets with counts. Reduces tokens by ~99% for overview queries".to_string(),
+            "    --granularity G   message (default) or session: one hit per conversation with top evidence messages".to_string(),
+            "    --evidence N      Evidence messages per session (default: 3; with --granularity session)".to_string(),
+            "    --file PATH       Messages whose tool calls read/edited PATH (also inline: file:src/lib.rs)".to_string(),
+            "  cass tag add|remove <session> <tag>... [--json]   Tag sessions (kept across index --full)".to_string(),
+            "  cass tag list [<session>] [--json]               List tags (all tags with counts if no session)".to_string(),
+            "  cass bookmarks add <path> [-n LINE] [--title T] [--note N] [--tags a,b] [--json]".to_string(),
+            "  cass bookmarks list [--tag T] [--stale] [--limit N] [--json|--robot-format F]".to_string(),
+            "  cass bookmarks search <query> [--limit N] [--json|--robot-format F]".to_string(),
+            "  cass bookmarks remove <id> [--json]".to_string(),
+            "  cass bookmarks export [--output FILE] [--json]".to_string(),
+            "                      Bookmarks are re-resolved after reindex; status: ok|relocated|stale|unverified".to_string(),
+            "  cass saved add <name> <query> [--agent A] [--mode M] [--limit N] [--alert-jsonl FILE] [--hook CMD]".to_string(),
+            "  cass saved list [--json]  |  cass saved remove <name> [--json]".to_string(),
+            "  cass saved run <name> [--new] [--limit N] [--json|--robot-format F]".to_string(),
+            "                      --new: only hits with created_at past the last run's high-water mark".to_string(),
+            "                      Searches with --alert-jsonl/--hook are evaluated by 'cass index --watch' after each batch".to_string(),
+            "  cass diff <sessionA> <sessionB> [--format tui|markdown|json] [-o FILE] [--json]".to_string(),
+            "                      Align two sessions: divergence point, files, tokens, duration, tool calls".to_string(),
+            "  cass files [--touched PATH] [--edited] [--agent A] [--workspace W] [--days N] [--limit N] [--json]".to_string(),
+            "                      Most-touched files, or the sessions that read/edited PATH (suffix match)".to_string(),
+            "  cass related --file PATH [--line N] [--symbol NAME] [--limit N] [--no-semantic] [--json]".to_string(),
+            "                      Sessions ranked by edits/reads/snippets of PATH, workspace, symbol hits, similar code".to_string(),
+            "  cass commands [--failed] [--grep TEXT] [--agent A] [--workspace W] [--days N] [--limit N] [--json]".to_string(),
+            "                      Shell commands run by agents with exit code; --failed: non-zero or tool error".to_string(),
+            "  cass mcp [--data-dir DIR]".to_string(),
+            "                      MCP server on stdio: tools search, view, expand, context, timeline, stats".to_string(),
+            "  cass serve [--bind ADDR] [--token T] [--cors-origin ORIGIN]... [--data-dir DIR]".to_string(),
+            "                      HTTP/JSON API: GET|POST /v1/{search,view,expand,timeline,stats,health,sources,related}, GET /openapi.json".to_string(),
+            "  cass pages keys list|add|revoke|rotate --archive DIR [--redeploy github|cloudflare] [--json]".to_string(),
+            "                      Manage an encrypted archive's key slots; passwords via CASS_PAGES_PASSWORD/CASS_PAGES_NEW_PASSWORD".to_string(),
+            "  cass pages --update DIR [--target github|cloudflare] [--json]".to_string(),
+            "                      Append conversations newer than the archive; re-encrypts only changed chunks".to_string(),
+            "  cass daemon [--socket PATH] [--data-dir DIR]".to_string(),
+            "                      Keep models and search indexes warm; 'cass search' routes through it when running".to_string(),
             "  cass stats [--json] [--data-dir DIR]".to_string(),
             "  cass status [--json] [--stale-threshold N] [--data-dir DIR]".to_string(),
             "  cass diag [--json] [--verbose] [--data-dir DIR]".to_string(),
             "  cass view <path> [-n LINE] [-C CONTEXT] [--json]".to_string(),
             "  cass index [--full] [--watch] [--json] [--data-dir DIR]".to_string(),
-            "  cass tui [--once] [--data-dir DIR] [--reset-state]".to_string(),
+            "             [--semantic [--quantization f32|f16|int8|binary] [--build-hnsw]]".to_string(),
+            "  cass tui [--once] [--data-dir DIR] [--reset-state] [--print-keymap]".to_string(),
             "  cass capabilities [--json]".to_string(),
             "  cass robot-docs <topic>".to_string(),
             "  cass --robot-help".to_string(),
@@ -3817,6 +4702,7 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  TOON_KEY_FOLDING=off|safe                 TOON key folding mode".to_string(),
             "  NO_COLOR / CASS_NO_COLOR                 disable color".to_string(),
             "  CASS_TRACE_FILE                          default trace path".to_string(),
+            "  CASS_TUI_CONFIG                          TUI keymap/theme file (default <config>/cass/tui.toml)".to_string(),
         ],
         RobotTopic::Paths => {
             let mut lines: Vec<String> = vec!["paths:".to_string()];
@@ -3859,6 +4745,23 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "# Filter by agent or workspace".to_string(),
             "  cass search \"error\" --agent codex         # codex sessions only".to_string(),
             "  cass search \"test\" --workspace /myproject # specific project".to_string(),
+            "  cass search \"deploy role:user\"           # only user prompts".to_string(),
+            "  cass search \"migration\" --tool Bash      # only messages that ran Bash".to_string(),
+            "  cass tag add /path/to/session.jsonl auth-bug  # tag a session".to_string(),
+            "  cass search \"token tag:auth-bug\"         # only tagged sessions".to_string(),
+            "  cass bookmarks add /path/to/session.jsonl -n 42 --note \"root cause\" --json".to_string(),
+            "  cass bookmarks list --stale --json         # bookmarks lost after reindex".to_string(),
+            "  cass saved add drop-table '\"DROP TABLE\"' --alert-jsonl ~/alerts.jsonl".to_string(),
+            "  cass saved run drop-table --new --json     # only matches since the last run".to_string(),
+            "  cass diff run1.jsonl run2.jsonl --json     # compare a retried session".to_string(),
+            "  cass files --touched src/foo.rs --json     # which sessions edited this file".to_string(),
+            "  cass related --file src/foo.rs --line 42 --symbol parse_args --json  # from your editor".to_string(),
+            "  cass commands --failed --grep cargo --json # failed cargo commands".to_string(),
+            "  cass mcp                                   # stdio MCP server; point your client's command at it".to_string(),
+            "  cass pages keys add --recovery --archive ./export --json  # new recovery secret slot".to_string(),
+            "  cass pages --update ./export --target github --json  # append new sessions, push the diff".to_string(),
+            "  cass serve --bind 127.0.0.1:8765           # then: curl '127.0.0.1:8765/v1/search?query=auth&limit=5'".to_string(),
+            "  cass search \"auth\" --daemon --json         # search via the warm daemon (spawns it if needed)".to_string(),
             String::new(),
             "# Follow up on search results".to_string(),
             "  cass view /path/to/session.jsonl -n 42   # view line 42 with context".to_string(),
@@ -3873,6 +4776,10 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "  cass search \"*\" --json --aggregate agent,workspace  # multi-field agg".to_string(),
             "  cass search \"bug\" --json --aggregate date --week  # time distribution".to_string(),
             String::new(),
+            "# Session-level results (one hit per conversation)".to_string(),
+            "  cass search \"oauth refresh\" --json --granularity session   # sessions[] with evidence[]".to_string(),
+            "  cass search \"flaky\" --granularity session --evidence 1 --mode hybrid".to_string(),
+            String::new(),
             "# Quick health check (ideal for agents)".to_string(),
             "  cass status --json                       # health check JSON".to_string(),
             "  cass status --stale-threshold 3600       # custom stale threshold (1hr)".to_string(),
@@ -3976,6 +4883,8 @@ fn print_robot_docs(topic: RobotTopic, wrap: WrapConfig) -> CliResult<()> {
             "## Related Commands".to_string(),
             "  cass sources list         List configured sources".to_string(),
             "  cass sources sync         Sync data from sources".to_string(),
+            "  cass sources daemon       Sync on each source's sync_schedule".to_string(),
+            "  cass sources serve-stdio  Serve native sync pulls (run on the remote)".to_string(),
             "  cass sources discover     Just discover hosts (no setup)".to_string(),
             "  cass sources add          Manually add a source".to_string(),
         ],
@@ -4100,6 +5009,8 @@ pub struct SemanticSearchOptions {
     pub reranker: Option<String>,
     /// Use daemon for warm model inference
     pub use_daemon: bool,
+    /// Never route through the daemon, even when one is already running
+    pub no_daemon: bool,
     /// Use approximate nearest neighbor search when available
     pub approximate: bool,
 }
@@ -4254,172 +5165,242 @@ fn parse_aggregate_fields(fields: &[String]) -> Vec<AggregateField> {
         .collect()
 }
 
-#[allow(clippy::too_many_arguments)]
-fn run_cli_search(
-    query: &str,
-    agents: &[String],
-    workspaces: &[String],
-    limit: &usize,
-    offset: &usize,
-    json: &bool,
-    robot_format: Option<RobotFormat>,
-    robot_meta: bool,
-    fields: Option<Vec<String>>,
-    max_content_length: Option<usize>,
-    max_tokens: Option<usize>,
-    request_id: Option<String>,
-    cursor: Option<String>,
-    display_format: Option<DisplayFormat>,
-    data_dir_override: &Option<PathBuf>,
-    db_override: Option<PathBuf>,
+/// Run `cass search` from its parsed subcommand.
+///
+/// Shared by the CLI dispatcher and the MCP server so both apply the same flag validation.
+fn run_search_command(
+    command: Commands,
+    db: Option<PathBuf>,
     wrap: WrapConfig,
-    _progress: ProgressResolved,
-    robot_auto: bool,
-    time_filter: TimeFilter,
-    aggregate: Option<Vec<String>>,
-    explain: bool,
-    dry_run: bool,
-    timeout_ms: Option<u64>,
-    highlight: bool,
-    source: Option<String>,
-    sessions_from: Option<String>,
-    mode: Option<crate::search::query::SearchMode>,
-    semantic_opts: SemanticSearchOptions,
+    progress: ProgressResolved,
+    robot_mode: bool,
 ) -> CliResult<()> {
-    use crate::search::ann_index::hnsw_index_path;
-    use crate::search::model_manager::{load_hash_semantic_context, load_semantic_context};
-    use crate::search::query::{
-        QueryExplanation, SearchClient, SearchClientOptions, SearchFilters, SearchMode,
+    let Commands::Search {
+        query,
+        agent,
+        workspace,
+        role,
+        tool,
+        tag,
+        file,
+        limit,
+        offset,
+        json,
+        robot_format,
+        robot_meta,
+        fields,
+        max_content_length,
+        max_tokens,
+        request_id,
+        cursor,
+        display,
+        data_dir,
+        days,
+        today,
+        yesterday,
+        week,
+        since,
+        until,
+        aggregate,
+        explain,
+        dry_run,
+        timeout,
+        highlight,
+        source,
+        sessions_from,
+        mode,
+        granularity,
+        evidence,
+        approximate,
+        model,
+        rerank,
+        reranker,
+        daemon,
+        no_daemon,
+        two_tier,
+        fast_only,
+        quality_only,
+    } = command
+    else {
+        return Err(CliError::unknown(
+            "run_search_command requires a search command",
+        ));
     };
-    use crate::search::tantivy::index_dir;
-    use crate::sources::provenance::SourceFilter;
-    use std::collections::HashSet;
-    use std::sync::Arc;
 
-    // Start timing for robot_meta elapsed_ms
-    let start_time = Instant::now();
+    // Validate mutually exclusive two-tier flags
+    let tier_count = [two_tier, fast_only, quality_only]
+        .iter()
+        .filter(|&&b| b)
+        .count();
+    if tier_count > 1 {
+        return Err(CliError::usage(
+            "Cannot specify multiple tier flags",
+            Some("Use only one of --two-tier, --fast-only, or --quality-only".to_string()),
+        ));
+    }
 
-    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
-    let index_path = index_dir(&data_dir).map_err(|e| CliError {
-        code: 9,
-        kind: "path",
-        message: format!("failed to open index dir: {e}"),
-        hint: None,
-        retryable: false,
-    })?;
-    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    // Validate mutually exclusive flags
+    if daemon && no_daemon {
+        return Err(CliError::usage(
+            "Cannot specify both --daemon and --no-daemon",
+            Some("Use --daemon to enable daemon or --no-daemon to disable it".to_string()),
+        ));
+    }
 
-    let client = SearchClient::open_with_options(
-        &index_path,
-        Some(&db_path),
-        SearchClientOptions {
-            enable_reload: false,
-            enable_warm: false,
-        },
-    )
-    .map_err(|e| CliError {
-        code: 9,
-        kind: "open-index",
-        message: format!("failed to open index: {e}"),
-        hint: Some("try cass index --full".to_string()),
-        retryable: true,
-    })?
-    .ok_or_else(|| CliError {
-        code: 3,
-        kind: "missing-index",
-        message: format!(
-            "Index not found at {}. Run 'cass index --full' first.",
-            index_path.display()
-        ),
-        hint: None,
-        retryable: true,
-    })?;
+    // Warn about reranker without rerank flag
+    if reranker.is_some() && !rerank {
+        eprintln!(
+            "Warning: --reranker specified but --rerank not enabled; reranker will be ignored"
+        );
+    }
 
-    // Determine effective search mode (default to Lexical)
-    let effective_mode = mode.unwrap_or(SearchMode::Lexical);
-    let approximate = if semantic_opts.approximate && matches!(effective_mode, SearchMode::Lexical)
-    {
-        eprintln!("Warning: --approximate has no effect in lexical mode.");
-        false
-    } else {
-        semantic_opts.approximate
+    // Build semantic options from new flags
+    let semantic_opts = SemanticSearchOptions {
+        model: model.clone(),
+        rerank,
+        reranker: reranker.clone(),
+        use_daemon: daemon && !no_daemon,
+        no_daemon,
+        approximate,
     };
 
-    if matches!(effective_mode, SearchMode::Semantic | SearchMode::Hybrid) {
-        use crate::search::embedder_registry::{EmbedderRegistry, HASH_EMBEDDER};
+    run_cli_search(
+        &query,
+        &agent,
+        &workspace,
+        &role,
+        &tool,
+        &tag,
+        &file,
+        &limit,
+        &offset,
+        &json,
+        robot_format,
+        robot_meta,
+        fields,
+        max_content_length,
+        max_tokens,
+        request_id.clone(),
+        cursor.clone(),
+        display,
+        &data_dir,
+        db,
+        wrap,
+        progress,
+        robot_mode,
+        TimeFilter::new(
+            days,
+            today,
+            yesterday,
+            week,
+            since.as_deref(),
+            until.as_deref(),
+        ),
+        aggregate,
+        explain,
+        dry_run,
+        timeout,
+        highlight,
+        source,
+        sessions_from,
+        mode,
+        granularity,
+        evidence,
+        semantic_opts,
+    )
+}
 
-        // Use embedder registry for model selection (bd-2mbe)
-        let registry = EmbedderRegistry::new(&data_dir);
-        let requested_model = semantic_opts.model.as_deref();
+/// Open the search index for a CLI command, mapping failures to CLI errors.
+fn open_cli_search_client(
+    index_path: &Path,
+    db_path: &Path,
+    options: crate::search::query::SearchClientOptions,
+) -> CliResult<crate::search::query::SearchClient> {
+    crate::search::query::SearchClient::open_with_options(index_path, Some(db_path), options)
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "open-index",
+            message: format!("failed to open index: {e}"),
+            hint: Some("try cass index --full".to_string()),
+            retryable: true,
+        })?
+        .ok_or_else(|| CliError {
+            code: 3,
+            kind: "missing-index",
+            message: format!(
+                "Index not found at {}. Run 'cass index --full' first.",
+                index_path.display()
+            ),
+            hint: None,
+            retryable: true,
+        })
+}
 
-        // Validate requested model if specified
-        if let Some(model_name) = requested_model
-            && let Err(e) = registry.validate(model_name)
-        {
-            return Err(CliError {
-                code: 15,
-                kind: "embedder-unavailable",
-                message: format!("Embedder validation failed: {e}"),
-                hint: Some("Run 'cass models list' to see available embedders".to_string()),
-                retryable: false,
-            });
-        }
+/// Load the vector index and embedder for semantic/hybrid search into `client`.
+fn attach_semantic_context(
+    client: &crate::search::query::SearchClient,
+    data_dir: &Path,
+    db_path: &Path,
+    semantic_opts: &SemanticSearchOptions,
+) -> CliResult<()> {
+    use crate::search::ann_index::hnsw_index_path;
+    use crate::search::embedder_registry::{EmbedderRegistry, HASH_EMBEDDER};
+    use crate::search::model_manager::{load_hash_semantic_context, load_semantic_context};
+    use std::sync::Arc;
 
-        // Determine which embedder to use
-        let embedder_info = match requested_model {
-            Some(name) => registry.get(name),
-            None => Some(registry.best_available()),
-        };
-        let prefer_hash = embedder_info.is_some_and(|e| e.name == HASH_EMBEDDER);
+    // Use embedder registry for model selection (bd-2mbe)
+    let registry = EmbedderRegistry::new(data_dir);
+    let requested_model = semantic_opts.model.as_deref();
 
-        let setup = if prefer_hash {
-            load_hash_semantic_context(&data_dir, &db_path)
-        } else {
-            load_semantic_context(&data_dir, &db_path)
-        };
+    // Validate requested model if specified
+    if let Some(model_name) = requested_model
+        && let Err(e) = registry.validate(model_name)
+    {
+        return Err(CliError {
+            code: 15,
+            kind: "embedder-unavailable",
+            message: format!("Embedder validation failed: {e}"),
+            hint: Some("Run 'cass models list' to see available embedders".to_string()),
+            retryable: false,
+        });
+    }
 
-        if let Some(context) = setup.context {
-            let embedder = context.embedder;
-            let index = context.index;
-            let filter_maps = context.filter_maps;
-            let roles = context.roles;
+    // Determine which embedder to use
+    let embedder_info = match requested_model {
+        Some(name) => registry.get(name),
+        None => Some(registry.best_available()),
+    };
+    let prefer_hash = embedder_info.is_some_and(|e| e.name == HASH_EMBEDDER);
 
-            let embedder: Arc<dyn crate::search::embedder::Embedder> = if semantic_opts.use_daemon {
-                use crate::search::daemon_client::{
-                    DaemonFallbackEmbedder, DaemonRetryConfig, NoopDaemonClient,
-                };
+    let setup = if prefer_hash {
+        load_hash_semantic_context(data_dir, db_path)
+    } else {
+        load_semantic_context(data_dir, db_path)
+    };
 
-                let daemon = Arc::new(NoopDaemonClient::new("daemon-unconfigured"));
-                let config = DaemonRetryConfig::from_env();
-                Arc::new(DaemonFallbackEmbedder::new(daemon, embedder, config))
-            } else {
-                embedder
+    if let Some(context) = setup.context {
+        let embedder = context.embedder;
+        let index = context.index;
+        let filter_maps = context.filter_maps;
+        let roles = context.roles;
+
+        let embedder: Arc<dyn crate::search::embedder::Embedder> = if semantic_opts.use_daemon {
+            use crate::search::daemon_client::{
+                DaemonFallbackEmbedder, DaemonRetryConfig, NoopDaemonClient,
             };
 
-            let ann_path = Some(hnsw_index_path(&data_dir, embedder.id()));
-            if let Err(err) =
-                client.set_semantic_context(embedder, index, filter_maps, roles, ann_path)
-            {
-                let hint = if prefer_hash {
-                    "Run 'cass index --semantic --embedder hash' to rebuild the hash vector index, or use --mode lexical"
-                        .to_string()
-                } else {
-                    "Run 'cass models install' and then 'cass index --semantic', or use --mode lexical"
-                        .to_string()
-                };
-                return Err(CliError {
-                    code: 15,
-                    kind: "semantic-unavailable",
-                    message: format!("Semantic search not available: {err}"),
-                    hint: Some(hint),
-                    retryable: false,
-                });
-            }
+            let daemon = Arc::new(NoopDaemonClient::new("daemon-unconfigured"));
+            let config = DaemonRetryConfig::from_env();
+            Arc::new(DaemonFallbackEmbedder::new(daemon, embedder, config))
         } else {
-            let _ = client.clear_semantic_context();
-            let summary = setup.availability.summary();
+            embedder
+        };
+
+        let ann_path = Some(hnsw_index_path(data_dir, embedder.id()));
+        if let Err(err) = client.set_semantic_context(embedder, index, filter_maps, roles, ann_path)
+        {
             let hint = if prefer_hash {
-                "Run 'cass index --semantic --embedder hash' to build the hash vector index, or use --mode lexical"
+                "Run 'cass index --semantic --embedder hash' to rebuild the hash vector index, or use --mode lexical"
                     .to_string()
             } else {
                 "Run 'cass models install' and then 'cass index --semantic', or use --mode lexical"
@@ -4428,169 +5409,55 @@ fn run_cli_search(
             return Err(CliError {
                 code: 15,
                 kind: "semantic-unavailable",
-                message: format!("Semantic search not available: {summary}"),
+                message: format!("Semantic search not available: {err}"),
                 hint: Some(hint),
                 retryable: false,
             });
         }
-    }
-
-    let mut filters = SearchFilters::default();
-    if !agents.is_empty() {
-        filters.agents = HashSet::from_iter(agents.iter().cloned());
-    }
-    if !workspaces.is_empty() {
-        filters.workspaces = HashSet::from_iter(workspaces.iter().cloned());
-    }
-    filters.created_from = time_filter.since;
-    filters.created_to = time_filter.until;
-
-    // Apply source filter (P3.1)
-    if let Some(ref source_str) = source {
-        filters.source_filter = SourceFilter::parse(source_str);
-    }
-
-    // Apply session paths filter (for chained searches)
-    if let Some(ref sessions_from_arg) = sessions_from {
-        let session_paths = read_session_paths(sessions_from_arg).map_err(|e| CliError {
-            code: 2,
-            kind: "sessions-from",
-            message: format!("failed to read session paths: {e}"),
-            hint: Some("Provide a file path or '-' for stdin".to_string()),
-            retryable: false,
-        })?;
-        filters.session_paths = session_paths;
-    }
-
-    // Apply cursor overrides (base64-encoded JSON { "offset": usize, "limit": usize })
-    let mut limit_val = *limit;
-    let mut offset_val = *offset;
-    if let Some(ref cursor_str) = cursor {
-        let decoded = BASE64_STANDARD.decode(cursor_str).map_err(|e| CliError {
-            code: 2,
-            kind: "cursor-decode",
-            message: format!("invalid cursor: {e}"),
-            hint: Some("Pass cursor returned in previous _meta.next_cursor".to_string()),
-            retryable: false,
-        })?;
-        let cursor_json: serde_json::Value =
-            serde_json::from_slice(&decoded).map_err(|e| CliError {
-                code: 2,
-                kind: "cursor-parse",
-                message: format!("invalid cursor payload: {e}"),
-                hint: Some("Cursor should be base64 of {\"offset\":N,\"limit\":M}".to_string()),
-                retryable: false,
-            })?;
-        if let Some(o) = cursor_json
-            .get("offset")
-            .and_then(serde_json::Value::as_u64)
-        {
-            offset_val = o as usize;
-        }
-        if let Some(l) = cursor_json.get("limit").and_then(serde_json::Value::as_u64) {
-            limit_val = l as usize;
-        }
-    }
-
-    // Determine the effective output format
-    // Priority: robot_format CLI > json flag > CASS_OUTPUT_FORMAT > TOON_DEFAULT_FORMAT > robot_auto > None
-    let effective_robot = robot_format
-        .or(if *json { Some(RobotFormat::Json) } else { None })
-        .or_else(robot_format_from_env)
-        .or(if robot_auto {
-            Some(RobotFormat::Json)
-        } else {
-            None
-        });
-    let field_mask = resolve_field_mask(&fields, effective_robot, display_format);
-
-    // Parse aggregate fields if provided
-    let agg_fields = aggregate
-        .as_ref()
-        .map(|f| parse_aggregate_fields(f))
-        .unwrap_or_default();
-    let has_aggregation = !agg_fields.is_empty();
-
-    // Handle dry-run mode: validate and analyze query without executing
-    if dry_run {
-        let explanation = QueryExplanation::analyze(query, &filters);
-        let elapsed_ms = start_time.elapsed().as_millis();
-
-        let output = serde_json::json!({
-            "dry_run": true,
-            "valid": explanation.warnings.iter().all(|w| !w.contains("error") && !w.contains("invalid")),
-            "query": query,
-            "explanation": explanation,
-            "estimated_cost": format!("{:?}", explanation.estimated_cost),
-            "warnings": explanation.warnings,
-            "request_id": request_id,
-            "_meta": {
-                "elapsed_ms": elapsed_ms,
-                "dry_run": true,
-            }
-        });
-
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
-        );
-        return Ok(());
-    }
-
-    // Use search_with_fallback to get full metadata (wildcard_fallback, cache_stats)
-    let sparse_threshold = 3; // Threshold for triggering wildcard fallback
-
-    // When aggregating, we need more results for accurate counts
-    // Fetch up to 1000 for aggregation starting at offset 0, then apply offset/limit
-    let (search_limit, search_offset) = if has_aggregation {
-        (1000.max(limit_val + offset_val), 0)
     } else {
-        (limit_val, offset_val)
-    };
-
-    // Check if we're already past timeout before starting search
-    let timeout_duration = timeout_ms.map(Duration::from_millis);
-    if let Some(timeout) = timeout_duration
-        && start_time.elapsed() >= timeout
-    {
+        let _ = client.clear_semantic_context();
+        let summary = setup.availability.summary();
+        let hint = if prefer_hash {
+            "Run 'cass index --semantic --embedder hash' to build the hash vector index, or use --mode lexical"
+                .to_string()
+        } else {
+            "Run 'cass models install' and then 'cass index --semantic', or use --mode lexical"
+                .to_string()
+        };
         return Err(CliError {
-            code: 10,
-            kind: "timeout",
-            message: format!(
-                "Operation timed out after {}ms (before search started)",
-                timeout.as_millis()
-            ),
-            hint: Some("Increase --timeout value or simplify query".to_string()),
-            retryable: true,
+            code: 15,
+            kind: "semantic-unavailable",
+            message: format!("Semantic search not available: {summary}"),
+            hint: Some(hint),
+            retryable: false,
         });
     }
 
-    // Log semantic options if any are set (bd-3bbv: flags are wired, infra pending)
-    if semantic_opts.model.is_some()
-        || semantic_opts.rerank
-        || semantic_opts.reranker.is_some()
-        || semantic_opts.use_daemon
-        || semantic_opts.approximate
-    {
-        tracing::debug!(
-            model = ?semantic_opts.model,
-            rerank = semantic_opts.rerank,
-            reranker = ?semantic_opts.reranker,
-            use_daemon = semantic_opts.use_daemon,
-            approximate = semantic_opts.approximate,
-            "Semantic search options configured"
-        );
-    }
+    Ok(())
+}
 
-    // Track search timing breakdown (T7.4)
-    let search_start = Instant::now();
-    let result = match effective_mode {
-        SearchMode::Lexical => client
-            .search_with_fallback(
-                query,
+/// Run `query` against `client` in the given search mode.
+#[allow(clippy::too_many_arguments)]
+fn search_with_mode(
+    client: &crate::search::query::SearchClient,
+    mode: crate::search::query::SearchMode,
+    query: &str,
+    filters: &crate::search::query::SearchFilters,
+    limit: usize,
+    offset: usize,
+    sparse_threshold: usize,
+    field_mask: crate::search::query::FieldMask,
+    approximate: bool,
+) -> CliResult<crate::search::query::SearchResult> {
+    use crate::search::query::SearchMode;
+
+    let result = match mode {
+        SearchMode::Lexical => client
+            .search_with_fallback(
+                query,
                 filters.clone(),
-                search_limit,
-                search_offset,
+                limit,
+                offset,
                 sparse_threshold,
                 field_mask,
             )
@@ -4606,8 +5473,8 @@ fn run_cli_search(
                 .search_semantic(
                     query,
                     filters.clone(),
-                    search_limit,
-                    search_offset,
+                    limit,
+                    offset,
                     field_mask,
                     approximate,
                 )
@@ -4658,8 +5525,8 @@ fn run_cli_search(
                 query,
                 query,
                 filters.clone(),
-                search_limit,
-                search_offset,
+                limit,
+                offset,
                 sparse_threshold,
                 field_mask,
                 approximate,
@@ -4688,201 +5555,610 @@ fn run_cli_search(
                 }
             })?,
     };
-    let search_ms = search_start.elapsed().as_millis() as u64;
-
-    // Apply reranking if enabled (bd-2t2d)
-    let rerank_start = Instant::now();
-    let result = if semantic_opts.rerank && !result.hits.is_empty() {
-        use crate::search::daemon_client::{
-            DaemonFallbackReranker, DaemonRetryConfig, NoopDaemonClient,
-        };
-        use crate::search::fastembed_reranker::FastEmbedReranker;
-        use crate::search::reranker::Reranker;
-
-        let model_dir = FastEmbedReranker::default_model_dir(&data_dir);
-        let local_reranker: Option<Arc<dyn Reranker>> =
-            match FastEmbedReranker::load_from_dir(&model_dir) {
-                Ok(reranker) => Some(Arc::new(reranker)),
-                Err(e) => {
-                    if !semantic_opts.use_daemon {
-                        tracing::debug!(error = %e, "Reranker not available, skipping rerank");
-                    }
-                    None
-                }
-            };
-
-        let reranker: Option<Arc<dyn Reranker>> = if semantic_opts.use_daemon {
-            let daemon = Arc::new(NoopDaemonClient::new("daemon-unconfigured"));
-            let config = DaemonRetryConfig::from_env();
-            Some(Arc::new(DaemonFallbackReranker::new(
-                daemon,
-                local_reranker,
-                config,
-            )))
-        } else {
-            local_reranker
-        };
-
-        if let Some(reranker) = reranker {
-            // Extract content from hits for reranking (use snippet if content is empty)
-            let docs: Vec<String> = result
-                .hits
-                .iter()
-                .map(|hit| {
-                    if hit.content.is_empty() {
-                        hit.snippet.clone()
-                    } else {
-                        hit.content.clone()
-                    }
-                })
-                .collect();
-
-            // Skip reranking if any document is empty (reranker rejects empty docs)
-            let has_empty_doc = docs.iter().any(|d| d.is_empty());
-            if has_empty_doc {
-                tracing::debug!("Skipping rerank: one or more hits have empty content and snippet");
-                result
-            } else {
-                let doc_refs: Vec<&str> = docs.iter().map(|s| s.as_str()).collect();
-
-                match reranker.rerank(query, &doc_refs) {
-                    Ok(scores) => {
-                        // Update scores and re-sort hits
-                        let mut scored_hits: Vec<_> = result
-                            .hits
-                            .into_iter()
-                            .zip(scores)
-                            .map(|(mut hit, score)| {
-                                hit.score = score;
-                                hit
-                            })
-                            .collect();
-                        scored_hits.sort_by(|a, b| {
-                            b.score
-                                .partial_cmp(&a.score)
-                                .unwrap_or(std::cmp::Ordering::Equal)
-                        });
-
-                        tracing::debug!(
-                            reranker_id = reranker.id(),
-                            hits_reranked = scored_hits.len(),
-                            "Reranking complete"
-                        );
+    Ok(result)
+}
 
-                        crate::search::query::SearchResult {
-                            hits: scored_hits,
-                            wildcard_fallback: result.wildcard_fallback,
-                            cache_stats: result.cache_stats,
-                            suggestions: result.suggestions,
-                            ann_stats: result.ann_stats,
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            error = %e,
-                            "Reranking failed, returning original results"
-                        );
-                        result
-                    }
-                }
+/// Daemon route for `cass search`, if one applies.
+///
+/// `--daemon` connects (spawning the daemon when needed); otherwise a daemon
+/// that is already running is used when reachable. `--no-daemon` and `cass mcp`
+/// (which keeps its own warm clients) always search in-process.
+fn daemon_search_router(
+    semantic_opts: &SemanticSearchOptions,
+) -> Option<crate::search::daemon_client::DaemonSearchRouter> {
+    use crate::search::daemon_client::{DaemonRetryConfig, DaemonSearchRouter};
+
+    if semantic_opts.no_daemon || crate::mcp::is_serving() {
+        return None;
+    }
+    let daemon = if semantic_opts.use_daemon {
+        match crate::daemon::client::connect_or_spawn() {
+            Ok(daemon) => daemon,
+            Err(err) => {
+                tracing::warn!(error = %err, "Daemon unavailable; searching locally");
+                return None;
             }
-        } else {
-            result
         }
     } else {
-        result
-    };
-    // Track reranking time (0 if not applied) (T7.4)
-    let rerank_ms = if semantic_opts.rerank {
-        rerank_start.elapsed().as_millis() as u64
-    } else {
-        0
+        crate::daemon::client::try_connect()?
     };
+    Some(DaemonSearchRouter::new(
+        daemon,
+        DaemonRetryConfig::from_env(),
+    ))
+}
 
-    // Check if search exceeded timeout - return partial results with timeout indicator
-    let timed_out = timeout_duration.is_some_and(|t| start_time.elapsed() > t);
-
-    // Build query explanation if requested
-    let explanation = if explain {
-        Some(
-            QueryExplanation::analyze(query, &filters)
-                .with_wildcard_fallback(result.wildcard_fallback),
-        )
-    } else {
-        None
+#[allow(clippy::too_many_arguments)]
+fn run_cli_search(
+    query: &str,
+    agents: &[String],
+    workspaces: &[String],
+    roles: &[String],
+    tools: &[String],
+    tags: &[String],
+    files: &[String],
+    limit: &usize,
+    offset: &usize,
+    json: &bool,
+    robot_format: Option<RobotFormat>,
+    robot_meta: bool,
+    fields: Option<Vec<String>>,
+    max_content_length: Option<usize>,
+    max_tokens: Option<usize>,
+    request_id: Option<String>,
+    cursor: Option<String>,
+    display_format: Option<DisplayFormat>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    wrap: WrapConfig,
+    _progress: ProgressResolved,
+    robot_auto: bool,
+    time_filter: TimeFilter,
+    aggregate: Option<Vec<String>>,
+    explain: bool,
+    dry_run: bool,
+    timeout_ms: Option<u64>,
+    highlight: bool,
+    source: Option<String>,
+    sessions_from: Option<String>,
+    mode: Option<crate::search::query::SearchMode>,
+    granularity: SearchGranularity,
+    evidence: usize,
+    semantic_opts: SemanticSearchOptions,
+) -> CliResult<()> {
+    use crate::search::query::{
+        QueryExplanation, SESSION_OVERFETCH, SearchClient, SearchClientOptions, SearchFilters,
+        SearchMode, group_hits_by_session, split_inline_filters,
     };
+    use crate::search::tantivy::index_dir;
+    use crate::sources::provenance::SourceFilter;
+    use std::collections::HashSet;
+    use std::sync::Arc;
 
-    // Compute aggregations and create display result based on mode
-    let (aggregations, display_result, total_matches) = if has_aggregation {
-        // Compute aggregations from all fetched results
-        let aggs = compute_aggregations(&result.hits, &agg_fields);
-        let total = result.hits.len();
+    // Start timing for robot_meta elapsed_ms
+    let start_time = Instant::now();
 
-        // Apply offset and limit to get display hits
-        let display_hits: Vec<_> = result
-            .hits
-            .iter()
-            .skip(offset_val)
-            .take(limit_val)
-            .cloned()
-            .collect();
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let index_path = index_dir(&data_dir).map_err(|e| CliError {
+        code: 9,
+        kind: "path",
+        message: format!("failed to open index dir: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
 
-        let display = crate::search::query::SearchResult {
-            hits: display_hits,
-            wildcard_fallback: result.wildcard_fallback,
-            cache_stats: result.cache_stats,
-            suggestions: result.suggestions.clone(),
-            ann_stats: result.ann_stats.clone(),
-        };
-        (aggs, display, total)
+    // Determine effective search mode (default to Lexical)
+    let effective_mode = mode.unwrap_or(SearchMode::Lexical);
+    let approximate = if semantic_opts.approximate && matches!(effective_mode, SearchMode::Lexical)
+    {
+        eprintln!("Warning: --approximate has no effect in lexical mode.");
+        false
     } else {
-        // No aggregation - use result as-is
-        let total = result.hits.len();
-        (Aggregations::default(), result, total)
+        semantic_opts.approximate
     };
 
-    let elapsed_ms = start_time.elapsed().as_millis() as u64;
-
-    // Derive per-field budgets, preferring snippet > content > title
-    let (snippet_budget, content_budget, title_budget, fallback_budget) = {
-        let base = max_content_length;
-        if let Some(tokens) = max_tokens {
-            let char_budget = tokens.saturating_mul(4);
-            let per_hit = char_budget / std::cmp::max(1, display_result.hits.len());
-            let snippet = std::cmp::max(16, (per_hit as f64 * 0.5) as usize);
-            let content = std::cmp::max(12, (per_hit as f64 * 0.35) as usize);
-            let title = std::cmp::max(8, (per_hit as f64 * 0.15) as usize);
-            (
-                Some(snippet),
-                Some(content),
-                Some(title),
-                base.map(|b| std::cmp::min(b, per_hit)),
-            )
-        } else {
-            (base, base, base, base)
+    // A running daemon already holds the index open; only open one in-process
+    // when there is no daemon or it cannot serve this request.
+    let daemon = daemon_search_router(&semantic_opts);
+    let open_local_client = || -> CliResult<Arc<SearchClient>> {
+        // Inside `cass mcp` the server keeps one client open across tool calls.
+        let client = match crate::mcp::warm_search_client(&index_path, &db_path) {
+            Some(client) => client?,
+            None => Arc::new(open_cli_search_client(
+                &index_path,
+                &db_path,
+                SearchClientOptions {
+                    enable_reload: false,
+                    enable_warm: false,
+                },
+            )?),
+        };
+        if matches!(effective_mode, SearchMode::Semantic | SearchMode::Hybrid) {
+            attach_semantic_context(&client, &data_dir, &db_path, &semantic_opts)?;
         }
+        Ok(client)
     };
-
-    let truncation_budgets = FieldBudgets {
-        snippet: snippet_budget,
-        content: content_budget,
-        title: title_budget,
-        fallback: fallback_budget,
-    };
-
-    // Build next cursor if more results remain
-    let next_cursor = if total_matches > offset_val + display_result.hits.len() {
-        let payload = serde_json::json!({
-            "offset": offset_val + display_result.hits.len(),
-            "limit": limit_val,
-        })
-        .to_string();
-        Some(BASE64_STANDARD.encode(payload))
-    } else {
-        None
+    let local_client = match daemon {
+        Some(_) => None,
+        None => Some(open_local_client()?),
     };
 
-    // Gather state meta for robot output (index/db freshness)
+    let mut filters = SearchFilters::default();
+    if !agents.is_empty() {
+        filters.agents = HashSet::from_iter(agents.iter().cloned());
+    }
+    if !workspaces.is_empty() {
+        filters.workspaces = HashSet::from_iter(workspaces.iter().cloned());
+    }
+    // The messages name the valid roles and tag characters themselves.
+    let filter_usage = |e: anyhow::Error| CliError::usage(e.to_string(), None);
+    for role in roles {
+        filters.add_role(role).map_err(filter_usage)?;
+    }
+    for tool in tools {
+        filters.add_tool(tool);
+    }
+    for tag in tags {
+        filters.add_tag(tag).map_err(filter_usage)?;
+    }
+    for file in files {
+        filters.add_file(file);
+    }
+    // Inline `role:` / `tool:` / `tag:` / `file:` tokens are filters, not search terms
+    let query = split_inline_filters(query, &mut filters).map_err(filter_usage)?;
+    let query = query.as_str();
+    filters.created_from = time_filter.since;
+    filters.created_to = time_filter.until;
+
+    // Apply source filter (P3.1)
+    if let Some(ref source_str) = source {
+        filters.source_filter = SourceFilter::parse(source_str);
+    }
+
+    // Apply session paths filter (for chained searches)
+    if let Some(ref sessions_from_arg) = sessions_from {
+        let session_paths = read_session_paths(sessions_from_arg).map_err(|e| CliError {
+            code: 2,
+            kind: "sessions-from",
+            message: format!("failed to read session paths: {e}"),
+            hint: Some("Provide a file path or '-' for stdin".to_string()),
+            retryable: false,
+        })?;
+        filters.session_paths = session_paths;
+    }
+
+    // Apply cursor overrides (base64-encoded JSON { "offset": usize, "limit": usize })
+    let mut limit_val = *limit;
+    let mut offset_val = *offset;
+    if let Some(ref cursor_str) = cursor {
+        let decoded = BASE64_STANDARD.decode(cursor_str).map_err(|e| CliError {
+            code: 2,
+            kind: "cursor-decode",
+            message: format!("invalid cursor: {e}"),
+            hint: Some("Pass cursor returned in previous _meta.next_cursor".to_string()),
+            retryable: false,
+        })?;
+        let cursor_json: serde_json::Value =
+            serde_json::from_slice(&decoded).map_err(|e| CliError {
+                code: 2,
+                kind: "cursor-parse",
+                message: format!("invalid cursor payload: {e}"),
+                hint: Some("Cursor should be base64 of {\"offset\":N,\"limit\":M}".to_string()),
+                retryable: false,
+            })?;
+        if let Some(o) = cursor_json
+            .get("offset")
+            .and_then(serde_json::Value::as_u64)
+        {
+            offset_val = o as usize;
+        }
+        if let Some(l) = cursor_json.get("limit").and_then(serde_json::Value::as_u64) {
+            limit_val = l as usize;
+        }
+    }
+
+    // Determine the effective output format
+    // Priority: robot_format CLI > json flag > CASS_OUTPUT_FORMAT > TOON_DEFAULT_FORMAT > robot_auto > None
+    let effective_robot = robot_format
+        .or(if *json { Some(RobotFormat::Json) } else { None })
+        .or_else(robot_format_from_env)
+        .or(if robot_auto {
+            Some(RobotFormat::Json)
+        } else {
+            None
+        });
+    let field_mask = resolve_field_mask(&fields, effective_robot, display_format);
+
+    // Parse aggregate fields if provided
+    let agg_fields = aggregate
+        .as_ref()
+        .map(|f| parse_aggregate_fields(f))
+        .unwrap_or_default();
+    let has_aggregation = !agg_fields.is_empty();
+    let by_session = granularity == SearchGranularity::Session;
+    if by_session && has_aggregation {
+        return Err(CliError::usage(
+            "--aggregate cannot be combined with --granularity session",
+            Some("Aggregate message hits instead (omit --granularity)".to_string()),
+        ));
+    }
+
+    // Handle dry-run mode: validate and analyze query without executing
+    if dry_run {
+        let explanation = QueryExplanation::analyze(query, &filters);
+        let elapsed_ms = start_time.elapsed().as_millis();
+
+        let output = serde_json::json!({
+            "dry_run": true,
+            "valid": explanation.warnings.iter().all(|w| !w.contains("error") && !w.contains("invalid")),
+            "query": query,
+            "explanation": explanation,
+            "estimated_cost": format!("{:?}", explanation.estimated_cost),
+            "warnings": explanation.warnings,
+            "request_id": request_id,
+            "_meta": {
+                "elapsed_ms": elapsed_ms,
+                "dry_run": true,
+            }
+        });
+        let Some(output) = crate::mcp::capture_structured_output(output) else {
+            return Ok(());
+        };
+
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string())
+        );
+        return Ok(());
+    }
+
+    // Use search_with_fallback to get full metadata (wildcard_fallback, cache_stats)
+    let sparse_threshold = 3; // Threshold for triggering wildcard fallback
+
+    // When aggregating, we need more results for accurate counts
+    // Fetch up to 1000 for aggregation starting at offset 0, then apply offset/limit
+    let (search_limit, search_offset) = if has_aggregation {
+        (1000.max(limit_val + offset_val), 0)
+    } else if by_session {
+        // Sessions are formed from a deeper message window, then paged
+        let window = (limit_val + offset_val).saturating_mul(SESSION_OVERFETCH);
+        (window.max(100), 0)
+    } else {
+        (limit_val, offset_val)
+    };
+
+    // Check if we're already past timeout before starting search
+    let timeout_duration = timeout_ms.map(Duration::from_millis);
+    if let Some(timeout) = timeout_duration
+        && start_time.elapsed() >= timeout
+    {
+        return Err(CliError {
+            code: 10,
+            kind: "timeout",
+            message: format!(
+                "Operation timed out after {}ms (before search started)",
+                timeout.as_millis()
+            ),
+            hint: Some("Increase --timeout value or simplify query".to_string()),
+            retryable: true,
+        });
+    }
+
+    // Log semantic options if any are set (bd-3bbv: flags are wired, infra pending)
+    if semantic_opts.model.is_some()
+        || semantic_opts.rerank
+        || semantic_opts.reranker.is_some()
+        || semantic_opts.use_daemon
+        || semantic_opts.approximate
+    {
+        tracing::debug!(
+            model = ?semantic_opts.model,
+            rerank = semantic_opts.rerank,
+            reranker = ?semantic_opts.reranker,
+            use_daemon = semantic_opts.use_daemon,
+            approximate = semantic_opts.approximate,
+            "Semantic search options configured"
+        );
+    }
+
+    // Track search timing breakdown (T7.4)
+    let search_start = Instant::now();
+    let daemon_result = daemon.as_ref().and_then(|router| {
+        router.search(&crate::daemon::protocol::SearchRequest {
+            data_dir: data_dir.clone(),
+            index_path: index_path.clone(),
+            db_path: db_path.clone(),
+            query: query.to_string(),
+            filters: (&filters).into(),
+            mode: effective_mode,
+            limit: search_limit,
+            offset: search_offset,
+            sparse_threshold,
+            field_mask: field_mask.bits(),
+            approximate,
+            model: semantic_opts.model.clone(),
+            chunk_size: 0,
+        })
+    });
+    let result = match daemon_result {
+        Some(result) => result,
+        None => {
+            let client = match local_client {
+                Some(client) => client,
+                None => open_local_client()?,
+            };
+            search_with_mode(
+                &client,
+                effective_mode,
+                query,
+                &filters,
+                search_limit,
+                search_offset,
+                sparse_threshold,
+                field_mask,
+                approximate,
+            )?
+        }
+    };
+    let search_ms = search_start.elapsed().as_millis() as u64;
+
+    // Apply reranking if enabled (bd-2t2d)
+    let rerank_start = Instant::now();
+    let result = if semantic_opts.rerank && !result.hits.is_empty() {
+        use crate::search::daemon_client::{
+            DaemonFallbackReranker, DaemonRetryConfig, NoopDaemonClient,
+        };
+        use crate::search::fastembed_reranker::FastEmbedReranker;
+        use crate::search::reranker::Reranker;
+
+        let model_dir = FastEmbedReranker::default_model_dir(&data_dir);
+        let local_reranker: Option<Arc<dyn Reranker>> =
+            match FastEmbedReranker::load_from_dir(&model_dir) {
+                Ok(reranker) => Some(Arc::new(reranker)),
+                Err(e) => {
+                    if !semantic_opts.use_daemon {
+                        tracing::debug!(error = %e, "Reranker not available, skipping rerank");
+                    }
+                    None
+                }
+            };
+
+        let reranker: Option<Arc<dyn Reranker>> = if semantic_opts.use_daemon {
+            let daemon = Arc::new(NoopDaemonClient::new("daemon-unconfigured"));
+            let config = DaemonRetryConfig::from_env();
+            Some(Arc::new(DaemonFallbackReranker::new(
+                daemon,
+                local_reranker,
+                config,
+            )))
+        } else {
+            local_reranker
+        };
+
+        if let Some(reranker) = reranker {
+            // Extract content from hits for reranking (use snippet if content is empty)
+            let docs: Vec<String> = result
+                .hits
+                .iter()
+                .map(|hit| {
+                    if hit.content.is_empty() {
+                        hit.snippet.clone()
+                    } else {
+                        hit.content.clone()
+                    }
+                })
+                .collect();
+
+            // Skip reranking if any document is empty (reranker rejects empty docs)
+            let has_empty_doc = docs.iter().any(|d| d.is_empty());
+            if has_empty_doc {
+                tracing::debug!("Skipping rerank: one or more hits have empty content and snippet");
+                result
+            } else {
+                let doc_refs: Vec<&str> = docs.iter().map(|s| s.as_str()).collect();
+
+                match reranker.rerank(query, &doc_refs) {
+                    Ok(scores) => {
+                        // Update scores and re-sort hits
+                        let mut scored_hits: Vec<_> = result
+                            .hits
+                            .into_iter()
+                            .zip(scores)
+                            .map(|(mut hit, score)| {
+                                hit.score = score;
+                                hit
+                            })
+                            .collect();
+                        scored_hits.sort_by(|a, b| {
+                            b.score
+                                .partial_cmp(&a.score)
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        });
+
+                        tracing::debug!(
+                            reranker_id = reranker.id(),
+                            hits_reranked = scored_hits.len(),
+                            "Reranking complete"
+                        );
+
+                        crate::search::query::SearchResult {
+                            hits: scored_hits,
+                            wildcard_fallback: result.wildcard_fallback,
+                            cache_stats: result.cache_stats,
+                            suggestions: result.suggestions,
+                            ann_stats: result.ann_stats,
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            error = %e,
+                            "Reranking failed, returning original results"
+                        );
+                        result
+                    }
+                }
+            }
+        } else {
+            result
+        }
+    } else {
+        result
+    };
+    // Track reranking time (0 if not applied) (T7.4)
+    let rerank_ms = if semantic_opts.rerank {
+        rerank_start.elapsed().as_millis() as u64
+    } else {
+        0
+    };
+
+    // Check if search exceeded timeout - return partial results with timeout indicator
+    let timed_out = timeout_duration.is_some_and(|t| start_time.elapsed() > t);
+
+    // Build query explanation if requested
+    let explanation = if explain {
+        Some(
+            QueryExplanation::analyze(query, &filters)
+                .with_wildcard_fallback(result.wildcard_fallback),
+        )
+    } else {
+        None
+    };
+
+    if by_session {
+        let sessions = group_hits_by_session(result.hits, evidence, Utc::now().timestamp_millis());
+        let total_sessions = sessions.len();
+        let page: Vec<_> = sessions
+            .into_iter()
+            .skip(offset_val)
+            .take(limit_val)
+            .collect();
+        let elapsed_ms = start_time.elapsed().as_millis() as u64;
+        if let Some(format) = effective_robot {
+            return output_session_results(
+                query,
+                limit_val,
+                offset_val,
+                &page,
+                total_sessions,
+                format,
+                robot_meta,
+                elapsed_ms,
+                effective_mode,
+                &fields,
+                FieldBudgets {
+                    snippet: max_content_length,
+                    content: max_content_length,
+                    title: max_content_length,
+                    fallback: max_content_length,
+                },
+                request_id,
+                explanation.as_ref(),
+                timed_out,
+            );
+        }
+        if page.is_empty() {
+            eprintln!("No results found.");
+            return Ok(());
+        }
+        for session in &page {
+            println!("================================================================");
+            println!(
+                "Score: {:.2} | {} matches | Agent: {} | WS: {}",
+                session.score, session.match_count, session.agent, session.workspace
+            );
+            println!("Path: {}", session.source_path);
+            if !session.title.is_empty() {
+                println!("Title: {}", session.title);
+            }
+            for hit in &session.evidence {
+                let snippet = hit.snippet.replace('\n', " ");
+                let snippet = if highlight {
+                    highlight_matches(&snippet, query, "**", "**")
+                } else {
+                    snippet
+                };
+                let line = hit
+                    .line_number
+                    .map_or_else(String::new, |l| format!("L{l} "));
+                println!("  - {line}{}", apply_wrap(&snippet, wrap));
+            }
+        }
+        println!("================================================================");
+        return Ok(());
+    }
+
+    // Compute aggregations and create display result based on mode
+    let (aggregations, display_result, total_matches) = if has_aggregation {
+        // Compute aggregations from all fetched results
+        let aggs = compute_aggregations(&result.hits, &agg_fields);
+        let total = result.hits.len();
+
+        // Apply offset and limit to get display hits
+        let display_hits: Vec<_> = result
+            .hits
+            .iter()
+            .skip(offset_val)
+            .take(limit_val)
+            .cloned()
+            .collect();
+
+        let display = crate::search::query::SearchResult {
+            hits: display_hits,
+            wildcard_fallback: result.wildcard_fallback,
+            cache_stats: result.cache_stats,
+            suggestions: result.suggestions.clone(),
+            ann_stats: result.ann_stats.clone(),
+        };
+        (aggs, display, total)
+    } else {
+        // No aggregation - use result as-is
+        let total = result.hits.len();
+        (Aggregations::default(), result, total)
+    };
+
+    let elapsed_ms = start_time.elapsed().as_millis() as u64;
+
+    // Derive per-field budgets, preferring snippet > content > title
+    let (snippet_budget, content_budget, title_budget, fallback_budget) = {
+        let base = max_content_length;
+        if let Some(tokens) = max_tokens {
+            let char_budget = tokens.saturating_mul(4);
+            let per_hit = char_budget / std::cmp::max(1, display_result.hits.len());
+            let snippet = std::cmp::max(16, (per_hit as f64 * 0.5) as usize);
+            let content = std::cmp::max(12, (per_hit as f64 * 0.35) as usize);
+            let title = std::cmp::max(8, (per_hit as f64 * 0.15) as usize);
+            (
+                Some(snippet),
+                Some(content),
+                Some(title),
+                base.map(|b| std::cmp::min(b, per_hit)),
+            )
+        } else {
+            (base, base, base, base)
+        }
+    };
+
+    let truncation_budgets = FieldBudgets {
+        snippet: snippet_budget,
+        content: content_budget,
+        title: title_budget,
+        fallback: fallback_budget,
+    };
+
+    // Build next cursor if more results remain
+    let next_cursor = if total_matches > offset_val + display_result.hits.len() {
+        let payload = serde_json::json!({
+            "offset": offset_val + display_result.hits.len(),
+            "limit": limit_val,
+        })
+        .to_string();
+        Some(BASE64_STANDARD.encode(payload))
+    } else {
+        None
+    };
+
+    // Gather state meta for robot output (index/db freshness)
     let state_meta = if robot_meta {
         Some(state_meta_json(
             &data_dir,
@@ -4973,18 +6249,138 @@ fn run_cli_search(
                 "Score: {:.2} | Agent: {} | WS: {}",
                 hit.score, hit.agent, hit.workspace
             );
-            println!("Path: {}", hit.source_path);
-            let snippet = hit.snippet.replace('\n', " ");
-            let snippet = if highlight {
-                highlight_matches(&snippet, query, "**", "**")
-            } else {
-                snippet
-            };
-            println!("Snippet: {}", apply_wrap(&snippet, wrap));
+            println!("Path: {}", hit.source_path);
+            let snippet = hit.snippet.replace('\n', " ");
+            let snippet = if highlight {
+                highlight_matches(&snippet, query, "**", "**")
+            } else {
+                snippet
+            };
+            println!("Snippet: {}", apply_wrap(&snippet, wrap));
+        }
+        println!("----------------------------------------------------------------");
+    }
+
+    Ok(())
+}
+
+/// Robot output for `--granularity session`: one object per conversation with
+/// its evidence messages nested (field selection and truncation apply to those).
+#[allow(clippy::too_many_arguments)]
+fn output_session_results(
+    query: &str,
+    limit: usize,
+    offset: usize,
+    sessions: &[crate::search::query::SessionHit],
+    total_sessions: usize,
+    format: RobotFormat,
+    include_meta: bool,
+    elapsed_ms: u64,
+    search_mode: crate::search::query::SearchMode,
+    fields: &Option<Vec<String>>,
+    truncation_budgets: FieldBudgets,
+    request_id: Option<String>,
+    explanation: Option<&crate::search::query::QueryExplanation>,
+    timed_out: bool,
+) -> CliResult<()> {
+    if matches!(format, RobotFormat::Sessions) {
+        for session in sessions {
+            println!("{}", session.source_path);
+        }
+        return Ok(());
+    }
+
+    let resolved_fields = expand_field_presets(fields);
+    let session_values: Vec<serde_json::Value> = sessions
+        .iter()
+        .map(|session| {
+            let evidence: Vec<serde_json::Value> = session
+                .evidence
+                .iter()
+                .map(|hit| filter_hit_fields(hit, &resolved_fields))
+                .map(|hit| apply_content_truncation(hit, truncation_budgets))
+                .collect();
+            serde_json::json!({
+                "source_path": session.source_path,
+                "agent": session.agent,
+                "workspace": session.workspace,
+                "title": session.title,
+                "score": session.score,
+                "max_score": session.max_score,
+                "sum_score": session.sum_score,
+                "match_count": session.match_count,
+                "latest_created_at": session.latest_created_at,
+                "source_id": session.source_id,
+                "origin_kind": session.origin_kind,
+                "origin_host": session.origin_host,
+                "evidence": evidence,
+            })
+        })
+        .collect();
+
+    let next_cursor = (total_sessions > offset + sessions.len()).then(|| {
+        let payload = serde_json::json!({
+            "offset": offset + sessions.len(),
+            "limit": limit,
+        })
+        .to_string();
+        BASE64_STANDARD.encode(payload)
+    });
+    let meta = serde_json::json!({
+        "elapsed_ms": elapsed_ms,
+        "search_mode": search_mode,
+        "granularity": SearchGranularity::Session,
+        "request_id": request_id,
+        "next_cursor": next_cursor,
+        "timed_out": timed_out,
+    });
+
+    if matches!(format, RobotFormat::Jsonl) {
+        if include_meta {
+            println!("{}", serde_json::json!({ "_meta": meta }));
+        }
+        for value in &session_values {
+            println!("{value}");
+        }
+        return Ok(());
+    }
+
+    let mut payload = serde_json::json!({
+        "query": query,
+        "granularity": SearchGranularity::Session,
+        "limit": limit,
+        "offset": offset,
+        "count": session_values.len(),
+        "total_sessions": total_sessions,
+        "sessions": session_values,
+        "request_id": request_id,
+    });
+    if let serde_json::Value::Object(ref mut map) = payload {
+        if let Some(exp) = explanation {
+            map.insert(
+                "explanation".to_string(),
+                serde_json::to_value(exp).unwrap_or_default(),
+            );
+        }
+        if include_meta {
+            map.insert("_meta".to_string(), meta);
         }
-        println!("----------------------------------------------------------------");
     }
+    let Some(payload) = crate::mcp::capture_structured_output(payload) else {
+        return Ok(());
+    };
 
+    match format {
+        RobotFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&payload).unwrap_or_default()
+        ),
+        RobotFormat::Toon => print!(
+            "{}",
+            toon_rust::encode(payload, Some(toon_encode_options_from_env()))
+        ),
+        _ => println!("{payload}"),
+    }
     Ok(())
 }
 
@@ -5315,6 +6711,9 @@ fn toon_encode_options_from_env() -> toon_rust::EncodeOptions {
 }
 
 fn output_structured_value(payload: serde_json::Value, format: RobotFormat) -> CliResult<()> {
+    let Some(payload) = crate::mcp::capture_structured_output(payload) else {
+        return Ok(());
+    };
     match format {
         RobotFormat::Json => {
             println!(
@@ -5529,6 +6928,9 @@ fn output_robot_results(
                     );
                 }
             }
+            let Some(payload) = crate::mcp::capture_structured_output(payload) else {
+                return Ok(());
+            };
 
             let out = serde_json::to_string_pretty(&payload).map_err(|e| CliError {
                 code: 9,
@@ -6212,6 +7614,12 @@ fn run_diag(
         ("cursor", &cursor_path, cursor_path.exists()),
         ("chatgpt", &chatgpt_path, chatgpt_path.exists()),
     ];
+    // Windsurf only indexes exported trajectories; its evidence says how many
+    // live conversations are missing an export.
+    let windsurf = {
+        use crate::connectors::Connector;
+        crate::connectors::windsurf::WindsurfConnector::new().detect()
+    };
 
     let platform = std::env::consts::OS;
     let arch = std::env::consts::ARCH;
@@ -6254,7 +7662,14 @@ fn run_diag(
                     "path": path.display().to_string(),
                     "found": exists,
                 })
-            }).collect::<Vec<_>>(),
+            }).chain(windsurf.root_paths.iter().zip(&windsurf.evidence).map(|(path, evidence)| {
+                serde_json::json!({
+                    "name": "windsurf",
+                    "path": path.display().to_string(),
+                    "found": true,
+                    "evidence": evidence,
+                })
+            })).collect::<Vec<_>>(),
         });
         return output_structured_value(payload, fmt);
     }
@@ -6299,6 +7714,9 @@ fn run_diag(
         let status = if *exists { "✓" } else { "✗" };
         println!("  {} {}: {}", status, name, path.display());
     }
+    for evidence in &windsurf.evidence {
+        println!("  ✓ windsurf: {evidence}");
+    }
 
     Ok(())
 }
@@ -6708,16 +8126,99 @@ fn ensure_cass_origin(
     }
 }
 
+/// Load a stored conversation's messages and convert it back into the
+/// normalized form `TantivyIndex::add_messages` expects, re-attaching
+/// provenance and user tags under `metadata.cass`.
+///
+/// Returns `Ok(None)` for rows without an id.
+fn stored_conversation_for_index(
+    storage: &crate::storage::sqlite::SqliteStorage,
+    conv: crate::model::types::Conversation,
+    source_map: &std::collections::HashMap<
+        String,
+        (crate::sources::provenance::SourceKind, Option<String>),
+    >,
+    tag_map: &std::collections::HashMap<(String, String), Vec<String>>,
+) -> CliResult<Option<crate::connectors::NormalizedConversation>> {
+    use crate::connectors::{NormalizedConversation, NormalizedMessage};
+    use crate::model::types::MessageRole;
+    use crate::sources::provenance::{LOCAL_SOURCE_ID, SourceKind};
+
+    let Some(conv_id) = conv.id else {
+        return Ok(None);
+    };
+
+    let messages = storage
+        .fetch_messages(conv_id)
+        .map_err(|e| CliError::unknown(format!("failed to fetch messages: {e}")))?;
+
+    let mut metadata = conv.metadata_json.clone();
+    let (kind, host_label) = source_map.get(&conv.source_id).cloned().unwrap_or_else(|| {
+        let fallback_kind = if conv.source_id == LOCAL_SOURCE_ID {
+            SourceKind::Local
+        } else {
+            SourceKind::Ssh
+        };
+        (fallback_kind, None)
+    });
+
+    let host = conv.origin_host.as_deref().or(host_label.as_deref());
+    ensure_cass_origin(&mut metadata, &conv.source_id, kind, host);
+
+    let tag_key = (
+        conv.source_id.clone(),
+        conv.source_path.to_string_lossy().into_owned(),
+    );
+    if let Some(tags) = tag_map.get(&tag_key)
+        && let Some(cass) = metadata.get_mut("cass").and_then(|c| c.as_object_mut())
+    {
+        cass.insert("tags".to_string(), serde_json::json!(tags));
+    }
+
+    let normalized_messages: Vec<NormalizedMessage> = messages
+        .into_iter()
+        .map(|msg| {
+            let role = match msg.role {
+                MessageRole::User => "user".to_string(),
+                MessageRole::Agent => "assistant".to_string(),
+                MessageRole::Tool => "tool".to_string(),
+                MessageRole::System => "system".to_string(),
+                MessageRole::Other(other) => other,
+            };
+
+            NormalizedMessage {
+                idx: msg.idx,
+                role,
+                author: msg.author,
+                created_at: msg.created_at,
+                content: msg.content,
+                extra: msg.extra_json,
+                snippets: Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok(Some(NormalizedConversation {
+        agent_slug: conv.agent_slug,
+        external_id: conv.external_id,
+        title: conv.title,
+        workspace: conv.workspace,
+        source_path: conv.source_path,
+        started_at: conv.started_at,
+        ended_at: conv.ended_at,
+        metadata,
+        messages: normalized_messages,
+    }))
+}
+
 fn rebuild_tantivy_from_db(
     db_path: &Path,
     data_dir: &Path,
     total_conversations: usize,
     progress: Option<std::sync::Arc<indexer::IndexingProgress>>,
 ) -> CliResult<usize> {
-    use crate::connectors::{NormalizedConversation, NormalizedMessage};
-    use crate::model::types::MessageRole;
     use crate::search::tantivy::TantivyIndex;
-    use crate::sources::provenance::{LOCAL_SOURCE_ID, SourceKind};
+    use crate::sources::provenance::SourceKind;
     use crate::storage::sqlite::SqliteStorage;
     use std::collections::HashMap;
     use std::sync::atomic::Ordering;
@@ -6735,6 +8236,7 @@ fn rebuild_tantivy_from_db(
     for source in sources {
         source_map.insert(source.id, (source.kind, source.host_label));
     }
+    let tag_map = storage.session_tag_map().unwrap_or_default();
 
     let index_path = crate::search::tantivy::index_dir(data_dir).map_err(|e| CliError {
         code: 5,
@@ -6782,63 +8284,12 @@ fn rebuild_tantivy_from_db(
         }
 
         for conv in batch {
-            let Some(conv_id) = conv.id else {
+            let Some(normalized) =
+                stored_conversation_for_index(&storage, conv, &source_map, &tag_map)?
+            else {
                 continue;
             };
 
-            let messages = storage
-                .fetch_messages(conv_id)
-                .map_err(|e| CliError::unknown(format!("failed to fetch messages: {e}")))?;
-
-            let mut metadata = conv.metadata_json.clone();
-            let (kind, host_label) =
-                source_map.get(&conv.source_id).cloned().unwrap_or_else(|| {
-                    let fallback_kind = if conv.source_id == LOCAL_SOURCE_ID {
-                        SourceKind::Local
-                    } else {
-                        SourceKind::Ssh
-                    };
-                    (fallback_kind, None)
-                });
-
-            let host = conv.origin_host.as_deref().or(host_label.as_deref());
-            ensure_cass_origin(&mut metadata, &conv.source_id, kind, host);
-
-            let normalized_messages: Vec<NormalizedMessage> = messages
-                .into_iter()
-                .map(|msg| {
-                    let role = match msg.role {
-                        MessageRole::User => "user".to_string(),
-                        MessageRole::Agent => "assistant".to_string(),
-                        MessageRole::Tool => "tool".to_string(),
-                        MessageRole::System => "system".to_string(),
-                        MessageRole::Other(other) => other,
-                    };
-
-                    NormalizedMessage {
-                        idx: msg.idx,
-                        role,
-                        author: msg.author,
-                        created_at: msg.created_at,
-                        content: msg.content,
-                        extra: msg.extra_json,
-                        snippets: Vec::new(),
-                    }
-                })
-                .collect();
-
-            let normalized = NormalizedConversation {
-                agent_slug: conv.agent_slug,
-                external_id: conv.external_id,
-                title: conv.title,
-                workspace: conv.workspace,
-                source_path: conv.source_path,
-                started_at: conv.started_at,
-                ended_at: conv.ended_at,
-                metadata,
-                messages: normalized_messages,
-            };
-
             indexed_docs += normalized.messages.len();
             t_index
                 .add_messages(&normalized, &normalized.messages)
@@ -7594,6 +9045,7 @@ fn run_doctor(
                     semantic: false,
                     build_hnsw: false,
                     embedder: "fastembed".to_string(),
+                    quantization: crate::search::vector_index::Quantization::F32,
                     progress: Some(progress.clone()),
                 };
 
@@ -8293,6 +9745,15 @@ fn run_capabilities(json: bool) -> CliResult<()> {
             "expand_command".to_string(),
             "timeline_command".to_string(),
             "highlight_matches".to_string(),
+            "external_connectors".to_string(),
+            "diff_command".to_string(),
+            "saved_searches".to_string(),
+            "session_granularity".to_string(),
+            "tool_events".to_string(),
+            "mcp_server".to_string(),
+            "daemon_search".to_string(),
+            "http_api".to_string(),
+            "scheduled_sync".to_string(),
         ],
         connectors: vec![
             "codex".to_string(),
@@ -8307,6 +9768,10 @@ fn run_capabilities(json: bool) -> CliResult<()> {
             "cursor".to_string(),
             "chatgpt".to_string(),
             "pi_agent".to_string(),
+            "continue".to_string(),
+            "windsurf".to_string(),
+            "copilot".to_string(),
+            "roo_code".to_string(),
         ],
         limits: CapabilitiesLimits {
             max_limit: 10000,
@@ -8473,6 +9938,62 @@ fn run_introspect(json: bool) -> CliResult<()> {
     Ok(())
 }
 
+/// Serve the MCP tool set over stdio until the client closes stdin.
+fn run_mcp(data_dir: Option<PathBuf>, db: Option<PathBuf>) -> CliResult<()> {
+    let server = crate::mcp::McpServer::new(data_dir, db);
+    server
+        .serve(io::stdin().lock(), io::stdout().lock())
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "io",
+            message: format!("mcp transport failed: {e}"),
+            hint: None,
+            retryable: true,
+        })
+}
+
+fn run_serve(
+    bind: &str,
+    token: Option<String>,
+    cors_origins: Vec<String>,
+    data_dir: Option<PathBuf>,
+    db: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::serve::{ApiServer, ServeConfig};
+
+    let config = ServeConfig {
+        data_dir,
+        db,
+        token: token.filter(|token| !token.is_empty()),
+        cors_origins,
+    };
+    let listener = crate::serve::bind(bind, &config)?;
+    if let Ok(addr) = listener.local_addr() {
+        eprintln!("cass API listening on http://{addr} (schema: /openapi.json)");
+    }
+    ApiServer::new(config).serve(listener);
+    Ok(())
+}
+
+fn run_daemon(socket: Option<PathBuf>, data_dir: Option<PathBuf>) -> CliResult<()> {
+    use crate::daemon::{DaemonConfig, ModelDaemon, ModelManager};
+
+    let data_dir = data_dir.unwrap_or_else(default_data_dir);
+    let mut config = DaemonConfig::from_env();
+    if let Some(socket) = socket {
+        config.socket_path = socket;
+    }
+    ModelDaemon::new(config, ModelManager::new(&data_dir))
+        .run()
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "io",
+            message: format!("daemon failed: {e}"),
+            hint: None,
+            retryable: true,
+        })
+}
+
 /// Run export based on JSON config file.
 fn run_config_based_export(
     config: &crate::pages::config_input::PagesConfig,
@@ -8592,108 +10113,476 @@ fn run_config_based_export(
         )?;
     }
 
-    // Build bundle
-    let bundle_config = crate::pages::bundle::BundleConfig {
-        title: wizard_state.title.clone(),
-        description: wizard_state.description.clone(),
-        hide_metadata: wizard_state.hide_metadata,
-        recovery_secret,
-        generate_qr: wizard_state.generate_qr,
-        generated_docs: vec![],
+    // Build bundle
+    let bundle_config = crate::pages::bundle::BundleConfig {
+        title: wizard_state.title.clone(),
+        description: wizard_state.description.clone(),
+        hide_metadata: wizard_state.hide_metadata,
+        recovery_secret,
+        generate_qr: wizard_state.generate_qr,
+        generated_docs: vec![],
+    };
+
+    let bundle_builder = crate::pages::bundle::BundleBuilder::with_config(bundle_config);
+    let bundle_result = bundle_builder.build(&encrypted_dir, output_dir, |_phase, _msg| {})?;
+
+    // Optional deployment
+    let deploy_result = deploy_pages_site(
+        wizard_state.target,
+        &bundle_result.site_dir,
+        false,
+        wizard_state.repo_name.clone(),
+        wizard_state.cloudflare_branch.clone(),
+        wizard_state.cloudflare_account_id.clone(),
+        wizard_state.cloudflare_api_token.clone(),
+    )?;
+
+    // Output results
+    if json_output {
+        let result = serde_json::json!({
+            "status": "success",
+            "output_dir": output_dir,
+            "bundle_dir": output_dir,
+            "site_dir": bundle_result.site_dir,
+            "private_dir": bundle_result.private_dir,
+            "stats": {
+                "conversations": stats.conversations_processed,
+                "messages": stats.messages_processed,
+            },
+            "encryption": {
+                "enabled": encryption_enabled,
+                "generate_recovery": wizard_state.generate_recovery && encryption_enabled,
+                "generate_qr": wizard_state.generate_qr && encryption_enabled,
+            },
+            "bundle": {
+                "total_files": bundle_result.total_files,
+                "fingerprint": bundle_result.fingerprint,
+            },
+            "deployment": deploy_result,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!("Export complete:");
+        println!("  Output: {}", output_dir.display());
+        println!("  Site: {}", bundle_result.site_dir.display());
+        println!("  Private: {}", bundle_result.private_dir.display());
+        println!("  Conversations: {}", stats.conversations_processed);
+        println!("  Messages: {}", stats.messages_processed);
+        if encryption_enabled {
+            println!("  Encryption: enabled");
+        } else {
+            println!("  Encryption: DISABLED (content is public)");
+        }
+        println!("  Fingerprint: {}", &bundle_result.fingerprint[..8]);
+
+        if let Some(deploy) = deploy_result {
+            println!("  Deployment: {}", deploy);
+        }
+    }
+
+    Ok(())
+}
+
+/// Deploy a built `site/` directory to GitHub Pages or Cloudflare Pages.
+///
+/// Returns the deployer's result as JSON, or `None` for a local target.
+/// Cloudflare credentials fall back to `CLOUDFLARE_ACCOUNT_ID` and
+/// `CLOUDFLARE_API_TOKEN`. With `existing`, the site replaces an earlier
+/// deployment instead of requiring a fresh GitHub repository.
+fn deploy_pages_site(
+    target: crate::pages::wizard::DeployTarget,
+    site_dir: &Path,
+    existing: bool,
+    repo: Option<String>,
+    branch: Option<String>,
+    account_id: Option<String>,
+    api_token: Option<String>,
+) -> anyhow::Result<Option<serde_json::Value>> {
+    match target {
+        crate::pages::wizard::DeployTarget::Local => Ok(None),
+        crate::pages::wizard::DeployTarget::GitHubPages => {
+            let repo =
+                repo.ok_or_else(|| anyhow::anyhow!("GitHub deployment requires deployment.repo"))?;
+            let deployer = crate::pages::deploy_github::GitHubDeployer::new(repo);
+            // An existing deployment only needs the changed files pushed on top.
+            let result = if existing {
+                deployer.update(site_dir, |_phase, _msg| {})?
+            } else {
+                deployer.deploy(site_dir, |_phase, _msg| {})?
+            };
+            Ok(Some(serde_json::to_value(result)?))
+        }
+        crate::pages::wizard::DeployTarget::CloudflarePages => {
+            let deployer = crate::pages::deploy_cloudflare::CloudflareDeployer::new(
+                crate::pages::deploy_cloudflare::CloudflareConfig {
+                    project_name: repo.unwrap_or_else(|| "cass-archive".to_string()),
+                    custom_domain: None,
+                    create_if_missing: true,
+                    branch: branch.unwrap_or_else(|| "main".to_string()),
+                    account_id: account_id.or_else(|| dotenvy::var("CLOUDFLARE_ACCOUNT_ID").ok()),
+                    api_token: api_token.or_else(|| dotenvy::var("CLOUDFLARE_API_TOKEN").ok()),
+                },
+            );
+            Ok(Some(serde_json::to_value(
+                deployer.deploy(site_dir, |_phase, _msg| {})?,
+            )?))
+        }
+    }
+}
+
+/// Directory holding an archive's `config.json`: `dir` itself, or `dir/site`
+/// for a bundle root as written by `cass pages`.
+fn resolve_pages_site_dir(dir: &Path) -> CliResult<PathBuf> {
+    if dir.join("config.json").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    let site = dir.join("site");
+    if site.join("config.json").is_file() {
+        return Ok(site);
+    }
+    Err(CliError {
+        code: 2,
+        kind: "pages",
+        message: format!("No encrypted archive found at {}", dir.display()),
+        hint: Some(
+            "Pass the export output directory or its site/ directory (must contain config.json)."
+                .to_string(),
+        ),
+        retryable: false,
+    })
+}
+
+/// Use `value` if given, otherwise prompt for it on an interactive terminal.
+fn pages_key_password(
+    value: Option<String>,
+    flag: &str,
+    prompt: &str,
+    confirm: bool,
+    json: bool,
+) -> CliResult<String> {
+    if let Some(value) = value.filter(|v| !v.is_empty()) {
+        return Ok(value);
+    }
+    if json || !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        let env_var = if flag == "--password" {
+            "CASS_PAGES_PASSWORD"
+        } else {
+            "CASS_PAGES_NEW_PASSWORD"
+        };
+        return Err(CliError {
+            code: 2,
+            kind: "pages",
+            message: format!("{flag} is required"),
+            hint: Some(format!("Pass {flag} or set {env_var}.")),
+            retryable: false,
+        });
+    }
+    let theme = dialoguer::theme::ColorfulTheme::default();
+    let mut input = dialoguer::Password::with_theme(&theme).with_prompt(prompt);
+    if confirm {
+        input = input.with_confirmation("Confirm password", "Passwords do not match");
+    }
+    input.interact().map_err(|e| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("Failed to read password: {e}"),
+        hint: None,
+        retryable: false,
+    })
+}
+
+/// Run `cass pages keys ...`.
+fn run_pages_keys(cmd: PagesKeysCommand, robot_mode: bool) -> CliResult<()> {
+    let key_error = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("Key operation failed: {e:#}"),
+        hint: None,
+        retryable: false,
+    };
+
+    let (json, site_dir, mut payload, deploy) = match cmd {
+        PagesKeysCommand::List { archive, json } => {
+            let site_dir = resolve_pages_site_dir(&archive)?;
+            let result = crate::pages::key_management::key_list(&site_dir).map_err(key_error)?;
+            if json || robot_mode {
+                let payload = serde_json::to_value(&result).unwrap_or_default();
+                return output_structured_value(payload, RobotFormat::Json);
+            }
+            println!("Archive: {}", site_dir.display());
+            println!("Export ID: {}", result.export_id);
+            println!("Key slots ({}):", result.active_slots);
+            for slot in &result.slots {
+                println!("  [{}] {} ({})", slot.id, slot.slot_type, slot.kdf);
+            }
+            return Ok(());
+        }
+        PagesKeysCommand::Add {
+            archive,
+            recovery,
+            password,
+            new_password,
+            deploy,
+            json,
+        } => {
+            let json = json || robot_mode;
+            let site_dir = resolve_pages_site_dir(&archive)?;
+            let password =
+                pages_key_password(password, "--password", "Current password", false, json)?;
+            let payload = if recovery {
+                let (slot_id, secret) =
+                    crate::pages::key_management::key_add_recovery(&site_dir, &password)
+                        .map_err(key_error)?;
+                serde_json::json!({
+                    "slot_id": slot_id,
+                    "slot_type": "recovery",
+                    "recovery_secret": secret.encoded(),
+                })
+            } else {
+                let new_password =
+                    pages_key_password(new_password, "--new-password", "New password", true, json)?;
+                let slot_id = crate::pages::key_management::key_add_password(
+                    &site_dir,
+                    &password,
+                    &new_password,
+                )
+                .map_err(key_error)?;
+                serde_json::json!({ "slot_id": slot_id, "slot_type": "password" })
+            };
+            (json, site_dir, payload, deploy)
+        }
+        PagesKeysCommand::Revoke {
+            archive,
+            slot,
+            password,
+            deploy,
+            json,
+        } => {
+            let json = json || robot_mode;
+            let site_dir = resolve_pages_site_dir(&archive)?;
+            let password = pages_key_password(
+                password,
+                "--password",
+                "Password (from a slot you are keeping)",
+                false,
+                json,
+            )?;
+            let result = crate::pages::key_management::key_revoke(&site_dir, &password, slot)
+                .map_err(key_error)?;
+            let payload = serde_json::to_value(&result).unwrap_or_default();
+            (json, site_dir, payload, deploy)
+        }
+        PagesKeysCommand::Rotate {
+            archive,
+            password,
+            new_password,
+            no_recovery,
+            deploy,
+            json,
+        } => {
+            let json = json || robot_mode;
+            let site_dir = resolve_pages_site_dir(&archive)?;
+            let had_recovery = crate::pages::key_management::key_list(&site_dir)
+                .map_err(key_error)?
+                .slots
+                .iter()
+                .any(|slot| slot.slot_type == "recovery");
+            let password =
+                pages_key_password(password, "--password", "Current password", false, json)?;
+            let new_password =
+                pages_key_password(new_password, "--new-password", "New password", true, json)?;
+            let result = crate::pages::key_management::key_rotate(
+                &site_dir,
+                &password,
+                &new_password,
+                had_recovery && !no_recovery,
+                |_progress| {},
+            )
+            .map_err(key_error)?;
+            let payload = serde_json::to_value(&result).unwrap_or_default();
+            (json, site_dir, payload, deploy)
+        }
+    };
+
+    let deployment = match deploy.redeploy {
+        Some(target) => deploy_pages_site(
+            target.to_wizard_target(),
+            &site_dir,
+            true,
+            deploy.project,
+            deploy.branch,
+            deploy.account_id,
+            deploy.api_token,
+        )
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "pages",
+            message: format!("Keys updated, but redeploy failed: {e:#}"),
+            hint: Some("Fix the deployment settings and rerun with --redeploy.".to_string()),
+            retryable: true,
+        })?,
+        None => None,
+    };
+
+    if json {
+        payload["site_dir"] = serde_json::json!(site_dir);
+        payload["deployment"] = deployment.unwrap_or(serde_json::Value::Null);
+        return output_structured_value(payload, RobotFormat::Json);
+    }
+
+    if let Some(slot_id) = payload.get("slot_id") {
+        println!("Added key slot {slot_id} to {}", site_dir.display());
+    } else if let Some(slot_id) = payload.get("revoked_slot_id") {
+        println!(
+            "Revoked key slot {slot_id}; {} slot(s) remain",
+            payload["remaining_slots"]
+        );
+    } else {
+        println!(
+            "Rotated the archive key; {} slot(s) now unlock it",
+            payload["slot_count"]
+        );
+        println!(
+            "The recovery secret and QR code in private/ from the original export \
+             no longer match this archive."
+        );
+    }
+    if let Some(secret) = payload
+        .get("recovery_secret")
+        .and_then(serde_json::Value::as_str)
+    {
+        println!();
+        println!("Recovery secret (store it somewhere safe; it is not saved anywhere):");
+        println!("  {secret}");
+    }
+    if let Some(deployment) = deployment {
+        println!("Redeployed: {deployment}");
+    }
+    Ok(())
+}
+
+/// Run `cass pages --update DIR`.
+fn run_pages_update(
+    archive: &Path,
+    db_path: &Path,
+    password: Option<String>,
+    fallback_filter: crate::pages::export::ExportFilter,
+    deploy: PagesRedeployArgs,
+    json: bool,
+) -> CliResult<()> {
+    let site_dir = resolve_pages_site_dir(archive)?;
+    if !db_path.exists() {
+        return Err(CliError {
+            code: 3,
+            kind: "missing-db",
+            message: format!("Database not found at {}", db_path.display()),
+            hint: Some("Run 'cass index --full' first".to_string()),
+            retryable: true,
+        });
+    }
+
+    let encrypted = std::fs::read(site_dir.join("config.json"))
+        .ok()
+        .and_then(|bytes| {
+            serde_json::from_slice::<crate::pages::archive_config::ArchiveConfig>(&bytes).ok()
+        })
+        .is_none_or(|config| config.is_encrypted());
+    let password = if encrypted {
+        Some(pages_key_password(
+            password,
+            "--password",
+            "Archive password",
+            false,
+            json,
+        )?)
+    } else {
+        None
     };
 
-    let bundle_builder = crate::pages::bundle::BundleBuilder::with_config(bundle_config);
-    let bundle_result = bundle_builder.build(&encrypted_dir, output_dir, |_phase, _msg| {})?;
+    let report = crate::pages::update::update_archive(
+        &site_dir,
+        db_path,
+        password.as_deref(),
+        fallback_filter,
+        |_phase, msg| {
+            if !json {
+                eprintln!("{msg}");
+            }
+        },
+    )
+    .map_err(|e| CliError {
+        code: 9,
+        kind: "pages",
+        message: format!("Update failed: {e:#}"),
+        hint: None,
+        retryable: false,
+    })?;
 
-    // Optional deployment
-    let deploy_result = match wizard_state.target {
-        crate::pages::wizard::DeployTarget::Local => None,
-        crate::pages::wizard::DeployTarget::GitHubPages => {
-            let repo = wizard_state
-                .repo_name
-                .as_ref()
-                .ok_or_else(|| anyhow::anyhow!("GitHub deployment requires deployment.repo"))?;
-            let deployer = crate::pages::deploy_github::GitHubDeployer::new(repo.clone());
-            Some(serde_json::to_value(
-                deployer.deploy(&bundle_result.site_dir, |_phase, _msg| {})?,
-            )?)
-        }
-        crate::pages::wizard::DeployTarget::CloudflarePages => {
-            let project_name = wizard_state
-                .repo_name
-                .clone()
-                .unwrap_or_else(|| "cass-archive".to_string());
-            let branch = wizard_state
-                .cloudflare_branch
-                .clone()
-                .unwrap_or_else(|| "main".to_string());
-            let account_id = wizard_state
-                .cloudflare_account_id
-                .clone()
-                .or_else(|| dotenvy::var("CLOUDFLARE_ACCOUNT_ID").ok());
-            let api_token = wizard_state
-                .cloudflare_api_token
-                .clone()
-                .or_else(|| dotenvy::var("CLOUDFLARE_API_TOKEN").ok());
-            let deployer = crate::pages::deploy_cloudflare::CloudflareDeployer::new(
-                crate::pages::deploy_cloudflare::CloudflareConfig {
-                    project_name: project_name.clone(),
-                    custom_domain: None,
-                    create_if_missing: true,
-                    branch,
-                    account_id,
-                    api_token,
-                },
-            );
-            Some(serde_json::to_value(
-                deployer.deploy(&bundle_result.site_dir, |_phase, _msg| {})?,
-            )?)
-        }
+    // Deploy even when nothing was appended so a failed push can be retried.
+    let deployment = match deploy.redeploy {
+        Some(target) => deploy_pages_site(
+            target.to_wizard_target(),
+            &site_dir,
+            true,
+            deploy.project,
+            deploy.branch,
+            deploy.account_id,
+            deploy.api_token,
+        )
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "pages",
+            message: format!("Archive updated, but deploy failed: {e:#}"),
+            hint: Some(
+                "Fix the deployment settings and rerun the same --update command.".to_string(),
+            ),
+            retryable: true,
+        })?,
+        None => None,
     };
 
-    // Output results
-    if json_output {
-        let result = serde_json::json!({
-            "status": "success",
-            "output_dir": output_dir,
-            "bundle_dir": output_dir,
-            "site_dir": bundle_result.site_dir,
-            "private_dir": bundle_result.private_dir,
-            "stats": {
-                "conversations": stats.conversations_processed,
-                "messages": stats.messages_processed,
-            },
-            "encryption": {
-                "enabled": encryption_enabled,
-                "generate_recovery": wizard_state.generate_recovery && encryption_enabled,
-                "generate_qr": wizard_state.generate_qr && encryption_enabled,
-            },
-            "bundle": {
-                "total_files": bundle_result.total_files,
-                "fingerprint": bundle_result.fingerprint,
-            },
-            "deployment": deploy_result,
-        });
-        println!("{}", serde_json::to_string_pretty(&result)?);
+    if json {
+        let mut payload = serde_json::to_value(&report).unwrap_or_default();
+        payload["up_to_date"] = serde_json::json!(report.is_up_to_date());
+        payload["deployment"] = deployment.unwrap_or(serde_json::Value::Null);
+        return output_structured_value(payload, RobotFormat::Json);
+    }
+
+    let newest = report
+        .high_water_mark
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| "-".to_string());
+    if report.is_up_to_date() {
+        println!(
+            "{} is up to date (newest conversation: {newest})",
+            site_dir.display()
+        );
     } else {
-        println!("Export complete:");
-        println!("  Output: {}", output_dir.display());
-        println!("  Site: {}", bundle_result.site_dir.display());
-        println!("  Private: {}", bundle_result.private_dir.display());
-        println!("  Conversations: {}", stats.conversations_processed);
-        println!("  Messages: {}", stats.messages_processed);
-        if encryption_enabled {
-            println!("  Encryption: enabled");
-        } else {
-            println!("  Encryption: DISABLED (content is public)");
+        println!(
+            "Appended {} conversation(s), extended {} ({} messages) in {}",
+            report.conversations_added,
+            report.conversations_extended,
+            report.messages_added,
+            site_dir.display()
+        );
+        println!("Newest conversation: {newest}");
+        println!(
+            "Payload: {} file(s) rewritten, {} removed, {} unchanged",
+            report.chunks_rewritten.len(),
+            report.chunks_removed.len(),
+            report.chunks_unchanged
+        );
+        if report.analytics_refreshed {
+            println!("Refreshed analytics in data/");
         }
-        println!("  Fingerprint: {}", &bundle_result.fingerprint[..8]);
-
-        if let Some(deploy) = deploy_result {
-            println!("  Deployment: {}", deploy);
+        if let Some(fingerprint) = &report.fingerprint {
+            println!("Integrity fingerprint: {fingerprint}");
         }
     }
-
+    if let Some(deployment) = deployment {
+        println!("Deployed: {deployment}");
+    }
     Ok(())
 }
 
@@ -9301,6 +11190,51 @@ fn build_response_schemas() -> std::collections::HashMap<String, serde_json::Val
         }),
     );
 
+    schemas.insert(
+        "related".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "file": { "type": "string" },
+                "line": { "type": ["integer", "null"] },
+                "symbol": { "type": ["string", "null"] },
+                "resolved_path": { "type": ["string", "null"] },
+                "signals": {
+                    "type": "object",
+                    "properties": {
+                        "file_references": { "type": "integer" },
+                        "symbol": { "type": "boolean" },
+                        "semantic": { "type": "boolean" }
+                    }
+                },
+                "sessions": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "source_path": { "type": "string" },
+                            "title": { "type": ["string", "null"] },
+                            "agent": { "type": "string" },
+                            "workspace": { "type": ["string", "null"] },
+                            "score": { "type": "number" },
+                            "line_number": { "type": ["integer", "null"] },
+                            "edits": { "type": "integer" },
+                            "reads": { "type": "integer" },
+                            "snippets": { "type": "integer" },
+                            "line_snippets": { "type": "integer" },
+                            "workspace_match": { "type": "boolean" },
+                            "symbol_hits": { "type": "integer" },
+                            "semantic_score": { "type": ["number", "null"] },
+                            "last_activity": { "type": ["integer", "null"] }
+                        }
+                    }
+                },
+                "count": { "type": "integer" },
+                "warnings": { "type": "array", "items": { "type": "string" } }
+            }
+        }),
+    );
+
     schemas.insert(
         "health".to_string(),
         json!({
@@ -9505,6 +11439,7 @@ fn spawn_background_indexer(
             semantic: false,
             build_hnsw: false,
             embedder: "fastembed".to_string(),
+            quantization: crate::search::vector_index::Quantization::F32,
             progress,
         };
         // Pass the receiver to run_index so it can listen for commands
@@ -9523,6 +11458,27 @@ fn spawn_background_indexer(
     Some(tx)
 }
 
+/// Run the source sync scheduler on a background thread, asking the watcher
+/// to rescan whichever mirror roots each sync changed.
+fn spawn_sync_scheduler(data_dir: &Path, events: Sender<IndexerEvent>) {
+    let scheduler = crate::sources::SyncScheduler::new(data_dir);
+    std::thread::spawn(move || {
+        scheduler.run(crate::sources::scheduler::DEFAULT_TICK, |synced| {
+            let roots: Vec<PathBuf> = synced
+                .iter()
+                .flat_map(|sync| sync.changed_roots.iter().cloned())
+                .collect();
+            // Stop once the watcher has gone away.
+            roots.is_empty()
+                || events
+                    .send(IndexerEvent::Command(indexer::ReindexCommand::Rescan(
+                        roots,
+                    )))
+                    .is_ok()
+        });
+    });
+}
+
 #[allow(clippy::too_many_arguments)]
 fn run_index_with_data(
     db_override: Option<PathBuf>,
@@ -9534,6 +11490,7 @@ fn run_index_with_data(
     semantic: bool,
     build_hnsw: bool,
     embedder: String,
+    quantization: crate::search::vector_index::Quantization,
     progress: ProgressResolved,
     json: bool,
     idempotency_key: Option<String>,
@@ -9568,6 +11525,7 @@ fn run_index_with_data(
         semantic.hash(&mut hasher);
         build_hnsw.hash(&mut hasher);
         embedder.hash(&mut hasher);
+        quantization.as_str().hash(&mut hasher);
         format!("{}", data_dir.display()).hash(&mut hasher);
         hasher.finish()
     };
@@ -9658,6 +11616,7 @@ fn run_index_with_data(
         semantic,
         build_hnsw,
         embedder: embedder.clone(),
+        quantization,
         progress: Some(index_progress.clone()),
     };
 
@@ -9679,9 +11638,17 @@ fn run_index_with_data(
 
     let start = Instant::now();
 
+    // In watch mode, sources with an hourly/daily sync_schedule are synced in
+    // the background and the watcher rescans only the mirror roots they touch.
+    let event_channel = (watch && watch_once_paths.is_none()).then(|| {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        spawn_sync_scheduler(&data_dir, tx.clone());
+        (tx, rx)
+    });
+
     // Run indexer in background thread so we can poll progress
     let opts_clone = opts.clone();
-    let index_handle = std::thread::spawn(move || indexer::run_index(opts_clone, None));
+    let index_handle = std::thread::spawn(move || indexer::run_index(opts_clone, event_channel));
 
     // Poll and display progress while indexer runs
     if show_progress {
@@ -10308,109 +12275,436 @@ fn load_opencode_session_for_export(
                         content_pieces.push(format!("[Patch]\n{text}"));
                     }
                 }
-                _ => {}
+                _ => {}
+            }
+        }
+
+        let assembled_content = content_pieces.join("\n\n");
+        if assembled_content.trim().is_empty() {
+            continue;
+        }
+
+        let role = msg_info.role.unwrap_or_else(|| "assistant".to_string());
+        let timestamp = msg_info.time.as_ref().and_then(|t| t.created).unwrap_or(0);
+
+        // Build JSON value matching expected format for formatters
+        let msg_json = serde_json::json!({
+            "role": role,
+            "content": assembled_content,
+            "timestamp": timestamp,
+            "model": msg_info.model_id,
+        });
+
+        messages.push((timestamp, msg_json));
+    }
+
+    // Sort by timestamp
+    messages.sort_by_key(|(ts, _)| *ts);
+    let sorted_messages: Vec<serde_json::Value> = messages.into_iter().map(|(_, m)| m).collect();
+
+    // Compute timestamps from messages if not in session
+    let start = session_start.or_else(|| {
+        sorted_messages
+            .first()
+            .and_then(|m| m["timestamp"].as_i64())
+    });
+    let end = session_end.or_else(|| sorted_messages.last().and_then(|m| m["timestamp"].as_i64()));
+
+    Ok((session_title, start, end, sorted_messages))
+}
+
+/// Export a conversation to markdown or other formats
+fn run_export(
+    path: &Path,
+    format: ConvExportFormat,
+    output: Option<&Path>,
+    include_tools: bool,
+) -> CliResult<()> {
+    use std::fs::File;
+    use std::io::{BufRead, BufReader, Write};
+
+    if !path.exists() {
+        return Err(CliError {
+            code: 3,
+            kind: "file-not-found",
+            message: format!("Session file not found: {}", path.display()),
+            hint: Some("Use 'cass search' to find session paths".to_string()),
+            retryable: false,
+        });
+    }
+
+    let mut messages: Vec<serde_json::Value> = Vec::new();
+    let mut session_title: Option<String> = None;
+    let mut session_start: Option<i64> = None;
+    let mut _session_end: Option<i64> = None;
+
+    // Check if this is an OpenCode storage session file
+    // OpenCode stores sessions in: storage/session/{projectID}/{sessionID}.json
+    // with messages in: storage/message/{sessionID}/*.json
+    // and parts in: storage/part/{messageID}/*.json
+    let is_opencode = detect_opencode_session(path);
+
+    if is_opencode {
+        // Load OpenCode session using split storage format
+        match load_opencode_session_for_export(path) {
+            Ok((title, start, end, msgs)) => {
+                session_title = title;
+                session_start = start;
+                _session_end = end;
+                messages = msgs;
+            }
+            Err(e) => {
+                return Err(CliError {
+                    code: 9,
+                    kind: "opencode-parse",
+                    message: format!("Failed to parse OpenCode session: {e}"),
+                    hint: Some(
+                        "Ensure the session file is valid and message/part directories exist"
+                            .into(),
+                    ),
+                    retryable: false,
+                });
+            }
+        }
+    } else {
+        // Standard JSONL format
+        let file = File::open(path).map_err(|e| CliError {
+            code: 9,
+            kind: "file-open",
+            message: format!("Failed to open file: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+
+        let reader = BufReader::new(file);
+
+        for line in reader.lines().map_while(Result::ok) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
+                if let Some(ts) = msg.get("timestamp").and_then(|t| t.as_i64()) {
+                    if session_start.is_none_or(|start| ts < start) {
+                        session_start = Some(ts);
+                    }
+                    if _session_end.is_none_or(|end| ts > end) {
+                        _session_end = Some(ts);
+                    }
+                }
+                messages.push(msg);
+            }
+        }
+    }
+
+    if messages.is_empty() {
+        return Err(CliError {
+            code: 9,
+            kind: "empty-session",
+            message: format!("No messages found in: {}", path.display()),
+            hint: if is_opencode {
+                Some("Check that storage/message/{sessionID}/ contains message files".into())
+            } else {
+                None
+            },
+            retryable: false,
+        });
+    }
+
+    // Find title from first user message (only if no title already set)
+    if session_title.is_none() {
+        for msg in &messages {
+            let role = extract_role(msg);
+            if role == "user" {
+                let content = extract_text_content(msg);
+                if !content.is_empty() {
+                    let first_line = content.lines().next().unwrap_or("Untitled Session");
+                    session_title = Some(smart_truncate(first_line, 80));
+                    break;
+                }
             }
         }
+    }
 
-        let assembled_content = content_pieces.join("\n\n");
-        if assembled_content.trim().is_empty() {
-            continue;
+    let formatted = match format {
+        ConvExportFormat::Markdown => {
+            format_as_markdown(&messages, &session_title, session_start, include_tools)
+        }
+        ConvExportFormat::Text => format_as_text(&messages, include_tools),
+        ConvExportFormat::Json => serde_json::to_string_pretty(&messages).unwrap_or_default(),
+        ConvExportFormat::Html => {
+            format_as_html(&messages, &session_title, session_start, include_tools)
         }
+    };
 
-        let role = msg_info.role.unwrap_or_else(|| "assistant".to_string());
-        let timestamp = msg_info.time.as_ref().and_then(|t| t.created).unwrap_or(0);
+    if let Some(out_path) = output {
+        let mut out_file = File::create(out_path).map_err(|e| CliError {
+            code: 9,
+            kind: "file-create",
+            message: format!("Failed to create output file: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+        out_file
+            .write_all(formatted.as_bytes())
+            .map_err(|e| CliError {
+                code: 9,
+                kind: "file-write",
+                message: format!("Failed to write output: {e}"),
+                hint: None,
+                retryable: false,
+            })?;
+        println!("Exported to: {}", out_path.display());
+    } else {
+        println!("{formatted}");
+    }
 
-        // Build JSON value matching expected format for formatters
-        let msg_json = serde_json::json!({
-            "role": role,
-            "content": assembled_content,
-            "timestamp": timestamp,
-            "model": msg_info.model_id,
-        });
+    Ok(())
+}
 
-        messages.push((timestamp, msg_json));
+/// Compare two indexed sessions and render the diff as a TUI, Markdown, or JSON.
+fn run_diff(
+    a: &Path,
+    b: &Path,
+    format: Option<DiffFormat>,
+    output: Option<&Path>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    let format = if json {
+        DiffFormat::Json
+    } else {
+        format.unwrap_or_else(|| {
+            if robot_format_from_env().is_some() {
+                DiffFormat::Json
+            } else if output.is_none() && io::stdout().is_terminal() {
+                DiffFormat::Tui
+            } else {
+                DiffFormat::Markdown
+            }
+        })
+    };
+    if format == DiffFormat::Tui && output.is_some() {
+        return Err(CliError::usage(
+            "--output cannot be used with the TUI diff view",
+            Some("Use --format markdown or --format json to write a file.".to_string()),
+        ));
     }
 
-    // Sort by timestamp
-    messages.sort_by_key(|(ts, _)| *ts);
-    let sorted_messages: Vec<serde_json::Value> = messages.into_iter().map(|(_, m)| m).collect();
-
-    // Compute timestamps from messages if not in session
-    let start = session_start.or_else(|| {
-        sorted_messages
-            .first()
-            .and_then(|m| m["timestamp"].as_i64())
-    });
-    let end = session_end.or_else(|| sorted_messages.last().and_then(|m| m["timestamp"].as_i64()));
+    let (storage, _) = open_tag_storage(data_dir_override, db_override)?;
+    let load = |path: &Path| {
+        let path_str = path.to_string_lossy().to_string();
+        match crate::diff::load_session(&storage, &path_str) {
+            Ok(Some(session)) => Ok(session),
+            Ok(None) => Err(CliError {
+                code: 4,
+                kind: "not_found",
+                message: format!("No session found at path: {path_str}"),
+                hint: Some(
+                    "Use 'cass search' to find sessions, then use the source_path from results."
+                        .to_string(),
+                ),
+                retryable: false,
+            }),
+            Err(e) => Err(CliError {
+                code: 9,
+                kind: "db-query",
+                message: format!("Failed to load session {path_str}: {e}"),
+                hint: None,
+                retryable: false,
+            }),
+        }
+    };
+    let session_a = load(a)?;
+    let session_b = load(b)?;
+    let diff = crate::diff::SessionDiff::compute(&session_a, &session_b);
+
+    let rendered = match format {
+        DiffFormat::Tui => {
+            return crate::ui::diff_view::run_diff_view(&diff)
+                .map_err(|e| CliError::unknown(format!("diff view failed: {e}")));
+        }
+        DiffFormat::Json if output.is_none() => {
+            let payload = serde_json::to_value(&diff)
+                .map_err(|e| CliError::unknown(format!("failed to serialize diff: {e}")))?;
+            let fmt = tag_output_format(json).unwrap_or(RobotFormat::Json);
+            return output_structured_value(payload, fmt);
+        }
+        DiffFormat::Json => serde_json::to_string_pretty(&diff)
+            .map_err(|e| CliError::unknown(format!("failed to serialize diff: {e}")))?,
+        DiffFormat::Markdown => crate::diff::render_markdown(&diff),
+    };
 
-    Ok((session_title, start, end, sorted_messages))
+    if let Some(out_path) = output {
+        std::fs::write(out_path, rendered.as_bytes()).map_err(|e| CliError {
+            code: 9,
+            kind: "file-write",
+            message: format!("Failed to write output: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+        println!("Diff written to: {}", out_path.display());
+    } else {
+        print!("{rendered}");
+    }
+    Ok(())
 }
 
-/// Export a conversation to markdown or other formats
-fn run_export(
-    path: &Path,
-    format: ConvExportFormat,
-    output: Option<&Path>,
+/// Export a session as a beautiful, self-contained HTML file with optional encryption.
+#[allow(clippy::too_many_arguments)]
+fn run_export_html(
+    session_path: &Path,
+    output_dir: Option<&Path>,
+    filename: Option<&str>,
+    encrypt: bool,
+    password: Option<&str>,
+    password_stdin: bool,
     include_tools: bool,
+    show_timestamps: bool,
+    enable_cdns: bool,
+    theme: &str,
+    dry_run: bool,
+    explain: bool,
+    open: bool,
+    json_output: bool,
 ) -> CliResult<()> {
+    use chrono::TimeZone;
+    use html_export::{
+        ExportOptions as HtmlExportOptions, HtmlExporter, Message, TemplateMetadata,
+        generate_full_filename, get_downloads_dir, is_valid_filename,
+    };
     use std::fs::File;
-    use std::io::{BufRead, BufReader, Write};
+    use std::io::{self, BufRead, BufReader, Write};
 
-    if !path.exists() {
-        return Err(CliError {
+    // --- Validate session exists ---
+    if !session_path.exists() {
+        let err = CliError {
             code: 3,
-            kind: "file-not-found",
-            message: format!("Session file not found: {}", path.display()),
+            kind: "session_not_found",
+            message: format!("Session file not found: {}", session_path.display()),
             hint: Some("Use 'cass search' to find session paths".to_string()),
             retryable: false,
-        });
+        };
+        if json_output {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "success": false,
+                    "error": {
+                        "code": err.code,
+                        "kind": err.kind,
+                        "message": err.message,
+                        "hint": err.hint,
+                        "retryable": err.retryable
+                    }
+                })
+            );
+            return Err(err);
+        }
+        return Err(err);
     }
 
-    let mut messages: Vec<serde_json::Value> = Vec::new();
+    // --- Get password if encryption requested ---
+    let final_password: Option<String> = if encrypt {
+        if let Some(p) = password {
+            Some(p.to_string())
+        } else if password_stdin {
+            let mut pwd = String::new();
+            io::stdin().read_line(&mut pwd).map_err(|e| CliError {
+                code: 6,
+                kind: "password_read_error",
+                message: format!("Failed to read password from stdin: {e}"),
+                hint: None,
+                retryable: false,
+            })?;
+            Some(pwd.trim().to_string())
+        } else {
+            let err = CliError {
+                code: 6,
+                kind: "password_required",
+                message: "Password required for encryption".to_string(),
+                hint: Some("Use --password <pwd> or --password-stdin".to_string()),
+                retryable: false,
+            };
+            if json_output {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "success": false,
+                        "error": {
+                            "code": err.code,
+                            "kind": err.kind,
+                            "message": err.message,
+                            "hint": err.hint,
+                            "retryable": err.retryable
+                        }
+                    })
+                );
+            }
+            return Err(err);
+        }
+    } else {
+        None
+    };
+
+    // --- Load session messages ---
+    let mut raw_messages: Vec<serde_json::Value> = Vec::new();
     let mut session_title: Option<String> = None;
     let mut session_start: Option<i64> = None;
-    let mut _session_end: Option<i64> = None;
+    let mut session_end: Option<i64> = None;
+    let mut agent_name: Option<String> = None;
+    let mut workspace: Option<String> = None;
 
-    // Check if this is an OpenCode storage session file
-    // OpenCode stores sessions in: storage/session/{projectID}/{sessionID}.json
-    // with messages in: storage/message/{sessionID}/*.json
-    // and parts in: storage/part/{messageID}/*.json
-    let is_opencode = detect_opencode_session(path);
+    // Detect agent from path
+    let path_str = session_path.to_string_lossy();
+    if path_str.contains(".claude") {
+        agent_name = Some("claude_code".to_string());
+    } else if path_str.contains(".codex") {
+        agent_name = Some("codex".to_string());
+    } else if path_str.contains("cursor") {
+        agent_name = Some("cursor".to_string());
+    } else if path_str.contains(".gemini") {
+        agent_name = Some("gemini".to_string());
+    } else if path_str.contains(".vibe") {
+        agent_name = Some("vibe".to_string());
+    }
+
+    // Extract workspace from path
+    if let Some(parent) = session_path.parent() {
+        workspace = Some(parent.display().to_string());
+    }
+
+    let is_opencode = detect_opencode_session(session_path);
 
     if is_opencode {
-        // Load OpenCode session using split storage format
-        match load_opencode_session_for_export(path) {
+        match load_opencode_session_for_export(session_path) {
             Ok((title, start, end, msgs)) => {
                 session_title = title;
                 session_start = start;
-                _session_end = end;
-                messages = msgs;
+                session_end = end;
+                raw_messages = msgs;
+                agent_name = Some("opencode".to_string());
             }
             Err(e) => {
                 return Err(CliError {
                     code: 9,
-                    kind: "opencode-parse",
+                    kind: "opencode_parse",
                     message: format!("Failed to parse OpenCode session: {e}"),
-                    hint: Some(
-                        "Ensure the session file is valid and message/part directories exist"
-                            .into(),
-                    ),
+                    hint: Some("Ensure the session file is valid".into()),
                     retryable: false,
                 });
             }
         }
     } else {
-        // Standard JSONL format
-        let file = File::open(path).map_err(|e| CliError {
+        let file = File::open(session_path).map_err(|e| CliError {
             code: 9,
-            kind: "file-open",
+            kind: "file_open",
             message: format!("Failed to open file: {e}"),
             hint: None,
             retryable: false,
         })?;
 
         let reader = BufReader::new(file);
-
         for line in reader.lines().map_while(Result::ok) {
             if line.trim().is_empty() {
                 continue;
@@ -10420,32 +12714,28 @@ fn run_export(
                     if session_start.is_none_or(|start| ts < start) {
                         session_start = Some(ts);
                     }
-                    if _session_end.is_none_or(|end| ts > end) {
-                        _session_end = Some(ts);
+                    if session_end.is_none_or(|end| ts > end) {
+                        session_end = Some(ts);
                     }
                 }
-                messages.push(msg);
+                raw_messages.push(msg);
             }
         }
     }
 
-    if messages.is_empty() {
+    if raw_messages.is_empty() {
         return Err(CliError {
             code: 9,
-            kind: "empty-session",
-            message: format!("No messages found in: {}", path.display()),
-            hint: if is_opencode {
-                Some("Check that storage/message/{sessionID}/ contains message files".into())
-            } else {
-                None
-            },
+            kind: "empty_session",
+            message: format!("No messages found in: {}", session_path.display()),
+            hint: None,
             retryable: false,
         });
     }
 
-    // Find title from first user message (only if no title already set)
+    // Find title from first user message
     if session_title.is_none() {
-        for msg in &messages {
+        for msg in &raw_messages {
             let role = extract_role(msg);
             if role == "user" {
                 let content = extract_text_content(msg);
@@ -10458,494 +12748,781 @@ fn run_export(
         }
     }
 
-    let formatted = match format {
-        ConvExportFormat::Markdown => {
-            format_as_markdown(&messages, &session_title, session_start, include_tools)
+    // --- Convert to renderer::Message format (filtering empty messages) ---
+    let messages: Vec<Message> = raw_messages
+        .iter()
+        .enumerate()
+        .filter_map(|(i, msg)| {
+            let role = extract_role(msg);
+            let content = extract_text_content(msg);
+            let ts = msg.get("timestamp").and_then(|t| t.as_i64());
+            let timestamp = ts
+                .and_then(|ts| chrono::Utc.timestamp_millis_opt(ts).single())
+                .map(|dt| dt.to_rfc3339());
+
+            // Extract tool call info if present
+            let tool_call = if include_tools {
+                extract_tool_call(msg)
+            } else {
+                None
+            };
+
+            // If we have a tool_call, strip the redundant "[Tool: X]" prefix from content
+            // since the tool call details are shown separately in the HTML export
+            let content = if tool_call.is_some() {
+                strip_tool_marker(&content)
+            } else {
+                content
+            };
+
+            // Skip empty messages: no content AND no tool call AND unknown role
+            // This filters out malformed/empty entries that would look broken
+            if content.is_empty() && tool_call.is_none() && role == "unknown" {
+                return None;
+            }
+
+            // Also skip messages that are completely empty (no content, no tool call)
+            // but keep tool calls even if content is empty (shows the tool interaction)
+            if content.is_empty() && tool_call.is_none() {
+                return None;
+            }
+
+            Some(Message {
+                role,
+                content,
+                timestamp,
+                tool_call,
+                index: Some(i),
+                author: None,
+            })
+        })
+        .collect();
+
+    // Store original message count for explain/dry_run modes
+    let message_count = messages.len();
+
+    // --- Build metadata ---
+    let duration = match (session_start, session_end) {
+        (Some(start), Some(end)) if end > start => {
+            let mins = (end - start) / 60_000;
+            if mins >= 60 {
+                Some(format!("{}h {}m", mins / 60, mins % 60))
+            } else if mins > 0 {
+                Some(format!("{}m", mins))
+            } else {
+                Some("< 1m".to_string())
+            }
+        }
+        _ => None,
+    };
+
+    let metadata = TemplateMetadata {
+        timestamp: session_start.map(|ts| {
+            chrono::Utc
+                .timestamp_millis_opt(ts)
+                .single()
+                .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_default()
+        }),
+        agent: agent_name.clone(),
+        message_count,
+        duration,
+        project: workspace.clone(),
+    };
+
+    // --- Generate output path ---
+    let output_directory = output_dir
+        .map(|p| p.to_path_buf())
+        .unwrap_or_else(get_downloads_dir);
+
+    let workspace_path = workspace.as_deref().map(Path::new);
+
+    let mut final_filename = if let Some(name) = filename {
+        name.to_string()
+    } else {
+        generate_full_filename(
+            agent_name.as_deref().unwrap_or("cass"),
+            workspace_path,
+            session_start,
+            session_title.as_deref(),
+            session_title.as_deref(),
+        )
+    };
+
+    if Path::new(&final_filename).extension().is_none() {
+        final_filename.push_str(".html");
+    }
+
+    if filename.is_some() && !is_valid_filename(&final_filename) {
+        return Err(CliError {
+            code: 4,
+            kind: "invalid_filename",
+            message: format!("Invalid output filename: {final_filename}"),
+            hint: Some("Avoid path separators and reserved characters".to_string()),
+            retryable: false,
+        });
+    }
+
+    let output_path = output_directory.join(final_filename);
+
+    // Estimate file size (rough: 200 bytes per message + overhead)
+    let estimated_size = message_count * 200 + 15000;
+
+    // --- Explain mode ---
+    if explain {
+        let plan = serde_json::json!({
+            "plan": {
+                "session_path": session_path.display().to_string(),
+                "agent": agent_name,
+                "messages": message_count,
+                "output_path": output_path.display().to_string(),
+                "estimated_size_bytes": estimated_size,
+                "options": {
+                    "encrypted": encrypt,
+                    "include_tools": include_tools,
+                    "show_timestamps": show_timestamps,
+                    "cdns_enabled": enable_cdns,
+                    "default_theme": theme
+                }
+            },
+            "warnings": []
+        });
+        println!("{}", serde_json::to_string_pretty(&plan).unwrap());
+        return Ok(());
+    }
+
+    // --- Dry run mode ---
+    if dry_run {
+        let result = serde_json::json!({
+            "dry_run": true,
+            "valid": true,
+            "session_path": session_path.display().to_string(),
+            "output_path": output_path.display().to_string(),
+            "messages": message_count,
+            "encrypted": encrypt,
+            "estimated_size_bytes": estimated_size
+        });
+        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+        return Ok(());
+    }
+
+    // --- Build export options ---
+    let export_options = HtmlExportOptions {
+        title: session_title.clone(),
+        include_cdn: enable_cdns,
+        syntax_highlighting: true,
+        include_search: true,
+        include_theme_toggle: true,
+        encrypt,
+        print_styles: true,
+        agent_name: agent_name.clone(),
+        show_timestamps,
+        show_tool_calls: include_tools,
+    };
+
+    // --- Export ---
+    let exporter = HtmlExporter::with_options(export_options);
+    let title = session_title.as_deref().unwrap_or("Conversation Export");
+
+    // Group messages for consolidated rendering (tool calls with parent messages)
+    let message_groups = group_messages_for_export(messages);
+
+    let html = exporter
+        .export_messages(title, &message_groups, metadata, final_password.as_deref())
+        .map_err(|e| CliError {
+            code: 5,
+            kind: "export_failed",
+            message: format!("Failed to export HTML: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
+
+    // --- Write file ---
+    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new("."))).ok();
+    let mut file = File::create(&output_path).map_err(|e| CliError {
+        code: 4,
+        kind: "output_not_writable",
+        message: format!("Could not create output file: {e}"),
+        hint: Some(format!(
+            "Check permissions for {}",
+            output_directory.display()
+        )),
+        retryable: false,
+    })?;
+    file.write_all(html.as_bytes()).map_err(|e| CliError {
+        code: 4,
+        kind: "write_failed",
+        message: format!("Failed to write file: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+
+    let file_size = html.len();
+
+    // --- Open in browser if requested ---
+    if open {
+        #[cfg(target_os = "macos")]
+        {
+            let _ = std::process::Command::new("open").arg(&output_path).spawn();
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let _ = std::process::Command::new("xdg-open")
+                .arg(&output_path)
+                .spawn();
         }
-        ConvExportFormat::Text => format_as_text(&messages, include_tools),
-        ConvExportFormat::Json => serde_json::to_string_pretty(&messages).unwrap_or_default(),
-        ConvExportFormat::Html => {
-            format_as_html(&messages, &session_title, session_start, include_tools)
+        #[cfg(target_os = "windows")]
+        {
+            let _ = std::process::Command::new("explorer")
+                .arg(&output_path)
+                .spawn();
         }
-    };
+    }
 
-    if let Some(out_path) = output {
-        let mut out_file = File::create(out_path).map_err(|e| CliError {
-            code: 9,
-            kind: "file-create",
-            message: format!("Failed to create output file: {e}"),
-            hint: None,
-            retryable: false,
-        })?;
-        out_file
-            .write_all(formatted.as_bytes())
-            .map_err(|e| CliError {
-                code: 9,
-                kind: "file-write",
-                message: format!("Failed to write output: {e}"),
-                hint: None,
-                retryable: false,
-            })?;
-        println!("Exported to: {}", out_path.display());
+    // --- Output result ---
+    if json_output {
+        let result = serde_json::json!({
+            "success": true,
+            "exported": {
+                "session_path": session_path.display().to_string(),
+                "output_path": output_path.display().to_string(),
+                "filename": output_path.file_name().map(|n| n.to_string_lossy().to_string()),
+                "size_bytes": file_size,
+                "encrypted": encrypt,
+                "messages_count": message_count,
+                "agent": agent_name,
+                "workspace": workspace,
+                "title": session_title
+            }
+        });
+        println!("{}", serde_json::to_string_pretty(&result).unwrap());
     } else {
-        println!("{formatted}");
+        println!("✓ Exported to {}", output_path.display());
+        if encrypt {
+            println!("  🔒 Encrypted with Web Crypto (AES-256-GCM)");
+        }
+        println!("  {} messages, {} bytes", message_count, file_size);
     }
 
     Ok(())
 }
 
-/// Export a session as a beautiful, self-contained HTML file with optional encryption.
+/// Sessions selected for a multi-session `export-html`.
+#[derive(Debug, Clone, Default)]
+struct ExportSiteSelection {
+    query: Option<String>,
+    workspaces: Vec<String>,
+    tags: Vec<String>,
+    agents: Vec<String>,
+    since: Option<String>,
+    until: Option<String>,
+    limit: usize,
+}
+
+/// Export a set of sessions as a static folder: `index.html` with a sortable,
+/// searchable session table plus one cross-linked page per session.
 #[allow(clippy::too_many_arguments)]
-fn run_export_html(
-    session_path: &Path,
+fn run_export_html_site(
+    selection: &ExportSiteSelection,
+    title: Option<&str>,
     output_dir: Option<&Path>,
-    filename: Option<&str>,
+    data_dir: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
     encrypt: bool,
     password: Option<&str>,
     password_stdin: bool,
     include_tools: bool,
     show_timestamps: bool,
     enable_cdns: bool,
-    theme: &str,
     dry_run: bool,
-    explain: bool,
     open: bool,
     json_output: bool,
 ) -> CliResult<()> {
     use chrono::TimeZone;
     use html_export::{
-        ExportOptions as HtmlExportOptions, HtmlExporter, Message, TemplateMetadata,
-        generate_full_filename, get_downloads_dir, is_valid_filename,
+        ExportOptions as HtmlExportOptions, Message, SiteSession, get_downloads_dir, render_site,
     };
-    use std::fs::File;
-    use std::io::{self, BufRead, BufReader, Write};
+    use std::collections::HashMap;
+    use std::io::{self, BufRead};
 
-    // --- Validate session exists ---
-    if !session_path.exists() {
-        let err = CliError {
-            code: 3,
-            kind: "session_not_found",
-            message: format!("Session file not found: {}", session_path.display()),
-            hint: Some("Use 'cass search' to find session paths".to_string()),
-            retryable: false,
-        };
-        if json_output {
-            println!(
-                "{}",
-                serde_json::json!({
-                    "success": false,
-                    "error": {
-                        "code": err.code,
-                        "kind": err.kind,
-                        "message": err.message,
-                        "hint": err.hint,
-                        "retryable": err.retryable
-                    }
+    let db_err = |e: rusqlite::Error| CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Query failed: {e}"),
+        hint: None,
+        retryable: false,
+    };
+
+    let parse_bound = |flag: &str, value: Option<&str>| -> CliResult<Option<i64>> {
+        value
+            .map(|v| {
+                parse_datetime_flexible(v).ok_or_else(|| {
+                    CliError::usage(
+                        format!("Invalid --{flag} value: {v}"),
+                        Some(
+                            "Use an ISO date (2024-01-31), 'today', or a relative time like 7d"
+                                .to_string(),
+                        ),
+                    )
                 })
-            );
-            return Err(err);
+            })
+            .transpose()
+    };
+    let since = parse_bound("since", selection.since.as_deref())?;
+    let until = parse_bound("until", selection.until.as_deref())?;
+
+    let mut tags = Vec::with_capacity(selection.tags.len());
+    for raw in &selection.tags {
+        let Some(tag) = crate::model::types::Tag::normalize_name(raw) else {
+            return Err(CliError::usage(
+                format!("Invalid tag name: {raw:?}"),
+                Some("Tags may contain letters, digits, '-', '_', '.', and '/'.".to_string()),
+            ));
+        };
+        if !tags.contains(&tag) {
+            tags.push(tag);
         }
-        return Err(err);
     }
+    let limit = selection.limit.max(1);
 
-    // --- Get password if encryption requested ---
-    let final_password: Option<String> = if encrypt {
+    let final_password = if encrypt {
         if let Some(p) = password {
             Some(p.to_string())
         } else if password_stdin {
             let mut pwd = String::new();
-            io::stdin().read_line(&mut pwd).map_err(|e| CliError {
-                code: 6,
-                kind: "password_read_error",
-                message: format!("Failed to read password from stdin: {e}"),
-                hint: None,
-                retryable: false,
-            })?;
+            io::stdin()
+                .lock()
+                .read_line(&mut pwd)
+                .map_err(|e| CliError {
+                    code: 6,
+                    kind: "password_read_error",
+                    message: format!("Failed to read password from stdin: {e}"),
+                    hint: None,
+                    retryable: false,
+                })?;
             Some(pwd.trim().to_string())
         } else {
-            let err = CliError {
+            return Err(CliError {
                 code: 6,
                 kind: "password_required",
                 message: "Password required for encryption".to_string(),
                 hint: Some("Use --password <pwd> or --password-stdin".to_string()),
                 retryable: false,
-            };
-            if json_output {
-                println!(
-                    "{}",
-                    serde_json::json!({
-                        "success": false,
-                        "error": {
-                            "code": err.code,
-                            "kind": err.kind,
-                            "message": err.message,
-                            "hint": err.hint,
-                            "retryable": err.retryable
-                        }
-                    })
-                );
-            }
-            return Err(err);
+            });
         }
     } else {
         None
     };
 
-    // --- Load session messages ---
-    let mut raw_messages: Vec<serde_json::Value> = Vec::new();
-    let mut session_title: Option<String> = None;
-    let mut session_start: Option<i64> = None;
-    let mut session_end: Option<i64> = None;
-    let mut agent_name: Option<String> = None;
-    let mut workspace: Option<String> = None;
-
-    // Detect agent from path
-    let path_str = session_path.to_string_lossy();
-    if path_str.contains(".claude") {
-        agent_name = Some("claude_code".to_string());
-    } else if path_str.contains(".codex") {
-        agent_name = Some("codex".to_string());
-    } else if path_str.contains("cursor") {
-        agent_name = Some("cursor".to_string());
-    } else if path_str.contains(".gemini") {
-        agent_name = Some("gemini".to_string());
-    } else if path_str.contains(".vibe") {
-        agent_name = Some("vibe".to_string());
-    }
-
-    // Extract workspace from path
-    if let Some(parent) = session_path.parent() {
-        workspace = Some(parent.display().to_string());
-    }
-
-    let is_opencode = detect_opencode_session(session_path);
+    // With a query, the search ranks sessions and the DB supplies the rest.
+    let ranked_paths: Option<Vec<String>> = match selection.query.as_deref() {
+        Some(query) => {
+            use crate::search::query::{FieldMask, SearchFilters, SearchMode};
 
-    if is_opencode {
-        match load_opencode_session_for_export(session_path) {
-            Ok((title, start, end, msgs)) => {
-                session_title = title;
-                session_start = start;
-                session_end = end;
-                raw_messages = msgs;
-                agent_name = Some("opencode".to_string());
-            }
-            Err(e) => {
-                return Err(CliError {
+            let data_dir = data_dir.clone().unwrap_or_else(default_data_dir);
+            let db_path = db_override
+                .clone()
+                .unwrap_or_else(|| data_dir.join("agent_search.db"));
+            let client =
+                crate::saved_searches::open_client(&data_dir, &db_path, SearchMode::Lexical)
+                    .map_err(|e| CliError {
+                        code: 9,
+                        kind: "open-index",
+                        message: format!("failed to open index: {e}"),
+                        hint: Some("try cass index --full".to_string()),
+                        retryable: true,
+                    })?
+                    .ok_or_else(|| CliError {
+                        code: 3,
+                        kind: "missing-index",
+                        message: "Index not found. Run 'cass index --full' first.".to_string(),
+                        hint: None,
+                        retryable: true,
+                    })?;
+
+            let filters = SearchFilters {
+                agents: selection.agents.iter().cloned().collect(),
+                workspaces: selection.workspaces.iter().cloned().collect(),
+                created_from: since,
+                created_to: until,
+                tags: tags.iter().cloned().collect(),
+                ..SearchFilters::default()
+            };
+            let sparse_threshold = 3; // Threshold for triggering wildcard fallback
+            // Hits are per message, so over-fetch to fill `limit` sessions.
+            let hits = client
+                .search_with_fallback(
+                    query,
+                    filters,
+                    limit.saturating_mul(10),
+                    0,
+                    sparse_threshold,
+                    FieldMask::FULL,
+                )
+                .map_err(|e| CliError {
                     code: 9,
-                    kind: "opencode_parse",
-                    message: format!("Failed to parse OpenCode session: {e}"),
-                    hint: Some("Ensure the session file is valid".into()),
-                    retryable: false,
-                });
+                    kind: "search",
+                    message: format!("search failed: {e}"),
+                    hint: None,
+                    retryable: true,
+                })?
+                .hits;
+            let mut paths: Vec<String> = Vec::new();
+            for hit in hits {
+                if !paths.contains(&hit.source_path) {
+                    paths.push(hit.source_path);
+                }
+                if paths.len() >= limit {
+                    break;
+                }
             }
+            Some(paths)
         }
-    } else {
-        let file = File::open(session_path).map_err(|e| CliError {
-            code: 9,
-            kind: "file_open",
-            message: format!("Failed to open file: {e}"),
-            hint: None,
-            retryable: false,
-        })?;
+        None => None,
+    };
 
-        let reader = BufReader::new(file);
-        for line in reader.lines().map_while(Result::ok) {
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&line) {
-                if let Some(ts) = msg.get("timestamp").and_then(|t| t.as_i64()) {
-                    if session_start.is_none_or(|start| ts < start) {
-                        session_start = Some(ts);
-                    }
-                    if session_end.is_none_or(|end| ts > end) {
-                        session_end = Some(ts);
-                    }
-                }
-                raw_messages.push(msg);
+    let lazy = crate::storage::sqlite::LazyDb::from_overrides(data_dir, db_override);
+    let conn = lazy.get("export-html").map_err(lazy_db_to_cli_error)?;
+
+    let mut sql = String::from(
+        "SELECT c.id, a.slug, w.path, c.title, c.source_path, c.started_at, c.ended_at
+         FROM conversations c
+         JOIN agents a ON c.agent_id = a.id
+         LEFT JOIN workspaces w ON c.workspace_id = w.id
+         WHERE 1 = 1",
+    );
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut push_in = |sql: &mut String, column: &str, values: &[String]| {
+        if values.is_empty() {
+            return;
+        }
+        sql.push_str(&format!(" AND {column} IN ("));
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
             }
+            sql.push_str(&format!("?{}", params.len() + 1));
+            params.push(Box::new(value.clone()));
         }
+        sql.push(')');
+    };
+    push_in(&mut sql, "w.path", &selection.workspaces);
+    push_in(&mut sql, "a.slug", &selection.agents);
+    if let Some(paths) = &ranked_paths {
+        if paths.is_empty() {
+            sql.push_str(" AND 0");
+        }
+        push_in(&mut sql, "c.source_path", paths);
+    }
+    if !tags.is_empty() {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM session_tags st JOIN tags t ON t.id = st.tag_id
+                 WHERE st.source_path = c.source_path",
+        );
+        push_in(&mut sql, "t.name", &tags);
+        sql.push(')');
+    }
+    if let Some(since) = since {
+        sql.push_str(&format!(" AND c.started_at >= ?{}", params.len() + 1));
+        params.push(Box::new(since));
+    }
+    if let Some(until) = until {
+        sql.push_str(&format!(" AND c.started_at <= ?{}", params.len() + 1));
+        params.push(Box::new(until));
+    }
+    sql.push_str(&format!(
+        " ORDER BY c.started_at DESC LIMIT ?{}",
+        params.len() + 1
+    ));
+    params.push(Box::new(limit as i64));
+
+    let mut stmt = conn.prepare(&sql).map_err(db_err)?;
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    #[allow(clippy::type_complexity)]
+    let mut rows: Vec<(
+        i64,
+        String,
+        Option<String>,
+        Option<String>,
+        String,
+        Option<i64>,
+        Option<i64>,
+    )> = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+                row.get(6)?,
+            ))
+        })
+        .map_err(db_err)?
+        .collect::<Result<_, _>>()
+        .map_err(db_err)?;
+    if let Some(paths) = &ranked_paths {
+        let rank: HashMap<&str, usize> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (p.as_str(), i))
+            .collect();
+        rows.sort_by_key(|row| rank.get(row.4.as_str()).copied().unwrap_or(usize::MAX));
     }
 
-    if raw_messages.is_empty() {
+    if rows.is_empty() {
         return Err(CliError {
-            code: 9,
-            kind: "empty_session",
-            message: format!("No messages found in: {}", session_path.display()),
-            hint: None,
+            code: 3,
+            kind: "no_sessions",
+            message: "No sessions matched the selection".to_string(),
+            hint: Some(
+                "Widen --query/--workspace/--tag/--since or check 'cass timeline'".to_string(),
+            ),
             retryable: false,
         });
     }
 
-    // Find title from first user message
-    if session_title.is_none() {
-        for msg in &raw_messages {
-            let role = extract_role(msg);
-            if role == "user" {
-                let content = extract_text_content(msg);
-                if !content.is_empty() {
-                    let first_line = content.lines().next().unwrap_or("Untitled Session");
-                    session_title = Some(smart_truncate(first_line, 80));
-                    break;
-                }
-            }
-        }
-    }
-
-    // --- Convert to renderer::Message format (filtering empty messages) ---
-    let messages: Vec<Message> = raw_messages
-        .iter()
-        .enumerate()
-        .filter_map(|(i, msg)| {
-            let role = extract_role(msg);
-            let content = extract_text_content(msg);
-            let ts = msg.get("timestamp").and_then(|t| t.as_i64());
-            let timestamp = ts
-                .and_then(|ts| chrono::Utc.timestamp_millis_opt(ts).single())
-                .map(|dt| dt.to_rfc3339());
-
-            // Extract tool call info if present
+    let mut sessions = Vec::with_capacity(rows.len());
+    for (conv_id, agent, workspace, db_title, source_path, started_at, ended_at) in rows {
+        let mut stmt = conn
+            .prepare_cached(
+                "SELECT idx, role, created_at, content, extra_json FROM messages
+                 WHERE conversation_id = ?1 ORDER BY idx",
+            )
+            .map_err(db_err)?;
+        let raw: Vec<(i64, String, Option<i64>, String, Option<String>)> = stmt
+            .query_map([conv_id], |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })
+            .map_err(db_err)?
+            .collect::<Result<_, _>>()
+            .map_err(db_err)?;
+
+        let mut search_text = String::new();
+        let mut messages = Vec::with_capacity(raw.len());
+        for (idx, role, created_at, content, extra_json) in raw {
+            let role = match role.as_str() {
+                "agent" | "assistant" => "assistant".to_string(),
+                "user" | "tool" | "system" => role,
+                _ => "other".to_string(),
+            };
             let tool_call = if include_tools {
-                extract_tool_call(msg)
+                extra_json
+                    .as_deref()
+                    .and_then(|extra| serde_json::from_str::<serde_json::Value>(extra).ok())
+                    .and_then(|extra| extract_tool_call(&extra))
             } else {
                 None
             };
-
-            // If we have a tool_call, strip the redundant "[Tool: X]" prefix from content
-            // since the tool call details are shown separately in the HTML export
             let content = if tool_call.is_some() {
                 strip_tool_marker(&content)
             } else {
                 content
             };
-
-            // Skip empty messages: no content AND no tool call AND unknown role
-            // This filters out malformed/empty entries that would look broken
-            if content.is_empty() && tool_call.is_none() && role == "unknown" {
-                return None;
-            }
-
-            // Also skip messages that are completely empty (no content, no tool call)
-            // but keep tool calls even if content is empty (shows the tool interaction)
             if content.is_empty() && tool_call.is_none() {
-                return None;
+                continue;
             }
-
-            Some(Message {
+            search_text.push_str(&content);
+            search_text.push('\n');
+            messages.push(Message {
                 role,
                 content,
-                timestamp,
+                timestamp: created_at
+                    .and_then(|ts| chrono::Utc.timestamp_millis_opt(ts).single())
+                    .map(|dt| dt.to_rfc3339()),
                 tool_call,
-                index: Some(i),
+                index: usize::try_from(idx).ok(),
                 author: None,
-            })
-        })
-        .collect();
-
-    // Store original message count for explain/dry_run modes
-    let message_count = messages.len();
-
-    // --- Build metadata ---
-    let duration = match (session_start, session_end) {
-        (Some(start), Some(end)) if end > start => {
-            let mins = (end - start) / 60_000;
-            if mins >= 60 {
-                Some(format!("{}h {}m", mins / 60, mins % 60))
-            } else if mins > 0 {
-                Some(format!("{}m", mins))
-            } else {
-                Some("< 1m".to_string())
-            }
+            });
         }
-        _ => None,
-    };
-
-    let metadata = TemplateMetadata {
-        timestamp: session_start.map(|ts| {
-            chrono::Utc
-                .timestamp_millis_opt(ts)
-                .single()
-                .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
-                .unwrap_or_default()
-        }),
-        agent: agent_name.clone(),
-        message_count,
-        duration,
-        project: workspace.clone(),
-    };
-
-    // --- Generate output path ---
-    let output_directory = output_dir
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(get_downloads_dir);
-
-    let workspace_path = workspace.as_deref().map(Path::new);
-
-    let mut final_filename = if let Some(name) = filename {
-        name.to_string()
-    } else {
-        generate_full_filename(
-            agent_name.as_deref().unwrap_or("cass"),
-            workspace_path,
-            session_start,
-            session_title.as_deref(),
-            session_title.as_deref(),
-        )
-    };
 
-    if Path::new(&final_filename).extension().is_none() {
-        final_filename.push_str(".html");
-    }
+        let title = db_title
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| {
+                messages
+                    .iter()
+                    .find(|m| m.role == "user" && !m.content.trim().is_empty())
+                    .and_then(|m| m.content.lines().next())
+                    .map(|line| smart_truncate(line, 80))
+                    .unwrap_or_else(|| "Untitled Session".to_string())
+            });
+        let session_tags: Vec<String> = conn
+            .prepare_cached(
+                "SELECT DISTINCT t.name FROM session_tags st
+                 JOIN tags t ON t.id = st.tag_id
+                 WHERE st.source_path = ?1
+                 ORDER BY t.name",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([&source_path], |row| row.get(0))?
+                    .collect::<Result<_, _>>()
+            })
+            .unwrap_or_default();
 
-    if filename.is_some() && !is_valid_filename(&final_filename) {
-        return Err(CliError {
-            code: 4,
-            kind: "invalid_filename",
-            message: format!("Invalid output filename: {final_filename}"),
-            hint: Some("Avoid path separators and reserved characters".to_string()),
-            retryable: false,
+        sessions.push(SiteSession {
+            title,
+            agent,
+            workspace,
+            source_path,
+            started_at,
+            ended_at,
+            tags: session_tags,
+            message_count: messages.len(),
+            groups: group_messages_for_export(messages),
+            search_text,
         });
     }
 
-    let output_path = output_directory.join(final_filename);
-
-    // Estimate file size (rough: 200 bytes per message + overhead)
-    let estimated_size = message_count * 200 + 15000;
-
-    // --- Explain mode ---
-    if explain {
-        let plan = serde_json::json!({
-            "plan": {
-                "session_path": session_path.display().to_string(),
-                "agent": agent_name,
-                "messages": message_count,
-                "output_path": output_path.display().to_string(),
-                "estimated_size_bytes": estimated_size,
-                "options": {
-                    "encrypted": encrypt,
-                    "include_tools": include_tools,
-                    "show_timestamps": show_timestamps,
-                    "cdns_enabled": enable_cdns,
-                    "default_theme": theme
-                }
-            },
-            "warnings": []
-        });
-        println!("{}", serde_json::to_string_pretty(&plan).unwrap());
-        return Ok(());
-    }
+    let output_directory = output_dir.map(Path::to_path_buf).unwrap_or_else(|| {
+        get_downloads_dir().join(format!(
+            "cass-sessions-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ))
+    });
+    let site_title = title.unwrap_or("Exported Sessions");
 
-    // --- Dry run mode ---
     if dry_run {
-        let result = serde_json::json!({
-            "dry_run": true,
-            "valid": true,
-            "session_path": session_path.display().to_string(),
-            "output_path": output_path.display().to_string(),
-            "messages": message_count,
-            "encrypted": encrypt,
-            "estimated_size_bytes": estimated_size
-        });
-        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+        let planned: Vec<serde_json::Value> = sessions
+            .iter()
+            .map(|s| {
+                serde_json::json!({
+                    "title": s.title,
+                    "agent": s.agent,
+                    "workspace": s.workspace,
+                    "source_path": s.source_path,
+                    "messages": s.message_count,
+                })
+            })
+            .collect();
+        if json_output {
+            let result = serde_json::json!({
+                "dry_run": true,
+                "valid": true,
+                "output_dir": output_directory.display().to_string(),
+                "sessions": planned,
+                "encrypted": encrypt,
+            });
+            println!("{}", serde_json::to_string_pretty(&result).unwrap());
+        } else {
+            println!(
+                "Would export {} sessions to {}",
+                sessions.len(),
+                output_directory.display()
+            );
+            for s in &sessions {
+                println!("  {} ({})", s.title, s.source_path);
+            }
+        }
         return Ok(());
     }
 
-    // --- Build export options ---
     let export_options = HtmlExportOptions {
-        title: session_title.clone(),
+        title: Some(site_title.to_string()),
         include_cdn: enable_cdns,
         syntax_highlighting: true,
         include_search: true,
         include_theme_toggle: true,
         encrypt,
         print_styles: true,
-        agent_name: agent_name.clone(),
+        agent_name: None,
         show_timestamps,
         show_tool_calls: include_tools,
     };
+    let files = render_site(
+        &sessions,
+        site_title,
+        &export_options,
+        final_password.as_deref(),
+    )
+    .map_err(|e| CliError {
+        code: 5,
+        kind: "export_failed",
+        message: format!("Failed to export HTML: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
 
-    // --- Export ---
-    let exporter = HtmlExporter::with_options(export_options);
-    let title = session_title.as_deref().unwrap_or("Conversation Export");
-
-    // Group messages for consolidated rendering (tool calls with parent messages)
-    let message_groups = group_messages_for_export(messages);
-
-    let html = exporter
-        .export_messages(title, &message_groups, metadata, final_password.as_deref())
-        .map_err(|e| CliError {
-            code: 5,
-            kind: "export_failed",
-            message: format!("Failed to export HTML: {e}"),
-            hint: None,
-            retryable: false,
-        })?;
-
-    // --- Write file ---
-    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new("."))).ok();
-    let mut file = File::create(&output_path).map_err(|e| CliError {
+    let write_err = |e: std::io::Error| CliError {
         code: 4,
         kind: "output_not_writable",
-        message: format!("Could not create output file: {e}"),
+        message: format!("Could not write export: {e}"),
         hint: Some(format!(
             "Check permissions for {}",
             output_directory.display()
         )),
         retryable: false,
-    })?;
-    file.write_all(html.as_bytes()).map_err(|e| CliError {
-        code: 4,
-        kind: "write_failed",
-        message: format!("Failed to write file: {e}"),
-        hint: None,
-        retryable: false,
-    })?;
-
-    let file_size = html.len();
+    };
+    std::fs::create_dir_all(output_directory.join(html_export::SESSIONS_DIR)).map_err(write_err)?;
+    let mut total_size = 0;
+    for file in &files {
+        std::fs::write(output_directory.join(&file.path), &file.html).map_err(write_err)?;
+        total_size += file.html.len();
+    }
+    let index_path = output_directory.join("index.html");
 
-    // --- Open in browser if requested ---
     if open {
         #[cfg(target_os = "macos")]
         {
-            let _ = std::process::Command::new("open").arg(&output_path).spawn();
+            let _ = std::process::Command::new("open").arg(&index_path).spawn();
         }
         #[cfg(target_os = "linux")]
         {
             let _ = std::process::Command::new("xdg-open")
-                .arg(&output_path)
+                .arg(&index_path)
                 .spawn();
         }
         #[cfg(target_os = "windows")]
         {
             let _ = std::process::Command::new("explorer")
-                .arg(&output_path)
+                .arg(&index_path)
                 .spawn();
         }
     }
 
-    // --- Output result ---
     if json_output {
+        let pages: Vec<serde_json::Value> = files[1..]
+            .iter()
+            .zip(&sessions)
+            .map(|(file, session)| {
+                serde_json::json!({
+                    "path": file.path,
+                    "title": session.title,
+                    "source_path": session.source_path,
+                    "agent": session.agent,
+                    "workspace": session.workspace,
+                    "messages": session.message_count,
+                })
+            })
+            .collect();
         let result = serde_json::json!({
             "success": true,
             "exported": {
-                "session_path": session_path.display().to_string(),
-                "output_path": output_path.display().to_string(),
-                "filename": output_path.file_name().map(|n| n.to_string_lossy().to_string()),
-                "size_bytes": file_size,
+                "output_dir": output_directory.display().to_string(),
+                "index_path": index_path.display().to_string(),
+                "sessions_count": sessions.len(),
+                "size_bytes": total_size,
                 "encrypted": encrypt,
-                "messages_count": message_count,
-                "agent": agent_name,
-                "workspace": workspace,
-                "title": session_title
+                "sessions": pages,
             }
         });
         println!("{}", serde_json::to_string_pretty(&result).unwrap());
     } else {
-        println!("✓ Exported to {}", output_path.display());
+        println!(
+            "✓ Exported {} sessions to {}",
+            sessions.len(),
+            output_directory.display()
+        );
         if encrypt {
-            println!("  🔒 Encrypted with Web Crypto (AES-256-GCM)");
+            println!("  🔒 Encrypted with Web Crypto (AES-256-GCM), one password for all pages");
         }
-        println!("  {} messages, {} bytes", message_count, file_size);
+        println!("  Open {}", index_path.display());
     }
 
     Ok(())
@@ -11137,1046 +13714,1596 @@ pub fn detect_agent_format(messages: &[html_export::Message]) -> AgentFormat {
                 );
                 return AgentFormat::Cursor;
             }
-            // Claude Code uses standard tool names (Bash, Read, Write, etc.)
-            if matches!(
-                tc.name.as_str(),
-                "Bash" | "Read" | "Write" | "Edit" | "Glob" | "Grep" | "Task" | "WebFetch"
-            ) {
-                trace!(
-                    agent_format = "claude_code",
-                    "Detected Claude Code format from tool name"
-                );
-                return AgentFormat::ClaudeCode;
+            // Claude Code uses standard tool names (Bash, Read, Write, etc.)
+            if matches!(
+                tc.name.as_str(),
+                "Bash" | "Read" | "Write" | "Edit" | "Glob" | "Grep" | "Task" | "WebFetch"
+            ) {
+                trace!(
+                    agent_format = "claude_code",
+                    "Detected Claude Code format from tool name"
+                );
+                return AgentFormat::ClaudeCode;
+            }
+        }
+    }
+
+    trace!(
+        agent_format = "generic",
+        "Using generic format (no specific pattern detected)"
+    );
+    AgentFormat::Generic
+}
+
+/// Classify a message for grouping purposes.
+///
+/// Determines how a message should be handled in the grouping algorithm:
+/// - Starting a new group
+/// - Attaching to current group
+/// - Being a tool result
+/// - Being skipped
+pub fn classify_message(msg: &html_export::Message, _format: AgentFormat) -> MessageClassification {
+    use tracing::trace;
+
+    let role = msg.role.as_str();
+    let has_content = !msg.content.trim().is_empty();
+    let has_tool = msg.tool_call.is_some();
+
+    trace!(
+        role = role,
+        has_content = has_content,
+        has_tool = has_tool,
+        "Classifying message"
+    );
+
+    match role {
+        "user" => {
+            if has_content {
+                MessageClassification::UserContent
+            } else {
+                MessageClassification::Empty
+            }
+        }
+        "assistant" | "agent" => {
+            if has_content {
+                MessageClassification::AssistantContent
+            } else if has_tool {
+                MessageClassification::AssistantToolOnly
+            } else {
+                MessageClassification::Empty
+            }
+        }
+        "tool" | "function" => {
+            // Tool result messages
+            MessageClassification::ToolResult
+        }
+        "system" => MessageClassification::System,
+        _ => {
+            // Unknown role - check if it has meaningful content
+            if has_content || has_tool {
+                MessageClassification::AssistantContent
+            } else {
+                MessageClassification::Empty
+            }
+        }
+    }
+}
+
+/// Extract correlation ID from a message for tool call/result matching.
+///
+/// Different formats use different correlation mechanisms:
+/// - Claude Code: tool_use_id in content blocks
+/// - Codex: function call name
+/// - Generic: message index fallback
+pub fn extract_correlation_id(msg: &html_export::Message, format: AgentFormat) -> Option<String> {
+    use tracing::trace;
+
+    // First, try to use the tool call name as a simple correlation
+    // This works for most formats as a baseline
+    if let Some(ref tc) = msg.tool_call {
+        let corr_id = match format {
+            AgentFormat::ClaudeCode => {
+                // Claude uses tool_use_id but we don't have access to raw JSON here
+                // Fall back to tool name + index
+                Some(format!("claude-{}", tc.name))
+            }
+            AgentFormat::Codex => {
+                // Codex correlates by function name
+                Some(format!("codex-{}", tc.name))
+            }
+            AgentFormat::Cursor => Some(format!("cursor-{}", tc.name)),
+            AgentFormat::OpenCode => Some(format!("opencode-{}", tc.name)),
+            AgentFormat::Generic => Some(format!("generic-{}", tc.name)),
+        };
+        trace!(correlation_id = ?corr_id, tool_name = %tc.name, "Extracted correlation ID");
+        return corr_id;
+    }
+
+    // For tool results without explicit tool_call, use index as fallback
+    msg.index.map(|idx| format!("index-{}", idx))
+}
+
+/// Flush the current group into the groups vector if it exists.
+fn flush_group(
+    groups: &mut Vec<html_export::MessageGroup>,
+    current_group: &mut Option<html_export::MessageGroup>,
+) {
+    if let Some(group) = current_group.take() {
+        tracing::trace!(
+            group_type = ?group.group_type,
+            tool_count = group.tool_count(),
+            "Flushing message group"
+        );
+        groups.push(group);
+    }
+}
+
+/// Groups flat messages into MessageGroups with tool correlation.
+///
+/// # Algorithm
+/// 1. Detect agent format from message structure
+/// 2. Classify each message
+/// 3. User/Assistant content messages start new groups
+/// 4. Tool-only messages attach to current assistant group
+/// 5. Tool results correlate by ID to matching tool call
+/// 6. System messages are standalone groups
+/// 7. Track timestamps for group range
+///
+/// # Logging
+/// - INFO: Group formation summary
+/// - DEBUG: Each message classification
+/// - TRACE: Correlation matching details
+///
+/// # Example
+/// ```ignore
+/// let messages: Vec<Message> = load_messages();
+/// let groups = group_messages_for_export(messages);
+/// for group in groups {
+///     render_message_group(&group);
+/// }
+/// ```
+pub fn group_messages_for_export(
+    messages: Vec<html_export::Message>,
+) -> Vec<html_export::MessageGroup> {
+    use tracing::{debug, info, trace};
+
+    info!(message_count = messages.len(), "Starting message grouping");
+
+    let format = detect_agent_format(&messages);
+    debug!(?format, "Detected agent format");
+
+    let mut groups: Vec<html_export::MessageGroup> = Vec::new();
+    let mut current_group: Option<html_export::MessageGroup> = None;
+
+    for (idx, msg) in messages.iter().enumerate() {
+        let classification = classify_message(msg, format);
+        debug!(
+            idx = idx,
+            classification = ?classification,
+            role = %msg.role,
+            content_preview = %msg.content.chars().take(50).collect::<String>(),
+            "Classified message"
+        );
+
+        match classification {
+            MessageClassification::UserContent => {
+                // User messages start a new group
+                flush_group(&mut groups, &mut current_group);
+                let group = html_export::MessageGroup::user(msg.clone());
+                current_group = Some(group);
+            }
+
+            MessageClassification::AssistantContent => {
+                // Assistant content starts a new group
+                flush_group(&mut groups, &mut current_group);
+                let mut group = html_export::MessageGroup::assistant(msg.clone());
+
+                // If assistant has embedded tool calls, add them
+                if let Some(ref tc) = msg.tool_call {
+                    let corr_id = extract_correlation_id(msg, format);
+                    group.add_tool_call(tc.clone(), corr_id);
+                    trace!(tool_name = %tc.name, "Added embedded tool call to assistant group");
+                }
+                current_group = Some(group);
+            }
+
+            MessageClassification::AssistantToolOnly => {
+                // Tool-only messages attach to current group or create tool-only group
+                if let Some(ref mut g) = current_group {
+                    // Attach to existing group
+                    if let Some(ref tc) = msg.tool_call {
+                        let corr_id = extract_correlation_id(msg, format);
+                        g.add_tool_call(tc.clone(), corr_id);
+                        g.update_end_timestamp(msg.timestamp.clone());
+                        trace!(tool_name = %tc.name, "Attached tool call to current group");
+                    }
+                } else {
+                    // Create a new tool-only group
+                    let mut group = html_export::MessageGroup::tool_only(msg.clone());
+                    if let Some(ref tc) = msg.tool_call {
+                        let corr_id = extract_correlation_id(msg, format);
+                        group.add_tool_call(tc.clone(), corr_id);
+                    }
+                    current_group = Some(group);
+                    trace!("Created new tool-only group");
+                }
+            }
+
+            MessageClassification::ToolResult => {
+                // Tool results attach to current group
+                if let Some(ref mut g) = current_group {
+                    // Create a ToolResult from the message
+                    let tool_name = msg
+                        .tool_call
+                        .as_ref()
+                        .map(|tc| tc.name.clone())
+                        .unwrap_or_else(|| "tool_result".to_string());
+
+                    let content = if let Some(ref tc) = msg.tool_call {
+                        tc.output.clone().unwrap_or_else(|| msg.content.clone())
+                    } else {
+                        msg.content.clone()
+                    };
+
+                    let status = msg
+                        .tool_call
+                        .as_ref()
+                        .and_then(|tc| tc.status)
+                        .unwrap_or(html_export::ToolStatus::Success);
+
+                    let mut result =
+                        html_export::ToolResult::new(tool_name.clone(), content, status);
+
+                    // Try to get correlation ID
+                    if let Some(corr_id) = extract_correlation_id(msg, format) {
+                        result = result.with_correlation_id(corr_id);
+                    }
+
+                    g.add_tool_result(result);
+                    g.update_end_timestamp(msg.timestamp.clone());
+                    trace!(tool_name = %tool_name, "Added tool result to group");
+                } else {
+                    debug!(
+                        idx = idx,
+                        "Orphan tool result, no current group to attach to"
+                    );
+                }
+            }
+
+            MessageClassification::System => {
+                // System messages are standalone groups
+                flush_group(&mut groups, &mut current_group);
+                let group = html_export::MessageGroup::system(msg.clone());
+                groups.push(group);
+                trace!("Added standalone system group");
+            }
+
+            MessageClassification::Empty => {
+                trace!(idx = idx, "Skipping empty message");
             }
         }
     }
 
-    trace!(
-        agent_format = "generic",
-        "Using generic format (no specific pattern detected)"
+    // Flush any remaining group
+    flush_group(&mut groups, &mut current_group);
+
+    info!(
+        group_count = groups.len(),
+        original_messages = messages.len(),
+        "Message grouping complete"
     );
-    AgentFormat::Generic
+
+    groups
 }
 
-/// Classify a message for grouping purposes.
-///
-/// Determines how a message should be handled in the grouping algorithm:
-/// - Starting a new group
-/// - Attaching to current group
-/// - Being a tool result
-/// - Being skipped
-pub fn classify_message(msg: &html_export::Message, _format: AgentFormat) -> MessageClassification {
-    use tracing::trace;
+// ============================================================================
+// Unit Tests for Message Grouping Algorithm
+// ============================================================================
 
-    let role = msg.role.as_str();
-    let has_content = !msg.content.trim().is_empty();
-    let has_tool = msg.tool_call.is_some();
+#[cfg(test)]
+mod message_grouping_tests {
+    use super::*;
+    use html_export::{Message, MessageGroupType, ToolCall, ToolStatus};
 
-    trace!(
-        role = role,
-        has_content = has_content,
-        has_tool = has_tool,
-        "Classifying message"
-    );
+    // Helper to create a user message
+    fn msg_user(content: &str) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: content.to_string(),
+            timestamp: Some("2026-01-15T10:00:00Z".to_string()),
+            tool_call: None,
+            index: None,
+            author: None,
+        }
+    }
 
-    match role {
-        "user" => {
-            if has_content {
-                MessageClassification::UserContent
-            } else {
-                MessageClassification::Empty
-            }
+    // Helper to create an assistant message
+    fn msg_assistant(content: &str) -> Message {
+        Message {
+            role: "assistant".to_string(),
+            content: content.to_string(),
+            timestamp: Some("2026-01-15T10:00:05Z".to_string()),
+            tool_call: None,
+            index: None,
+            author: None,
         }
-        "assistant" | "agent" => {
-            if has_content {
-                MessageClassification::AssistantContent
-            } else if has_tool {
-                MessageClassification::AssistantToolOnly
-            } else {
-                MessageClassification::Empty
-            }
+    }
+
+    // Helper to create an assistant message with tool call
+    fn msg_assistant_with_tool(content: &str, tool_name: &str, tool_input: &str) -> Message {
+        Message {
+            role: "assistant".to_string(),
+            content: content.to_string(),
+            timestamp: Some("2026-01-15T10:00:05Z".to_string()),
+            tool_call: Some(ToolCall {
+                name: tool_name.to_string(),
+                input: tool_input.to_string(),
+                output: None,
+                status: None,
+            }),
+            index: None,
+            author: None,
         }
-        "tool" | "function" => {
-            // Tool result messages
-            MessageClassification::ToolResult
+    }
+
+    // Helper to create a tool result message
+    fn msg_tool_result(tool_name: &str, output: &str, status: ToolStatus) -> Message {
+        Message {
+            role: "tool".to_string(),
+            content: output.to_string(),
+            timestamp: Some("2026-01-15T10:00:10Z".to_string()),
+            tool_call: Some(ToolCall {
+                name: tool_name.to_string(),
+                input: String::new(),
+                output: Some(output.to_string()),
+                status: Some(status),
+            }),
+            index: None,
+            author: None,
         }
-        "system" => MessageClassification::System,
-        _ => {
-            // Unknown role - check if it has meaningful content
-            if has_content || has_tool {
-                MessageClassification::AssistantContent
-            } else {
-                MessageClassification::Empty
-            }
+    }
+
+    // Helper to create a system message
+    fn msg_system(content: &str) -> Message {
+        Message {
+            role: "system".to_string(),
+            content: content.to_string(),
+            timestamp: Some("2026-01-15T09:59:00Z".to_string()),
+            tool_call: None,
+            index: None,
+            author: None,
         }
     }
-}
 
-/// Extract correlation ID from a message for tool call/result matching.
-///
-/// Different formats use different correlation mechanisms:
-/// - Claude Code: tool_use_id in content blocks
-/// - Codex: function call name
-/// - Generic: message index fallback
-pub fn extract_correlation_id(msg: &html_export::Message, format: AgentFormat) -> Option<String> {
-    use tracing::trace;
+    // Helper to create an empty message
+    fn msg_empty() -> Message {
+        Message {
+            role: "assistant".to_string(),
+            content: String::new(),
+            timestamp: Some("2026-01-15T10:00:00Z".to_string()),
+            tool_call: None,
+            index: None,
+            author: None,
+        }
+    }
 
-    // First, try to use the tool call name as a simple correlation
-    // This works for most formats as a baseline
-    if let Some(ref tc) = msg.tool_call {
-        let corr_id = match format {
-            AgentFormat::ClaudeCode => {
-                // Claude uses tool_use_id but we don't have access to raw JSON here
-                // Fall back to tool name + index
-                Some(format!("claude-{}", tc.name))
-            }
-            AgentFormat::Codex => {
-                // Codex correlates by function name
-                Some(format!("codex-{}", tc.name))
-            }
-            AgentFormat::Cursor => Some(format!("cursor-{}", tc.name)),
-            AgentFormat::OpenCode => Some(format!("opencode-{}", tc.name)),
-            AgentFormat::Generic => Some(format!("generic-{}", tc.name)),
-        };
-        trace!(correlation_id = ?corr_id, tool_name = %tc.name, "Extracted correlation ID");
-        return corr_id;
+    // ========================================================================
+    // Basic Grouping Tests
+    // ========================================================================
+
+    #[test]
+    fn test_single_user_message() {
+        let msgs = vec![msg_user("Hello")];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].group_type, MessageGroupType::User);
+        assert_eq!(groups[0].primary.content, "Hello");
     }
 
-    // For tool results without explicit tool_call, use index as fallback
-    msg.index.map(|idx| format!("index-{}", idx))
-}
+    #[test]
+    fn test_single_assistant_message() {
+        let msgs = vec![msg_assistant("Hi there!")];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].group_type, MessageGroupType::Assistant);
+    }
 
-/// Flush the current group into the groups vector if it exists.
-fn flush_group(
-    groups: &mut Vec<html_export::MessageGroup>,
-    current_group: &mut Option<html_export::MessageGroup>,
-) {
-    if let Some(group) = current_group.take() {
-        tracing::trace!(
-            group_type = ?group.group_type,
-            tool_count = group.tool_count(),
-            "Flushing message group"
-        );
-        groups.push(group);
+    #[test]
+    fn test_user_assistant_pair() {
+        let msgs = vec![msg_user("Hello"), msg_assistant("Hi there!")];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group_type, MessageGroupType::User);
+        assert_eq!(groups[1].group_type, MessageGroupType::Assistant);
     }
-}
 
-/// Groups flat messages into MessageGroups with tool correlation.
-///
-/// # Algorithm
-/// 1. Detect agent format from message structure
-/// 2. Classify each message
-/// 3. User/Assistant content messages start new groups
-/// 4. Tool-only messages attach to current assistant group
-/// 5. Tool results correlate by ID to matching tool call
-/// 6. System messages are standalone groups
-/// 7. Track timestamps for group range
-///
-/// # Logging
-/// - INFO: Group formation summary
-/// - DEBUG: Each message classification
-/// - TRACE: Correlation matching details
-///
-/// # Example
-/// ```ignore
-/// let messages: Vec<Message> = load_messages();
-/// let groups = group_messages_for_export(messages);
-/// for group in groups {
-///     render_message_group(&group);
-/// }
-/// ```
-pub fn group_messages_for_export(
-    messages: Vec<html_export::Message>,
-) -> Vec<html_export::MessageGroup> {
-    use tracing::{debug, info, trace};
+    #[test]
+    fn test_assistant_with_single_tool() {
+        let msgs = vec![
+            msg_assistant_with_tool("Let me check that file.", "Read", "/path/file.rs"),
+            msg_tool_result("Read", "file contents here", ToolStatus::Success),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 1, "Should group assistant + tool into one");
+        assert_eq!(groups[0].group_type, MessageGroupType::Assistant);
+        assert_eq!(groups[0].tool_calls.len(), 1, "Should have 1 tool call");
+    }
+
+    #[test]
+    fn test_assistant_with_multiple_tools() {
+        let msgs = vec![
+            msg_assistant_with_tool("Running multiple commands.", "Bash", "ls"),
+            msg_tool_result("Bash", "file1 file2", ToolStatus::Success),
+            msg_assistant_with_tool("", "Read", "/README.md"),
+            msg_tool_result("Read", "# Title", ToolStatus::Success),
+        ];
+        let groups = group_messages_for_export(msgs);
+        // First assistant group, then tool-only groups that get attached
+        assert!(!groups.is_empty(), "Should have at least one group");
+    }
+
+    // ========================================================================
+    // System Message Tests
+    // ========================================================================
+
+    #[test]
+    fn test_system_message_standalone() {
+        let msgs = vec![
+            msg_system("You are a helpful assistant."),
+            msg_user("Hello"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].group_type, MessageGroupType::System);
+        assert_eq!(groups[1].group_type, MessageGroupType::User);
+    }
 
-    info!(message_count = messages.len(), "Starting message grouping");
+    #[test]
+    fn test_system_message_in_middle() {
+        let msgs = vec![
+            msg_user("Hello"),
+            msg_assistant("Hi!"),
+            msg_system("Context reminder"),
+            msg_user("Continue"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 4);
+        assert_eq!(groups[2].group_type, MessageGroupType::System);
+    }
 
-    let format = detect_agent_format(&messages);
-    debug!(?format, "Detected agent format");
+    // ========================================================================
+    // Edge Cases
+    // ========================================================================
 
-    let mut groups: Vec<html_export::MessageGroup> = Vec::new();
-    let mut current_group: Option<html_export::MessageGroup> = None;
+    #[test]
+    fn test_empty_messages_filtered() {
+        let msgs = vec![
+            msg_user("Hello"),
+            msg_empty(), // Should be filtered
+            msg_assistant("Hi there!"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        // Empty message should be skipped
+        assert_eq!(groups.len(), 2);
+    }
 
-    for (idx, msg) in messages.iter().enumerate() {
-        let classification = classify_message(msg, format);
-        debug!(
-            idx = idx,
-            classification = ?classification,
-            role = %msg.role,
-            content_preview = %msg.content.chars().take(50).collect::<String>(),
-            "Classified message"
+    #[test]
+    fn test_consecutive_user_messages() {
+        let msgs = vec![
+            msg_user("First question"),
+            msg_user("Second question"),
+            msg_user("Third question"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(
+            groups.len(),
+            3,
+            "Each user message should be separate group"
         );
+        for group in &groups {
+            assert_eq!(group.group_type, MessageGroupType::User);
+        }
+    }
 
-        match classification {
-            MessageClassification::UserContent => {
-                // User messages start a new group
-                flush_group(&mut groups, &mut current_group);
-                let group = html_export::MessageGroup::user(msg.clone());
-                current_group = Some(group);
-            }
+    #[test]
+    fn test_consecutive_assistant_messages() {
+        let msgs = vec![
+            msg_assistant("First response"),
+            msg_assistant("Second response"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 2, "Each assistant message should be separate");
+    }
 
-            MessageClassification::AssistantContent => {
-                // Assistant content starts a new group
-                flush_group(&mut groups, &mut current_group);
-                let mut group = html_export::MessageGroup::assistant(msg.clone());
+    #[test]
+    fn test_orphan_tool_result() {
+        // Tool result without preceding tool call should be handled gracefully
+        let msgs = vec![
+            msg_user("Hello"),
+            msg_tool_result("Read", "orphan result", ToolStatus::Success),
+        ];
+        let groups = group_messages_for_export(msgs);
+        // Should have user group, orphan tool result might be dropped or attached
+        assert!(!groups.is_empty());
+    }
 
-                // If assistant has embedded tool calls, add them
-                if let Some(ref tc) = msg.tool_call {
-                    let corr_id = extract_correlation_id(msg, format);
-                    group.add_tool_call(tc.clone(), corr_id);
-                    trace!(tool_name = %tc.name, "Added embedded tool call to assistant group");
-                }
-                current_group = Some(group);
-            }
+    #[test]
+    fn test_unicode_content() {
+        let msgs = vec![
+            msg_user("Test Unicode: 你好世界! 🎉🚀 مرحبا العالم"),
+            msg_assistant("Handling multilingual: Привет мир 日本語"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].primary.content.contains("你好"));
+        assert!(groups[1].primary.content.contains("Привет"));
+    }
 
-            MessageClassification::AssistantToolOnly => {
-                // Tool-only messages attach to current group or create tool-only group
-                if let Some(ref mut g) = current_group {
-                    // Attach to existing group
-                    if let Some(ref tc) = msg.tool_call {
-                        let corr_id = extract_correlation_id(msg, format);
-                        g.add_tool_call(tc.clone(), corr_id);
-                        g.update_end_timestamp(msg.timestamp.clone());
-                        trace!(tool_name = %tc.name, "Attached tool call to current group");
-                    }
-                } else {
-                    // Create a new tool-only group
-                    let mut group = html_export::MessageGroup::tool_only(msg.clone());
-                    if let Some(ref tc) = msg.tool_call {
-                        let corr_id = extract_correlation_id(msg, format);
-                        group.add_tool_call(tc.clone(), corr_id);
-                    }
-                    current_group = Some(group);
-                    trace!("Created new tool-only group");
-                }
-            }
+    #[test]
+    fn test_html_special_characters() {
+        let msgs = vec![
+            msg_user("<script>alert('xss')</script>"),
+            msg_assistant("Response with & \"quotes\" 'single'"),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 2);
+        // Content should be preserved (escaping happens at render time)
+        assert!(groups[0].primary.content.contains("<script>"));
+    }
 
-            MessageClassification::ToolResult => {
-                // Tool results attach to current group
-                if let Some(ref mut g) = current_group {
-                    // Create a ToolResult from the message
-                    let tool_name = msg
-                        .tool_call
-                        .as_ref()
-                        .map(|tc| tc.name.clone())
-                        .unwrap_or_else(|| "tool_result".to_string());
+    #[test]
+    fn test_empty_input_returns_empty() {
+        let msgs: Vec<Message> = vec![];
+        let groups = group_messages_for_export(msgs);
+        assert!(groups.is_empty());
+    }
 
-                    let content = if let Some(ref tc) = msg.tool_call {
-                        tc.output.clone().unwrap_or_else(|| msg.content.clone())
-                    } else {
-                        msg.content.clone()
-                    };
+    // ========================================================================
+    // Tool Status Tests
+    // ========================================================================
 
-                    let status = msg
-                        .tool_call
-                        .as_ref()
-                        .and_then(|tc| tc.status)
-                        .unwrap_or(html_export::ToolStatus::Success);
+    #[test]
+    fn test_tool_success_status() {
+        let msgs = vec![
+            msg_assistant_with_tool("Running command", "Bash", "echo hello"),
+            msg_tool_result("Bash", "hello", ToolStatus::Success),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 1);
 
-                    let mut result =
-                        html_export::ToolResult::new(tool_name.clone(), content, status);
+        // Verify tool call exists
+        assert!(
+            !groups[0].tool_calls.is_empty(),
+            "Should have at least one tool call"
+        );
 
-                    // Try to get correlation ID
-                    if let Some(corr_id) = extract_correlation_id(msg, format) {
-                        result = result.with_correlation_id(corr_id);
-                    }
+        // Check if result was correlated (may not always correlate depending on format)
+        let tc = &groups[0].tool_calls[0];
+        assert_eq!(tc.call.name, "Bash", "Tool call should be Bash");
+    }
 
-                    g.add_tool_result(result);
-                    g.update_end_timestamp(msg.timestamp.clone());
-                    trace!(tool_name = %tool_name, "Added tool result to group");
-                } else {
-                    debug!(
-                        idx = idx,
-                        "Orphan tool result, no current group to attach to"
-                    );
-                }
-            }
+    #[test]
+    fn test_tool_error_status() {
+        let msgs = vec![
+            msg_assistant_with_tool("Reading file", "Read", "/nonexistent"),
+            msg_tool_result("Read", "File not found", ToolStatus::Error),
+        ];
+        let groups = group_messages_for_export(msgs);
+        assert_eq!(groups.len(), 1);
 
-            MessageClassification::System => {
-                // System messages are standalone groups
-                flush_group(&mut groups, &mut current_group);
-                let group = html_export::MessageGroup::system(msg.clone());
-                groups.push(group);
-                trace!("Added standalone system group");
-            }
+        // Verify tool call exists
+        assert!(
+            !groups[0].tool_calls.is_empty(),
+            "Should have at least one tool call"
+        );
 
-            MessageClassification::Empty => {
-                trace!(idx = idx, "Skipping empty message");
-            }
-        }
+        // Tool call should exist with Read name
+        let tc = &groups[0].tool_calls[0];
+        assert_eq!(tc.call.name, "Read", "Tool call should be Read");
     }
 
-    // Flush any remaining group
-    flush_group(&mut groups, &mut current_group);
-
-    info!(
-        group_count = groups.len(),
-        original_messages = messages.len(),
-        "Message grouping complete"
-    );
+    // ========================================================================
+    // Agent Format Detection Tests
+    // ========================================================================
 
-    groups
-}
+    #[test]
+    fn test_detect_claude_format() {
+        let msgs = vec![msg_assistant_with_tool("Let me check", "Read", "/file.rs")];
+        let format = detect_agent_format(&msgs);
+        assert_eq!(format, AgentFormat::ClaudeCode);
+    }
 
-// ============================================================================
-// Unit Tests for Message Grouping Algorithm
-// ============================================================================
+    #[test]
+    fn test_detect_generic_format() {
+        let msgs = vec![msg_user("Hello"), msg_assistant("Hi")];
+        let format = detect_agent_format(&msgs);
+        // No tool calls, so should be generic
+        assert_eq!(format, AgentFormat::Generic);
+    }
 
-#[cfg(test)]
-mod message_grouping_tests {
-    use super::*;
-    use html_export::{Message, MessageGroupType, ToolCall, ToolStatus};
+    // ========================================================================
+    // Message Classification Tests
+    // ========================================================================
 
-    // Helper to create a user message
-    fn msg_user(content: &str) -> Message {
-        Message {
-            role: "user".to_string(),
-            content: content.to_string(),
-            timestamp: Some("2026-01-15T10:00:00Z".to_string()),
-            tool_call: None,
-            index: None,
-            author: None,
-        }
+    #[test]
+    fn test_classify_user_content() {
+        let msg = msg_user("Hello");
+        let class = classify_message(&msg, AgentFormat::Generic);
+        assert_eq!(class, MessageClassification::UserContent);
     }
 
-    // Helper to create an assistant message
-    fn msg_assistant(content: &str) -> Message {
-        Message {
-            role: "assistant".to_string(),
-            content: content.to_string(),
-            timestamp: Some("2026-01-15T10:00:05Z".to_string()),
-            tool_call: None,
-            index: None,
-            author: None,
-        }
+    #[test]
+    fn test_classify_assistant_content() {
+        let msg = msg_assistant("Hi there");
+        let class = classify_message(&msg, AgentFormat::Generic);
+        assert_eq!(class, MessageClassification::AssistantContent);
     }
 
-    // Helper to create an assistant message with tool call
-    fn msg_assistant_with_tool(content: &str, tool_name: &str, tool_input: &str) -> Message {
-        Message {
-            role: "assistant".to_string(),
-            content: content.to_string(),
-            timestamp: Some("2026-01-15T10:00:05Z".to_string()),
-            tool_call: Some(ToolCall {
-                name: tool_name.to_string(),
-                input: tool_input.to_string(),
-                output: None,
-                status: None,
-            }),
-            index: None,
-            author: None,
-        }
+    #[test]
+    fn test_classify_system() {
+        let msg = msg_system("You are helpful");
+        let class = classify_message(&msg, AgentFormat::Generic);
+        assert_eq!(class, MessageClassification::System);
     }
 
-    // Helper to create a tool result message
-    fn msg_tool_result(tool_name: &str, output: &str, status: ToolStatus) -> Message {
-        Message {
-            role: "tool".to_string(),
-            content: output.to_string(),
-            timestamp: Some("2026-01-15T10:00:10Z".to_string()),
-            tool_call: Some(ToolCall {
-                name: tool_name.to_string(),
-                input: String::new(),
-                output: Some(output.to_string()),
-                status: Some(status),
-            }),
-            index: None,
-            author: None,
-        }
+    #[test]
+    fn test_classify_empty() {
+        let msg = msg_empty();
+        let class = classify_message(&msg, AgentFormat::Generic);
+        assert_eq!(class, MessageClassification::Empty);
     }
 
-    // Helper to create a system message
-    fn msg_system(content: &str) -> Message {
-        Message {
-            role: "system".to_string(),
-            content: content.to_string(),
-            timestamp: Some("2026-01-15T09:59:00Z".to_string()),
-            tool_call: None,
-            index: None,
-            author: None,
-        }
+    #[test]
+    fn test_classify_tool_result() {
+        let msg = msg_tool_result("Read", "contents", ToolStatus::Success);
+        let class = classify_message(&msg, AgentFormat::Generic);
+        assert_eq!(class, MessageClassification::ToolResult);
     }
 
-    // Helper to create an empty message
-    fn msg_empty() -> Message {
-        Message {
-            role: "assistant".to_string(),
-            content: String::new(),
-            timestamp: Some("2026-01-15T10:00:00Z".to_string()),
-            tool_call: None,
-            index: None,
-            author: None,
+    // ========================================================================
+    // Performance Tests
+    // ========================================================================
+
+    #[test]
+    fn test_large_session_performance() {
+        use std::time::Instant;
+
+        // Generate 1000 messages
+        let mut msgs = Vec::with_capacity(1000);
+        for i in 0..500 {
+            msgs.push(msg_user(&format!("Question {}", i)));
+            msgs.push(msg_assistant(&format!("Answer {}", i)));
         }
+
+        let start = Instant::now();
+        let groups = group_messages_for_export(msgs);
+        let elapsed = start.elapsed();
+
+        assert_eq!(groups.len(), 1000);
+        assert!(
+            elapsed.as_millis() < 500,
+            "Grouping 1000 messages took {}ms, should be < 500ms",
+            elapsed.as_millis()
+        );
     }
 
     // ========================================================================
-    // Basic Grouping Tests
+    // Timestamp Tests
     // ========================================================================
 
     #[test]
-    fn test_single_user_message() {
-        let msgs = vec![msg_user("Hello")];
+    fn test_group_timestamps_captured() {
+        let mut user_msg = msg_user("Hello");
+        user_msg.timestamp = Some("2026-01-15T10:00:00Z".to_string());
+
+        let msgs = vec![user_msg];
         let groups = group_messages_for_export(msgs);
+
         assert_eq!(groups.len(), 1);
-        assert_eq!(groups[0].group_type, MessageGroupType::User);
-        assert_eq!(groups[0].primary.content, "Hello");
+        assert_eq!(
+            groups[0].start_timestamp,
+            Some("2026-01-15T10:00:00Z".to_string())
+        );
+    }
+}
+
+fn format_as_markdown(
+    messages: &[serde_json::Value],
+    title: &Option<String>,
+    start_ts: Option<i64>,
+    include_tools: bool,
+) -> String {
+    use chrono::{TimeZone, Utc};
+    let mut md = String::new();
+    md.push_str("# ");
+    md.push_str(title.as_deref().unwrap_or("Conversation Export"));
+    md.push('\n');
+
+    if let Some(ts) = start_ts
+        && let Some(dt) = Utc.timestamp_millis_opt(ts).single()
+    {
+        md.push_str(&format!(
+            "\n*Started: {}*\n",
+            dt.format("%Y-%m-%d %H:%M UTC")
+        ));
+    }
+    md.push_str("\n---\n\n");
+
+    for msg in messages {
+        let role = extract_role(msg);
+        match role.as_str() {
+            "user" => md.push_str("## 👤 User\n\n"),
+            "assistant" => md.push_str("## 🤖 Assistant\n\n"),
+            _ => md.push_str(&format!("## {}\n\n", role)),
+        }
+
+        let content = extract_text_content(msg);
+        if !content.is_empty() {
+            md.push_str(&content);
+            md.push_str("\n\n");
+        }
+
+        // Also handle tool blocks if include_tools is set
+        if include_tools {
+            let content_val = msg
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .or_else(|| msg.get("content"));
+            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
+                for block in arr {
+                    if let Some(block_type) = block.get("type").and_then(|t| t.as_str()) {
+                        match block_type {
+                            "tool_use" => {
+                                let name =
+                                    block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                                md.push_str(&format!("**Tool: {}**\n", name));
+                                if let Some(input) = block.get("input") {
+                                    md.push_str("```json\n");
+                                    md.push_str(
+                                        &serde_json::to_string_pretty(input).unwrap_or_default(),
+                                    );
+                                    md.push_str("\n```\n\n");
+                                }
+                            }
+                            "tool_result" => {
+                                md.push_str("**Tool Result:**\n");
+                                if let Some(c) = block.get("content").and_then(|c| c.as_str()) {
+                                    let preview: String = c.chars().take(500).collect();
+                                    md.push_str("```\n");
+                                    md.push_str(&preview);
+                                    if c.len() > 500 {
+                                        md.push_str("\n... (truncated)");
+                                    }
+                                    md.push_str("\n```\n\n");
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+        md.push_str("---\n\n");
     }
+    md
+}
 
-    #[test]
-    fn test_single_assistant_message() {
-        let msgs = vec![msg_assistant("Hi there!")];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 1);
-        assert_eq!(groups[0].group_type, MessageGroupType::Assistant);
-    }
+fn format_as_text(messages: &[serde_json::Value], include_tools: bool) -> String {
+    let mut text = String::new();
+    for msg in messages {
+        let role = extract_role(msg);
+        text.push_str(&format!("=== {} ===\n\n", role.to_uppercase()));
 
-    #[test]
-    fn test_user_assistant_pair() {
-        let msgs = vec![msg_user("Hello"), msg_assistant("Hi there!")];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 2);
-        assert_eq!(groups[0].group_type, MessageGroupType::User);
-        assert_eq!(groups[1].group_type, MessageGroupType::Assistant);
-    }
+        let content = extract_text_content(msg);
+        if !content.is_empty() {
+            text.push_str(&content);
+            text.push_str("\n\n");
+        }
 
-    #[test]
-    fn test_assistant_with_single_tool() {
-        let msgs = vec![
-            msg_assistant_with_tool("Let me check that file.", "Read", "/path/file.rs"),
-            msg_tool_result("Read", "file contents here", ToolStatus::Success),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 1, "Should group assistant + tool into one");
-        assert_eq!(groups[0].group_type, MessageGroupType::Assistant);
-        assert_eq!(groups[0].tool_calls.len(), 1, "Should have 1 tool call");
+        // Also handle tool blocks if include_tools is set
+        if include_tools {
+            // Check nested message.content for tool blocks
+            let content_val = msg
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .or_else(|| msg.get("content"));
+            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
+                for block in arr {
+                    if let Some(block_type) = block.get("type").and_then(|t| t.as_str())
+                        && block_type == "tool_use"
+                    {
+                        let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                        text.push_str(&format!("[Tool: {}]\n", name));
+                    }
+                }
+            }
+        }
     }
+    text
+}
 
-    #[test]
-    fn test_assistant_with_multiple_tools() {
-        let msgs = vec![
-            msg_assistant_with_tool("Running multiple commands.", "Bash", "ls"),
-            msg_tool_result("Bash", "file1 file2", ToolStatus::Success),
-            msg_assistant_with_tool("", "Read", "/README.md"),
-            msg_tool_result("Read", "# Title", ToolStatus::Success),
-        ];
-        let groups = group_messages_for_export(msgs);
-        // First assistant group, then tool-only groups that get attached
-        assert!(!groups.is_empty(), "Should have at least one group");
-    }
+fn format_as_html(
+    messages: &[serde_json::Value],
+    title: &Option<String>,
+    start_ts: Option<i64>,
+    include_tools: bool,
+) -> String {
+    use chrono::{TimeZone, Utc};
+    let title_str = title.as_deref().unwrap_or("Conversation Export");
+    let date_str = start_ts
+        .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
+        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
 
-    // ========================================================================
-    // System Message Tests
-    // ========================================================================
+    let mut html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>{title_str}</title>
+    <style>
+        body {{ font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; padding: 20px; background: #0d0d12; color: #e0e0e5; }}
+        .message {{ background: #18181f; border-radius: 8px; padding: 16px; margin: 12px 0; box-shadow: 0 1px 3px rgba(0,0,0,0.3); }}
+        .user {{ border-left: 4px solid #3b82f6; }}
+        .assistant {{ border-left: 4px solid #22c55e; }}
+        .role {{ font-weight: bold; color: #a0a0a8; margin-bottom: 8px; }}
+        .content {{ white-space: pre-wrap; line-height: 1.6; }}
+        h1 {{ color: #f0f0f5; }}
+        .meta {{ color: #6b7280; font-size: 0.9em; }}
+        /* Tool badges */
+        .tool-badges {{ display: flex; flex-wrap: wrap; gap: 4px; margin-top: 8px; }}
+        .tool-badge {{ position: relative; display: inline-flex; align-items: center; gap: 4px; padding: 2px 8px; font-size: 11px; font-family: 'JetBrains Mono', ui-monospace, monospace; background: #1e1e26; border: 1px solid #2e2e38; border-radius: 4px; cursor: pointer; transition: all 0.15s; }}
+        .tool-badge:hover {{ border-color: #f59e0b; background: rgba(245,158,11,0.1); }}
+        .tool-badge-name {{ font-weight: 600; color: #f59e0b; }}
+        .tool-popover {{ position: absolute; bottom: calc(100% + 8px); left: 50%; transform: translateX(-50%) scale(0.95); min-width: 280px; max-width: 400px; padding: 12px; background: #18181f; border: 1px solid #2e2e38; border-radius: 8px; box-shadow: 0 8px 32px rgba(0,0,0,0.4); opacity: 0; visibility: hidden; transition: all 0.15s; z-index: 1000; pointer-events: none; text-align: left; white-space: normal; }}
+        .tool-badge:hover .tool-popover {{ opacity: 1; visibility: visible; transform: translateX(-50%) scale(1); pointer-events: auto; }}
+        .tool-popover::after {{ content: ''; position: absolute; top: 100%; left: 50%; transform: translateX(-50%); border: 6px solid transparent; border-top-color: #2e2e38; }}
+        .tool-popover-header {{ display: flex; align-items: center; gap: 8px; padding-bottom: 8px; margin-bottom: 8px; border-bottom: 1px solid #2e2e38; font-weight: 600; color: #f59e0b; }}
+        .tool-popover-section {{ margin-bottom: 8px; }}
+        .tool-popover-label {{ font-size: 9px; font-weight: 700; text-transform: uppercase; letter-spacing: 0.8px; color: #6b7280; margin-bottom: 4px; display: block; }}
+        .tool-popover pre {{ margin: 0; padding: 8px; font-size: 10px; background: #0d0d12; border-radius: 4px; max-height: 150px; overflow: auto; white-space: pre-wrap; word-break: break-word; }}
+    </style>
+</head>
+<body>
+    <h1>{title_str}</h1>
+    <p class="meta">{date_str}</p>
+"#
+    );
 
-    #[test]
-    fn test_system_message_standalone() {
-        let msgs = vec![
-            msg_system("You are a helpful assistant."),
-            msg_user("Hello"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 2);
-        assert_eq!(groups[0].group_type, MessageGroupType::System);
-        assert_eq!(groups[1].group_type, MessageGroupType::User);
-    }
+    for msg in messages {
+        let role = extract_role(msg);
+        let role_class = if role == "user" { "user" } else { "assistant" };
+        let role_display = match role.as_str() {
+            "user" => "👤 User",
+            "assistant" => "🤖 Assistant",
+            "system" => "⚙️ System",
+            _ => "💬 Message",
+        };
 
-    #[test]
-    fn test_system_message_in_middle() {
-        let msgs = vec![
-            msg_user("Hello"),
-            msg_assistant("Hi!"),
-            msg_system("Context reminder"),
-            msg_user("Continue"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 4);
-        assert_eq!(groups[2].group_type, MessageGroupType::System);
-    }
+        html.push_str(&format!(
+            r#"    <div class="message {role_class}">
+        <div class="role">{role_display}</div>
+        <div class="content">"#
+        ));
 
-    // ========================================================================
-    // Edge Cases
-    // ========================================================================
+        // Use extract_text_content for consistent content extraction
+        let content = extract_text_content(msg);
+        // Strip "[Tool: X]" markers when we're showing tool badges
+        let content = if include_tools {
+            strip_tool_marker(&content)
+        } else {
+            content
+        };
+        html.push_str(&html_escape(&content));
 
-    #[test]
-    fn test_empty_messages_filtered() {
-        let msgs = vec![
-            msg_user("Hello"),
-            msg_empty(), // Should be filtered
-            msg_assistant("Hi there!"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        // Empty message should be skipped
-        assert_eq!(groups.len(), 2);
-    }
+        // Also handle tool use blocks if requested
+        if include_tools {
+            // Check for tool_use in nested message.content array
+            let content_val = msg
+                .get("message")
+                .and_then(|m| m.get("content"))
+                .or_else(|| msg.get("content"));
+            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
+                let mut tool_badges = Vec::new();
+                for block in arr {
+                    if let Some("tool_use") = block.get("type").and_then(|t| t.as_str()) {
+                        let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
+                        let icon = match name.to_lowercase().as_str() {
+                            "bash" | "shell" => "💻",
+                            "read" | "read_file" => "📖",
+                            "write" | "write_file" => "📝",
+                            "edit" => "✏️",
+                            "glob" | "find" => "🔍",
+                            "grep" | "search" => "🔎",
+                            "webfetch" | "fetch" | "http" => "🌐",
+                            "websearch" => "🔍",
+                            "task" => "🚀",
+                            _ => "🔧",
+                        };
+                        // Get input preview (truncated)
+                        let input_preview = block
+                            .get("input")
+                            .map(|v| {
+                                let s = serde_json::to_string_pretty(v).unwrap_or_default();
+                                if s.len() > 400 {
+                                    format!("{}…", &s[..400])
+                                } else {
+                                    s
+                                }
+                            })
+                            .unwrap_or_default();
 
-    #[test]
-    fn test_consecutive_user_messages() {
-        let msgs = vec![
-            msg_user("First question"),
-            msg_user("Second question"),
-            msg_user("Third question"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(
-            groups.len(),
-            3,
-            "Each user message should be separate group"
-        );
-        for group in &groups {
-            assert_eq!(group.group_type, MessageGroupType::User);
+                        let popover_content = if !input_preview.is_empty() {
+                            format!(
+                                r#"<div class="tool-popover-section"><span class="tool-popover-label">Input</span><pre>{}</pre></div>"#,
+                                html_escape(&input_preview)
+                            )
+                        } else {
+                            String::new()
+                        };
+
+                        tool_badges.push(format!(
+                            r#"<span class="tool-badge" tabindex="0">
+                                <span>{icon}</span>
+                                <span class="tool-badge-name">{name}</span>
+                                <div class="tool-popover">
+                                    <div class="tool-popover-header">{icon} {name}</div>
+                                    {popover}
+                                </div>
+                            </span>"#,
+                            icon = icon,
+                            name = html_escape(name),
+                            popover = popover_content,
+                        ));
+                    }
+                }
+                if !tool_badges.is_empty() {
+                    html.push_str(r#"<div class="tool-badges">"#);
+                    for badge in tool_badges {
+                        html.push_str(&badge);
+                    }
+                    html.push_str("</div>");
+                }
+            }
         }
-    }
 
-    #[test]
-    fn test_consecutive_assistant_messages() {
-        let msgs = vec![
-            msg_assistant("First response"),
-            msg_assistant("Second response"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 2, "Each assistant message should be separate");
+        html.push_str("</div>\n    </div>\n");
     }
+    html.push_str("</body>\n</html>\n");
+    html
+}
 
-    #[test]
-    fn test_orphan_tool_result() {
-        // Tool result without preceding tool call should be handled gracefully
-        let msgs = vec![
-            msg_user("Hello"),
-            msg_tool_result("Read", "orphan result", ToolStatus::Success),
-        ];
-        let groups = group_messages_for_export(msgs);
-        // Should have user group, orphan tool result might be dropped or attached
-        assert!(!groups.is_empty());
-    }
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    #[test]
-    fn test_unicode_content() {
-        let msgs = vec![
-            msg_user("Test Unicode: 你好世界! 🎉🚀 مرحبا العالم"),
-            msg_assistant("Handling multilingual: Привет мир 日本語"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 2);
-        assert!(groups[0].primary.content.contains("你好"));
-        assert!(groups[1].primary.content.contains("Привет"));
+/// Show messages around a specific line in a session file
+fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult<()> {
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+
+    if !path.exists() {
+        return Err(CliError {
+            code: 3,
+            kind: "file-not-found",
+            message: format!("Session file not found: {}", path.display()),
+            hint: Some("Use 'cass search' to find session paths".to_string()),
+            retryable: false,
+        });
     }
 
-    #[test]
-    fn test_html_special_characters() {
-        let msgs = vec![
-            msg_user("<script>alert('xss')</script>"),
-            msg_assistant("Response with & \"quotes\" 'single'"),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 2);
-        // Content should be preserved (escaping happens at render time)
-        assert!(groups[0].primary.content.contains("<script>"));
+    let file = File::open(path).map_err(|e| CliError {
+        code: 9,
+        kind: "file-open",
+        message: format!("Failed to open file: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+
+    let reader = BufReader::new(file);
+    let mut messages: Vec<(usize, serde_json::Value)> = Vec::new();
+    let mut target_msg_idx: Option<usize> = None;
+    let mut current_line: usize = 0;
+
+    for raw_line in reader.lines().map_while(Result::ok) {
+        current_line += 1;
+        if raw_line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&raw_line) {
+            if current_line == line {
+                target_msg_idx = Some(messages.len());
+            }
+            messages.push((current_line, msg));
+        }
     }
 
-    #[test]
-    fn test_empty_input_returns_empty() {
-        let msgs: Vec<Message> = vec![];
-        let groups = group_messages_for_export(msgs);
-        assert!(groups.is_empty());
+    if target_msg_idx.is_none() && line > 0 {
+        for (idx, (msg_line, _)) in messages.iter().enumerate() {
+            if *msg_line >= line {
+                target_msg_idx = Some(idx);
+                break;
+            }
+        }
+        if target_msg_idx.is_none() && !messages.is_empty() {
+            target_msg_idx = Some(messages.len() - 1);
+        }
     }
 
-    // ========================================================================
-    // Tool Status Tests
-    // ========================================================================
+    let target_idx = target_msg_idx.ok_or_else(|| CliError {
+        code: 2,
+        kind: "line-not-found",
+        message: format!("No message found at or near line {}", line),
+        hint: Some(format!("File has {} messages", messages.len())),
+        retryable: false,
+    })?;
 
-    #[test]
-    fn test_tool_success_status() {
-        let msgs = vec![
-            msg_assistant_with_tool("Running command", "Bash", "echo hello"),
-            msg_tool_result("Bash", "hello", ToolStatus::Success),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 1);
+    let start = target_idx.saturating_sub(context);
+    let end = (target_idx + context + 1).min(messages.len());
 
-        // Verify tool call exists
-        assert!(
-            !groups[0].tool_calls.is_empty(),
-            "Should have at least one tool call"
-        );
+    let context_messages: Vec<_> = messages[start..end]
+        .iter()
+        .enumerate()
+        .map(|(i, (line_num, msg))| {
+            let is_target = start + i == target_idx;
+            (line_num, msg, is_target)
+        })
+        .collect();
 
-        // Check if result was correlated (may not always correlate depending on format)
-        let tc = &groups[0].tool_calls[0];
-        assert_eq!(tc.call.name, "Bash", "Tool call should be Bash");
+    let structured_format = if json {
+        Some(RobotFormat::Json)
+    } else {
+        robot_format_from_env()
     }
+    .map(|fmt| {
+        if matches!(fmt, RobotFormat::Sessions) {
+            RobotFormat::Compact
+        } else {
+            fmt
+        }
+    });
 
-    #[test]
-    fn test_tool_error_status() {
-        let msgs = vec![
-            msg_assistant_with_tool("Reading file", "Read", "/nonexistent"),
-            msg_tool_result("Read", "File not found", ToolStatus::Error),
-        ];
-        let groups = group_messages_for_export(msgs);
-        assert_eq!(groups.len(), 1);
-
-        // Verify tool call exists
-        assert!(
-            !groups[0].tool_calls.is_empty(),
-            "Should have at least one tool call"
-        );
-
-        // Tool call should exist with Read name
-        let tc = &groups[0].tool_calls[0];
-        assert_eq!(tc.call.name, "Read", "Tool call should be Read");
+    if let Some(fmt) = structured_format {
+        let output: Vec<serde_json::Value> = context_messages
+            .iter()
+            .map(|(line_num, msg, is_target)| {
+                let role = extract_role(msg);
+                let content = extract_text_content(msg);
+                serde_json::json!({
+                    "line": line_num,
+                    "role": role,
+                    "is_target": is_target,
+                    "content": content,
+                })
+            })
+            .collect();
+        return output_structured_value(serde_json::Value::Array(output), fmt);
     }
 
-    // ========================================================================
-    // Agent Format Detection Tests
-    // ========================================================================
+    println!("\n📍 Context around line {} in {}\n", line, path.display());
+    println!("{}", "─".repeat(60));
 
-    #[test]
-    fn test_detect_claude_format() {
-        let msgs = vec![msg_assistant_with_tool("Let me check", "Read", "/file.rs")];
-        let format = detect_agent_format(&msgs);
-        assert_eq!(format, AgentFormat::ClaudeCode);
-    }
+    for (line_num, msg, is_target) in context_messages {
+        let role = extract_role(msg);
+        let content = extract_text_content(msg);
+        let preview: String = content.chars().take(300).collect();
+        let marker = if is_target { ">>>" } else { "   " };
+        let role_icon = match role.as_str() {
+            "user" => "👤",
+            "assistant" => "🤖",
+            _ => "📝",
+        };
 
-    #[test]
-    fn test_detect_generic_format() {
-        let msgs = vec![msg_user("Hello"), msg_assistant("Hi")];
-        let format = detect_agent_format(&msgs);
-        // No tool calls, so should be generic
-        assert_eq!(format, AgentFormat::Generic);
+        println!(
+            "{} L{:>4} {} {}",
+            marker,
+            line_num,
+            role_icon,
+            role.to_uppercase()
+        );
+        println!("        {}", preview.replace('\n', " "));
+        if content.len() > 300 {
+            println!("        ... ({} more chars)", content.len() - 300);
+        }
+        println!();
     }
 
-    // ========================================================================
-    // Message Classification Tests
-    // ========================================================================
+    println!("{}", "─".repeat(60));
+    println!(
+        "Showing messages {} to {} of {} total",
+        start + 1,
+        end,
+        messages.len()
+    );
+    Ok(())
+}
 
-    #[test]
-    fn test_classify_user_content() {
-        let msg = msg_user("Hello");
-        let class = classify_message(&msg, AgentFormat::Generic);
-        assert_eq!(class, MessageClassification::UserContent);
+fn extract_text_content(msg: &serde_json::Value) -> String {
+    // Use the well-tested flatten_content helper from connectors module
+    // It handles: direct strings, {"type": "text"}, {"type": "input_text"},
+    // blocks with "text" but no "type", and tool_use blocks
+    fn try_flatten(content: &serde_json::Value) -> Option<String> {
+        let result = crate::connectors::flatten_content(content);
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
     }
 
-    #[test]
-    fn test_classify_assistant_content() {
-        let msg = msg_assistant("Hi there");
-        let class = classify_message(&msg, AgentFormat::Generic);
-        assert_eq!(class, MessageClassification::AssistantContent);
+    // Try direct content first (standard format)
+    if let Some(content) = msg.get("content")
+        && let Some(text) = try_flatten(content)
+    {
+        return text;
     }
-
-    #[test]
-    fn test_classify_system() {
-        let msg = msg_system("You are helpful");
-        let class = classify_message(&msg, AgentFormat::Generic);
-        assert_eq!(class, MessageClassification::System);
+    // Try nested message.content (Claude Code format)
+    if let Some(inner) = msg.get("message")
+        && let Some(content) = inner.get("content")
+        && let Some(text) = try_flatten(content)
+    {
+        return text;
     }
-
-    #[test]
-    fn test_classify_empty() {
-        let msg = msg_empty();
-        let class = classify_message(&msg, AgentFormat::Generic);
-        assert_eq!(class, MessageClassification::Empty);
+    // Try nested payload.content (Codex format: {"type": "response_item", "payload": {"content": ...}})
+    if let Some(payload) = msg.get("payload")
+        && let Some(content) = payload.get("content")
+        && let Some(text) = try_flatten(content)
+    {
+        return text;
     }
+    String::new()
+}
 
-    #[test]
-    fn test_classify_tool_result() {
-        let msg = msg_tool_result("Read", "contents", ToolStatus::Success);
-        let class = classify_message(&msg, AgentFormat::Generic);
-        assert_eq!(class, MessageClassification::ToolResult);
+/// Extract role from message (supports various formats)
+fn extract_role(msg: &serde_json::Value) -> String {
+    // Try direct role
+    if let Some(role) = msg.get("role").and_then(|r| r.as_str()) {
+        return role.to_string();
     }
-
-    // ========================================================================
-    // Performance Tests
-    // ========================================================================
-
-    #[test]
-    fn test_large_session_performance() {
-        use std::time::Instant;
-
-        // Generate 1000 messages
-        let mut msgs = Vec::with_capacity(1000);
-        for i in 0..500 {
-            msgs.push(msg_user(&format!("Question {}", i)));
-            msgs.push(msg_assistant(&format!("Answer {}", i)));
+    // Try nested message.role (Claude Code format)
+    if let Some(inner) = msg.get("message")
+        && let Some(role) = inner.get("role").and_then(|r| r.as_str())
+    {
+        return role.to_string();
+    }
+    // Try nested payload.role (Codex format: {"type": "response_item", "payload": {"role": "user", ...}})
+    if let Some(payload) = msg.get("payload")
+        && let Some(role) = payload.get("role").and_then(|r| r.as_str())
+    {
+        return role.to_string();
+    }
+    // Try type field (Claude Code also uses "type": "user" or "type": "assistant")
+    if let Some(type_val) = msg.get("type").and_then(|t| t.as_str()) {
+        match type_val {
+            "user" => return "user".to_string(),
+            "assistant" => return "assistant".to_string(),
+            _ => {}
         }
+    }
+    "unknown".to_string()
+}
 
-        let start = Instant::now();
-        let groups = group_messages_for_export(msgs);
-        let elapsed = start.elapsed();
+/// Strip redundant "[Tool: X]" markers from content when tool call is shown separately.
+///
+/// When a message has a tool_call object that's rendered as a collapsible section,
+/// including the "[Tool: X]" text in the content is redundant and looks bad.
+fn strip_tool_marker(content: &str) -> String {
+    let trimmed = content.trim();
 
-        assert_eq!(groups.len(), 1000);
-        assert!(
-            elapsed.as_millis() < 500,
-            "Grouping 1000 messages took {}ms, should be < 500ms",
-            elapsed.as_millis()
-        );
+    // Check if content starts with "[Tool: X]" pattern
+    if trimmed.starts_with("[Tool:")
+        && let Some(close_idx) = trimmed.find(']')
+    {
+        // Get content after the tool marker
+        let after = trimmed[close_idx + 1..].trim();
+        if after.is_empty() {
+            // Entire content was just "[Tool: X]" - return empty
+            return String::new();
+        }
+        // Return the content after the marker
+        return after.to_string();
     }
 
-    // ========================================================================
-    // Timestamp Tests
-    // ========================================================================
-
-    #[test]
-    fn test_group_timestamps_captured() {
-        let mut user_msg = msg_user("Hello");
-        user_msg.timestamp = Some("2026-01-15T10:00:00Z".to_string());
+    content.to_string()
+}
 
-        let msgs = vec![user_msg];
-        let groups = group_messages_for_export(msgs);
+/// Truncate a string smartly at word boundaries with ellipsis.
+///
+/// Returns the original string if it fits within max_len.
+/// Otherwise, truncates at the last word boundary before max_len and adds "…".
+fn smart_truncate(s: &str, max_len: usize) -> String {
+    let s = s.trim();
+    if s.chars().count() <= max_len {
+        return s.to_string();
+    }
 
-        assert_eq!(groups.len(), 1);
-        assert_eq!(
-            groups[0].start_timestamp,
-            Some("2026-01-15T10:00:00Z".to_string())
-        );
+    // Find the last space before the limit
+    let char_indices: Vec<_> = s.char_indices().take(max_len).collect();
+    if char_indices.is_empty() {
+        return "…".to_string();
     }
-}
 
-fn format_as_markdown(
-    messages: &[serde_json::Value],
-    title: &Option<String>,
-    start_ts: Option<i64>,
-    include_tools: bool,
-) -> String {
-    use chrono::{TimeZone, Utc};
-    let mut md = String::new();
-    md.push_str("# ");
-    md.push_str(title.as_deref().unwrap_or("Conversation Export"));
-    md.push('\n');
+    // Look for last word boundary (space)
+    let last_idx = char_indices.last().map(|(i, _)| *i).unwrap_or(0);
+    let truncated = &s[..=last_idx];
 
-    if let Some(ts) = start_ts
-        && let Some(dt) = Utc.timestamp_millis_opt(ts).single()
+    // Find last space to break at word boundary
+    if let Some(last_space) = truncated.rfind(|c: char| c.is_whitespace())
+        && last_space > max_len / 2
     {
-        md.push_str(&format!(
-            "\n*Started: {}*\n",
-            dt.format("%Y-%m-%d %H:%M UTC")
-        ));
+        // Only break at word if we're not losing too much
+        return format!("{}…", truncated[..last_space].trim_end());
     }
-    md.push_str("\n---\n\n");
 
-    for msg in messages {
-        let role = extract_role(msg);
-        match role.as_str() {
-            "user" => md.push_str("## 👤 User\n\n"),
-            "assistant" => md.push_str("## 🤖 Assistant\n\n"),
-            _ => md.push_str(&format!("## {}\n\n", role)),
-        }
+    // No good word boundary, just truncate
+    format!("{}…", truncated.trim_end())
+}
 
-        let content = extract_text_content(msg);
-        if !content.is_empty() {
-            md.push_str(&content);
-            md.push_str("\n\n");
-        }
+/// Show activity timeline for a time range
+#[allow(clippy::too_many_arguments)]
+fn run_timeline(
+    since: Option<&str>,
+    until: Option<&str>,
+    today: bool,
+    agents: &[String],
+    data_dir: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+    group_by: TimelineGrouping,
+    source: Option<String>,
+) -> CliResult<()> {
+    use crate::sources::provenance::SourceFilter;
+    use chrono::{Local, TimeZone, Utc};
+    use std::collections::HashMap;
 
-        // Also handle tool blocks if include_tools is set
-        if include_tools {
-            let content_val = msg
-                .get("message")
-                .and_then(|m| m.get("content"))
-                .or_else(|| msg.get("content"));
-            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
-                for block in arr {
-                    if let Some(block_type) = block.get("type").and_then(|t| t.as_str()) {
-                        match block_type {
-                            "tool_use" => {
-                                let name =
-                                    block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
-                                md.push_str(&format!("**Tool: {}**\n", name));
-                                if let Some(input) = block.get("input") {
-                                    md.push_str("```json\n");
-                                    md.push_str(
-                                        &serde_json::to_string_pretty(input).unwrap_or_default(),
-                                    );
-                                    md.push_str("\n```\n\n");
-                                }
-                            }
-                            "tool_result" => {
-                                md.push_str("**Tool Result:**\n");
-                                if let Some(c) = block.get("content").and_then(|c| c.as_str()) {
-                                    let preview: String = c.chars().take(500).collect();
-                                    md.push_str("```\n");
-                                    md.push_str(&preview);
-                                    if c.len() > 500 {
-                                        md.push_str("\n... (truncated)");
-                                    }
-                                    md.push_str("\n```\n\n");
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-        }
-        md.push_str("---\n\n");
-    }
-    md
-}
+    // Parse source filter (P3.2)
+    let source_filter = source.as_ref().map(|s| SourceFilter::parse(s));
+
+    let lazy = crate::storage::sqlite::LazyDb::from_overrides(data_dir, db_override);
+    let conn = lazy.get("timeline").map_err(lazy_db_to_cli_error)?;
+
+    let now = Local::now();
+    let (start_ts, end_ts) = if today {
+        let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
+        let local_start = match Local.from_local_datetime(&start_of_day) {
+            chrono::LocalResult::Single(dt) => dt,
+            chrono::LocalResult::Ambiguous(dt, _) => dt,
+            chrono::LocalResult::None => Local.from_utc_datetime(&start_of_day),
+        };
+        (local_start.timestamp_millis(), now.timestamp_millis())
+    } else {
+        let start = since
+            .and_then(parse_datetime_flexible)
+            .unwrap_or_else(|| (now - chrono::Duration::days(7)).timestamp_millis());
+        let end = until
+            .and_then(parse_datetime_flexible)
+            .unwrap_or_else(|| now.timestamp_millis());
+        (start, end)
+    };
 
-fn format_as_text(messages: &[serde_json::Value], include_tools: bool) -> String {
-    let mut text = String::new();
-    for msg in messages {
-        let role = extract_role(msg);
-        text.push_str(&format!("=== {} ===\n\n", role.to_uppercase()));
+    let mut sql = String::from(
+        "SELECT c.id, a.slug as agent, c.title, c.started_at, c.ended_at, c.source_path,
+                COUNT(m.id) as message_count, c.source_id, c.origin_host, s.kind as origin_kind
+         FROM conversations c
+         JOIN agents a ON c.agent_id = a.id
+         LEFT JOIN sources s ON c.source_id = s.id
+         LEFT JOIN messages m ON m.conversation_id = c.id
+         WHERE c.started_at >= ?1 AND c.started_at <= ?2",
+    );
 
-        let content = extract_text_content(msg);
-        if !content.is_empty() {
-            text.push_str(&content);
-            text.push_str("\n\n");
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_ts), Box::new(end_ts)];
+
+    if !agents.is_empty() {
+        sql.push_str(" AND a.slug IN (");
+        for (i, agent) in agents.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str(&format!("?{}", params.len() + 1));
+            params.push(Box::new(agent.clone()));
         }
+        sql.push(')');
+    }
 
-        // Also handle tool blocks if include_tools is set
-        if include_tools {
-            // Check nested message.content for tool blocks
-            let content_val = msg
-                .get("message")
-                .and_then(|m| m.get("content"))
-                .or_else(|| msg.get("content"));
-            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
-                for block in arr {
-                    if let Some(block_type) = block.get("type").and_then(|t| t.as_str())
-                        && block_type == "tool_use"
-                    {
-                        let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
-                        text.push_str(&format!("[Tool: {}]\n", name));
-                    }
-                }
+    // Source filter (P3.2)
+    if let Some(ref filter) = source_filter {
+        match filter {
+            SourceFilter::All => {
+                // No filtering needed
+            }
+            SourceFilter::Local => {
+                sql.push_str(" AND c.source_id = 'local'");
+            }
+            SourceFilter::Remote => {
+                sql.push_str(" AND c.source_id != 'local'");
+            }
+            SourceFilter::SourceId(id) => {
+                sql.push_str(&format!(" AND c.source_id = ?{}", params.len() + 1));
+                params.push(Box::new(id.clone()));
             }
         }
     }
-    text
-}
 
-fn format_as_html(
-    messages: &[serde_json::Value],
-    title: &Option<String>,
-    start_ts: Option<i64>,
-    include_tools: bool,
-) -> String {
-    use chrono::{TimeZone, Utc};
-    let title_str = title.as_deref().unwrap_or("Conversation Export");
-    let date_str = start_ts
-        .and_then(|ts| Utc.timestamp_millis_opt(ts).single())
-        .map(|dt| dt.format("%Y-%m-%d %H:%M UTC").to_string())
-        .unwrap_or_default();
+    sql.push_str(" GROUP BY c.id ORDER BY c.started_at DESC");
 
-    let mut html = format!(
-        r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-    <meta charset="UTF-8">
-    <title>{title_str}</title>
-    <style>
-        body {{ font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; padding: 20px; background: #0d0d12; color: #e0e0e5; }}
-        .message {{ background: #18181f; border-radius: 8px; padding: 16px; margin: 12px 0; box-shadow: 0 1px 3px rgba(0,0,0,0.3); }}
-        .user {{ border-left: 4px solid #3b82f6; }}
-        .assistant {{ border-left: 4px solid #22c55e; }}
-        .role {{ font-weight: bold; color: #a0a0a8; margin-bottom: 8px; }}
-        .content {{ white-space: pre-wrap; line-height: 1.6; }}
-        h1 {{ color: #f0f0f5; }}
-        .meta {{ color: #6b7280; font-size: 0.9em; }}
-        /* Tool badges */
-        .tool-badges {{ display: flex; flex-wrap: wrap; gap: 4px; margin-top: 8px; }}
-        .tool-badge {{ position: relative; display: inline-flex; align-items: center; gap: 4px; padding: 2px 8px; font-size: 11px; font-family: 'JetBrains Mono', ui-monospace, monospace; background: #1e1e26; border: 1px solid #2e2e38; border-radius: 4px; cursor: pointer; transition: all 0.15s; }}
-        .tool-badge:hover {{ border-color: #f59e0b; background: rgba(245,158,11,0.1); }}
-        .tool-badge-name {{ font-weight: 600; color: #f59e0b; }}
-        .tool-popover {{ position: absolute; bottom: calc(100% + 8px); left: 50%; transform: translateX(-50%) scale(0.95); min-width: 280px; max-width: 400px; padding: 12px; background: #18181f; border: 1px solid #2e2e38; border-radius: 8px; box-shadow: 0 8px 32px rgba(0,0,0,0.4); opacity: 0; visibility: hidden; transition: all 0.15s; z-index: 1000; pointer-events: none; text-align: left; white-space: normal; }}
-        .tool-badge:hover .tool-popover {{ opacity: 1; visibility: visible; transform: translateX(-50%) scale(1); pointer-events: auto; }}
-        .tool-popover::after {{ content: ''; position: absolute; top: 100%; left: 50%; transform: translateX(-50%); border: 6px solid transparent; border-top-color: #2e2e38; }}
-        .tool-popover-header {{ display: flex; align-items: center; gap: 8px; padding-bottom: 8px; margin-bottom: 8px; border-bottom: 1px solid #2e2e38; font-weight: 600; color: #f59e0b; }}
-        .tool-popover-section {{ margin-bottom: 8px; }}
-        .tool-popover-label {{ font-size: 9px; font-weight: 700; text-transform: uppercase; letter-spacing: 0.8px; color: #6b7280; margin-bottom: 4px; display: block; }}
-        .tool-popover pre {{ margin: 0; padding: 8px; font-size: 10px; background: #0d0d12; border-radius: 4px; max-height: 150px; overflow: auto; white-space: pre-wrap; word-break: break-word; }}
-    </style>
-</head>
-<body>
-    <h1>{title_str}</h1>
-    <p class="meta">{date_str}</p>
-"#
-    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Query failed: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
 
-    for msg in messages {
-        let role = extract_role(msg);
-        let role_class = if role == "user" { "user" } else { "assistant" };
-        let role_display = match role.as_str() {
-            "user" => "👤 User",
-            "assistant" => "🤖 Assistant",
-            "system" => "⚙️ System",
-            _ => "💬 Message",
-        };
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-        html.push_str(&format!(
-            r#"    <div class="message {role_class}">
-        <div class="role">{role_display}</div>
-        <div class="content">"#
-        ));
+    let rows = stmt
+        .query_map(param_refs.as_slice(), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,            // id
+                row.get::<_, String>(1)?,         // agent
+                row.get::<_, Option<String>>(2)?, // title
+                row.get::<_, i64>(3)?,            // started_at
+                row.get::<_, Option<i64>>(4)?,    // ended_at
+                row.get::<_, String>(5)?,         // source_path
+                row.get::<_, i64>(6)?,            // message_count
+                row.get::<_, String>(7)?,         // source_id (P3.2)
+                row.get::<_, Option<String>>(8)?, // origin_host (P3.5)
+                row.get::<_, Option<String>>(9)?, // origin_kind (P3.5)
+            ))
+        })
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "db-query",
+            message: format!("Query failed: {e}"),
+            hint: None,
+            retryable: false,
+        })?;
 
-        // Use extract_text_content for consistent content extraction
-        let content = extract_text_content(msg);
-        // Strip "[Tool: X]" markers when we're showing tool badges
-        let content = if include_tools {
-            strip_tool_marker(&content)
+    #[allow(clippy::type_complexity)]
+    let mut sessions: Vec<(
+        i64,
+        String,
+        Option<String>,
+        i64,
+        Option<i64>,
+        String,
+        i64,
+        String,
+        Option<String>,
+        Option<String>,
+    )> = Vec::new();
+    for r in rows.flatten() {
+        sessions.push(r);
+    }
+
+    let structured_format = if json {
+        Some(RobotFormat::Json)
+    } else {
+        robot_format_from_env()
+    }
+    .map(|fmt| {
+        if matches!(fmt, RobotFormat::Sessions) {
+            RobotFormat::Compact
         } else {
-            content
-        };
-        html.push_str(&html_escape(&content));
+            fmt
+        }
+    });
 
-        // Also handle tool use blocks if requested
-        if include_tools {
-            // Check for tool_use in nested message.content array
-            let content_val = msg
-                .get("message")
-                .and_then(|m| m.get("content"))
-                .or_else(|| msg.get("content"));
-            if let Some(arr) = content_val.and_then(|c| c.as_array()) {
-                let mut tool_badges = Vec::new();
-                for block in arr {
-                    if let Some("tool_use") = block.get("type").and_then(|t| t.as_str()) {
-                        let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("tool");
-                        let icon = match name.to_lowercase().as_str() {
-                            "bash" | "shell" => "💻",
-                            "read" | "read_file" => "📖",
-                            "write" | "write_file" => "📝",
-                            "edit" => "✏️",
-                            "glob" | "find" => "🔍",
-                            "grep" | "search" => "🔎",
-                            "webfetch" | "fetch" | "http" => "🌐",
-                            "websearch" => "🔍",
-                            "task" => "🚀",
-                            _ => "🔧",
-                        };
-                        // Get input preview (truncated)
-                        let input_preview = block
-                            .get("input")
-                            .map(|v| {
-                                let s = serde_json::to_string_pretty(v).unwrap_or_default();
-                                if s.len() > 400 {
-                                    format!("{}…", &s[..400])
-                                } else {
-                                    s
-                                }
+    if let Some(fmt) = structured_format {
+        let output = match group_by {
+            TimelineGrouping::None => {
+                let items: Vec<serde_json::Value> = sessions
+                    .iter()
+                    .map(
+                        |(
+                            id,
+                            agent,
+                            title,
+                            started,
+                            ended,
+                            path,
+                            msg_count,
+                            source_id,
+                            origin_host,
+                            origin_kind,
+                        )| {
+                            let duration = ended.map(|e| e - started);
+                            // Use "local" as default origin_kind if not in DB (backward compat)
+                            let kind = origin_kind.as_deref().unwrap_or("local");
+                            serde_json::json!({
+                                "id": id, "agent": agent, "title": title,
+                                "started_at": started, "ended_at": ended,
+                                "duration_seconds": duration, "source_path": path,
+                                "message_count": msg_count,
+                                // Provenance fields (P3.5)
+                                "source_id": source_id,
+                                "origin_kind": kind,
+                                "origin_host": origin_host,
                             })
-                            .unwrap_or_default();
-
-                        let popover_content = if !input_preview.is_empty() {
-                            format!(
-                                r#"<div class="tool-popover-section"><span class="tool-popover-label">Input</span><pre>{}</pre></div>"#,
-                                html_escape(&input_preview)
-                            )
-                        } else {
-                            String::new()
-                        };
-
-                        tool_badges.push(format!(
-                            r#"<span class="tool-badge" tabindex="0">
-                                <span>{icon}</span>
-                                <span class="tool-badge-name">{name}</span>
-                                <div class="tool-popover">
-                                    <div class="tool-popover-header">{icon} {name}</div>
-                                    {popover}
-                                </div>
-                            </span>"#,
-                            icon = icon,
-                            name = html_escape(name),
-                            popover = popover_content,
-                        ));
-                    }
-                }
-                if !tool_badges.is_empty() {
-                    html.push_str(r#"<div class="tool-badges">"#);
-                    for badge in tool_badges {
-                        html.push_str(&badge);
-                    }
-                    html.push_str("</div>");
+                        },
+                    )
+                    .collect();
+                serde_json::json!({
+                    "range": { "start": start_ts, "end": end_ts },
+                    "total_sessions": sessions.len(),
+                    "sessions": items,
+                })
+            }
+            TimelineGrouping::Hour | TimelineGrouping::Day => {
+                let mut groups: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+                for (
+                    id,
+                    agent,
+                    title,
+                    started,
+                    ended,
+                    path,
+                    msg_count,
+                    source_id,
+                    origin_host,
+                    origin_kind,
+                ) in &sessions
+                {
+                    let dt = Utc
+                        .timestamp_millis_opt(*started)
+                        .single()
+                        .unwrap_or_else(Utc::now);
+                    let key = match group_by {
+                        TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
+                        TimelineGrouping::Day => dt.format("%Y-%m-%d").to_string(),
+                        _ => unreachable!(),
+                    };
+                    // Use "local" as default origin_kind if not in DB (backward compat)
+                    let kind = origin_kind.as_deref().unwrap_or("local");
+                    groups.entry(key).or_default().push(serde_json::json!({
+                        "id": id, "agent": agent, "title": title,
+                        "started_at": started, "ended_at": ended,
+                        "source_path": path, "message_count": msg_count,
+                        // Provenance fields (P3.5)
+                        "source_id": source_id,
+                        "origin_kind": kind,
+                        "origin_host": origin_host,
+                    }));
                 }
+                serde_json::json!({
+                    "range": { "start": start_ts, "end": end_ts },
+                    "total_sessions": sessions.len(),
+                    "groups": groups,
+                })
             }
-        }
-
-        html.push_str("</div>\n    </div>\n");
+        };
+        return output_structured_value(output, fmt);
     }
-    html.push_str("</body>\n</html>\n");
-    html
-}
 
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-}
+    let start_dt = Utc
+        .timestamp_millis_opt(start_ts)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let end_dt = Utc
+        .timestamp_millis_opt(end_ts)
+        .single()
+        .unwrap_or_else(Utc::now);
 
-/// Show messages around a specific line in a session file
-fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult<()> {
-    use std::fs::File;
-    use std::io::{BufRead, BufReader};
+    println!("\n📅 Activity Timeline");
+    println!(
+        "   {} to {}",
+        start_dt.format("%Y-%m-%d %H:%M"),
+        end_dt.format("%Y-%m-%d %H:%M")
+    );
+    println!("{}", "─".repeat(70));
 
-    if !path.exists() {
-        return Err(CliError {
-            code: 3,
-            kind: "file-not-found",
-            message: format!("Session file not found: {}", path.display()),
-            hint: Some("Use 'cass search' to find session paths".to_string()),
-            retryable: false,
-        });
+    if sessions.is_empty() {
+        println!("\n   No sessions found in this time range.\n");
+        return Ok(());
     }
 
-    let file = File::open(path).map_err(|e| CliError {
-        code: 9,
-        kind: "file-open",
-        message: format!("Failed to open file: {e}"),
-        hint: None,
-        retryable: false,
-    })?;
+    let mut current_group = String::new();
+    for (
+        _id,
+        agent,
+        title,
+        started,
+        ended,
+        _path,
+        msg_count,
+        source_id,
+        origin_host,
+        _origin_kind,
+    ) in &sessions
+    {
+        let dt = Utc
+            .timestamp_millis_opt(*started)
+            .single()
+            .unwrap_or_else(Utc::now);
 
-    let reader = BufReader::new(file);
-    let mut messages: Vec<(usize, serde_json::Value)> = Vec::new();
-    let mut target_msg_idx: Option<usize> = None;
-    let mut current_line: usize = 0;
+        let group_key = match group_by {
+            TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
+            TimelineGrouping::Day => dt.format("%Y-%m-%d (%A)").to_string(),
+            TimelineGrouping::None => String::new(),
+        };
 
-    for raw_line in reader.lines().map_while(Result::ok) {
-        current_line += 1;
-        if raw_line.trim().is_empty() {
-            continue;
-        }
-        if let Ok(msg) = serde_json::from_str::<serde_json::Value>(&raw_line) {
-            if current_line == line {
-                target_msg_idx = Some(messages.len());
-            }
-            messages.push((current_line, msg));
+        if group_key != current_group && group_by != TimelineGrouping::None {
+            println!("\n  📆 {}", group_key);
+            current_group = group_key;
         }
-    }
 
-    if target_msg_idx.is_none() && line > 0 {
-        for (idx, (msg_line, _)) in messages.iter().enumerate() {
-            if *msg_line >= line {
-                target_msg_idx = Some(idx);
-                break;
+        let duration = ended.map(|e| {
+            // Timestamps are in milliseconds, divide by 60_000 to get minutes
+            let mins = (e - started) / 60_000;
+            if mins < 60 {
+                format!("{}m", mins)
+            } else {
+                format!("{}h{}m", mins / 60, mins % 60)
             }
-        }
-        if target_msg_idx.is_none() && !messages.is_empty() {
-            target_msg_idx = Some(messages.len() - 1);
-        }
-    }
+        });
 
-    let target_idx = target_msg_idx.ok_or_else(|| CliError {
-        code: 2,
-        kind: "line-not-found",
-        message: format!("No message found at or near line {}", line),
-        hint: Some(format!("File has {} messages", messages.len())),
-        retryable: false,
-    })?;
+        let title_str = title.as_deref().unwrap_or("(untitled)");
+        let title_preview: String = title_str.chars().take(40).collect();
 
-    let start = target_idx.saturating_sub(context);
-    let end = (target_idx + context + 1).min(messages.len());
+        let agent_icon = match agent.as_str() {
+            "claude_code" => "🟣",
+            "codex" => "🟢",
+            "gemini" => "🔵",
+            "amp" => "🟡",
+            "cursor" => "⚪",
+            "pi_agent" => "🟠",
+            _ => "⚫",
+        };
 
-    let context_messages: Vec<_> = messages[start..end]
-        .iter()
-        .enumerate()
-        .map(|(i, (line_num, msg))| {
-            let is_target = start + i == target_idx;
-            (line_num, msg, is_target)
-        })
-        .collect();
+        // Source badge for remote sessions (P3.2, P3.5)
+        // Prefer origin_host if available, otherwise use source_id
+        let source_badge = if source_id != "local" {
+            let label = origin_host.as_deref().unwrap_or(source_id.as_str());
+            format!(" [{}]", label)
+        } else {
+            String::new()
+        };
+
+        println!(
+            "     {} {} {:>5} │ {:>3} msgs │ {}{}",
+            dt.format("%H:%M"),
+            agent_icon,
+            duration.as_deref().unwrap_or(""),
+            msg_count,
+            title_preview,
+            source_badge
+        );
+    }
+
+    println!("\n{}", "─".repeat(70));
+    println!("   Total: {} sessions\n", sessions.len());
+    Ok(())
+}
+
+/// Handle tag subcommands
+fn run_tag_command(cmd: TagCommand, db_override: Option<PathBuf>) -> CliResult<()> {
+    match cmd {
+        TagCommand::Add {
+            session,
+            tags,
+            data_dir,
+            json,
+        } => run_tag_edit(&session, &tags, true, &data_dir, db_override, json),
+        TagCommand::Remove {
+            session,
+            tags,
+            data_dir,
+            json,
+        } => run_tag_edit(&session, &tags, false, &data_dir, db_override, json),
+        TagCommand::List {
+            session,
+            data_dir,
+            json,
+        } => run_tag_list(session.as_deref(), &data_dir, db_override, json),
+    }
+}
 
-    let structured_format = if json {
+/// Structured output format for `cass tag` (`--json`, else `CASS_OUTPUT_FORMAT`).
+fn tag_output_format(json: bool) -> Option<RobotFormat> {
+    if json {
         Some(RobotFormat::Json)
     } else {
         robot_format_from_env()
@@ -12187,522 +15314,1336 @@ fn run_expand(path: &Path, line: usize, context: usize, json: bool) -> CliResult
         } else {
             fmt
         }
-    });
+    })
+}
 
-    if let Some(fmt) = structured_format {
-        let output: Vec<serde_json::Value> = context_messages
-            .iter()
-            .map(|(line_num, msg, is_target)| {
-                let role = extract_role(msg);
-                let content = extract_text_content(msg);
-                serde_json::json!({
-                    "line": line_num,
-                    "role": role,
-                    "is_target": is_target,
-                    "content": content,
-                })
-            })
-            .collect();
-        return output_structured_value(serde_json::Value::Array(output), fmt);
+/// Open the main database for tag operations, failing if it has not been created yet.
+fn open_tag_storage(
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<(crate::storage::sqlite::SqliteStorage, PathBuf)> {
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    if !db_path.exists() {
+        return Err(lazy_db_to_cli_error(
+            crate::storage::sqlite::LazyDbError::NotFound(db_path),
+        ));
     }
+    let storage = crate::storage::sqlite::SqliteStorage::open(&db_path).map_err(|e| CliError {
+        code: 9,
+        kind: "db-open",
+        message: format!("Failed to open database at {}: {e}", db_path.display()),
+        hint: None,
+        retryable: false,
+    })?;
+    Ok((storage, data_dir))
+}
 
-    println!("\n📍 Context around line {} in {}\n", line, path.display());
-    println!("{}", "─".repeat(60));
-
-    for (line_num, msg, is_target) in context_messages {
-        let role = extract_role(msg);
-        let content = extract_text_content(msg);
-        let preview: String = content.chars().take(300).collect();
-        let marker = if is_target { ">>>" } else { "   " };
-        let role_icon = match role.as_str() {
-            "user" => "👤",
-            "assistant" => "🤖",
-            _ => "📝",
+fn run_tag_edit(
+    session: &Path,
+    raw_tags: &[String],
+    add: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    let mut tags = Vec::with_capacity(raw_tags.len());
+    for raw in raw_tags {
+        let Some(tag) = crate::model::types::Tag::normalize_name(raw) else {
+            return Err(CliError::usage(
+                format!("Invalid tag name: {raw:?}"),
+                Some("Tags may contain letters, digits, '-', '_', '.', and '/'.".to_string()),
+            ));
         };
-
-        println!(
-            "{} L{:>4} {} {}",
-            marker,
-            line_num,
-            role_icon,
-            role.to_uppercase()
-        );
-        println!("        {}", preview.replace('\n', " "));
-        if content.len() > 300 {
-            println!("        ... ({} more chars)", content.len() - 300);
+        if !tags.contains(&tag) {
+            tags.push(tag);
         }
-        println!();
     }
 
-    println!("{}", "─".repeat(60));
-    println!(
-        "Showing messages {} to {} of {} total",
-        start + 1,
-        end,
-        messages.len()
-    );
+    let (mut storage, data_dir) = open_tag_storage(data_dir_override, db_override)?;
+    let path_str = session.to_string_lossy().to_string();
+    let result = if add {
+        storage.add_session_tags(&path_str, &tags)
+    } else {
+        storage.remove_session_tags(&path_str, &tags)
+    };
+    let matched = result.map_err(|e| CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Failed to update tags: {e}"),
+        hint: None,
+        retryable: false,
+    })?;
+    if matched == 0 {
+        return Err(CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No session found at path: {path_str}"),
+            hint: Some(
+                "Use 'cass search' to find sessions, then use the source_path from results."
+                    .to_string(),
+            ),
+            retryable: false,
+        });
+    }
+
+    let current = storage
+        .session_tags(&path_str)
+        .map_err(|e| CliError::unknown(format!("failed to read tags: {e}")))?;
+
+    // SQLite is the source of truth; the lexical index is refreshed best-effort.
+    let index_warning = reindex_session_for_tags(&storage, &data_dir, &path_str).err();
+
+    if let Some(fmt) = tag_output_format(json) {
+        let payload = serde_json::json!({
+            "session": path_str,
+            "action": if add { "add" } else { "remove" },
+            "tags": current,
+            "conversations": matched,
+            "index_updated": index_warning.is_none(),
+            "warning": index_warning,
+        });
+        return output_structured_value(payload, fmt);
+    }
+
+    if current.is_empty() {
+        println!("{path_str}: no tags");
+    } else {
+        let pills: Vec<String> = current.iter().map(|t| format!("#{t}")).collect();
+        println!("{path_str}: {}", pills.join(" "));
+    }
+    if let Some(warning) = index_warning {
+        eprintln!(
+            "{} {warning}; run 'cass index --full' to make tag: filters reflect this change",
+            console::style("warning:").yellow()
+        );
+    }
     Ok(())
 }
 
-fn extract_text_content(msg: &serde_json::Value) -> String {
-    // Use the well-tested flatten_content helper from connectors module
-    // It handles: direct strings, {"type": "text"}, {"type": "input_text"},
-    // blocks with "text" but no "type", and tool_use blocks
-    fn try_flatten(content: &serde_json::Value) -> Option<String> {
-        let result = crate::connectors::flatten_content(content);
-        if result.is_empty() {
-            None
+/// Re-index one session in Tantivy so its documents carry the current tags.
+///
+/// Skips silently when no index exists yet. Refuses to touch an index built
+/// with a different schema (opening it would wipe it) and reports writer lock
+/// contention (e.g. `cass index --watch` running) as an error for the caller to surface.
+fn reindex_session_for_tags(
+    storage: &crate::storage::sqlite::SqliteStorage,
+    data_dir: &Path,
+    source_path: &str,
+) -> std::result::Result<(), String> {
+    use crate::search::tantivy::{TantivyIndex, index_dir, schema_hash_matches};
+    use std::collections::HashMap;
+
+    let index_path = index_dir(data_dir).map_err(|e| format!("index path unavailable: {e}"))?;
+    let Ok(meta) = std::fs::read_to_string(index_path.join("schema_hash.json")) else {
+        return Ok(());
+    };
+    let stored_hash = serde_json::from_str::<serde_json::Value>(&meta)
+        .ok()
+        .and_then(|v| v.get("schema_hash")?.as_str().map(str::to_string))
+        .unwrap_or_default();
+    if !schema_hash_matches(&stored_hash) {
+        return Err("search index schema is outdated".to_string());
+    }
+
+    let mut t_index = TantivyIndex::open_or_create(&index_path)
+        .map_err(|e| format!("search index is busy or unavailable ({e})"))?;
+
+    let source_map: HashMap<_, _> = storage
+        .list_sources()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|s| (s.id, (s.kind, s.host_label)))
+        .collect();
+    let tag_map = storage
+        .session_tag_map()
+        .map_err(|e| format!("failed to read tags: {e}"))?;
+    let convs = storage
+        .list_conversations_for_path(source_path)
+        .map_err(|e| format!("failed to load session: {e}"))?;
+
+    t_index.delete_source_path(source_path);
+    for conv in convs {
+        let Some(normalized) = stored_conversation_for_index(storage, conv, &source_map, &tag_map)
+            .map_err(|e| e.message)?
+        else {
+            continue;
+        };
+        t_index
+            .add_messages(&normalized, &normalized.messages)
+            .map_err(|e| format!("failed to index session: {e}"))?;
+    }
+    t_index
+        .commit()
+        .map_err(|e| format!("failed to commit index: {e}"))
+}
+
+fn run_tag_list(
+    session: Option<&Path>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    let (storage, _) = open_tag_storage(data_dir_override, db_override)?;
+    let structured = tag_output_format(json);
+    let query_err = |e: anyhow::Error| CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Failed to read tags: {e}"),
+        hint: None,
+        retryable: false,
+    };
+
+    if let Some(session) = session {
+        let path_str = session.to_string_lossy().to_string();
+        let tags = storage.session_tags(&path_str).map_err(query_err)?;
+        if let Some(fmt) = structured {
+            return output_structured_value(
+                serde_json::json!({ "session": path_str, "tags": tags }),
+                fmt,
+            );
+        }
+        if tags.is_empty() {
+            println!("{path_str}: no tags");
         } else {
-            Some(result)
+            let pills: Vec<String> = tags.iter().map(|t| format!("#{t}")).collect();
+            println!("{path_str}: {}", pills.join(" "));
         }
+        return Ok(());
     }
 
-    // Try direct content first (standard format)
-    if let Some(content) = msg.get("content")
-        && let Some(text) = try_flatten(content)
-    {
-        return text;
+    let tags = storage.list_tags().map_err(query_err)?;
+    if let Some(fmt) = structured {
+        let items: Vec<serde_json::Value> = tags
+            .iter()
+            .map(|(name, sessions)| serde_json::json!({ "tag": name, "sessions": sessions }))
+            .collect();
+        return output_structured_value(
+            serde_json::json!({ "tags": items, "total": tags.len() }),
+            fmt,
+        );
     }
-    // Try nested message.content (Claude Code format)
-    if let Some(inner) = msg.get("message")
-        && let Some(content) = inner.get("content")
-        && let Some(text) = try_flatten(content)
-    {
-        return text;
+    if tags.is_empty() {
+        println!("No tags. Add one with: cass tag add <session> <tag>");
     }
-    // Try nested payload.content (Codex format: {"type": "response_item", "payload": {"content": ...}})
-    if let Some(payload) = msg.get("payload")
-        && let Some(content) = payload.get("content")
-        && let Some(text) = try_flatten(content)
-    {
-        return text;
+    for (name, sessions) in tags {
+        println!(
+            "#{name}  ({sessions} session{})",
+            if sessions == 1 { "" } else { "s" }
+        );
     }
-    String::new()
+    Ok(())
 }
 
-/// Extract role from message (supports various formats)
-fn extract_role(msg: &serde_json::Value) -> String {
-    // Try direct role
-    if let Some(role) = msg.get("role").and_then(|r| r.as_str()) {
-        return role.to_string();
+/// Milliseconds timestamp `days` days before now, for `--days` filters.
+fn days_ago_millis(days: u32) -> i64 {
+    (Utc::now() - chrono::Duration::days(i64::from(days))).timestamp_millis()
+}
+
+fn tool_event_query_err(e: anyhow::Error) -> CliError {
+    CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Failed to read tool events: {e}"),
+        hint: Some("Run 'cass index --full' if the database predates tool events.".to_string()),
+        retryable: false,
     }
-    // Try nested message.role (Claude Code format)
-    if let Some(inner) = msg.get("message")
-        && let Some(role) = inner.get("role").and_then(|r| r.as_str())
-    {
-        return role.to_string();
+}
+
+fn format_event_time(ts: Option<i64>) -> String {
+    ts.and_then(chrono::DateTime::from_timestamp_millis)
+        .map_or_else(
+            || "-".to_string(),
+            |d| d.format("%Y-%m-%d %H:%M").to_string(),
+        )
+}
+
+/// `cass files`: most-touched files, or with `--touched` the sessions that touched one file.
+fn run_files(
+    filter: crate::storage::sqlite::ToolEventFilter,
+    limit: usize,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use crate::storage::sqlite::ToolEventRow;
+
+    let (storage, _) = open_tag_storage(data_dir_override, db_override)?;
+    let structured = tag_output_format(json);
+
+    let Some(file) = filter.path.clone() else {
+        let files = storage
+            .touched_files(&filter, limit)
+            .map_err(tool_event_query_err)?;
+        if let Some(fmt) = structured {
+            return output_structured_value(
+                serde_json::json!({ "files": files, "count": files.len() }),
+                fmt,
+            );
+        }
+        if files.is_empty() {
+            println!(
+                "No file reads or edits recorded. Re-index with 'cass index --full' to extract them."
+            );
+        }
+        for f in &files {
+            println!(
+                "{:>4} session{}  {:>4} edits  {:>4} reads  {}  {}",
+                f.sessions,
+                if f.sessions == 1 { " " } else { "s" },
+                f.edits,
+                f.reads,
+                format_event_time(f.last_touched),
+                f.path
+            );
+        }
+        return Ok(());
+    };
+
+    // Group events by session, keeping the newest-first order of first appearance
+    let events = storage
+        .tool_events(&filter, 10_000)
+        .map_err(tool_event_query_err)?;
+    let mut sessions: Vec<(String, Vec<ToolEventRow>)> = Vec::new();
+    for event in events {
+        match sessions
+            .iter_mut()
+            .find(|(path, _)| *path == event.source_path)
+        {
+            Some((_, group)) => group.push(event),
+            None => sessions.push((event.source_path.clone(), vec![event])),
+        }
     }
-    // Try nested payload.role (Codex format: {"type": "response_item", "payload": {"role": "user", ...}})
-    if let Some(payload) = msg.get("payload")
-        && let Some(role) = payload.get("role").and_then(|r| r.as_str())
-    {
-        return role.to_string();
+    let total_sessions = sessions.len();
+    sessions.truncate(limit);
+
+    if let Some(fmt) = structured {
+        let items: Vec<serde_json::Value> = sessions
+            .iter()
+            .map(|(source_path, group)| {
+                let first = &group[0];
+                serde_json::json!({
+                    "source_path": source_path,
+                    "agent": first.agent,
+                    "workspace": first.workspace,
+                    "title": first.title,
+                    "last_touched": first.created_at,
+                    "edits": group.iter().filter(|e| e.kind == "edit").count(),
+                    "reads": group.iter().filter(|e| e.kind == "read").count(),
+                    "events": group.iter().map(|e| serde_json::json!({
+                        "kind": e.kind,
+                        "tool": e.tool,
+                        "path": e.path,
+                        "line_number": e.line_number,
+                        "created_at": e.created_at,
+                    })).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        return output_structured_value(
+            serde_json::json!({
+                "file": file,
+                "sessions": items,
+                "count": items.len(),
+                "total_sessions": total_sessions,
+            }),
+            fmt,
+        );
     }
-    // Try type field (Claude Code also uses "type": "user" or "type": "assistant")
-    if let Some(type_val) = msg.get("type").and_then(|t| t.as_str()) {
-        match type_val {
-            "user" => return "user".to_string(),
-            "assistant" => return "assistant".to_string(),
-            _ => {}
-        }
+
+    if sessions.is_empty() {
+        println!("No session read or edited {file}");
+        return Ok(());
     }
-    "unknown".to_string()
+    println!(
+        "{file}: touched in {total_sessions} session{}",
+        if total_sessions == 1 { "" } else { "s" }
+    );
+    for (source_path, group) in &sessions {
+        let first = &group[0];
+        let edits = group.iter().filter(|e| e.kind == "edit").count();
+        let reads = group.len() - edits;
+        println!();
+        println!(
+            "  {}  {}  {}",
+            format_event_time(first.created_at),
+            first.agent,
+            first.title.as_deref().unwrap_or("(untitled)")
+        );
+        let lines: Vec<String> = group
+            .iter()
+            .rev()
+            .map(|e| format!("{}@{}", e.kind, e.line_number))
+            .collect();
+        println!("    {edits} edits, {reads} reads ({})", lines.join(", "));
+        println!("    {source_path}");
+    }
+    Ok(())
 }
 
-/// Strip redundant "[Tool: X]" markers from content when tool call is shown separately.
-///
-/// When a message has a tool_call object that's rendered as a collapsible section,
-/// including the "[Tool: X]" text in the content is redundant and looks bad.
-fn strip_tool_marker(content: &str) -> String {
-    let trimmed = content.trim();
+/// Candidate sessions fetched per signal before ranking.
+const RELATED_CANDIDATES: usize = 200;
+
+/// `cass related`: sessions that read, edited or discussed a source file.
+#[allow(clippy::too_many_arguments)]
+fn run_related(
+    file: &str,
+    line: Option<usize>,
+    symbol: Option<&str>,
+    limit: usize,
+    no_semantic: bool,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    json: bool,
+) -> CliResult<()> {
+    use crate::search::query::{
+        FieldMask, SearchClient, SearchClientOptions, SearchFilters, SearchMode,
+    };
+    use crate::search::related::{RelatedRanker, code_window, symbol_line};
+    use crate::search::tantivy::index_dir;
+    use std::sync::Arc;
 
-    // Check if content starts with "[Tool: X]" pattern
-    if trimmed.starts_with("[Tool:")
-        && let Some(close_idx) = trimmed.find(']')
-    {
-        // Get content after the tool marker
-        let after = trimmed[close_idx + 1..].trim();
-        if after.is_empty() {
-            // Entire content was just "[Tool: X]" - return empty
-            return String::new();
+    let (storage, data_dir) = open_tag_storage(data_dir_override, db_override.clone())?;
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let symbol = symbol.map(str::trim).filter(|s| !s.is_empty());
+    let mut ranker = RelatedRanker::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    let refs = storage
+        .file_references(file, line, RELATED_CANDIDATES)
+        .map_err(tool_event_query_err)?;
+    ranker.add_file_references(&refs);
+
+    // The file on disk, when it is there, anchors the workspace signal and
+    // supplies the code around --line (or the symbol) for semantic matching.
+    let local_file = std::fs::canonicalize(file).ok();
+    let contents = local_file
+        .as_ref()
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    let anchor = line.or_else(|| {
+        symbol
+            .zip(contents.as_deref())
+            .and_then(|(symbol, contents)| symbol_line(contents, symbol))
+    });
+    let window = if no_semantic {
+        None
+    } else {
+        anchor
+            .zip(contents.as_deref())
+            .and_then(|(line, contents)| code_window(contents, line))
+    };
+
+    let mut used_symbol = false;
+    let mut used_semantic = false;
+    if symbol.is_some() || window.is_some() {
+        let client = index_dir(&data_dir)
+            .map_err(|e| CliError::unknown(format!("failed to open index dir: {e}")))
+            .and_then(
+                |index_path| match crate::mcp::warm_search_client(&index_path, &db_path) {
+                    Some(client) => client,
+                    None => open_cli_search_client(
+                        &index_path,
+                        &db_path,
+                        SearchClientOptions {
+                            enable_reload: false,
+                            enable_warm: false,
+                        },
+                    )
+                    .map(Arc::new),
+                },
+            );
+        match client {
+            Ok(client) => {
+                let client: &SearchClient = &client;
+                let mask = FieldMask::new(false, false, true, true);
+                if let Some(symbol) = symbol {
+                    let phrase = format!("\"{}\"", symbol.replace('"', ""));
+                    match search_with_mode(
+                        client,
+                        SearchMode::Lexical,
+                        &phrase,
+                        &SearchFilters::default(),
+                        RELATED_CANDIDATES,
+                        0,
+                        0,
+                        mask,
+                        false,
+                    ) {
+                        Ok(result) => {
+                            used_symbol = true;
+                            ranker.add_symbol_hits(&result.hits);
+                        }
+                        Err(e) => warnings.push(format!("symbol search skipped: {}", e.message)),
+                    }
+                }
+                if let Some(window) = window.as_deref() {
+                    let semantic = attach_semantic_context(
+                        client,
+                        &data_dir,
+                        &db_path,
+                        &SemanticSearchOptions::default(),
+                    )
+                    .and_then(|()| {
+                        search_with_mode(
+                            client,
+                            SearchMode::Semantic,
+                            window,
+                            &SearchFilters::default(),
+                            RELATED_CANDIDATES / 4,
+                            0,
+                            0,
+                            mask,
+                            false,
+                        )
+                    });
+                    match semantic {
+                        Ok(result) => {
+                            used_semantic = true;
+                            ranker.add_semantic_hits(&result.hits);
+                        }
+                        Err(e) => {
+                            warnings.push(format!("semantic similarity skipped: {}", e.message));
+                        }
+                    }
+                }
+            }
+            Err(e) => warnings.push(format!(
+                "symbol and semantic signals skipped: {}",
+                e.message
+            )),
         }
-        // Return the content after the marker
-        return after.to_string();
     }
 
-    content.to_string()
-}
+    let sessions = ranker.rank(local_file.as_deref(), limit);
 
-/// Truncate a string smartly at word boundaries with ellipsis.
-///
-/// Returns the original string if it fits within max_len.
-/// Otherwise, truncates at the last word boundary before max_len and adds "…".
-fn smart_truncate(s: &str, max_len: usize) -> String {
-    let s = s.trim();
-    if s.chars().count() <= max_len {
-        return s.to_string();
+    if let Some(fmt) = tag_output_format(json) {
+        return output_structured_value(
+            serde_json::json!({
+                "file": file,
+                "line": line,
+                "symbol": symbol,
+                "resolved_path": local_file,
+                "signals": {
+                    "file_references": refs.len(),
+                    "symbol": used_symbol,
+                    "semantic": used_semantic,
+                },
+                "sessions": sessions,
+                "count": sessions.len(),
+                "warnings": warnings,
+            }),
+            fmt,
+        );
     }
 
-    // Find the last space before the limit
-    let char_indices: Vec<_> = s.char_indices().take(max_len).collect();
-    if char_indices.is_empty() {
-        return "…".to_string();
+    let target = match line {
+        Some(line) => format!("{file}:{line}"),
+        None => file.to_string(),
+    };
+    if sessions.is_empty() {
+        println!("No sessions related to {target}");
+    } else {
+        println!("Sessions related to {target}");
     }
-
-    // Look for last word boundary (space)
-    let last_idx = char_indices.last().map(|(i, _)| *i).unwrap_or(0);
-    let truncated = &s[..=last_idx];
-
-    // Find last space to break at word boundary
-    if let Some(last_space) = truncated.rfind(|c: char| c.is_whitespace())
-        && last_space > max_len / 2
-    {
-        // Only break at word if we're not losing too much
-        return format!("{}…", truncated[..last_space].trim_end());
+    for s in &sessions {
+        let mut evidence = Vec::new();
+        if s.edits + s.reads > 0 {
+            evidence.push(format!("{} edits, {} reads", s.edits, s.reads));
+        }
+        if s.snippets > 0 {
+            evidence.push(format!(
+                "{} snippets ({} at line)",
+                s.snippets, s.line_snippets
+            ));
+        }
+        if s.workspace_match {
+            evidence.push("same workspace".to_string());
+        }
+        if s.symbol_hits > 0 {
+            evidence.push(format!("symbol x{}", s.symbol_hits));
+        }
+        if let Some(similarity) = s.semantic_score {
+            evidence.push(format!("similar code {similarity:.2}"));
+        }
+        println!();
+        println!(
+            "  {:>5.2}  {}  {}  {}",
+            s.score,
+            format_event_time(s.last_activity),
+            s.agent,
+            s.title.as_deref().unwrap_or("(untitled)")
+        );
+        println!("         {}", evidence.join(" · "));
+        match s.line_number {
+            Some(n) => println!("         {}:{n}", s.source_path),
+            None => println!("         {}", s.source_path),
+        }
     }
-
-    // No good word boundary, just truncate
-    format!("{}…", truncated.trim_end())
+    for warning in &warnings {
+        eprintln!("Note: {warning}");
+    }
+    Ok(())
 }
 
-/// Show activity timeline for a time range
-#[allow(clippy::too_many_arguments)]
-fn run_timeline(
-    since: Option<&str>,
-    until: Option<&str>,
-    today: bool,
-    agents: &[String],
-    data_dir: &Option<PathBuf>,
+/// `cass commands`: shell commands run by tool calls, newest first.
+fn run_commands(
+    filter: crate::storage::sqlite::ToolEventFilter,
+    limit: usize,
+    data_dir_override: &Option<PathBuf>,
     db_override: Option<PathBuf>,
     json: bool,
-    group_by: TimelineGrouping,
-    source: Option<String>,
 ) -> CliResult<()> {
-    use crate::sources::provenance::SourceFilter;
-    use chrono::{Local, TimeZone, Utc};
-    use std::collections::HashMap;
-
-    // Parse source filter (P3.2)
-    let source_filter = source.as_ref().map(|s| SourceFilter::parse(s));
-
-    let lazy = crate::storage::sqlite::LazyDb::from_overrides(data_dir, db_override);
-    let conn = lazy.get("timeline").map_err(lazy_db_to_cli_error)?;
+    let (storage, _) = open_tag_storage(data_dir_override, db_override)?;
+    let commands = storage
+        .tool_events(&filter, limit)
+        .map_err(tool_event_query_err)?;
+
+    if let Some(fmt) = tag_output_format(json) {
+        return output_structured_value(
+            serde_json::json!({ "commands": commands, "count": commands.len() }),
+            fmt,
+        );
+    }
 
-    let now = Local::now();
-    let (start_ts, end_ts) = if today {
-        let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap();
-        let local_start = match Local.from_local_datetime(&start_of_day) {
-            chrono::LocalResult::Single(dt) => dt,
-            chrono::LocalResult::Ambiguous(dt, _) => dt,
-            chrono::LocalResult::None => Local.from_utc_datetime(&start_of_day),
+    if commands.is_empty() {
+        println!("No matching commands.");
+    }
+    for c in &commands {
+        let status = match (c.failed, c.exit_code) {
+            (Some(true), Some(code)) => format!("✗ {code:>3}"),
+            (Some(true), None) => "✗ err".to_string(),
+            (Some(false), _) => "✓    ".to_string(),
+            (None, _) => "?    ".to_string(),
         };
-        (local_start.timestamp_millis(), now.timestamp_millis())
-    } else {
-        let start = since
-            .and_then(parse_datetime_flexible)
-            .unwrap_or_else(|| (now - chrono::Duration::days(7)).timestamp_millis());
-        let end = until
-            .and_then(parse_datetime_flexible)
-            .unwrap_or_else(|| now.timestamp_millis());
-        (start, end)
-    };
-
-    let mut sql = String::from(
-        "SELECT c.id, a.slug as agent, c.title, c.started_at, c.ended_at, c.source_path,
-                COUNT(m.id) as message_count, c.source_id, c.origin_host, s.kind as origin_kind
-         FROM conversations c
-         JOIN agents a ON c.agent_id = a.id
-         LEFT JOIN sources s ON c.source_id = s.id
-         LEFT JOIN messages m ON m.conversation_id = c.id
-         WHERE c.started_at >= ?1 AND c.started_at <= ?2",
-    );
-
-    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(start_ts), Box::new(end_ts)];
+        println!("{status}  {}", c.command.as_deref().unwrap_or_default());
+        println!(
+            "        {} · {} · {}:{}",
+            c.agent,
+            format_event_time(c.created_at),
+            c.source_path,
+            c.line_number
+        );
+    }
+    Ok(())
+}
 
-    if !agents.is_empty() {
-        sql.push_str(" AND a.slug IN (");
-        for (i, agent) in agents.iter().enumerate() {
-            if i > 0 {
-                sql.push_str(", ");
+/// Handle bookmarks subcommands
+fn run_bookmarks_command(cmd: BookmarksCommand, db_override: Option<PathBuf>) -> CliResult<()> {
+    match cmd {
+        BookmarksCommand::List {
+            tag,
+            stale,
+            limit,
+            data_dir,
+            json,
+            robot_format,
+        } => {
+            let store = open_bookmark_store(&data_dir)?;
+            let mut bookmarks = store.list(tag.as_deref()).map_err(bookmark_query_err)?;
+            let (resolve, warning) = resolve_bookmarks(&mut bookmarks, &data_dir, db_override);
+            if stale {
+                bookmarks.retain(|b| b.status == BookmarkStatus::Stale);
             }
-            sql.push_str(&format!("?{}", params.len() + 1));
-            params.push(Box::new(agent.clone()));
+            if limit > 0 {
+                bookmarks.truncate(limit);
+            }
+            output_bookmarks(
+                &bookmarks,
+                bookmarks_output_format(json, robot_format),
+                resolve,
+                warning,
+            )
         }
-        sql.push(')');
-    }
-
-    // Source filter (P3.2)
-    if let Some(ref filter) = source_filter {
-        match filter {
-            SourceFilter::All => {
-                // No filtering needed
+        BookmarksCommand::Search {
+            query,
+            limit,
+            data_dir,
+            json,
+            robot_format,
+        } => {
+            let store = open_bookmark_store(&data_dir)?;
+            let mut bookmarks = store.search(&query).map_err(bookmark_query_err)?;
+            let (resolve, warning) = resolve_bookmarks(&mut bookmarks, &data_dir, db_override);
+            if limit > 0 {
+                bookmarks.truncate(limit);
             }
-            SourceFilter::Local => {
-                sql.push_str(" AND c.source_id = 'local'");
+            output_bookmarks(
+                &bookmarks,
+                bookmarks_output_format(json, robot_format),
+                resolve,
+                warning,
+            )
+        }
+        BookmarksCommand::Add {
+            source_path,
+            line,
+            title,
+            note,
+            tags,
+            data_dir,
+            json,
+            robot_format,
+        } => run_bookmarks_add(
+            &source_path,
+            line,
+            title,
+            note,
+            tags,
+            &data_dir,
+            db_override,
+            bookmarks_output_format(json, robot_format),
+        ),
+        BookmarksCommand::Remove {
+            id,
+            data_dir,
+            json,
+            robot_format,
+        } => {
+            let store = open_bookmark_store(&data_dir)?;
+            let removed = store.remove(id).map_err(bookmark_query_err)?;
+            if !removed {
+                return Err(CliError {
+                    code: 4,
+                    kind: "not_found",
+                    message: format!("No bookmark with id {id}"),
+                    hint: Some("Use 'cass bookmarks list' to see bookmark ids.".to_string()),
+                    retryable: false,
+                });
             }
-            SourceFilter::Remote => {
-                sql.push_str(" AND c.source_id != 'local'");
+            if let Some(fmt) = bookmarks_output_format(json, robot_format) {
+                return output_structured_value(
+                    serde_json::json!({ "id": id, "removed": true }),
+                    fmt,
+                );
             }
-            SourceFilter::SourceId(id) => {
-                sql.push_str(&format!(" AND c.source_id = ?{}", params.len() + 1));
-                params.push(Box::new(id.clone()));
+            println!("Removed bookmark #{id}");
+            Ok(())
+        }
+        BookmarksCommand::Export {
+            output,
+            data_dir,
+            json,
+            robot_format,
+        } => {
+            let store = open_bookmark_store(&data_dir)?;
+            let exported = store.export_json().map_err(bookmark_query_err)?;
+            let Some(path) = output else {
+                println!("{exported}");
+                return Ok(());
+            };
+            std::fs::write(&path, &exported).map_err(|e| CliError {
+                code: 9,
+                kind: "io",
+                message: format!("Failed to write {}: {e}", path.display()),
+                hint: None,
+                retryable: false,
+            })?;
+            let count = store.count().map_err(bookmark_query_err)?;
+            if let Some(fmt) = bookmarks_output_format(json, robot_format) {
+                return output_structured_value(
+                    serde_json::json!({ "exported": count, "path": path.display().to_string() }),
+                    fmt,
+                );
+            }
+            println!("Exported {count} bookmarks to {}", path.display());
+            Ok(())
+        }
+        BookmarksCommand::Resolve {
+            data_dir,
+            json,
+            robot_format,
+        } => {
+            let store = open_bookmark_store(&data_dir)?;
+            let lazy = crate::storage::sqlite::LazyDb::from_overrides(&data_dir, db_override);
+            let conn = lazy.get("bookmarks").map_err(lazy_db_to_cli_error)?;
+            // Check every bookmark, not just those resolved before the last index run.
+            let summary = store.resolve_against(&conn, None).map_err(|e| CliError {
+                code: 9,
+                kind: "db-query",
+                message: format!("Failed to re-resolve bookmarks: {e}"),
+                hint: None,
+                retryable: false,
+            })?;
+            if let Some(fmt) = bookmarks_output_format(json, robot_format) {
+                return output_structured_value(serde_json::json!({ "resolve": summary }), fmt);
             }
+            println!(
+                "Checked {} bookmark(s): {} ok, {} relocated, {} stale",
+                summary.checked, summary.ok, summary.relocated, summary.stale
+            );
+            Ok(())
         }
     }
+}
 
-    sql.push_str(" GROUP BY c.id ORDER BY c.started_at DESC");
+/// Structured output format for `cass bookmarks`, resolved like `cass search`.
+fn bookmarks_output_format(json: bool, robot_format: Option<RobotFormat>) -> Option<RobotFormat> {
+    robot_format
+        .or(if json { Some(RobotFormat::Json) } else { None })
+        .or_else(robot_format_from_env)
+}
 
-    let mut stmt = conn.prepare(&sql).map_err(|e| CliError {
+fn open_bookmark_store(
+    data_dir_override: &Option<PathBuf>,
+) -> CliResult<crate::bookmarks::BookmarkStore> {
+    let path = match data_dir_override {
+        Some(dir) => crate::bookmarks::bookmarks_path_in(dir),
+        None => crate::bookmarks::default_bookmarks_path(),
+    };
+    crate::bookmarks::BookmarkStore::open(&path).map_err(|e| CliError {
+        code: 9,
+        kind: "db-open",
+        message: format!("Failed to open bookmarks at {}: {e}", path.display()),
+        hint: None,
+        retryable: false,
+    })
+}
+
+fn bookmark_query_err(e: anyhow::Error) -> CliError {
+    CliError {
+        code: 9,
+        kind: "db-query",
+        message: format!("Failed to read bookmarks: {e}"),
+        hint: None,
+        retryable: false,
+    }
+}
+
+/// Re-resolve bookmarks in memory against the main index if it has changed since
+/// they were last checked. Nothing is saved; `cass bookmarks resolve` does that.
+///
+/// Returns `(None, None)` when there is no index yet; resolution failures become a
+/// warning so bookmarks stay readable.
+fn resolve_bookmarks(
+    bookmarks: &mut [crate::bookmarks::Bookmark],
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> (Option<crate::bookmarks::ResolveSummary>, Option<String>) {
+    let lazy = crate::storage::sqlite::LazyDb::from_overrides(data_dir_override, db_override);
+    if !lazy.path().exists() {
+        return (None, None);
+    }
+    let conn = match lazy.get("bookmarks") {
+        Ok(conn) => conn,
+        Err(e) => return (None, Some(format!("could not re-resolve bookmarks: {e}"))),
+    };
+    let indexed_at = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'last_indexed_at'",
+            [],
+            |r| r.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|s| s.parse::<i64>().ok());
+    match crate::bookmarks::resolve_bookmarks(&conn, bookmarks, indexed_at) {
+        Ok(summary) => (Some(summary), None),
+        Err(e) => (None, Some(format!("could not re-resolve bookmarks: {e}"))),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_bookmarks_add(
+    source_path: &Path,
+    line: Option<usize>,
+    title: Option<String>,
+    note: Option<String>,
+    tags: Option<String>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    format: Option<RobotFormat>,
+) -> CliResult<()> {
+    use rusqlite::OptionalExtension;
+
+    if line == Some(0) {
+        return Err(CliError::usage(
+            "Line numbers start at 1",
+            Some("Use the line_number field from 'cass search' results.".to_string()),
+        ));
+    }
+    let path_str = source_path.to_string_lossy().to_string();
+    let lazy = crate::storage::sqlite::LazyDb::from_overrides(data_dir_override, db_override);
+    let conn = lazy.get("bookmarks").map_err(lazy_db_to_cli_error)?;
+    let db_err = |e: rusqlite::Error| CliError {
         code: 9,
         kind: "db-query",
-        message: format!("Query failed: {e}"),
+        message: format!("Failed to look up session: {e}"),
         hint: None,
         retryable: false,
-    })?;
-
-    let param_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    };
 
-    let rows = stmt
-        .query_map(param_refs.as_slice(), |row| {
-            Ok((
-                row.get::<_, i64>(0)?,            // id
-                row.get::<_, String>(1)?,         // agent
-                row.get::<_, Option<String>>(2)?, // title
-                row.get::<_, i64>(3)?,            // started_at
-                row.get::<_, Option<i64>>(4)?,    // ended_at
-                row.get::<_, String>(5)?,         // source_path
-                row.get::<_, i64>(6)?,            // message_count
-                row.get::<_, String>(7)?,         // source_id (P3.2)
-                row.get::<_, Option<String>>(8)?, // origin_host (P3.5)
-                row.get::<_, Option<String>>(9)?, // origin_kind (P3.5)
-            ))
-        })
-        .map_err(|e| CliError {
-            code: 9,
-            kind: "db-query",
-            message: format!("Query failed: {e}"),
-            hint: None,
+    let session: Option<(String, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT a.slug, w.path, c.title FROM conversations c
+             JOIN agents a ON a.id = c.agent_id
+             LEFT JOIN workspaces w ON w.id = c.workspace_id
+             WHERE c.source_path = ?1
+             ORDER BY c.id LIMIT 1",
+            [&path_str],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(db_err)?;
+    let Some((agent, workspace, session_title)) = session else {
+        return Err(CliError {
+            code: 4,
+            kind: "not_found",
+            message: format!("No session found at path: {path_str}"),
+            hint: Some(
+                "Use 'cass search' to find sessions, then use the source_path from results."
+                    .to_string(),
+            ),
             retryable: false,
-        })?;
+        });
+    };
 
-    #[allow(clippy::type_complexity)]
-    let mut sessions: Vec<(
-        i64,
-        String,
-        Option<String>,
-        i64,
-        Option<i64>,
-        String,
-        i64,
-        String,
-        Option<String>,
-        Option<String>,
-    )> = Vec::new();
-    for r in rows.flatten() {
-        sessions.push(r);
+    let title = title.or(session_title).unwrap_or_else(|| {
+        source_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path_str.clone())
+    });
+    let mut bookmark = crate::bookmarks::Bookmark::new(
+        title,
+        path_str.clone(),
+        agent,
+        workspace.unwrap_or_default(),
+    );
+    if let Some(line) = line {
+        let message: Option<(String, Option<i64>)> = conn
+            .query_row(
+                "SELECT m.content, m.created_at FROM messages m
+                 JOIN conversations c ON c.id = m.conversation_id
+                 WHERE c.source_path = ?1 AND m.idx = ?2
+                 LIMIT 1",
+                rusqlite::params![path_str, line as i64 - 1],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(db_err)?;
+        let Some((content, created_at)) = message else {
+            return Err(CliError {
+                code: 4,
+                kind: "not_found",
+                message: format!("No message at line {line} in {path_str}"),
+                hint: Some("Use the line_number field from 'cass search' results.".to_string()),
+                retryable: false,
+            });
+        };
+        let snippet: String = content.split_whitespace().collect::<Vec<_>>().join(" ");
+        bookmark = bookmark
+            .with_line(line)
+            .with_snippet(snippet.chars().take(200).collect::<String>())
+            .with_anchor(&content, created_at);
     }
+    if let Some(note) = note {
+        bookmark = bookmark.with_note(note);
+    }
+    if let Some(tags) = tags {
+        bookmark = bookmark.with_tags(tags);
+    }
+    // Just looked up in the index, so the bookmark starts out resolved.
+    bookmark.status = BookmarkStatus::Ok;
+    bookmark.resolved_at = Some(bookmark.created_at);
 
-    let structured_format = if json {
-        Some(RobotFormat::Json)
-    } else {
-        robot_format_from_env()
+    let store = open_bookmark_store(data_dir_override)?;
+    bookmark.id = store.add(&bookmark).map_err(bookmark_query_err)?;
+
+    if let Some(fmt) = format {
+        return output_structured_value(serde_json::to_value(&bookmark).unwrap_or_default(), fmt);
     }
-    .map(|fmt| {
-        if matches!(fmt, RobotFormat::Sessions) {
-            RobotFormat::Compact
-        } else {
-            fmt
-        }
-    });
+    println!("Added bookmark #{}: {}", bookmark.id, bookmark.title);
+    Ok(())
+}
 
-    if let Some(fmt) = structured_format {
-        let output = match group_by {
-            TimelineGrouping::None => {
-                let items: Vec<serde_json::Value> = sessions
-                    .iter()
-                    .map(
-                        |(
-                            id,
-                            agent,
-                            title,
-                            started,
-                            ended,
-                            path,
-                            msg_count,
-                            source_id,
-                            origin_host,
-                            origin_kind,
-                        )| {
-                            let duration = ended.map(|e| e - started);
-                            // Use "local" as default origin_kind if not in DB (backward compat)
-                            let kind = origin_kind.as_deref().unwrap_or("local");
-                            serde_json::json!({
-                                "id": id, "agent": agent, "title": title,
-                                "started_at": started, "ended_at": ended,
-                                "duration_seconds": duration, "source_path": path,
-                                "message_count": msg_count,
-                                // Provenance fields (P3.5)
-                                "source_id": source_id,
-                                "origin_kind": kind,
-                                "origin_host": origin_host,
-                            })
-                        },
-                    )
-                    .collect();
-                serde_json::json!({
-                    "range": { "start": start_ts, "end": end_ts },
-                    "total_sessions": sessions.len(),
-                    "sessions": items,
-                })
+/// Print bookmarks using the same formats as `cass search` robot output.
+fn output_bookmarks(
+    bookmarks: &[crate::bookmarks::Bookmark],
+    format: Option<RobotFormat>,
+    resolve: Option<crate::bookmarks::ResolveSummary>,
+    warning: Option<String>,
+) -> CliResult<()> {
+    let stale = bookmarks
+        .iter()
+        .filter(|b| b.status == BookmarkStatus::Stale)
+        .count();
+    let Some(format) = format else {
+        if let Some(warning) = &warning {
+            eprintln!("{} {warning}", console::style("warning:").yellow());
+        }
+        if bookmarks.is_empty() {
+            println!("No bookmarks. Add one with: cass bookmarks add <session> [-n LINE]");
+        }
+        for b in bookmarks {
+            let status = match b.status {
+                BookmarkStatus::Stale => console::style(" [stale]").red().to_string(),
+                BookmarkStatus::Relocated => console::style(" [relocated]").yellow().to_string(),
+                _ => String::new(),
+            };
+            println!("#{} {}{status}", b.id, console::style(&b.title).bold());
+            match b.line_number {
+                Some(line) => println!("    {}:{line}", b.source_path),
+                None => println!("    {}", b.source_path),
             }
-            TimelineGrouping::Hour | TimelineGrouping::Day => {
-                let mut groups: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
-                for (
-                    id,
-                    agent,
-                    title,
-                    started,
-                    ended,
-                    path,
-                    msg_count,
-                    source_id,
-                    origin_host,
-                    origin_kind,
-                ) in &sessions
+            if !b.tags.is_empty() {
+                let pills: Vec<String> = b.tag_list().iter().map(|t| format!("#{t}")).collect();
+                println!("    {}", pills.join(" "));
+            }
+            if !b.note.is_empty() {
+                println!("    {}", b.note);
+            }
+        }
+        if stale > 0 {
+            eprintln!(
+                "{} {stale} bookmark(s) no longer match the index (session moved, compacted, or deleted)",
+                console::style("warning:").yellow()
+            );
+        }
+        return Ok(());
+    };
+
+    let meta = serde_json::json!({
+        "count": bookmarks.len(),
+        "stale": stale,
+        "resolve": resolve,
+    });
+    match format {
+        RobotFormat::Jsonl => {
+            if stale > 0 || warning.is_some() || resolve.is_some_and(|r| r.checked > 0) {
+                let mut line = serde_json::json!({ "_meta": meta });
+                if let Some(warn) = &warning
+                    && let serde_json::Value::Object(ref mut map) = line
                 {
-                    let dt = Utc
-                        .timestamp_millis_opt(*started)
-                        .single()
-                        .unwrap_or_else(Utc::now);
-                    let key = match group_by {
-                        TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
-                        TimelineGrouping::Day => dt.format("%Y-%m-%d").to_string(),
-                        _ => unreachable!(),
-                    };
-                    // Use "local" as default origin_kind if not in DB (backward compat)
-                    let kind = origin_kind.as_deref().unwrap_or("local");
-                    groups.entry(key).or_default().push(serde_json::json!({
-                        "id": id, "agent": agent, "title": title,
-                        "started_at": started, "ended_at": ended,
-                        "source_path": path, "message_count": msg_count,
-                        // Provenance fields (P3.5)
-                        "source_id": source_id,
-                        "origin_kind": kind,
-                        "origin_host": origin_host,
-                    }));
+                    map.insert("_warning".to_string(), serde_json::json!(warn));
                 }
-                serde_json::json!({
-                    "range": { "start": start_ts, "end": end_ts },
-                    "total_sessions": sessions.len(),
-                    "groups": groups,
-                })
+                println!("{}", serde_json::to_string(&line).unwrap_or_default());
             }
-        };
-        return output_structured_value(output, fmt);
+            for b in bookmarks {
+                println!("{}", serde_json::to_string(b).unwrap_or_default());
+            }
+            Ok(())
+        }
+        RobotFormat::Sessions => {
+            let mut seen = std::collections::HashSet::new();
+            for b in bookmarks {
+                if seen.insert(b.source_path.as_str()) {
+                    println!("{}", b.source_path);
+                }
+            }
+            Ok(())
+        }
+        _ => {
+            let mut payload = serde_json::json!({
+                "count": bookmarks.len(),
+                "stale": stale,
+                "bookmarks": bookmarks,
+                "_meta": { "resolve": resolve },
+            });
+            if let Some(warn) = warning
+                && let serde_json::Value::Object(ref mut map) = payload
+            {
+                map.insert("_warning".to_string(), serde_json::Value::String(warn));
+            }
+            output_structured_value(payload, format)
+        }
     }
+}
 
-    let start_dt = Utc
-        .timestamp_millis_opt(start_ts)
-        .single()
-        .unwrap_or_else(Utc::now);
-    let end_dt = Utc
-        .timestamp_millis_opt(end_ts)
-        .single()
-        .unwrap_or_else(Utc::now);
-
-    println!("\n📅 Activity Timeline");
-    println!(
-        "   {} to {}",
-        start_dt.format("%Y-%m-%d %H:%M"),
-        end_dt.format("%Y-%m-%d %H:%M")
-    );
-    println!("{}", "─".repeat(70));
+/// Handle saved-search subcommands
+fn run_saved_command(cmd: SavedCommand, db_override: Option<PathBuf>) -> CliResult<()> {
+    use crate::saved_searches::{AlertTarget, SavedFilters, SavedSearch, is_valid_name};
 
-    if sessions.is_empty() {
-        println!("\n   No sessions found in this time range.\n");
-        return Ok(());
+    match cmd {
+        SavedCommand::Add {
+            name,
+            query,
+            agent,
+            workspace,
+            role,
+            tool,
+            tag,
+            source,
+            days,
+            mode,
+            limit,
+            alert_jsonl,
+            hook,
+            data_dir,
+            json,
+        } => {
+            if !is_valid_name(&name) {
+                return Err(CliError::usage(
+                    format!("Invalid saved search name: {name:?}"),
+                    Some("Use 1-64 letters, digits, '-', '_' or '.'.".to_string()),
+                ));
+            }
+            if query.trim().is_empty() {
+                return Err(CliError::usage("Saved search query is empty", None));
+            }
+            let filters = SavedFilters {
+                agents: agent,
+                workspaces: workspace,
+                roles: role,
+                tools: tool,
+                tags: tag,
+                source,
+                days,
+            };
+            // Reject filters that could never match now, not on every run.
+            let mut resolved = filters
+                .to_search_filters(0)
+                .map_err(|e| CliError::usage(e.to_string(), None))?;
+            crate::search::query::split_inline_filters(&query, &mut resolved)
+                .map_err(|e| CliError::usage(e.to_string(), None))?;
+            let mut search = SavedSearch::new(name, query, Utc::now().timestamp_millis());
+            search.filters = filters;
+            search.mode = mode.unwrap_or_default();
+            search.limit = limit.max(1);
+            // The watcher may run from another directory, so pin relative paths now.
+            let alert = AlertTarget {
+                jsonl: alert_jsonl.map(|p| std::path::absolute(&p).unwrap_or(p)),
+                hook,
+            };
+            search.alert = (!alert.is_empty()).then_some(alert);
+
+            let mut store = open_saved_store(&data_dir)?;
+            let replaced = store.upsert(search.clone());
+            store.save().map_err(saved_write_err)?;
+            let saved = store.get(&search.name).cloned().unwrap_or(search);
+            if let Some(fmt) = tag_output_format(json) {
+                return output_structured_value(
+                    serde_json::json!({ "saved_search": saved, "replaced": replaced }),
+                    fmt,
+                );
+            }
+            let verb = if replaced { "Updated" } else { "Saved" };
+            println!("{verb} search '{}': {}", saved.name, saved.query);
+            if saved.has_alert() {
+                println!("  Alerts fire on new matches while `cass index --watch` runs.");
+            }
+            Ok(())
+        }
+        SavedCommand::List { data_dir, json } => {
+            let store = open_saved_store(&data_dir)?;
+            let searches = store.list();
+            if let Some(fmt) = tag_output_format(json) {
+                return output_structured_value(
+                    serde_json::json!({
+                        "count": searches.len(),
+                        "path": store.path().display().to_string(),
+                        "saved_searches": searches,
+                    }),
+                    fmt,
+                );
+            }
+            if searches.is_empty() {
+                println!("No saved searches. Add one with: cass saved add <name> <query>");
+            }
+            for s in searches {
+                let mode = match s.mode {
+                    crate::search::query::SearchMode::Lexical => "",
+                    crate::search::query::SearchMode::Semantic => " [semantic]",
+                    crate::search::query::SearchMode::Hybrid => " [hybrid]",
+                };
+                let alert = if s.has_alert() {
+                    console::style(" [alert]").yellow().to_string()
+                } else {
+                    String::new()
+                };
+                println!("{}{mode}{alert}", console::style(&s.name).bold());
+                println!("    {}", s.query);
+                if let Some(ts) = s
+                    .last_run_at
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                {
+                    println!("    last run {}", ts.format("%Y-%m-%d %H:%M"));
+                }
+            }
+            Ok(())
+        }
+        SavedCommand::Remove {
+            name,
+            data_dir,
+            json,
+        } => {
+            let mut store = open_saved_store(&data_dir)?;
+            if !store.remove(&name) {
+                return Err(saved_not_found(&name));
+            }
+            store.save().map_err(saved_write_err)?;
+            if let Some(fmt) = tag_output_format(json) {
+                return output_structured_value(
+                    serde_json::json!({ "name": name, "removed": true }),
+                    fmt,
+                );
+            }
+            println!("Removed saved search '{name}'");
+            Ok(())
+        }
+        SavedCommand::Run {
+            name,
+            new,
+            limit,
+            data_dir,
+            json,
+            robot_format,
+        } => run_saved_search_cli(
+            &name,
+            new,
+            limit,
+            &data_dir,
+            db_override,
+            bookmarks_output_format(json, robot_format),
+        ),
     }
+}
 
-    let mut current_group = String::new();
-    for (
-        _id,
-        agent,
-        title,
-        started,
-        ended,
-        _path,
-        msg_count,
-        source_id,
-        origin_host,
-        _origin_kind,
-    ) in &sessions
-    {
-        let dt = Utc
-            .timestamp_millis_opt(*started)
-            .single()
-            .unwrap_or_else(Utc::now);
+fn open_saved_store(
+    data_dir_override: &Option<PathBuf>,
+) -> CliResult<crate::saved_searches::SavedSearchStore> {
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let path = crate::saved_searches::SavedSearchStore::path_for(&data_dir);
+    crate::saved_searches::SavedSearchStore::load(&path).map_err(|e| CliError {
+        code: 9,
+        kind: "config",
+        message: format!("Failed to read saved searches: {e}"),
+        hint: Some(format!("Fix or delete {}", path.display())),
+        retryable: false,
+    })
+}
 
-        let group_key = match group_by {
-            TimelineGrouping::Hour => dt.format("%Y-%m-%d %H:00").to_string(),
-            TimelineGrouping::Day => dt.format("%Y-%m-%d (%A)").to_string(),
-            TimelineGrouping::None => String::new(),
-        };
+fn saved_write_err(e: anyhow::Error) -> CliError {
+    CliError {
+        code: 9,
+        kind: "io",
+        message: format!("Failed to write saved searches: {e}"),
+        hint: None,
+        retryable: false,
+    }
+}
 
-        if group_key != current_group && group_by != TimelineGrouping::None {
-            println!("\n  📆 {}", group_key);
-            current_group = group_key;
-        }
+fn saved_not_found(name: &str) -> CliError {
+    CliError {
+        code: 4,
+        kind: "not_found",
+        message: format!("No saved search named '{name}'"),
+        hint: Some("Use 'cass saved list' to see saved searches.".to_string()),
+        retryable: false,
+    }
+}
 
-        let duration = ended.map(|e| {
-            // Timestamps are in milliseconds, divide by 60_000 to get minutes
-            let mins = (e - started) / 60_000;
-            if mins < 60 {
-                format!("{}m", mins)
-            } else {
-                format!("{}h{}m", mins / 60, mins % 60)
-            }
-        });
+/// Run a saved search, print its hits, and advance its high-water mark.
+fn run_saved_search_cli(
+    name: &str,
+    new_only: bool,
+    limit: Option<usize>,
+    data_dir_override: &Option<PathBuf>,
+    db_override: Option<PathBuf>,
+    format: Option<RobotFormat>,
+) -> CliResult<()> {
+    let mut store = open_saved_store(data_dir_override)?;
+    let mut search = store
+        .get(name)
+        .cloned()
+        .ok_or_else(|| saved_not_found(name))?;
+    if let Some(limit) = limit {
+        search.limit = limit.max(1);
+    }
 
-        let title_str = title.as_deref().unwrap_or("(untitled)");
-        let title_preview: String = title_str.chars().take(40).collect();
+    let data_dir = data_dir_override.clone().unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let client = crate::saved_searches::open_client(&data_dir, &db_path, search.mode)
+        .map_err(|e| CliError {
+            code: 9,
+            kind: "open-index",
+            message: format!("failed to open index: {e}"),
+            hint: Some("try cass index --full".to_string()),
+            retryable: true,
+        })?
+        .ok_or_else(|| CliError {
+            code: 3,
+            kind: "missing-index",
+            message: "Index not found. Run 'cass index --full' first.".to_string(),
+            hint: None,
+            retryable: true,
+        })?;
 
-        let agent_icon = match agent.as_str() {
-            "claude_code" => "🟣",
-            "codex" => "🟢",
-            "gemini" => "🔵",
-            "amp" => "🟡",
-            "cursor" => "⚪",
-            "pi_agent" => "🟠",
-            _ => "⚫",
-        };
+    let now_ms = Utc::now().timestamp_millis();
+    let since = if new_only {
+        search.high_water_mark.map(|hwm| hwm + 1)
+    } else {
+        None
+    };
+    let run =
+        crate::saved_searches::run_saved_search(&client, &search, since, now_ms).map_err(|e| {
+            CliError {
+                code: 9,
+                kind: "search",
+                message: format!("saved search '{name}' failed: {e}"),
+                hint: None,
+                retryable: true,
+            }
+        })?;
+    store.record_run(name, now_ms, run.high_water_mark);
+    store.save().map_err(saved_write_err)?;
 
-        // Source badge for remote sessions (P3.2, P3.5)
-        // Prefer origin_host if available, otherwise use source_id
-        let source_badge = if source_id != "local" {
-            let label = origin_host.as_deref().unwrap_or(source_id.as_str());
-            format!(" [{}]", label)
-        } else {
-            String::new()
+    let Some(format) = format else {
+        let scope = match run.since.and_then(chrono::DateTime::from_timestamp_millis) {
+            Some(ts) => format!(" new since {}", ts.format("%Y-%m-%d %H:%M:%S")),
+            None => String::new(),
         };
-
         println!(
-            "     {} {} {:>5} │ {:>3} msgs │ {}{}",
-            dt.format("%H:%M"),
-            agent_icon,
-            duration.as_deref().unwrap_or(""),
-            msg_count,
-            title_preview,
-            source_badge
+            "{}: {} result(s){scope}",
+            console::style(name).bold(),
+            run.hits.len()
         );
-    }
+        for hit in &run.hits {
+            let snippet = truncate_end(&hit.snippet.replace('\n', " "), 60);
+            match hit.line_number {
+                Some(line) => println!("  {} | {}:{line} | {snippet}", hit.agent, hit.source_path),
+                None => println!("  {} | {} | {snippet}", hit.agent, hit.source_path),
+            }
+        }
+        return Ok(());
+    };
 
-    println!("\n{}", "─".repeat(70));
-    println!("   Total: {} sessions\n", sessions.len());
-    Ok(())
+    let meta = serde_json::json!({
+        "saved_search": name,
+        "query": search.query,
+        "mode": search.mode,
+        "new_only": new_only,
+        "since": run.since,
+        "high_water_mark": run.high_water_mark,
+        "count": run.hits.len(),
+    });
+    match format {
+        RobotFormat::Jsonl => {
+            println!("{}", serde_json::json!({ "_meta": meta }));
+            for hit in &run.hits {
+                println!("{}", serde_json::to_string(hit).unwrap_or_default());
+            }
+            Ok(())
+        }
+        RobotFormat::Sessions => {
+            let mut seen = std::collections::HashSet::new();
+            for hit in &run.hits {
+                if seen.insert(hit.source_path.as_str()) {
+                    println!("{}", hit.source_path);
+                }
+            }
+            Ok(())
+        }
+        _ => {
+            let mut payload = meta;
+            if let serde_json::Value::Object(ref mut map) = payload {
+                map.insert("hits".to_string(), serde_json::json!(run.hits));
+            }
+            output_structured_value(payload, format)
+        }
+    }
 }
 
 /// Handle sources subcommands (P5.x)
-fn run_sources_command(cmd: SourcesCommand) -> CliResult<()> {
+fn run_sources_command(cmd: SourcesCommand, db_override: Option<PathBuf>) -> CliResult<()> {
     match cmd {
         SourcesCommand::List { verbose, json } => {
             run_sources_list(verbose, json)?;
@@ -12712,9 +16653,10 @@ fn run_sources_command(cmd: SourcesCommand) -> CliResult<()> {
             name,
             preset,
             paths,
+            native,
             no_test,
         } => {
-            run_sources_add(&url, name, preset, paths, no_test)?;
+            run_sources_add(&url, name, preset, paths, native, no_test)?;
         }
         SourcesCommand::Remove { name, purge, yes } => {
             run_sources_remove(&name, purge, yes)?;
@@ -12731,6 +16673,17 @@ fn run_sources_command(cmd: SourcesCommand) -> CliResult<()> {
         } => {
             run_sources_sync(source, no_index, verbose, dry_run, json)?;
         }
+        SourcesCommand::Daemon {
+            interval,
+            once,
+            no_index,
+            json,
+        } => {
+            run_sources_daemon(interval, once, no_index, json)?;
+        }
+        SourcesCommand::ServeStdio { data_dir } => {
+            run_sources_serve_stdio(data_dir, db_override)?;
+        }
         SourcesCommand::Mappings(action) => {
             run_mappings_command(action)?;
         }
@@ -12812,6 +16765,7 @@ fn run_sources_list(verbose: bool, json: bool) -> CliResult<()> {
                     "host": s.host,
                     "paths": s.paths,
                     "sync_schedule": s.sync_schedule.to_string(),
+                    "transport": s.transport.to_string(),
                     "platform": s.platform.map(|p| p.to_string()),
                 })
             })
@@ -12847,6 +16801,7 @@ fn run_sources_list(verbose: bool, json: bool) -> CliResult<()> {
                 println!("  Host: {host}");
             }
             println!("  Schedule: {}", source.sync_schedule);
+            println!("  Transport: {}", source.transport);
             if let Some(platform) = source.platform {
                 println!("  Platform: {platform}");
             }
@@ -12906,9 +16861,12 @@ fn run_sources_add(
     name: Option<String>,
     preset: Option<String>,
     paths_arg: Vec<String>,
+    native: bool,
     no_test: bool,
 ) -> CliResult<()> {
-    use crate::sources::config::{Platform, SourceDefinition, SourcesConfig, get_preset_paths};
+    use crate::sources::config::{
+        Platform, SourceDefinition, SourcesConfig, SyncTransport, get_preset_paths,
+    };
     use crate::sources::provenance::SourceKind;
 
     // Parse URL to extract host
@@ -12925,12 +16883,17 @@ fn run_sources_add(
         })?
     } else if !paths_arg.is_empty() {
         paths_arg
+    } else if native {
+        // The host's cass decides what to serve.
+        Vec::new()
     } else {
         return Err(CliError {
             code: 10,
             kind: "config",
             message: "No paths specified".into(),
-            hint: Some("Use --preset macos-defaults or --path <path> to specify paths".into()),
+            hint: Some(
+                "Use --preset macos-defaults or --path <path> to specify paths, or --native".into(),
+            ),
             retryable: false,
         });
     };
@@ -12979,6 +16942,11 @@ fn run_sources_add(
         source_type: SourceKind::Ssh,
         host: Some(host.clone()),
         paths: paths.clone(),
+        transport: if native {
+            SyncTransport::Native
+        } else {
+            SyncTransport::Files
+        },
         platform,
         ..Default::default()
     };
@@ -13009,7 +16977,11 @@ fn run_sources_add(
     println!();
     println!("Added source '{source_id}'");
     println!("  Host: {host}");
-    println!("  Paths: {} path(s)", paths.len());
+    if native {
+        println!("  Transport: native (cass sources serve-stdio)");
+    } else {
+        println!("  Paths: {} path(s)", paths.len());
+    }
     println!("  Config: {config_path}");
     println!();
     println!("Next steps:");
@@ -13562,7 +17534,7 @@ fn run_sources_sync(
     dry_run: bool,
     json_output: bool,
 ) -> CliResult<()> {
-    use crate::sources::config::SourcesConfig;
+    use crate::sources::config::{SourcesConfig, SyncTransport};
     use crate::sources::sync::{SyncEngine, SyncStatus};
     use colored::Colorize;
 
@@ -13651,8 +17623,16 @@ fn run_sources_sync(
         if dry_run {
             // In dry run, just show what would be synced
             if !json_output {
-                for path in &source.paths {
-                    println!("  {} {}", "Would sync:".dimmed(), path);
+                if source.transport == SyncTransport::Native {
+                    println!(
+                        "  {} new conversations from cass on {}",
+                        "Would pull:".dimmed(),
+                        source.host.as_deref().unwrap_or("-")
+                    );
+                } else {
+                    for path in &source.paths {
+                        println!("  {} {}", "Would sync:".dimmed(), path);
+                    }
                 }
                 println!();
             }
@@ -13784,6 +17764,7 @@ fn run_sources_sync(
             false,          // semantic
             false,          // build_hnsw
             "fastembed".to_string(),
+            crate::search::vector_index::Quantization::F32,
             progress,
             json_output,
             None, // idempotency_key
@@ -13793,6 +17774,212 @@ fn run_sources_sync(
     Ok(())
 }
 
+/// Sync sources on their `sync_schedule`, reindexing only the mirror roots
+/// that received files. Runs until interrupted unless `once` is set.
+fn run_sources_daemon(
+    interval: u64,
+    once: bool,
+    no_index: bool,
+    json_output: bool,
+) -> CliResult<()> {
+    use crate::sources::config::SourcesConfig;
+    use crate::sources::scheduler::{ScheduledSync, SyncScheduler};
+    use colored::Colorize;
+
+    let config = SourcesConfig::load().map_err(|e| CliError {
+        code: 9,
+        kind: "config",
+        message: format!("Failed to load sources config: {e}"),
+        hint: Some("Run 'cass sources add' to configure a source".into()),
+        retryable: false,
+    })?;
+    let scheduled = config
+        .remote_sources()
+        .filter(|source| source.sync_schedule.interval().is_some())
+        .count();
+    if scheduled == 0 && !json_output {
+        eprintln!(
+            "{}",
+            "No remote source has an hourly or daily sync_schedule; nothing will sync until one does."
+                .yellow()
+        );
+    }
+
+    let data_dir = default_data_dir();
+    let db_path = data_dir.join("agent_search.db");
+    let scheduler = SyncScheduler::new(&data_dir);
+
+    let handle_synced = |synced: &[ScheduledSync]| {
+        for sync in synced {
+            let report = &sync.report;
+            let status = if report.all_succeeded {
+                "success"
+            } else if report.successful_paths() > 0 {
+                "partial"
+            } else {
+                "failed"
+            };
+            let errors: Vec<&str> = report
+                .path_results
+                .iter()
+                .filter_map(|r| r.error.as_deref())
+                .collect();
+            let next_sync = sync
+                .next_sync
+                .and_then(chrono::DateTime::<Utc>::from_timestamp_millis);
+            if json_output {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "event": "sync",
+                        "source": report.source_name,
+                        "status": status,
+                        "files": report.total_files(),
+                        "bytes": report.total_bytes(),
+                        "duration_ms": report.total_duration_ms,
+                        "errors": errors,
+                        "next_sync": next_sync.map(|t| t.to_rfc3339()),
+                    })
+                );
+                continue;
+            }
+            let next = next_sync
+                .map(|t| {
+                    t.with_timezone(&chrono::Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_else(|| "-".into());
+            if status == "failed" {
+                println!(
+                    "{} {}: {} (retry after {next})",
+                    "Sync failed".red().bold(),
+                    report.source_name.white().bold(),
+                    errors.join("; ").red()
+                );
+            } else {
+                println!(
+                    "{} {}: {} files, {} (next sync {next})",
+                    "Synced".green().bold(),
+                    report.source_name.white().bold(),
+                    report.total_files(),
+                    format_bytes(report.total_bytes())
+                );
+            }
+        }
+
+        let roots: Vec<PathBuf> = synced
+            .iter()
+            .flat_map(|sync| sync.changed_roots.iter().cloned())
+            .collect();
+        if no_index || roots.is_empty() {
+            return;
+        }
+        let opts = IndexOptions {
+            full: false,
+            force_rebuild: false,
+            watch: false,
+            watch_once_paths: None,
+            db_path: db_path.clone(),
+            data_dir: data_dir.clone(),
+            semantic: false,
+            build_hnsw: false,
+            embedder: "fastembed".to_string(),
+            quantization: crate::search::vector_index::Quantization::F32,
+            progress: None,
+        };
+        match indexer::reindex_remote_roots(&opts, &roots) {
+            Ok(conversations) if json_output => println!(
+                "{}",
+                serde_json::json!({
+                    "event": "reindex",
+                    "roots": roots,
+                    "conversations": conversations,
+                })
+            ),
+            Ok(conversations) => println!(
+                "{} {conversations} conversations from {} mirror root(s)",
+                "Re-indexed".cyan().bold(),
+                roots.len()
+            ),
+            Err(e) if json_output => println!(
+                "{}",
+                serde_json::json!({
+                    "event": "reindex",
+                    "roots": roots,
+                    "error": e.to_string(),
+                })
+            ),
+            Err(e) => eprintln!("{} {e}", "Re-index failed:".red().bold()),
+        }
+    };
+
+    if once {
+        let synced = scheduler.run_due(&config.sources);
+        if synced.is_empty() && !json_output {
+            println!("No sources are due for sync.");
+        }
+        handle_synced(&synced);
+        return Ok(());
+    }
+
+    if !json_output {
+        eprintln!(
+            "Syncing {scheduled} scheduled source(s), checking every {interval}s (Ctrl+C to stop)"
+        );
+    }
+    scheduler.run(Duration::from_secs(interval.max(1)), |synced| {
+        handle_synced(synced);
+        true
+    });
+    Ok(())
+}
+
+/// Answer one native sync pull on stdin/stdout; run over ssh by `sources sync`.
+fn run_sources_serve_stdio(
+    data_dir: Option<PathBuf>,
+    db_override: Option<PathBuf>,
+) -> CliResult<()> {
+    use crate::sources::native;
+    use crate::storage::sqlite::{LazyDbError, SqliteStorage};
+
+    let data_dir = data_dir.unwrap_or_else(default_data_dir);
+    let db_path = db_override.unwrap_or_else(|| data_dir.join("agent_search.db"));
+    let opened = if db_path.exists() {
+        SqliteStorage::open_readonly(&db_path).map_err(|e| CliError {
+            code: 9,
+            kind: "db-open",
+            message: format!("Failed to open database at {}: {e}", db_path.display()),
+            hint: None,
+            retryable: false,
+        })
+    } else {
+        Err(lazy_db_to_cli_error(LazyDbError::NotFound(db_path.clone())))
+    };
+    let storage = match opened {
+        Ok(storage) => storage,
+        Err(err) => {
+            // The puller only reads stdout, so say why there before exiting.
+            let _ = native::write_error(io::stdout().lock(), &err.message);
+            return Err(err);
+        }
+    };
+
+    native::serve(
+        &storage,
+        io::stdin().lock(),
+        io::BufWriter::new(io::stdout().lock()),
+    )
+    .map_err(|e| CliError {
+        code: 9,
+        kind: "io",
+        message: format!("native sync serve failed: {e:#}"),
+        hint: None,
+        retryable: true,
+    })?;
+    Ok(())
+}
+
 /// Auto-discover SSH hosts from ~/.ssh/config (P5.6)
 fn run_sources_discover(preset: &str, skip_existing: bool, json_output: bool) -> CliResult<()> {
     use crate::sources::config::{SourcesConfig, discover_ssh_hosts, get_preset_paths};
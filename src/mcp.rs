@@ -0,0 +1,788 @@
+//! Model Context Protocol server for `cass mcp`.
+//!
+//! Speaks newline-delimited JSON-RPC 2.0 on stdin/stdout and exposes the
+//! read-only robot commands (`search`, `view`, `expand`, `context`, `timeline`,
+//! `stats`) as MCP tools. Tool input schemas are generated from the same clap
+//! definitions `cass introspect` publishes, so the two never drift apart.
+//!
+//! A tool call is parsed exactly like the equivalent `cass <command> --json`
+//! invocation and runs in-process. Structured output that the command would
+//! normally print is captured instead (stdout is the protocol channel), and the
+//! search index stays open for the life of the server rather than being
+//! reopened per call.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use clap::Parser;
+use serde_json::{Map, Value, json};
+
+use crate::search::query::{SearchClient, SearchClientOptions};
+use crate::{
+    ArgumentSchema, Cli, CliError, CliResult, CommandSchema, Commands, ProgressResolved, WrapConfig,
+};
+
+/// Protocol revisions this server understands, newest first.
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Commands exposed as tools, in `tools/list` order.
+pub const TOOL_COMMANDS: &[&str] = &["search", "view", "expand", "context", "timeline", "stats"];
+
+/// Arguments that only shape terminal output, are fixed by the server, or
+/// would read from stdin (which carries the protocol).
+const SERVER_OWNED_ARGS: &[&str] = &[
+    "json",
+    "robot-format",
+    "robot-meta",
+    "display",
+    "data-dir",
+    "sessions-from",
+];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+thread_local! {
+    /// Payloads emitted through the structured output path during a tool call.
+    static CAPTURED: RefCell<Option<Vec<Value>>> = const { RefCell::new(None) };
+    /// Search clients held open while the server runs, keyed by index path.
+    static WARM_CLIENTS: RefCell<Option<HashMap<PathBuf, Arc<SearchClient>>>> =
+        const { RefCell::new(None) };
+}
+
+/// Divert a structured payload into the active tool call or HTTP request, if any.
+///
+/// Returns the payload untouched when no tool call is capturing, so callers
+/// print it as usual.
+pub(crate) fn capture_structured_output(payload: Value) -> Option<Value> {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(buffer) => {
+            buffer.push(payload);
+            None
+        }
+        None => Some(payload),
+    })
+}
+
+/// Whether this thread is serving `cass mcp` or `cass serve` (and so holds its own warm clients).
+pub(crate) fn is_serving() -> bool {
+    WARM_CLIENTS.with(|clients| clients.borrow().is_some())
+}
+
+/// The server's long-lived search client for `index_path`.
+///
+/// Returns `None` outside `cass mcp`/`cass serve`; callers then open a one-shot client.
+pub(crate) fn warm_search_client(
+    index_path: &Path,
+    db_path: &Path,
+) -> Option<CliResult<Arc<SearchClient>>> {
+    WARM_CLIENTS.with(|clients| {
+        let mut clients = clients.borrow_mut();
+        let clients = clients.as_mut()?;
+        if let Some(client) = clients.get(index_path) {
+            return Some(Ok(client.clone()));
+        }
+        let opened = crate::open_cli_search_client(
+            index_path,
+            db_path,
+            SearchClientOptions {
+                enable_reload: true,
+                enable_warm: true,
+            },
+        )
+        .map(Arc::new);
+        if let Ok(client) = &opened {
+            clients.insert(index_path.to_path_buf(), client.clone());
+        }
+        Some(opened)
+    })
+}
+
+/// A JSON-RPC error object.
+#[derive(Debug, Clone, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Run `serve` with search clients held open across the calls it makes.
+pub(crate) fn with_warm_clients<T>(serve: impl FnOnce() -> T) -> T {
+    WARM_CLIENTS.with(|clients| *clients.borrow_mut() = Some(HashMap::new()));
+    let result = serve();
+    WARM_CLIENTS.with(|clients| *clients.borrow_mut() = None);
+    result
+}
+
+/// A tool backed by one CLI subcommand.
+pub(crate) struct Tool {
+    pub(crate) schema: CommandSchema,
+    /// Subcommand path in argv (`["sources", "list"]` for nested commands).
+    command_path: Vec<String>,
+    accepts_data_dir: bool,
+    accepts_robot_meta: bool,
+}
+
+impl Tool {
+    /// Wrap an introspected command, hiding the arguments the server owns.
+    pub(crate) fn new(mut schema: CommandSchema, command_path: &[&str]) -> Self {
+        let accepts = |name: &str| schema.arguments.iter().any(|arg| arg.name == name);
+        let accepts_data_dir = accepts("data-dir");
+        let accepts_robot_meta = accepts("robot-meta");
+        schema
+            .arguments
+            .retain(|arg| !SERVER_OWNED_ARGS.contains(&arg.name.as_str()));
+        Self {
+            schema,
+            command_path: command_path
+                .iter()
+                .map(|part| (*part).to_string())
+                .collect(),
+            accepts_data_dir,
+            accepts_robot_meta,
+        }
+    }
+
+    /// Translate arguments into the argv of the equivalent CLI call.
+    ///
+    /// `robot_meta` adds `--robot-meta` for commands that accept it.
+    pub(crate) fn argv(
+        &self,
+        db: Option<&Path>,
+        data_dir: Option<&Path>,
+        arguments: &Map<String, Value>,
+        robot_meta: bool,
+    ) -> Result<Vec<String>, String> {
+        let schema = &self.schema;
+        if let Some(unknown) = arguments
+            .keys()
+            .find(|key| !schema.arguments.iter().any(|arg| &arg.name == *key))
+        {
+            return Err(format!(
+                "unknown argument '{unknown}' for tool '{}'",
+                schema.name
+            ));
+        }
+
+        let mut argv = vec!["cass".to_string()];
+        if let Some(db) = db {
+            argv.push(format!("--db={}", db.display()));
+        }
+        argv.extend(self.command_path.iter().cloned());
+        let mut positionals = Vec::new();
+        for arg in &schema.arguments {
+            let Some(value) = arguments.get(&arg.name).filter(|v| !v.is_null()) else {
+                continue;
+            };
+            match arg.arg_type.as_str() {
+                "flag" => match value {
+                    Value::Bool(true) => argv.push(format!("--{}", arg.name)),
+                    Value::Bool(false) => {}
+                    _ => return Err(format!("'{}' must be a boolean", arg.name)),
+                },
+                "positional" => positionals.push(scalar_arg(&arg.name, value)?),
+                _ => {
+                    let values = match value {
+                        Value::Array(items) => items.iter().collect(),
+                        single => vec![single],
+                    };
+                    for value in values {
+                        argv.push(format!("--{}={}", arg.name, scalar_arg(&arg.name, value)?));
+                    }
+                }
+            }
+        }
+        if self.accepts_data_dir
+            && let Some(data_dir) = data_dir
+        {
+            argv.push(format!("--data-dir={}", data_dir.display()));
+        }
+        argv.push("--json".to_string());
+        if robot_meta && self.accepts_robot_meta {
+            argv.push("--robot-meta".to_string());
+        }
+        if !positionals.is_empty() {
+            argv.push("--".to_string());
+            argv.extend(positionals);
+        }
+        Ok(argv)
+    }
+}
+
+/// MCP server state: the tool table plus the data dir and db every call uses.
+pub struct McpServer {
+    data_dir: Option<PathBuf>,
+    db: Option<PathBuf>,
+    tools: Vec<Tool>,
+}
+
+impl McpServer {
+    pub fn new(data_dir: Option<PathBuf>, db: Option<PathBuf>) -> Self {
+        let mut schemas = crate::build_command_schemas();
+        let tools = TOOL_COMMANDS
+            .iter()
+            .filter_map(|name| {
+                let idx = schemas.iter().position(|schema| schema.name == *name)?;
+                Some(schemas.swap_remove(idx))
+            })
+            .map(|schema| {
+                let name = schema.name.clone();
+                Tool::new(schema, &[name.as_str()])
+            })
+            .collect();
+        Self {
+            data_dir,
+            db,
+            tools,
+        }
+    }
+
+    /// Serve requests from `input` until EOF, writing one response per line.
+    pub fn serve(&self, input: impl BufRead, output: impl Write) -> std::io::Result<()> {
+        with_warm_clients(|| self.serve_lines(input, output))
+    }
+
+    fn serve_lines(&self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle_message(message),
+                Err(err) => Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, format!("parse error: {err}")),
+                )),
+            };
+            if let Some(response) = response {
+                writeln!(output, "{response}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one decoded message; notifications produce no response.
+    pub fn handle_message(&self, message: Value) -> Option<Value> {
+        let Value::Object(request) = message else {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "expected a JSON-RPC request object"),
+            ));
+        };
+        let method = request.get("method").and_then(Value::as_str);
+        let Some(id) = request.get("id").cloned() else {
+            // Notifications (`notifications/initialized`, cancellations) need no reply.
+            return None;
+        };
+        let Some(method) = method else {
+            return Some(error_response(
+                id,
+                RpcError::new(INVALID_REQUEST, "missing method"),
+            ));
+        };
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": self.tool_definitions() })),
+            "tools/call" => self.call_tool(&params),
+            other => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method not found: {other}"),
+            )),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        let version = requested
+            .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+            .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+        json!({
+            "protocolVersion": version,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "cass", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Search and read coding agent session history. Start with `search`, \
+                then use `expand` or `view` with a hit's source_path and line_number.",
+        })
+    }
+
+    /// MCP tool definitions generated from the introspection schemas.
+    pub fn tool_definitions(&self) -> Vec<Value> {
+        self.tools
+            .iter()
+            .map(|tool| {
+                let mut properties = Map::new();
+                let mut required = Vec::new();
+                for arg in &tool.schema.arguments {
+                    properties.insert(arg.name.clone(), argument_json_schema(arg));
+                    if arg.required {
+                        required.push(Value::String(arg.name.clone()));
+                    }
+                }
+                json!({
+                    "name": tool.schema.name,
+                    "description": tool.schema.description,
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                        "additionalProperties": false,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "tools/call requires a tool name"))?;
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.schema.name == name)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("unknown tool: {name}")))?;
+        let empty = Map::new();
+        let arguments = match params.get("arguments") {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(arguments)) => arguments,
+            Some(_) => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "tool arguments must be an object",
+                ));
+            }
+        };
+        let argv = self
+            .tool_argv(tool, arguments)
+            .map_err(|message| RpcError::new(INVALID_PARAMS, message))?;
+
+        let (payload, result) = run_argv(&argv);
+        Ok(match result.map(|()| payload) {
+            Ok(payload) => {
+                let text = serde_json::to_string_pretty(&payload).unwrap_or_default();
+                let mut result = json!({
+                    "content": [{ "type": "text", "text": text }],
+                    "isError": false,
+                });
+                if payload.is_object() {
+                    result["structuredContent"] = payload;
+                }
+                result
+            }
+            Err(err) => {
+                let error = json!({
+                    "error": {
+                        "code": err.code,
+                        "kind": err.kind,
+                        "message": err.message,
+                        "hint": err.hint,
+                        "retryable": err.retryable,
+                    }
+                });
+                json!({
+                    "content": [{ "type": "text", "text": error.to_string() }],
+                    "isError": true,
+                })
+            }
+        })
+    }
+
+    /// Translate tool arguments into the argv of the equivalent CLI call.
+    fn tool_argv(
+        &self,
+        tool: &Tool,
+        arguments: &Map<String, Value>,
+    ) -> Result<Vec<String>, String> {
+        tool.argv(
+            self.db.as_deref(),
+            self.data_dir.as_deref(),
+            arguments,
+            false,
+        )
+    }
+}
+
+/// Parse and run a tool argv in-process.
+///
+/// Returns whatever structured output the command emitted alongside its
+/// result: a failing command (an unhealthy `health`, say) may still have
+/// printed a payload worth returning.
+pub(crate) fn run_argv(argv: &[String]) -> (Value, CliResult<()>) {
+    match Cli::try_parse_from(argv) {
+        Ok(Cli {
+            command: Some(command),
+            db,
+            ..
+        }) => run_captured(command, db),
+        Ok(_) => (
+            Value::Null,
+            Err(CliError::unknown("tool produced no command")),
+        ),
+        Err(err) => (
+            Value::Null,
+            Err(CliError::usage(err.to_string().trim().to_string(), None)),
+        ),
+    }
+}
+
+/// Run a parsed command with its structured output captured.
+fn run_captured(command: Commands, db: Option<PathBuf>) -> (Value, CliResult<()>) {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    let result = run_tool_command(command, db);
+    let mut payloads = CAPTURED
+        .with(|captured| captured.borrow_mut().take())
+        .unwrap_or_default();
+    let payload = match payloads.len() {
+        0 => Value::Null,
+        1 => payloads.remove(0),
+        _ => Value::Array(payloads),
+    };
+    (payload, result)
+}
+
+fn run_tool_command(command: Commands, db: Option<PathBuf>) -> CliResult<()> {
+    match command {
+        search @ Commands::Search { .. } => crate::run_search_command(
+            search,
+            db,
+            WrapConfig::new(None, true),
+            ProgressResolved::None,
+            true,
+        ),
+        Commands::View {
+            path,
+            line,
+            context,
+            ..
+        } => crate::run_view(&path, line, context, true),
+        Commands::Expand {
+            path,
+            line,
+            context,
+            ..
+        } => crate::run_expand(&path, line, context, true),
+        Commands::Context {
+            path,
+            data_dir,
+            limit,
+            ..
+        } => crate::run_context(&path, &data_dir, db, true, limit),
+        Commands::Timeline {
+            since,
+            until,
+            today,
+            agent,
+            data_dir,
+            group_by,
+            source,
+            ..
+        } => crate::run_timeline(
+            since.as_deref(),
+            until.as_deref(),
+            today,
+            &agent,
+            &data_dir,
+            db,
+            true,
+            group_by,
+            source,
+        ),
+        Commands::Stats {
+            data_dir,
+            source,
+            by_source,
+            ..
+        } => crate::run_stats(&data_dir, db, true, source.as_deref(), by_source),
+        Commands::Health {
+            data_dir,
+            robot_meta,
+            stale_threshold,
+            ..
+        } => crate::run_health(&data_dir, db, true, stale_threshold, robot_meta),
+        Commands::Related {
+            file,
+            line,
+            symbol,
+            limit,
+            no_semantic,
+            data_dir,
+            ..
+        } => crate::run_related(
+            &file,
+            line,
+            symbol.as_deref(),
+            limit,
+            no_semantic,
+            &data_dir,
+            db,
+            true,
+        ),
+        Commands::Sources(crate::SourcesCommand::List { verbose, .. }) => {
+            crate::run_sources_list(verbose, true)
+        }
+        _ => Err(CliError::unknown(
+            "command is not exposed as an MCP tool or HTTP endpoint",
+        )),
+    }
+}
+
+/// JSON Schema for one introspected argument.
+pub(crate) fn argument_json_schema(arg: &ArgumentSchema) -> Value {
+    let mut schema = match (arg.arg_type.as_str(), arg.value_type.as_deref()) {
+        ("flag", _) => json!({ "type": "boolean" }),
+        (_, Some("integer")) => json!({ "type": "integer", "minimum": 0 }),
+        (_, Some("enum")) => json!({ "type": "string", "enum": arg.enum_values }),
+        _ => json!({ "type": "string" }),
+    };
+    if arg.arg_type != "flag" {
+        if arg.repeatable == Some(true) {
+            schema = json!({ "type": "array", "items": schema });
+        } else if let Some(default) = &arg.default {
+            schema["default"] = match arg.value_type.as_deref() {
+                Some("integer") => default
+                    .parse::<u64>()
+                    .map_or_else(|_| Value::String(default.clone()), Value::from),
+                _ => Value::String(default.clone()),
+            };
+        }
+    }
+    if !arg.description.is_empty() {
+        schema["description"] = Value::String(arg.description.clone());
+    }
+    schema
+}
+
+fn scalar_arg(name: &str, value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(format!("'{name}' must be a string, number or boolean")),
+    }
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server() -> McpServer {
+        McpServer::new(None, None)
+    }
+
+    fn request(method: &str, params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params })
+    }
+
+    #[test]
+    fn initialize_negotiates_protocol_version() {
+        let server = server();
+        let response = server
+            .handle_message(request(
+                "initialize",
+                json!({ "protocolVersion": "2024-11-05" }),
+            ))
+            .unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(response["result"]["serverInfo"]["name"], "cass");
+        assert!(response["result"]["capabilities"]["tools"].is_object());
+
+        let response = server
+            .handle_message(request(
+                "initialize",
+                json!({ "protocolVersion": "1999-01-01" }),
+            ))
+            .unwrap();
+        assert_eq!(
+            response["result"]["protocolVersion"],
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+    }
+
+    #[test]
+    fn tools_list_mirrors_introspect_schemas() {
+        let server = server();
+        let response = server
+            .handle_message(request("tools/list", json!({})))
+            .unwrap();
+        let tools = response["result"]["tools"].as_array().unwrap();
+        let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+        assert_eq!(names, TOOL_COMMANDS);
+
+        let search = &tools[0]["inputSchema"];
+        assert_eq!(search["required"], json!(["query"]));
+        assert_eq!(search["properties"]["limit"]["type"], "integer");
+        assert_eq!(search["properties"]["limit"]["default"], 10);
+        assert_eq!(search["properties"]["agent"]["type"], "array");
+        assert_eq!(search["properties"]["explain"]["type"], "boolean");
+        assert!(search["properties"]["mode"]["enum"].is_array());
+        for owned in SERVER_OWNED_ARGS {
+            assert!(
+                search["properties"].get(*owned).is_none(),
+                "{owned} exposed"
+            );
+        }
+
+        let expand = &tools[2]["inputSchema"];
+        let mut required: Vec<&str> = expand["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        required.sort_unstable();
+        assert_eq!(required, ["line", "path"]);
+    }
+
+    #[test]
+    fn tool_arguments_become_cli_argv() {
+        let server = McpServer::new(Some(PathBuf::from("/data")), Some(PathBuf::from("/db")));
+        let search = &server.tools[0];
+        let args = json!({ "query": "-leading dash", "agent": ["codex", "claude_code"], "limit": 3, "explain": true });
+        let argv = server.tool_argv(search, args.as_object().unwrap()).unwrap();
+        assert_eq!(
+            argv,
+            [
+                "cass",
+                "--db=/db",
+                "search",
+                "--agent=codex",
+                "--agent=claude_code",
+                "--limit=3",
+                "--explain",
+                "--data-dir=/data",
+                "--json",
+                "--",
+                "-leading dash",
+            ]
+        );
+        let cli = Cli::try_parse_from(&argv).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Search { limit: 3, .. })
+        ));
+
+        let view = &server.tools[1];
+        let argv = server
+            .tool_argv(view, json!({ "path": "/s.jsonl" }).as_object().unwrap())
+            .unwrap();
+        assert!(!argv.iter().any(|a| a.starts_with("--data-dir")));
+
+        let err = server
+            .tool_argv(
+                search,
+                json!({ "data-dir": "/elsewhere" }).as_object().unwrap(),
+            )
+            .unwrap_err();
+        assert!(err.contains("unknown argument 'data-dir'"));
+    }
+
+    #[test]
+    fn errors_follow_json_rpc_and_mcp_conventions() {
+        let server = server();
+        let response = server
+            .handle_message(request("resources/list", json!({})))
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server
+            .handle_message(request("tools/call", json!({ "name": "index" })))
+            .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        assert!(
+            server
+                .handle_message(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+                .is_none()
+        );
+
+        // Command failures are tool results, not protocol errors.
+        let response = server
+            .handle_message(request(
+                "tools/call",
+                json!({ "name": "view", "arguments": { "path": "/no/such/session.jsonl" } }),
+            ))
+            .unwrap();
+        assert_eq!(response["result"]["isError"], true);
+        let text = response["result"]["content"][0]["text"].as_str().unwrap();
+        let error: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(error["error"]["kind"], "file-not-found");
+    }
+
+    #[test]
+    fn view_tool_returns_captured_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+
+        let server = server();
+        let response = server
+            .handle_message(request(
+                "tools/call",
+                json!({ "name": "view", "arguments": { "path": file, "line": 2, "context": 0 } }),
+            ))
+            .unwrap();
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        assert!(result["structuredContent"].is_object());
+        let text = result["content"][0]["text"].as_str().unwrap();
+        assert!(text.contains("two"));
+        assert!(CAPTURED.with(|captured| captured.borrow().is_none()));
+    }
+
+    #[test]
+    fn serve_answers_each_request_line() {
+        let server = server();
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n\nnot json\n",
+            r#"{"jsonrpc":"2.0","id":"b","method":"ping"}"#,
+            "\n",
+        );
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[2]["id"], "b");
+        assert_eq!(responses[2]["result"], json!({}));
+        assert!(WARM_CLIENTS.with(|clients| clients.borrow().is_none()));
+    }
+}
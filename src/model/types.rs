@@ -102,6 +102,24 @@ pub struct Tag {
     pub name: String,
 }
 
+impl Tag {
+    /// Normalize a user-supplied tag name: trims, drops a leading `#`, lowercases.
+    ///
+    /// Returns `None` when the result is empty or contains characters other than
+    /// ASCII alphanumerics and `-`, `_`, `.`, `/` (tags must be usable as `tag:` query tokens).
+    pub fn normalize_name(raw: &str) -> Option<String> {
+        let name = raw.trim().trim_start_matches('#').to_ascii_lowercase();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+        {
+            return None;
+        }
+        Some(name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,6 +313,19 @@ mod tests {
         assert_eq!(deserialized.name, "");
     }
 
+    #[test]
+    fn tag_normalize_name() {
+        assert_eq!(Tag::normalize_name("  #Auth-Bug "), Some("auth-bug".into()));
+        assert_eq!(
+            Tag::normalize_name("infra/ci_v2.1"),
+            Some("infra/ci_v2.1".into())
+        );
+        assert_eq!(Tag::normalize_name("#"), None);
+        assert_eq!(Tag::normalize_name("two words"), None);
+        assert_eq!(Tag::normalize_name("a,b"), None);
+        assert_eq!(Tag::normalize_name("tag:x"), None);
+    }
+
     // =========================
     // Snippet Tests
     // =========================
@@ -104,6 +104,7 @@ mod tests {
                 total_compressed_size: 1024,
                 total_plaintext_size: 2048,
                 files: vec!["chunk_0".to_string()],
+                chunk_generations: Vec::new(),
             },
             key_slots: vec![],
         }
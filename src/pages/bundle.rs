@@ -222,10 +222,7 @@ impl BundleBuilder {
         progress("assets", "Copying web assets...");
 
         // Copy embedded assets to site/
-        for (name, content) in PAGES_ASSETS {
-            let dest_path = site_dir.join(name);
-            fs::write(&dest_path, content).with_context(|| format!("Failed to write {}", name))?;
-        }
+        write_site_assets(&site_dir)?;
 
         // Copy payload into site/payload/
         let (chunk_count, is_encrypted) = match archive_config.as_encrypted() {
@@ -303,10 +300,7 @@ impl BundleBuilder {
         progress("integrity", "Generating integrity manifest...");
 
         // Generate integrity.json for all files in site/
-        let integrity_manifest = generate_integrity_manifest(&site_dir)?;
-        let integrity_path = site_dir.join("integrity.json");
-        let integrity_file = File::create(&integrity_path)?;
-        serde_json::to_writer_pretty(BufWriter::new(integrity_file), &integrity_manifest)?;
+        let integrity_manifest = write_integrity_manifest(&site_dir)?;
 
         // Compute integrity fingerprint (short hash for visual verification)
         let fingerprint = compute_fingerprint(&integrity_manifest);
@@ -354,6 +348,24 @@ pub struct BundleResult {
     pub total_files: usize,
 }
 
+/// Write the embedded viewer assets into `site_dir`.
+///
+/// Files whose content already matches are left alone so that redeploying an
+/// existing site only uploads assets that changed between cass versions.
+/// Returns the names of the assets that were written.
+pub fn write_site_assets(site_dir: &Path) -> Result<Vec<&'static str>> {
+    let mut written = Vec::new();
+    for (name, content) in PAGES_ASSETS {
+        let dest_path = site_dir.join(name);
+        if fs::read(&dest_path).is_ok_and(|existing| existing == *content) {
+            continue;
+        }
+        fs::write(&dest_path, content).with_context(|| format!("Failed to write {}", name))?;
+        written.push(*name);
+    }
+    Ok(written)
+}
+
 /// Copy payload chunks from source to destination
 fn copy_payload_chunks(src_dir: &Path, dest_dir: &Path) -> Result<usize> {
     let mut count = 0;
@@ -445,6 +457,17 @@ fn generate_integrity_manifest(dir: &Path) -> Result<IntegrityManifest> {
     })
 }
 
+/// Hash every file in `site_dir` and (re)write its integrity.json.
+///
+/// Used by the bundle builder and by anything that modifies a built site
+/// afterwards (key management, incremental updates).
+pub fn write_integrity_manifest(site_dir: &Path) -> Result<IntegrityManifest> {
+    let manifest = generate_integrity_manifest(site_dir)?;
+    let file = File::create(site_dir.join("integrity.json"))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &manifest)?;
+    Ok(manifest)
+}
+
 /// Recursively collect SHA256 hashes of all files
 fn collect_file_hashes(
     base_dir: &Path,
@@ -494,7 +517,7 @@ fn collect_file_hashes(
 }
 
 /// Compute a short fingerprint from the integrity manifest
-fn compute_fingerprint(manifest: &IntegrityManifest) -> String {
+pub fn compute_fingerprint(manifest: &IntegrityManifest) -> String {
     // Compute a fingerprint by hashing the sorted list of file hashes
     let mut hasher = Sha256::new();
 
@@ -510,7 +533,7 @@ fn compute_fingerprint(manifest: &IntegrityManifest) -> String {
 }
 
 /// Write private artifacts that should never be deployed
-fn write_private_fingerprint(private_dir: &Path, fingerprint: &str) -> Result<()> {
+pub fn write_private_fingerprint(private_dir: &Path, fingerprint: &str) -> Result<()> {
     let fingerprint_content = format!(
         "Integrity Fingerprint: {}\n\n\
         Generated: {}\n\n\
@@ -230,6 +230,110 @@ impl GitHubDeployer {
             .context("Could not determine GitHub username")?;
 
         // Step 2: Check size
+        self.ensure_size_limits(bundle_dir, &mut progress)?;
+
+        // Step 3: Create or verify repository
+        progress("repo", "Creating repository...");
+        let repo_url = self.ensure_repository(username)?;
+
+        // Step 4: Clone to temp directory
+        progress("clone", "Cloning repository...");
+        let temp_dir = create_temp_dir()?;
+        clone_repo(&repo_url, &temp_dir)?;
+
+        // Step 5: Copy bundle contents
+        progress("copy", "Copying bundle files...");
+        let work_dir = temp_dir.join(&self.repo_name);
+        copy_bundle_to_repo(bundle_dir, &work_dir)?;
+
+        // Step 6: Create orphan branch and push
+        progress("push", "Pushing to gh-pages branch...");
+        let commit_sha = push_gh_pages(&work_dir)?;
+
+        // Step 7: Enable GitHub Pages
+        progress("pages", "Enabling GitHub Pages...");
+        let pages_enabled = enable_github_pages(username, &self.repo_name);
+
+        // Construct URLs
+        let pages_url = format!("https://{}.github.io/{}", username, self.repo_name);
+
+        progress("complete", "Deployment complete!");
+
+        Ok(DeployResult {
+            repo_url,
+            pages_url,
+            pages_enabled,
+            commit_sha,
+        })
+    }
+
+    /// Update an existing GitHub Pages deployment in place
+    ///
+    /// Unlike [`deploy`](Self::deploy), this requires the repository and its
+    /// gh-pages branch to exist already. The branch is cloned, the bundle is
+    /// copied over it and only the resulting diff is committed and pushed
+    /// (without force), so unchanged payload chunks are not re-uploaded.
+    pub fn update<P: AsRef<Path>>(
+        &self,
+        bundle_dir: P,
+        mut progress: impl FnMut(&str, &str),
+    ) -> Result<DeployResult> {
+        let bundle_dir = bundle_dir.as_ref();
+
+        progress("prereq", "Checking prerequisites...");
+        let prereqs = self.check_prerequisites()?;
+
+        if !prereqs.is_ready() {
+            let missing = prereqs.missing();
+            bail!("Prerequisites not met:\n{}", missing.join("\n"));
+        }
+
+        let username = prereqs
+            .gh_username
+            .as_ref()
+            .context("Could not determine GitHub username")?;
+
+        self.ensure_size_limits(bundle_dir, &mut progress)?;
+
+        let repo_full_name = format!("{}/{}", username, self.repo_name);
+        if !check_repo_exists(&repo_full_name) {
+            bail!(
+                "Repository {} does not exist. Deploy the archive first.",
+                repo_full_name
+            );
+        }
+        let repo_url = format!("https://github.com/{}", repo_full_name);
+
+        progress("clone", "Cloning gh-pages branch...");
+        let temp_dir = create_temp_dir()?;
+        clone_gh_pages(&repo_url, &temp_dir)?;
+
+        progress("copy", "Copying bundle files...");
+        let work_dir = temp_dir.join(&self.repo_name);
+        copy_bundle_to_repo(bundle_dir, &work_dir)?;
+
+        progress("push", "Pushing changes to gh-pages branch...");
+        let commit_sha = push_gh_pages_update(&work_dir)?;
+
+        let pages_enabled = enable_github_pages(username, &self.repo_name);
+        let pages_url = format!("https://{}.github.io/{}", username, self.repo_name);
+
+        progress("complete", "Update complete!");
+
+        Ok(DeployResult {
+            repo_url,
+            pages_url,
+            pages_enabled,
+            commit_sha,
+        })
+    }
+
+    /// Fail if the bundle exceeds GitHub's limits; warn about large files
+    fn ensure_size_limits(
+        &self,
+        bundle_dir: &Path,
+        progress: &mut impl FnMut(&str, &str),
+    ) -> Result<()> {
         progress("size", "Checking bundle size...");
         let size_check = self.check_size(bundle_dir)?;
 
@@ -278,39 +382,7 @@ impl GitHubDeployer {
             );
         }
 
-        // Step 3: Create or verify repository
-        progress("repo", "Creating repository...");
-        let repo_url = self.ensure_repository(username)?;
-
-        // Step 4: Clone to temp directory
-        progress("clone", "Cloning repository...");
-        let temp_dir = create_temp_dir()?;
-        clone_repo(&repo_url, &temp_dir)?;
-
-        // Step 5: Copy bundle contents
-        progress("copy", "Copying bundle files...");
-        let work_dir = temp_dir.join(&self.repo_name);
-        copy_bundle_to_repo(bundle_dir, &work_dir)?;
-
-        // Step 6: Create orphan branch and push
-        progress("push", "Pushing to gh-pages branch...");
-        let commit_sha = push_gh_pages(&work_dir)?;
-
-        // Step 7: Enable GitHub Pages
-        progress("pages", "Enabling GitHub Pages...");
-        let pages_enabled = enable_github_pages(username, &self.repo_name);
-
-        // Construct URLs
-        let pages_url = format!("https://{}.github.io/{}", username, self.repo_name);
-
-        progress("complete", "Deployment complete!");
-
-        Ok(DeployResult {
-            repo_url,
-            pages_url,
-            pages_enabled,
-            commit_sha,
-        })
+        Ok(())
     }
 
     /// Ensure repository exists, create if needed
@@ -638,6 +710,91 @@ fn push_gh_pages(repo_dir: &Path) -> Result<String> {
     Ok(commit_sha)
 }
 
+/// Shallow-clone only the gh-pages branch of an existing repository
+fn clone_gh_pages(repo_url: &str, dest: &Path) -> Result<()> {
+    retry_with_backoff("git clone", || {
+        let output = Command::new("git")
+            .args(["clone", "--depth", "1", "--branch", "gh-pages", repo_url])
+            .current_dir(dest)
+            .output()
+            .context("Failed to run git clone")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to clone gh-pages branch: {}", stderr);
+        }
+
+        Ok(())
+    })
+}
+
+/// Commit the working tree on top of gh-pages and push it (no force).
+///
+/// Returns the current HEAD unchanged if the bundle matches what is deployed.
+fn push_gh_pages_update(repo_dir: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(["add", "-A"])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to git add")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to add files: {}", stderr);
+    }
+
+    // `git diff --cached --quiet` exits 1 when there is something to commit
+    let has_changes = !Command::new("git")
+        .args(["diff", "--cached", "--quiet"])
+        .current_dir(repo_dir)
+        .status()
+        .context("Failed to run git diff")?
+        .success();
+
+    if has_changes {
+        let output = Command::new("git")
+            .args(["commit", "-m", "Update cass archive"])
+            .current_dir(repo_dir)
+            .output()
+            .context("Failed to git commit")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("Failed to commit: {}", stderr);
+        }
+    }
+
+    let sha_output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo_dir)
+        .output()
+        .context("Failed to get commit SHA")?;
+
+    let commit_sha = String::from_utf8_lossy(&sha_output.stdout)
+        .trim()
+        .to_string();
+
+    if has_changes {
+        let repo_dir_owned = repo_dir.to_owned();
+        retry_with_backoff("git push", move || {
+            let output = Command::new("git")
+                .args(["push", "origin", "gh-pages"])
+                .current_dir(&repo_dir_owned)
+                .output()
+                .context("Failed to git push")?;
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("Failed to push: {}", stderr);
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(commit_sha)
+}
+
 /// Enable GitHub Pages via API with retry logic
 fn enable_github_pages(username: &str, repo_name: &str) -> bool {
     let api_path = format!("repos/{}/{}/pages", username, repo_name);
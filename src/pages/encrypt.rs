@@ -113,6 +113,31 @@ pub struct PayloadMeta {
     pub total_compressed_size: u64,
     pub total_plaintext_size: u64,
     pub files: Vec<String>,
+    /// How many times each chunk has been re-encrypted by an incremental
+    /// update (missing entries are 0). Folded into the chunk nonce so a
+    /// rewritten chunk never reuses one; empty for never-updated archives.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunk_generations: Vec<u32>,
+}
+
+impl PayloadMeta {
+    /// Generation of the chunk at `index`.
+    pub fn chunk_generation(&self, index: usize) -> u32 {
+        self.chunk_generations.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// Result of [`DecryptionEngine::update_payload`]
+#[derive(Debug, Clone)]
+pub struct PayloadUpdate {
+    /// The config.json that was written
+    pub config: EncryptionConfig,
+    /// Chunk files (relative to the archive directory) that were re-encrypted
+    pub rewritten: Vec<String>,
+    /// Chunk files deleted because the payload shrank
+    pub removed: Vec<String>,
+    /// Number of chunks left untouched
+    pub unchanged: usize,
 }
 
 /// Full config.json structure
@@ -275,30 +300,15 @@ impl EncryptionEngine {
 
             plaintext.truncate(total_read);
 
-            // Compress the chunk
-            let mut compressed = Vec::new();
-            {
-                let mut encoder = DeflateEncoder::new(&mut compressed, Compression::default());
-                encoder.write_all(&plaintext)?;
-                encoder.finish()?;
-            }
-
-            // Derive nonce for this chunk (counter-based)
-            let nonce = derive_chunk_nonce(&self.base_nonce, chunk_index);
-
-            // Build AAD: export_id || chunk_index || schema_version
-            let aad = build_chunk_aad(&self.export_id, chunk_index);
-
-            // Encrypt with AEAD
-            let ciphertext = cipher
-                .encrypt(
-                    Nonce::from_slice(&nonce),
-                    Payload {
-                        msg: &compressed,
-                        aad: &aad,
-                    },
-                )
-                .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+            // Compress and encrypt the chunk (first generation)
+            let ciphertext = encrypt_chunk(
+                &cipher,
+                &plaintext,
+                &self.base_nonce,
+                &self.export_id,
+                chunk_index,
+                0,
+            )?;
 
             // Write chunk file
             let chunk_filename = format!("chunk-{:05}.bin", chunk_index);
@@ -330,6 +340,7 @@ impl EncryptionEngine {
                 total_compressed_size: total_compressed,
                 total_plaintext_size: input_size,
                 files: chunk_files,
+                chunk_generations: Vec::new(),
             },
             key_slots: self.key_slots.clone(),
         };
@@ -434,7 +445,11 @@ impl DecryptionEngine {
             let ciphertext = std::fs::read(&chunk_path)?;
 
             // Derive nonce
-            let nonce = derive_chunk_nonce(base_nonce.as_slice().try_into()?, chunk_index as u32);
+            let nonce = derive_chunk_nonce(
+                base_nonce.as_slice().try_into()?,
+                chunk_index as u32,
+                self.config.payload.chunk_generation(chunk_index),
+            );
 
             // Build AAD
             let aad = build_chunk_aad(export_id.as_slice().try_into()?, chunk_index as u32);
@@ -466,6 +481,187 @@ impl DecryptionEngine {
 
         Ok(())
     }
+
+    /// Re-encrypt an updated plaintext in place, rewriting only the chunks
+    /// whose plaintext differs from `previous`.
+    ///
+    /// `previous` must be the archive's current plaintext (as produced by
+    /// [`Self::decrypt_to_file`]). Every rewritten chunk gets its generation
+    /// bumped, so it is sealed under a nonce that was never used before;
+    /// chunks past the new end are deleted. Rewritten chunks are staged and
+    /// only moved into `payload/` once all of them encrypted successfully.
+    /// Writes and returns the new config.json.
+    pub fn update_payload<P: AsRef<Path>>(
+        &self,
+        encrypted_dir: P,
+        previous: P,
+        updated: P,
+        progress: impl Fn(u64, u64),
+    ) -> Result<PayloadUpdate> {
+        let encrypted_dir = encrypted_dir.as_ref();
+        let payload_dir = encrypted_dir.join("payload");
+        std::fs::create_dir_all(&payload_dir)?;
+
+        let cipher = Aes256Gcm::new_from_slice(self.dek.as_bytes()).expect("Invalid key length");
+        let base_nonce: [u8; 12] = BASE64_STANDARD
+            .decode(&self.config.base_nonce)?
+            .as_slice()
+            .try_into()?;
+        let export_id: [u8; 16] = BASE64_STANDARD
+            .decode(&self.config.export_id)?
+            .as_slice()
+            .try_into()?;
+
+        let chunk_size = self.config.payload.chunk_size;
+        let old_count = self.config.payload.chunk_count;
+        let mut generations = self.config.payload.chunk_generations.clone();
+        if generations.len() < old_count {
+            generations.resize(old_count, 0);
+        }
+
+        let input_size = std::fs::metadata(updated.as_ref())?.len();
+        let mut previous = BufReader::new(
+            File::open(previous.as_ref()).context("Failed to open previous payload")?,
+        );
+        let mut updated =
+            BufReader::new(File::open(updated.as_ref()).context("Failed to open updated payload")?);
+
+        let staging = tempfile::Builder::new()
+            .prefix(".update-")
+            .tempdir_in(&payload_dir)?;
+        let mut files = Vec::new();
+        let mut rewritten = Vec::new();
+        let mut unchanged = 0;
+        let mut total_compressed = 0u64;
+        let mut bytes_read = 0u64;
+        let mut old_plaintext = vec![0u8; chunk_size];
+
+        for chunk_index in 0..=u32::MAX {
+            let mut plaintext = vec![0u8; chunk_size];
+            let len = read_chunk(&mut updated, &mut plaintext)?;
+            if len == 0 {
+                break;
+            }
+            plaintext.truncate(len);
+            bytes_read += len as u64;
+            progress(bytes_read, input_size);
+
+            let index = chunk_index as usize;
+            let chunk_filename = format!("chunk-{:05}.bin", chunk_index);
+            let old_len = read_chunk(&mut previous, &mut old_plaintext)?;
+            if index < old_count && old_plaintext[..old_len] == plaintext[..] {
+                total_compressed += std::fs::metadata(payload_dir.join(&chunk_filename))?.len();
+                files.push(format!("payload/{}", chunk_filename));
+                unchanged += 1;
+                continue;
+            }
+
+            let generation = match generations.get_mut(index) {
+                Some(generation) => {
+                    *generation = generation
+                        .checked_add(1)
+                        .context("Chunk has been rewritten too many times")?;
+                    *generation
+                }
+                None => {
+                    generations.push(0);
+                    0
+                }
+            };
+            let ciphertext = encrypt_chunk(
+                &cipher,
+                &plaintext,
+                &base_nonce,
+                &export_id,
+                chunk_index,
+                generation,
+            )?;
+            std::fs::write(staging.path().join(&chunk_filename), &ciphertext)?;
+
+            total_compressed += ciphertext.len() as u64;
+            rewritten.push(format!("payload/{}", chunk_filename));
+            files.push(format!("payload/{}", chunk_filename));
+        }
+
+        for file in &rewritten {
+            let chunk_filename = file.trim_start_matches("payload/");
+            std::fs::rename(
+                staging.path().join(chunk_filename),
+                payload_dir.join(chunk_filename),
+            )?;
+        }
+
+        let mut removed = Vec::new();
+        for file in self.config.payload.files.iter().skip(files.len()) {
+            let path = encrypted_dir.join(file);
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            removed.push(file.clone());
+        }
+
+        let mut config = self.config.clone();
+        config.payload.chunk_count = files.len();
+        config.payload.total_compressed_size = total_compressed;
+        config.payload.total_plaintext_size = input_size;
+        config.payload.files = files;
+        config.payload.chunk_generations = generations;
+
+        let config_file = File::create(encrypted_dir.join("config.json"))?;
+        serde_json::to_writer_pretty(BufWriter::new(config_file), &config)?;
+
+        Ok(PayloadUpdate {
+            config,
+            rewritten,
+            removed,
+            unchanged,
+        })
+    }
+}
+
+/// Fill `buf` from `reader`, returning a short count only at end of input.
+fn read_chunk(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        match reader.read(&mut buf[total..])? {
+            0 => break,
+            n => total += n,
+        }
+    }
+    Ok(total)
+}
+
+/// Compress one plaintext chunk and seal it with AES-256-GCM.
+fn encrypt_chunk(
+    cipher: &Aes256Gcm,
+    plaintext: &[u8],
+    base_nonce: &[u8; 12],
+    export_id: &[u8; 16],
+    chunk_index: u32,
+    generation: u32,
+) -> Result<Vec<u8>> {
+    let mut compressed = Vec::new();
+    {
+        let mut encoder = DeflateEncoder::new(&mut compressed, Compression::default());
+        encoder.write_all(plaintext)?;
+        encoder.finish()?;
+    }
+
+    // Derive nonce for this chunk (counter-based)
+    let nonce = derive_chunk_nonce(base_nonce, chunk_index, generation);
+
+    // Build AAD: export_id || chunk_index || schema_version
+    let aad = build_chunk_aad(export_id, chunk_index);
+
+    cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &compressed,
+                aad: &aad,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))
 }
 
 /// Derive KEK from password using Argon2id
@@ -556,14 +752,19 @@ fn unwrap_key(
         .map_err(|_| anyhow::anyhow!("Invalid DEK length"))
 }
 
-/// Derive chunk nonce from base nonce and chunk index (counter mode)
+/// Derive chunk nonce from base nonce, chunk index and chunk generation
 ///
 /// Uses deterministic counter mode: the first 8 bytes come from the random
 /// base_nonce (unique per export), and the last 4 bytes are the chunk index.
 /// This ensures unique nonces for up to 2^32 chunks per export without
-/// collision risk.
-fn derive_chunk_nonce(base_nonce: &[u8; 12], chunk_index: u32) -> [u8; 12] {
+/// collision risk. Bytes 4..8 are XORed with the chunk's generation, so a
+/// chunk re-encrypted by an incremental update gets a fresh nonce too
+/// (generation 0 leaves the base nonce untouched).
+fn derive_chunk_nonce(base_nonce: &[u8; 12], chunk_index: u32, generation: u32) -> [u8; 12] {
     let mut nonce = *base_nonce;
+    for (byte, generation_byte) in nonce[4..8].iter_mut().zip(generation.to_be_bytes()) {
+        *byte ^= generation_byte;
+    }
     // Set the last 4 bytes to the chunk index (big-endian)
     // This is safer than XOR as it guarantees unique nonces for each chunk
     nonce[8..12].copy_from_slice(&chunk_index.to_be_bytes());
@@ -652,14 +853,22 @@ mod tests {
     fn test_chunk_nonce_derivation() {
         let base = [0u8; 12];
 
-        let n0 = derive_chunk_nonce(&base, 0);
-        let n1 = derive_chunk_nonce(&base, 1);
-        let n2 = derive_chunk_nonce(&base, 2);
+        let n0 = derive_chunk_nonce(&base, 0, 0);
+        let n1 = derive_chunk_nonce(&base, 1, 0);
+        let n2 = derive_chunk_nonce(&base, 2, 0);
 
         // Each chunk should have unique nonce
         assert_ne!(n0, n1);
         assert_ne!(n1, n2);
         assert_ne!(n0, n2);
+
+        // Rewriting a chunk changes its nonce; generation 0 is the plain counter
+        assert_ne!(derive_chunk_nonce(&base, 1, 1), n1);
+        assert_ne!(
+            derive_chunk_nonce(&base, 1, 1),
+            derive_chunk_nonce(&base, 1, 2)
+        );
+        assert_eq!(&n1[8..], &1u32.to_be_bytes());
     }
 
     #[test]
@@ -696,6 +905,80 @@ mod tests {
         assert_eq!(decrypted, test_data);
     }
 
+    #[test]
+    fn test_update_payload_rewrites_only_changed_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let original_path = temp_dir.path().join("original.bin");
+        let updated_path = temp_dir.path().join("updated.bin");
+        let decrypted_path = temp_dir.path().join("decrypted.bin");
+        let output_dir = temp_dir.path().join("encrypted");
+
+        // Four 1 KiB chunks with distinct contents
+        let original: Vec<u8> = (0..4096u32).map(|i| (i / 7) as u8).collect();
+        std::fs::write(&original_path, &original).unwrap();
+
+        let mut engine = EncryptionEngine::new(1024);
+        engine.add_password_slot("test-password").unwrap();
+        let config = engine
+            .encrypt_file(&original_path, &output_dir, |_, _| {})
+            .unwrap();
+        assert_eq!(config.payload.chunk_count, 4);
+        let chunk0 = std::fs::read(output_dir.join("payload/chunk-00000.bin")).unwrap();
+        let chunk2 = std::fs::read(output_dir.join("payload/chunk-00002.bin")).unwrap();
+
+        // Change chunk 2 and grow into a fifth chunk
+        let mut updated = original.clone();
+        updated[2100] ^= 0xff;
+        updated.extend_from_slice(b"appended conversation");
+        std::fs::write(&updated_path, &updated).unwrap();
+
+        let decryptor = DecryptionEngine::unlock_with_password(config, "test-password").unwrap();
+        let update = decryptor
+            .update_payload(&output_dir, &original_path, &updated_path, |_, _| {})
+            .unwrap();
+        assert_eq!(
+            update.rewritten,
+            vec!["payload/chunk-00002.bin", "payload/chunk-00004.bin"]
+        );
+        assert_eq!(update.unchanged, 3);
+        assert!(update.removed.is_empty());
+        assert_eq!(update.config.payload.chunk_count, 5);
+        assert_eq!(update.config.payload.chunk_generations, vec![0, 0, 1, 0, 0]);
+        assert_eq!(
+            std::fs::read(output_dir.join("payload/chunk-00000.bin")).unwrap(),
+            chunk0
+        );
+        assert_ne!(
+            std::fs::read(output_dir.join("payload/chunk-00002.bin")).unwrap(),
+            chunk2
+        );
+
+        // The config on disk decrypts to the updated plaintext
+        let config = load_config(&output_dir).unwrap();
+        let decryptor = DecryptionEngine::unlock_with_password(config, "test-password").unwrap();
+        decryptor
+            .decrypt_to_file(&output_dir, &decrypted_path, |_, _| {})
+            .unwrap();
+        assert_eq!(std::fs::read(&decrypted_path).unwrap(), updated);
+
+        // Shrinking drops trailing chunks but keeps their generations
+        std::fs::write(&original_path, &original[..1500]).unwrap();
+        let update = decryptor
+            .update_payload(&output_dir, &updated_path, &original_path, |_, _| {})
+            .unwrap();
+        assert_eq!(update.rewritten, vec!["payload/chunk-00001.bin"]);
+        assert_eq!(
+            update.removed,
+            vec![
+                "payload/chunk-00002.bin",
+                "payload/chunk-00003.bin",
+                "payload/chunk-00004.bin"
+            ]
+        );
+        assert_eq!(update.config.payload.chunk_generations, vec![0, 1, 1, 0, 0]);
+        assert!(!output_dir.join("payload/chunk-00002.bin").exists());
+    }
+
     #[test]
     fn test_multiple_key_slots() {
         let temp_dir = TempDir::new().unwrap();
@@ -2,14 +2,21 @@ use crate::ui::time_parser::parse_time_input;
 use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
 use clap::ValueEnum;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-#[derive(Debug, Clone)]
+/// `export_meta` key holding the [`ExportFilter`] the archive was built with (JSON).
+pub const META_FILTER: &str = "filter";
+
+/// `export_meta` key holding the newest conversation `started_at` (ms) in the archive.
+pub const META_HIGH_WATER_MARK: &str = "high_water_mark";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportFilter {
     pub agents: Option<Vec<String>>,
     pub workspaces: Option<Vec<PathBuf>>,
@@ -18,7 +25,8 @@ pub struct ExportFilter {
     pub path_mode: PathMode,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PathMode {
     Relative,
     Basename,
@@ -26,6 +34,65 @@ pub enum PathMode {
     Hash,
 }
 
+impl ExportFilter {
+    /// The filter recorded in an export database, if it was written by a
+    /// version of cass that records one.
+    pub fn recorded(export_db: &Path) -> Result<Option<Self>> {
+        let conn =
+            Connection::open_with_flags(export_db, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .context("Failed to open archive database")?;
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM export_meta WHERE key = ?",
+                params![META_FILTER],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        value
+            .map(|json| serde_json::from_str(&json).context("Invalid filter in export_meta"))
+            .transpose()
+    }
+}
+
+/// How [`ExportEngine::copy_conversations`] treats the destination.
+#[derive(Debug, Clone, Copy)]
+enum CopyMode {
+    /// Empty database: keep source conversation IDs.
+    Fresh,
+    /// Existing archive: add missing conversations and the new messages of
+    /// ones already archived.
+    Append,
+}
+
+/// The `started_at` (ms) of the newest conversation an export database covers.
+pub fn recorded_high_water_mark(export_db: &Path) -> Result<Option<i64>> {
+    let conn = Connection::open_with_flags(export_db, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .context("Failed to open archive database")?;
+    read_high_water_mark(&conn)
+}
+
+/// The archive's high-water mark, falling back to its newest conversation
+/// for exports that predate the `high_water_mark` key.
+fn read_high_water_mark(conn: &Connection) -> Result<Option<i64>> {
+    let recorded: Option<String> = conn
+        .query_row(
+            "SELECT value FROM export_meta WHERE key = ?",
+            params![META_HIGH_WATER_MARK],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+    if let Some(mark) = recorded.and_then(|value| value.parse::<i64>().ok()) {
+        return Ok(Some(mark));
+    }
+    Ok(
+        conn.query_row("SELECT MAX(started_at) FROM conversations", [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
 pub struct ExportEngine {
     source_db_path: PathBuf,
     output_path: PathBuf,
@@ -35,6 +102,8 @@ pub struct ExportEngine {
 pub struct ExportStats {
     pub conversations_processed: usize,
     pub messages_processed: usize,
+    /// Archived conversations that received new messages (append only)
+    pub conversations_extended: usize,
 }
 
 impl ExportEngine {
@@ -150,7 +219,89 @@ impl ExportEngine {
         )
         .context("Failed to create messages_code_fts table")?;
 
-        // 4. Query Source
+        // 4. Copy matching conversations
+        let stats = self.copy_conversations(&src, &tx, CopyMode::Fresh, progress, running)?;
+
+        // Metadata
+        tx.execute(
+            "INSERT INTO export_meta (key, value) VALUES ('schema_version', '1')",
+            [],
+        )?;
+        tx.execute(
+            "INSERT INTO export_meta (key, value) VALUES ('exported_at', ?)",
+            params![Utc::now().to_rfc3339()],
+        )?;
+        self.record_update_meta(&tx)?;
+
+        tx.commit()?;
+
+        Ok(stats)
+    }
+
+    /// Bring the existing export database at `output_path` up to date.
+    ///
+    /// Conversations not yet in the archive are added with fresh IDs, so a
+    /// source reindex that renumbered conversations cannot collide with rows
+    /// already in the archive. For conversations already present (same agent,
+    /// path and start time), messages past the last archived `idx` are
+    /// appended; archived messages are never rewritten.
+    pub fn append<F>(&self, progress: F, running: Option<Arc<AtomicBool>>) -> Result<ExportStats>
+    where
+        F: Fn(usize, usize),
+    {
+        if !self.output_path.is_file() {
+            bail!("archive database not found: {}", self.output_path.display());
+        }
+
+        let src = Connection::open_with_flags(
+            &self.source_db_path,
+            rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .context("Failed to open source database")?;
+        src.busy_timeout(Duration::from_secs(5))?;
+
+        let mut dest =
+            Connection::open(&self.output_path).context("Failed to open archive database")?;
+        let tx = dest.transaction()?;
+
+        let stats = self.copy_conversations(&src, &tx, CopyMode::Append, progress, running)?;
+
+        tx.execute(
+            "INSERT OR REPLACE INTO export_meta (key, value) VALUES ('updated_at', ?)",
+            params![Utc::now().to_rfc3339()],
+        )?;
+        self.record_update_meta(&tx)?;
+
+        tx.commit()?;
+
+        Ok(stats)
+    }
+
+    /// Record the filter (first export only) and the new high-water mark.
+    fn record_update_meta(&self, tx: &Connection) -> Result<()> {
+        tx.execute(
+            "INSERT OR IGNORE INTO export_meta (key, value) VALUES (?, ?)",
+            params![META_FILTER, serde_json::to_string(&self.filter)?],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO export_meta (key, value)
+             VALUES (?, (SELECT MAX(started_at) FROM conversations))",
+            params![META_HIGH_WATER_MARK],
+        )?;
+        Ok(())
+    }
+
+    fn copy_conversations<F>(
+        &self,
+        src: &Connection,
+        tx: &Connection,
+        mode: CopyMode,
+        progress: F,
+        running: Option<Arc<AtomicBool>>,
+    ) -> Result<ExportStats>
+    where
+        F: Fn(usize, usize),
+    {
         let mut query = String::from(
             "SELECT c.id, a.slug as agent, w.path as workspace, c.title, c.source_path, c.started_at, c.ended_at, 
              (SELECT COUNT(*) FROM messages m WHERE m.conversation_id = c.id) as message_count, 
@@ -218,13 +369,15 @@ impl ExportEngine {
         let mut stmt = src.prepare(&query)?;
         let mut rows = stmt.query(rusqlite::params_from_iter(params.iter()))?;
 
+        let mut seen = 0;
         let mut processed = 0;
+        let mut extended = 0;
         let mut msg_processed = 0;
 
         let mut msg_stmt = src.prepare(
             "SELECT role, content, created_at, idx 
              FROM messages 
-             WHERE conversation_id = ? 
+             WHERE conversation_id = ? AND idx > ?
              ORDER BY idx ASC",
         )?;
 
@@ -244,6 +397,23 @@ impl ExportEngine {
         let mut insert_code_fts =
             tx.prepare("INSERT INTO messages_code_fts (rowid, content) VALUES (?, ?)")?;
 
+        let (mut existing, mut extend_conv) = match mode {
+            CopyMode::Fresh => (None, None),
+            CopyMode::Append => (
+                Some(tx.prepare(
+                    "SELECT c.id, (SELECT MAX(m.idx) FROM messages m WHERE m.conversation_id = c.id)
+                     FROM conversations c
+                     WHERE c.agent = ? AND c.source_path = ? AND c.started_at IS ?
+                     LIMIT 1",
+                )?),
+                Some(tx.prepare(
+                    "UPDATE conversations
+                     SET message_count = COALESCE(message_count, 0) + ?, ended_at = ?, title = ?
+                     WHERE id = ?",
+                )?),
+            ),
+        };
+
         while let Some(row) = rows.next()? {
             if let Some(r) = &running
                 && !r.load(Ordering::Relaxed)
@@ -264,61 +434,72 @@ impl ExportEngine {
             // Transform Path
             let transformed_path = self.transform_path(&source_path, &workspace);
 
-            insert_conv.execute(params![
-                id,
-                agent,
-                workspace,
-                title,
-                transformed_path,
-                started_at,
-                ended_at,
-                message_count,
-                metadata_json
-            ])?;
+            seen += 1;
+            progress(seen, total_convs);
+
+            let archived: Option<(i64, Option<i64>)> = match existing.as_mut() {
+                Some(existing) => existing
+                    .query_row(params![agent, transformed_path, started_at], |row| {
+                        Ok((row.get(0)?, row.get(1)?))
+                    })
+                    .optional()?,
+                None => None,
+            };
+
+            let (conv_id, after_idx) = match archived {
+                // Already archived: only messages past the last archived one
+                Some((conv_id, last_idx)) => (conv_id, last_idx.unwrap_or(-1)),
+                None => {
+                    // Appended rows get a fresh ID; a fresh export keeps the source ID
+                    let keep_id = matches!(mode, CopyMode::Fresh).then_some(id);
+                    let conv_id = insert_conv.insert(params![
+                        keep_id,
+                        agent,
+                        workspace,
+                        title,
+                        transformed_path,
+                        started_at,
+                        ended_at,
+                        message_count,
+                        metadata_json
+                    ])?;
+                    processed += 1;
+                    (conv_id, -1)
+                }
+            };
 
             // Fetch messages
-            let mut msg_rows = msg_stmt.query(params![id])?;
+            let mut added = 0;
+            let mut msg_rows = msg_stmt.query(params![id, after_idx])?;
             while let Some(msg_row) = msg_rows.next()? {
                 let role: String = msg_row.get(0)?;
                 let content: String = msg_row.get(1)?;
                 let created_at: Option<i64> = msg_row.get(2)?;
                 let idx: i64 = msg_row.get(3)?;
 
-                let msg_id = insert_msg.insert(params![id, idx, role, content, created_at])?;
+                let msg_id = insert_msg.insert(params![conv_id, idx, role, content, created_at])?;
 
                 // Populate FTS
                 insert_fts.execute(params![msg_id, content])?;
                 insert_code_fts.execute(params![msg_id, content])?;
 
-                msg_processed += 1;
+                added += 1;
             }
+            msg_processed += added;
 
-            processed += 1;
-            progress(processed, total_convs);
+            if archived.is_some()
+                && added > 0
+                && let Some(extend_conv) = extend_conv.as_mut()
+            {
+                extend_conv.execute(params![added as i64, ended_at, title, conv_id])?;
+                extended += 1;
+            }
         }
 
-        // Metadata
-        tx.execute(
-            "INSERT INTO export_meta (key, value) VALUES ('schema_version', '1')",
-            [],
-        )?;
-        tx.execute(
-            "INSERT INTO export_meta (key, value) VALUES ('exported_at', ?)",
-            params![Utc::now().to_rfc3339()],
-        )?;
-
-        drop(insert_conv);
-        drop(insert_msg);
-        drop(insert_fts);
-        drop(insert_code_fts);
-        // drop(msg_stmt); // Removed: Let Rust handle drop order
-        // drop(stmt);     // Removed: Let Rust handle drop order
-
-        tx.commit()?;
-
         Ok(ExportStats {
             conversations_processed: processed,
             messages_processed: msg_processed,
+            conversations_extended: extended,
         })
     }
 
@@ -790,6 +971,7 @@ mod tests {
         let stats = ExportStats {
             conversations_processed: 0,
             messages_processed: 0,
+            conversations_extended: 0,
         };
 
         assert_eq!(stats.conversations_processed, 0);
@@ -801,6 +983,7 @@ mod tests {
         let stats = ExportStats {
             conversations_processed: 100,
             messages_processed: 5000,
+            conversations_extended: 0,
         };
 
         assert_eq!(stats.conversations_processed, 100);
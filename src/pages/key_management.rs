@@ -14,11 +14,15 @@
 //! - KEK is derived from password (Argon2id) or recovery secret (HKDF-SHA256)
 //! - Add/revoke only modifies config.json; payload unchanged
 //! - Rotate re-encrypts entire payload with new DEK
+//! - Every operation regenerates integrity.json and, for bundles that have one,
+//!   private/integrity-fingerprint.txt
 
+use crate::pages::bundle::write_integrity_manifest;
 use crate::pages::encrypt::{
     Argon2Params, EncryptionConfig, KdfAlgorithm, KeySlot, SlotType, load_config,
 };
 use crate::pages::qr::RecoverySecret;
+use crate::pages::update::refresh_private_fingerprint;
 use aes_gcm::{
     Aes256Gcm, Nonce,
     aead::{Aead, KeyInit, Payload},
@@ -33,7 +37,7 @@ use rand::{RngCore, rngs::OsRng};
 use serde::Serialize;
 use sha2::Sha256;
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufWriter, Read, Write};
 use std::path::Path;
 use tracing::info;
 use zeroize::Zeroize;
@@ -152,7 +156,7 @@ pub fn key_add_password(
     serde_json::to_writer_pretty(BufWriter::new(file), &config)?;
 
     // Update integrity.json if present
-    update_integrity_hash(archive_dir, "config.json")?;
+    update_integrity_manifest(archive_dir)?;
 
     info!(slot_id, "Added password key slot");
     Ok(slot_id)
@@ -192,7 +196,7 @@ pub fn key_add_recovery(
     serde_json::to_writer_pretty(BufWriter::new(file), &config)?;
 
     // Update integrity.json if present
-    update_integrity_hash(archive_dir, "config.json")?;
+    update_integrity_manifest(archive_dir)?;
 
     info!(slot_id, "Added recovery key slot");
     Ok((slot_id, secret))
@@ -239,7 +243,7 @@ pub fn key_revoke(
     serde_json::to_writer_pretty(BufWriter::new(file), &config)?;
 
     // Update integrity.json if present
-    update_integrity_hash(archive_dir, "config.json")?;
+    update_integrity_manifest(archive_dir)?;
 
     info!(slot_id = slot_id_to_revoke, "Revoked key slot");
     Ok(RevokeResult {
@@ -317,6 +321,7 @@ pub fn key_rotate(
             files: (0..chunk_count)
                 .map(|i| format!("payload/chunk-{:05}.bin", i))
                 .collect(),
+            chunk_generations: Vec::new(),
         },
         key_slots: new_slots.clone(),
     };
@@ -324,8 +329,9 @@ pub fn key_rotate(
     let file = File::create(&config_path)?;
     serde_json::to_writer_pretty(BufWriter::new(file), &new_config)?;
 
-    // 6. Regenerate integrity.json
-    regenerate_integrity_manifest(archive_dir)?;
+    // 6. Regenerate integrity.json and the private fingerprint
+    let manifest = write_integrity_manifest(archive_dir)?;
+    refresh_private_fingerprint(archive_dir, &manifest)?;
 
     // 7. Zeroize sensitive key material
     old_dek.zeroize();
@@ -571,7 +577,11 @@ fn decrypt_all_chunks(
         let ciphertext = std::fs::read(&chunk_path)?;
 
         // Derive nonce
-        let nonce = derive_chunk_nonce(&base_nonce, chunk_index as u32);
+        let nonce = derive_chunk_nonce(
+            &base_nonce,
+            chunk_index as u32,
+            config.payload.chunk_generation(chunk_index),
+        );
 
         // Build AAD
         let aad = build_chunk_aad(&export_id, chunk_index as u32);
@@ -626,8 +636,8 @@ fn encrypt_all_chunks(
             encoder.finish()?;
         }
 
-        // Derive nonce
-        let nonce = derive_chunk_nonce(base_nonce, chunk_index);
+        // Derive nonce (fresh DEK and base nonce, so every chunk starts over)
+        let nonce = derive_chunk_nonce(base_nonce, chunk_index, 0);
 
         // Build AAD
         let aad = build_chunk_aad(export_id, chunk_index);
@@ -662,10 +672,14 @@ fn encrypt_all_chunks(
     Ok(chunk_index as usize)
 }
 
-/// Derive chunk nonce from base nonce and chunk index
-fn derive_chunk_nonce(base_nonce: &[u8], chunk_index: u32) -> [u8; 12] {
+/// Derive chunk nonce from base nonce, chunk index and chunk generation
+/// (see `encrypt::derive_chunk_nonce`)
+fn derive_chunk_nonce(base_nonce: &[u8], chunk_index: u32, generation: u32) -> [u8; 12] {
     let mut nonce = [0u8; 12];
     nonce[..base_nonce.len().min(12)].copy_from_slice(&base_nonce[..base_nonce.len().min(12)]);
+    for (byte, generation_byte) in nonce[4..8].iter_mut().zip(generation.to_be_bytes()) {
+        *byte ^= generation_byte;
+    }
     // Set the last 4 bytes to the chunk index (big-endian)
     nonce[8..12].copy_from_slice(&chunk_index.to_be_bytes());
     nonce
@@ -680,88 +694,17 @@ fn build_chunk_aad(export_id: &[u8], chunk_index: u32) -> Vec<u8> {
     aad
 }
 
-/// Update integrity.json for a single file
-fn update_integrity_hash(archive_dir: &Path, filename: &str) -> Result<()> {
-    let integrity_path = archive_dir.join("integrity.json");
-    if !integrity_path.exists() {
+/// Rewrite integrity.json (and the private fingerprint) after config.json
+/// changed, if the archive has one
+fn update_integrity_manifest(archive_dir: &Path) -> Result<()> {
+    if !archive_dir.join("integrity.json").exists() {
         return Ok(());
     }
-
-    // Read current integrity.json
-    let file = File::open(&integrity_path)?;
-    let mut integrity: serde_json::Value = serde_json::from_reader(BufReader::new(file))?;
-
-    // Calculate new hash for file
-    let file_path = archive_dir.join(filename);
-    let content = std::fs::read(&file_path)?;
-    let hash = sha256_hex(&content);
-
-    // Update hash in integrity
-    if let Some(files) = integrity.get_mut("files").and_then(|f| f.as_object_mut()) {
-        files.insert(filename.to_string(), serde_json::json!(hash));
-    }
-
-    // Write updated integrity.json
-    let file = File::create(&integrity_path)?;
-    serde_json::to_writer_pretty(BufWriter::new(file), &integrity)?;
-
+    let manifest = write_integrity_manifest(archive_dir)?;
+    refresh_private_fingerprint(archive_dir, &manifest)?;
     Ok(())
 }
 
-/// Regenerate entire integrity.json
-fn regenerate_integrity_manifest(archive_dir: &Path) -> Result<()> {
-    let integrity_path = archive_dir.join("integrity.json");
-
-    // Find all files to hash
-    let mut files_map = serde_json::Map::new();
-
-    // Hash config.json
-    let config_path = archive_dir.join("config.json");
-    if config_path.exists() {
-        let content = std::fs::read(&config_path)?;
-        files_map.insert(
-            "config.json".to_string(),
-            serde_json::json!(sha256_hex(&content)),
-        );
-    }
-
-    // Hash all payload chunks
-    let payload_dir = archive_dir.join("payload");
-    if payload_dir.exists() {
-        for entry in std::fs::read_dir(&payload_dir)? {
-            let entry = entry?;
-            if entry.file_type()?.is_file() {
-                let filename = entry.file_name().to_string_lossy().to_string();
-                let content = std::fs::read(entry.path())?;
-                files_map.insert(
-                    format!("payload/{}", filename),
-                    serde_json::json!(sha256_hex(&content)),
-                );
-            }
-        }
-    }
-
-    let integrity = serde_json::json!({
-        "version": 1,
-        "algorithm": "sha256",
-        "files": files_map,
-        "generated_at": Utc::now().to_rfc3339(),
-    });
-
-    let file = File::create(&integrity_path)?;
-    serde_json::to_writer_pretty(BufWriter::new(file), &integrity)?;
-
-    Ok(())
-}
-
-/// Calculate SHA-256 hash as hex string
-fn sha256_hex(data: &[u8]) -> String {
-    use sha2::{Digest, Sha256};
-    let mut hasher = Sha256::new();
-    hasher.update(data);
-    hex::encode(hasher.finalize())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -859,6 +802,39 @@ mod tests {
         assert!(unwrap_dek_with_password(&config, "second-password").is_ok());
     }
 
+    #[test]
+    fn test_key_changes_refresh_private_fingerprint() {
+        use crate::pages::bundle::{
+            IntegrityManifest, compute_fingerprint, write_private_fingerprint,
+        };
+
+        let (temp_dir, archive_dir) = setup_test_archive();
+        let private_dir = temp_dir.path().join("private");
+        std::fs::create_dir_all(&private_dir).unwrap();
+        let original = compute_fingerprint(&write_integrity_manifest(&archive_dir).unwrap());
+        write_private_fingerprint(&private_dir, &original).unwrap();
+
+        let current = || {
+            let manifest: IntegrityManifest = serde_json::from_str(
+                &std::fs::read_to_string(archive_dir.join("integrity.json")).unwrap(),
+            )
+            .unwrap();
+            let recorded =
+                std::fs::read_to_string(private_dir.join("integrity-fingerprint.txt")).unwrap();
+            (compute_fingerprint(&manifest), recorded)
+        };
+
+        key_add_password(&archive_dir, "test-password", "second-password").unwrap();
+        let (after_add, recorded) = current();
+        assert_ne!(after_add, original);
+        assert!(recorded.contains(&after_add), "{recorded}");
+
+        key_revoke(&archive_dir, "second-password", 0).unwrap();
+        let (after_revoke, recorded) = current();
+        assert_ne!(after_revoke, after_add);
+        assert!(recorded.contains(&after_revoke), "{recorded}");
+    }
+
     #[test]
     fn test_key_revoke_last_slot_fails() {
         let (_temp_dir, archive_dir) = setup_test_archive();
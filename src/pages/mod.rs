@@ -21,5 +21,6 @@ pub mod redact;
 pub mod secret_scan;
 pub mod size;
 pub mod summary;
+pub mod update;
 pub mod verify;
 pub mod wizard;
@@ -0,0 +1,245 @@
+//! Incremental updates for existing pages archives.
+//!
+//! `cass pages --update DIR` brings a previously exported archive up to date
+//! without a full re-export:
+//! - Only conversations started at or after the archive's recorded
+//!   high-water mark are appended, using the filter recorded at export time
+//! - Only payload chunks whose plaintext changed are re-encrypted (with a
+//!   bumped per-chunk generation so no nonce is reused under the same DEK)
+//! - Pre-computed analytics (if the site has them) and integrity.json are
+//!   regenerated so the site verifies afterwards
+//!
+//! Unchanged files are left byte-for-byte identical, so deployers that diff
+//! against what is already published only upload what changed.
+
+use anyhow::{Context, Result, bail};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use super::analytics::AnalyticsGenerator;
+use super::archive_config::ArchiveConfig;
+use super::bundle::{
+    IntegrityManifest, compute_fingerprint, write_integrity_manifest, write_private_fingerprint,
+    write_site_assets,
+};
+use super::encrypt::DecryptionEngine;
+use super::export::{ExportEngine, ExportFilter, recorded_high_water_mark};
+
+/// Summary of an incremental archive update
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateReport {
+    /// The site/ directory that was updated
+    pub site_dir: PathBuf,
+    /// Whether the archive payload is encrypted
+    pub encrypted: bool,
+    /// Conversations appended to the archive
+    pub conversations_added: usize,
+    /// Archived conversations that received new messages
+    pub conversations_extended: usize,
+    /// Messages appended to the archive
+    pub messages_added: usize,
+    /// `started_at` (ms) of the newest archived conversation after the update
+    pub high_water_mark: Option<i64>,
+    /// Payload files that were rewritten (relative to site/)
+    pub chunks_rewritten: Vec<String>,
+    /// Payload files deleted because the payload shrank
+    pub chunks_removed: Vec<String>,
+    /// Payload chunks left untouched
+    pub chunks_unchanged: usize,
+    /// Whether site/data/*.json analytics were regenerated
+    pub analytics_refreshed: bool,
+    /// Viewer assets rewritten because they differed from this cass version
+    pub assets_refreshed: Vec<String>,
+    /// Integrity fingerprint after the update (`None` if nothing was added)
+    pub fingerprint: Option<String>,
+}
+
+impl UpdateReport {
+    /// True if the archive already contained everything the filter selects
+    pub fn is_up_to_date(&self) -> bool {
+        self.conversations_added == 0 && self.messages_added == 0
+    }
+}
+
+/// Append new conversations, and new messages in archived ones, from
+/// `source_db` to the archive in `site_dir`.
+///
+/// `password` is required for encrypted archives. `fallback_filter` is used
+/// only for archives exported before the filter was recorded in the payload.
+pub fn update_archive(
+    site_dir: &Path,
+    source_db: &Path,
+    password: Option<&str>,
+    fallback_filter: ExportFilter,
+    progress: impl Fn(&str, &str),
+) -> Result<UpdateReport> {
+    let config_path = site_dir.join("config.json");
+    let archive_config: ArchiveConfig = {
+        let file = File::open(&config_path).context("Failed to open config.json")?;
+        serde_json::from_reader(BufReader::new(file)).context("Invalid config.json")?
+    };
+
+    let work_dir = tempfile::tempdir().context("Failed to create temporary directory")?;
+    let previous_db = work_dir.path().join("previous.db");
+    let updated_db = work_dir.path().join("updated.db");
+
+    let mut report = UpdateReport {
+        site_dir: site_dir.to_path_buf(),
+        encrypted: archive_config.is_encrypted(),
+        conversations_added: 0,
+        conversations_extended: 0,
+        messages_added: 0,
+        high_water_mark: None,
+        chunks_rewritten: Vec::new(),
+        chunks_removed: Vec::new(),
+        chunks_unchanged: 0,
+        analytics_refreshed: false,
+        assets_refreshed: Vec::new(),
+        fingerprint: None,
+    };
+
+    match archive_config {
+        ArchiveConfig::Encrypted(config) => {
+            let Some(password) = password else {
+                bail!("A password is required to update an encrypted archive");
+            };
+            progress("unlock", "Unlocking archive...");
+            let engine = DecryptionEngine::unlock_with_password(config, password)?;
+
+            progress("decrypt", "Decrypting current payload...");
+            engine.decrypt_to_file(site_dir, previous_db.as_path(), |_, _| {})?;
+            fs::copy(&previous_db, &updated_db)?;
+
+            if !append_new_conversations(&updated_db, source_db, fallback_filter, &mut report)? {
+                return Ok(report);
+            }
+
+            progress("encrypt", "Re-encrypting changed chunks...");
+            let update = engine.update_payload(
+                site_dir,
+                previous_db.as_path(),
+                updated_db.as_path(),
+                |_, _| {},
+            )?;
+            report.chunks_rewritten = update.rewritten;
+            report.chunks_removed = update.removed;
+            report.chunks_unchanged = update.unchanged;
+        }
+        ArchiveConfig::Unencrypted(mut config) => {
+            let payload_path = site_dir.join(&config.payload.path);
+            fs::copy(&payload_path, &updated_db)
+                .with_context(|| format!("Failed to read payload {}", payload_path.display()))?;
+
+            if !append_new_conversations(&updated_db, source_db, fallback_filter, &mut report)? {
+                return Ok(report);
+            }
+
+            progress("payload", "Writing updated payload...");
+            report.chunks_rewritten.push(config.payload.path.clone());
+            fs::copy(&updated_db, &payload_path)?;
+            config.payload.size_bytes = Some(fs::metadata(&payload_path)?.len());
+            let file = File::create(&config_path)?;
+            serde_json::to_writer_pretty(
+                BufWriter::new(file),
+                &ArchiveConfig::Unencrypted(config),
+            )?;
+        }
+    }
+
+    // The viewer reads pre-computed analytics in plaintext, so only refresh
+    // them for sites that were published with them.
+    let data_dir = site_dir.join("data");
+    if data_dir.join("statistics.json").is_file() {
+        progress("analytics", "Refreshing analytics...");
+        let conn =
+            Connection::open_with_flags(&updated_db, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        AnalyticsGenerator::new(&conn)
+            .generate_all()?
+            .write_to_dir(&data_dir)?;
+        report.analytics_refreshed = true;
+    }
+
+    // Chunk generations need a viewer that understands them.
+    report.assets_refreshed = write_site_assets(site_dir)?
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    progress("integrity", "Regenerating integrity manifest...");
+    let manifest = write_integrity_manifest(site_dir)?;
+    report.fingerprint = Some(refresh_private_fingerprint(site_dir, &manifest)?);
+
+    info!(
+        conversations = report.conversations_added,
+        extended = report.conversations_extended,
+        rewritten = report.chunks_rewritten.len(),
+        unchanged = report.chunks_unchanged,
+        "Updated pages archive"
+    );
+    Ok(report)
+}
+
+/// Append to `export_db` in place. Returns false if nothing was new.
+fn append_new_conversations(
+    export_db: &Path,
+    source_db: &Path,
+    fallback_filter: ExportFilter,
+    report: &mut UpdateReport,
+) -> Result<bool> {
+    let filter = ExportFilter::recorded(export_db)?.unwrap_or(fallback_filter);
+    let stats = ExportEngine::new(source_db, export_db, filter).append(|_, _| {}, None)?;
+    report.conversations_added = stats.conversations_processed;
+    report.conversations_extended = stats.conversations_extended;
+    report.messages_added = stats.messages_processed;
+    report.high_water_mark = recorded_high_water_mark(export_db)?;
+    Ok(!report.is_up_to_date())
+}
+
+/// Fingerprint of a regenerated integrity manifest, also written to the bundle's
+/// private/integrity-fingerprint.txt when the export created one.
+pub(crate) fn refresh_private_fingerprint(
+    site_dir: &Path,
+    manifest: &IntegrityManifest,
+) -> Result<String> {
+    let fingerprint = compute_fingerprint(manifest);
+    if let Some(private_dir) = private_dir_for(site_dir)
+        && private_dir.join("integrity-fingerprint.txt").exists()
+    {
+        write_private_fingerprint(&private_dir, &fingerprint)?;
+    }
+    Ok(fingerprint)
+}
+
+/// The private/ directory next to a bundle's site/ directory, if any.
+fn private_dir_for(site_dir: &Path) -> Option<PathBuf> {
+    if site_dir.file_name()? != OsStr::new("site") {
+        return None;
+    }
+    let private_dir = site_dir.parent()?.join("private");
+    private_dir.is_dir().then_some(private_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_private_dir_for_bundle_root() {
+        let temp = TempDir::new().unwrap();
+        let site = temp.path().join("site");
+        fs::create_dir_all(&site).unwrap();
+        assert_eq!(private_dir_for(&site), None);
+
+        fs::create_dir_all(temp.path().join("private")).unwrap();
+        assert_eq!(private_dir_for(&site), Some(temp.path().join("private")));
+
+        // A bare site directory under another name has no private/ sibling
+        assert_eq!(private_dir_for(temp.path()), None);
+    }
+}
@@ -0,0 +1,675 @@
+//! Named saved searches.
+//!
+//! Saved searches live in `saved_searches.json` next to `tui_state.json` in the data
+//! dir. Each one remembers a query, its filters and search mode, plus a `created_at`
+//! high-water mark so `cass saved run --new` only reports messages newer than the
+//! previous run. Searches with an alert target are evaluated by `cass index --watch`
+//! after every ingest batch; matches are appended to a JSONL file and/or piped to a
+//! local command hook.
+
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::search::query::{
+    FieldMask, SearchClient, SearchClientOptions, SearchFilters, SearchHit, SearchMode,
+};
+use crate::sources::provenance::SourceFilter;
+
+/// File name of the saved-search store inside the data dir.
+pub const SAVED_SEARCHES_FILE: &str = "saved_searches.json";
+
+const DEFAULT_LIMIT: usize = 20;
+const SPARSE_THRESHOLD: usize = 3;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+/// Filters stored with a saved search (mirrors the `cass search` flags).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedFilters {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub agents: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Source filter: `local`, `remote`, or a source id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Only messages from the last N days, relative to each run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+}
+
+impl SavedFilters {
+    /// Build search filters for a run at `now_ms`; fails for filter values
+    /// that can never match (an unknown role or invalid tag).
+    pub fn to_search_filters(&self, now_ms: i64) -> Result<SearchFilters> {
+        let mut filters = SearchFilters {
+            agents: self.agents.iter().cloned().collect::<HashSet<_>>(),
+            workspaces: self.workspaces.iter().cloned().collect::<HashSet<_>>(),
+            ..SearchFilters::default()
+        };
+        for role in &self.roles {
+            filters.add_role(role)?;
+        }
+        for tool in &self.tools {
+            filters.add_tool(tool);
+        }
+        for tag in &self.tags {
+            filters.add_tag(tag)?;
+        }
+        if let Some(source) = &self.source {
+            filters.source_filter = SourceFilter::parse(source);
+        }
+        if let Some(days) = self.days {
+            filters.created_from = Some(now_ms - i64::from(days) * DAY_MS);
+        }
+        Ok(filters)
+    }
+}
+
+/// Where `cass index --watch` sends new matches of a saved search.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlertTarget {
+    /// Append one JSON line per match to this file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jsonl: Option<PathBuf>,
+    /// Run this shell command with the match lines on stdin.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+}
+
+impl AlertTarget {
+    pub fn is_empty(&self) -> bool {
+        self.jsonl.is_none() && self.hook.is_none()
+    }
+}
+
+/// A named query with its filters, mode and run bookkeeping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub filters: SavedFilters,
+    #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alert: Option<AlertTarget>,
+    /// Creation time (ms); watch alerts only report messages newer than this.
+    pub created_at: i64,
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    /// Newest `created_at` (ms) among the results of previous runs.
+    #[serde(default)]
+    pub high_water_mark: Option<i64>,
+}
+
+impl SavedSearch {
+    pub fn new(name: impl Into<String>, query: impl Into<String>, created_at: i64) -> Self {
+        Self {
+            name: name.into(),
+            query: query.into(),
+            filters: SavedFilters::default(),
+            mode: SearchMode::default(),
+            limit: DEFAULT_LIMIT,
+            alert: None,
+            created_at,
+            last_run_at: None,
+            high_water_mark: None,
+        }
+    }
+
+    /// True when `cass index --watch` should evaluate this search.
+    pub fn has_alert(&self) -> bool {
+        self.alert.as_ref().is_some_and(|a| !a.is_empty())
+    }
+}
+
+/// Saved-search names: 1-64 letters, digits, `-`, `_` or `.`.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedSearchFile {
+    #[serde(default)]
+    searches: Vec<SavedSearch>,
+}
+
+/// The saved searches of one data dir, loaded from and written back to JSON.
+#[derive(Debug)]
+pub struct SavedSearchStore {
+    path: PathBuf,
+    searches: Vec<SavedSearch>,
+}
+
+impl SavedSearchStore {
+    /// Path of the store for `data_dir`.
+    pub fn path_for(data_dir: &Path) -> PathBuf {
+        data_dir.join(SAVED_SEARCHES_FILE)
+    }
+
+    /// Load the store; a missing file is an empty store.
+    pub fn load(path: &Path) -> Result<Self> {
+        let searches = match std::fs::read_to_string(path) {
+            Ok(body) => {
+                serde_json::from_str::<SavedSearchFile>(&body)
+                    .with_context(|| format!("parsing {}", path.display()))?
+                    .searches
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            searches,
+        })
+    }
+
+    /// Write the store atomically (temp file + rename).
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("creating {}", parent.display()))?;
+        }
+        let body = serde_json::to_string_pretty(&SavedSearchFile {
+            searches: self.searches.clone(),
+        })?;
+        let temp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&temp_path, body)
+            .with_context(|| format!("writing {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &self.path)
+            .with_context(|| format!("replacing {}", self.path.display()))?;
+        Ok(())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All saved searches, sorted by name.
+    pub fn list(&self) -> &[SavedSearch] {
+        &self.searches
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SavedSearch> {
+        self.searches.iter().find(|s| s.name == name)
+    }
+
+    /// Add or replace a saved search; returns true if one was replaced.
+    ///
+    /// Replacing keeps the previous run bookkeeping only when the query is unchanged.
+    pub fn upsert(&mut self, mut search: SavedSearch) -> bool {
+        let replaced = match self.searches.iter().position(|s| s.name == search.name) {
+            Some(pos) => {
+                let old = self.searches.remove(pos);
+                if old.query == search.query && old.filters == search.filters {
+                    search.created_at = old.created_at;
+                    search.last_run_at = old.last_run_at;
+                    search.high_water_mark = old.high_water_mark;
+                }
+                true
+            }
+            None => false,
+        };
+        self.searches.push(search);
+        self.searches.sort_by(|a, b| a.name.cmp(&b.name));
+        replaced
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.searches.len();
+        self.searches.retain(|s| s.name != name);
+        self.searches.len() != before
+    }
+
+    /// Record a run: bump `last_run_at` and advance the high-water mark.
+    pub fn record_run(&mut self, name: &str, run_at: i64, high_water_mark: Option<i64>) {
+        if let Some(search) = self.searches.iter_mut().find(|s| s.name == name) {
+            search.last_run_at = Some(run_at);
+            search.high_water_mark = search.high_water_mark.max(high_water_mark);
+        }
+    }
+}
+
+/// Result of running a saved search once.
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedRun {
+    /// Lower `created_at` bound applied for new-only runs.
+    pub since: Option<i64>,
+    /// High-water mark after this run.
+    pub high_water_mark: Option<i64>,
+    pub hits: Vec<SearchHit>,
+}
+
+/// Newest `created_at` among `hits`, never moving backwards from `previous`.
+pub fn next_high_water_mark(previous: Option<i64>, hits: &[SearchHit]) -> Option<i64> {
+    hits.iter().filter_map(|h| h.created_at).max().max(previous)
+}
+
+/// Open a search client for `data_dir`, attaching semantic context when `mode` needs it.
+///
+/// Returns `Ok(None)` if there is no lexical index yet.
+pub fn open_client(
+    data_dir: &Path,
+    db_path: &Path,
+    mode: SearchMode,
+) -> Result<Option<SearchClient>> {
+    use crate::search::ann_index::hnsw_index_path;
+    use crate::search::model_manager::{load_hash_semantic_context, load_semantic_context};
+
+    let index_path = crate::search::tantivy::index_dir(data_dir)?;
+    let Some(client) = SearchClient::open_with_options(
+        &index_path,
+        Some(db_path),
+        SearchClientOptions {
+            enable_reload: false,
+            enable_warm: false,
+        },
+    )?
+    else {
+        return Ok(None);
+    };
+    if mode == SearchMode::Lexical {
+        return Ok(Some(client));
+    }
+
+    let setup = load_semantic_context(data_dir, db_path);
+    let context = match setup.context {
+        Some(context) => context,
+        None => match load_hash_semantic_context(data_dir, db_path).context {
+            Some(context) => context,
+            None => bail!(
+                "semantic index not available; run 'cass index --semantic' or save the search with --mode lexical"
+            ),
+        },
+    };
+    let embedder: Arc<dyn crate::search::embedder::Embedder> = context.embedder;
+    let ann_path = Some(hnsw_index_path(data_dir, embedder.id()));
+    client.set_semantic_context(
+        embedder,
+        context.index,
+        context.filter_maps,
+        context.roles,
+        ann_path,
+    )?;
+    Ok(Some(client))
+}
+
+/// Run `search` once. When `since` is set, only messages created at or after it match.
+pub fn run_saved_search(
+    client: &SearchClient,
+    search: &SavedSearch,
+    since: Option<i64>,
+    now_ms: i64,
+) -> Result<SavedRun> {
+    let mut filters = search.filters.to_search_filters(now_ms)?;
+    if let Some(since) = since {
+        filters.created_from = filters.created_from.max(Some(since));
+    }
+    let limit = search.limit.max(1);
+    let query = search.query.as_str();
+    let hits = match search.mode {
+        SearchMode::Lexical => {
+            client
+                .search_with_fallback(query, filters, limit, 0, SPARSE_THRESHOLD, FieldMask::FULL)?
+                .hits
+        }
+        SearchMode::Semantic => {
+            client
+                .search_semantic(query, filters, limit, 0, FieldMask::FULL, false)?
+                .0
+        }
+        SearchMode::Hybrid => {
+            client
+                .search_hybrid(
+                    query,
+                    query,
+                    filters,
+                    limit,
+                    0,
+                    SPARSE_THRESHOLD,
+                    FieldMask::FULL,
+                    false,
+                )?
+                .hits
+        }
+    };
+    Ok(SavedRun {
+        since,
+        high_water_mark: next_high_water_mark(search.high_water_mark, &hits),
+        hits,
+    })
+}
+
+/// One alert line, as written to JSONL files and hook stdin.
+#[derive(Debug, Serialize)]
+struct AlertRecord<'a> {
+    saved_search: &'a str,
+    query: &'a str,
+    evaluated_at: i64,
+    hit: &'a SearchHit,
+}
+
+fn alert_lines(search: &SavedSearch, hits: &[SearchHit], now_ms: i64) -> Result<String> {
+    let mut out = String::new();
+    for hit in hits {
+        let record = AlertRecord {
+            saved_search: &search.name,
+            query: &search.query,
+            evaluated_at: now_ms,
+            hit,
+        };
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn emit_alert(target: &AlertTarget, search: &SavedSearch, lines: &str, count: usize) -> Result<()> {
+    if let Some(path) = &target.jsonl {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("opening alert file {}", path.display()))?;
+        file.write_all(lines.as_bytes())?;
+    }
+    if let Some(hook) = &target.hook {
+        let mut cmd = if cfg!(windows) {
+            let mut c = Command::new("cmd");
+            c.args(["/C", hook]);
+            c
+        } else {
+            let mut c = Command::new("sh");
+            c.args(["-c", hook]);
+            c
+        };
+        let mut child = cmd
+            .env("CASS_SAVED_SEARCH", &search.name)
+            .env("CASS_SAVED_QUERY", &search.query)
+            .env("CASS_MATCH_COUNT", count.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .with_context(|| format!("launching hook for saved search '{}'", search.name))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores stdin may exit before reading it; that's fine.
+            let _ = stdin.write_all(lines.as_bytes());
+        }
+        // Reap the hook in the background so a slow command can't stall indexing.
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+    }
+    Ok(())
+}
+
+/// Evaluate every saved search that has an alert target and emit its new matches.
+///
+/// Called by `cass index --watch` after each ingest batch. Searches that were never
+/// run only report messages created after the search itself. Returns the number of
+/// matches emitted.
+pub fn evaluate_alerts(data_dir: &Path, db_path: &Path) -> Result<usize> {
+    let path = SavedSearchStore::path_for(data_dir);
+    let store = SavedSearchStore::load(&path)?;
+    let alerting: Vec<SavedSearch> = store
+        .list()
+        .iter()
+        .filter(|s| s.has_alert())
+        .cloned()
+        .collect();
+    if alerting.is_empty() {
+        return Ok(0);
+    }
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let mut lexical: Option<SearchClient> = None;
+    let mut semantic: Option<SearchClient> = None;
+    let mut runs = Vec::new();
+    let mut emitted = 0usize;
+    for search in &alerting {
+        let slot = if search.mode == SearchMode::Lexical {
+            &mut lexical
+        } else {
+            &mut semantic
+        };
+        if slot.is_none() {
+            match open_client(data_dir, db_path, search.mode) {
+                Ok(client) => *slot = client,
+                Err(e) => {
+                    tracing::warn!(saved_search = %search.name, "skipping saved search: {e}");
+                    continue;
+                }
+            }
+        }
+        let Some(client) = slot.as_ref() else {
+            return Ok(0);
+        };
+
+        let since = search
+            .high_water_mark
+            .map_or(search.created_at, |hwm| hwm + 1);
+        let run = match run_saved_search(client, search, Some(since), now_ms) {
+            Ok(run) => run,
+            Err(e) => {
+                tracing::warn!(saved_search = %search.name, "saved search failed: {e}");
+                continue;
+            }
+        };
+        if !run.hits.is_empty() {
+            let lines = alert_lines(search, &run.hits, now_ms)?;
+            if let Some(target) = &search.alert
+                && let Err(e) = emit_alert(target, search, &lines, run.hits.len())
+            {
+                tracing::warn!(saved_search = %search.name, "failed to emit alert: {e}");
+            }
+            emitted += run.hits.len();
+            tracing::info!(
+                saved_search = %search.name,
+                matches = run.hits.len(),
+                "saved search matched new messages"
+            );
+        }
+        runs.push((search.name.clone(), run.high_water_mark));
+    }
+
+    // Reload before writing so edits made while we searched are kept.
+    let mut store = SavedSearchStore::load(&path)?;
+    for (name, hwm) in runs {
+        store.record_run(&name, now_ms, hwm);
+    }
+    store.save()?;
+    Ok(emitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::MatchType;
+    use tempfile::TempDir;
+
+    fn hit(created_at: Option<i64>) -> SearchHit {
+        SearchHit {
+            title: "t".into(),
+            snippet: "DROP TABLE users".into(),
+            content: "DROP TABLE users".into(),
+            content_hash: 0,
+            score: 1.0,
+            source_path: "/logs/a.jsonl".into(),
+            agent: "codex".into(),
+            workspace: "/ws".into(),
+            workspace_original: None,
+            created_at,
+            line_number: Some(3),
+            match_type: MatchType::Exact,
+            source_id: "local".into(),
+            origin_kind: "local".into(),
+            origin_host: None,
+        }
+    }
+
+    #[test]
+    fn store_roundtrips_and_sorts_by_name() {
+        let tmp = TempDir::new().unwrap();
+        let path = SavedSearchStore::path_for(tmp.path());
+        let mut store = SavedSearchStore::load(&path).unwrap();
+        assert!(store.list().is_empty());
+
+        let mut drop = SavedSearch::new("drop-table", "\"DROP TABLE\"", 10);
+        drop.filters.agents = vec!["codex".into()];
+        drop.alert = Some(AlertTarget {
+            jsonl: Some(tmp.path().join("alerts.jsonl")),
+            hook: None,
+        });
+        assert!(!store.upsert(SavedSearch::new("rate-limit", "rate limit", 5)));
+        assert!(!store.upsert(drop.clone()));
+        store.save().unwrap();
+
+        let loaded = SavedSearchStore::load(&path).unwrap();
+        let names: Vec<&str> = loaded.list().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["drop-table", "rate-limit"]);
+        assert_eq!(loaded.get("drop-table"), Some(&drop));
+        assert!(loaded.get("drop-table").unwrap().has_alert());
+        assert!(!loaded.get("rate-limit").unwrap().has_alert());
+    }
+
+    #[test]
+    fn upsert_keeps_high_water_mark_only_for_same_query() {
+        let tmp = TempDir::new().unwrap();
+        let mut store = SavedSearchStore::load(&SavedSearchStore::path_for(tmp.path())).unwrap();
+        store.upsert(SavedSearch::new("s", "rate limit", 1));
+        store.record_run("s", 100, Some(90));
+
+        let mut same = SavedSearch::new("s", "rate limit", 2);
+        same.limit = 5;
+        assert!(store.upsert(same));
+        assert_eq!(store.get("s").unwrap().high_water_mark, Some(90));
+        assert_eq!(store.get("s").unwrap().limit, 5);
+
+        assert!(store.upsert(SavedSearch::new("s", "quota exceeded", 3)));
+        assert_eq!(store.get("s").unwrap().high_water_mark, None);
+        assert!(store.remove("s"));
+        assert!(!store.remove("s"));
+    }
+
+    #[test]
+    fn high_water_mark_never_moves_backwards() {
+        assert_eq!(next_high_water_mark(None, &[]), None);
+        assert_eq!(
+            next_high_water_mark(None, &[hit(Some(5)), hit(None), hit(Some(9))]),
+            Some(9)
+        );
+        assert_eq!(next_high_water_mark(Some(20), &[hit(Some(9))]), Some(20));
+
+        let tmp = TempDir::new().unwrap();
+        let mut store = SavedSearchStore::load(&SavedSearchStore::path_for(tmp.path())).unwrap();
+        store.upsert(SavedSearch::new("s", "q", 1));
+        store.record_run("s", 50, Some(40));
+        store.record_run("s", 60, None);
+        let s = store.get("s").unwrap();
+        assert_eq!(s.last_run_at, Some(60));
+        assert_eq!(s.high_water_mark, Some(40));
+    }
+
+    #[test]
+    fn filters_convert_to_search_filters() {
+        let filters = SavedFilters {
+            agents: vec!["codex".into()],
+            roles: vec!["assistant".into()],
+            tools: vec!["Bash".into()],
+            source: Some("local".into()),
+            days: Some(2),
+            ..SavedFilters::default()
+        };
+        let sf = filters.to_search_filters(10 * DAY_MS).unwrap();
+        assert!(sf.agents.contains("codex"));
+        assert!(sf.roles.contains("agent"));
+        assert!(sf.tools.contains("bash"));
+        assert_eq!(sf.source_filter, SourceFilter::Local);
+        assert_eq!(sf.created_from, Some(8 * DAY_MS));
+
+        let unknown = SavedFilters {
+            roles: vec!["robot".into()],
+            ..SavedFilters::default()
+        };
+        assert!(unknown.to_search_filters(0).is_err());
+        let invalid = SavedFilters {
+            tags: vec!["two words".into()],
+            ..SavedFilters::default()
+        };
+        assert!(invalid.to_search_filters(0).is_err());
+    }
+
+    #[test]
+    fn names_are_validated() {
+        assert!(is_valid_name("drop-table"));
+        assert!(is_valid_name("rate_limit.v2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("has space"));
+        assert!(!is_valid_name("../escape"));
+        assert!(!is_valid_name(&"x".repeat(65)));
+    }
+
+    #[test]
+    fn alerts_append_jsonl_lines() {
+        let tmp = TempDir::new().unwrap();
+        let out = tmp.path().join("alerts").join("matches.jsonl");
+        let search = SavedSearch::new("drop-table", "DROP TABLE", 1);
+        let target = AlertTarget {
+            jsonl: Some(out.clone()),
+            hook: None,
+        };
+        let hits = [hit(Some(5)), hit(Some(6))];
+        let lines = alert_lines(&search, &hits, 7).unwrap();
+        emit_alert(&target, &search, &lines, hits.len()).unwrap();
+        emit_alert(&target, &search, &lines, hits.len()).unwrap();
+
+        let body = std::fs::read_to_string(&out).unwrap();
+        let records: Vec<serde_json::Value> = body
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0]["saved_search"], "drop-table");
+        assert_eq!(records[0]["evaluated_at"], 7);
+        assert_eq!(records[1]["hit"]["created_at"], 6);
+        assert_eq!(records[1]["hit"]["source_path"], "/logs/a.jsonl");
+    }
+
+    #[test]
+    fn evaluate_alerts_is_noop_without_alerting_searches() {
+        let tmp = TempDir::new().unwrap();
+        let mut store = SavedSearchStore::load(&SavedSearchStore::path_for(tmp.path())).unwrap();
+        store.upsert(SavedSearch::new("quiet", "q", 1));
+        store.save().unwrap();
+        let emitted = evaluate_alerts(tmp.path(), &tmp.path().join("agent_search.db")).unwrap();
+        assert_eq!(emitted, 0);
+    }
+}
@@ -63,7 +63,7 @@ pub fn hnsw_index_path(data_dir: &Path, embedder_id: &str) -> PathBuf {
 /// Result from an approximate nearest neighbor search.
 #[derive(Debug, Clone)]
 pub struct AnnSearchResult {
-    /// Index into the VectorIndex rows array.
+    /// Graph id of the row; resolve with [`VectorIndex::row_for_graph_id`].
     pub row_idx: usize,
     /// Approximate distance (lower is better for dot product converted to distance).
     pub distance: f32,
@@ -72,7 +72,7 @@ pub struct AnnSearchResult {
 /// Statistics from an ANN search operation.
 ///
 /// These metrics help users understand the quality/speed tradeoff of approximate search.
-#[derive(Debug, Clone, Default, serde::Serialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct AnnSearchStats {
     /// Total vectors in the HNSW index.
     pub index_size: usize,
@@ -96,8 +96,9 @@ pub struct AnnSearchStats {
 
 /// HNSW index wrapper for approximate nearest neighbor search.
 ///
-/// The index stores references to row indices in the corresponding VectorIndex,
-/// allowing fast approximate lookup followed by metadata retrieval.
+/// The index stores row graph ids of the corresponding VectorIndex (row
+/// positions, unless segments with tombstones were merged), allowing fast
+/// approximate lookup followed by metadata retrieval.
 pub struct HnswIndex {
     /// The underlying HNSW graph structure.
     /// Uses DistDot for dot product similarity (converted to distance).
@@ -114,7 +115,8 @@ impl HnswIndex {
     /// Build a new HNSW index from an existing VectorIndex.
     ///
     /// This reads all vectors from the CVVI file and builds the HNSW graph.
-    /// The row index (position in VectorIndex.rows()) is used as the ID.
+    /// Each row's graph id ([`VectorIndex::graph_id`], normally its position in
+    /// VectorIndex.rows()) is used as the ID.
     pub fn build_from_vector_index(
         vector_index: &VectorIndex,
         m: usize,
@@ -144,7 +146,7 @@ impl HnswIndex {
         let vectors_with_ids: Vec<(&Vec<f32>, usize)> = vectors
             .iter()
             .enumerate()
-            .map(|(idx, vec)| (vec, idx))
+            .map(|(idx, vec)| (vec, vector_index.graph_id(idx)))
             .collect();
 
         // Parallel insertion (HNSW clones vector data internally).
@@ -160,6 +162,54 @@ impl HnswIndex {
         })
     }
 
+    /// Insert every row of `delta` (an appended vector segment) into the graph.
+    ///
+    /// Row `i` gets id `first_graph_id + i`. Ids must continue the existing
+    /// ones, i.e. `first_graph_id == self.len()`; otherwise the graph is out of
+    /// step with the vector index and should be rebuilt.
+    pub fn insert_segment(&mut self, delta: &VectorIndex, first_graph_id: usize) -> Result<()> {
+        let header = delta.header();
+        if header.embedder_id != self.embedder_id {
+            bail!(
+                "HNSW index embedder mismatch: expected {}, got {}",
+                self.embedder_id,
+                header.embedder_id
+            );
+        }
+        if header.dimension as usize != self.dimension {
+            bail!(
+                "HNSW index dimension mismatch: expected {}, got {}",
+                self.dimension,
+                header.dimension
+            );
+        }
+        if first_graph_id != self.count {
+            bail!(
+                "HNSW index holds {} nodes but the segment starts at id {first_graph_id}",
+                self.count
+            );
+        }
+
+        let mut vectors: Vec<Vec<f32>> = Vec::with_capacity(delta.rows().len());
+        for row in delta.rows() {
+            vectors.push(delta.vector_at_f32(row)?);
+        }
+        let vectors_with_ids: Vec<(&Vec<f32>, usize)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(idx, vec)| (vec, first_graph_id + idx))
+            .collect();
+        self.hnsw.parallel_insert(&vectors_with_ids);
+        self.count += vectors.len();
+
+        tracing::info!(
+            inserted = vectors.len(),
+            count = self.count,
+            "Inserted segment into HNSW index"
+        );
+        Ok(())
+    }
+
     /// Search for approximate nearest neighbors.
     ///
     /// Returns up to `k` results sorted by similarity (highest first).
@@ -613,4 +663,46 @@ mod tests {
         let temp_dir = tempfile::tempdir().unwrap();
         assert!(!HnswIndex::exists(temp_dir.path(), "nonexistent"));
     }
+
+    fn unit_entries(ids: std::ops::Range<u64>) -> Vec<crate::search::vector_index::VectorEntry> {
+        ids.map(|id| {
+            let angle = id as f32 * 0.09;
+            let mut vector = vec![angle.cos(), angle.sin(), (angle * 2.0).cos(), 0.5];
+            let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+            vector.iter_mut().for_each(|v| *v /= norm);
+            crate::search::vector_index::VectorEntry {
+                message_id: id,
+                created_at_ms: 0,
+                agent_id: 0,
+                workspace_id: 0,
+                source_id: 0,
+                role: 0,
+                chunk_idx: 0,
+                content_hash: [0; 32],
+                vector,
+            }
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_insert_segment_extends_graph() {
+        use crate::search::vector_index::Quantization;
+
+        let base =
+            VectorIndex::build("test", "1", 4, Quantization::F32, unit_entries(0..30)).unwrap();
+        let mut hnsw = HnswIndex::build_from_vector_index(&base, DEFAULT_M, 64).unwrap();
+        let delta =
+            VectorIndex::build("test", "1", 4, Quantization::F32, unit_entries(30..35)).unwrap();
+
+        let err = hnsw.insert_segment(&delta, 29).unwrap_err();
+        assert!(err.to_string().contains("segment starts at id 29"));
+
+        hnsw.insert_segment(&delta, 30).unwrap();
+        assert_eq!(hnsw.len(), 35);
+
+        let query = delta.vector_at_f32(&delta.rows()[2]).unwrap();
+        let hits = hnsw.search(&query, 1, 50).unwrap();
+        assert_eq!(hits[0].row_idx, 32);
+    }
 }
@@ -14,6 +14,11 @@
 //! 5. **Low-signal filtering** - Remove "OK", "Done.", etc.
 //! 6. **Truncation** - Limit to MAX_EMBED_CHARS (2000)
 //!
+//! Messages longer than [`MAX_EMBED_CHARS`] are embedded by
+//! [`canonicalize_for_embedding_chunks`] as overlapping windows instead of
+//! being truncated, so the tail of a long answer is still searchable. The
+//! first window is always identical to [`canonicalize_for_embedding`].
+//!
 //! # Why This Matters
 //!
 //! Without proper canonicalization:
@@ -39,6 +44,12 @@ use unicode_normalization::UnicodeNormalization;
 /// Maximum characters to keep after canonicalization.
 pub const MAX_EMBED_CHARS: usize = 2000;
 
+/// Characters shared between consecutive chunks of a long message.
+pub const EMBED_CHUNK_OVERLAP: usize = 200;
+
+/// Maximum chunks embedded per message (chunk indices are stored as `u8`).
+pub const MAX_EMBED_CHUNKS: usize = 16;
+
 /// Maximum lines to keep from the beginning of a code block.
 pub const CODE_HEAD_LINES: usize = 20;
 
@@ -91,10 +102,42 @@ pub fn canonicalize_for_embedding(text: &str) -> String {
     }
 }
 
+/// Canonicalize text for embedding as one or more overlapping chunks.
+///
+/// Runs the same pipeline as [`canonicalize_for_embedding`] but, instead of
+/// truncating, splits the canonical text into windows of [`MAX_EMBED_CHARS`]
+/// that overlap by roughly [`EMBED_CHUNK_OVERLAP`] characters. At most
+/// [`MAX_EMBED_CHUNKS`] chunks are returned; anything beyond them is dropped.
+///
+/// The first chunk always equals `canonicalize_for_embedding(text)`, so
+/// short messages produce exactly one chunk with an unchanged content hash.
+/// Returns an empty vector when the text canonicalizes to nothing.
+pub fn canonicalize_for_embedding_chunks(text: &str) -> Vec<String> {
+    let canonical = if *STREAMING_CANONICALIZE_ENABLED {
+        canonicalize_streaming_untruncated(text)
+    } else {
+        canonicalize_legacy_untruncated(text)
+    };
+    split_into_chunks(
+        &canonical,
+        MAX_EMBED_CHARS,
+        EMBED_CHUNK_OVERLAP,
+        MAX_EMBED_CHUNKS,
+    )
+}
+
 /// Legacy canonicalization pipeline (pre-streaming).
 ///
 /// Exposed for benchmarks and regression comparisons.
 pub fn canonicalize_for_embedding_legacy(text: &str) -> String {
+    let filtered = canonicalize_legacy_untruncated(text);
+
+    // Step 5: Truncate to max length
+    truncate_to_chars(&filtered, MAX_EMBED_CHARS)
+}
+
+/// Steps 1-4 of the legacy pipeline, without truncation.
+fn canonicalize_legacy_untruncated(text: &str) -> String {
     // Step 1: Unicode NFC normalization (CRITICAL for hash stability)
     let normalized: String = text.nfc().collect();
 
@@ -105,16 +148,25 @@ pub fn canonicalize_for_embedding_legacy(text: &str) -> String {
     let whitespace_normalized = normalize_whitespace(&stripped);
 
     // Step 4: Filter low-signal content
-    let filtered = filter_low_signal(&whitespace_normalized);
-
-    // Step 5: Truncate to max length
-    truncate_to_chars(&filtered, MAX_EMBED_CHARS)
+    filter_low_signal(&whitespace_normalized)
 }
 
 /// Streaming canonicalization pipeline with reduced allocations.
 ///
 /// Exposed for benchmarks and regression comparisons.
 pub fn canonicalize_for_embedding_streaming(text: &str) -> String {
+    let output = canonicalize_streaming_untruncated(text);
+
+    // Step 5: Truncate to max length
+    if output.chars().count() <= MAX_EMBED_CHARS {
+        output
+    } else {
+        output.chars().take(MAX_EMBED_CHARS).collect()
+    }
+}
+
+/// Steps 1-4 of the streaming pipeline, without truncation.
+fn canonicalize_streaming_untruncated(text: &str) -> String {
     // Step 1: Unicode NFC normalization (CRITICAL for hash stability)
     let normalized: String = text.nfc().collect();
 
@@ -153,7 +205,7 @@ pub fn canonicalize_for_embedding_streaming(text: &str) -> String {
         writer.push_text("\n");
     }
 
-    let mut output = writer.finish();
+    let output = writer.finish();
 
     // Step 4: Filter low-signal content (exact match after normalization)
     let trimmed_lower = output.trim().to_lowercase();
@@ -163,13 +215,7 @@ pub fn canonicalize_for_embedding_streaming(text: &str) -> String {
         }
     }
 
-    // Step 5: Truncate to max length
-    if output.chars().count() <= MAX_EMBED_CHARS {
-        output
-    } else {
-        output = output.chars().take(MAX_EMBED_CHARS).collect();
-        output
-    }
+    output
 }
 
 struct WhitespaceWriter {
@@ -490,6 +536,34 @@ fn truncate_to_chars(text: &str, max_chars: usize) -> String {
     }
 }
 
+/// Split text into windows of `window` chars overlapping by about `overlap`.
+///
+/// The first window starts at the beginning of the text; later windows start
+/// on the first word boundary inside the overlap region when there is one.
+fn split_into_chunks(text: &str, window: usize, overlap: usize, max_chunks: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() || window == 0 || max_chunks == 0 {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + window).min(chars.len());
+        chunks.push(chars[start..end].iter().collect::<String>());
+        if end == chars.len() || chunks.len() >= max_chunks {
+            break;
+        }
+
+        let mut next = end.saturating_sub(overlap).max(start + 1);
+        if let Some(pos) = chars[next..end].iter().position(|c| *c == ' ') {
+            next += pos + 1;
+        }
+        start = next;
+    }
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -631,6 +705,50 @@ mod tests {
         assert_eq!(canonical.len(), MAX_EMBED_CHARS);
     }
 
+    #[test]
+    fn test_chunks_short_text_single_chunk() {
+        let text = "**Hello** world, this is a short message.";
+        let chunks = canonicalize_for_embedding_chunks(text);
+
+        assert_eq!(chunks, vec![canonicalize_for_embedding(text)]);
+        assert!(canonicalize_for_embedding_chunks("OK").is_empty());
+        assert!(canonicalize_for_embedding_chunks("").is_empty());
+    }
+
+    #[test]
+    fn test_chunks_long_text_overlap_and_tail() {
+        let words: Vec<String> = (0..1500).map(|i| format!("word{i}")).collect();
+        let text = words.join(" ");
+        let chunks = canonicalize_for_embedding_chunks(&text);
+
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks[0], canonicalize_for_embedding(&text));
+        assert!(chunks.iter().all(|c| c.chars().count() <= MAX_EMBED_CHARS));
+        assert!(chunks.last().unwrap().ends_with("word1499"));
+
+        for pair in chunks.windows(2) {
+            // Later chunks start on a word boundary inside the previous chunk's tail.
+            let first_word = pair[1].split(' ').next().unwrap();
+            assert!(first_word.starts_with("word"));
+            assert!(pair[0].contains(&format!("{first_word} ")));
+        }
+    }
+
+    #[test]
+    fn test_chunks_capped() {
+        let long_text: String = "a".repeat(MAX_EMBED_CHARS * (MAX_EMBED_CHUNKS + 4));
+        let chunks = canonicalize_for_embedding_chunks(&long_text);
+
+        assert_eq!(chunks.len(), MAX_EMBED_CHUNKS);
+        assert_eq!(chunks[0].len(), MAX_EMBED_CHARS);
+    }
+
+    #[test]
+    fn test_split_into_chunks_without_spaces() {
+        let chunks = split_into_chunks("abcdefghij", 4, 1, 10);
+        assert_eq!(chunks, vec!["abcd", "defg", "ghij"]);
+    }
+
     #[test]
     fn test_empty_input() {
         let canonical = canonicalize_for_embedding("");
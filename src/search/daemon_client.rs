@@ -3,6 +3,8 @@
 //! This module provides:
 //! - A `DaemonClient` trait to abstract the daemon protocol (bd-1lps, bd-31z).
 //! - Fallback wrappers for `Embedder` and `Reranker` with retry + jittered backoff.
+//! - `DaemonSearchRouter`, which sends whole search requests to a daemon that
+//!   keeps the index resident and reports when the caller must search locally.
 //! - Structured logging for daemon usage and fallback decisions.
 //!
 //! The concrete daemon transport is intentionally unspecified here until the
@@ -17,7 +19,9 @@ use std::time::{Duration, Instant};
 use parking_lot::Mutex;
 use tracing::{debug, warn};
 
+use crate::daemon::protocol::SearchRequest;
 use crate::search::embedder::{Embedder, EmbedderResult};
+use crate::search::query::SearchResult;
 use crate::search::reranker::{Reranker, RerankerError, RerankerResult};
 
 /// Retry/backoff configuration for daemon requests.
@@ -120,6 +124,19 @@ pub trait DaemonClient: Send + Sync {
         documents: &[&str],
         request_id: &str,
     ) -> Result<Vec<f32>, DaemonError>;
+
+    /// Run a search against the daemon's resident index.
+    ///
+    /// Clients that cannot serve search keep this default and report `Unavailable`.
+    fn search(
+        &self,
+        _request: &SearchRequest,
+        _request_id: &str,
+    ) -> Result<SearchResult, DaemonError> {
+        Err(DaemonError::Unavailable(
+            "daemon search not supported".to_string(),
+        ))
+    }
 }
 
 /// No-op daemon client used when daemon config is missing.
@@ -601,6 +618,134 @@ impl Reranker for DaemonFallbackReranker {
     }
 }
 
+/// Search router that uses the daemon when available; `None` means search locally.
+pub struct DaemonSearchRouter {
+    daemon: Arc<dyn DaemonClient>,
+    config: DaemonRetryConfig,
+    state: Mutex<DaemonState>,
+}
+
+impl DaemonSearchRouter {
+    pub fn new(daemon: Arc<dyn DaemonClient>, config: DaemonRetryConfig) -> Self {
+        Self {
+            daemon,
+            config,
+            state: Mutex::new(DaemonState::new()),
+        }
+    }
+
+    /// Local search is always a valid answer, so only timeouts are worth retrying.
+    fn should_retry(err: &DaemonError) -> bool {
+        matches!(err, DaemonError::Timeout(_))
+    }
+
+    fn log_fallback(&self, request_id: &str, retries: u32, failure: &DaemonFailure) {
+        let reason = DaemonFallbackEmbedder::fallback_reason(&failure.error, failure.backoff);
+        if matches!(failure.error, DaemonError::Unavailable(_)) {
+            // Routing is opportunistic: an absent or older daemon is not worth a warning.
+            debug!(
+                daemon_id = self.daemon.id(),
+                request_id = request_id,
+                fallback_reason = reason,
+                error = %failure.error,
+                "Daemon search unavailable; searching locally"
+            );
+        } else {
+            warn!(
+                daemon_id = self.daemon.id(),
+                request_id = request_id,
+                retry_count = retries,
+                fallback_reason = reason,
+                error = %failure.error,
+                "Daemon search failed; searching locally"
+            );
+        }
+    }
+
+    /// Search through the daemon, or `None` if the caller should search in-process.
+    pub fn search(&self, request: &SearchRequest) -> Option<SearchResult> {
+        let request_id = next_request_id();
+        match self.try_search(&request_id, request) {
+            Ok(result) => Some(result),
+            Err(failure) => {
+                let retries = failure.attempts.saturating_sub(1);
+                self.log_fallback(&request_id, retries, &failure);
+                None
+            }
+        }
+    }
+
+    fn try_search(
+        &self,
+        request_id: &str,
+        request: &SearchRequest,
+    ) -> Result<SearchResult, DaemonFailure> {
+        // No `is_available()` gate: it reports model readiness, which search does not need.
+        let now = Instant::now();
+        {
+            let state = self.state.lock();
+            if !state.can_attempt(now) {
+                return Err(DaemonFailure {
+                    error: DaemonError::Unavailable("backoff active".to_string()),
+                    attempts: 0,
+                    backoff: true,
+                });
+            }
+        }
+        let mut attempts = 0;
+        let mut last_err: Option<DaemonError> = None;
+        while attempts < self.config.max_attempts {
+            attempts += 1;
+            debug!(
+                daemon_id = self.daemon.id(),
+                request_id = request_id,
+                attempt = attempts,
+                max_attempts = self.config.max_attempts,
+                "Attempting daemon search"
+            );
+            match self.daemon.search(request, request_id) {
+                Ok(result) => {
+                    self.state.lock().record_success();
+                    return Ok(result);
+                }
+                Err(err) => {
+                    let should_retry = Self::should_retry(&err);
+                    let should_backoff = !matches!(err, DaemonError::InvalidInput(_));
+                    let backoff = if should_backoff {
+                        Some(self.state.lock().record_failure(&self.config, &err))
+                    } else {
+                        None
+                    };
+                    let backoff_ms = backoff.map(|delay| delay.as_millis() as u64).unwrap_or(0);
+                    debug!(
+                        daemon_id = self.daemon.id(),
+                        request_id = request_id,
+                        attempt = attempts,
+                        max_attempts = self.config.max_attempts,
+                        backoff_ms = backoff_ms,
+                        will_retry = should_retry && attempts < self.config.max_attempts,
+                        error = %&err,
+                        "Daemon search failed"
+                    );
+                    last_err = Some(err);
+                    if !should_retry || attempts >= self.config.max_attempts {
+                        break;
+                    }
+                    if let Some(backoff) = backoff {
+                        std::thread::sleep(backoff);
+                    }
+                }
+            }
+        }
+        Err(DaemonFailure {
+            error: last_err
+                .unwrap_or_else(|| DaemonError::Unavailable("daemon search failed".to_string())),
+            attempts,
+            backoff: false,
+        })
+    }
+}
+
 fn apply_jitter(duration: Duration, jitter_pct: f64) -> Duration {
     if jitter_pct <= 0.0 {
         return duration;
@@ -1180,4 +1325,126 @@ mod tests {
         let calls_after_third = daemon.calls.load(Ordering::Relaxed);
         assert!(calls_after_third > calls_after_second);
     }
+
+    struct SearchFixtureDaemon {
+        calls: AtomicUsize,
+        fail_first: usize,
+        mode: FailureMode,
+    }
+
+    impl SearchFixtureDaemon {
+        fn new(fail_first: usize, mode: FailureMode) -> Self {
+            Self {
+                calls: AtomicUsize::new(0),
+                fail_first,
+                mode,
+            }
+        }
+    }
+
+    impl DaemonClient for SearchFixtureDaemon {
+        fn id(&self) -> &str {
+            "search-fixture-daemon"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        fn embed(&self, _text: &str, _request_id: &str) -> Result<Vec<f32>, DaemonError> {
+            Err(FailureMode::Unavailable.error())
+        }
+
+        fn embed_batch(
+            &self,
+            _texts: &[&str],
+            _request_id: &str,
+        ) -> Result<Vec<Vec<f32>>, DaemonError> {
+            Err(FailureMode::Unavailable.error())
+        }
+
+        fn rerank(
+            &self,
+            _query: &str,
+            _documents: &[&str],
+            _request_id: &str,
+        ) -> Result<Vec<f32>, DaemonError> {
+            Err(FailureMode::Unavailable.error())
+        }
+
+        fn search(
+            &self,
+            _request: &SearchRequest,
+            _request_id: &str,
+        ) -> Result<SearchResult, DaemonError> {
+            let call = self.calls.fetch_add(1, Ordering::Relaxed);
+            if call < self.fail_first {
+                Err(self.mode.error())
+            } else {
+                Ok(SearchResult {
+                    hits: Vec::new(),
+                    wildcard_fallback: true,
+                    cache_stats: Default::default(),
+                    suggestions: Vec::new(),
+                    ann_stats: None,
+                })
+            }
+        }
+    }
+
+    fn search_request() -> SearchRequest {
+        SearchRequest {
+            data_dir: PathBuf::from("/data"),
+            index_path: PathBuf::from("/data/index"),
+            db_path: PathBuf::from("/data/agent_search.db"),
+            query: "cargo".to_string(),
+            filters: Default::default(),
+            mode: crate::search::query::SearchMode::Lexical,
+            limit: 10,
+            offset: 0,
+            sparse_threshold: 3,
+            field_mask: crate::search::query::FieldMask::FULL.bits(),
+            approximate: false,
+            model: None,
+            chunk_size: 0,
+        }
+    }
+
+    fn fast_retry_config() -> DaemonRetryConfig {
+        DaemonRetryConfig {
+            max_attempts: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            jitter_pct: 0.0,
+        }
+    }
+
+    #[test]
+    fn search_router_uses_daemon_result() {
+        let daemon = Arc::new(SearchFixtureDaemon::new(0, FailureMode::Failed));
+        let router = DaemonSearchRouter::new(daemon.clone(), fast_retry_config());
+        let result = router.search(&search_request()).expect("daemon result");
+        assert!(result.wildcard_fallback);
+        assert_eq!(daemon.calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn search_router_retries_timeouts_only() {
+        let daemon = Arc::new(SearchFixtureDaemon::new(1, FailureMode::Timeout));
+        let router = DaemonSearchRouter::new(daemon.clone(), fast_retry_config());
+        assert!(router.search(&search_request()).is_some());
+        assert_eq!(daemon.calls.load(Ordering::Relaxed), 2);
+
+        let daemon = Arc::new(SearchFixtureDaemon::new(1, FailureMode::Failed));
+        let router = DaemonSearchRouter::new(daemon.clone(), fast_retry_config());
+        assert!(router.search(&search_request()).is_none());
+        assert_eq!(daemon.calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn search_router_falls_back_without_daemon_search() {
+        let router =
+            DaemonSearchRouter::new(Arc::new(NoopDaemonClient::new("noop")), fast_retry_config());
+        assert!(router.search(&search_request()).is_none());
+    }
 }
@@ -15,7 +15,9 @@
 //! - **[`model_manager`]**: Semantic model detection + context wiring (no downloads).
 //! - **[`model_download`]**: Model download system with consent, verification, and atomic install.
 //! - **[`canonicalize`]**: Text preprocessing for consistent embedding input.
+//! - **[`related`]**: Ranking sessions related to a source file (`cass related`).
 //! - **[`ann_index`]**: HNSW-based approximate nearest neighbor index (Opt 9).
+//! - **[`vector_segments`]**: Append-only delta segments and compaction for the CVVI vector index.
 //! - **[`two_tier_search`]**: Two-tier progressive search with fast/quality embeddings (bd-3dcw).
 
 pub mod ann_index;
@@ -29,8 +31,10 @@ pub mod hash_embedder;
 pub mod model_download;
 pub mod model_manager;
 pub mod query;
+pub mod related;
 pub mod reranker;
 pub mod reranker_registry;
 pub mod tantivy;
 pub mod two_tier_search;
 pub mod vector_index;
+pub mod vector_segments;
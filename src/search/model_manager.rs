@@ -2,7 +2,7 @@
 //!
 //! This module wires the FastEmbed MiniLM embedder into semantic search by:
 //! - validating the local model files
-//! - loading the vector index
+//! - loading the vector index (merged with any delta segments)
 //! - building filter maps from the SQLite database
 //! - detecting model version mismatches
 //!
@@ -20,6 +20,7 @@ use crate::search::model_download::{ModelManifest, ModelState, check_version_mis
 use crate::search::vector_index::{
     ROLE_ASSISTANT, ROLE_USER, SemanticFilterMaps, VectorIndex, vector_index_path,
 };
+use crate::search::vector_segments::{clear_segments, load_segmented};
 use crate::storage::sqlite::SqliteStorage;
 
 /// Unified TUI state machine for semantic search availability.
@@ -319,7 +320,7 @@ pub fn load_hash_semantic_context(data_dir: &Path, db_path: &Path) -> SemanticSe
         }
     };
 
-    let index = match VectorIndex::load(&index_path) {
+    let index = match load_segmented(&index_path) {
         Ok(index) => index,
         Err(err) => {
             return SemanticSetup {
@@ -427,7 +428,7 @@ fn load_semantic_context_inner(
         }
     };
 
-    let index = match VectorIndex::load(&index_path) {
+    let index = match load_segmented(&index_path) {
         Ok(index) => index,
         Err(err) => {
             return SemanticSetup {
@@ -510,6 +511,7 @@ pub fn delete_vector_index_for_rebuild(data_dir: &Path) -> std::io::Result<bool>
     let index_path = vector_index_path(data_dir, FastEmbedder::embedder_id_static());
 
     if index_path.is_file() {
+        clear_segments(&index_path).map_err(std::io::Error::other)?;
         std::fs::remove_file(&index_path)?;
         Ok(true)
     } else {
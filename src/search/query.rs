@@ -24,6 +24,8 @@ use tokio::task::JoinHandle;
 
 use rusqlite::Connection;
 
+use crate::connectors::canonical_role;
+use crate::model::types::Tag;
 use crate::search::ann_index::{DEFAULT_EF_SEARCH, HnswIndex};
 use crate::search::canonicalize::canonicalize_for_embedding;
 use crate::search::embedder::Embedder;
@@ -156,9 +158,121 @@ pub struct SearchFilters {
     /// Filter to specific session source paths (for chained searches)
     #[serde(skip_serializing_if = "HashSet::is_empty")]
     pub session_paths: HashSet<String>,
+    /// Filter by message role (canonical: user, agent, tool, system)
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub roles: HashSet<String>,
+    /// Filter to messages that invoked any of these tools (lowercased names)
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub tools: HashSet<String>,
+    /// Filter to sessions carrying any of these user tags (normalized names)
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub tags: HashSet<String>,
+    /// Filter to messages whose tool calls read or edited any of these files
+    /// (path as recorded, or relative to the session workspace)
+    #[serde(skip_serializing_if = "HashSet::is_empty")]
+    pub files: HashSet<String>,
+}
+
+/// Roles accepted by `--role` and `role:` (after alias normalization).
+pub const FILTER_ROLES: &[&str] = &["user", "agent", "tool", "system"];
+
+impl SearchFilters {
+    /// Add a role filter, normalizing aliases ("assistant" -> "agent").
+    ///
+    /// Fails for roles other than [`FILTER_ROLES`], which would match nothing.
+    pub fn add_role(&mut self, role: &str) -> Result<()> {
+        let role = canonical_role(role);
+        if role.is_empty() {
+            return Ok(());
+        }
+        if !FILTER_ROLES.contains(&role.as_str()) {
+            bail!(
+                "unknown role '{role}' (expected one of: {})",
+                FILTER_ROLES.join(", ")
+            );
+        }
+        self.roles.insert(role);
+        Ok(())
+    }
+
+    /// Add a tool-name filter (matched case-insensitively).
+    pub fn add_tool(&mut self, tool: &str) {
+        let tool = tool.trim().to_lowercase();
+        if !tool.is_empty() {
+            self.tools.insert(tool);
+        }
+    }
+
+    /// Add a session-tag filter.
+    ///
+    /// Fails for names no tag can have, rather than dropping the filter and
+    /// matching every session.
+    pub fn add_tag(&mut self, tag: &str) -> Result<()> {
+        if tag.trim().is_empty() {
+            return Ok(());
+        }
+        let Some(name) = Tag::normalize_name(tag) else {
+            bail!(
+                "invalid tag name '{}' (tags may contain letters, digits, '-', '_', '.' and '/')",
+                tag.trim()
+            );
+        };
+        self.tags.insert(name);
+        Ok(())
+    }
+
+    /// Add a touched-file filter (case-sensitive; a leading `./` is dropped).
+    pub fn add_file(&mut self, path: &str) {
+        let path = path.trim();
+        let path = path.strip_prefix("./").unwrap_or(path);
+        if !path.is_empty() {
+            self.files.insert(path.to_string());
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, clap::ValueEnum)]
+/// Strip inline `role:` / `tool:` / `tag:` / `file:` filters from a query and merge them into `filters`.
+///
+/// Values may be comma-separated (`role:user,agent`). Tokens inside quoted
+/// phrases are left untouched. Returns the remaining query text, or an error
+/// for a filter value that can never match (an unknown role or invalid tag).
+pub fn split_inline_filters(query: &str, filters: &mut SearchFilters) -> Result<String> {
+    let mut remaining: Vec<&str> = Vec::new();
+    let mut in_quotes = false;
+    for token in query.split_whitespace() {
+        if !in_quotes && let Some((key, value)) = token.split_once(':') {
+            let key = key.to_ascii_lowercase();
+            if matches!(key.as_str(), "role" | "tool" | "tag" | "file") && !value.is_empty() {
+                for v in value.split(',') {
+                    match key.as_str() {
+                        "role" => filters.add_role(v)?,
+                        "tool" => filters.add_tool(v),
+                        "file" => filters.add_file(v),
+                        _ => filters.add_tag(v)?,
+                    }
+                }
+                continue;
+            }
+        }
+        if token.matches('"').count() % 2 == 1 {
+            in_quotes = !in_quotes;
+        }
+        remaining.push(token);
+    }
+    Ok(remaining.join(" "))
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    clap::ValueEnum,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum SearchMode {
     /// Lexical (BM25) search - keyword matching
@@ -180,9 +294,53 @@ impl SearchMode {
     }
 }
 
+/// Unit of a search result: individual messages or whole conversations.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchGranularity {
+    /// One hit per matching message
+    #[default]
+    Message,
+    /// One hit per conversation, with the best messages as evidence
+    Session,
+}
+
+impl SearchGranularity {
+    pub fn next(self) -> Self {
+        match self {
+            SearchGranularity::Message => SearchGranularity::Session,
+            SearchGranularity::Session => SearchGranularity::Message,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SearchGranularity::Message => "message",
+            SearchGranularity::Session => "session",
+        }
+    }
+}
+
 const RRF_K: f32 = 60.0;
 const HYBRID_CANDIDATE_MULTIPLIER: usize = 3;
 const ANN_CANDIDATE_MULTIPLIER: usize = 4;
+/// Semantic candidates ranked per requested hit when tool/tag/file filters
+/// have to be checked in SQLite; the pool grows by the same factor per retry.
+const FILTERED_CANDIDATE_MULTIPLIER: usize = 8;
+/// Largest semantic candidate pool checked against tool/tag/file filters.
+const MAX_FILTERED_CANDIDATES: usize = 10_000;
+/// Message ids bound per SQLite filter lookup.
+const FILTER_ID_BATCH: usize = 500;
 
 // ============================================================================
 // Query Explanation types (--explain flag support)
@@ -407,7 +565,11 @@ impl QueryExplanation {
             || !filters.workspaces.is_empty()
             || filters.created_from.is_some()
             || filters.created_to.is_some()
-            || !filters.source_filter.is_all();
+            || !filters.source_filter.is_all()
+            || !filters.roles.is_empty()
+            || !filters.tools.is_empty()
+            || !filters.tags.is_empty()
+            || !filters.files.is_empty();
 
         if has_filters {
             return QueryType::Filtered;
@@ -525,6 +687,26 @@ impl QueryExplanation {
         if has_time_filter {
             parts.push("time range".to_string());
         }
+        if !filters.roles.is_empty() {
+            let mut roles: Vec<_> = filters.roles.iter().map(String::as_str).collect();
+            roles.sort_unstable();
+            parts.push(format!("role {}", roles.join("|")));
+        }
+        if !filters.tools.is_empty() {
+            let mut tools: Vec<_> = filters.tools.iter().map(String::as_str).collect();
+            tools.sort_unstable();
+            parts.push(format!("tool {}", tools.join("|")));
+        }
+        if !filters.tags.is_empty() {
+            let mut tags: Vec<_> = filters.tags.iter().map(String::as_str).collect();
+            tags.sort_unstable();
+            parts.push(format!("tag {}", tags.join("|")));
+        }
+        if !filters.files.is_empty() {
+            let mut files: Vec<_> = filters.files.iter().map(String::as_str).collect();
+            files.sort_unstable();
+            parts.push(format!("file {}", files.join("|")));
+        }
 
         let description = if parts.is_empty() {
             None
@@ -616,7 +798,7 @@ impl QueryExplanation {
 
 /// Indicates how a search result matched the query.
 /// Used for ranking: exact matches rank higher than wildcard matches.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MatchType {
     /// No wildcards - matched via exact term or edge n-gram prefix
@@ -649,7 +831,7 @@ impl MatchType {
 }
 
 /// Type of suggestion for did-you-mean
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SuggestionKind {
     /// Typo correction (Levenshtein distance)
@@ -785,6 +967,18 @@ impl FieldMask {
     pub fn allows_cache(self) -> bool {
         self.flags & Self::CACHE != 0
     }
+
+    /// Raw flag bits, for sending a mask over the daemon protocol.
+    pub fn bits(self) -> u8 {
+        self.flags
+    }
+
+    /// Rebuild a mask from [`FieldMask::bits`]; unknown bits are dropped.
+    pub fn from_bits(bits: u8) -> Self {
+        Self {
+            flags: bits & Self::FULL.flags,
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -1102,6 +1296,161 @@ pub fn rrf_fuse_hits(
     results
 }
 
+// =============================================================================
+// Session granularity
+// =============================================================================
+
+/// Message hits fetched per requested session when grouping, so that sessions
+/// further down the message ranking still get a fair match count.
+pub const SESSION_OVERFETCH: usize = 8;
+
+/// Evidence messages kept per session unless the caller asks otherwise.
+pub const DEFAULT_SESSION_EVIDENCE: usize = 3;
+
+/// Weight of the remaining (non-best) match mass relative to the best match.
+const SESSION_SUM_WEIGHT: f32 = 0.5;
+/// Weight of the log match count.
+const SESSION_COUNT_WEIGHT: f32 = 0.15;
+/// Maximum boost for a session active right now; halves every half-life.
+const SESSION_RECENCY_WEIGHT: f32 = 0.2;
+const SESSION_RECENCY_HALF_LIFE_DAYS: f32 = 30.0;
+
+/// A conversation aggregated from its matching messages.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionHit {
+    pub source_path: String,
+    pub agent: String,
+    pub workspace: String,
+    pub title: String,
+    /// Combined session score (see [`session_score`]).
+    pub score: f32,
+    /// Best single-message score.
+    pub max_score: f32,
+    /// Sum of all matching message scores.
+    pub sum_score: f32,
+    /// Number of matching messages in the fetched window.
+    pub match_count: usize,
+    /// Most recent matching message timestamp (ms).
+    pub latest_created_at: Option<i64>,
+    pub source_id: String,
+    pub origin_kind: String,
+    pub origin_host: Option<String>,
+    /// Top matching messages, best first.
+    pub evidence: Vec<SearchHit>,
+}
+
+impl SessionHit {
+    /// Collapse the session into a single hit for message-oriented views:
+    /// the best evidence message, carrying the session score and a match
+    /// count prefix on the snippet.
+    pub fn to_representative_hit(&self) -> Option<SearchHit> {
+        let mut hit = self.evidence.first()?.clone();
+        hit.score = self.score;
+        if self.match_count > 1 {
+            hit.snippet = format!("[{} matches] {}", self.match_count, hit.snippet);
+        }
+        Some(hit)
+    }
+}
+
+/// Score a session from its message scores.
+///
+/// The best match dominates; additional matches add diminishing credit
+/// (log of the remaining mass relative to the best, and log of the count) so
+/// long conversations cannot win on volume alone. Recent sessions get a small
+/// boost that decays with a 30-day half-life. The result is scale-free with
+/// respect to the underlying scorer, so BM25, cosine and RRF scores all work.
+pub fn session_score(
+    max_score: f32,
+    sum_score: f32,
+    match_count: usize,
+    latest_created_at: Option<i64>,
+    now_ms: i64,
+) -> f32 {
+    let mut score = max_score;
+    if max_score > 0.0 {
+        let rest = (sum_score - max_score).max(0.0) / max_score;
+        score *= 1.0 + SESSION_SUM_WEIGHT * rest.ln_1p();
+    }
+    if match_count > 1 {
+        score *= 1.0 + SESSION_COUNT_WEIGHT * (match_count as f32).ln();
+    }
+    if let Some(ts) = latest_created_at {
+        let age_days = (now_ms - ts).max(0) as f32 / 86_400_000.0;
+        let decay = 0.5f32.powf(age_days / SESSION_RECENCY_HALF_LIFE_DAYS);
+        score *= 1.0 + SESSION_RECENCY_WEIGHT * decay;
+    }
+    score
+}
+
+/// Group message hits into sessions keyed by `(source_id, source_path)`.
+///
+/// Sessions are ordered by [`session_score`] (ties: most recent first, then
+/// path). Each keeps its `evidence_per_session` best messages.
+pub fn group_hits_by_session(
+    hits: Vec<SearchHit>,
+    evidence_per_session: usize,
+    now_ms: i64,
+) -> Vec<SessionHit> {
+    let mut index: HashMap<(String, String), usize> = HashMap::new();
+    let mut sessions: Vec<SessionHit> = Vec::new();
+
+    for hit in hits {
+        let key = (hit.source_id.clone(), hit.source_path.clone());
+        let idx = *index.entry(key).or_insert_with(|| {
+            sessions.push(SessionHit {
+                source_path: hit.source_path.clone(),
+                agent: hit.agent.clone(),
+                workspace: hit.workspace.clone(),
+                title: hit.title.clone(),
+                score: 0.0,
+                max_score: f32::NEG_INFINITY,
+                sum_score: 0.0,
+                match_count: 0,
+                latest_created_at: None,
+                source_id: hit.source_id.clone(),
+                origin_kind: hit.origin_kind.clone(),
+                origin_host: hit.origin_host.clone(),
+                evidence: Vec::new(),
+            });
+            sessions.len() - 1
+        });
+        let session = &mut sessions[idx];
+        session.max_score = session.max_score.max(hit.score);
+        session.sum_score += hit.score;
+        session.match_count += 1;
+        session.latest_created_at = session.latest_created_at.max(hit.created_at);
+        if session.title.is_empty() && !hit.title.is_empty() {
+            session.title = hit.title.clone();
+        }
+        session.evidence.push(hit);
+    }
+
+    for session in &mut sessions {
+        session.score = session_score(
+            session.max_score,
+            session.sum_score,
+            session.match_count,
+            session.latest_created_at,
+            now_ms,
+        );
+        session.evidence.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(a.line_number.cmp(&b.line_number))
+        });
+        session.evidence.truncate(evidence_per_session.max(1));
+    }
+
+    sessions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.latest_created_at.cmp(&a.latest_created_at))
+            .then_with(|| a.source_path.cmp(&b.source_path))
+    });
+    sessions
+}
+
 struct QueryCache {
     embedder_id: String,
     embeddings: LruCache<String, Vec<f32>>,
@@ -1144,6 +1493,71 @@ struct SemanticSearchState {
     query_cache: QueryCache,
 }
 
+impl SemanticSearchState {
+    /// Top `pool` messages for `embedding` (best chunk per message), from the
+    /// HNSW graph when `approximate` and by exact scan otherwise.
+    fn rank_candidates(
+        &self,
+        embedding: &[f32],
+        filter: &SemanticFilter,
+        pool: usize,
+        approximate: bool,
+    ) -> Result<(
+        Vec<VectorSearchResult>,
+        Option<crate::search::ann_index::AnnSearchStats>,
+    )> {
+        if !approximate {
+            let hits = self
+                .index
+                .search_top_k_collapsed(embedding, pool, Some(filter))?;
+            return Ok((hits, None));
+        }
+
+        let ann = self
+            .ann_index
+            .as_ref()
+            .ok_or_else(|| anyhow!("HNSW index failed to initialize"))?;
+        let candidate = pool.saturating_mul(ANN_CANDIDATE_MULTIPLIER).max(pool);
+        let ef = DEFAULT_EF_SEARCH.max(candidate);
+        let (ann_results, search_stats) = ann.search_with_stats(embedding, candidate, ef)?;
+
+        let mut best_by_message: HashMap<u64, VectorSearchResult> = HashMap::new();
+        for ann_hit in ann_results {
+            // Tombstoned rows stay in the graph until compaction; skip them.
+            let row = match self.index.row_for_graph_id(ann_hit.row_idx) {
+                Some(row) => row,
+                None => continue,
+            };
+            if !filter.matches(row) {
+                continue;
+            }
+            let score = self.index.dot_product_row(row, embedding)?;
+            best_by_message
+                .entry(row.message_id)
+                .and_modify(|entry| {
+                    if score > entry.score {
+                        entry.score = score;
+                        entry.chunk_idx = row.chunk_idx;
+                    }
+                })
+                .or_insert(VectorSearchResult {
+                    message_id: row.message_id,
+                    chunk_idx: row.chunk_idx,
+                    score,
+                });
+        }
+
+        let mut ann_hits: Vec<VectorSearchResult> = best_by_message.into_values().collect();
+        ann_hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.message_id.cmp(&b.message_id))
+        });
+        ann_hits.truncate(pool);
+        Ok((ann_hits, Some(search_stats)))
+    }
+}
+
 pub struct SearchClient {
     reader: Option<(IndexReader, crate::search::tantivy::Fields)>,
     sqlite: Mutex<Option<Connection>>,
@@ -1177,7 +1591,7 @@ impl Default for SearchClientOptions {
     }
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct CacheStats {
     pub cache_hits: u64,
     pub cache_miss: u64,
@@ -1974,6 +2388,14 @@ fn build_term_query_clauses(
                     IndexRecordOption::WithFreqsAndPositions,
                 )),
             ));
+            // Shell commands run by the message's tool calls
+            shoulds.push((
+                Occur::Should,
+                Box::new(TermQuery::new(
+                    Term::from_field_text(fields.command, term),
+                    IndexRecordOption::WithFreqsAndPositions,
+                )),
+            ));
         }
         WildcardPattern::Suffix(term)
         | WildcardPattern::Substring(term)
@@ -2185,6 +2607,8 @@ impl SearchClient {
         offset: usize,
         field_mask: FieldMask,
     ) -> Result<Vec<SearchHit>> {
+        let mut filters = filters;
+        let query = &split_inline_filters(query, &mut filters)?;
         let sanitized = sanitize_query(query);
         let field_mask = effective_field_mask(field_mask);
         let can_use_cache = field_mask.allows_cache() && field_mask.needs_content();
@@ -2392,6 +2816,8 @@ impl SearchClient {
         Option<crate::search::ann_index::AnnSearchStats>,
     )> {
         let field_mask = effective_field_mask(field_mask);
+        let mut filters = filters;
+        let query = &split_inline_filters(query, &mut filters)?;
         let canonical = canonicalize_for_embedding(query);
         if canonical.trim().is_empty() {
             return Ok((Vec::new(), None));
@@ -2410,6 +2836,10 @@ impl SearchClient {
         let mut semantic_filter =
             SemanticFilter::from_search_filters(&filters, &state.filter_maps)?;
         if let Some(roles) = state.roles.clone() {
+            let roles = match semantic_filter.roles.take() {
+                Some(requested) => requested.intersection(&roles).copied().collect(),
+                None => roles,
+            };
             semantic_filter = semantic_filter.with_roles(Some(roles));
         }
 
@@ -2418,89 +2848,72 @@ impl SearchClient {
             return Ok((Vec::new(), None));
         }
 
-        // Track ANN stats if approximate search is used
-        let mut ann_stats: Option<crate::search::ann_index::AnnSearchStats> = None;
-
-        let mut results = if approximate {
-            if state.ann_index.is_none() {
-                let ann_path = state.ann_path.as_ref().ok_or_else(|| {
-                    anyhow!(
-                        "approximate search unavailable: HNSW index missing (run 'cass index --semantic --build-hnsw')"
-                    )
-                })?;
-                if !ann_path.is_file() {
-                    bail!(
-                        "approximate search unavailable: HNSW index not found at {}",
-                        ann_path.display()
-                    );
-                }
-                let ann = HnswIndex::load(ann_path)?;
-                let header = state.index.header();
-                if ann.embedder_id() != header.embedder_id {
-                    bail!(
-                        "HNSW index embedder mismatch: expected {}, got {}",
-                        header.embedder_id,
-                        ann.embedder_id()
-                    );
-                }
-                if ann.dimension() != header.dimension as usize {
-                    bail!(
-                        "HNSW index dimension mismatch: expected {}, got {}",
-                        header.dimension,
-                        ann.dimension()
-                    );
-                }
-                state.ann_index = Some(ann);
+        if approximate && state.ann_index.is_none() {
+            let ann_path = state.ann_path.as_ref().ok_or_else(|| {
+                anyhow!(
+                    "approximate search unavailable: HNSW index missing (run 'cass index --semantic --build-hnsw')"
+                )
+            })?;
+            if !ann_path.is_file() {
+                bail!(
+                    "approximate search unavailable: HNSW index not found at {}",
+                    ann_path.display()
+                );
             }
-
-            let ann = state
-                .ann_index
-                .as_ref()
-                .ok_or_else(|| anyhow!("HNSW index failed to initialize"))?;
-            let candidate = fetch.saturating_mul(ANN_CANDIDATE_MULTIPLIER).max(fetch);
-            let ef = DEFAULT_EF_SEARCH.max(candidate);
-            let (ann_results, search_stats) = ann.search_with_stats(&embedding, candidate, ef)?;
-            ann_stats = Some(search_stats);
-
-            let mut best_by_message: HashMap<u64, VectorSearchResult> = HashMap::new();
-            for ann_hit in ann_results {
-                let row = match state.index.rows().get(ann_hit.row_idx) {
-                    Some(row) => row,
-                    None => continue,
-                };
-                if !semantic_filter.matches(row) {
-                    continue;
-                }
-                let score = state.index.dot_product_row(row, &embedding)?;
-                best_by_message
-                    .entry(row.message_id)
-                    .and_modify(|entry| {
-                        if score > entry.score {
-                            entry.score = score;
-                            entry.chunk_idx = row.chunk_idx;
-                        }
-                    })
-                    .or_insert(VectorSearchResult {
-                        message_id: row.message_id,
-                        chunk_idx: row.chunk_idx,
-                        score,
-                    });
+            let ann = HnswIndex::load(ann_path)?;
+            let header = state.index.header();
+            if ann.embedder_id() != header.embedder_id {
+                bail!(
+                    "HNSW index embedder mismatch: expected {}, got {}",
+                    header.embedder_id,
+                    ann.embedder_id()
+                );
             }
-
-            let mut ann_hits: Vec<VectorSearchResult> = best_by_message.into_values().collect();
-            ann_hits.sort_by(|a, b| {
-                b.score
-                    .total_cmp(&a.score)
-                    .then_with(|| a.message_id.cmp(&b.message_id))
-            });
-            if ann_hits.len() > fetch {
-                ann_hits.truncate(fetch);
+            if ann.dimension() != header.dimension as usize {
+                bail!(
+                    "HNSW index dimension mismatch: expected {}, got {}",
+                    header.dimension,
+                    ann.dimension()
+                );
             }
-            ann_hits
+            state.ann_index = Some(ann);
+        }
+        let state = &*state;
+
+        // Tool, tag and file filters are not encoded in vector rows. Rank an
+        // over-fetched candidate pool and check it against SQLite in batches,
+        // widening the pool (up to a cap) while too few candidates pass.
+        let sql_filtered =
+            !filters.tools.is_empty() || !filters.tags.is_empty() || !filters.files.is_empty();
+        let mut pool = if sql_filtered {
+            fetch
+                .saturating_mul(FILTERED_CANDIDATE_MULTIPLIER)
+                .min(MAX_FILTERED_CANDIDATES)
+                .max(fetch)
         } else {
-            state
-                .index
-                .search_top_k_collapsed(&embedding, fetch, Some(&semantic_filter))?
+            fetch
+        };
+        let mut ann_stats: Option<crate::search::ann_index::AnnSearchStats>;
+        let mut results = loop {
+            let (candidates, stats) =
+                state.rank_candidates(&embedding, &semantic_filter, pool, approximate)?;
+            ann_stats = stats;
+            if !sql_filtered {
+                break candidates;
+            }
+            let exhausted = candidates.len() < pool;
+            let allowed = self.semantic_candidates_matching(&filters, &candidates)?;
+            let mut kept: Vec<VectorSearchResult> = candidates
+                .into_iter()
+                .filter(|candidate| allowed.contains(&candidate.message_id))
+                .collect();
+            if kept.len() >= fetch || exhausted || pool >= MAX_FILTERED_CANDIDATES {
+                kept.truncate(fetch);
+                break kept;
+            }
+            pool = pool
+                .saturating_mul(FILTERED_CANDIDATE_MULTIPLIER)
+                .min(MAX_FILTERED_CANDIDATES);
         };
         if offset > 0 {
             results = results.into_iter().skip(offset).collect();
@@ -2519,6 +2932,98 @@ impl SearchClient {
         Ok((hits, ann_stats))
     }
 
+    /// Message ids among `candidates` that pass every tool, tag and file
+    /// filter that is set, looked up in batches of [`FILTER_ID_BATCH`].
+    fn semantic_candidates_matching(
+        &self,
+        filters: &SearchFilters,
+        candidates: &[VectorSearchResult],
+    ) -> Result<HashSet<u64>> {
+        let lookups: [(&HashSet<String>, &str); 3] = [
+            (
+                &filters.tools,
+                "SELECT DISTINCT message_id FROM message_tools
+                 WHERE message_id IN ({ids}) AND tool IN ({values})",
+            ),
+            (
+                &filters.tags,
+                "SELECT DISTINCT m.id FROM messages m
+                 JOIN conversation_tags ct ON ct.conversation_id = m.conversation_id
+                 JOIN tags tg ON tg.id = ct.tag_id
+                 WHERE m.id IN ({ids}) AND tg.name IN ({values})",
+            ),
+            (
+                &filters.files,
+                "SELECT DISTINCT e.message_id FROM tool_events e
+                 JOIN messages m ON m.id = e.message_id
+                 JOIN conversations c ON c.id = m.conversation_id
+                 LEFT JOIN workspaces w ON w.id = c.workspace_id
+                 WHERE e.message_id IN ({ids})
+                   AND (e.path IN ({values})
+                        OR (substr(e.path, 1, length(w.path) + 1) = w.path || '/'
+                            AND substr(e.path, length(w.path) + 2) IN ({values})))",
+            ),
+        ];
+        let mut allowed = HashSet::new();
+        for batch in candidates.chunks(FILTER_ID_BATCH) {
+            let ids = batch
+                .iter()
+                .map(|candidate| i64::try_from(candidate.message_id))
+                .collect::<Result<Vec<i64>, _>>()?;
+            let mut batch_allowed: Option<HashSet<u64>> = None;
+            for (values, sql) in lookups {
+                if values.is_empty() {
+                    continue;
+                }
+                let matched = self.message_ids_matching(sql, &ids, values)?;
+                batch_allowed = Some(match batch_allowed {
+                    Some(previous) => previous.intersection(&matched).copied().collect(),
+                    None => matched,
+                });
+            }
+            allowed.extend(batch_allowed.unwrap_or_default());
+        }
+        Ok(allowed)
+    }
+
+    /// Return the message ids selected by `sql_template`. Its single `{ids}`
+    /// placeholder must come first and is bound to `ids`; each later
+    /// `{values}` placeholder is bound to `values`.
+    fn message_ids_matching(
+        &self,
+        sql_template: &str,
+        ids: &[i64],
+        values: &HashSet<String>,
+    ) -> Result<HashSet<u64>> {
+        let sqlite_guard = self.sqlite_guard()?;
+        let conn = sqlite_guard
+            .as_ref()
+            .ok_or_else(|| anyhow!("tool/tag filtering requires database connection"))?;
+
+        let value_uses = sql_template.matches("{values}").count();
+        let sql = sql_template
+            .replace("{ids}", &sql_placeholders(ids.len()))
+            .replace("{values}", &sql_placeholders(values.len()));
+        let mut params: Vec<rusqlite::types::Value> =
+            Vec::with_capacity(ids.len() + values.len() * value_uses);
+        params.extend(ids.iter().map(|&id| rusqlite::types::Value::from(id)));
+        for _ in 0..value_uses {
+            for value in values {
+                params.push(value.clone().into());
+            }
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params), |row| {
+            row.get::<_, i64>(0)
+        })?;
+        let mut matched = HashSet::new();
+        for row in rows {
+            matched.insert(row? as u64);
+        }
+        Ok(matched)
+    }
+
     fn hydrate_semantic_hits(
         &self,
         results: &[VectorSearchResult],
@@ -2649,6 +3154,8 @@ impl SearchClient {
         sparse_threshold: usize,
         field_mask: FieldMask,
     ) -> Result<SearchResult> {
+        let mut filters = filters;
+        let query = &split_inline_filters(query, &mut filters)?;
         // First, try the normal search
         let hits = self.search(query, filters.clone(), limit, offset, field_mask)?;
         let baseline_stats = self.cache_stats();
@@ -2974,6 +3481,74 @@ impl SearchClient {
             clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
         }
 
+        if !filters.roles.is_empty() {
+            let terms = filters
+                .roles
+                .iter()
+                .map(|role| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.role, role),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+
+        if !filters.tools.is_empty() {
+            let terms = filters
+                .tools
+                .iter()
+                .map(|tool| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.tool, tool),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+
+        if !filters.tags.is_empty() {
+            let terms = filters
+                .tags
+                .iter()
+                .map(|tag| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.tag, tag),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+
+        if !filters.files.is_empty() {
+            let terms = filters
+                .files
+                .iter()
+                .map(|file| {
+                    (
+                        Occur::Should,
+                        Box::new(TermQuery::new(
+                            Term::from_field_text(fields.file, file),
+                            IndexRecordOption::Basic,
+                        )) as Box<dyn Query>,
+                    )
+                })
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(terms))));
+        }
+
         if filters.created_from.is_some() || filters.created_to.is_some() {
             use std::ops::Bound::{Included, Unbounded};
             let lower = filters.created_from.map_or(Unbounded, |v| {
@@ -3204,6 +3779,46 @@ impl SearchClient {
             }
         }
 
+        if !filters.roles.is_empty() {
+            let placeholders = sql_placeholders(filters.roles.len());
+            sql.push_str(&format!(" AND m.role IN ({placeholders})"));
+            for r in filters.roles {
+                params.push(Box::new(r));
+            }
+        }
+
+        if !filters.tools.is_empty() {
+            let placeholders = sql_placeholders(filters.tools.len());
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM message_tools t WHERE t.message_id = f.message_id AND t.tool IN ({placeholders}))"
+            ));
+            for t in filters.tools {
+                params.push(Box::new(t));
+            }
+        }
+
+        if !filters.tags.is_empty() {
+            let placeholders = sql_placeholders(filters.tags.len());
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM conversation_tags ct JOIN tags tg ON tg.id = ct.tag_id WHERE ct.conversation_id = m.conversation_id AND tg.name IN ({placeholders}))"
+            ));
+            for t in filters.tags {
+                params.push(Box::new(t));
+            }
+        }
+
+        if !filters.files.is_empty() {
+            let placeholders = sql_placeholders(filters.files.len());
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM tool_events e WHERE e.message_id = f.message_id AND (e.path IN ({placeholders}) OR (substr(e.path, 1, length(f.workspace) + 1) = f.workspace || '/' AND substr(e.path, length(f.workspace) + 2) IN ({placeholders}))))"
+            ));
+            for _ in 0..2 {
+                for file in &filters.files {
+                    params.push(Box::new(file.clone()));
+                }
+            }
+        }
+
         if let Some(created_from) = filters.created_from {
             sql.push_str(" AND f.created_at >= ?");
             params.push(Box::new(created_from));
@@ -3783,6 +4398,26 @@ fn filters_fingerprint(filters: &SearchFilters) -> String {
         v.sort();
         parts.push(format!("sp:{v:?}"));
     }
+    if !filters.roles.is_empty() {
+        let mut v: Vec<_> = filters.roles.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("r:{v:?}"));
+    }
+    if !filters.tools.is_empty() {
+        let mut v: Vec<_> = filters.tools.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("t:{v:?}"));
+    }
+    if !filters.tags.is_empty() {
+        let mut v: Vec<_> = filters.tags.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("g:{v:?}"));
+    }
+    if !filters.files.is_empty() {
+        let mut v: Vec<_> = filters.files.iter().cloned().collect();
+        v.sort();
+        parts.push(format!("fi:{v:?}"));
+    }
     parts.join("|")
 }
 
@@ -8143,6 +8778,364 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn split_inline_filters_extracts_role_and_tool() {
+        let mut filters = SearchFilters::default();
+        let rest =
+            split_inline_filters("role:assistant tool:Bash,Edit fix build", &mut filters).unwrap();
+        assert_eq!(rest, "fix build");
+        assert_eq!(filters.roles, HashSet::from(["agent".to_string()]));
+        assert_eq!(
+            filters.tools,
+            HashSet::from(["bash".to_string(), "edit".to_string()])
+        );
+
+        // Quoted phrases and unrelated field prefixes are preserved
+        let mut filters = SearchFilters::default();
+        let rest =
+            split_inline_filters("\"role:user is literal\" agent:codex", &mut filters).unwrap();
+        assert_eq!(rest, "\"role:user is literal\" agent:codex");
+        assert!(filters.roles.is_empty());
+
+        // Unknown roles are errors rather than filters that match nothing
+        let mut filters = SearchFilters::default();
+        let err = split_inline_filters("role:user,foo build", &mut filters).unwrap_err();
+        assert!(err.to_string().contains("unknown role 'foo'"));
+        assert!(filters.add_role("Assistant").is_ok());
+        assert!(filters.add_role("tool").is_ok());
+        assert!(filters.add_role("robot").is_err());
+        assert_eq!(
+            filters.roles,
+            HashSet::from(["user".to_string(), "agent".to_string(), "tool".to_string()])
+        );
+    }
+
+    #[test]
+    fn search_role_and_tool_filters() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+
+        let conv = NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: None,
+            title: Some("role tool session".into()),
+            workspace: Some(std::path::PathBuf::from("/ws")),
+            source_path: dir.path().join("session.jsonl"),
+            started_at: Some(100),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![
+                NormalizedMessage {
+                    idx: 0,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(100),
+                    content: "deploy the service please".into(),
+                    extra: serde_json::json!({}),
+                    snippets: vec![],
+                },
+                NormalizedMessage {
+                    idx: 1,
+                    role: "assistant".into(),
+                    author: None,
+                    created_at: Some(101),
+                    content: "deploy started\n[Tool: Bash - run deploy script]".into(),
+                    extra: serde_json::json!({}),
+                    snippets: vec![],
+                },
+                NormalizedMessage {
+                    idx: 2,
+                    role: "assistant".into(),
+                    author: None,
+                    created_at: Some(102),
+                    content: "deploy config updated".into(),
+                    extra: serde_json::json!({"content": [{"type": "tool_use", "name": "Edit"}]}),
+                    snippets: vec![],
+                },
+            ],
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+
+        let mut filters = SearchFilters::default();
+        filters.add_role("user")?;
+        let hits = client.search("deploy", filters, 10, 0, FieldMask::FULL)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(1));
+
+        let mut filters = SearchFilters::default();
+        filters.add_tool("bash");
+        let hits = client.search("deploy", filters, 10, 0, FieldMask::FULL)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(2));
+
+        // Inline syntax resolves to the same filters
+        let hits = client.search(
+            "deploy role:agent tool:edit",
+            SearchFilters::default(),
+            10,
+            0,
+            FieldMask::FULL,
+        )?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn semantic_tool_filter_reaches_beyond_unfiltered_top_k() -> Result<()> {
+        use crate::search::hash_embedder::HashEmbedder;
+        use crate::search::vector_index::{Quantization, VectorEntry, role_code_from_str};
+        use crate::storage::sqlite::SqliteStorage;
+
+        let dir = TempDir::new()?;
+        let db_path = dir.path().join("agent_search.db");
+        let index_path = dir.path().join("index");
+        let mut storage = SqliteStorage::open(&db_path)?;
+        let mut index = TantivyIndex::open_or_create(&index_path)?;
+
+        // Many close matches without tools, and one distant message that ran Bash.
+        let mut messages: Vec<NormalizedMessage> = (0..6)
+            .map(|idx| NormalizedMessage {
+                idx,
+                role: "assistant".into(),
+                author: None,
+                created_at: Some(100 + idx),
+                content: format!("deploy pipeline rollout step {idx}"),
+                extra: serde_json::json!({}),
+                snippets: vec![],
+            })
+            .collect();
+        messages.push(NormalizedMessage {
+            idx: 6,
+            role: "assistant".into(),
+            author: None,
+            created_at: Some(106),
+            content: "listing the build directory".into(),
+            extra: serde_json::json!({"content": [{"type": "tool_use", "name": "Bash"}]}),
+            snippets: vec![],
+        });
+        let conv = NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: Some("semantic-tools".into()),
+            title: None,
+            workspace: None,
+            source_path: dir.path().join("session.jsonl"),
+            started_at: Some(100),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages,
+        };
+        crate::indexer::persist::persist_conversation(&mut storage, &mut index, &conv)?;
+        index.commit()?;
+
+        let embedder = HashEmbedder::new(64);
+        let mut entries = Vec::new();
+        let mut stmt = storage
+            .raw()
+            .prepare("SELECT id, role, content, created_at FROM messages")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, role, content, created_at) = row?;
+            entries.push(VectorEntry {
+                message_id: id as u64,
+                created_at_ms: created_at.unwrap_or_default(),
+                agent_id: 0,
+                workspace_id: 0,
+                source_id: 0,
+                role: role_code_from_str(&role).unwrap_or_default(),
+                chunk_idx: 0,
+                content_hash: [0; 32],
+                vector: embedder.embed(&canonicalize_for_embedding(&content))?,
+            });
+        }
+        drop(stmt);
+        let vectors = VectorIndex::build(embedder.id(), "1", 64, Quantization::F32, entries)?;
+        let filter_maps = SemanticFilterMaps::from_storage(&storage)?;
+        let ann_path = dir.path().join("vectors.hnsw");
+        HnswIndex::build_from_vector_index(&vectors, 16, 200)?.save(&ann_path)?;
+
+        let client = SearchClient::open(&index_path, Some(&db_path))?.expect("index present");
+        client.set_semantic_context(
+            Arc::new(embedder),
+            vectors,
+            filter_maps,
+            None,
+            Some(ann_path),
+        )?;
+
+        let query = "deploy pipeline rollout";
+        let (unfiltered, _) = client.search_semantic(
+            query,
+            SearchFilters::default(),
+            2,
+            0,
+            FieldMask::FULL,
+            false,
+        )?;
+        assert_eq!(unfiltered.len(), 2);
+        assert!(unfiltered.iter().all(|h| h.line_number != Some(7)));
+
+        let mut filters = SearchFilters::default();
+        filters.add_tool("bash");
+        let (hits, _) = client.search_semantic(query, filters, 2, 0, FieldMask::FULL, false)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(7));
+
+        // The ANN path stays approximate and checks its candidate pool.
+        let (hits, ann_stats) = client.search_semantic(
+            "deploy pipeline rollout tool:bash",
+            SearchFilters::default(),
+            2,
+            0,
+            FieldMask::FULL,
+            true,
+        )?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(7));
+        assert!(ann_stats.is_some());
+
+        let mut filters = SearchFilters::default();
+        filters.add_tool("no-such-tool");
+        let (hits, _) = client.search_semantic(query, filters, 2, 0, FieldMask::FULL, false)?;
+        assert!(hits.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn search_file_filter_and_command_terms() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+
+        let edit = |idx: i64, path: &str| NormalizedMessage {
+            idx,
+            role: "assistant".into(),
+            author: None,
+            created_at: Some(100 + idx),
+            content: format!("refactor step {idx}"),
+            extra: serde_json::json!({"content": [
+                {"type": "tool_use", "id": format!("t{idx}"), "name": "Edit", "input": {"file_path": path}}
+            ]}),
+            snippets: vec![],
+        };
+        let conv = NormalizedConversation {
+            agent_slug: "claude_code".into(),
+            external_id: None,
+            title: Some("refactor session".into()),
+            workspace: Some(std::path::PathBuf::from("/ws")),
+            source_path: dir.path().join("session.jsonl"),
+            started_at: Some(100),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: vec![
+                edit(0, "/ws/src/lib.rs"),
+                edit(1, "/ws/src/main.rs"),
+                NormalizedMessage {
+                    idx: 2,
+                    role: "assistant".into(),
+                    author: None,
+                    created_at: Some(103),
+                    content: "running the suite now".into(),
+                    extra: serde_json::json!({"content": [
+                        {"type": "tool_use", "id": "t2", "name": "Bash", "input": {"command": "cargo nextest run"}}
+                    ]}),
+                    snippets: vec![],
+                },
+            ],
+        };
+        index.add_conversation(&conv)?;
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+
+        // Workspace-relative and absolute paths both match
+        for query in ["refactor file:src/lib.rs", "refactor file:/ws/src/lib.rs"] {
+            let hits = client.search(query, SearchFilters::default(), 10, 0, FieldMask::FULL)?;
+            assert_eq!(hits.len(), 1, "query {query}");
+            assert_eq!(hits[0].line_number, Some(1));
+        }
+
+        let hits = client.search("nextest", SearchFilters::default(), 10, 0, FieldMask::FULL)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn search_tag_filter() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut index = TantivyIndex::open_or_create(dir.path())?;
+
+        for (i, tags) in [vec!["auth-bug"], vec![]].into_iter().enumerate() {
+            let conv = NormalizedConversation {
+                agent_slug: "codex".into(),
+                external_id: None,
+                title: Some(format!("session-{i}")),
+                workspace: None,
+                source_path: dir.path().join(format!("session-{i}.jsonl")),
+                started_at: Some(100),
+                ended_at: None,
+                metadata: serde_json::json!({"cass": {"tags": tags}}),
+                messages: vec![NormalizedMessage {
+                    idx: 0,
+                    role: "user".into(),
+                    author: None,
+                    created_at: Some(100),
+                    content: "login token refresh".into(),
+                    extra: serde_json::json!({}),
+                    snippets: vec![],
+                }],
+            };
+            index.add_conversation(&conv)?;
+        }
+        index.commit()?;
+
+        let client = SearchClient::open(dir.path(), None)?.expect("index present");
+
+        let mut filters = SearchFilters::default();
+        filters.add_tag("#Auth-Bug")?;
+        let hits = client.search("login", filters, 10, 0, FieldMask::FULL)?;
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].source_path.ends_with("session-0.jsonl"));
+
+        let hits = client.search(
+            "login tag:auth-bug",
+            SearchFilters::default(),
+            10,
+            0,
+            FieldMask::FULL,
+        )?;
+        assert_eq!(hits.len(), 1);
+
+        // Names no tag can have are errors, not filters that are dropped
+        let mut filters = SearchFilters::default();
+        assert!(filters.add_tag("two words").is_err());
+        let err = client
+            .search(
+                "login tag:a:b",
+                SearchFilters::default(),
+                10,
+                0,
+                FieldMask::FULL,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid tag name 'a:b'"));
+        assert!(filters.tags.is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn search_session_paths_empty_filter_returns_all() -> Result<()> {
         // Empty session_paths filter should not restrict results
@@ -8402,6 +9395,87 @@ mod tests {
         }
     }
 
+    // ==========================================================================
+    // Session granularity
+    // ==========================================================================
+
+    fn session_hit(path: &str, line: usize, score: f32) -> SearchHit {
+        let mut hit = make_test_hit(&format!("{path}:{line}"), score);
+        hit.source_path = format!("/path/{path}.jsonl");
+        hit.line_number = Some(line);
+        hit
+    }
+
+    #[test]
+    fn group_hits_by_session_collapses_messages_and_keeps_top_evidence() {
+        let hits = vec![
+            session_hit("a", 1, 5.0),
+            session_hit("b", 1, 9.0),
+            session_hit("a", 2, 7.0),
+            session_hit("a", 3, 1.0),
+            session_hit("a", 4, 3.0),
+        ];
+        let sessions = group_hits_by_session(hits, 2, 1_700_000_000_000);
+
+        assert_eq!(sessions.len(), 2);
+        let a = sessions
+            .iter()
+            .find(|s| s.source_path == "/path/a.jsonl")
+            .unwrap();
+        assert_eq!(a.match_count, 4);
+        assert_eq!(a.max_score, 7.0);
+        assert_eq!(a.sum_score, 16.0);
+        let lines: Vec<_> = a.evidence.iter().map(|h| h.line_number).collect();
+        assert_eq!(lines, vec![Some(2), Some(1)]);
+    }
+
+    #[test]
+    fn session_score_rewards_breadth_without_drowning_best_match() {
+        let now = 1_700_000_000_000;
+        let single = session_score(10.0, 10.0, 1, None, now);
+        let broad = session_score(10.0, 25.0, 4, None, now);
+        assert!(broad > single);
+        // Many weak matches should not beat a much stronger single match.
+        let noisy = session_score(2.0, 40.0, 20, None, now);
+        assert!(noisy < single);
+    }
+
+    #[test]
+    fn session_score_prefers_recent_sessions() {
+        let now = 1_700_000_000_000;
+        let day = 86_400_000;
+        let fresh = session_score(1.0, 1.0, 1, Some(now - day), now);
+        let stale = session_score(1.0, 1.0, 1, Some(now - 365 * day), now);
+        assert!(fresh > stale);
+        // Future timestamps (clock skew) are treated as "now".
+        assert_eq!(session_score(1.0, 1.0, 1, Some(now + day), now), 1.2);
+    }
+
+    #[test]
+    fn session_ranking_is_scale_free_for_rrf_scores() {
+        // RRF scores are tiny; grouping must still order by the fused ranking.
+        let hits = vec![
+            session_hit("x", 1, 0.0164),
+            session_hit("y", 1, 0.0322),
+            session_hit("x", 2, 0.0161),
+        ];
+        let sessions = group_hits_by_session(hits, 3, 1_700_000_000_000);
+        assert_eq!(sessions[0].source_path, "/path/y.jsonl");
+        assert_eq!(sessions[1].match_count, 2);
+    }
+
+    #[test]
+    fn representative_hit_carries_session_score_and_count() {
+        let mut best = session_hit("a", 2, 7.0);
+        best.snippet = "best **match**".into();
+        let sessions =
+            group_hits_by_session(vec![session_hit("a", 1, 5.0), best], 3, 1_700_000_000_000);
+        let hit = sessions[0].to_representative_hit().unwrap();
+        assert_eq!(hit.line_number, Some(2));
+        assert_eq!(hit.score, sessions[0].score);
+        assert_eq!(hit.snippet, "[2 matches] best **match**");
+    }
+
     // ==========================================================================
     // QueryTokenList SmallVec Tests (Opt 4.4)
     // ==========================================================================
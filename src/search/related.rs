@@ -0,0 +1,303 @@
+//! Rank sessions related to a source file for `cass related`.
+//!
+//! Four signals feed one score per session:
+//!
+//! - structured references to the file: tool-call reads and edits, and message
+//!   snippets whose `file_path` is the file (snippets covering the requested
+//!   line count most);
+//! - whether the session's workspace contains the file;
+//! - lexical hits on a symbol name;
+//! - semantic similarity to the code around the requested line.
+//!
+//! Counts are log-squashed so one chatty session cannot drown out the rest,
+//! and semantic scores are taken relative to the best semantic hit.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::search::query::SearchHit;
+use crate::storage::sqlite::FileReferences;
+
+const STRUCTURED_WEIGHT: f32 = 1.5;
+const WORKSPACE_WEIGHT: f32 = 0.75;
+const SYMBOL_WEIGHT: f32 = 1.0;
+const SEMANTIC_WEIGHT: f32 = 2.0;
+
+/// Lines either side of `--line` used as the semantic query.
+pub const CODE_WINDOW_RADIUS: usize = 6;
+const CODE_WINDOW_MAX_CHARS: usize = 2000;
+
+/// One ranked session and the evidence behind its score.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RelatedSession {
+    pub source_path: String,
+    pub title: Option<String>,
+    pub agent: String,
+    pub workspace: Option<String>,
+    pub score: f32,
+    /// Best line to open the session at (1-indexed)
+    pub line_number: Option<usize>,
+    pub edits: i64,
+    pub reads: i64,
+    pub snippets: i64,
+    pub line_snippets: i64,
+    pub workspace_match: bool,
+    pub symbol_hits: usize,
+    /// Similarity relative to the best semantic hit, in `0.0..=1.0`
+    pub semantic_score: Option<f32>,
+    pub last_activity: Option<i64>,
+}
+
+impl RelatedSession {
+    fn from_hit(hit: &SearchHit) -> Self {
+        Self {
+            source_path: hit.source_path.clone(),
+            title: (!hit.title.is_empty()).then(|| hit.title.clone()),
+            agent: hit.agent.clone(),
+            workspace: (!hit.workspace.is_empty()).then(|| hit.workspace.clone()),
+            last_activity: hit.created_at,
+            ..Self::default()
+        }
+    }
+
+    fn compute_score(&self) -> f32 {
+        let refs = 3.0 * self.line_snippets as f32
+            + 2.0 * self.edits as f32
+            + self.snippets as f32
+            + 0.5 * self.reads as f32;
+        let mut score = STRUCTURED_WEIGHT * refs.ln_1p();
+        if self.workspace_match {
+            score += WORKSPACE_WEIGHT;
+        }
+        score += SYMBOL_WEIGHT * (self.symbol_hits as f32).ln_1p();
+        score += SEMANTIC_WEIGHT * self.semantic_score.unwrap_or(0.0);
+        score
+    }
+}
+
+/// Collects signals per session, then ranks them.
+#[derive(Debug, Default)]
+pub struct RelatedRanker {
+    sessions: Vec<RelatedSession>,
+    by_path: HashMap<String, usize>,
+}
+
+impl RelatedRanker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entry(
+        &mut self,
+        source_path: &str,
+        init: impl FnOnce() -> RelatedSession,
+    ) -> &mut RelatedSession {
+        let idx = match self.by_path.get(source_path) {
+            Some(&idx) => idx,
+            None => {
+                self.sessions.push(init());
+                self.by_path
+                    .insert(source_path.to_string(), self.sessions.len() - 1);
+                self.sessions.len() - 1
+            }
+        };
+        &mut self.sessions[idx]
+    }
+
+    pub fn add_file_references(&mut self, refs: &[FileReferences]) {
+        for r in refs {
+            let session = self.entry(&r.source_path, || RelatedSession {
+                source_path: r.source_path.clone(),
+                title: r.title.clone(),
+                agent: r.agent.clone(),
+                workspace: r.workspace.clone(),
+                ..RelatedSession::default()
+            });
+            session.edits += r.edits;
+            session.reads += r.reads;
+            session.snippets += r.snippets;
+            session.line_snippets += r.line_snippets;
+            session.line_number = session.line_number.or(usize::try_from(r.line_number).ok());
+            session.last_activity = session.last_activity.max(r.last_referenced);
+        }
+    }
+
+    /// Lexical hits for the symbol; each message hit counts once.
+    pub fn add_symbol_hits(&mut self, hits: &[SearchHit]) {
+        for hit in hits {
+            let session = self.entry(&hit.source_path, || RelatedSession::from_hit(hit));
+            session.symbol_hits += 1;
+            session.line_number = session.line_number.or(hit.line_number);
+        }
+    }
+
+    /// Semantic hits for the surrounding code; a session keeps its best hit.
+    pub fn add_semantic_hits(&mut self, hits: &[SearchHit]) {
+        let best = hits.iter().map(|hit| hit.score).fold(0.0_f32, f32::max);
+        if best <= 0.0 {
+            return;
+        }
+        for hit in hits {
+            let relative = (hit.score / best).clamp(0.0, 1.0);
+            let session = self.entry(&hit.source_path, || RelatedSession::from_hit(hit));
+            if session.semantic_score.is_none_or(|score| relative > score) {
+                session.semantic_score = Some(relative);
+            }
+            session.line_number = session.line_number.or(hit.line_number);
+        }
+    }
+
+    /// Score every session and return the best `limit`.
+    ///
+    /// `file` is the absolute path of the file, when known, for the
+    /// workspace signal.
+    pub fn rank(self, file: Option<&Path>, limit: usize) -> Vec<RelatedSession> {
+        let mut sessions = self.sessions;
+        for session in &mut sessions {
+            session.workspace_match = match (file, session.workspace.as_deref()) {
+                (Some(file), Some(workspace)) => workspace_contains(workspace, file),
+                _ => false,
+            };
+            session.score = session.compute_score();
+        }
+        sessions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.last_activity.cmp(&a.last_activity))
+                .then_with(|| a.source_path.cmp(&b.source_path))
+        });
+        sessions.truncate(limit);
+        sessions
+    }
+}
+
+/// Whether `file` (absolute) lies inside `workspace`.
+pub fn workspace_contains(workspace: &str, file: &Path) -> bool {
+    let workspace = Path::new(workspace);
+    workspace.is_absolute() && file.is_absolute() && file.starts_with(workspace)
+}
+
+/// The code within [`CODE_WINDOW_RADIUS`] lines of `line` (1-indexed), for
+/// use as a semantic query. `None` when the window is blank.
+pub fn code_window(content: &str, line: usize) -> Option<String> {
+    let start = line.saturating_sub(CODE_WINDOW_RADIUS + 1);
+    let mut window = String::new();
+    for text in content.lines().skip(start).take(CODE_WINDOW_RADIUS * 2 + 1) {
+        if window.len() + text.len() > CODE_WINDOW_MAX_CHARS {
+            break;
+        }
+        window.push_str(text.trim());
+        window.push('\n');
+    }
+    let window = window.trim();
+    (!window.is_empty()).then(|| window.to_string())
+}
+
+/// First line (1-indexed) mentioning `symbol`, to anchor the code window
+/// when no line was given.
+pub fn symbol_line(content: &str, symbol: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|text| text.contains(symbol))
+        .map(|idx| idx + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::MatchType;
+
+    fn refs(path: &str, edits: i64, reads: i64, line_snippets: i64) -> FileReferences {
+        FileReferences {
+            source_path: path.to_string(),
+            title: Some(format!("session {path}")),
+            agent: "codex".to_string(),
+            workspace: Some("/repo".to_string()),
+            edits,
+            reads,
+            snippets: line_snippets,
+            line_snippets,
+            line_number: 3,
+            last_referenced: Some(1_000),
+        }
+    }
+
+    fn hit(path: &str, score: f32, line: usize) -> SearchHit {
+        SearchHit {
+            title: String::new(),
+            snippet: String::new(),
+            content: String::new(),
+            content_hash: 0,
+            score,
+            source_path: path.to_string(),
+            agent: "claude_code".to_string(),
+            workspace: "/elsewhere".to_string(),
+            workspace_original: None,
+            created_at: Some(2_000),
+            line_number: Some(line),
+            match_type: MatchType::default(),
+            source_id: "local".to_string(),
+            origin_kind: "local".to_string(),
+            origin_host: None,
+        }
+    }
+
+    #[test]
+    fn structured_references_outrank_incidental_mentions() {
+        let mut ranker = RelatedRanker::new();
+        ranker.add_file_references(&[refs("/a.jsonl", 2, 1, 1), refs("/b.jsonl", 0, 1, 0)]);
+        ranker.add_symbol_hits(&[hit("/c.jsonl", 9.0, 7), hit("/b.jsonl", 4.0, 9)]);
+        let ranked = ranker.rank(Some(Path::new("/repo/src/foo.rs")), 10);
+
+        let order: Vec<&str> = ranked.iter().map(|s| s.source_path.as_str()).collect();
+        assert_eq!(order, ["/a.jsonl", "/b.jsonl", "/c.jsonl"]);
+        assert!(ranked[0].workspace_match);
+        assert!(!ranked[2].workspace_match);
+        // References keep their own jump line; symbol-only sessions use the hit's.
+        assert_eq!(ranked[1].line_number, Some(3));
+        assert_eq!(ranked[1].symbol_hits, 1);
+        assert_eq!(ranked[2].line_number, Some(7));
+        assert_eq!(ranked[2].title, None);
+    }
+
+    #[test]
+    fn semantic_scores_are_relative_and_keep_the_best_hit() {
+        let mut ranker = RelatedRanker::new();
+        ranker.add_semantic_hits(&[
+            hit("/a.jsonl", 0.8, 1),
+            hit("/b.jsonl", 0.4, 2),
+            hit("/a.jsonl", 0.2, 5),
+        ]);
+        let ranked = ranker.rank(None, 1);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].source_path, "/a.jsonl");
+        assert_eq!(ranked[0].semantic_score, Some(1.0));
+        assert_eq!(ranked[0].line_number, Some(1));
+    }
+
+    #[test]
+    fn workspace_match_requires_absolute_containment() {
+        let file = Path::new("/home/me/repo/src/lib.rs");
+        assert!(workspace_contains("/home/me/repo", file));
+        assert!(!workspace_contains("/home/me/repo2", file));
+        assert!(!workspace_contains("repo", file));
+        assert!(!workspace_contains(
+            "/home/me/repo",
+            Path::new("src/lib.rs")
+        ));
+    }
+
+    #[test]
+    fn code_window_centres_on_line() {
+        let content: String = (1..=30).map(|n| format!("  line {n}\n")).collect();
+        let window = code_window(&content, 10).unwrap();
+        assert!(window.starts_with("line 4\n"));
+        assert!(window.ends_with("line 16"));
+        assert!(code_window(&content, 1).unwrap().starts_with("line 1\n"));
+        assert_eq!(code_window(&content, 200), None);
+        assert_eq!(symbol_line(&content, "line 12"), Some(12));
+        assert_eq!(symbol_line(&content, "missing"), None);
+    }
+}
@@ -8,10 +8,12 @@ use tantivy::schema::{
     FAST, Field, INDEXED, IndexRecordOption, STORED, STRING, Schema, TEXT, TextFieldIndexing,
     TextOptions,
 };
-use tantivy::{Index, IndexReader, IndexWriter, doc};
+use tantivy::{Index, IndexReader, IndexWriter, Term, doc};
 use tracing::{debug, info, warn};
 
-use crate::connectors::NormalizedConversation;
+use crate::connectors::{
+    NormalizedConversation, ToolEventKind, canonical_role, extract_tool_events, extract_tool_names,
+};
 use crate::sources::provenance::LOCAL_SOURCE_ID;
 
 const SCHEMA_VERSION: &str = "v6";
@@ -49,7 +51,7 @@ impl MergeStatus {
 }
 
 // Bump this when schema/tokenizer changes. Used to trigger rebuilds.
-pub const SCHEMA_HASH: &str = "tantivy-schema-v6-long-tokens";
+pub const SCHEMA_HASH: &str = "tantivy-schema-v9-tool-events";
 
 /// Returns true if the given stored hash matches the current schema hash.
 pub fn schema_hash_matches(stored: &str) -> bool {
@@ -73,6 +75,14 @@ pub struct Fields {
     pub source_id: Field,
     pub origin_kind: Field,
     pub origin_host: Field,
+    // Message-level filter fields
+    pub role: Field,
+    pub tool: Field,
+    // User-assigned session tags (from metadata.cass.tags)
+    pub tag: Field,
+    // Tool events: files read/edited and shell commands run
+    pub file: Field,
+    pub command: Field,
 }
 
 pub struct TantivyIndex {
@@ -152,6 +162,14 @@ impl TantivyIndex {
         Ok(())
     }
 
+    /// Delete every document belonging to the session at `source_path`.
+    ///
+    /// Used to re-index a single session in place (e.g. after tag edits).
+    pub fn delete_source_path(&mut self, source_path: &str) {
+        self.writer
+            .delete_term(Term::from_field_text(self.fields.source_path, source_path));
+    }
+
     pub fn commit(&mut self) -> Result<()> {
         self.writer.commit()?;
         Ok(())
@@ -291,6 +309,13 @@ impl TantivyIndex {
             .get("cass")
             .and_then(|c| c.get("workspace_original"))
             .and_then(|v| v.as_str());
+        let tags: Vec<&str> = conv
+            .metadata
+            .get("cass")
+            .and_then(|c| c.get("tags"))
+            .and_then(|v| v.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
         let title = conv.title.as_deref();
         let title_prefix = title.map(generate_edge_ngrams);
         let started_at_fallback = conv.started_at;
@@ -309,6 +334,32 @@ impl TantivyIndex {
             {
                 d.add_text(self.fields.origin_host, host);
             }
+            d.add_text(self.fields.role, canonical_role(&msg.role));
+            for tool in extract_tool_names(&msg.content, &msg.extra) {
+                d.add_text(self.fields.tool, tool);
+            }
+            for tag in &tags {
+                d.add_text(self.fields.tag, *tag);
+            }
+            for event in extract_tool_events(&msg.extra) {
+                if let Some(command) = &event.command
+                    && event.kind == ToolEventKind::Command
+                {
+                    d.add_text(self.fields.command, command);
+                }
+                if let Some(path) = &event.path {
+                    d.add_text(self.fields.file, path);
+                    // Also index the workspace-relative form so `file:src/lib.rs` matches
+                    if let Some(relative) = workspace
+                        .as_deref()
+                        .and_then(|ws| path.strip_prefix(ws))
+                        .and_then(|rest| rest.strip_prefix('/'))
+                        .filter(|rest| !rest.is_empty())
+                    {
+                        d.add_text(self.fields.file, relative);
+                    }
+                }
+            }
             if let Some(ws) = &workspace {
                 d.add_text(self.fields.workspace, ws.as_ref());
             }
@@ -405,18 +456,28 @@ pub fn build_schema() -> Schema {
     schema_builder.add_text_field("workspace", STRING | STORED);
     // workspace_original stores the pre-rewrite path for audit/display (P6.2)
     schema_builder.add_text_field("workspace_original", STORED);
-    schema_builder.add_text_field("source_path", STORED);
+    // STRING so a session's documents can be deleted by term on re-index.
+    schema_builder.add_text_field("source_path", STRING | STORED);
     schema_builder.add_u64_field("msg_idx", INDEXED | STORED);
     schema_builder.add_i64_field("created_at", INDEXED | STORED | FAST);
     schema_builder.add_text_field("title", text.clone());
     schema_builder.add_text_field("content", text);
     schema_builder.add_text_field("title_prefix", text_not_stored.clone());
-    schema_builder.add_text_field("content_prefix", text_not_stored);
+    schema_builder.add_text_field("content_prefix", text_not_stored.clone());
     schema_builder.add_text_field("preview", TEXT | STORED);
     // Provenance fields (P1.4) - STRING for exact match filtering
     schema_builder.add_text_field("source_id", STRING | STORED);
     schema_builder.add_text_field("origin_kind", STRING | STORED);
     schema_builder.add_text_field("origin_host", STRING | STORED);
+    // Message role (canonical, lowercase) and invoked tool names (multi-valued)
+    schema_builder.add_text_field("role", STRING | STORED);
+    schema_builder.add_text_field("tool", STRING | STORED);
+    // User-assigned session tags (multi-valued, normalized lowercase)
+    schema_builder.add_text_field("tag", STRING | STORED);
+    // Paths read/edited by tool calls (multi-valued, as given plus workspace-relative)
+    schema_builder.add_text_field("file", STRING | STORED);
+    // Shell commands run by tool calls, searchable as free text
+    schema_builder.add_text_field("command", text_not_stored);
     schema_builder.build()
 }
 
@@ -441,6 +502,11 @@ pub fn fields_from_schema(schema: &Schema) -> Result<Fields> {
         source_id: get("source_id")?,
         origin_kind: get("origin_kind")?,
         origin_host: get("origin_host")?,
+        role: get("role")?,
+        tool: get("tool")?,
+        tag: get("tag")?,
+        file: get("file")?,
+        command: get("command")?,
     })
 }
 
@@ -750,6 +816,11 @@ mod tests {
         assert!(schema.get_field("source_id").is_ok());
         assert!(schema.get_field("origin_kind").is_ok());
         assert!(schema.get_field("origin_host").is_ok());
+        assert!(schema.get_field("role").is_ok());
+        assert!(schema.get_field("tool").is_ok());
+        assert!(schema.get_field("tag").is_ok());
+        assert!(schema.get_field("file").is_ok());
+        assert!(schema.get_field("command").is_ok());
     }
 
     #[test]
@@ -773,6 +844,11 @@ mod tests {
         let _ = fields.source_id;
         let _ = fields.origin_kind;
         let _ = fields.origin_host;
+        let _ = fields.role;
+        let _ = fields.tool;
+        let _ = fields.tag;
+        let _ = fields.file;
+        let _ = fields.command;
     }
 
     #[test]
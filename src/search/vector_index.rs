@@ -10,7 +10,7 @@
 //!   EmbedderRevision length: u16
 //!   EmbedderRevision: bytes
 //!   Dimension: u32
-//!   Quantization: u8 (0=f32, 1=f16)
+//!   Quantization: u8 (0=f32, 1=f16, 2=int8, 3=binary)
 //!   Count: u32
 //!   HeaderCRC32: u32 (CRC32 of header bytes before this field)
 //!
@@ -21,12 +21,19 @@
 //!   WorkspaceID: u32
 //!   SourceID: u32
 //!   Role: u8 (0=user, 1=assistant, 2=system, 3=tool)
-//!   ChunkIdx: u8 (0 for single-chunk)
+//!   ChunkIdx: u8 (0 for single-chunk; long messages span several rows)
 //!   VecOffset: u64 (offset into vector slab)
 //!   ContentHash: [u8; 32] (SHA256 of canonical content)
 //!
 //! Vector slab:
 //!   Count × Dimension × bytes_per_quant, contiguous, 32-byte aligned.
+//!   int8: Dimension × f32 per-dimension scales, then Count × Dimension i8.
+//!   binary: Count × ceil(Dimension / 8) bytes of sign bits (LSB first).
+//!
+//! int8 and binary rows must be laid out in row order. Their indexes are
+//! usually saved with an f16 companion (see [`rescore_index_path`]): searches
+//! score every row with the compact vectors, then rescore the best candidates
+//! against the companion.
 
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap, HashSet};
@@ -58,6 +65,13 @@ const PARALLEL_THRESHOLD: usize = 10_000;
 /// Smaller chunks = better load balancing but more overhead. 1024 is a good default.
 const PARALLEL_CHUNK_SIZE: usize = 1024;
 
+/// Candidates rescored per requested result for int8 indexes.
+const INT8_RESCORE_OVERSAMPLE: usize = 4;
+
+/// Candidates rescored per requested result for binary indexes.
+/// Hamming distance is much coarser than int8, so it needs a wider net.
+const BINARY_RESCORE_OVERSAMPLE: usize = 10;
+
 /// Cached parallel search enable flag (checked once at first use).
 /// Set CASS_PARALLEL_SEARCH=0 to disable parallel search.
 static PARALLEL_SEARCH_ENABLED: once_cell::sync::Lazy<bool> = once_cell::sync::Lazy::new(|| {
@@ -72,10 +86,19 @@ pub fn vector_index_path(data_dir: &Path, embedder_id: &str) -> PathBuf {
         .join(format!("index-{embedder_id}.cvvi"))
 }
 
+/// Path of the f16 companion used to rescore an int8 or binary index.
+pub fn rescore_index_path(index_path: &Path) -> PathBuf {
+    index_path.with_extension("f16.cvvi")
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Quantization {
     F32,
     F16,
+    /// Scalar int8 with one f32 scale per dimension.
+    Int8,
+    /// One sign bit per dimension, compared by Hamming distance.
+    Binary,
 }
 
 impl Quantization {
@@ -83,6 +106,8 @@ impl Quantization {
         match self {
             Quantization::F32 => 0,
             Quantization::F16 => 1,
+            Quantization::Int8 => 2,
+            Quantization::Binary => 3,
         }
     }
 
@@ -90,14 +115,77 @@ impl Quantization {
         match value {
             0 => Ok(Quantization::F32),
             1 => Ok(Quantization::F16),
+            2 => Ok(Quantization::Int8),
+            3 => Ok(Quantization::Binary),
             other => bail!("unknown quantization value: {other}"),
         }
     }
 
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Quantization::F32 => "f32",
+            Quantization::F16 => "f16",
+            Quantization::Int8 => "int8",
+            Quantization::Binary => "binary",
+        }
+    }
+
+    /// Storage granularity of a component in bytes.
+    ///
+    /// Binary packs eight components per byte; use [`Self::vector_bytes`] for sizes.
     pub fn bytes_per_component(self) -> usize {
         match self {
             Quantization::F32 => 4,
             Quantization::F16 => 2,
+            Quantization::Int8 | Quantization::Binary => 1,
+        }
+    }
+
+    /// Bytes used by one stored vector of `dimension` components.
+    pub fn vector_bytes(self, dimension: usize) -> Result<usize> {
+        match self {
+            Quantization::Binary => Ok(dimension.div_ceil(8)),
+            other => dimension
+                .checked_mul(other.bytes_per_component())
+                .ok_or_else(|| anyhow!("vector size overflow")),
+        }
+    }
+
+    /// Bytes at the start of the vector slab before the first vector.
+    pub fn slab_prefix_bytes(self, dimension: usize) -> Result<usize> {
+        match self {
+            Quantization::Int8 => dimension
+                .checked_mul(4)
+                .ok_or_else(|| anyhow!("scale table size overflow")),
+            _ => Ok(0),
+        }
+    }
+
+    /// Whether scores from this format are approximate and worth rescoring.
+    pub fn is_compact(self) -> bool {
+        matches!(self, Quantization::Int8 | Quantization::Binary)
+    }
+
+    /// Number of candidates to rescore for a top-`k` search.
+    fn rescore_candidates(self, k: usize) -> usize {
+        match self {
+            Quantization::Int8 => k.saturating_mul(INT8_RESCORE_OVERSAMPLE),
+            Quantization::Binary => k.saturating_mul(BINARY_RESCORE_OVERSAMPLE),
+            Quantization::F32 | Quantization::F16 => k,
+        }
+    }
+}
+
+impl std::str::FromStr for Quantization {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "f32" => Ok(Quantization::F32),
+            "f16" => Ok(Quantization::F16),
+            "int8" => Ok(Quantization::Int8),
+            "binary" => Ok(Quantization::Binary),
+            other => bail!("unknown quantization: {other} (expected f32, f16, int8 or binary)"),
         }
     }
 }
@@ -352,6 +440,8 @@ pub struct SemanticFilter {
     pub roles: Option<HashSet<u8>>,
     pub created_from: Option<i64>,
     pub created_to: Option<i64>,
+    /// Restrict to these messages (filters resolved outside the index).
+    pub message_ids: Option<HashSet<u64>>,
 }
 
 impl SemanticFilter {
@@ -386,6 +476,11 @@ impl SemanticFilter {
         {
             return false;
         }
+        if let Some(message_ids) = &self.message_ids
+            && !message_ids.contains(&row.message_id)
+        {
+            return false;
+        }
         true
     }
 
@@ -393,14 +488,23 @@ impl SemanticFilter {
         let agents = map_filter_set(&filters.agents, &maps.agent_slug_to_id);
         let workspaces = map_filter_set(&filters.workspaces, &maps.workspace_path_to_id);
         let sources = maps.sources_from_filter(&filters.source_filter)?;
+        // Unknown roles map to nothing, so a filter of only unknown roles matches no rows.
+        let roles = (!filters.roles.is_empty()).then(|| {
+            filters
+                .roles
+                .iter()
+                .filter_map(|role| role_code_from_str(role))
+                .collect()
+        });
 
         Ok(Self {
             agents,
             workspaces,
             sources,
-            roles: None,
+            roles,
             created_from: filters.created_from,
             created_to: filters.created_to,
+            message_ids: None,
         })
     }
 
@@ -408,6 +512,11 @@ impl SemanticFilter {
         self.roles = roles;
         self
     }
+
+    pub fn with_message_ids(mut self, message_ids: Option<HashSet<u64>>) -> Self {
+        self.message_ids = message_ids;
+        self
+    }
 }
 
 pub const ROLE_USER: u8 = 0;
@@ -535,6 +644,11 @@ pub struct VectorIndex {
     header: CvviHeader,
     rows: Vec<VectorRow>,
     vectors: VectorStorage,
+    /// f16 companion used to rescore int8/binary candidates (same rows, same order).
+    rescore: Option<Box<VectorIndex>>,
+    /// HNSW node id of each row when it differs from the row position
+    /// (segments merged with tombstoned rows dropped); ascending.
+    graph_ids: Option<Vec<u32>>,
 }
 
 #[derive(Debug)]
@@ -545,6 +659,14 @@ enum VectorStorage {
     /// The vec_offset values are still in F16 byte terms (2 bytes per component),
     /// so we use 2 as the divisor when computing element indices.
     PreconvertedF32(Vec<f32>),
+    /// Scalar int8; `values` excludes the scale table, so element indices are
+    /// `vec_offset - dimension * 4`.
+    Int8 {
+        scales: Vec<f32>,
+        values: Vec<i8>,
+    },
+    /// Packed sign bits, `ceil(dimension / 8)` bytes per vector.
+    Binary(Vec<u8>),
     Mmap {
         mmap: Mmap,
         offset: usize,
@@ -563,83 +685,123 @@ impl VectorIndex {
     where
         I: IntoIterator<Item = VectorEntry>,
     {
+        let entries: Vec<VectorEntry> = entries.into_iter().collect();
+        Self::build_from_entries(
+            embedder_id.into(),
+            embedder_revision.into(),
+            dimension,
+            quantization,
+            &entries,
+        )
+    }
+
+    /// Build an index and, for int8/binary, the f16 companion used for rescoring.
+    ///
+    /// For f32 and f16 this is the same as [`Self::build`].
+    pub fn build_with_rescore<I>(
+        embedder_id: impl Into<String>,
+        embedder_revision: impl Into<String>,
+        dimension: usize,
+        quantization: Quantization,
+        entries: I,
+    ) -> Result<Self>
+    where
+        I: IntoIterator<Item = VectorEntry>,
+    {
+        let embedder_id = embedder_id.into();
+        let embedder_revision = embedder_revision.into();
+        let entries: Vec<VectorEntry> = entries.into_iter().collect();
+        let mut index = Self::build_from_entries(
+            embedder_id.clone(),
+            embedder_revision.clone(),
+            dimension,
+            quantization,
+            &entries,
+        )?;
+        if quantization.is_compact() {
+            let rescore = Self::build_from_entries(
+                embedder_id,
+                embedder_revision,
+                dimension,
+                Quantization::F16,
+                &entries,
+            )?;
+            index.rescore = Some(Box::new(rescore));
+        }
+        Ok(index)
+    }
+
+    fn build_from_entries(
+        embedder_id: String,
+        embedder_revision: String,
+        dimension: usize,
+        quantization: Quantization,
+        entries: &[VectorEntry],
+    ) -> Result<Self> {
         if dimension == 0 {
             bail!("dimension must be non-zero");
         }
         let dimension_u32 =
             u32::try_from(dimension).map_err(|_| anyhow!("dimension out of range"))?;
-
-        let entries: Vec<VectorEntry> = entries.into_iter().collect();
         let count_u32 =
             u32::try_from(entries.len()).map_err(|_| anyhow!("entry count out of range"))?;
 
         let mut rows = Vec::with_capacity(entries.len());
-        let mut offset_bytes: usize = 0;
-        let bytes_per = quantization.bytes_per_component();
-        let vector_bytes = dimension
-            .checked_mul(bytes_per)
-            .ok_or_else(|| anyhow!("vector size overflow"))?;
+        let mut offset_bytes = quantization.slab_prefix_bytes(dimension)?;
+        let vector_bytes = quantization.vector_bytes(dimension)?;
+        for entry in entries {
+            if entry.vector.len() != dimension {
+                bail!(
+                    "vector dimension mismatch: expected {}, got {}",
+                    dimension,
+                    entry.vector.len()
+                );
+            }
+            let vec_offset =
+                u64::try_from(offset_bytes).map_err(|_| anyhow!("vector offset out of range"))?;
+            rows.push(VectorRow {
+                message_id: entry.message_id,
+                created_at_ms: entry.created_at_ms,
+                agent_id: entry.agent_id,
+                workspace_id: entry.workspace_id,
+                source_id: entry.source_id,
+                role: entry.role,
+                chunk_idx: entry.chunk_idx,
+                vec_offset,
+                content_hash: entry.content_hash,
+            });
+            offset_bytes = offset_bytes
+                .checked_add(vector_bytes)
+                .ok_or_else(|| anyhow!("vector slab size overflow"))?;
+        }
 
         let vectors = match quantization {
-            Quantization::F32 => {
-                let mut slab = Vec::with_capacity(entries.len() * dimension);
-                for entry in &entries {
-                    if entry.vector.len() != dimension {
-                        bail!(
-                            "vector dimension mismatch: expected {}, got {}",
-                            dimension,
-                            entry.vector.len()
-                        );
-                    }
-                    let vec_offset = u64::try_from(offset_bytes)
-                        .map_err(|_| anyhow!("vector offset out of range"))?;
-                    rows.push(VectorRow {
-                        message_id: entry.message_id,
-                        created_at_ms: entry.created_at_ms,
-                        agent_id: entry.agent_id,
-                        workspace_id: entry.workspace_id,
-                        source_id: entry.source_id,
-                        role: entry.role,
-                        chunk_idx: entry.chunk_idx,
-                        vec_offset,
-                        content_hash: entry.content_hash,
-                    });
-                    slab.extend(entry.vector.iter().copied());
-                    offset_bytes = offset_bytes
-                        .checked_add(vector_bytes)
-                        .ok_or_else(|| anyhow!("vector slab size overflow"))?;
+            Quantization::F32 => VectorStorage::F32(
+                entries
+                    .iter()
+                    .flat_map(|entry| entry.vector.iter().copied())
+                    .collect(),
+            ),
+            Quantization::F16 => VectorStorage::F16(
+                entries
+                    .iter()
+                    .flat_map(|entry| entry.vector.iter().map(|v| f16::from_f32(*v)))
+                    .collect(),
+            ),
+            Quantization::Int8 => {
+                let scales = int8_scales(entries.iter().map(|e| e.vector.as_slice()), dimension);
+                let mut values = Vec::with_capacity(entries.len() * dimension);
+                for entry in entries {
+                    values.extend(quantize_int8(&entry.vector, &scales));
                 }
-                VectorStorage::F32(slab)
+                VectorStorage::Int8 { scales, values }
             }
-            Quantization::F16 => {
-                let mut slab = Vec::with_capacity(entries.len() * dimension);
-                for entry in &entries {
-                    if entry.vector.len() != dimension {
-                        bail!(
-                            "vector dimension mismatch: expected {}, got {}",
-                            dimension,
-                            entry.vector.len()
-                        );
-                    }
-                    let vec_offset = u64::try_from(offset_bytes)
-                        .map_err(|_| anyhow!("vector offset out of range"))?;
-                    rows.push(VectorRow {
-                        message_id: entry.message_id,
-                        created_at_ms: entry.created_at_ms,
-                        agent_id: entry.agent_id,
-                        workspace_id: entry.workspace_id,
-                        source_id: entry.source_id,
-                        role: entry.role,
-                        chunk_idx: entry.chunk_idx,
-                        vec_offset,
-                        content_hash: entry.content_hash,
-                    });
-                    slab.extend(entry.vector.iter().map(|v| f16::from_f32(*v)));
-                    offset_bytes = offset_bytes
-                        .checked_add(vector_bytes)
-                        .ok_or_else(|| anyhow!("vector slab size overflow"))?;
+            Quantization::Binary => {
+                let mut bits = Vec::with_capacity(entries.len() * vector_bytes);
+                for entry in entries {
+                    bits.extend(pack_sign_bits(&entry.vector));
                 }
-                VectorStorage::F16(slab)
+                VectorStorage::Binary(bits)
             }
         };
 
@@ -655,12 +817,29 @@ impl VectorIndex {
             header,
             rows,
             vectors,
+            rescore: None,
+            graph_ids: None,
         };
         index.validate()?;
         Ok(index)
     }
 
+    /// Load a CVVI file, attaching its f16 rescoring companion when present.
     pub fn load(path: &Path) -> Result<Self> {
+        // P0 Opt 1: Pre-convert F16→F32 at load time to eliminate per-query conversion.
+        // Env var CASS_F16_PRECONVERT=0 disables this (keeps mmap + lazy conversion).
+        let f16_preconvert_enabled = dotenvy::var("CASS_F16_PRECONVERT")
+            .map(|v| v != "0" && v.to_lowercase() != "false")
+            .unwrap_or(true);
+
+        let mut index = Self::load_with(path, f16_preconvert_enabled)?;
+        if index.header.quantization.is_compact() {
+            index.attach_rescore_companion(path);
+        }
+        Ok(index)
+    }
+
+    fn load_with(path: &Path, f16_preconvert_enabled: bool) -> Result<Self> {
         if cfg!(target_endian = "big") {
             bail!("CVVI load is only supported on little-endian targets");
         }
@@ -718,13 +897,27 @@ impl VectorIndex {
             slab_size,
         )?;
 
-        // P0 Opt 1: Pre-convert F16→F32 at load time to eliminate per-query conversion.
-        // Env var CASS_F16_PRECONVERT=0 disables this (keeps mmap + lazy conversion).
-        let f16_preconvert_enabled = dotenvy::var("CASS_F16_PRECONVERT")
-            .map(|v| v != "0" && v.to_lowercase() != "false")
-            .unwrap_or(true);
-
-        let vectors = if f16_preconvert_enabled && header.quantization == Quantization::F16 {
+        let vectors = if header.quantization.is_compact() {
+            // Compact slabs are small enough to keep in memory outright.
+            let slab_end = slab_offset
+                .checked_add(slab_size)
+                .ok_or_else(|| anyhow!("slab offset overflow"))?;
+            let slab_bytes = mmap
+                .get(slab_offset..slab_end)
+                .ok_or_else(|| anyhow!("slab out of bounds"))?;
+            if header.quantization == Quantization::Int8 {
+                let prefix = header
+                    .quantization
+                    .slab_prefix_bytes(header.dimension as usize)?;
+                let (scale_bytes, value_bytes) = slab_bytes.split_at(prefix);
+                VectorStorage::Int8 {
+                    scales: bytes_as_f32(scale_bytes)?.to_vec(),
+                    values: value_bytes.iter().map(|b| *b as i8).collect(),
+                }
+            } else {
+                VectorStorage::Binary(slab_bytes.to_vec())
+            }
+        } else if f16_preconvert_enabled && header.quantization == Quantization::F16 {
             // Pre-convert entire F16 slab to F32 for faster dot products.
             // Trade-off: 2x memory usage, but eliminates 19.2M conversions/query for 50k vectors.
             // Bench (search_perf::vector_index_search_50k_loaded, 2026-01-11):
@@ -750,11 +943,118 @@ impl VectorIndex {
             header,
             rows,
             vectors,
+            rescore: None,
+            graph_ids: None,
         };
         index.validate()?;
         Ok(index)
     }
 
+    /// Attach the f16 companion next to `path`, if it exists and matches.
+    ///
+    /// The companion stays memory-mapped; only rescored candidates touch it.
+    fn attach_rescore_companion(&mut self, path: &Path) {
+        let companion_path = rescore_index_path(path);
+        if !companion_path.exists() {
+            return;
+        }
+        match Self::load_with(&companion_path, false) {
+            Ok(companion) if self.matches_rescore_companion(&companion) => {
+                self.rescore = Some(Box::new(companion));
+            }
+            Ok(_) => tracing::warn!(
+                path = %companion_path.display(),
+                "f16 rescoring index does not match the vector index; using compact scores only"
+            ),
+            Err(err) => tracing::warn!(
+                path = %companion_path.display(),
+                error = %err,
+                "failed to load f16 rescoring index; using compact scores only"
+            ),
+        }
+    }
+
+    fn matches_rescore_companion(&self, companion: &VectorIndex) -> bool {
+        let (ours, theirs) = (&self.header, &companion.header);
+        theirs.quantization == Quantization::F16
+            && theirs.embedder_id == ours.embedder_id
+            && theirs.dimension == ours.dimension
+            && theirs.count == ours.count
+            && self.rows.iter().zip(&companion.rows).all(|(a, b)| {
+                a.message_id == b.message_id
+                    && a.chunk_idx == b.chunk_idx
+                    && a.content_hash == b.content_hash
+            })
+    }
+
+    /// The f16 companion used to rescore compact candidates, if loaded.
+    pub fn rescore_index(&self) -> Option<&VectorIndex> {
+        self.rescore.as_deref()
+    }
+
+    /// Drop the rescoring companion so searches use compact scores only.
+    pub fn without_rescore(mut self) -> Self {
+        self.rescore = None;
+        self
+    }
+
+    /// Record the HNSW node id of each row (must be ascending, one per row).
+    pub fn with_graph_ids(mut self, graph_ids: Vec<u32>) -> Result<Self> {
+        if graph_ids.len() != self.rows.len() {
+            bail!(
+                "graph id count mismatch: expected {}, got {}",
+                self.rows.len(),
+                graph_ids.len()
+            );
+        }
+        if graph_ids.windows(2).any(|w| w[0] >= w[1]) {
+            bail!("graph ids must be strictly ascending");
+        }
+        self.graph_ids = Some(graph_ids);
+        Ok(self)
+    }
+
+    /// HNSW node id for the row at `idx`.
+    pub fn graph_id(&self, idx: usize) -> usize {
+        match &self.graph_ids {
+            Some(ids) => ids[idx] as usize,
+            None => idx,
+        }
+    }
+
+    /// Row for an HNSW node id, or `None` if that row has been tombstoned.
+    pub fn row_for_graph_id(&self, graph_id: usize) -> Option<&VectorRow> {
+        match &self.graph_ids {
+            Some(ids) => {
+                let id = u32::try_from(graph_id).ok()?;
+                ids.binary_search(&id).ok().map(|idx| &self.rows[idx])
+            }
+            None => self.rows.get(graph_id),
+        }
+    }
+
+    /// Rebuild entries (metadata plus f32 vectors) for every row, in row order.
+    ///
+    /// int8/binary vectors come from the f16 companion when one is loaded.
+    pub fn to_entries(&self) -> Result<Vec<VectorEntry>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                Ok(VectorEntry {
+                    message_id: row.message_id,
+                    created_at_ms: row.created_at_ms,
+                    agent_id: row.agent_id,
+                    workspace_id: row.workspace_id,
+                    source_id: row.source_id,
+                    role: row.role,
+                    chunk_idx: row.chunk_idx,
+                    content_hash: row.content_hash,
+                    vector: self.vector_at_f32(row)?,
+                })
+            })
+            .collect()
+    }
+
     pub fn save(&self, path: &Path) -> Result<()> {
         let parent = path
             .parent()
@@ -769,6 +1069,15 @@ impl VectorIndex {
         std::fs::rename(&temp_path, path)
             .with_context(|| format!("rename CVVI temp file {temp_path:?}"))?;
         sync_dir(parent).context("fsync CVVI directory post-rename")?;
+
+        // Keep the rescoring companion in step; a stale one would be rejected on load anyway.
+        let companion_path = rescore_index_path(path);
+        if let Some(rescore) = &self.rescore {
+            rescore.save(&companion_path)?;
+        } else if companion_path.exists() {
+            std::fs::remove_file(&companion_path)
+                .with_context(|| format!("remove stale rescoring index {companion_path:?}"))?;
+        }
         Ok(())
     }
 
@@ -813,6 +1122,9 @@ impl VectorIndex {
         if k == 0 {
             return Ok(Vec::new());
         }
+        if self.header.quantization.is_compact() {
+            return self.search_compact(query_vec, k, filter, false);
+        }
 
         // P1 Opt 3: Dispatch to parallel search for large indices.
         // Skip parallelism for small indices where Rayon overhead exceeds benefit.
@@ -930,6 +1242,10 @@ impl VectorIndex {
         Ok(results)
     }
 
+    /// Like [`Self::search_top_k`], but returns at most one result per message.
+    ///
+    /// Long messages are stored as several chunk rows; each message is scored
+    /// by its best-matching chunk (max-sim), and `chunk_idx` names that chunk.
     pub fn search_top_k_collapsed(
         &self,
         query_vec: &[f32],
@@ -946,6 +1262,9 @@ impl VectorIndex {
         if k == 0 {
             return Ok(Vec::new());
         }
+        if self.header.quantization.is_compact() {
+            return self.search_compact(query_vec, k, filter, true);
+        }
 
         let mut best_by_message: HashMap<u64, VectorSearchResult> = HashMap::new();
         for row in &self.rows {
@@ -982,6 +1301,145 @@ impl VectorIndex {
         Ok(results)
     }
 
+    /// Search an int8/binary index: score every row with the compact vectors,
+    /// then rescore the best candidates against the f16 companion if loaded.
+    ///
+    /// With `collapse`, rows are first reduced to the best compact-scoring
+    /// chunk per message, and only that chunk is rescored.
+    fn search_compact(
+        &self,
+        query_vec: &[f32],
+        k: usize,
+        filter: Option<&SemanticFilter>,
+        collapse: bool,
+    ) -> Result<Vec<VectorSearchResult>> {
+        let query = self.compact_query(query_vec);
+        let score_row = |(idx, row): (usize, &VectorRow)| -> Option<Result<(usize, f32)>> {
+            if let Some(filter) = filter
+                && !filter.matches(row)
+            {
+                return None;
+            }
+            Some(self.compact_score(idx, &query).map(|score| (idx, score)))
+        };
+        let mut scored: Vec<(usize, f32)> =
+            if *PARALLEL_SEARCH_ENABLED && self.rows.len() >= PARALLEL_THRESHOLD {
+                self.rows
+                    .par_iter()
+                    .enumerate()
+                    .filter_map(score_row)
+                    .collect::<Result<_>>()?
+            } else {
+                self.rows
+                    .iter()
+                    .enumerate()
+                    .filter_map(score_row)
+                    .collect::<Result<_>>()?
+            };
+
+        if collapse {
+            let mut best_by_message: HashMap<u64, (usize, f32)> = HashMap::new();
+            for (idx, score) in scored {
+                best_by_message
+                    .entry(self.rows[idx].message_id)
+                    .and_modify(|best| {
+                        if score > best.1 {
+                            *best = (idx, score);
+                        }
+                    })
+                    .or_insert((idx, score));
+            }
+            scored = best_by_message.into_values().collect();
+        }
+
+        let by_score_desc =
+            |a: &(usize, f32), b: &(usize, f32)| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0));
+        let candidates = self.header.quantization.rescore_candidates(k);
+        if scored.len() > candidates {
+            scored.select_nth_unstable_by(candidates - 1, by_score_desc);
+            scored.truncate(candidates);
+        }
+
+        let mut results = Vec::with_capacity(scored.len());
+        for (idx, approx) in scored {
+            let row = &self.rows[idx];
+            let score = match &self.rescore {
+                Some(rescore) => rescore.dot_product_row(&rescore.rows[idx], query_vec)?,
+                None => approx,
+            };
+            results.push(VectorSearchResult {
+                message_id: row.message_id,
+                chunk_idx: row.chunk_idx,
+                score,
+            });
+        }
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.message_id.cmp(&b.message_id))
+        });
+        results.truncate(k);
+        Ok(results)
+    }
+
+    fn compact_query(&self, query_vec: &[f32]) -> CompactQuery {
+        match &self.vectors {
+            // Fold the per-dimension scales into the query once per search.
+            VectorStorage::Int8 { scales, .. } => CompactQuery::Int8(
+                query_vec
+                    .iter()
+                    .zip(scales)
+                    .map(|(q, scale)| q * scale)
+                    .collect(),
+            ),
+            _ => CompactQuery::Binary(pack_sign_bits(query_vec)),
+        }
+    }
+
+    /// Approximate score of row `idx` from its compact vector.
+    fn compact_score(&self, idx: usize, query: &CompactQuery) -> Result<f32> {
+        let dimension = self.header.dimension as usize;
+        match (&self.vectors, query) {
+            (VectorStorage::Int8 { values, .. }, CompactQuery::Int8(scaled)) => {
+                let start = idx
+                    .checked_mul(dimension)
+                    .ok_or_else(|| anyhow!("vector slice overflow"))?;
+                let slice = values
+                    .get(start..start + dimension)
+                    .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
+                Ok(dot_product_i8(slice, scaled))
+            }
+            (VectorStorage::Binary(bits), CompactQuery::Binary(query_bits)) => {
+                let width = dimension.div_ceil(8);
+                let start = idx
+                    .checked_mul(width)
+                    .ok_or_else(|| anyhow!("vector slice overflow"))?;
+                let slice = bits
+                    .get(start..start + width)
+                    .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
+                let distance = hamming_distance(slice, query_bits) as f32;
+                Ok(1.0 - 2.0 * distance / dimension as f32)
+            }
+            _ => bail!("compact query does not match vector storage"),
+        }
+    }
+
+    /// Row position of a compact vector; compact rows are stored in row order.
+    fn compact_row_index(&self, vec_offset: u64) -> Result<usize> {
+        let dimension = self.header.dimension as usize;
+        let prefix = self.header.quantization.slab_prefix_bytes(dimension)?;
+        let vector_bytes = self.header.quantization.vector_bytes(dimension)?;
+        let offset =
+            usize::try_from(vec_offset).map_err(|_| anyhow!("vector offset out of range"))?;
+        let relative = offset
+            .checked_sub(prefix)
+            .ok_or_else(|| anyhow!("vector offset inside scale table"))?;
+        if !relative.is_multiple_of(vector_bytes) {
+            bail!("vector offset is not aligned to vector size");
+        }
+        Ok(relative / vector_bytes)
+    }
+
     pub fn vector_at_f32(&self, row: &VectorRow) -> Result<Vec<f32>> {
         let dimension = self.header.dimension as usize;
         match &self.vectors {
@@ -1016,6 +1474,17 @@ impl VectorIndex {
                     .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
                 Ok(slice.to_vec())
             }
+            VectorStorage::Int8 { .. } | VectorStorage::Binary(_) => {
+                let idx = self.compact_row_index(row.vec_offset)?;
+                if let Some(rescore) = &self.rescore {
+                    let companion_row = rescore
+                        .rows
+                        .get(idx)
+                        .ok_or_else(|| anyhow!("rescoring row out of bounds"))?;
+                    return rescore.vector_at_f32(companion_row);
+                }
+                self.dequantize_at(idx)
+            }
             VectorStorage::Mmap { mmap, offset, .. } => {
                 let bytes_per = self.header.quantization.bytes_per_component();
                 let base = offset
@@ -1042,11 +1511,56 @@ impl VectorIndex {
                         let slice = bytes_as_f16(bytes)?;
                         Ok(slice.iter().map(|v| f32::from(*v)).collect())
                     }
+                    Quantization::Int8 | Quantization::Binary => {
+                        bail!("compact vectors are never memory-mapped")
+                    }
                 }
             }
         }
     }
 
+    /// Reconstruct row `idx` from its compact vector.
+    ///
+    /// Binary vectors come back as unit-length ±1/sqrt(dimension) signs.
+    fn dequantize_at(&self, idx: usize) -> Result<Vec<f32>> {
+        let dimension = self.header.dimension as usize;
+        match &self.vectors {
+            VectorStorage::Int8 { scales, values } => {
+                let start = idx
+                    .checked_mul(dimension)
+                    .ok_or_else(|| anyhow!("vector slice overflow"))?;
+                let slice = values
+                    .get(start..start + dimension)
+                    .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
+                Ok(slice
+                    .iter()
+                    .zip(scales)
+                    .map(|(v, scale)| *v as f32 * scale)
+                    .collect())
+            }
+            VectorStorage::Binary(bits) => {
+                let width = dimension.div_ceil(8);
+                let start = idx
+                    .checked_mul(width)
+                    .ok_or_else(|| anyhow!("vector slice overflow"))?;
+                let slice = bits
+                    .get(start..start + width)
+                    .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
+                let magnitude = 1.0 / (dimension as f32).sqrt();
+                Ok((0..dimension)
+                    .map(|d| {
+                        if slice[d / 8] & (1 << (d % 8)) != 0 {
+                            magnitude
+                        } else {
+                            -magnitude
+                        }
+                    })
+                    .collect())
+            }
+            _ => bail!("vector storage is not compact"),
+        }
+    }
+
     pub fn header(&self) -> &CvviHeader {
         &self.header
     }
@@ -1111,6 +1625,14 @@ impl VectorIndex {
                 let bytes = f16_as_bytes(&f16_slab);
                 writer.write_all(bytes)?;
             }
+            VectorStorage::Int8 { scales, values } => {
+                writer.write_all(f32_as_bytes(scales))?;
+                let bytes: Vec<u8> = values.iter().map(|v| *v as u8).collect();
+                writer.write_all(&bytes)?;
+            }
+            VectorStorage::Binary(bits) => {
+                writer.write_all(bits)?;
+            }
             VectorStorage::Mmap { mmap, offset, len } => {
                 let bytes = mmap
                     .get(*offset..offset + len)
@@ -1155,6 +1677,18 @@ impl VectorIndex {
                     .ok_or_else(|| anyhow!("vector slice out of bounds"))?;
                 Ok(dot_product(slice, query))
             }
+            VectorStorage::Int8 { .. } | VectorStorage::Binary(_) => {
+                // Exact scoring prefers the f16 companion; otherwise dequantize.
+                let idx = self.compact_row_index(vec_offset)?;
+                if let Some(rescore) = &self.rescore {
+                    let companion_row = rescore
+                        .rows
+                        .get(idx)
+                        .ok_or_else(|| anyhow!("rescoring row out of bounds"))?;
+                    return rescore.dot_product_row(companion_row, query);
+                }
+                Ok(dot_product(&self.dequantize_at(idx)?, query))
+            }
             VectorStorage::Mmap { mmap, offset, len } => {
                 let bytes_per = self.header.quantization.bytes_per_component();
                 let base = offset
@@ -1185,6 +1719,9 @@ impl VectorIndex {
                         let slice = bytes_as_f16(bytes)?;
                         Ok(dot_product_f16(slice, query))
                     }
+                    Quantization::Int8 | Quantization::Binary => {
+                        bail!("compact vectors are never memory-mapped")
+                    }
                 }
             }
         }
@@ -1210,11 +1747,12 @@ pub fn vector_slab_size_bytes(
     dimension: u32,
     quantization: Quantization,
 ) -> Result<usize> {
-    let components = (count as usize)
-        .checked_mul(dimension as usize)
+    let dimension = dimension as usize;
+    let vectors = (count as usize)
+        .checked_mul(quantization.vector_bytes(dimension)?)
         .ok_or_else(|| anyhow!("vector slab size overflow"))?;
-    components
-        .checked_mul(quantization.bytes_per_component())
+    vectors
+        .checked_add(quantization.slab_prefix_bytes(dimension)?)
         .ok_or_else(|| anyhow!("vector slab size overflow"))
 }
 
@@ -1313,6 +1851,22 @@ impl VectorStorage {
                     .checked_mul(2) // Each F32 element represents one F16 value
                     .ok_or_else(|| anyhow!("vector slab size overflow"))
             }
+            VectorStorage::Int8 { scales, values } => {
+                if quantization != Quantization::Int8 {
+                    bail!("vector storage quantization mismatch (expected int8)");
+                }
+                scales
+                    .len()
+                    .checked_mul(4)
+                    .and_then(|len| len.checked_add(values.len()))
+                    .ok_or_else(|| anyhow!("vector slab size overflow"))
+            }
+            VectorStorage::Binary(bits) => {
+                if quantization != Quantization::Binary {
+                    bail!("vector storage quantization mismatch (expected binary)");
+                }
+                Ok(bits.len())
+            }
             VectorStorage::Mmap { len, .. } => Ok(*len),
         }
     }
@@ -1325,15 +1879,23 @@ fn validate_row_offsets(
     slab_size: usize,
 ) -> Result<()> {
     let bytes_per = quantization.bytes_per_component();
-    let vector_bytes = dimension
-        .checked_mul(bytes_per)
-        .ok_or_else(|| anyhow!("vector size overflow"))?;
+    let vector_bytes = quantization.vector_bytes(dimension)?;
+    let prefix = quantization.slab_prefix_bytes(dimension)?;
     for (idx, row) in rows.iter().enumerate() {
         let offset = usize::try_from(row.vec_offset)
             .map_err(|_| anyhow!("row {idx} vector offset out of range"))?;
         if offset % bytes_per != 0 {
             bail!("row {idx} vector offset not aligned");
         }
+        // Compact rows are addressed by position (see `compact_row_index`).
+        if quantization.is_compact() {
+            let expected = idx
+                .checked_mul(vector_bytes)
+                .and_then(|o| o.checked_add(prefix));
+            if expected != Some(offset) {
+                bail!("row {idx} vector offset out of order");
+            }
+        }
         let end = offset
             .checked_add(vector_bytes)
             .ok_or_else(|| anyhow!("row {idx} vector offset overflow"))?;
@@ -1535,6 +2097,73 @@ fn dot_product_f16(a: &[f16], b: &[f32]) -> f32 {
     }
 }
 
+/// Query prepared once per search of an int8 or binary index.
+enum CompactQuery {
+    /// Query components multiplied by the per-dimension scales.
+    Int8(Vec<f32>),
+    /// Packed sign bits of the query.
+    Binary(Vec<u8>),
+}
+
+/// Per-dimension int8 scales: the largest magnitude in each dimension maps to 127.
+fn int8_scales<'a>(vectors: impl Iterator<Item = &'a [f32]>, dimension: usize) -> Vec<f32> {
+    let mut max_abs = vec![0.0f32; dimension];
+    for vector in vectors {
+        for (max, v) in max_abs.iter_mut().zip(vector) {
+            *max = max.max(v.abs());
+        }
+    }
+    max_abs
+        .into_iter()
+        .map(|max| if max > 0.0 { max / 127.0 } else { 1.0 })
+        .collect()
+}
+
+fn quantize_int8<'a>(vector: &'a [f32], scales: &'a [f32]) -> impl Iterator<Item = i8> + 'a {
+    vector
+        .iter()
+        .zip(scales)
+        .map(|(v, scale)| (v / scale).round().clamp(-127.0, 127.0) as i8)
+}
+
+/// Pack one bit per component (set when positive), least significant bit first.
+fn pack_sign_bits(vector: &[f32]) -> Vec<u8> {
+    let mut bits = vec![0u8; vector.len().div_ceil(8)];
+    for (d, v) in vector.iter().enumerate() {
+        if *v > 0.0 {
+            bits[d / 8] |= 1 << (d % 8);
+        }
+    }
+    bits
+}
+
+#[inline]
+fn dot_product_i8(a: &[i8], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| *x as f32 * y).sum()
+}
+
+#[inline]
+fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    let words_a = a.chunks_exact(8);
+    let words_b = b.chunks_exact(8);
+    let tail: u32 = words_a
+        .remainder()
+        .iter()
+        .zip(words_b.remainder())
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum();
+    words_a
+        .zip(words_b)
+        .map(|(x, y)| {
+            // chunks_exact guarantees 8-byte words.
+            let x = u64::from_le_bytes(x.try_into().unwrap_or_default());
+            let y = u64::from_le_bytes(y.try_into().unwrap_or_default());
+            (x ^ y).count_ones()
+        })
+        .sum::<u32>()
+        + tail
+}
+
 fn sync_dir(path: &Path) -> Result<()> {
     let dir = File::open(path)?;
     dir.sync_all()?;
@@ -1757,6 +2386,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn collapsed_search_scores_messages_by_best_chunk() -> Result<()> {
+        let mut entries = sample_entries();
+        // Message 1 gets a second chunk that matches the query better than any row.
+        entries.push(VectorEntry {
+            chunk_idx: 1,
+            content_hash: [0x12; 32],
+            vector: vec![0.0, 0.6, 0.8],
+            ..entries[0].clone()
+        });
+        let index = VectorIndex::build("hash-3", "rev", 3, Quantization::F32, entries)?;
+
+        let rows = index.search_top_k(&[0.0, 0.0, 1.0], 5, None)?;
+        assert_eq!(rows.len(), 4);
+
+        let results = index.search_top_k_collapsed(&[0.0, 0.0, 1.0], 5, None)?;
+        let ids: Vec<u64> = results.iter().map(|r| r.message_id).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert_eq!(results[1].chunk_idx, 1);
+        assert!((results[1].score - 0.8).abs() < 1e-6);
+
+        let top = index.search_top_k_collapsed(&[0.0, 0.0, 1.0], 2, None)?;
+        assert_eq!(top.len(), 2);
+        Ok(())
+    }
+
     #[test]
     fn f16_and_f32_rankings_match() -> Result<()> {
         let entries = sample_entries();
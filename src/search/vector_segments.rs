@@ -0,0 +1,596 @@
+//! Append-only segments for the CVVI vector index.
+//!
+//! A semantic index is a base CVVI file (see [`vector_index_path`]) plus zero
+//! or more delta segments written next to it:
+//!
+//! ```text
+//! index-<embedder>.cvvi            base
+//! index-<embedder>.seg-<n>.cvvi    delta segments, oldest first
+//! index-<embedder>.segments.json   manifest listing the deltas
+//! ```
+//!
+//! Incremental indexing appends a delta holding the newly embedded rows and
+//! the message ids it tombstones. A tombstone hides that message's rows in the
+//! base and in every earlier delta, so an edited message is a tombstone plus
+//! fresh rows and a deleted one is just a tombstone.
+//!
+//! Loading merges the base and deltas into one in-memory [`VectorIndex`].
+//! Every row keeps its "graph id", its position in the concatenation of the
+//! base and all deltas (tombstoned rows included), which is the node id the
+//! HNSW graph uses; ids only change when [`compact`] drops tombstoned rows.
+//!
+//! Compaction folds the deltas into a new base and removes them. It runs once
+//! [`SegmentManifest::needs_compaction`] says the deltas have grown too large
+//! relative to the base.
+//!
+//! [`vector_index_path`]: crate::search::vector_index::vector_index_path
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::search::vector_index::{VectorEntry, VectorIndex, rescore_index_path};
+
+/// Manifest format version.
+pub const SEGMENT_MANIFEST_VERSION: u32 = 1;
+
+/// Compact once this many deltas have accumulated.
+pub const MAX_DELTA_SEGMENTS: usize = 8;
+
+/// Compact once the deltas hold more than `1 / DELTA_ROW_RATIO` of the base's rows.
+const DELTA_ROW_RATIO: usize = 4;
+
+/// Serializes manifest updates (appends and compaction) within this process.
+static SEGMENT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Path to the segment manifest for a base CVVI file.
+pub fn segment_manifest_path(index_path: &Path) -> PathBuf {
+    index_path.with_extension("segments.json")
+}
+
+/// Path to delta segment `seq` for a base CVVI file.
+pub fn delta_segment_path(index_path: &Path, seq: u64) -> PathBuf {
+    index_path.with_extension(format!("seg-{seq}.cvvi"))
+}
+
+/// One delta segment listed in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaSegment {
+    /// Sequence number; the file is [`delta_segment_path`]`(base, seq)`.
+    pub seq: u64,
+    /// Rows stored in the segment file.
+    pub rows: usize,
+    /// Message ids whose rows in the base and earlier deltas are superseded.
+    #[serde(default)]
+    pub tombstones: Vec<u64>,
+}
+
+/// Deltas layered on top of a base CVVI file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentManifest {
+    pub version: u32,
+    /// Row count of the base file the deltas apply to.
+    pub base_rows: usize,
+    /// Next delta sequence number.
+    pub next_seq: u64,
+    /// Deltas, oldest first.
+    pub segments: Vec<DeltaSegment>,
+}
+
+impl SegmentManifest {
+    fn new(base_rows: usize) -> Self {
+        Self {
+            version: SEGMENT_MANIFEST_VERSION,
+            base_rows,
+            next_seq: 1,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Load the manifest for `index_path`; `None` when the index has no deltas.
+    pub fn load(index_path: &Path) -> Result<Option<Self>> {
+        let path = segment_manifest_path(index_path);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("read segment manifest {path:?}"));
+            }
+        };
+        let manifest: Self = serde_json::from_str(&content)
+            .with_context(|| format!("parse segment manifest {path:?}"))?;
+        if manifest.version != SEGMENT_MANIFEST_VERSION {
+            bail!(
+                "unsupported segment manifest version {} in {path:?}",
+                manifest.version
+            );
+        }
+        Ok(Some(manifest))
+    }
+
+    fn save(&self, index_path: &Path) -> Result<()> {
+        let path = segment_manifest_path(index_path);
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("write segment manifest {temp_path:?}"))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("rename segment manifest {temp_path:?}"))?;
+        Ok(())
+    }
+
+    /// Rows across the base and all deltas, tombstoned rows included.
+    ///
+    /// This is also the graph id the next appended row will get.
+    pub fn total_rows(&self) -> usize {
+        self.base_rows + self.delta_rows()
+    }
+
+    /// Rows stored in deltas.
+    pub fn delta_rows(&self) -> usize {
+        self.segments.iter().map(|s| s.rows).sum()
+    }
+
+    /// Tombstones recorded across all deltas.
+    pub fn tombstone_count(&self) -> usize {
+        self.segments.iter().map(|s| s.tombstones.len()).sum()
+    }
+
+    /// Whether the deltas are numerous or large enough to fold into the base.
+    pub fn needs_compaction(&self) -> bool {
+        self.segments.len() >= MAX_DELTA_SEGMENTS
+            || (self.delta_rows() + self.tombstone_count()) * DELTA_ROW_RATIO
+                > self.base_rows.max(1)
+    }
+}
+
+/// Where an appended delta's rows landed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentAppend {
+    /// Graph id of the delta's first row.
+    pub first_graph_id: usize,
+    /// Whether the manifest now asks for compaction.
+    pub needs_compaction: bool,
+}
+
+/// Result of folding the deltas into the base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionOutcome {
+    /// Deltas removed.
+    pub segments: usize,
+    /// Rows in the new base.
+    pub rows: usize,
+    /// Tombstoned rows dropped; when non-zero, graph ids were renumbered.
+    pub dropped_rows: usize,
+}
+
+/// Load a base CVVI file merged with its deltas.
+///
+/// Without deltas this is [`VectorIndex::load`], memory mapping included.
+pub fn load_segmented(index_path: &Path) -> Result<VectorIndex> {
+    // A compaction may swap the base between reading the manifest and the
+    // base; the row count check catches that and one retry picks up the new pair.
+    for attempt in 0..2 {
+        let manifest = SegmentManifest::load(index_path)?;
+        let base = VectorIndex::load(index_path)?;
+        let Some(manifest) = manifest.filter(|m| !m.segments.is_empty()) else {
+            return Ok(base);
+        };
+        if base.rows().len() != manifest.base_rows {
+            if attempt == 0 {
+                continue;
+            }
+            bail!(
+                "vector index base has {} rows but its segment manifest expects {}",
+                base.rows().len(),
+                manifest.base_rows
+            );
+        }
+        let merged = merge(index_path, base, &manifest)?;
+        let index = rebuild(&merged.base, merged.entries)?;
+        return if merged.dropped_rows > 0 {
+            index.with_graph_ids(merged.graph_ids)
+        } else {
+            Ok(index)
+        };
+    }
+    unreachable!("segment load loop always returns")
+}
+
+/// Append a delta segment holding `delta`'s rows and `tombstones`.
+///
+/// The base at `index_path` must already exist; `delta` must match its
+/// embedder, dimension and quantization.
+pub fn append_segment(
+    index_path: &Path,
+    delta: &VectorIndex,
+    tombstones: Vec<u64>,
+) -> Result<SegmentAppend> {
+    let _guard = SEGMENT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("segment lock poisoned"))?;
+
+    let mut manifest = match SegmentManifest::load(index_path)? {
+        Some(manifest) => manifest,
+        None => {
+            let base = VectorIndex::load(index_path)
+                .with_context(|| format!("load base vector index {index_path:?}"))?;
+            check_compatible(&base, delta)?;
+            SegmentManifest::new(base.rows().len())
+        }
+    };
+
+    let seq = manifest.next_seq;
+    delta.save(&delta_segment_path(index_path, seq))?;
+
+    let first_graph_id = manifest.total_rows();
+    manifest.next_seq += 1;
+    manifest.segments.push(DeltaSegment {
+        seq,
+        rows: delta.rows().len(),
+        tombstones,
+    });
+    manifest.save(index_path)?;
+
+    Ok(SegmentAppend {
+        first_graph_id,
+        needs_compaction: manifest.needs_compaction(),
+    })
+}
+
+/// Fold every delta into a new base and remove the delta files.
+///
+/// Returns `None` when there was nothing to compact.
+pub fn compact(index_path: &Path) -> Result<Option<CompactionOutcome>> {
+    let _guard = SEGMENT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("segment lock poisoned"))?;
+
+    let Some(manifest) = SegmentManifest::load(index_path)? else {
+        return Ok(None);
+    };
+    if manifest.segments.is_empty() {
+        std::fs::remove_file(segment_manifest_path(index_path))?;
+        return Ok(None);
+    }
+
+    let base = VectorIndex::load(index_path)?;
+    if base.rows().len() != manifest.base_rows {
+        bail!(
+            "vector index base has {} rows but its segment manifest expects {}",
+            base.rows().len(),
+            manifest.base_rows
+        );
+    }
+    let merged = merge(index_path, base, &manifest)?;
+    let dropped_rows = merged.dropped_rows;
+    let compacted = rebuild(&merged.base, merged.entries)?;
+    compacted.save(index_path)?;
+    let rows = compacted.rows().len();
+
+    // The new base is in place; drop the manifest before the deltas so a
+    // reader never pairs the new base with half-deleted deltas.
+    std::fs::remove_file(segment_manifest_path(index_path))?;
+    for segment in &manifest.segments {
+        remove_segment_files(index_path, segment.seq)?;
+    }
+
+    tracing::info!(
+        path = %index_path.display(),
+        segments = manifest.segments.len(),
+        rows,
+        dropped_rows,
+        "compacted vector index segments"
+    );
+    Ok(Some(CompactionOutcome {
+        segments: manifest.segments.len(),
+        rows,
+        dropped_rows,
+    }))
+}
+
+/// Remove the manifest and every delta for `index_path` (the base is kept).
+///
+/// Used before a full rebuild replaces the base.
+pub fn clear_segments(index_path: &Path) -> Result<()> {
+    let _guard = SEGMENT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("segment lock poisoned"))?;
+
+    let manifest = SegmentManifest::load(index_path)?;
+    let manifest_path = segment_manifest_path(index_path);
+    if manifest_path.exists() {
+        std::fs::remove_file(&manifest_path)?;
+    }
+    if let Some(manifest) = manifest {
+        for segment in &manifest.segments {
+            remove_segment_files(index_path, segment.seq)?;
+        }
+    }
+    Ok(())
+}
+
+/// Live rows of a base and its deltas, in graph id order.
+struct Merged {
+    base: VectorIndex,
+    entries: Vec<VectorEntry>,
+    graph_ids: Vec<u32>,
+    dropped_rows: usize,
+}
+
+/// Merge `base` with the manifest's deltas, skipping tombstoned rows.
+fn merge(index_path: &Path, base: VectorIndex, manifest: &SegmentManifest) -> Result<Merged> {
+    // Layer 0 is the base, layer i the i-th delta. A message's rows are dead
+    // in every layer below the newest delta that tombstones it.
+    let mut tombstoned_below: HashMap<u64, usize> = HashMap::new();
+    for (i, segment) in manifest.segments.iter().enumerate() {
+        for message_id in &segment.tombstones {
+            tombstoned_below.insert(*message_id, i + 1);
+        }
+    }
+
+    let mut layers = Vec::with_capacity(manifest.segments.len());
+    for segment in &manifest.segments {
+        let path = delta_segment_path(index_path, segment.seq);
+        let delta =
+            VectorIndex::load(&path).with_context(|| format!("load vector segment {path:?}"))?;
+        check_compatible(&base, &delta)?;
+        if delta.rows().len() != segment.rows {
+            bail!(
+                "vector segment {path:?} has {} rows but the manifest expects {}",
+                delta.rows().len(),
+                segment.rows
+            );
+        }
+        layers.push(delta);
+    }
+
+    let mut entries = Vec::new();
+    let mut graph_ids = Vec::new();
+    let mut graph_id = 0u32;
+    for (layer_idx, layer) in std::iter::once(&base).chain(&layers).enumerate() {
+        for entry in layer.to_entries()? {
+            let live = tombstoned_below
+                .get(&entry.message_id)
+                .is_none_or(|below| *below <= layer_idx);
+            if live {
+                entries.push(entry);
+                graph_ids.push(graph_id);
+            }
+            graph_id = graph_id
+                .checked_add(1)
+                .ok_or_else(|| anyhow!("vector segment row count out of range"))?;
+        }
+    }
+
+    let dropped_rows = graph_id as usize - entries.len();
+    Ok(Merged {
+        base,
+        entries,
+        graph_ids,
+        dropped_rows,
+    })
+}
+
+/// Build an index with `template`'s embedder, dimension and quantization.
+fn rebuild(template: &VectorIndex, entries: Vec<VectorEntry>) -> Result<VectorIndex> {
+    let header = template.header();
+    VectorIndex::build_with_rescore(
+        header.embedder_id.clone(),
+        header.embedder_revision.clone(),
+        header.dimension as usize,
+        header.quantization,
+        entries,
+    )
+}
+
+fn check_compatible(base: &VectorIndex, delta: &VectorIndex) -> Result<()> {
+    let (ours, theirs) = (base.header(), delta.header());
+    if theirs.embedder_id != ours.embedder_id
+        || theirs.dimension != ours.dimension
+        || theirs.quantization != ours.quantization
+    {
+        bail!(
+            "vector segment ({}, dim {}, {}) does not match base index ({}, dim {}, {})",
+            theirs.embedder_id,
+            theirs.dimension,
+            theirs.quantization.as_str(),
+            ours.embedder_id,
+            ours.dimension,
+            ours.quantization.as_str()
+        );
+    }
+    Ok(())
+}
+
+fn remove_segment_files(index_path: &Path, seq: u64) -> Result<()> {
+    let path = delta_segment_path(index_path, seq);
+    for file in [rescore_index_path(&path), path] {
+        match std::fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("remove vector segment {file:?}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::vector_index::Quantization;
+    use tempfile::tempdir;
+
+    fn entry(message_id: u64, seed: f32) -> VectorEntry {
+        let mut vector = vec![seed, 1.0 - seed, 0.5, -seed];
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        vector.iter_mut().for_each(|v| *v /= norm);
+        VectorEntry {
+            message_id,
+            created_at_ms: message_id as i64,
+            agent_id: 1,
+            workspace_id: 1,
+            source_id: 0,
+            role: 0,
+            chunk_idx: 0,
+            content_hash: [message_id as u8; 32],
+            vector,
+        }
+    }
+
+    fn build(quantization: Quantization, entries: Vec<VectorEntry>) -> VectorIndex {
+        VectorIndex::build_with_rescore("test", "1", 4, quantization, entries).unwrap()
+    }
+
+    fn message_ids(index: &VectorIndex) -> Vec<u64> {
+        index.rows().iter().map(|r| r.message_id).collect()
+    }
+
+    #[test]
+    fn segment_paths_sit_next_to_base() {
+        let base = Path::new("/data/vector_index/index-hash.cvvi");
+        assert_eq!(
+            segment_manifest_path(base),
+            PathBuf::from("/data/vector_index/index-hash.segments.json")
+        );
+        assert_eq!(
+            delta_segment_path(base, 3),
+            PathBuf::from("/data/vector_index/index-hash.seg-3.cvvi")
+        );
+    }
+
+    #[test]
+    fn load_without_segments_returns_base() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("index-test.cvvi");
+        build(Quantization::F32, vec![entry(1, 0.1), entry(2, 0.2)])
+            .save(&path)
+            .unwrap();
+
+        let loaded = load_segmented(&path).unwrap();
+        assert_eq!(message_ids(&loaded), vec![1, 2]);
+        assert_eq!(SegmentManifest::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn appended_segments_merge_with_tombstones_and_graph_ids() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("index-test.cvvi");
+        build(Quantization::F16, vec![entry(1, 0.1), entry(2, 0.2)])
+            .save(&path)
+            .unwrap();
+
+        let first = append_segment(
+            &path,
+            &build(Quantization::F16, vec![entry(3, 0.3)]),
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(first.first_graph_id, 2);
+
+        // Message 1 is edited (tombstone + new row), message 3 is deleted.
+        let second = append_segment(
+            &path,
+            &build(Quantization::F16, vec![entry(1, 0.9)]),
+            vec![1, 3],
+        )
+        .unwrap();
+        assert_eq!(second.first_graph_id, 3);
+
+        let merged = load_segmented(&path).unwrap();
+        assert_eq!(message_ids(&merged), vec![2, 1]);
+        assert_eq!(merged.graph_id(0), 1);
+        assert_eq!(merged.graph_id(1), 3);
+        assert!(merged.row_for_graph_id(0).is_none());
+        assert!(merged.row_for_graph_id(2).is_none());
+        assert_eq!(merged.row_for_graph_id(3).unwrap().message_id, 1);
+
+        let hits = merged.search_top_k(&entry(0, 0.9).vector, 1, None).unwrap();
+        assert_eq!(hits[0].message_id, 1);
+
+        let manifest = SegmentManifest::load(&path).unwrap().unwrap();
+        assert_eq!(manifest.total_rows(), 4);
+        assert_eq!(manifest.tombstone_count(), 2);
+    }
+
+    #[test]
+    fn compaction_folds_segments_into_base() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("index-test.cvvi");
+        build(Quantization::Int8, vec![entry(1, 0.1), entry(2, 0.2)])
+            .save(&path)
+            .unwrap();
+        append_segment(
+            &path,
+            &build(Quantization::Int8, vec![entry(3, 0.3)]),
+            vec![2],
+        )
+        .unwrap();
+        let seg_path = delta_segment_path(&path, 1);
+        assert!(seg_path.exists());
+        assert!(rescore_index_path(&seg_path).exists());
+
+        let outcome = compact(&path).unwrap().unwrap();
+        assert_eq!(
+            outcome,
+            CompactionOutcome {
+                segments: 1,
+                rows: 2,
+                dropped_rows: 1,
+            }
+        );
+        assert!(!seg_path.exists());
+        assert!(!rescore_index_path(&seg_path).exists());
+        assert!(!segment_manifest_path(&path).exists());
+
+        let loaded = load_segmented(&path).unwrap();
+        assert_eq!(message_ids(&loaded), vec![1, 3]);
+        assert_eq!(loaded.header().quantization, Quantization::Int8);
+        assert!(loaded.rescore_index().is_some());
+        assert_eq!(loaded.graph_id(1), 1);
+        assert_eq!(compact(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn append_rejects_mismatched_quantization() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("index-test.cvvi");
+        build(Quantization::F32, vec![entry(1, 0.1)])
+            .save(&path)
+            .unwrap();
+        let err = append_segment(
+            &path,
+            &build(Quantization::F16, vec![entry(2, 0.2)]),
+            Vec::new(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("does not match"));
+    }
+
+    #[test]
+    fn manifest_requests_compaction_when_deltas_grow() {
+        let mut manifest = SegmentManifest::new(100);
+        manifest.segments.push(DeltaSegment {
+            seq: 1,
+            rows: 10,
+            tombstones: Vec::new(),
+        });
+        assert!(!manifest.needs_compaction());
+        manifest.segments[0].rows = 30;
+        assert!(manifest.needs_compaction());
+
+        let mut many = SegmentManifest::new(1_000_000);
+        many.segments = (1..=MAX_DELTA_SEGMENTS as u64)
+            .map(|seq| DeltaSegment {
+                seq,
+                rows: 1,
+                tombstones: Vec::new(),
+            })
+            .collect();
+        assert!(many.needs_compaction());
+    }
+}
@@ -0,0 +1,1192 @@
+//! Local HTTP/JSON API for `cass serve`.
+//!
+//! Exposes the read-only robot commands as `GET`/`POST /v1/<endpoint>`:
+//! `search`, `view`, `expand`, `timeline`, `stats`, `health`, `sources` and
+//! `related`.
+//! Query parameters (or a JSON object body) use the CLI flag names, exactly
+//! like `cass mcp` tool arguments, and each request runs in-process as the
+//! equivalent `cass <command> --json --robot-meta` call. Response bodies are
+//! that command's robot output, so `--fields`, cursor pagination and `_meta`
+//! behave as they do on the command line.
+//!
+//! `GET /openapi.json` describes every endpoint. Like the MCP tool schemas it
+//! is generated from the definitions `cass introspect` publishes.
+//!
+//! Requests are handled one at a time on the serving thread, which keeps the
+//! search index warm between calls. This is a local API: binding beyond
+//! loopback requires a bearer token, and without one only requests addressed
+//! to a loopback `Host` are answered, so a DNS-rebound web page cannot reach
+//! it. `view` and `expand` only read session files that are in the index.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::CommandFactory;
+use serde_json::{Map, Value, json};
+
+use crate::mcp::{Tool, argument_json_schema, run_argv, with_warm_clients};
+use crate::storage::sqlite::SqliteStorage;
+use crate::{ArgumentSchema, Cli, CliError, CliResult};
+
+/// Default listen address.
+pub const DEFAULT_BIND: &str = "127.0.0.1:8765";
+
+/// Endpoints served under `/v1/`, with the subcommand each one runs.
+pub const ENDPOINTS: &[(&str, &[&str])] = &[
+    ("search", &["search"]),
+    ("view", &["view"]),
+    ("expand", &["expand"]),
+    ("timeline", &["timeline"]),
+    ("stats", &["stats"]),
+    ("health", &["health"]),
+    ("sources", &["sources", "list"]),
+    ("related", &["related"]),
+];
+
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `Host` names accepted when no token is configured.
+const LOOPBACK_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+/// Endpoints whose `path` argument opens a file, limited to indexed sessions.
+const PATH_ENDPOINTS: &[&str] = &["view", "expand"];
+
+/// Settings shared by every request.
+#[derive(Debug, Clone, Default)]
+pub struct ServeConfig {
+    pub data_dir: Option<PathBuf>,
+    pub db: Option<PathBuf>,
+    /// Bearer token required on every request, if set.
+    pub token: Option<String>,
+    /// Origins allowed to call the API from a browser (`*` allows any).
+    pub cors_origins: Vec<String>,
+}
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Percent-decoded path without the query string.
+    pub path: String,
+    /// Percent-decoded query parameters, in order.
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Port the request arrived on, when known.
+    pub local_port: Option<u16>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// An HTTP response; bodies are always JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, body: &Value) -> Self {
+        let mut body = serde_json::to_vec_pretty(body).unwrap_or_default();
+        body.push(b'\n');
+        Self {
+            status,
+            headers: vec![(
+                "Content-Type".to_string(),
+                "application/json; charset=utf-8".to_string(),
+            )],
+            body,
+        }
+    }
+
+    fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// The robot error envelope, `{"error": {code, kind, message, hint, retryable}}`.
+    fn error(status: u16, err: &CliError) -> Self {
+        Self::json(
+            status,
+            &json!({
+                "error": {
+                    "code": err.code,
+                    "kind": err.kind,
+                    "message": err.message,
+                    "hint": err.hint,
+                    "retryable": err.retryable,
+                }
+            }),
+        )
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::error(400, &CliError::usage(message, None))
+    }
+
+    fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "Content-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        ));
+        out.write_all(head.as_bytes())?;
+        out.write_all(&self.body)?;
+        out.flush()
+    }
+}
+
+/// An endpoint backed by one CLI subcommand.
+struct Endpoint {
+    name: &'static str,
+    tool: Tool,
+}
+
+/// API server state: the endpoint table plus the settings every call uses.
+pub struct ApiServer {
+    config: ServeConfig,
+    endpoints: Vec<Endpoint>,
+}
+
+/// Bind the listener, refusing unauthenticated access from beyond loopback.
+pub fn bind(addr: &str, config: &ServeConfig) -> CliResult<TcpListener> {
+    let listener = TcpListener::bind(addr).map_err(|e| CliError {
+        code: 9,
+        kind: "io",
+        message: format!("failed to bind {addr}: {e}"),
+        hint: Some("Pick a free port with --bind 127.0.0.1:PORT".to_string()),
+        retryable: true,
+    })?;
+    let local = listener.local_addr().map_err(|e| CliError {
+        code: 9,
+        kind: "io",
+        message: format!("failed to read listen address: {e}"),
+        hint: None,
+        retryable: true,
+    })?;
+    if config.token.is_none() && !local.ip().is_loopback() {
+        return Err(CliError::usage(
+            format!("refusing to serve {local} without a token"),
+            Some(
+                "Pass --token (or set CASS_SERVE_TOKEN), or bind to a loopback address".to_string(),
+            ),
+        ));
+    }
+    Ok(listener)
+}
+
+impl ApiServer {
+    pub fn new(config: ServeConfig) -> Self {
+        let root = Cli::command();
+        let endpoints = ENDPOINTS
+            .iter()
+            .filter_map(|&(name, path)| {
+                let mut command = &root;
+                for part in path {
+                    command = command.find_subcommand(part)?;
+                }
+                let mut schema = crate::command_schema_from_clap(command);
+                schema.name = name.to_string();
+                Some(Endpoint {
+                    name,
+                    tool: Tool::new(schema, path),
+                })
+            })
+            .collect();
+        Self { config, endpoints }
+    }
+
+    /// Answer connections, one request each, for as long as the process runs.
+    pub fn serve(&self, listener: TcpListener) {
+        with_warm_clients(|| {
+            for stream in listener.incoming() {
+                let result = stream.and_then(|stream| self.handle_connection(stream));
+                if let Err(err) = result {
+                    tracing::debug!(error = %err, "http connection failed");
+                }
+            }
+        });
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match read_request(&mut reader) {
+            Ok(mut request) => {
+                request.local_port = stream.local_addr().ok().map(|addr| addr.port());
+                self.handle(&request)
+            }
+            Err(rejection) => rejection,
+        };
+        response.write_to(&mut stream)
+    }
+
+    /// Route one request, applying auth and CORS.
+    pub fn handle(&self, request: &Request) -> Response {
+        let mut response = self.route(request);
+        if let Some(origin) = request
+            .header("origin")
+            .and_then(|origin| self.allowed_origin(origin))
+        {
+            if origin != "*" {
+                response = response.with_header("Vary", "Origin");
+            }
+            response = response.with_header("Access-Control-Allow-Origin", origin);
+            if request.method == "OPTIONS" {
+                response = response
+                    .with_header("Access-Control-Allow-Methods", "GET, POST, OPTIONS")
+                    .with_header(
+                        "Access-Control-Allow-Headers",
+                        "Authorization, Content-Type",
+                    )
+                    .with_header("Access-Control-Max-Age", "600");
+            }
+        }
+        response
+    }
+
+    fn route(&self, request: &Request) -> Response {
+        if let Err(err) = self.check_host(request) {
+            return Response::error(403, &err);
+        }
+        // Preflights carry no credentials; the real request is checked.
+        if request.method == "OPTIONS" {
+            return Response::empty(204);
+        }
+        if let Err(err) = self.authorize(request) {
+            return Response::error(401, &err).with_header("WWW-Authenticate", "Bearer");
+        }
+        if request.path == "/openapi.json" {
+            return match request.method.as_str() {
+                "GET" => Response::json(200, &self.openapi()),
+                _ => method_not_allowed("GET, OPTIONS"),
+            };
+        }
+        let Some(endpoint) = request
+            .path
+            .strip_prefix("/v1/")
+            .and_then(|name| self.endpoints.iter().find(|e| e.name == name))
+        else {
+            return Response::error(
+                404,
+                &CliError {
+                    code: 2,
+                    kind: "not-found",
+                    message: format!("no endpoint at {}", request.path),
+                    hint: Some("GET /openapi.json lists the available endpoints".to_string()),
+                    retryable: false,
+                },
+            );
+        };
+        let arguments = match request.method.as_str() {
+            "GET" => query_arguments(&endpoint.tool.schema.arguments, &request.query),
+            "POST" => body_arguments(&request.body),
+            _ => return method_not_allowed("GET, POST, OPTIONS"),
+        };
+        let arguments = match arguments {
+            Ok(arguments) => arguments,
+            Err(message) => return Response::bad_request(message),
+        };
+        if PATH_ENDPOINTS.contains(&endpoint.name)
+            && let Err(err) = self.check_indexed_path(&arguments)
+        {
+            return Response::error(error_status(&err), &err);
+        }
+        self.call(endpoint, &arguments)
+    }
+
+    /// Without a token, only answer requests addressed to loopback on our port.
+    ///
+    /// Browsers send the page's own host name, so this rejects DNS rebinding
+    /// even though the connection itself comes from 127.0.0.1.
+    fn check_host(&self, request: &Request) -> CliResult<()> {
+        if self.config.token.is_some() {
+            return Ok(());
+        }
+        let host = request.header("host").unwrap_or_default();
+        if is_loopback_host(host, request.local_port) {
+            return Ok(());
+        }
+        Err(CliError {
+            code: 2,
+            kind: "forbidden-host",
+            message: format!("refusing request for Host '{host}'"),
+            hint: Some(
+                "Address the API as localhost or 127.0.0.1, or run with --token to accept other hosts"
+                    .to_string(),
+            ),
+            retryable: false,
+        })
+    }
+
+    /// Refuse `path` arguments that are not the `source_path` of an indexed
+    /// conversation, so the API cannot read arbitrary files.
+    fn check_indexed_path(&self, arguments: &Map<String, Value>) -> CliResult<()> {
+        let path = match arguments.get("path") {
+            // A missing path is reported by the command itself.
+            None => return Ok(()),
+            Some(Value::String(path)) => path,
+            Some(_) => return Err(CliError::usage("'path' must be a string", None)),
+        };
+        let db_path = self.config.db.clone().unwrap_or_else(|| {
+            self.config
+                .data_dir
+                .clone()
+                .unwrap_or_else(crate::default_data_dir)
+                .join("agent_search.db")
+        });
+        let indexed = SqliteStorage::open_readonly(&db_path)
+            .and_then(|storage| storage.list_conversations_for_path(path))
+            .map_err(|e| CliError {
+                code: 9,
+                kind: "db-open",
+                message: format!("failed to look up {path} in {}: {e}", db_path.display()),
+                hint: Some("Run 'cass index' to build the database".to_string()),
+                retryable: true,
+            })?;
+        if indexed.is_empty() {
+            return Err(CliError {
+                code: 3,
+                kind: "session-not-found",
+                message: format!("{path} is not an indexed session"),
+                hint: Some(
+                    "Only indexed session files can be read; use the source_path from /v1/search"
+                        .to_string(),
+                ),
+                retryable: false,
+            });
+        }
+        Ok(())
+    }
+
+    fn authorize(&self, request: &Request) -> CliResult<()> {
+        let Some(token) = &self.config.token else {
+            return Ok(());
+        };
+        let presented = request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        match presented {
+            Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => Ok(()),
+            _ => Err(CliError {
+                code: 2,
+                kind: "unauthorized",
+                message: "missing or invalid bearer token".to_string(),
+                hint: Some("Send 'Authorization: Bearer <token>' with every request".to_string()),
+                retryable: false,
+            }),
+        }
+    }
+
+    fn allowed_origin<'a>(&'a self, origin: &'a str) -> Option<&'a str> {
+        if self.config.cors_origins.iter().any(|o| o == "*") {
+            Some("*")
+        } else {
+            self.config
+                .cors_origins
+                .iter()
+                .any(|o| o.trim_end_matches('/') == origin)
+                .then_some(origin)
+        }
+    }
+
+    fn call(&self, endpoint: &Endpoint, arguments: &Map<String, Value>) -> Response {
+        let argv = match endpoint.tool.argv(
+            self.config.db.as_deref(),
+            self.config.data_dir.as_deref(),
+            arguments,
+            true,
+        ) {
+            Ok(argv) => argv,
+            Err(message) => return Response::bad_request(message),
+        };
+        match run_argv(&argv) {
+            (payload, Ok(())) => Response::json(200, &payload),
+            // An unhealthy `health` still reports its state.
+            (payload, Err(err)) if !payload.is_null() => {
+                Response::json(error_status(&err), &payload)
+            }
+            (_, Err(err)) => Response::error(error_status(&err), &err),
+        }
+    }
+
+    /// OpenAPI 3.1 document generated from the introspection schemas.
+    pub fn openapi(&self) -> Value {
+        let response_schemas = crate::build_response_schemas();
+        let error = json!({ "$ref": "#/components/responses/Error" });
+        let mut paths = Map::new();
+        for endpoint in &self.endpoints {
+            let schema = &endpoint.tool.schema;
+            let parameters: Vec<Value> = schema.arguments.iter().map(query_parameter).collect();
+            let mut properties = Map::new();
+            let mut required = Vec::new();
+            for arg in &schema.arguments {
+                properties.insert(arg.name.clone(), argument_json_schema(arg));
+                if arg.required {
+                    required.push(Value::String(arg.name.clone()));
+                }
+            }
+            let body = json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            });
+            let output = response_schemas
+                .get(endpoint.name)
+                .cloned()
+                .unwrap_or_else(|| json!({ "type": "object" }));
+            let ok = json!({
+                "description": format!("Output of `cass {}` in robot mode", schema.name),
+                "content": { "application/json": { "schema": output } },
+            });
+            paths.insert(
+                format!("/v1/{}", endpoint.name),
+                json!({
+                    "get": {
+                        "operationId": endpoint.name,
+                        "summary": schema.description,
+                        "parameters": parameters,
+                        "responses": { "200": ok, "default": error },
+                    },
+                    "post": {
+                        "operationId": format!("{}_post", endpoint.name),
+                        "summary": schema.description,
+                        "requestBody": {
+                            "required": !required.is_empty(),
+                            "content": { "application/json": { "schema": body } },
+                        },
+                        "responses": { "200": ok, "default": error },
+                    },
+                }),
+            );
+        }
+        paths.insert(
+            "/openapi.json".to_string(),
+            json!({
+                "get": {
+                    "operationId": "openapi",
+                    "summary": "This document",
+                    "responses": {
+                        "200": {
+                            "description": "OpenAPI document",
+                            "content": { "application/json": { "schema": { "type": "object" } } },
+                        },
+                        "default": error,
+                    },
+                }
+            }),
+        );
+        let security = if self.config.token.is_some() {
+            json!([{ "bearerAuth": [] }])
+        } else {
+            json!([])
+        };
+        json!({
+            "openapi": "3.1.0",
+            "info": {
+                "title": "cass",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "Search and read coding agent session history. Parameters use \
+                    the CLI flag names; responses are the robot (`--json`) output of the \
+                    matching command.",
+            },
+            "x-contract-version": crate::CONTRACT_VERSION,
+            "paths": paths,
+            "security": security,
+            "components": {
+                "securitySchemes": { "bearerAuth": { "type": "http", "scheme": "bearer" } },
+                "responses": {
+                    "Error": {
+                        "description": "Robot error envelope",
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Error" }
+                            }
+                        },
+                    }
+                },
+                "schemas": {
+                    "Error": {
+                        "type": "object",
+                        "required": ["error"],
+                        "properties": {
+                            "error": {
+                                "type": "object",
+                                "required": ["code", "kind", "message", "retryable"],
+                                "properties": {
+                                    "code": { "type": "integer" },
+                                    "kind": { "type": "string" },
+                                    "message": { "type": "string" },
+                                    "hint": { "type": ["string", "null"] },
+                                    "retryable": { "type": "boolean" },
+                                },
+                            }
+                        },
+                    }
+                },
+            },
+        })
+    }
+}
+
+/// Read one request from `reader`, or the response rejecting it.
+pub fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let mut budget = MAX_HEAD_BYTES;
+    let request_line = read_head_line(reader, &mut budget)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(Response::bad_request("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(Response::bad_request(format!(
+            "unsupported protocol {version}"
+        )));
+    }
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_head_line(reader, &mut budget)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(Response::bad_request(format!("malformed header: {line}")));
+        };
+        headers.push((name.trim().to_string(), value.trim().to_string()));
+    }
+
+    if find_header(&headers, "transfer-encoding").is_some() {
+        return Err(Response::error(
+            411,
+            &CliError::usage("chunked request bodies are not supported", None),
+        ));
+    }
+    let length = match find_header(&headers, "content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| Response::bad_request("invalid Content-Length"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(Response::error(
+            413,
+            &CliError::usage(format!("request body exceeds {MAX_BODY_BYTES} bytes"), None),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| Response::bad_request(format!("failed to read request body: {e}")))?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_string(),
+        path: decode_component(path),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (
+                    decode_component(&key.replace('+', " ")),
+                    decode_component(&value.replace('+', " ")),
+                )
+            })
+            .collect(),
+        headers,
+        body,
+        local_port: None,
+    })
+}
+
+fn read_head_line(reader: &mut impl BufRead, budget: &mut usize) -> Result<String, Response> {
+    let mut line = Vec::new();
+    let read = Read::take(&mut *reader, *budget as u64 + 1)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| Response::bad_request(format!("failed to read request: {e}")))?;
+    if read > *budget {
+        return Err(Response::error(
+            431,
+            &CliError::usage("request head is too large", None),
+        ));
+    }
+    *budget -= read;
+    let line =
+        String::from_utf8(line).map_err(|_| Response::bad_request("request is not UTF-8"))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn decode_component(raw: &str) -> String {
+    urlencoding::decode(raw).map_or_else(|_| raw.to_string(), |decoded| decoded.into_owned())
+}
+
+/// Query parameters as tool arguments: flags become booleans and repeatable
+/// options collect every occurrence.
+fn query_arguments(
+    schema: &[ArgumentSchema],
+    query: &[(String, String)],
+) -> Result<Map<String, Value>, String> {
+    let mut arguments = Map::new();
+    for (key, value) in query {
+        let arg = schema.iter().find(|arg| &arg.name == key);
+        let value = match arg {
+            Some(arg) if arg.arg_type == "flag" => match value.as_str() {
+                "" | "true" | "1" | "yes" => Value::Bool(true),
+                "false" | "0" | "no" => Value::Bool(false),
+                _ => return Err(format!("'{key}' must be true or false")),
+            },
+            _ => Value::String(value.clone()),
+        };
+        if arg.is_some_and(|arg| arg.repeatable == Some(true)) {
+            let entry = arguments
+                .entry(key.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(values) = entry {
+                values.push(value);
+            }
+        } else if arguments.insert(key.clone(), value).is_some() {
+            return Err(format!("'{key}' given more than once"));
+        }
+    }
+    Ok(arguments)
+}
+
+fn body_arguments(body: &[u8]) -> Result<Map<String, Value>, String> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Map::new());
+    }
+    match serde_json::from_slice(body) {
+        Ok(Value::Object(arguments)) => Ok(arguments),
+        Ok(_) => Err("request body must be a JSON object".to_string()),
+        Err(err) => Err(format!("invalid JSON body: {err}")),
+    }
+}
+
+fn query_parameter(arg: &ArgumentSchema) -> Value {
+    let mut schema = argument_json_schema(arg);
+    let description = schema
+        .as_object_mut()
+        .and_then(|schema| schema.remove("description"));
+    let mut parameter = json!({
+        "name": arg.name,
+        "in": "query",
+        "required": arg.required,
+        "schema": schema,
+    });
+    if let Some(description) = description {
+        parameter["description"] = description;
+    }
+    if arg.repeatable == Some(true) {
+        parameter["explode"] = Value::Bool(true);
+    }
+    parameter
+}
+
+/// HTTP status for a command failure.
+fn error_status(err: &CliError) -> u16 {
+    match err.kind {
+        "health"
+        | "missing-index"
+        | "missing-db"
+        | "open-index"
+        | "db-open"
+        | "semantic-unavailable"
+        | "embedder-unavailable" => 503,
+        "timeout" => 504,
+        kind if kind.ends_with("not-found") || kind == "line-out-of-range" => 404,
+        "usage" | "invalid-line" | "cursor-parse" => 400,
+        _ if err.code == 2 => 400,
+        _ => 500,
+    }
+}
+
+fn method_not_allowed(allow: &str) -> Response {
+    Response::error(
+        405,
+        &CliError::usage(format!("method not allowed; use {allow}"), None),
+    )
+    .with_header("Allow", allow)
+}
+
+/// True if `host` (a `Host` header) names loopback, on `port` when known.
+fn is_loopback_host(host: &str, port: Option<u16>) -> bool {
+    let (name, host_port) = match host.rfind(':') {
+        // The colon must follow any IPv6 literal, not sit inside it.
+        Some(i) if !host[i..].contains(']') => (&host[..i], Some(&host[i + 1..])),
+        _ => (host, None),
+    };
+    let loopback = LOOPBACK_HOSTS
+        .iter()
+        .any(|allowed| name.eq_ignore_ascii_case(allowed));
+    let port_matches = match (port, host_port) {
+        (None, _) => true,
+        (Some(port), Some(given)) => given.parse::<u16>() == Ok(port),
+        (Some(port), None) => port == 80,
+    };
+    loopback && port_matches
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn server(config: ServeConfig) -> ApiServer {
+        ApiServer::new(config)
+    }
+
+    fn get(target: &str) -> Request {
+        parse(&format!("GET {target} HTTP/1.1\r\nHost: localhost\r\n\r\n")).unwrap()
+    }
+
+    fn parse(raw: &str) -> Result<Request, Response> {
+        read_request(&mut raw.as_bytes())
+    }
+
+    fn body(response: &Response) -> Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    /// A database with one local conversation read from `source_path`.
+    fn index_session(db_path: &Path, source_path: &Path) {
+        use crate::model::types::{Agent, AgentKind, Conversation};
+
+        let mut storage = SqliteStorage::open(db_path).unwrap();
+        let agent_id = storage
+            .ensure_agent(&Agent {
+                id: None,
+                slug: "codex".to_string(),
+                name: "Codex".to_string(),
+                version: None,
+                kind: AgentKind::Cli,
+            })
+            .unwrap();
+        let conv = Conversation {
+            id: None,
+            agent_slug: "codex".to_string(),
+            workspace: None,
+            external_id: Some("notes".to_string()),
+            title: None,
+            source_path: source_path.to_path_buf(),
+            started_at: None,
+            ended_at: None,
+            approx_tokens: None,
+            metadata_json: Value::Null,
+            messages: Vec::new(),
+            source_id: "local".to_string(),
+            origin_host: None,
+        };
+        storage
+            .insert_conversation_tree(agent_id, None, &conv)
+            .unwrap();
+    }
+
+    #[test]
+    fn parses_request_line_query_and_body() {
+        let request = parse(concat!(
+            "POST /v1/search?query=auth+timeout&agent=codex&agent=claude%5Fcode HTTP/1.1\r\n",
+            "Content-Type: application/json\r\n",
+            "Content-Length: 15\r\n",
+            "\r\n",
+            "{\"limit\": 3}   ",
+        ))
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/v1/search");
+        assert_eq!(
+            request.query,
+            [
+                ("query".to_string(), "auth timeout".to_string()),
+                ("agent".to_string(), "codex".to_string()),
+                ("agent".to_string(), "claude_code".to_string()),
+            ]
+        );
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.body, b"{\"limit\": 3}   ");
+
+        assert_eq!(parse("nonsense\r\n\r\n").unwrap_err().status, 400);
+        let chunked = "POST /v1/search HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n";
+        assert_eq!(parse(chunked).unwrap_err().status, 411);
+        let huge = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEAD_BYTES));
+        assert_eq!(parse(&huge).unwrap_err().status, 431);
+    }
+
+    #[test]
+    fn query_parameters_become_tool_arguments() {
+        let server = server(ServeConfig::default());
+        let search = &server.endpoints[0].tool.schema.arguments;
+        let query = [
+            ("query", "auth"),
+            ("agent", "codex"),
+            ("agent", "claude_code"),
+            ("limit", "3"),
+            ("explain", ""),
+            ("fields", "source_path,line_number"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let arguments = query_arguments(search, &query).unwrap();
+        assert_eq!(
+            Value::Object(arguments),
+            json!({
+                "query": "auth",
+                "agent": ["codex", "claude_code"],
+                "limit": "3",
+                "explain": true,
+                "fields": ["source_path,line_number"],
+            })
+        );
+
+        let twice = vec![("limit".to_string(), "1".to_string()); 2];
+        let err = query_arguments(search, &twice).unwrap_err();
+        assert!(err.contains("more than once"));
+        let err = query_arguments(search, &[("explain".into(), "maybe".into())]).unwrap_err();
+        assert!(err.contains("true or false"));
+    }
+
+    #[test]
+    fn endpoints_run_with_robot_meta_and_nested_commands() {
+        let server = server(ServeConfig {
+            data_dir: Some(PathBuf::from("/data")),
+            ..ServeConfig::default()
+        });
+        let names: Vec<&str> = server.endpoints.iter().map(|e| e.name).collect();
+        let expected: Vec<&str> = ENDPOINTS.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, expected);
+
+        let search = &server.endpoints[0].tool;
+        let args = json!({ "query": "auth", "cursor": "abc" });
+        let argv = search
+            .argv(
+                None,
+                Some(Path::new("/data")),
+                args.as_object().unwrap(),
+                true,
+            )
+            .unwrap();
+        assert!(argv.contains(&"--robot-meta".to_string()));
+        assert!(argv.contains(&"--cursor=abc".to_string()));
+        assert!(Cli::try_parse_from(&argv).is_ok());
+
+        let sources = &server.endpoints[6].tool;
+        let argv = sources.argv(None, None, &Map::new(), true).unwrap();
+        assert_eq!(argv, ["cass", "sources", "list", "--json"]);
+    }
+
+    #[test]
+    fn view_endpoint_returns_robot_json() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let path = urlencoding::encode(&file.to_string_lossy()).into_owned();
+        let db = dir.path().join("agent_search.db");
+        index_session(&db, &file);
+
+        let server = server(ServeConfig {
+            db: Some(db),
+            ..ServeConfig::default()
+        });
+        let response = server.handle(&get(&format!("/v1/view?path={path}&line=2&context=0")));
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            response.header("content-type"),
+            Some("application/json; charset=utf-8")
+        );
+        assert!(body(&response).to_string().contains("two"));
+
+        let response = server.handle(&get("/v1/view?path=/no/such/session.jsonl"));
+        assert_eq!(response.status, 404);
+        assert_eq!(body(&response)["error"]["kind"], "session-not-found");
+
+        let response = server.handle(&get("/v1/view?bogus=1"));
+        assert_eq!(response.status, 400);
+        assert!(
+            body(&response)["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("unknown argument 'bogus'")
+        );
+
+        assert_eq!(server.handle(&get("/v1/index")).status, 404);
+        let mut delete = get("/v1/view");
+        delete.method = "DELETE".to_string();
+        let response = server.handle(&delete);
+        assert_eq!(response.status, 405);
+        assert_eq!(response.header("allow"), Some("GET, POST, OPTIONS"));
+    }
+
+    #[test]
+    fn view_and_expand_only_read_indexed_sessions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let session = dir.path().join("session.jsonl");
+        std::fs::write(&session, "{\"type\":\"user\"}\n").unwrap();
+        let secret = dir.path().join("id_rsa");
+        std::fs::write(&secret, "PRIVATE KEY\n").unwrap();
+        let db = dir.path().join("agent_search.db");
+        index_session(&db, &session);
+        let server = server(ServeConfig {
+            db: Some(db),
+            ..ServeConfig::default()
+        });
+
+        for endpoint in PATH_ENDPOINTS {
+            let path = urlencoding::encode(&secret.to_string_lossy()).into_owned();
+            let response = server.handle(&get(&format!("/v1/{endpoint}?path={path}&line=1")));
+            assert_eq!(response.status, 404, "{endpoint}");
+            assert_eq!(body(&response)["error"]["kind"], "session-not-found");
+            assert!(!body(&response).to_string().contains("PRIVATE KEY"));
+
+            // Starting with an indexed path is not enough.
+            let dotted = session.join("../id_rsa");
+            let path = urlencoding::encode(&dotted.to_string_lossy()).into_owned();
+            let response = server.handle(&get(&format!("/v1/{endpoint}?path={path}&line=1")));
+            assert_eq!(response.status, 404, "{endpoint}");
+        }
+
+        let mut post = get("/v1/view");
+        post.method = "POST".to_string();
+        post.body = serde_json::to_vec(&json!({ "path": secret })).unwrap();
+        assert_eq!(server.handle(&post).status, 404);
+        post.body = br#"{"path": 7}"#.to_vec();
+        assert_eq!(server.handle(&post).status, 400);
+
+        let path = urlencoding::encode(&session.to_string_lossy()).into_owned();
+        let response = server.handle(&get(&format!("/v1/view?path={path}&line=1")));
+        assert_eq!(response.status, 200);
+    }
+
+    #[test]
+    fn foreign_host_is_rejected_without_token() {
+        let server = server(ServeConfig::default());
+        let mut request = get("/openapi.json");
+        for host in ["localhost", "127.0.0.1:80", "[::1]", "LOCALHOST"] {
+            request.headers[0].1 = host.to_string();
+            assert_eq!(server.handle(&request).status, 200, "{host}");
+        }
+
+        // A DNS-rebound page sends its own host name.
+        for host in [
+            "evil.example:8765",
+            "localhost.evil.example",
+            "",
+            "127.0.0.2",
+        ] {
+            request.headers[0].1 = host.to_string();
+            let response = server.handle(&request);
+            assert_eq!(response.status, 403, "{host}");
+            assert_eq!(body(&response)["error"]["kind"], "forbidden-host");
+        }
+        request.headers.clear();
+        assert_eq!(server.handle(&request).status, 403);
+        let mut preflight = get("/v1/view");
+        preflight.method = "OPTIONS".to_string();
+        preflight.headers[0].1 = "evil.example".to_string();
+        assert_eq!(server.handle(&preflight).status, 403);
+
+        // Once the port is known it has to match as well.
+        let mut request = get("/openapi.json");
+        request.local_port = Some(8765);
+        request.headers[0].1 = "localhost:8765".to_string();
+        assert_eq!(server.handle(&request).status, 200);
+        request.headers[0].1 = "localhost:9999".to_string();
+        assert_eq!(server.handle(&request).status, 403);
+        request.headers[0].1 = "localhost".to_string();
+        assert_eq!(server.handle(&request).status, 403);
+
+        // With a token, any Host is fine.
+        let server = ApiServer::new(ServeConfig {
+            token: Some("t".to_string()),
+            ..ServeConfig::default()
+        });
+        request.headers = vec![
+            ("Host".to_string(), "evil.example".to_string()),
+            ("Authorization".to_string(), "Bearer t".to_string()),
+        ];
+        assert_eq!(server.handle(&request).status, 200);
+    }
+
+    #[test]
+    fn token_is_required_when_configured() {
+        let server = server(ServeConfig {
+            token: Some("s3cret".to_string()),
+            ..ServeConfig::default()
+        });
+        let response = server.handle(&get("/openapi.json"));
+        assert_eq!(response.status, 401);
+        assert_eq!(response.header("www-authenticate"), Some("Bearer"));
+        assert_eq!(body(&response)["error"]["kind"], "unauthorized");
+
+        let mut request = get("/openapi.json");
+        request
+            .headers
+            .push(("Authorization".to_string(), "Bearer wrong".to_string()));
+        assert_eq!(server.handle(&request).status, 401);
+
+        request.headers.pop();
+        request
+            .headers
+            .push(("Authorization".to_string(), "Bearer s3cret".to_string()));
+        let response = server.handle(&request);
+        assert_eq!(response.status, 200);
+        assert_eq!(body(&response)["security"], json!([{ "bearerAuth": [] }]));
+    }
+
+    #[test]
+    fn cors_headers_follow_allowed_origins() {
+        let server = server(ServeConfig {
+            token: Some("t".to_string()),
+            cors_origins: vec!["http://localhost:3000/".to_string()],
+            ..ServeConfig::default()
+        });
+        let mut preflight = get("/v1/search");
+        preflight.method = "OPTIONS".to_string();
+        preflight
+            .headers
+            .push(("Origin".to_string(), "http://localhost:3000".to_string()));
+        let response = server.handle(&preflight);
+        assert_eq!(response.status, 204);
+        assert_eq!(
+            response.header("access-control-allow-origin"),
+            Some("http://localhost:3000")
+        );
+        assert_eq!(response.header("vary"), Some("Origin"));
+        assert!(
+            response
+                .header("access-control-allow-headers")
+                .unwrap()
+                .contains("Authorization")
+        );
+
+        preflight.headers[1].1 = "https://evil.example".to_string();
+        let response = server.handle(&preflight);
+        assert!(response.header("access-control-allow-origin").is_none());
+
+        let any = ApiServer::new(ServeConfig {
+            cors_origins: vec!["*".to_string()],
+            ..ServeConfig::default()
+        });
+        let response = any.handle(&preflight);
+        assert_eq!(response.header("access-control-allow-origin"), Some("*"));
+        assert!(response.header("vary").is_none());
+    }
+
+    #[test]
+    fn openapi_mirrors_introspect_schemas() {
+        let doc = server(ServeConfig::default()).openapi();
+        assert_eq!(doc["openapi"], "3.1.0");
+        for (name, _) in ENDPOINTS {
+            let path = &doc["paths"][format!("/v1/{name}")];
+            assert!(path["get"].is_object(), "{name} missing GET");
+            assert!(path["post"].is_object(), "{name} missing POST");
+        }
+        let search = &doc["paths"]["/v1/search"];
+        let params = search["get"]["parameters"].as_array().unwrap();
+        let query = params.iter().find(|p| p["name"] == "query").unwrap();
+        assert_eq!(query["required"], true);
+        assert!(params.iter().any(|p| p["name"] == "fields"));
+        assert!(params.iter().any(|p| p["name"] == "cursor"));
+        assert!(!params.iter().any(|p| p["name"] == "json"));
+        let body = &search["post"]["requestBody"]["content"]["application/json"]["schema"];
+        assert_eq!(body["properties"]["limit"]["type"], "integer");
+        let ok = &search["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(ok["properties"]["hits"]["type"], "array");
+        assert_eq!(doc["security"], json!([]));
+    }
+
+    #[test]
+    fn serves_over_tcp() {
+        let listener = bind("127.0.0.1:0", &ServeConfig::default()).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut client = TcpStream::connect(addr).unwrap();
+        let head = format!(
+            "GET /openapi.json HTTP/1.1\r\nHost: localhost:{}\r\n\r\n",
+            addr.port()
+        );
+        client.write_all(head.as_bytes()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        server(ServeConfig::default())
+            .handle_connection(stream)
+            .unwrap();
+        let mut raw = String::new();
+        client.read_to_string(&mut raw).unwrap();
+        assert!(raw.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(raw.contains("Connection: close\r\n"));
+        let (_, json) = raw.split_once("\r\n\r\n").unwrap();
+        let doc: Value = serde_json::from_str(json).unwrap();
+        assert!(doc["paths"]["/v1/health"].is_object());
+    }
+
+    #[test]
+    fn non_loopback_bind_requires_token() {
+        let err = bind("0.0.0.0:0", &ServeConfig::default()).unwrap_err();
+        assert_eq!(err.kind, "usage");
+        assert!(
+            bind(
+                "0.0.0.0:0",
+                &ServeConfig {
+                    token: Some("t".to_string()),
+                    ..ServeConfig::default()
+                }
+            )
+            .is_ok()
+        );
+    }
+}
@@ -31,6 +31,14 @@
 //! from = "/opt/work"
 //! to = "/Volumes/Work"
 //! agents = ["claude-code"]
+//!
+//! # Hosts that run cass themselves can serve normalized conversations
+//! # instead of raw logs (see `sources::native`)
+//! [[sources]]
+//! name = "buildbox"
+//! type = "ssh"
+//! host = "user@buildbox"
+//! transport = "native"
 //! ```
 
 use serde::{Deserialize, Serialize};
@@ -183,6 +191,11 @@ pub struct SourceDefinition {
     #[serde(default)]
     pub sync_schedule: SyncSchedule,
 
+    /// How sessions are pulled: mirror the raw `paths` (default), or ask the
+    /// host's own cass for normalized conversations (`paths` is then unused).
+    #[serde(default)]
+    pub transport: SyncTransport,
+
     /// Path mappings for workspace rewriting.
     /// Maps remote paths to local equivalents.
     /// Example: "/home/user/projects" -> "/Users/me/projects"
@@ -322,6 +335,17 @@ pub enum SyncSchedule {
     Daily,
 }
 
+impl SyncSchedule {
+    /// Time between scheduled syncs, or `None` for manual sources.
+    pub fn interval(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::Manual => None,
+            Self::Hourly => Some(std::time::Duration::from_secs(60 * 60)),
+            Self::Daily => Some(std::time::Duration::from_secs(24 * 60 * 60)),
+        }
+    }
+}
+
 impl std::fmt::Display for SyncSchedule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -332,6 +356,26 @@ impl std::fmt::Display for SyncSchedule {
     }
 }
 
+/// How a remote source is synced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncTransport {
+    /// Copy the configured agent log directories with rsync (or SFTP).
+    #[default]
+    Files,
+    /// Pull normalized conversations from `cass sources serve-stdio` on the host.
+    Native,
+}
+
+impl std::fmt::Display for SyncTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Files => write!(f, "files"),
+            Self::Native => write!(f, "native"),
+        }
+    }
+}
+
 /// Platform hint for choosing default paths.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -502,6 +546,7 @@ pub fn get_preset_paths(preset: &str) -> Result<Vec<String>, ConfigError> {
             "~/.pi/agent/sessions".into(),
             "~/Library/Application Support/opencode/storage".into(),
             "~/.continue/sessions".into(),
+            "~/.codeium/windsurf/cascade".into(),
             "~/.aider.chat.history.md".into(),
             "~/.goose/sessions".into(),
         ]),
@@ -516,6 +561,7 @@ pub fn get_preset_paths(preset: &str) -> Result<Vec<String>, ConfigError> {
             "~/.pi/agent/sessions".into(),
             "~/.local/share/opencode/storage".into(),
             "~/.continue/sessions".into(),
+            "~/.codeium/windsurf/cascade".into(),
             "~/.aider.chat.history.md".into(),
             "~/.goose/sessions".into(),
         ]),
@@ -802,6 +848,7 @@ impl SourceConfigGenerator {
             host: Some(host_name.to_string()), // Use SSH alias
             paths,
             sync_schedule: SyncSchedule::Manual,
+            transport: SyncTransport::Files,
             path_mappings,
             platform,
         }
@@ -1233,6 +1280,7 @@ mod tests {
             host: Some("user@laptop.local".into()),
             paths: vec!["~/.claude/projects".into()],
             sync_schedule: SyncSchedule::Daily,
+            transport: SyncTransport::Native,
             path_mappings: vec![PathMapping::new("/home/user", "/Users/me")],
             platform: Some(Platform::Linux),
         });
@@ -1243,6 +1291,7 @@ mod tests {
         assert_eq!(deserialized.sources.len(), 1);
         assert_eq!(deserialized.sources[0].name, "laptop");
         assert_eq!(deserialized.sources[0].sync_schedule, SyncSchedule::Daily);
+        assert_eq!(deserialized.sources[0].transport, SyncTransport::Native);
         assert_eq!(deserialized.sources[0].path_mappings.len(), 1);
         assert_eq!(deserialized.sources[0].path_mappings[0].from, "/home/user");
         assert_eq!(deserialized.sources[0].path_mappings[0].to, "/Users/me");
@@ -1257,6 +1306,7 @@ mod tests {
             host: Some("user@server".into()),
             paths: vec![],
             sync_schedule: SyncSchedule::Manual,
+            transport: SyncTransport::Files,
             path_mappings: vec![
                 PathMapping::new("/home/user", "/Users/me"),
                 PathMapping::with_agents("/opt/work", "/Volumes/Work", vec!["claude-code".into()]),
@@ -1296,6 +1346,41 @@ mod tests {
         assert_eq!(SyncSchedule::Daily.to_string(), "daily");
     }
 
+    #[test]
+    fn test_sync_transport_defaults_to_files() {
+        let config: SourcesConfig = toml::from_str(
+            r#"
+            [[sources]]
+            name = "laptop"
+            type = "ssh"
+            host = "user@laptop"
+
+            [[sources]]
+            name = "buildbox"
+            type = "ssh"
+            host = "user@buildbox"
+            transport = "native"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.sources[0].transport, SyncTransport::Files);
+        assert_eq!(config.sources[1].transport, SyncTransport::Native);
+        assert_eq!(SyncTransport::Native.to_string(), "native");
+    }
+
+    #[test]
+    fn test_sync_schedule_interval() {
+        assert_eq!(SyncSchedule::Manual.interval(), None);
+        assert_eq!(
+            SyncSchedule::Hourly.interval(),
+            Some(std::time::Duration::from_secs(3600))
+        );
+        assert_eq!(
+            SyncSchedule::Daily.interval(),
+            Some(std::time::Duration::from_secs(86_400))
+        );
+    }
+
     #[test]
     fn test_discover_ssh_hosts() {
         // Just test that the function doesn't panic
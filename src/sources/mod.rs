@@ -9,6 +9,8 @@
 //! - **config**: Configuration types for defining remote sources
 //! - **provenance**: Types for tracking conversation origins
 //! - **sync**: Sync engine for pulling sessions from remotes via rsync/SSH
+//! - **native**: Pulling normalized conversations from a remote cass over SSH
+//! - **scheduler**: Background sync for sources with an hourly/daily schedule
 //! - **status** (future): Sync status tracking
 //!
 //! # Configuration
@@ -72,8 +74,10 @@ pub mod config;
 pub mod index;
 pub mod install;
 pub mod interactive;
+pub mod native;
 pub mod probe;
 pub mod provenance;
+pub mod scheduler;
 pub mod setup;
 pub mod sync;
 
@@ -81,7 +85,7 @@ pub mod sync;
 pub use config::{
     BackupInfo, ConfigError, ConfigPreview, DiscoveredHost, MergeResult, PathMapping, Platform,
     SkipReason, SourceConfigGenerator, SourceDefinition, SourcesConfig, SyncSchedule,
-    discover_ssh_hosts, get_preset_paths,
+    SyncTransport, discover_ssh_hosts, get_preset_paths,
 };
 
 // Re-export commonly used provenance types
@@ -93,6 +97,9 @@ pub use sync::{
     SyncStatus,
 };
 
+// Re-export commonly used scheduler types
+pub use scheduler::{ScheduledSync, SyncScheduler};
+
 // Re-export commonly used probe types
 pub use probe::{
     CassStatus, DetectedAgent, HostProbeResult, ProbeCache, ResourceInfo, SystemInfo, probe_host,
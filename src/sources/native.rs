@@ -0,0 +1,827 @@
+//! Native sync: pull normalized conversations from a remote cass.
+//!
+//! Hosts set up with `cass sources setup` (or `install`/`index`) keep their own
+//! cass database. For sources with `transport = "native"` the sync engine runs
+//! `cass sources serve-stdio` on the host over ssh instead of mirroring the raw
+//! agent log directories, and receives only messages indexed there since the
+//! previous pull. Nothing is re-parsed locally and the local mirror holds
+//! normalized conversations rather than whole log trees.
+//!
+//! # Protocol (version 1)
+//!
+//! The client writes a single JSON request line:
+//!
+//! ```json
+//! {"protocol":1,"since":1234,"epoch":"9f3c..."}
+//! ```
+//!
+//! `since` and `epoch` are the cursor and epoch from the previous pull (0 and
+//! absent for the first). The server answers with JSON lines tagged by `type`:
+//!
+//! ```json
+//! {"type":"hello","protocol":1,"version":"0.1.63"}
+//! {"type":"conversation","conversation":{...}}
+//! {"type":"done","cursor":5678,"epoch":"9f3c...","reset":false,"conversations":1,"messages":3}
+//! ```
+//!
+//! The cursor is the remote's highest message rowid and the epoch identifies
+//! its rowid sequence, which starts over when the database is rebuilt (e.g.
+//! `cass index --full`). Each conversation is a [`NormalizedConversation`]
+//! carrying only the messages inserted after `since`, with their original
+//! `idx`, so ingesting it appends to what earlier pulls delivered. Only the
+//! host's own conversations are served, never ones it synced from other
+//! sources. If the epoch changed (or `since` is ahead of the host) the server
+//! starts again from 0 and sets `reset`. Failures are reported as
+//! `{"type":"error","message":"..."}`.
+//!
+//! # Spool
+//!
+//! Each pull that returns conversations is written to
+//! `remotes/<name>/mirror/cass-native/<since>-<cursor>.ndjson`, one
+//! conversation per line, and ingested by the `native` connector like any other
+//! mirror root. The epoch is kept next to it in `epoch`. The next pull resumes
+//! from the newest file's cursor; a reset clears the spool first. Once more
+//! than [`COMPACT_AFTER`] files pile up they are folded into one file holding
+//! each conversation once, so the spool never holds more than the host's
+//! normalized history.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::sync::SyncError;
+use crate::connectors::{NormalizedConversation, NormalizedMessage, NormalizedSnippet};
+use crate::model::types::{Conversation, Message};
+use crate::storage::sqlite::{SqliteStorage, role_str};
+
+/// Protocol version spoken by `cass sources serve-stdio`.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Directory under a source's mirror that holds pulled conversations.
+pub const SPOOL_DIR: &str = "cass-native";
+
+/// Extension of spool files (distinct from agent logs other connectors read).
+const SPOOL_EXTENSION: &str = "ndjson";
+
+/// File in the spool holding the epoch of the pulled cursor.
+const EPOCH_FILE: &str = "epoch";
+
+/// Spool files kept before they are compacted into one.
+pub const COMPACT_AFTER: usize = 8;
+
+/// Remote command line; cass is often installed outside the non-login `PATH`.
+pub const REMOTE_COMMAND: &str =
+    r#"PATH="$HOME/.local/bin:$HOME/.cargo/bin:$PATH" cass sources serve-stdio"#;
+
+const STREAM_CLOSED: &str = "remote closed the stream before finishing";
+
+/// Request sent by the client.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PullRequest {
+    pub protocol: u32,
+    /// Cursor returned by the previous pull.
+    #[serde(default)]
+    pub since: i64,
+    /// Epoch `since` was read under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<String>,
+}
+
+/// One line of the server's response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServeMessage {
+    Hello {
+        protocol: u32,
+        version: String,
+    },
+    Conversation {
+        conversation: NormalizedConversation,
+    },
+    Done {
+        cursor: i64,
+        #[serde(default)]
+        epoch: String,
+        reset: bool,
+        conversations: u64,
+        messages: u64,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Outcome of serving or pulling one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullSummary {
+    /// Cursor to send with the next request.
+    pub cursor: i64,
+    /// Epoch the cursor belongs to.
+    pub epoch: String,
+    /// Whether the server restarted from 0 because the cursor was stale.
+    pub reset: bool,
+    pub conversations: u64,
+    pub messages: u64,
+    /// Bytes read from the server (client side only).
+    pub bytes: u64,
+    /// Spool file written by this pull, if it returned any conversations.
+    pub spool_file: Option<PathBuf>,
+}
+
+/// Answer one pull request read from `input` using the local database.
+pub fn serve(
+    storage: &SqliteStorage,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<PullSummary> {
+    let request = match read_request(input) {
+        Ok(request) => request,
+        Err(e) => {
+            write_message(
+                &mut output,
+                &ServeMessage::Error {
+                    message: format!("{e:#}"),
+                },
+            )?;
+            return Err(e);
+        }
+    };
+
+    write_message(
+        &mut output,
+        &ServeMessage::Hello {
+            protocol: PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+    )?;
+
+    // Snapshot the cursor first so messages indexed while we stream are left
+    // for the next pull instead of being half-sent.
+    let cursor = storage.max_message_id()?;
+    let epoch = storage.sync_epoch()?.unwrap_or_default();
+    // A cursor from another epoch counts rowids that may since have been
+    // reused, so it says nothing about what the client already has.
+    let reset = request.since > cursor
+        || (request.since > 0 && request.epoch.as_deref() != Some(epoch.as_str()));
+    let since = if reset { 0 } else { request.since };
+
+    let mut summary = PullSummary {
+        cursor,
+        epoch: epoch.clone(),
+        reset,
+        ..Default::default()
+    };
+    for conv in storage.local_conversations_changed(since, cursor)? {
+        let Some(id) = conv.id else { continue };
+        let messages: Vec<Message> = storage
+            .fetch_messages_with_snippets(id)?
+            .into_iter()
+            .filter(|m| m.id.is_some_and(|mid| mid > since && mid <= cursor))
+            .collect();
+        if messages.is_empty() {
+            continue;
+        }
+        summary.conversations += 1;
+        summary.messages += messages.len() as u64;
+        write_message(
+            &mut output,
+            &ServeMessage::Conversation {
+                conversation: to_normalized(conv, messages),
+            },
+        )?;
+    }
+
+    write_message(
+        &mut output,
+        &ServeMessage::Done {
+            cursor,
+            epoch,
+            reset,
+            conversations: summary.conversations,
+            messages: summary.messages,
+        },
+    )?;
+    output.flush()?;
+    Ok(summary)
+}
+
+fn read_request(mut input: impl BufRead) -> Result<PullRequest> {
+    let mut line = String::new();
+    input.read_line(&mut line).context("reading pull request")?;
+    let request: PullRequest = serde_json::from_str(line.trim()).context("invalid pull request")?;
+    if request.protocol != PROTOCOL_VERSION {
+        bail!(
+            "unsupported protocol version {} (this cass speaks {PROTOCOL_VERSION})",
+            request.protocol
+        );
+    }
+    Ok(request)
+}
+
+/// Report a failure that happened before [`serve`] could run, e.g. a missing
+/// database, so the puller shows it instead of a truncated stream.
+pub fn write_error(mut output: impl Write, message: &str) -> Result<()> {
+    write_message(
+        &mut output,
+        &ServeMessage::Error {
+            message: message.to_string(),
+        },
+    )?;
+    output.flush()?;
+    Ok(())
+}
+
+fn write_message(output: &mut impl Write, message: &ServeMessage) -> Result<()> {
+    serde_json::to_writer(&mut *output, message)?;
+    output.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Rebuild the connector-level form of a stored conversation.
+fn to_normalized(conv: Conversation, messages: Vec<Message>) -> NormalizedConversation {
+    // Conversations are matched by external_id when appending, so give
+    // anonymous ones a stable id derived from where they came from.
+    let external_id = conv.external_id.unwrap_or_else(|| {
+        format!(
+            "{}@{}",
+            conv.source_path.display(),
+            conv.started_at.unwrap_or_default()
+        )
+    });
+    NormalizedConversation {
+        agent_slug: conv.agent_slug,
+        external_id: Some(external_id),
+        title: conv.title,
+        workspace: conv.workspace,
+        source_path: conv.source_path,
+        started_at: conv.started_at,
+        ended_at: conv.ended_at,
+        metadata: conv.metadata_json,
+        messages: messages
+            .into_iter()
+            .map(|m| NormalizedMessage {
+                idx: m.idx,
+                role: role_str(&m.role),
+                author: m.author,
+                created_at: m.created_at,
+                content: m.content,
+                extra: m.extra_json,
+                snippets: m
+                    .snippets
+                    .into_iter()
+                    .map(|s| NormalizedSnippet {
+                        file_path: s.file_path,
+                        start_line: s.start_line,
+                        end_line: s.end_line,
+                        language: s.language,
+                        snippet_text: s.snippet_text,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+/// Pull new conversations from `host` into `spool_dir`.
+pub fn pull(
+    host: &str,
+    spool_dir: &Path,
+    connection_timeout: u64,
+) -> Result<PullSummary, SyncError> {
+    fs::create_dir_all(spool_dir)?;
+    let since = spool_cursor(spool_dir)?;
+    let epoch = spool_epoch(spool_dir)?;
+
+    let mut child = Command::new("ssh")
+        .arg("-o")
+        .arg("BatchMode=yes")
+        .arg("-o")
+        .arg(format!("ConnectTimeout={connection_timeout}"))
+        .arg("-o")
+        .arg("StrictHostKeyChecking=accept-new")
+        .arg("-o")
+        .arg("LogLevel=ERROR")
+        .arg("--")
+        .arg(host)
+        .arg(REMOTE_COMMAND)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SyncError::SshFailed(format!("Failed to execute ssh: {e}")))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let request = PullRequest {
+            protocol: PROTOCOL_VERSION,
+            since,
+            epoch,
+        };
+        // A write error means the remote exited early; its stderr says why.
+        let _ = serde_json::to_writer(&mut stdin, &request);
+        let _ = stdin.write_all(b"\n");
+    }
+
+    // Drain stderr concurrently so a chatty remote can't block the stream.
+    let stderr = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_string(&mut text);
+        }
+        text
+    });
+
+    let received = match child.stdout.take() {
+        Some(stdout) => receive(BufReader::new(stdout), spool_dir, since),
+        None => Err(SyncError::Protocol("ssh stdout unavailable".into())),
+    };
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    match received {
+        // Without a reply, ssh's stderr says what went wrong.
+        Err(SyncError::Protocol(message)) if message == STREAM_CLOSED && !status.success() => {
+            let stderr = stderr.trim();
+            Err(if stderr.contains("command not found") {
+                SyncError::Protocol(format!(
+                    "cass is not installed on {host}; run 'cass sources setup' or use transport = \"files\""
+                ))
+            } else if stderr.contains("unrecognized subcommand") {
+                SyncError::Protocol(format!(
+                    "cass on {host} is too old for native sync; upgrade it there"
+                ))
+            } else {
+                SyncError::SshFailed(stderr.to_string())
+            })
+        }
+        other => other,
+    }
+}
+
+/// Read a server response, spooling conversations into `spool_dir`.
+pub fn receive(
+    input: impl BufRead,
+    spool_dir: &Path,
+    since: i64,
+) -> Result<PullSummary, SyncError> {
+    let mut batch = tempfile::NamedTempFile::new_in(spool_dir)?;
+    let mut summary = PullSummary::default();
+    let mut done = false;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        summary.bytes += line.len() as u64 + 1;
+        let message: ServeMessage = serde_json::from_str(&line)
+            .map_err(|e| SyncError::Protocol(format!("invalid response line: {e}")))?;
+        match message {
+            ServeMessage::Hello { protocol, .. } if protocol != PROTOCOL_VERSION => {
+                return Err(SyncError::Protocol(format!(
+                    "remote speaks protocol {protocol}, expected {PROTOCOL_VERSION}"
+                )));
+            }
+            ServeMessage::Hello { .. } => {}
+            ServeMessage::Conversation { conversation } => {
+                summary.conversations += 1;
+                summary.messages += conversation.messages.len() as u64;
+                serde_json::to_writer(&mut batch, &conversation)
+                    .map_err(|e| SyncError::Protocol(e.to_string()))?;
+                batch.write_all(b"\n")?;
+            }
+            ServeMessage::Done {
+                cursor,
+                epoch,
+                reset,
+                ..
+            } => {
+                summary.cursor = cursor;
+                summary.epoch = epoch;
+                summary.reset = reset;
+                done = true;
+                break;
+            }
+            ServeMessage::Error { message } => return Err(SyncError::Protocol(message)),
+        }
+    }
+    if !done {
+        return Err(SyncError::Protocol(STREAM_CLOSED.into()));
+    }
+
+    if summary.reset {
+        for file in spool_files(spool_dir)? {
+            fs::remove_file(file)?;
+        }
+    }
+    if summary.conversations > 0 {
+        let from = if summary.reset { 0 } else { since };
+        let path = spool_dir.join(format!(
+            "{from:020}-{:020}.{SPOOL_EXTENSION}",
+            summary.cursor
+        ));
+        batch.flush()?;
+        batch.persist(&path).map_err(|e| e.error)?;
+        summary.spool_file = Some(path);
+    }
+    // Written last: if anything above failed, the old epoch makes the next
+    // pull start over rather than skip messages.
+    if spool_epoch(spool_dir)?.as_deref() != Some(summary.epoch.as_str()) {
+        fs::write(spool_dir.join(EPOCH_FILE), &summary.epoch)?;
+    }
+    if let Some(compacted) = compact_spool(spool_dir)? {
+        summary.spool_file = Some(compacted);
+    }
+    Ok(summary)
+}
+
+/// Epoch of the spooled cursor, if a pull has completed.
+pub fn spool_epoch(spool_dir: &Path) -> std::io::Result<Option<String>> {
+    match fs::read_to_string(spool_dir.join(EPOCH_FILE)) {
+        Ok(epoch) => Ok(Some(epoch.trim().to_string())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Fold the spool into a single file once it holds more than
+/// [`COMPACT_AFTER`] pulls, returning the compacted file.
+///
+/// Each conversation appears once, with the messages of every pull. The file
+/// keeps the newest input's mtime so incremental scans don't re-read
+/// conversations that were already ingested.
+pub fn compact_spool(spool_dir: &Path) -> Result<Option<PathBuf>, SyncError> {
+    let files = spool_files(spool_dir)?;
+    if files.len() <= COMPACT_AFTER {
+        return Ok(None);
+    }
+    let (Some((from, _)), Some((_, to))) = (
+        files.first().and_then(|path| parse_spool_name(path)),
+        files.last().and_then(|path| parse_spool_name(path)),
+    ) else {
+        return Ok(None);
+    };
+
+    let mut merged: Vec<NormalizedConversation> = Vec::new();
+    let mut positions: HashMap<(String, Option<String>), usize> = HashMap::new();
+    let mut newest = None;
+    for path in &files {
+        let modified = fs::metadata(path)?.modified()?;
+        newest = newest.max(Some(modified));
+        let convs = read_spool_file(path).map_err(|e| SyncError::Protocol(format!("{e:#}")))?;
+        for conv in convs {
+            let key = (conv.agent_slug.clone(), conv.external_id.clone());
+            match positions.get(&key) {
+                Some(&i) => merge_delta(&mut merged[i], conv),
+                None => {
+                    positions.insert(key, merged.len());
+                    merged.push(conv);
+                }
+            }
+        }
+    }
+
+    let mut batch = tempfile::NamedTempFile::new_in(spool_dir)?;
+    for conv in &merged {
+        serde_json::to_writer(&mut batch, conv).map_err(|e| SyncError::Protocol(e.to_string()))?;
+        batch.write_all(b"\n")?;
+    }
+    batch.flush()?;
+    if let Some(newest) = newest {
+        batch.as_file().set_modified(newest)?;
+    }
+    let path = spool_dir.join(format!("{from:020}-{to:020}.{SPOOL_EXTENSION}"));
+    batch.persist(&path).map_err(|e| e.error)?;
+    for file in files {
+        if file != path {
+            fs::remove_file(file)?;
+        }
+    }
+    Ok(Some(path))
+}
+
+/// Apply a later pull's delta of the same conversation.
+fn merge_delta(into: &mut NormalizedConversation, delta: NormalizedConversation) {
+    let last = into.messages.iter().map(|m| m.idx).max();
+    into.messages.extend(
+        delta
+            .messages
+            .into_iter()
+            .filter(|m| last.is_none_or(|last| m.idx > last)),
+    );
+    into.title = delta.title.or(into.title.take());
+    into.workspace = delta.workspace.or(into.workspace.take());
+    into.ended_at = delta.ended_at.or(into.ended_at);
+    into.metadata = delta.metadata;
+}
+
+/// Cursor to resume from: the end of the newest spool file, or 0.
+pub fn spool_cursor(spool_dir: &Path) -> std::io::Result<i64> {
+    Ok(spool_files(spool_dir)?
+        .iter()
+        .filter_map(|path| parse_spool_name(path).map(|(_, to)| to))
+        .max()
+        .unwrap_or(0))
+}
+
+/// Spool files in pull order.
+pub fn spool_files(spool_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !spool_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = fs::read_dir(spool_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| parse_spool_name(path).is_some())
+        .collect();
+    // Zero-padded names sort by cursor.
+    files.sort();
+    Ok(files)
+}
+
+fn parse_spool_name(path: &Path) -> Option<(i64, i64)> {
+    if path.extension()? != SPOOL_EXTENSION {
+        return None;
+    }
+    let (from, to) = path.file_stem()?.to_str()?.split_once('-')?;
+    Some((from.parse().ok()?, to.parse().ok()?))
+}
+
+/// Read the conversations spooled in `spool_dir`, oldest pull first,
+/// skipping files not modified since `since_ts`.
+pub fn read_spool(spool_dir: &Path, since_ts: Option<i64>) -> Result<Vec<NormalizedConversation>> {
+    let mut convs = Vec::new();
+    for path in spool_files(spool_dir)? {
+        if !crate::connectors::file_modified_since(&path, since_ts) {
+            continue;
+        }
+        convs.extend(read_spool_file(&path)?);
+    }
+    Ok(convs)
+}
+
+fn read_spool_file(path: &Path) -> Result<Vec<NormalizedConversation>> {
+    let file = fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut convs = Vec::new();
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<NormalizedConversation>(&line) {
+            Ok(conv) => convs.push(conv),
+            Err(e) => tracing::warn!(
+                file = %path.display(),
+                line = line_no + 1,
+                "skipping malformed spooled conversation: {e}"
+            ),
+        }
+    }
+    Ok(convs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indexer::persist::persist_conversation;
+    use crate::search::tantivy::TantivyIndex;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    fn conversation(external_id: &str, contents: &[&str]) -> NormalizedConversation {
+        NormalizedConversation {
+            agent_slug: "codex".into(),
+            external_id: Some(external_id.into()),
+            title: Some(format!("session {external_id}")),
+            workspace: Some(PathBuf::from("/home/me/project")),
+            source_path: PathBuf::from(format!("/home/me/.codex/{external_id}.jsonl")),
+            started_at: Some(1_700_000_000_000),
+            ended_at: None,
+            metadata: serde_json::json!({}),
+            messages: contents
+                .iter()
+                .enumerate()
+                .map(|(i, content)| NormalizedMessage {
+                    idx: i as i64,
+                    role: if i % 2 == 0 { "user" } else { "assistant" }.into(),
+                    author: None,
+                    created_at: Some(1_700_000_000_000 + i as i64),
+                    content: (*content).into(),
+                    extra: serde_json::json!({}),
+                    snippets: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// A "remote" data dir with its own storage and index.
+    struct Remote {
+        _dir: TempDir,
+        storage: SqliteStorage,
+        index: TantivyIndex,
+    }
+
+    impl Remote {
+        fn new() -> Self {
+            let dir = TempDir::new().unwrap();
+            let storage = SqliteStorage::open(&dir.path().join("agent_search.db")).unwrap();
+            let index = TantivyIndex::open_or_create(&dir.path().join("index")).unwrap();
+            Self {
+                _dir: dir,
+                storage,
+                index,
+            }
+        }
+
+        fn add(&mut self, conv: &NormalizedConversation) {
+            persist_conversation(&mut self.storage, &mut self.index, conv).unwrap();
+        }
+
+        /// Run one pull into `spool` the way [`pull`] does, minus ssh.
+        fn pull(&self, spool: &Path) -> PullSummary {
+            let since = spool_cursor(spool).unwrap();
+            let request = PullRequest {
+                protocol: PROTOCOL_VERSION,
+                since,
+                epoch: spool_epoch(spool).unwrap(),
+            };
+            let mut out = Vec::new();
+            let input = format!("{}\n", serde_json::to_string(&request).unwrap());
+            serve(&self.storage, Cursor::new(input), &mut out).unwrap();
+            receive(Cursor::new(out), spool, since).unwrap()
+        }
+    }
+
+    #[test]
+    fn pulls_only_messages_added_since_the_cursor() {
+        let mut remote = Remote::new();
+        remote.add(&conversation("a", &["hello", "hi there"]));
+        let spool = TempDir::new().unwrap();
+
+        let first = remote.pull(spool.path());
+        assert_eq!(first.conversations, 1);
+        assert_eq!(first.messages, 2);
+        assert!(!first.reset);
+        assert_eq!(spool_cursor(spool.path()).unwrap(), first.cursor);
+        assert_eq!(
+            spool_epoch(spool.path()).unwrap(),
+            remote.storage.sync_epoch().unwrap()
+        );
+
+        // Nothing new: no spool file, cursor unchanged.
+        let idle = remote.pull(spool.path());
+        assert_eq!(idle.conversations, 0);
+        assert!(!idle.reset);
+        assert!(idle.spool_file.is_none());
+        assert_eq!(spool_files(spool.path()).unwrap().len(), 1);
+
+        remote.add(&conversation("a", &["hello", "hi there", "run the tests"]));
+        remote.add(&conversation("b", &["new session"]));
+        let second = remote.pull(spool.path());
+        assert_eq!(second.conversations, 2);
+        assert_eq!(second.messages, 2);
+
+        let convs = read_spool(spool.path(), None).unwrap();
+        assert_eq!(convs.len(), 3);
+        let delta = &convs[1];
+        assert_eq!(delta.external_id.as_deref(), Some("a"));
+        assert_eq!(delta.messages.len(), 1);
+        assert_eq!(delta.messages[0].idx, 2);
+        assert_eq!(delta.messages[0].content, "run the tests");
+        assert_eq!(convs[0].messages[1].role, "agent");
+    }
+
+    #[test]
+    fn serves_only_the_hosts_own_conversations() {
+        let mut remote = Remote::new();
+        remote.add(&conversation("mine", &["local work"]));
+        let mut pulled = conversation("theirs", &["synced from elsewhere"]);
+        pulled.metadata = serde_json::json!({
+            "cass": {"origin": {"source_id": "laptop", "kind": "ssh", "host": "me@laptop"}}
+        });
+        remote.add(&pulled);
+
+        let spool = TempDir::new().unwrap();
+        remote.pull(spool.path());
+        let convs = read_spool(spool.path(), None).unwrap();
+        assert_eq!(convs.len(), 1);
+        assert_eq!(convs[0].external_id.as_deref(), Some("mine"));
+    }
+
+    #[test]
+    fn cursor_ahead_of_remote_resets_the_spool() {
+        let mut remote = Remote::new();
+        remote.add(&conversation("a", &["one", "two"]));
+        let spool = TempDir::new().unwrap();
+        let stale = spool.path().join(format!("{:020}-{:020}.ndjson", 0, 9_999));
+        fs::write(&stale, "").unwrap();
+        let epoch = remote.storage.sync_epoch().unwrap().unwrap();
+        fs::write(spool.path().join(EPOCH_FILE), &epoch).unwrap();
+
+        let summary = remote.pull(spool.path());
+        assert!(summary.reset);
+        assert_eq!(summary.messages, 2);
+        assert!(!stale.exists());
+        assert_eq!(spool_cursor(spool.path()).unwrap(), summary.cursor);
+    }
+
+    #[test]
+    fn rebuilt_remote_is_pulled_again_from_scratch() {
+        let mut remote = Remote::new();
+        remote.add(&conversation("a", &["one", "two"]));
+        let spool = TempDir::new().unwrap();
+        let first = remote.pull(spool.path());
+
+        // A full rebuild wipes the messages and reuses their rowids, so the
+        // old cursor is soon behind the host again.
+        remote
+            .storage
+            .raw()
+            .execute_batch("DELETE FROM messages; DELETE FROM conversations;")
+            .unwrap();
+        remote.storage.reset_sync_epoch().unwrap();
+        remote.add(&conversation("b", &["first", "second", "third"]));
+        assert!(remote.storage.max_message_id().unwrap() >= first.cursor);
+
+        let summary = remote.pull(spool.path());
+        assert!(summary.reset);
+        assert_eq!(summary.conversations, 1);
+        assert_eq!(summary.messages, 3);
+        assert_ne!(summary.epoch, first.epoch);
+        let convs = read_spool(spool.path(), None).unwrap();
+        assert_eq!(convs.len(), 1);
+        assert_eq!(convs[0].external_id.as_deref(), Some("b"));
+        assert_eq!(spool_epoch(spool.path()).unwrap(), Some(summary.epoch));
+
+        // A cursor without an epoch can't be trusted either.
+        fs::remove_file(spool.path().join(EPOCH_FILE)).unwrap();
+        assert!(remote.pull(spool.path()).reset);
+    }
+
+    #[test]
+    fn spool_is_compacted_into_one_file_per_conversation() {
+        let mut remote = Remote::new();
+        let spool = TempDir::new().unwrap();
+        let mut contents: Vec<String> = Vec::new();
+        for i in 0..COMPACT_AFTER {
+            contents.push(format!("message {i}"));
+            let refs: Vec<&str> = contents.iter().map(String::as_str).collect();
+            remote.add(&conversation("a", &refs));
+            remote.pull(spool.path());
+        }
+        assert_eq!(spool_files(spool.path()).unwrap().len(), COMPACT_AFTER);
+        let before = fs::metadata(spool_files(spool.path()).unwrap().last().unwrap())
+            .unwrap()
+            .modified()
+            .unwrap();
+
+        contents.push("one more".to_string());
+        let refs: Vec<&str> = contents.iter().map(String::as_str).collect();
+        remote.add(&conversation("a", &refs));
+        remote.add(&conversation("b", &["other"]));
+        let summary = remote.pull(spool.path());
+
+        let files = spool_files(spool.path()).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(summary.spool_file.as_ref(), Some(&files[0]));
+        assert_eq!(parse_spool_name(&files[0]), Some((0, summary.cursor)));
+        assert_eq!(spool_cursor(spool.path()).unwrap(), summary.cursor);
+        assert!(fs::metadata(&files[0]).unwrap().modified().unwrap() >= before);
+
+        let convs = read_spool(spool.path(), None).unwrap();
+        assert_eq!(convs.len(), 2);
+        let idxs: Vec<i64> = convs[0].messages.iter().map(|m| m.idx).collect();
+        assert_eq!(idxs, (0..=COMPACT_AFTER as i64).collect::<Vec<_>>());
+        assert_eq!(convs[0].messages.last().unwrap().content, "one more");
+        assert_eq!(convs[1].external_id.as_deref(), Some("b"));
+
+        // The next pull carries on from the compacted cursor.
+        assert_eq!(remote.pull(spool.path()).conversations, 0);
+    }
+
+    #[test]
+    fn rejects_unknown_protocol_and_truncated_streams() {
+        let remote = Remote::new();
+        let mut out = Vec::new();
+        assert!(
+            serve(
+                &remote.storage,
+                Cursor::new("{\"protocol\":99}\n"),
+                &mut out
+            )
+            .is_err()
+        );
+        let spool = TempDir::new().unwrap();
+        let err = receive(Cursor::new(out), spool.path(), 0).unwrap_err();
+        assert!(err.to_string().contains("unsupported protocol version 99"));
+
+        let truncated = "{\"type\":\"hello\",\"protocol\":1,\"version\":\"x\"}\n";
+        let err = receive(Cursor::new(truncated), spool.path(), 0).unwrap_err();
+        assert!(err.to_string().contains("before finishing"));
+        assert!(spool_files(spool.path()).unwrap().is_empty());
+    }
+}
@@ -304,6 +304,9 @@ for dir in ~/.claude/projects ~/.codex/sessions ~/.cursor \
            ~/.config/Cursor/User/globalStorage/saoudrizwan.claude-dev \
            ~/Library/Application\ Support/Code/User/globalStorage/saoudrizwan.claude-dev \
            ~/Library/Application\ Support/Cursor/User/globalStorage/saoudrizwan.claude-dev \
+           ~/.config/Code/User/globalStorage/rooveterinaryinc.roo-cline \
+           ~/Library/Application\ Support/Code/User/globalStorage/rooveterinaryinc.roo-cline \
+           ~/.codeium/windsurf/cascade \
            ~/.gemini/tmp ~/.pi/agent/sessions ~/.aider.chat.history.md \
            ~/.local/share/opencode ~/.goose/sessions ~/.continue/sessions; do
     # Expand the path
@@ -552,8 +555,12 @@ fn parse_probe_output(host_name: &str, output: &str, connection_time_ms: u64) ->
 /// contains `claude` so Cline must be checked before Claude Code).
 fn infer_agent_type(path: &str) -> String {
     // Check Cline first - it contains "claude-dev" which could match ".claude"
-    if path.contains("saoudrizwan.claude-dev") || path.contains("rooveterinaryinc.roo-cline") {
+    if path.contains("saoudrizwan.claude-dev") {
         "cline".to_string()
+    } else if path.contains("rooveterinaryinc.roo-cline") {
+        "roo_code".to_string()
+    } else if path.contains(".codeium/windsurf") {
+        "windsurf".to_string()
     } else if path.contains(".claude") {
         "claude_code".to_string()
     } else if path.contains(".codex") {
@@ -819,6 +826,11 @@ mod tests {
             infer_agent_type("~/.config/Code/User/globalStorage/saoudrizwan.claude-dev"),
             "cline"
         );
+        assert_eq!(
+            infer_agent_type("~/.config/Code/User/globalStorage/rooveterinaryinc.roo-cline"),
+            "roo_code"
+        );
+        assert_eq!(infer_agent_type("~/.codeium/windsurf/cascade"), "windsurf");
         assert_eq!(infer_agent_type("/some/random/path"), "unknown");
     }
 
@@ -0,0 +1,290 @@
+//! Background sync for sources with an `hourly` or `daily` schedule.
+//!
+//! [`SyncScheduler`] runs [`SyncEngine::sync_source`] for every remote source
+//! whose [`SyncSchedule`](super::config::SyncSchedule) has come due, records
+//! the outcome in [`SyncStatus`], and reports which mirror roots received
+//! files so the caller can reindex just those.
+//!
+//! A source whose sync fails completely is retried with exponential backoff
+//! (5 minutes, then 10, 20, ...), never waiting longer than its own schedule
+//! interval. Partial failures reset the backoff: the reachable paths synced.
+//!
+//! The scheduler runs inside `cass index --watch` and as `cass sources daemon`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::config::{SourceDefinition, SourcesConfig};
+use super::sync::{SourceSyncInfo, SyncEngine, SyncReport, SyncStatus};
+
+/// How often the scheduler checks for due sources.
+pub const DEFAULT_TICK: Duration = Duration::from_secs(60);
+
+/// First retry delay after a failed sync; doubles with each further failure.
+const BACKOFF_BASE: Duration = Duration::from_secs(5 * 60);
+
+/// When `source` is next due, in milliseconds since the epoch.
+///
+/// `None` for manual sources. A source that has never synced is due at once.
+pub fn next_sync_at(source: &SourceDefinition, info: Option<&SourceSyncInfo>) -> Option<i64> {
+    let interval = source.sync_schedule.interval()?;
+    let Some(last_sync) = info.and_then(|info| info.last_sync) else {
+        return Some(0);
+    };
+    let delay = match info.map_or(0, |info| info.consecutive_failures) {
+        0 => interval,
+        failures => backoff(failures).min(interval),
+    };
+    Some(last_sync.saturating_add(i64::try_from(delay.as_millis()).unwrap_or(i64::MAX)))
+}
+
+/// Retry delay after `failures` (at least 1) failed syncs in a row.
+fn backoff(failures: u32) -> Duration {
+    let factor = 1u32
+        .checked_shl(failures.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    BACKOFF_BASE.saturating_mul(factor)
+}
+
+fn now_millis() -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    i64::try_from(now).unwrap_or(i64::MAX)
+}
+
+/// Outcome of one scheduled sync.
+#[derive(Debug, Clone)]
+pub struct ScheduledSync {
+    pub report: SyncReport,
+    /// Mirror roots that received files and need reindexing.
+    pub changed_roots: Vec<PathBuf>,
+    /// When the source is next due (ms since epoch), including any backoff.
+    pub next_sync: Option<i64>,
+}
+
+/// Syncs remote sources when their schedule comes due.
+pub struct SyncScheduler {
+    engine: SyncEngine,
+    data_dir: PathBuf,
+}
+
+impl SyncScheduler {
+    /// Create a scheduler that mirrors into, and keeps status in, `data_dir`.
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            engine: SyncEngine::new(data_dir),
+            data_dir: data_dir.to_path_buf(),
+        }
+    }
+
+    /// Remote sources among `sources` that are due at `now_ms`.
+    pub fn due<'a>(
+        sources: &'a [SourceDefinition],
+        status: &SyncStatus,
+        now_ms: i64,
+    ) -> Vec<&'a SourceDefinition> {
+        sources
+            .iter()
+            .filter(|source| source.is_remote())
+            .filter(|source| {
+                next_sync_at(source, status.get(&source.name)).is_some_and(|at| at <= now_ms)
+            })
+            .collect()
+    }
+
+    /// Sync every source that is due now, saving [`SyncStatus`] after each.
+    ///
+    /// Failures are recorded rather than returned, so one unreachable host
+    /// neither stops the others nor gets retried before its backoff expires.
+    pub fn run_due(&self, sources: &[SourceDefinition]) -> Vec<ScheduledSync> {
+        let mut status = SyncStatus::load(&self.data_dir).unwrap_or_else(|e| {
+            tracing::warn!("failed to load sync status, treating all sources as unsynced: {e}");
+            SyncStatus::default()
+        });
+
+        let mut synced = Vec::new();
+        for source in Self::due(sources, &status, now_millis()) {
+            let report = self
+                .engine
+                .sync_source(source)
+                .unwrap_or_else(|e| SyncReport::failed(&source.name, e));
+            status.update(&source.name, &report);
+            if let Err(e) = status.save(&self.data_dir) {
+                tracing::warn!(source = %source.name, "failed to save sync status: {e}");
+            }
+
+            let info = status.get(&source.name);
+            let next_sync = next_sync_at(source, info);
+            if report.successful_paths() > 0 {
+                tracing::info!(
+                    source = %source.name,
+                    files = report.total_files(),
+                    bytes = report.total_bytes(),
+                    "scheduled sync finished"
+                );
+            } else {
+                tracing::warn!(
+                    source = %source.name,
+                    failures = info.map_or(0, |info| info.consecutive_failures),
+                    ?next_sync,
+                    "scheduled sync failed"
+                );
+            }
+
+            let changed_roots = report
+                .path_results
+                .iter()
+                .filter(|result| result.success && result.files_transferred > 0)
+                .map(|result| result.local_path.clone())
+                .collect();
+            synced.push(ScheduledSync {
+                report,
+                changed_roots,
+                next_sync,
+            });
+        }
+        synced
+    }
+
+    /// Check `sources.toml` every `tick` and sync whatever is due, handing
+    /// each non-empty batch of results to `on_synced`.
+    ///
+    /// The config is re-read on every tick so edits apply without a restart.
+    /// Returns when `on_synced` returns `false`.
+    pub fn run(&self, tick: Duration, mut on_synced: impl FnMut(&[ScheduledSync]) -> bool) {
+        loop {
+            match SourcesConfig::load() {
+                Ok(config) => {
+                    let synced = self.run_due(&config.sources);
+                    if !synced.is_empty() && !on_synced(&synced) {
+                        return;
+                    }
+                }
+                Err(e) => tracing::warn!("scheduled sync skipped: {e}"),
+            }
+            std::thread::sleep(tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::config::SyncSchedule;
+    use crate::sources::sync::SyncResult;
+
+    const HOUR_MS: i64 = 60 * 60 * 1000;
+    const MINUTE_MS: i64 = 60 * 1000;
+
+    fn source(name: &str, schedule: SyncSchedule) -> SourceDefinition {
+        SourceDefinition {
+            sync_schedule: schedule,
+            paths: vec!["~/.claude/projects".into()],
+            ..SourceDefinition::ssh(name, format!("user@{name}"))
+        }
+    }
+
+    fn synced_at(last_sync: i64, consecutive_failures: u32) -> SourceSyncInfo {
+        SourceSyncInfo {
+            last_sync: Some(last_sync),
+            last_result: if consecutive_failures == 0 {
+                SyncResult::Success
+            } else {
+                SyncResult::Failed("unreachable".into())
+            },
+            consecutive_failures,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn manual_sources_are_never_due() {
+        let manual = source("laptop", SyncSchedule::Manual);
+        assert_eq!(next_sync_at(&manual, None), None);
+        assert_eq!(next_sync_at(&manual, Some(&synced_at(0, 3))), None);
+    }
+
+    #[test]
+    fn scheduled_sources_follow_their_interval() {
+        let hourly = source("laptop", SyncSchedule::Hourly);
+        let daily = source("server", SyncSchedule::Daily);
+        assert_eq!(next_sync_at(&hourly, None), Some(0));
+        assert_eq!(
+            next_sync_at(&hourly, Some(&synced_at(1_000, 0))),
+            Some(1_000 + HOUR_MS)
+        );
+        assert_eq!(
+            next_sync_at(&daily, Some(&synced_at(1_000, 0))),
+            Some(1_000 + 24 * HOUR_MS)
+        );
+    }
+
+    #[test]
+    fn failures_back_off_up_to_the_interval() {
+        let hourly = source("laptop", SyncSchedule::Hourly);
+        let at = |failures| next_sync_at(&hourly, Some(&synced_at(0, failures)));
+        assert_eq!(at(1), Some(5 * MINUTE_MS));
+        assert_eq!(at(2), Some(10 * MINUTE_MS));
+        assert_eq!(at(4), Some(40 * MINUTE_MS));
+        assert_eq!(at(5), Some(HOUR_MS));
+        assert_eq!(at(40), Some(HOUR_MS));
+    }
+
+    #[test]
+    fn due_skips_local_manual_and_recent_sources() {
+        let sources = vec![
+            source("fresh", SyncSchedule::Hourly),
+            source("recent", SyncSchedule::Hourly),
+            source("stale", SyncSchedule::Daily),
+            source("manual", SyncSchedule::Manual),
+            SourceDefinition {
+                sync_schedule: SyncSchedule::Hourly,
+                ..SourceDefinition::local("local")
+            },
+        ];
+        let now = 100 * HOUR_MS;
+        let mut status = SyncStatus::default();
+        status
+            .sources
+            .insert("recent".into(), synced_at(now - 10 * MINUTE_MS, 0));
+        status
+            .sources
+            .insert("stale".into(), synced_at(now - 25 * HOUR_MS, 0));
+
+        let due: Vec<&str> = SyncScheduler::due(&sources, &status, now)
+            .into_iter()
+            .map(|source| source.name.as_str())
+            .collect();
+        assert_eq!(due, ["fresh", "stale"]);
+    }
+
+    #[test]
+    fn run_due_records_failures() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        // No paths: the sync fails before touching the network.
+        let broken = SourceDefinition {
+            paths: vec![],
+            ..source("broken", SyncSchedule::Hourly)
+        };
+        let scheduler = SyncScheduler::new(tmp.path());
+
+        let synced = scheduler.run_due(std::slice::from_ref(&broken));
+        assert_eq!(synced.len(), 1);
+        assert!(synced[0].changed_roots.is_empty());
+        assert!(!synced[0].report.all_succeeded);
+
+        let status = SyncStatus::load(tmp.path()).unwrap();
+        let info = status.get("broken").unwrap();
+        assert_eq!(info.consecutive_failures, 1);
+        assert!(matches!(info.last_result, SyncResult::Failed(_)));
+        assert_eq!(
+            synced[0].next_sync,
+            info.last_sync.map(|at| at + 5 * MINUTE_MS)
+        );
+
+        // Still backing off, so a second pass does nothing.
+        assert!(scheduler.run_due(std::slice::from_ref(&broken)).is_empty());
+    }
+}
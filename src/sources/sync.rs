@@ -2,6 +2,8 @@
 //!
 //! This module provides the core sync functionality using rsync over SSH
 //! for efficient delta transfers, with progress reporting and error recovery.
+//! Sources with `transport = "native"` pull normalized conversations from the
+//! host's own cass instead (see [`super::native`]).
 //!
 //! # Safety
 //!
@@ -30,7 +32,8 @@ use std::time::Instant;
 
 use thiserror::Error;
 
-use super::config::{SourceDefinition, discover_ssh_hosts};
+use super::config::{SourceDefinition, SyncTransport, discover_ssh_hosts};
+use super::native;
 use ssh2::{Session, Sftp};
 use std::io::{Read as IoRead, Write as IoWrite};
 use std::net::TcpStream;
@@ -58,6 +61,9 @@ pub enum SyncError {
 
     #[error("Sync cancelled")]
     Cancelled,
+
+    #[error("Native sync failed: {0}")]
+    Protocol(String),
 }
 
 /// Method used for syncing files from remote.
@@ -67,6 +73,8 @@ pub enum SyncMethod {
     Rsync,
     /// SFTP fallback when rsync is unavailable
     Sftp,
+    /// Normalized conversations from `cass sources serve-stdio` on the host
+    Native,
 }
 
 impl std::fmt::Display for SyncMethod {
@@ -74,6 +82,7 @@ impl std::fmt::Display for SyncMethod {
         match self {
             Self::Rsync => write!(f, "rsync"),
             Self::Sftp => write!(f, "sftp"),
+            Self::Native => write!(f, "native"),
         }
     }
 }
@@ -85,7 +94,7 @@ pub struct PathSyncResult {
     pub remote_path: String,
     /// Local destination path.
     pub local_path: PathBuf,
-    /// Number of files transferred.
+    /// Number of files transferred (conversations for native syncs).
     pub files_transferred: u64,
     /// Total bytes transferred.
     pub bytes_transferred: u64,
@@ -302,6 +311,14 @@ impl SyncEngine {
 
         let host = source.host.as_ref().ok_or(SyncError::NoHost)?;
 
+        if source.transport == SyncTransport::Native {
+            let mut report = SyncReport::new(&source.name, SyncMethod::Native);
+            let result = self.sync_native(host, &self.mirror_dir(&source.name));
+            report.total_duration_ms = result.duration_ms;
+            report.add_path_result(result);
+            return Ok(report);
+        }
+
         if source.paths.is_empty() {
             return Err(SyncError::NoPaths);
         }
@@ -335,6 +352,8 @@ impl SyncEngine {
                 SyncMethod::Sftp => {
                     self.sync_path_sftp(host, remote_path, &mirror_dir, remote_home.as_deref())
                 }
+                // Chosen by `transport = "native"` only, which returned above.
+                SyncMethod::Native => unreachable!("native sources don't sync paths"),
             };
             report.add_path_result(result);
         }
@@ -359,6 +378,44 @@ impl SyncEngine {
             .collect()
     }
 
+    /// Pull new conversations from the host's cass into the mirror's spool.
+    fn sync_native(&self, host: &str, mirror_dir: &Path) -> PathSyncResult {
+        let start = Instant::now();
+        let local_path = mirror_dir.join(native::SPOOL_DIR);
+        match native::pull(host, &local_path, self.connection_timeout) {
+            Ok(summary) => {
+                tracing::info!(
+                    host = %host,
+                    conversations = summary.conversations,
+                    messages = summary.messages,
+                    cursor = summary.cursor,
+                    reset = summary.reset,
+                    "native sync completed"
+                );
+                PathSyncResult {
+                    remote_path: "cass sources serve-stdio".to_string(),
+                    local_path,
+                    files_transferred: summary.conversations,
+                    bytes_transferred: summary.bytes,
+                    success: true,
+                    error: None,
+                    duration_ms: start.elapsed().as_millis() as u64,
+                }
+            }
+            Err(e) => {
+                tracing::warn!(host = %host, error = %e, "native sync failed");
+                PathSyncResult {
+                    remote_path: "cass sources serve-stdio".to_string(),
+                    local_path,
+                    success: false,
+                    error: Some(e.to_string()),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
     /// Sync a single path using rsync.
     ///
     /// **IMPORTANT**: Uses rsync WITHOUT --delete for safe additive syncs.
@@ -1066,6 +1123,9 @@ pub struct SourceSyncInfo {
     pub bytes_transferred: u64,
     /// Duration of last sync in milliseconds.
     pub duration_ms: u64,
+    /// Syncs in a row that failed completely; drives scheduler backoff.
+    #[serde(default)]
+    pub consecutive_failures: u32,
 }
 
 /// Persistent sync status for all sources.
@@ -1106,6 +1166,11 @@ impl SyncStatus {
             .as_millis();
         let now = i64::try_from(now).unwrap_or(i64::MAX);
 
+        let previous_failures = self
+            .sources
+            .get(source_name)
+            .map_or(0, |info| info.consecutive_failures);
+
         let result = if report.all_succeeded {
             SyncResult::Success
         } else if report.successful_paths() > 0 {
@@ -1124,6 +1189,12 @@ impl SyncStatus {
             SyncResult::Failed(errors.join("; "))
         };
 
+        let consecutive_failures = if matches!(result, SyncResult::Failed(_)) {
+            previous_failures.saturating_add(1)
+        } else {
+            0
+        };
+
         self.sources.insert(
             source_name.to_string(),
             SourceSyncInfo {
@@ -1132,6 +1203,7 @@ impl SyncStatus {
                 files_synced: report.total_files(),
                 bytes_transferred: report.total_bytes(),
                 duration_ms: report.total_duration_ms,
+                consecutive_failures,
             },
         );
     }
@@ -1252,6 +1324,7 @@ Total transferred file size: 1,234 bytes
     fn test_sync_method_display() {
         assert_eq!(SyncMethod::Rsync.to_string(), "rsync");
         assert_eq!(SyncMethod::Sftp.to_string(), "sftp");
+        assert_eq!(SyncMethod::Native.to_string(), "native");
     }
 
     #[test]
@@ -1372,6 +1445,18 @@ Total transferred file size: 1,234 bytes
 
         let info = status.get("dead-host").unwrap();
         assert!(matches!(info.last_result, SyncResult::Failed(_)));
+        assert_eq!(info.consecutive_failures, 1);
+
+        status.update("dead-host", &report);
+        assert_eq!(status.get("dead-host").unwrap().consecutive_failures, 2);
+
+        let mut recovered = SyncReport::new("dead-host", SyncMethod::Rsync);
+        recovered.add_path_result(PathSyncResult {
+            success: true,
+            ..Default::default()
+        });
+        status.update("dead-host", &recovered);
+        assert_eq!(status.get("dead-host").unwrap().consecutive_failures, 0);
     }
 
     #[test]
@@ -1399,6 +1484,10 @@ Total transferred file size: 1,234 bytes
             "Connection timed out after 30 seconds"
         );
         assert_eq!(SyncError::Cancelled.to_string(), "Sync cancelled");
+        assert_eq!(
+            SyncError::Protocol("remote closed the stream".into()).to_string(),
+            "Native sync failed: remote closed the stream"
+        );
     }
 
     // =========================================================================
@@ -1,5 +1,8 @@
 //! `SQLite` backend: schema, pragmas, and migrations.
 
+use crate::connectors::{
+    CursorMap, FileCursor, ToolEventKind, extract_tool_events, extract_tool_names,
+};
 use crate::model::types::{Agent, AgentKind, Conversation, Message, MessageRole, Snippet};
 use crate::sources::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
 use anyhow::{Context, Result, anyhow};
@@ -200,7 +203,13 @@ impl From<anyhow::Error> for MigrationError {
 const MAX_BACKUPS: usize = 3;
 
 /// Files that contain user-authored state and must NEVER be deleted during rebuild.
-const USER_DATA_FILES: &[&str] = &["bookmarks.db", "tui_state.json", "sources.toml", ".env"];
+const USER_DATA_FILES: &[&str] = &[
+    "bookmarks.db",
+    "tui_state.json",
+    "saved_searches.json",
+    "sources.toml",
+    ".env",
+];
 
 /// Check if a file is user-authored data that must be preserved during rebuild.
 pub fn is_user_data_file(path: &Path) -> bool {
@@ -327,7 +336,7 @@ pub fn cleanup_old_backups(db_path: &Path, keep_count: usize) -> Result<(), std:
 }
 
 /// Public schema version constant for external checks.
-pub const CURRENT_SCHEMA_VERSION: i64 = 8;
+pub const CURRENT_SCHEMA_VERSION: i64 = 13;
 
 /// Result of checking schema compatibility.
 #[derive(Debug, Clone)]
@@ -402,7 +411,7 @@ fn check_schema_compatibility(path: &Path) -> std::result::Result<SchemaCheck, r
     }
 }
 
-const SCHEMA_VERSION: i64 = 8;
+const SCHEMA_VERSION: i64 = 13;
 
 const MIGRATION_V1: &str = r"
 PRAGMA foreign_keys = ON;
@@ -624,6 +633,119 @@ CREATE INDEX IF NOT EXISTS idx_daily_stats_agent ON daily_stats(agent_slug, day_
 CREATE INDEX IF NOT EXISTS idx_daily_stats_source ON daily_stats(source_id, day_id);
 ";
 
+const MIGRATION_V9: &str = r"
+-- Tool names invoked by each message (lowercased), used by `tool:` / --tool filters.
+-- Existing rows are backfilled from message content/extra_json by migrate().
+CREATE TABLE IF NOT EXISTS message_tools (
+    message_id INTEGER NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    tool TEXT NOT NULL,
+    PRIMARY KEY (message_id, tool)
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_tools_tool ON message_tools(tool);
+";
+
+const MIGRATION_V10: &str = r"
+-- User-assigned session tags keyed by (source_id, source_path) so they survive
+-- `cass index --full`, which drops and re-creates conversation rows.
+-- conversation_tags remains the per-conversation resolution and is re-attached on insert.
+CREATE TABLE IF NOT EXISTS session_tags (
+    source_id TEXT NOT NULL,
+    source_path TEXT NOT NULL,
+    tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (source_id, source_path, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_tags_tag ON session_tags(tag_id);
+
+INSERT OR IGNORE INTO session_tags(source_id, source_path, tag_id, created_at)
+SELECT c.source_id, c.source_path, ct.tag_id, strftime('%s','now')*1000
+FROM conversation_tags ct
+JOIN conversations c ON c.id = ct.conversation_id;
+";
+
+const MIGRATION_V11: &str = r"
+-- Per-file resume cursors for append-only JSONL sessions. Incremental scans parse
+-- only the bytes after byte_offset (while the inode is unchanged) and continue
+-- message indices at next_idx. Cleared together with conversation rows on reset.
+CREATE TABLE IF NOT EXISTS scan_cursors (
+    source_path TEXT PRIMARY KEY,
+    inode INTEGER NOT NULL,
+    byte_offset INTEGER NOT NULL,
+    next_idx INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+";
+
+const MIGRATION_V12: &str = r"
+-- Files read/edited and shell commands run by each message's tool calls, with
+-- the exit status when the tool result was seen. Backs `cass files` and
+-- `cass commands`; existing rows are backfilled from extra_json by migrate().
+CREATE TABLE IF NOT EXISTS tool_events (
+    id INTEGER PRIMARY KEY,
+    message_id INTEGER NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    tool TEXT NOT NULL,
+    path TEXT,
+    command TEXT,
+    exit_code INTEGER,
+    failed INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_tool_events_message ON tool_events(message_id);
+CREATE INDEX IF NOT EXISTS idx_tool_events_path ON tool_events(path);
+CREATE INDEX IF NOT EXISTS idx_tool_events_kind ON tool_events(kind, failed);
+";
+
+const MIGRATION_V13: &str = r"
+-- Roo Code tasks (globalStorage/rooveterinaryinc.roo-cline) used to be indexed by the
+-- Cline connector. They now belong to the roo_code connector, so move those sessions
+-- to the roo_code agent instead of letting the next scan index them a second time.
+-- Where that scan already ran, the stale cline copy is dropped. Foreign keys are off
+-- during migrations, so dependent rows are deleted explicitly.
+INSERT OR IGNORE INTO agents(slug, name, version, kind, created_at, updated_at)
+SELECT 'roo_code', 'roo_code', NULL, 'cli', created_at, updated_at FROM agents
+WHERE slug = 'cline'
+  AND EXISTS (
+    SELECT 1 FROM conversations c
+    WHERE c.agent_id = agents.id AND c.source_path LIKE '%rooveterinaryinc.roo-cline%'
+  );
+
+CREATE TEMP TABLE roo_cline_copies AS
+SELECT c.id FROM conversations c
+JOIN agents a ON a.id = c.agent_id AND a.slug = 'cline'
+WHERE c.source_path LIKE '%rooveterinaryinc.roo-cline%'
+  AND EXISTS (
+    SELECT 1 FROM conversations r
+    JOIN agents ra ON ra.id = r.agent_id AND ra.slug = 'roo_code'
+    WHERE r.source_id = c.source_id AND r.external_id = c.external_id
+  );
+
+DELETE FROM fts_messages WHERE message_id IN (
+    SELECT id FROM messages WHERE conversation_id IN (SELECT id FROM roo_cline_copies)
+);
+DELETE FROM tool_events WHERE message_id IN (
+    SELECT id FROM messages WHERE conversation_id IN (SELECT id FROM roo_cline_copies)
+);
+DELETE FROM message_tools WHERE message_id IN (
+    SELECT id FROM messages WHERE conversation_id IN (SELECT id FROM roo_cline_copies)
+);
+DELETE FROM snippets WHERE message_id IN (
+    SELECT id FROM messages WHERE conversation_id IN (SELECT id FROM roo_cline_copies)
+);
+DELETE FROM messages WHERE conversation_id IN (SELECT id FROM roo_cline_copies);
+DELETE FROM conversation_tags WHERE conversation_id IN (SELECT id FROM roo_cline_copies);
+DELETE FROM conversations WHERE id IN (SELECT id FROM roo_cline_copies);
+DROP TABLE roo_cline_copies;
+
+UPDATE fts_messages SET agent = 'roo_code'
+WHERE agent = 'cline' AND source_path LIKE '%rooveterinaryinc.roo-cline%';
+UPDATE conversations SET agent_id = (SELECT id FROM agents WHERE slug = 'roo_code')
+WHERE agent_id = (SELECT id FROM agents WHERE slug = 'cline')
+  AND source_path LIKE '%rooveterinaryinc.roo-cline%';
+";
+
 pub struct SqliteStorage {
     conn: Connection,
 }
@@ -644,6 +766,68 @@ pub struct MessageForEmbedding {
     pub content: String,
 }
 
+/// Filters for [`SqliteStorage::tool_events`] and [`SqliteStorage::touched_files`].
+#[derive(Debug, Clone, Default)]
+pub struct ToolEventFilter {
+    pub kind: Option<ToolEventKind>,
+    /// File path; matches exactly or by trailing path components in either
+    /// direction, so `src/lib.rs` finds `/home/me/repo/src/lib.rs`.
+    pub path: Option<String>,
+    pub failed: Option<bool>,
+    /// Case-insensitive substring of the command line
+    pub command_contains: Option<String>,
+    pub agent: Option<String>,
+    /// Substring of the workspace path
+    pub workspace: Option<String>,
+    pub since_ms: Option<i64>,
+}
+
+/// A tool event with the session and message it came from.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ToolEventRow {
+    pub kind: String,
+    pub tool: String,
+    pub path: Option<String>,
+    pub command: Option<String>,
+    pub exit_code: Option<i64>,
+    pub failed: Option<bool>,
+    pub agent: String,
+    pub workspace: Option<String>,
+    pub source_path: String,
+    pub title: Option<String>,
+    /// 1-based message position, as used by `cass view -n`
+    pub line_number: i64,
+    pub created_at: Option<i64>,
+}
+
+/// Per-session references to one file, from tool calls and message snippets.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileReferences {
+    pub source_path: String,
+    pub title: Option<String>,
+    pub agent: String,
+    pub workspace: Option<String>,
+    pub edits: i64,
+    pub reads: i64,
+    /// Code snippets whose `file_path` is the file
+    pub snippets: i64,
+    /// Snippets whose line range covers the requested line
+    pub line_snippets: i64,
+    /// 1-indexed message of the first reference
+    pub line_number: i64,
+    pub last_referenced: Option<i64>,
+}
+
+/// Per-file aggregate of read/edit events.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TouchedFile {
+    pub path: String,
+    pub sessions: i64,
+    pub edits: i64,
+    pub reads: i64,
+    pub last_touched: Option<i64>,
+}
+
 impl SqliteStorage {
     pub fn open(path: &Path) -> Result<Self> {
         if let Some(parent) = path.parent() {
@@ -1297,28 +1481,90 @@ impl SqliteStorage {
                 LIMIT ? OFFSET ?",
         )?;
 
-        let rows = stmt.query_map(params![limit, offset], |row| {
-            Ok(Conversation {
-                id: Some(row.get(0)?),
-                agent_slug: row.get(1)?,
-                workspace: row
-                    .get::<_, Option<String>>(2)?
-                    .map(|p| Path::new(&p).to_path_buf()),
-                external_id: row.get(3)?,
-                title: row.get(4)?,
-                source_path: Path::new(&row.get::<_, String>(5)?).to_path_buf(),
-                started_at: row.get(6)?,
-                ended_at: row.get(7)?,
-                approx_tokens: row.get(8)?,
-                // Read from binary column first (idx 12), fallback to JSON (idx 9)
-                metadata_json: read_metadata_compat(row, 9, 12),
-                messages: Vec::new(),
-                source_id: row
-                    .get::<_, String>(10)
-                    .unwrap_or_else(|_| "local".to_string()),
-                origin_host: row.get(11)?,
-            })
-        })?;
+        let rows = stmt.query_map(params![limit, offset], conversation_from_row)?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Conversations indexed from `source_path` (one per source_id/external_id).
+    pub fn list_conversations_for_path(&self, source_path: &str) -> Result<Vec<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
+                       c.started_at, c.ended_at, c.approx_tokens, c.metadata_json,
+                       c.source_id, c.origin_host, c.metadata_bin
+                FROM conversations c
+                JOIN agents a ON c.agent_id = a.id
+                LEFT JOIN workspaces w ON c.workspace_id = w.id
+                WHERE c.source_path = ?
+                ORDER BY c.id",
+        )?;
+        let rows = stmt.query_map(params![source_path], conversation_from_row)?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Identifier of this database's message rowid sequence.
+    ///
+    /// Rowids restart when messages are wiped, so the epoch is regenerated
+    /// then (see [`Self::reset_sync_epoch`]); a cursor from [`Self::max_message_id`]
+    /// is only meaningful together with the epoch it was read under. `None`
+    /// for databases not opened read-write since the epoch was introduced.
+    pub fn sync_epoch(&self) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT value FROM meta WHERE key = 'sync_epoch'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Start a new sync epoch, invalidating cursors handed out so far.
+    pub fn reset_sync_epoch(&mut self) -> Result<String> {
+        let epoch = new_sync_epoch();
+        self.conn.execute(
+            "INSERT OR REPLACE INTO meta(key, value) VALUES('sync_epoch', ?)",
+            params![epoch],
+        )?;
+        Ok(epoch)
+    }
+
+    /// Highest message rowid, or 0 for an empty database.
+    ///
+    /// Rowids only grow as messages are appended (until a reset, which starts
+    /// a new [sync epoch](Self::sync_epoch)), so native sync uses this as its
+    /// change cursor.
+    pub fn max_message_id(&self) -> Result<i64> {
+        Ok(self
+            .conn
+            .query_row("SELECT COALESCE(MAX(id), 0) FROM messages", [], |row| {
+                row.get(0)
+            })?)
+    }
+
+    /// Local conversations (not pulled from another source) that gained
+    /// messages with rowid in `(after, upto]`. Headers only.
+    pub fn local_conversations_changed(&self, after: i64, upto: i64) -> Result<Vec<Conversation>> {
+        let mut stmt = self.conn.prepare(
+            r"SELECT c.id, a.slug, w.path, c.external_id, c.title, c.source_path,
+                       c.started_at, c.ended_at, c.approx_tokens, c.metadata_json,
+                       c.source_id, c.origin_host, c.metadata_bin
+                FROM conversations c
+                JOIN agents a ON c.agent_id = a.id
+                LEFT JOIN workspaces w ON c.workspace_id = w.id
+                WHERE c.source_id = ?
+                  AND EXISTS (SELECT 1 FROM messages m
+                              WHERE m.conversation_id = c.id AND m.id > ? AND m.id <= ?)
+                ORDER BY c.id",
+        )?;
+        let rows = stmt.query_map(params![LOCAL_SOURCE_ID, after, upto], conversation_from_row)?;
         let mut out = Vec::new();
         for r in rows {
             out.push(r?);
@@ -1357,6 +1603,45 @@ impl SqliteStorage {
         Ok(out)
     }
 
+    /// Like [`Self::fetch_messages`], with each message's snippets attached.
+    pub fn fetch_messages_with_snippets(&self, conversation_id: i64) -> Result<Vec<Message>> {
+        let mut messages = self.fetch_messages(conversation_id)?;
+        let mut stmt = self.conn.prepare(
+            r"SELECT s.message_id, s.id, s.file_path, s.start_line, s.end_line, s.language, s.snippet_text
+              FROM snippets s
+              JOIN messages m ON s.message_id = m.id
+              WHERE m.conversation_id = ?
+              ORDER BY s.id",
+        )?;
+        let rows = stmt.query_map(params![conversation_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Snippet {
+                    id: Some(row.get(1)?),
+                    file_path: row.get::<_, Option<String>>(2)?.map(PathBuf::from),
+                    start_line: row.get(3)?,
+                    end_line: row.get(4)?,
+                    language: row.get(5)?,
+                    snippet_text: row.get(6)?,
+                },
+            ))
+        })?;
+        let mut by_message: std::collections::HashMap<i64, Vec<Snippet>> =
+            std::collections::HashMap::new();
+        for r in rows {
+            let (message_id, snippet) = r?;
+            by_message.entry(message_id).or_default().push(snippet);
+        }
+        for msg in &mut messages {
+            if let Some(id) = msg.id
+                && let Some(snippets) = by_message.remove(&id)
+            {
+                msg.snippets = snippets;
+            }
+        }
+        Ok(messages)
+    }
+
     /// Fetch all messages with their conversation metadata for semantic indexing.
     ///
     /// Returns MessageForEmbedding records with all metadata needed for vector indexing.
@@ -1435,6 +1720,17 @@ impl SqliteStorage {
         Ok(())
     }
 
+    /// Consume a search index rebuild requested by a schema migration.
+    ///
+    /// Returns true at most once per request; the caller is expected to rescan
+    /// every root and rebuild Tantivy and daily stats.
+    pub fn take_rebuild_request(&mut self) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM meta WHERE key = 'rebuild_requested'", [])?;
+        Ok(removed > 0)
+    }
+
     /// Set the timestamp of the last successful index completion (milliseconds since epoch).
     pub fn set_last_indexed_at(&mut self, ts: i64) -> Result<()> {
         self.conn.execute(
@@ -1576,6 +1872,352 @@ impl SqliteStorage {
         Ok(rows_affected > 0)
     }
 
+    // -------------------------------------------------------------------------
+    // Session tags - user data keyed by (source_id, source_path)
+    // -------------------------------------------------------------------------
+
+    /// Attach tags to every conversation indexed from `source_path`.
+    ///
+    /// Tags are recorded in `session_tags` (durable across full rebuilds) and
+    /// mirrored into `conversation_tags`. Returns the number of matching
+    /// conversations; 0 means the session is unknown and nothing was written.
+    pub fn add_session_tags(&mut self, source_path: &str, tags: &[String]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let source_ids = session_source_ids(&tx, source_path)?;
+        if source_ids.is_empty() {
+            return Ok(0);
+        }
+        let now = Self::now_millis();
+        for name in tags {
+            tx.execute("INSERT OR IGNORE INTO tags(name) VALUES(?)", params![name])?;
+            let tag_id: i64 =
+                tx.query_row("SELECT id FROM tags WHERE name = ?", params![name], |row| {
+                    row.get(0)
+                })?;
+            for source_id in &source_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO session_tags(source_id, source_path, tag_id, created_at)
+                     VALUES(?, ?, ?, ?)",
+                    params![source_id, source_path, tag_id, now],
+                )?;
+            }
+            tx.execute(
+                "INSERT OR IGNORE INTO conversation_tags(conversation_id, tag_id)
+                 SELECT id, ? FROM conversations WHERE source_path = ?",
+                params![tag_id, source_path],
+            )?;
+        }
+        let matched: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM conversations WHERE source_path = ?",
+            params![source_path],
+            |row| row.get(0),
+        )?;
+        tx.commit()?;
+        Ok(matched as usize)
+    }
+
+    /// Detach tags from the session at `source_path`, pruning tags no longer in use.
+    ///
+    /// Returns the number of matching conversations (0 if the session is unknown).
+    pub fn remove_session_tags(&mut self, source_path: &str, tags: &[String]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let matched: i64 = tx.query_row(
+            "SELECT COUNT(*) FROM conversations WHERE source_path = ?",
+            params![source_path],
+            |row| row.get(0),
+        )?;
+        for name in tags {
+            tx.execute(
+                "DELETE FROM session_tags
+                 WHERE source_path = ? AND tag_id = (SELECT id FROM tags WHERE name = ?)",
+                params![source_path, name],
+            )?;
+            tx.execute(
+                "DELETE FROM conversation_tags
+                 WHERE tag_id = (SELECT id FROM tags WHERE name = ?)
+                   AND conversation_id IN (SELECT id FROM conversations WHERE source_path = ?)",
+                params![name, source_path],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM tags
+             WHERE id NOT IN (SELECT tag_id FROM session_tags)
+               AND id NOT IN (SELECT tag_id FROM conversation_tags)",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(matched as usize)
+    }
+
+    /// Tags attached to the session at `source_path`, sorted by name.
+    pub fn session_tags(&self, source_path: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT t.name FROM session_tags st
+             JOIN tags t ON t.id = st.tag_id
+             WHERE st.source_path = ?
+             ORDER BY t.name",
+        )?;
+        let rows = stmt.query_map(params![source_path], |row| row.get(0))?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// All tags with the number of sessions carrying them, sorted by name.
+    pub fn list_tags(&self) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.name, COUNT(DISTINCT st.source_path) FROM tags t
+             JOIN session_tags st ON st.tag_id = t.id
+             GROUP BY t.id
+             ORDER BY t.name",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Map of `(source_id, source_path)` to sorted tag names, used when
+    /// (re)building the Tantivy index so `tag:` filters see user tags.
+    pub fn session_tag_map(&self) -> Result<HashMap<(String, String), Vec<String>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT st.source_id, st.source_path, t.name FROM session_tags st
+             JOIN tags t ON t.id = st.tag_id
+             ORDER BY t.name",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        let mut out: HashMap<(String, String), Vec<String>> = HashMap::new();
+        for r in rows {
+            let (source_id, source_path, name) = r?;
+            out.entry((source_id, source_path)).or_default().push(name);
+        }
+        Ok(out)
+    }
+
+    // -------------------------------------------------------------------------
+    // Tool events - files touched and commands run
+    // -------------------------------------------------------------------------
+
+    /// Tool events matching `filter`, newest first.
+    pub fn tool_events(&self, filter: &ToolEventFilter, limit: usize) -> Result<Vec<ToolEventRow>> {
+        let (where_sql, mut params_vec) = tool_event_where(filter);
+        let sql = format!(
+            "SELECT e.kind, e.tool, e.path, e.command, e.exit_code, e.failed,
+                    a.slug, w.path, c.source_path, c.title, m.idx, COALESCE(m.created_at, c.started_at)
+             FROM tool_events e
+             JOIN messages m ON m.id = e.message_id
+             JOIN conversations c ON c.id = m.conversation_id
+             JOIN agents a ON a.id = c.agent_id
+             LEFT JOIN workspaces w ON w.id = c.workspace_id
+             WHERE {where_sql}
+             ORDER BY COALESCE(m.created_at, c.started_at) DESC, e.id DESC
+             LIMIT ?"
+        );
+        params_vec.push(Box::new(limit as i64));
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|b| b.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            Ok(ToolEventRow {
+                kind: row.get(0)?,
+                tool: row.get(1)?,
+                path: row.get(2)?,
+                command: row.get(3)?,
+                exit_code: row.get(4)?,
+                failed: row.get(5)?,
+                agent: row.get(6)?,
+                workspace: row.get(7)?,
+                source_path: row.get(8)?,
+                title: row.get(9)?,
+                line_number: row.get::<_, i64>(10)? + 1,
+                created_at: row.get(11)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Files read or edited by tool calls matching `filter`, most sessions first.
+    pub fn touched_files(
+        &self,
+        filter: &ToolEventFilter,
+        limit: usize,
+    ) -> Result<Vec<TouchedFile>> {
+        let (where_sql, mut params_vec) = tool_event_where(filter);
+        let sql = format!(
+            "SELECT e.path, COUNT(DISTINCT c.id),
+                    SUM(e.kind = 'edit'), SUM(e.kind = 'read'),
+                    MAX(COALESCE(m.created_at, c.started_at))
+             FROM tool_events e
+             JOIN messages m ON m.id = e.message_id
+             JOIN conversations c ON c.id = m.conversation_id
+             JOIN agents a ON a.id = c.agent_id
+             LEFT JOIN workspaces w ON w.id = c.workspace_id
+             WHERE {where_sql} AND e.path IS NOT NULL
+             GROUP BY e.path
+             ORDER BY COUNT(DISTINCT c.id) DESC, SUM(e.kind = 'edit') DESC, e.path
+             LIMIT ?"
+        );
+        params_vec.push(Box::new(limit as i64));
+        let params_refs: Vec<&dyn rusqlite::ToSql> =
+            params_vec.iter().map(|b| b.as_ref()).collect();
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            Ok(TouchedFile {
+                path: row.get(0)?,
+                sessions: row.get(1)?,
+                edits: row.get(2)?,
+                reads: row.get(3)?,
+                last_touched: row.get(4)?,
+            })
+        })?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    /// Sessions whose tool calls or snippets reference `path`, most references first.
+    ///
+    /// `path` matches like [`ToolEventFilter::path`]. With `line`, snippets
+    /// whose range covers it are counted separately.
+    pub fn file_references(
+        &self,
+        path: &str,
+        line: Option<usize>,
+        limit: usize,
+    ) -> Result<Vec<FileReferences>> {
+        let sql = format!(
+            "WITH refs AS (
+                SELECT e.message_id AS message_id, e.kind AS kind, 0 AS covers_line
+                FROM tool_events e
+                WHERE e.kind IN ('read', 'edit') AND {tool_match}
+                UNION ALL
+                SELECT s.message_id, 'snippet',
+                       COALESCE(?2 BETWEEN s.start_line AND COALESCE(s.end_line, s.start_line), 0)
+                FROM snippets s
+                WHERE s.file_path IS NOT NULL AND {snippet_match}
+            )
+            SELECT c.source_path, c.title, a.slug, w.path,
+                   SUM(r.kind = 'edit'), SUM(r.kind = 'read'), SUM(r.kind = 'snippet'),
+                   SUM(r.covers_line), MIN(m.idx) + 1, MAX(COALESCE(m.created_at, c.started_at))
+            FROM refs r
+            JOIN messages m ON m.id = r.message_id
+            JOIN conversations c ON c.id = m.conversation_id
+            JOIN agents a ON a.id = c.agent_id
+            LEFT JOIN workspaces w ON w.id = c.workspace_id
+            GROUP BY c.id
+            ORDER BY SUM(r.covers_line) DESC, SUM(r.kind = 'edit') DESC, COUNT(*) DESC,
+                     MAX(COALESCE(m.created_at, c.started_at)) DESC
+            LIMIT ?3",
+            tool_match = path_match_sql("e.path", 1),
+            snippet_match = path_match_sql("s.file_path", 1),
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt.query_map(
+            params![
+                normalize_match_path(path),
+                line.map(|l| l as i64),
+                limit as i64
+            ],
+            |row| {
+                Ok(FileReferences {
+                    source_path: row.get(0)?,
+                    title: row.get(1)?,
+                    agent: row.get(2)?,
+                    workspace: row.get(3)?,
+                    edits: row.get(4)?,
+                    reads: row.get(5)?,
+                    snippets: row.get(6)?,
+                    line_snippets: row.get(7)?,
+                    line_number: row.get(8)?,
+                    last_referenced: row.get(9)?,
+                })
+            },
+        )?;
+        let mut out = Vec::new();
+        for r in rows {
+            out.push(r?);
+        }
+        Ok(out)
+    }
+
+    // -------------------------------------------------------------------------
+    // Scan cursors - resume points for append-only JSONL sessions
+    // -------------------------------------------------------------------------
+
+    /// Load stored scan cursors for sessions that are still indexed.
+    ///
+    /// Cursors whose conversation rows are gone are ignored so the file is
+    /// re-parsed from the start instead of only its tail.
+    pub fn load_scan_cursors(&self) -> Result<CursorMap> {
+        let mut stmt = self.conn.prepare(
+            "SELECT sc.source_path, sc.inode, sc.byte_offset, sc.next_idx FROM scan_cursors sc
+             WHERE EXISTS (SELECT 1 FROM conversations c WHERE c.source_path = sc.source_path)",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                PathBuf::from(row.get::<_, String>(0)?),
+                FileCursor {
+                    inode: row.get::<_, i64>(1)? as u64,
+                    offset: row.get::<_, i64>(2)? as u64,
+                    next_idx: row.get(3)?,
+                },
+            ))
+        })?;
+        let mut out = CursorMap::new();
+        for r in rows {
+            let (path, cursor) = r?;
+            out.insert(path, cursor);
+        }
+        Ok(out)
+    }
+
+    /// Upsert scan cursors after their conversations have been ingested.
+    pub fn save_scan_cursors(&mut self, cursors: &[(PathBuf, FileCursor)]) -> Result<()> {
+        if cursors.is_empty() {
+            return Ok(());
+        }
+        let now = Self::now_millis();
+        let tx = self.conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO scan_cursors(source_path, inode, byte_offset, next_idx, updated_at)
+                 VALUES(?, ?, ?, ?, ?)
+                 ON CONFLICT(source_path) DO UPDATE SET
+                    inode = excluded.inode,
+                    byte_offset = excluded.byte_offset,
+                    next_idx = excluded.next_idx,
+                    updated_at = excluded.updated_at",
+            )?;
+            for (path, cursor) in cursors {
+                stmt.execute(params![
+                    path_to_string(path),
+                    cursor.inode as i64,
+                    cursor.offset as i64,
+                    cursor.next_idx,
+                    now
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
     // -------------------------------------------------------------------------
     // Daily Stats (Opt 3.2) - Materialized Aggregates for O(1) Range Queries
     // -------------------------------------------------------------------------
@@ -2107,9 +2749,18 @@ fn init_meta(conn: &mut Connection) -> Result<()> {
         )?;
     }
 
+    conn.execute(
+        "INSERT OR IGNORE INTO meta(key, value) VALUES('sync_epoch', ?)",
+        params![new_sync_epoch()],
+    )?;
+
     Ok(())
 }
 
+fn new_sync_epoch() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
 fn migrate(conn: &mut Connection) -> Result<()> {
     let current: i64 = conn
         .query_row(
@@ -2130,6 +2781,10 @@ fn migrate(conn: &mut Connection) -> Result<()> {
 
     let tx = conn.transaction()?;
 
+    // Sessions moved by V13 keep `cline` in their search index documents until
+    // they are re-indexed, so ask the next `cass index` for a rebuild.
+    let roo_sessions_moved = (1..13).contains(&current) && count_roo_cline_sessions(&tx)? > 0;
+
     match current {
         0 => {
             tx.execute_batch(MIGRATION_V1)?;
@@ -2140,6 +2795,11 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         1 => {
             tx.execute_batch(MIGRATION_V2)?;
@@ -2149,6 +2809,11 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         2 => {
             tx.execute_batch(MIGRATION_V3)?;
@@ -2157,6 +2822,11 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         3 => {
             tx.execute_batch(MIGRATION_V4)?;
@@ -2164,28 +2834,91 @@ fn migrate(conn: &mut Connection) -> Result<()> {
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         4 => {
             tx.execute_batch(MIGRATION_V5)?;
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         5 => {
             tx.execute_batch(MIGRATION_V6)?;
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         6 => {
             tx.execute_batch(MIGRATION_V7)?;
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
         }
         7 => {
             tx.execute_batch(MIGRATION_V8)?;
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
+        }
+        8 => {
+            tx.execute_batch(MIGRATION_V9)?;
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
+        }
+        9 => {
+            tx.execute_batch(MIGRATION_V10)?;
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
+        }
+        10 => {
+            tx.execute_batch(MIGRATION_V11)?;
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
+        }
+        11 => {
+            tx.execute_batch(MIGRATION_V12)?;
+            tx.execute_batch(MIGRATION_V13)?;
+        }
+        12 => {
+            tx.execute_batch(MIGRATION_V13)?;
         }
         v => return Err(anyhow!("unsupported schema version {v}")),
     }
 
+    if current < 9 {
+        backfill_message_tools(&tx)?;
+    }
+    if current < 12 {
+        backfill_tool_events(&tx)?;
+    }
+    if roo_sessions_moved {
+        tx.execute(
+            "INSERT OR REPLACE INTO meta(key, value) VALUES('rebuild_requested', '1')",
+            [],
+        )?;
+    }
+
     tx.execute(
         "UPDATE meta SET value = ? WHERE key = 'schema_version'",
         params![SCHEMA_VERSION.to_string()],
@@ -2199,6 +2932,149 @@ fn migrate(conn: &mut Connection) -> Result<()> {
     Ok(())
 }
 
+/// Roo Code sessions still attributed to the Cline agent (rewritten by V13).
+fn count_roo_cline_sessions(tx: &Transaction<'_>) -> Result<i64> {
+    Ok(tx.query_row(
+        "SELECT COUNT(*) FROM conversations c
+         JOIN agents a ON a.id = c.agent_id AND a.slug = 'cline'
+         WHERE c.source_path LIKE '%rooveterinaryinc.roo-cline%'",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Populate `message_tools` for messages indexed before the table existed (V9).
+fn backfill_message_tools(tx: &Transaction<'_>) -> Result<()> {
+    let mut select = tx.prepare("SELECT id, content, extra_json FROM messages")?;
+    let mut insert =
+        tx.prepare("INSERT OR IGNORE INTO message_tools(message_id, tool) VALUES(?, ?)")?;
+    let mut rows = select.query([])?;
+    while let Some(row) = rows.next()? {
+        let message_id: i64 = row.get(0)?;
+        let content: String = row.get(1)?;
+        let extra: serde_json::Value = row
+            .get::<_, Option<String>>(2)?
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or(serde_json::Value::Null);
+        for tool in extract_tool_names(&content, &extra) {
+            insert.execute(params![message_id, tool])?;
+        }
+    }
+    Ok(())
+}
+
+/// WHERE clause (over `tool_events e`, `messages m`, `conversations c`,
+/// `agents a`, `workspaces w`) and its parameters for a [`ToolEventFilter`].
+fn tool_event_where(filter: &ToolEventFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut sql = "1=1".to_string();
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    if let Some(kind) = filter.kind {
+        sql.push_str(" AND e.kind = ?");
+        params_vec.push(Box::new(kind.as_str()));
+    }
+    if let Some(path) = filter.path.as_deref() {
+        let n = params_vec.len() + 1;
+        sql.push_str(" AND ");
+        sql.push_str(&path_match_sql("e.path", n));
+        params_vec.push(Box::new(normalize_match_path(path)));
+    }
+    if let Some(failed) = filter.failed {
+        sql.push_str(if failed {
+            " AND e.failed = 1"
+        } else {
+            " AND (e.failed = 0 OR e.failed IS NULL)"
+        });
+    }
+    if let Some(needle) = filter.command_contains.as_deref() {
+        sql.push_str(" AND instr(lower(e.command), ?) > 0");
+        params_vec.push(Box::new(needle.to_lowercase()));
+    }
+    if let Some(agent) = filter.agent.as_deref() {
+        sql.push_str(" AND a.slug = ?");
+        params_vec.push(Box::new(agent.to_string()));
+    }
+    if let Some(workspace) = filter.workspace.as_deref() {
+        sql.push_str(" AND instr(w.path, ?) > 0");
+        params_vec.push(Box::new(workspace.to_string()));
+    }
+    if let Some(since) = filter.since_ms {
+        sql.push_str(" AND COALESCE(m.created_at, c.started_at) >= ?");
+        params_vec.push(Box::new(since));
+    }
+    (sql, params_vec)
+}
+
+/// Trim `./` and trailing slashes so user-supplied paths compare like stored ones.
+fn normalize_match_path(path: &str) -> String {
+    path.trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// SQL matching `column` against numbered parameter `?n`: exact, or one path is a
+/// component-aligned suffix of the other (numbered so the parameter can be reused).
+fn path_match_sql(column: &str, n: usize) -> String {
+    format!(
+        "({column} = ?{n}
+            OR (length({column}) > length(?{n}) AND substr({column}, -length(?{n}) - 1) = '/' || ?{n})
+            OR (length(?{n}) > length({column}) AND substr(?{n}, -length({column}) - 1) = '/' || {column}))"
+    )
+}
+
+/// Populate `tool_events` for messages indexed before the table existed (V12).
+fn backfill_tool_events(tx: &Transaction<'_>) -> Result<()> {
+    let mut select = tx.prepare("SELECT id, extra_json FROM messages")?;
+    let mut rows = select.query([])?;
+    while let Some(row) = rows.next()? {
+        let message_id: i64 = row.get(0)?;
+        let Some(extra) = row
+            .get::<_, Option<String>>(1)?
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        else {
+            continue;
+        };
+        insert_tool_events(tx, message_id, &extra)?;
+    }
+    Ok(())
+}
+
+/// Map a row from the `list_conversations` column layout to a `Conversation`.
+fn conversation_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Conversation> {
+    Ok(Conversation {
+        id: Some(row.get(0)?),
+        agent_slug: row.get(1)?,
+        workspace: row
+            .get::<_, Option<String>>(2)?
+            .map(|p| Path::new(&p).to_path_buf()),
+        external_id: row.get(3)?,
+        title: row.get(4)?,
+        source_path: Path::new(&row.get::<_, String>(5)?).to_path_buf(),
+        started_at: row.get(6)?,
+        ended_at: row.get(7)?,
+        approx_tokens: row.get(8)?,
+        // Read from binary column first (idx 12), fallback to JSON (idx 9)
+        metadata_json: read_metadata_compat(row, 9, 12),
+        messages: Vec::new(),
+        source_id: row
+            .get::<_, String>(10)
+            .unwrap_or_else(|_| "local".to_string()),
+        origin_host: row.get(11)?,
+    })
+}
+
+/// Distinct `source_id`s of conversations indexed from `source_path`.
+fn session_source_ids(tx: &Transaction<'_>, source_path: &str) -> Result<Vec<String>> {
+    let mut stmt =
+        tx.prepare("SELECT DISTINCT source_id FROM conversations WHERE source_path = ?")?;
+    let rows = stmt.query_map(params![source_path], |row| row.get(0))?;
+    let mut out = Vec::new();
+    for r in rows {
+        out.push(r?);
+    }
+    Ok(out)
+}
+
 fn insert_conversation(
     tx: &Transaction<'_>,
     agent_id: i64,
@@ -2228,7 +3104,14 @@ fn insert_conversation(
             metadata_bin
         ],
     )?;
-    Ok(tx.last_insert_rowid())
+    let conv_id = tx.last_insert_rowid();
+    // Re-attach durable user tags (session_tags outlives conversation rows across rebuilds).
+    tx.execute(
+        "INSERT OR IGNORE INTO conversation_tags(conversation_id, tag_id)
+         SELECT ?1, tag_id FROM session_tags WHERE source_id = ?2 AND source_path = ?3",
+        params![conv_id, &conv.source_id, path_to_string(&conv.source_path)],
+    )?;
+    Ok(conv_id)
 }
 
 fn insert_message(tx: &Transaction<'_>, conversation_id: i64, msg: &Message) -> Result<i64> {
@@ -2249,7 +3132,43 @@ fn insert_message(tx: &Transaction<'_>, conversation_id: i64, msg: &Message) ->
             extra_bin
         ],
     )?;
-    Ok(tx.last_insert_rowid())
+    let message_id = tx.last_insert_rowid();
+    insert_message_tools(tx, message_id, msg)?;
+    insert_tool_events(tx, message_id, &msg.extra_json)?;
+    Ok(message_id)
+}
+
+fn insert_tool_events(
+    tx: &Transaction<'_>,
+    message_id: i64,
+    extra: &serde_json::Value,
+) -> Result<()> {
+    for event in extract_tool_events(extra) {
+        tx.execute(
+            "INSERT INTO tool_events(message_id, kind, tool, path, command, exit_code, failed)
+             VALUES(?,?,?,?,?,?,?)",
+            params![
+                message_id,
+                event.kind.as_str(),
+                event.tool,
+                event.path,
+                event.command,
+                event.exit_code,
+                event.failed,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_message_tools(tx: &Transaction<'_>, message_id: i64, msg: &Message) -> Result<()> {
+    for tool in extract_tool_names(&msg.content, &msg.extra_json) {
+        tx.execute(
+            "INSERT OR IGNORE INTO message_tools(message_id, tool) VALUES(?, ?)",
+            params![message_id, tool],
+        )?;
+    }
+    Ok(())
 }
 
 fn insert_snippets(tx: &Transaction<'_>, message_id: i64, snippets: &[Snippet]) -> Result<()> {
@@ -2577,7 +3496,7 @@ fn path_to_string<P: AsRef<Path>>(p: P) -> String {
     p.as_ref().to_string_lossy().into_owned()
 }
 
-fn role_str(role: &MessageRole) -> String {
+pub(crate) fn role_str(role: &MessageRole) -> String {
     match role {
         MessageRole::User => "user".to_owned(),
         MessageRole::Agent => "agent".to_owned(),
@@ -2619,6 +3538,11 @@ mod tests {
         assert!(is_user_data_file(Path::new("/data/tui_state.json")));
     }
 
+    #[test]
+    fn is_user_data_file_detects_saved_searches() {
+        assert!(is_user_data_file(Path::new("/data/saved_searches.json")));
+    }
+
     #[test]
     fn is_user_data_file_detects_sources_toml() {
         assert!(is_user_data_file(Path::new("/config/sources.toml")));
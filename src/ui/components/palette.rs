@@ -12,7 +12,7 @@ use ratatui::{
 use crate::ui::components::theme::ThemePalette;
 
 /// Action identifiers the palette can emit. These map to app-level commands.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum PaletteAction {
     ToggleTheme,
     ToggleDensity,
@@ -30,6 +30,28 @@ pub enum PaletteAction {
     ReloadIndex,
 }
 
+impl PaletteAction {
+    /// Stable identifier used by keymap files (`palette.<id>`).
+    pub fn id(&self) -> String {
+        match self {
+            Self::ToggleTheme => "toggle_theme".to_string(),
+            Self::ToggleDensity => "toggle_density".to_string(),
+            Self::ToggleHelpStrip => "toggle_help_strip".to_string(),
+            Self::OpenUpdateBanner => "open_update_banner".to_string(),
+            Self::FilterAgent => "filter_agent".to_string(),
+            Self::FilterWorkspace => "filter_workspace".to_string(),
+            Self::FilterToday => "filter_today".to_string(),
+            Self::FilterWeek => "filter_week".to_string(),
+            Self::FilterCustomDate => "filter_custom_date".to_string(),
+            Self::OpenSavedViews => "open_saved_views".to_string(),
+            Self::SaveViewSlot(slot) => format!("save_view_slot_{slot}"),
+            Self::LoadViewSlot(slot) => format!("load_view_slot_{slot}"),
+            Self::OpenBulkActions => "open_bulk_actions".to_string(),
+            Self::ReloadIndex => "reload_index".to_string(),
+        }
+    }
+}
+
 /// Render-ready descriptor for an action.
 #[derive(Clone, Debug)]
 pub struct PaletteItem {
@@ -652,4 +674,13 @@ mod tests {
         assert_eq!(result.label, "My Label");
         assert_eq!(result.hint, "My Hint");
     }
+
+    #[test]
+    fn test_palette_action_ids_are_unique() {
+        let ids: Vec<String> = default_actions().iter().map(|i| i.action.id()).collect();
+        let unique: std::collections::HashSet<&String> = ids.iter().collect();
+        assert_eq!(ids.len(), unique.len());
+        assert!(ids.contains(&"save_view_slot_9".to_string()));
+        assert!(ids.contains(&"reload_index".to_string()));
+    }
 }
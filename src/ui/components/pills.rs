@@ -4,6 +4,7 @@ use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
+    text::Span,
     widgets::{Block, BorderType, Borders, Paragraph},
 };
 
@@ -82,6 +83,22 @@ pub fn draw_pills(
     rects
 }
 
+/// Inline `#tag` pills for a single line (e.g. session tags in the detail header).
+pub fn tag_spans(tags: &[String], palette: ThemePalette) -> Vec<Span<'static>> {
+    let style = Style::default()
+        .fg(palette.accent)
+        .bg(palette.surface)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::with_capacity(tags.len() * 2);
+    for (idx, tag) in tags.iter().enumerate() {
+        if idx > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(Span::styled(format!(" #{tag} "), style));
+    }
+    spans
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,4 +212,13 @@ mod tests {
         };
         assert!(active_editable.active && active_editable.editable);
     }
+
+    #[test]
+    fn test_tag_spans() {
+        let tags = vec!["auth-bug".to_string(), "infra".to_string()];
+        let spans = tag_spans(&tags, ThemePalette::dark());
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, " #auth-bug   #infra ");
+        assert!(tag_spans(&[], ThemePalette::dark()).is_empty());
+    }
 }
@@ -425,7 +425,7 @@ impl ThemePalette {
             // Core agents with distinct color identities
             "claude_code" | "claude" => (colors::AGENT_CLAUDE_BG, colors::ACCENT_PRIMARY), // Blue
             "codex" => (colors::AGENT_CODEX_BG, colors::STATUS_SUCCESS),                   // Green
-            "cline" => (colors::AGENT_CLINE_BG, colors::ACCENT_TERTIARY),                  // Cyan
+            "cline" | "roo_code" => (colors::AGENT_CLINE_BG, colors::ACCENT_TERTIARY),     // Cyan
             "gemini" | "gemini_cli" => (colors::AGENT_GEMINI_BG, colors::ACCENT_SECONDARY), // Purple
             "amp" => (colors::AGENT_AMP_BG, colors::STATUS_ERROR), // Orange/Red
             "aider" => (colors::AGENT_AIDER_BG, Color::Rgb(64, 224, 208)), // Turquoise accent
@@ -457,6 +457,10 @@ impl ThemePalette {
             "chatgpt" => "💬",
             "opencode" => "📦",
             "pi_agent" => "🥧",
+            "continue" => "⏩",
+            "windsurf" => "🏄",
+            "copilot" => "🛩",
+            "roo_code" => "🦘",
             _ => "✨",
         }
     }
@@ -900,6 +904,167 @@ impl ThemePalette {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// USER THEMES - Named color slots loaded from tui.toml
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Color slots a user theme can set, named after the `ThemePalette` fields.
+pub const THEME_SLOTS: &[&str] = &[
+    "accent",
+    "accent_alt",
+    "bg",
+    "fg",
+    "surface",
+    "hint",
+    "border",
+    "user",
+    "agent",
+    "tool",
+    "system",
+    "stripe_even",
+    "stripe_odd",
+];
+
+impl ThemePalette {
+    /// Read a color slot by name (see [`THEME_SLOTS`]).
+    pub fn slot(&self, name: &str) -> Option<Color> {
+        Some(match name {
+            "accent" => self.accent,
+            "accent_alt" => self.accent_alt,
+            "bg" => self.bg,
+            "fg" => self.fg,
+            "surface" => self.surface,
+            "hint" => self.hint,
+            "border" => self.border,
+            "user" => self.user,
+            "agent" => self.agent,
+            "tool" => self.tool,
+            "system" => self.system,
+            "stripe_even" => self.stripe_even,
+            "stripe_odd" => self.stripe_odd,
+            _ => return None,
+        })
+    }
+
+    /// Overwrite a color slot by name. Returns false for unknown slots.
+    pub fn set_slot(&mut self, name: &str, color: Color) -> bool {
+        let field = match name {
+            "accent" => &mut self.accent,
+            "accent_alt" => &mut self.accent_alt,
+            "bg" => &mut self.bg,
+            "fg" => &mut self.fg,
+            "surface" => &mut self.surface,
+            "hint" => &mut self.hint,
+            "border" => &mut self.border,
+            "user" => &mut self.user,
+            "agent" => &mut self.agent,
+            "tool" => &mut self.tool,
+            "system" => &mut self.system,
+            "stripe_even" => &mut self.stripe_even,
+            "stripe_odd" => &mut self.stripe_odd,
+            _ => return false,
+        };
+        *field = color;
+        true
+    }
+}
+
+impl ThemePreset {
+    /// Look up a preset by config name (`dark`, `light`, `catppuccin`, `dracula`, `nord`,
+    /// `high-contrast`). Case-insensitive; `_` and spaces are accepted in place of `-`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let normalized = name.trim().to_ascii_lowercase().replace(['_', ' '], "-");
+        Self::all()
+            .iter()
+            .copied()
+            .find(|preset| preset.name().to_ascii_lowercase().replace(' ', "-") == normalized)
+    }
+}
+
+/// Components in `ui::components` that accept per-component color slots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ThemeComponent {
+    Breadcrumbs,
+    ExportModal,
+    HelpStrip,
+    Palette,
+    Pills,
+    Toast,
+    Widgets,
+}
+
+impl ThemeComponent {
+    /// Config table name for this component (matches its module name).
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Breadcrumbs => "breadcrumbs",
+            Self::ExportModal => "export_modal",
+            Self::HelpStrip => "help_strip",
+            Self::Palette => "palette",
+            Self::Pills => "pills",
+            Self::Toast => "toast",
+            Self::Widgets => "widgets",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Breadcrumbs,
+            Self::ExportModal,
+            Self::HelpStrip,
+            Self::Palette,
+            Self::Pills,
+            Self::Toast,
+            Self::Widgets,
+        ]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|c| c.name() == name)
+    }
+}
+
+/// Parse a theme color: `#rrggbb`, a 0-255 ANSI index, or a named color such as
+/// `red`, `light-blue` or `reset`.
+pub fn parse_color(value: &str) -> Result<Color, String> {
+    value.trim().parse::<Color>().map_err(|_| {
+        format!("invalid color \"{value}\" (expected #rrggbb, an ANSI index 0-255, or a name like \"light-blue\")")
+    })
+}
+
+/// A user theme: an optional base preset plus slot overrides, globally and per component.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeOverrides {
+    pub base: Option<ThemePreset>,
+    pub slots: Vec<(&'static str, Color)>,
+    pub components: Vec<(ThemeComponent, Vec<(&'static str, Color)>)>,
+}
+
+impl ThemeOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.base.is_none() && self.slots.is_empty() && self.components.is_empty()
+    }
+
+    /// The palette the TUI draws with: the base preset (Dark by default) with global slots applied.
+    pub fn palette(&self) -> ThemePalette {
+        let mut palette = self.base.unwrap_or_default().to_palette();
+        for (slot, color) in &self.slots {
+            palette.set_slot(slot, *color);
+        }
+        palette
+    }
+
+    /// `palette` with the slots configured for `component` applied on top.
+    pub fn component(&self, component: ThemeComponent, mut palette: ThemePalette) -> ThemePalette {
+        if let Some((_, slots)) = self.components.iter().find(|(c, _)| *c == component) {
+            for (slot, color) in slots {
+                palette.set_slot(slot, *color);
+            }
+        }
+        palette
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1309,4 +1474,69 @@ mod tests {
         assert_ne!(pane.bg, Color::Reset);
         assert_ne!(pane.accent, Color::Reset);
     }
+
+    // ==================== User theme tests ====================
+
+    #[test]
+    fn test_theme_slots_round_trip() {
+        let mut palette = ThemePalette::dark();
+        for slot in THEME_SLOTS {
+            assert!(palette.slot(slot).is_some(), "slot {slot} readable");
+            assert!(palette.set_slot(slot, Color::Rgb(1, 2, 3)));
+            assert_eq!(palette.slot(slot), Some(Color::Rgb(1, 2, 3)));
+        }
+        assert!(!palette.set_slot("nope", Color::Red));
+        assert_eq!(palette.slot("nope"), None);
+    }
+
+    #[test]
+    fn test_theme_preset_from_name() {
+        assert_eq!(ThemePreset::from_name("nord"), Some(ThemePreset::Nord));
+        assert_eq!(
+            ThemePreset::from_name("High_Contrast"),
+            Some(ThemePreset::HighContrast)
+        );
+        assert_eq!(
+            ThemePreset::from_name("high-contrast"),
+            Some(ThemePreset::HighContrast)
+        );
+        assert_eq!(ThemePreset::from_name("solarized"), None);
+    }
+
+    #[test]
+    fn test_parse_color_forms() {
+        assert_eq!(parse_color("#ff8000"), Ok(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse_color("red"), Ok(Color::Red));
+        assert_eq!(parse_color("42"), Ok(Color::Indexed(42)));
+        assert!(parse_color("#zzzzzz").unwrap_err().contains("#zzzzzz"));
+    }
+
+    #[test]
+    fn test_theme_overrides_layering() {
+        let overrides = ThemeOverrides {
+            base: Some(ThemePreset::HighContrast),
+            slots: vec![("accent", Color::Rgb(9, 9, 9))],
+            components: vec![(ThemeComponent::Toast, vec![("surface", Color::Red)])],
+        };
+        let palette = overrides.palette();
+        assert_eq!(palette.accent, Color::Rgb(9, 9, 9));
+        assert_eq!(palette.bg, ThemePalette::high_contrast().bg);
+
+        let toast = overrides.component(ThemeComponent::Toast, palette);
+        assert_eq!(toast.surface, Color::Red);
+        assert_eq!(toast.accent, Color::Rgb(9, 9, 9));
+        let pills = overrides.component(ThemeComponent::Pills, palette);
+        assert_eq!(pills.surface, palette.surface);
+    }
+
+    #[test]
+    fn test_theme_component_names_match_modules() {
+        for component in ThemeComponent::all() {
+            assert_eq!(
+                ThemeComponent::from_name(component.name()),
+                Some(*component)
+            );
+        }
+        assert_eq!(ThemeComponent::all().len(), 7);
+    }
 }
@@ -0,0 +1,411 @@
+//! TUI config file: key bindings and theme overrides.
+//!
+//! Loaded from `tui.toml` in the cass config dir (`$XDG_CONFIG_HOME/cass/tui.toml`,
+//! or `CASS_TUI_CONFIG` to point elsewhere). Every problem is reported at startup:
+//!
+//! ```toml
+//! [keys]
+//! quit = ["ctrl+q", "esc"]          # replaces the default chords for this action
+//! move_down = ["down", "ctrl+n"]
+//! palette.filter_today = "alt+t"    # any command palette action can be bound
+//!
+//! [theme]
+//! preset = "high-contrast"          # dark, light, catppuccin, dracula, nord, high-contrast
+//! accent = "#00bfff"                # global slot overrides (ThemePalette fields)
+//!
+//! [theme.toast]                     # per-component slots (one table per ui::components module)
+//! surface = "black"
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::ui::components::theme::{
+    THEME_SLOTS, ThemeComponent, ThemeOverrides, ThemePreset, parse_color,
+};
+use crate::ui::keymap::{KeyChord, Keymap, PALETTE_PREFIX, Target, is_default_chord};
+
+pub const CONFIG_FILE_NAME: &str = "tui.toml";
+
+/// Resolve the TUI config path. `CASS_TUI_CONFIG` wins, then XDG, then the platform config dir.
+pub fn config_path() -> Option<PathBuf> {
+    if let Ok(path) = dotenvy::var("CASS_TUI_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    if let Ok(xdg_config) = dotenvy::var("XDG_CONFIG_HOME") {
+        return Some(
+            PathBuf::from(xdg_config)
+                .join("cass")
+                .join(CONFIG_FILE_NAME),
+        );
+    }
+    dirs::config_dir().map(|p| p.join("cass").join(CONFIG_FILE_NAME))
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TuiConfig {
+    pub keymap: Keymap,
+    pub theme: ThemeOverrides,
+    /// File the config was read from; `None` when running on built-in defaults.
+    pub source: Option<PathBuf>,
+}
+
+impl TuiConfig {
+    /// Load and validate the config file. A missing default file means built-in defaults;
+    /// a missing file named by `CASS_TUI_CONFIG` is an error.
+    pub fn load() -> Result<Self> {
+        let explicit = dotenvy::var("CASS_TUI_CONFIG").is_ok();
+        match config_path() {
+            Some(path) if explicit || path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading TUI config {}", path.display()))?;
+        let mut config = Self::parse(&text).map_err(|errors| {
+            anyhow!(
+                "invalid TUI config {}:\n  - {}",
+                path.display(),
+                errors.join("\n  - ")
+            )
+        })?;
+        config.source = Some(path.to_path_buf());
+        Ok(config)
+    }
+
+    /// Parse config text, collecting every validation error instead of stopping at the first.
+    pub fn parse(text: &str) -> Result<Self, Vec<String>> {
+        let table: toml::Table =
+            toml::from_str(text).map_err(|e| vec![e.to_string().trim_end().to_string()])?;
+        let mut errors = Vec::new();
+        let mut config = Self::default();
+        for (key, value) in &table {
+            match (key.as_str(), value.as_table()) {
+                ("keys", Some(keys)) => config.keymap = parse_keys(keys, &mut errors),
+                ("theme", Some(theme)) => config.theme = parse_theme(theme, &mut errors),
+                ("keys" | "theme", None) => {
+                    errors.push(format!("{key}: expected a table ([{key}])"));
+                }
+                (other, _) => {
+                    errors.push(format!(
+                        "{other}: unknown section (expected [keys] or [theme])"
+                    ));
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Effective bindings as pasteable TOML, headed by where they came from.
+    pub fn keymap_report(&self) -> String {
+        let origin = match (&self.source, config_path()) {
+            (Some(path), _) => path.display().to_string(),
+            (None, Some(path)) => format!("built-in defaults ({} not found)", path.display()),
+            (None, None) => "built-in defaults".to_string(),
+        };
+        format!(
+            "# Effective cass TUI key bindings\n# Source: {origin}\n\n{}",
+            self.keymap.to_toml()
+        )
+    }
+}
+
+fn parse_keys(table: &toml::Table, errors: &mut Vec<String>) -> Keymap {
+    let mut overrides: Vec<(Target, Vec<KeyChord>)> = Vec::new();
+    let mut claimed: HashMap<KeyChord, String> = HashMap::new();
+    for (name, value) in table {
+        if name == PALETTE_PREFIX.trim_end_matches('.')
+            && let Some(palette) = value.as_table()
+        {
+            for (action, value) in palette {
+                let id = format!("{PALETTE_PREFIX}{action}");
+                parse_binding(&id, value, &mut overrides, &mut claimed, errors);
+            }
+            continue;
+        }
+        parse_binding(name, value, &mut overrides, &mut claimed, errors);
+    }
+    Keymap::with_overrides(overrides)
+}
+
+fn parse_binding(
+    id: &str,
+    value: &toml::Value,
+    overrides: &mut Vec<(Target, Vec<KeyChord>)>,
+    claimed: &mut HashMap<KeyChord, String>,
+    errors: &mut Vec<String>,
+) {
+    let path = format!("keys.{id}");
+    let Some(target) = Target::from_id(id) else {
+        errors.push(format!(
+            "{path}: unknown action \"{id}\" (run `cass tui --print-keymap` to list actions)"
+        ));
+        return;
+    };
+    let specs: Vec<&str> = match value {
+        toml::Value::String(spec) => vec![spec.as_str()],
+        toml::Value::Array(items) if items.iter().all(toml::Value::is_str) => {
+            items.iter().filter_map(toml::Value::as_str).collect()
+        }
+        _ => {
+            errors.push(format!(
+                "{path}: expected a key string or a list of key strings"
+            ));
+            return;
+        }
+    };
+
+    let mut chords = Vec::new();
+    for spec in specs {
+        let chord = match KeyChord::parse(spec) {
+            Ok(chord) => chord,
+            Err(e) => {
+                errors.push(format!("{path}: {e}"));
+                continue;
+            }
+        };
+        if chord.is_text_input() && !is_default_chord(chord) {
+            errors.push(format!(
+                "{path}: \"{spec}\" would type into the search box; add a modifier such as ctrl or alt"
+            ));
+            continue;
+        }
+        match claimed.get(&chord) {
+            Some(owner) if owner != id => {
+                errors.push(format!("{path}: \"{spec}\" is already bound to {owner}"));
+                continue;
+            }
+            Some(_) => {}
+            None => {
+                claimed.insert(chord, id.to_string());
+            }
+        }
+        if !chords.contains(&chord) {
+            chords.push(chord);
+        }
+    }
+    overrides.push((target, chords));
+}
+
+fn parse_theme(table: &toml::Table, errors: &mut Vec<String>) -> ThemeOverrides {
+    let mut theme = ThemeOverrides::default();
+    for (key, value) in table {
+        let path = format!("theme.{key}");
+        if key == "preset" {
+            match value.as_str().and_then(ThemePreset::from_name) {
+                Some(preset) => theme.base = Some(preset),
+                None => {
+                    let given = value
+                        .as_str()
+                        .map_or_else(|| value.type_str().to_string(), |s| format!("\"{s}\""));
+                    let names: Vec<String> = ThemePreset::all()
+                        .iter()
+                        .map(|p| p.name().to_ascii_lowercase().replace(' ', "-"))
+                        .collect();
+                    errors.push(format!(
+                        "{path}: unknown preset {given} (expected one of {})",
+                        names.join(", ")
+                    ));
+                }
+            }
+        } else if let Some(component) = ThemeComponent::from_name(key) {
+            match value.as_table() {
+                Some(slots) => {
+                    let slots = parse_slots(&path, slots, errors);
+                    theme.components.push((component, slots));
+                }
+                None => errors.push(format!(
+                    "{path}: expected a table of color slots ([{path}])"
+                )),
+            }
+        } else if let Some(slot) = THEME_SLOTS.iter().find(|slot| **slot == key.as_str()) {
+            if let Some(color) = parse_slot_color(&path, value, errors) {
+                theme.slots.push((*slot, color));
+            }
+        } else {
+            let components: Vec<&str> = ThemeComponent::all().iter().map(|c| c.name()).collect();
+            errors.push(format!(
+                "{path}: unknown color slot or component (slots: {}; components: {})",
+                THEME_SLOTS.join(", "),
+                components.join(", ")
+            ));
+        }
+    }
+    theme
+}
+
+fn parse_slots(
+    prefix: &str,
+    table: &toml::Table,
+    errors: &mut Vec<String>,
+) -> Vec<(&'static str, ratatui::style::Color)> {
+    let mut slots = Vec::new();
+    for (key, value) in table {
+        let path = format!("{prefix}.{key}");
+        match THEME_SLOTS.iter().find(|slot| **slot == key.as_str()) {
+            Some(slot) => {
+                if let Some(color) = parse_slot_color(&path, value, errors) {
+                    slots.push((*slot, color));
+                }
+            }
+            None => errors.push(format!(
+                "{path}: unknown color slot (expected one of {})",
+                THEME_SLOTS.join(", ")
+            )),
+        }
+    }
+    slots
+}
+
+fn parse_slot_color(
+    path: &str,
+    value: &toml::Value,
+    errors: &mut Vec<String>,
+) -> Option<ratatui::style::Color> {
+    let Some(text) = value.as_str() else {
+        errors.push(format!("{path}: expected a color string"));
+        return None;
+    };
+    parse_color(text)
+        .map_err(|e| errors.push(format!("{path}: {e}")))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::components::palette::PaletteAction;
+    use crate::ui::keymap::Resolved;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::style::Color;
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = TuiConfig::parse("").unwrap();
+        assert!(config.theme.is_empty());
+        assert_eq!(
+            config
+                .keymap
+                .resolve(&KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE)),
+            Resolved::Unchanged
+        );
+    }
+
+    #[test]
+    fn parses_keys_and_theme() {
+        let config = TuiConfig::parse(
+            r##"
+[keys]
+quit = ["ctrl+q"]
+move_down = ["down", "ctrl+n"]
+palette.filter_today = "alt+t"
+
+[theme]
+preset = "high-contrast"
+accent = "#010203"
+
+[theme.toast]
+surface = "black"
+"##,
+        )
+        .unwrap();
+        assert_eq!(
+            config
+                .keymap
+                .resolve(&KeyEvent::new(KeyCode::Char('t'), KeyModifiers::ALT)),
+            Resolved::Palette(PaletteAction::FilterToday)
+        );
+        assert_eq!(
+            config
+                .keymap
+                .resolve(&KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)),
+            Resolved::Key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE))
+        );
+        assert_eq!(config.theme.base, Some(ThemePreset::HighContrast));
+        assert_eq!(config.theme.palette().accent, Color::Rgb(1, 2, 3));
+        let toast = config
+            .theme
+            .component(ThemeComponent::Toast, config.theme.palette());
+        assert_eq!(toast.surface, Color::Black);
+    }
+
+    #[test]
+    fn reports_every_error_with_its_path() {
+        let errors = TuiConfig::parse(
+            r##"
+[keys]
+qiut = "ctrl+q"
+help = "hyper+h"
+copy = "x"
+toggle_select = "ctrl+y"
+palette.filter_week = 3
+
+[theme]
+preset = "solarized"
+acent = "#fff"
+fg = "#zzzzzz"
+
+[theme.toast]
+glow = "red"
+
+[extras]
+"##,
+        )
+        .unwrap_err();
+        let joined = errors.join("\n");
+        for needle in [
+            "keys.qiut: unknown action",
+            "keys.help: unknown modifier \"hyper\"",
+            "keys.copy: \"x\" would type into the search box",
+            "keys.palette.filter_week: expected a key string",
+            "theme.preset: unknown preset",
+            "theme.acent: unknown color slot or component",
+            "theme.fg: invalid color",
+            "theme.toast.glow: unknown color slot",
+            "extras: unknown section",
+        ] {
+            assert!(joined.contains(needle), "missing {needle:?} in:\n{joined}");
+        }
+        // toggle_select may claim Ctrl+Y: user bindings override defaults.
+        assert!(!joined.contains("keys.toggle_select"));
+    }
+
+    #[test]
+    fn rejects_chord_bound_twice() {
+        let errors = TuiConfig::parse(
+            r#"
+[keys]
+copy = "ctrl+k"
+select_all = "ctrl+k"
+"#,
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("already bound to copy"), "{errors:?}");
+    }
+
+    #[test]
+    fn reports_toml_syntax_errors() {
+        let errors = TuiConfig::parse("[keys\nquit = ").unwrap_err();
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn load_from_names_the_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, "[keys]\nnope = \"ctrl+n\"\n").unwrap();
+        let err = TuiConfig::load_from(&path).unwrap_err().to_string();
+        assert!(err.contains("invalid TUI config"));
+        assert!(err.contains(&path.display().to_string()));
+        assert!(err.contains("keys.nope"));
+    }
+}
@@ -34,6 +34,8 @@ pub struct ConversationView {
     pub convo: Conversation,
     pub messages: Vec<Message>,
     pub workspace: Option<Workspace>,
+    /// User-assigned session tags (`cass tag`), sorted by name
+    pub tags: Vec<String>,
 }
 
 // -------------------------------------------------------------------------
@@ -237,10 +239,12 @@ fn load_conversation_uncached(
             display_name: row.get(4).ok().flatten(),
         });
         let messages = storage.fetch_messages(convo_id)?;
+        let tags = storage.session_tags(source_path)?;
         return Ok(Some(ConversationView {
             convo,
             messages,
             workspace,
+            tags,
         }));
     }
     Ok(None)
@@ -377,6 +381,7 @@ mod tests {
                 path: PathBuf::from("/test/workspace"),
                 display_name: None,
             }),
+            tags: Vec::new(),
         }
     }
 
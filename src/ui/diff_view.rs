@@ -0,0 +1,327 @@
+//! Side-by-side terminal view for `cass diff`.
+//!
+//! Shows the two session summaries on top and the aligned message rows below.
+//! `j`/`k` scroll, `n`/`N` jump between differences, `q` or `Esc` quits.
+
+use std::io;
+
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use ratatui::prelude::*;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+
+use crate::diff::{AlignKind, MessageRef, SessionDiff, SessionSummary, format_duration};
+use crate::ui::components::theme::{ThemePalette, colors};
+
+/// Run the interactive view until the user quits.
+pub fn run_diff_view(diff: &SessionDiff) -> Result<()> {
+    let mut stdout = io::stdout();
+    enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen)?;
+
+    // Restore the terminal on exit or panic
+    struct ViewGuard;
+    impl Drop for ViewGuard {
+        fn drop(&mut self) {
+            let _ = disable_raw_mode();
+            let mut stdout = io::stdout();
+            let _ = execute!(stdout, LeaveAlternateScreen, crossterm::cursor::Show);
+        }
+    }
+    let _guard = ViewGuard;
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+    let mut state = ListState::default();
+    state.select(
+        (!diff.alignment.is_empty()).then_some(diff.divergence.as_ref().map_or(0, |d| d.row)),
+    );
+
+    loop {
+        terminal.draw(|frame| draw(frame, diff, &mut state))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let page = terminal.size()?.height.saturating_sub(12).max(1) as usize;
+        let last = diff.alignment.len().saturating_sub(1);
+        let current = state.selected().unwrap_or(0);
+        let next = match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+            KeyCode::Down | KeyCode::Char('j') => current.saturating_add(1).min(last),
+            KeyCode::Up | KeyCode::Char('k') => current.saturating_sub(1),
+            KeyCode::PageDown | KeyCode::Char(' ') => current.saturating_add(page).min(last),
+            KeyCode::PageUp => current.saturating_sub(page),
+            KeyCode::Home | KeyCode::Char('g') => 0,
+            KeyCode::End | KeyCode::Char('G') => last,
+            KeyCode::Char('n') => next_difference(diff, current, true).unwrap_or(current),
+            KeyCode::Char('N') => next_difference(diff, current, false).unwrap_or(current),
+            _ => current,
+        };
+        state.select(Some(next));
+    }
+    Ok(())
+}
+
+/// Row of the next (or previous) difference block after `from`.
+fn next_difference(diff: &SessionDiff, from: usize, forward: bool) -> Option<usize> {
+    let rows = &diff.alignment;
+    let starts_block = |i: usize| {
+        rows[i].kind != AlignKind::Same && (i == 0 || rows[i - 1].kind == AlignKind::Same)
+    };
+    if forward {
+        (from + 1..rows.len()).find(|&i| starts_block(i))
+    } else {
+        (0..from).rev().find(|&i| starts_block(i))
+    }
+}
+
+fn draw(frame: &mut Frame, diff: &SessionDiff, state: &mut ListState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(8),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .split(frame.area());
+
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(layout[0]);
+    frame.render_widget(
+        summary(&diff.a, "A", &diff.files.only_a, &diff.tools.only_a),
+        halves[0],
+    );
+    frame.render_widget(
+        summary(&diff.b, "B", &diff.files.only_b, &diff.tools.only_b),
+        halves[1],
+    );
+
+    let body = layout[1];
+    let col = (body.width.saturating_sub(8) / 2) as usize;
+    let items: Vec<ListItem> = diff
+        .alignment
+        .iter()
+        .enumerate()
+        .map(|(row, pair)| {
+            let (marker, color) = match pair.kind {
+                AlignKind::Same => (' ', colors::TEXT_SECONDARY),
+                AlignKind::Changed => ('~', colors::STATUS_WARNING),
+                AlignKind::OnlyA => ('-', colors::STATUS_ERROR),
+                AlignKind::OnlyB => ('+', colors::STATUS_SUCCESS),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{marker}{:>4} ", row + 1),
+                    Style::default().fg(color),
+                ),
+                cell(pair.a.as_ref(), col, color),
+                Span::styled("│", Style::default().fg(colors::BORDER)),
+                cell(pair.b.as_ref(), col, color),
+            ]))
+        })
+        .collect();
+
+    let title = match &diff.divergence {
+        None => " Messages — identical ".to_string(),
+        Some(d) => format!(
+            " Messages — {} shared, diverge at row {} ",
+            diff.common_prefix,
+            d.row + 1
+        ),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(colors::BORDER))
+                .title(title),
+        )
+        .highlight_style(Style::default().bg(colors::BG_HIGHLIGHT));
+    frame.render_stateful_widget(list, body, state);
+
+    let help = Paragraph::new(Line::from(Span::styled(
+        " j/k scroll · n/N next/prev difference · g/G top/bottom · q quit",
+        Style::default().fg(colors::TEXT_MUTED),
+    )));
+    frame.render_widget(help, layout[2]);
+}
+
+/// One side of a message row, padded to `width` columns.
+fn cell(msg: Option<&MessageRef>, width: usize, color: Color) -> Span<'static> {
+    let text = msg.map_or(String::new(), |m| format!("{}: {}", m.role, m.preview));
+    let mut clipped: String = text.chars().take(width).collect();
+    let pad = width.saturating_sub(clipped.chars().count());
+    clipped.push_str(&" ".repeat(pad));
+    let fg = if msg.is_some() {
+        color
+    } else {
+        colors::TEXT_DISABLED
+    };
+    Span::styled(clipped, Style::default().fg(fg))
+}
+
+fn summary<'a>(
+    s: &'a SessionSummary,
+    label: &str,
+    unique_files: &[String],
+    unique_tools: &[String],
+) -> Paragraph<'a> {
+    let key = |k: &str| Span::styled(format!("{k:<9}"), Style::default().fg(colors::TEXT_MUTED));
+    let tokens = if s.tokens_estimated {
+        format!("~{}", s.tokens)
+    } else {
+        s.tokens.to_string()
+    };
+    let lines = vec![
+        Line::from(vec![
+            key("Agent"),
+            Span::raw(format!(
+                "{} {}",
+                ThemePalette::agent_icon(&s.agent),
+                s.agent
+            )),
+        ]),
+        Line::from(vec![
+            key("Title"),
+            Span::raw(s.title.clone().unwrap_or_default()),
+        ]),
+        Line::from(vec![
+            key("Duration"),
+            Span::raw(
+                s.duration_ms
+                    .map_or_else(|| "-".to_string(), format_duration),
+            ),
+            Span::raw(format!("   {} messages   {tokens} tokens", s.messages)),
+        ]),
+        Line::from(vec![
+            key("Files"),
+            Span::raw(format!(
+                "{} touched, {} unique",
+                s.files_touched.len(),
+                unique_files.len()
+            )),
+        ]),
+        Line::from(vec![
+            key("Tools"),
+            Span::raw(if unique_tools.is_empty() {
+                "no unique tool calls".to_string()
+            } else {
+                format!("unique: {}", unique_tools.join(", "))
+            }),
+        ]),
+    ];
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(colors::BORDER))
+            .title(format!(" {label}: {} ", s.source_path)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{AlignedPair, Delta, Divergence, SetComparison};
+    use ratatui::backend::TestBackend;
+    use std::collections::BTreeMap;
+
+    fn side(path: &str) -> SessionSummary {
+        SessionSummary {
+            source_path: path.into(),
+            agent: "codex".into(),
+            title: Some("Fix flaky test".into()),
+            workspace: None,
+            started_at: Some(0),
+            ended_at: Some(90_000),
+            duration_ms: Some(90_000),
+            messages: 2,
+            tokens: 40,
+            tokens_estimated: true,
+            files_touched: Vec::new(),
+            tool_calls: BTreeMap::new(),
+        }
+    }
+
+    fn row(kind: AlignKind, a: Option<&str>, b: Option<&str>) -> AlignedPair {
+        let r = |p: &str| MessageRef {
+            idx: 0,
+            role: "user".into(),
+            preview: p.into(),
+        };
+        AlignedPair {
+            kind,
+            a: a.map(r),
+            b: b.map(r),
+        }
+    }
+
+    fn sample() -> SessionDiff {
+        SessionDiff {
+            a: side("/a.jsonl"),
+            b: side("/b.jsonl"),
+            common_prefix: 1,
+            divergence: Some(Divergence {
+                row: 1,
+                a_idx: Some(1),
+                b_idx: None,
+            }),
+            identical: false,
+            delta: Delta {
+                messages: 0,
+                tokens: 0,
+                duration_ms: Some(0),
+            },
+            files: SetComparison::default(),
+            tools: SetComparison {
+                common: Vec::new(),
+                only_a: vec!["Bash".into()],
+                only_b: Vec::new(),
+            },
+            alignment: vec![
+                row(AlignKind::Same, Some("start"), Some("start")),
+                row(AlignKind::OnlyA, Some("retry"), None),
+                row(AlignKind::Same, Some("done"), Some("done")),
+                row(AlignKind::OnlyB, None, Some("extra")),
+            ],
+        }
+    }
+
+    #[test]
+    fn next_difference_jumps_between_blocks() {
+        let diff = sample();
+        assert_eq!(next_difference(&diff, 0, true), Some(1));
+        assert_eq!(next_difference(&diff, 1, true), Some(3));
+        assert_eq!(next_difference(&diff, 3, true), None);
+        assert_eq!(next_difference(&diff, 3, false), Some(1));
+    }
+
+    #[test]
+    fn draw_renders_summaries_and_rows() {
+        let diff = sample();
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        let mut state = ListState::default();
+        state.select(Some(1));
+        terminal.draw(|f| draw(f, &diff, &mut state)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = (0..buffer.area.height)
+            .flat_map(|y| (0..buffer.area.width).map(move |x| (x, y)))
+            .map(|pos| buffer.cell(pos).unwrap().symbol().to_string())
+            .collect();
+        assert!(text.contains("A: /a.jsonl"));
+        assert!(text.contains("unique: Bash"));
+        assert!(text.contains("diverge at row 2"));
+        assert!(text.contains("user: retry"));
+        assert!(text.contains("1m 30s"));
+    }
+}
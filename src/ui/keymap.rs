@@ -0,0 +1,824 @@
+//! Configurable key bindings for the main search view.
+//!
+//! The handlers in `tui.rs` match on the built-in chords directly. A [`Keymap`] sits in
+//! front of them: a user chord is rewritten into the default chord of the action it is
+//! bound to, a default chord that was moved elsewhere is swallowed, and palette actions
+//! bound to a chord are dispatched as if picked from the command palette.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::ui::components::palette::{PaletteAction, default_actions};
+
+/// Prefix for palette actions in keymap files (`palette.filter_today`).
+pub const PALETTE_PREFIX: &str = "palette.";
+
+/// A key plus modifiers, normalized so that equivalent terminal encodings compare equal.
+///
+/// Letters carry Shift in their case (`Shift+a` is stored as `A`), Shift is dropped for
+/// other printable characters, and digits keep it so `Shift+1` stays distinct from `!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+const NONE: KeyModifiers = KeyModifiers::NONE;
+const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+const ALT: KeyModifiers = KeyModifiers::ALT;
+const SHIFT: KeyModifiers = KeyModifiers::SHIFT;
+const CTRL_SHIFT: KeyModifiers = KeyModifiers::CONTROL.union(KeyModifiers::SHIFT);
+
+const fn chord(code: KeyCode, modifiers: KeyModifiers) -> KeyChord {
+    KeyChord { code, modifiers }
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers
+            & (KeyModifiers::CONTROL
+                | KeyModifiers::ALT
+                | KeyModifiers::SHIFT
+                | KeyModifiers::SUPER);
+        let code = match code {
+            KeyCode::Char(c) if c.is_ascii_digit() => code,
+            KeyCode::Char(c) => {
+                let shifted = modifiers.contains(KeyModifiers::SHIFT) || c.is_ascii_uppercase();
+                modifiers.remove(KeyModifiers::SHIFT);
+                if shifted && c.is_ascii_alphabetic() {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    code
+                }
+            }
+            KeyCode::BackTab => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                code
+            }
+            _ => code,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::new(key.code, key.modifiers)
+    }
+
+    /// Parse a chord such as `ctrl+shift+r`, `alt+j`, `f3`, `shift+f11`, `pagedown` or `?`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let trimmed = spec.trim();
+        if trimmed.is_empty() {
+            return Err("empty key binding".to_string());
+        }
+        let (mods, key) = if trimmed == "+" {
+            ("", "+")
+        } else if let Some(mods) = trimmed.strip_suffix("++") {
+            (mods, "+")
+        } else {
+            trimmed.rsplit_once('+').unwrap_or(("", trimmed))
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        if !mods.is_empty() {
+            for part in mods.split('+') {
+                modifiers |= match part.trim().to_ascii_lowercase().as_str() {
+                    "ctrl" | "control" => KeyModifiers::CONTROL,
+                    "alt" | "meta" | "option" => KeyModifiers::ALT,
+                    "shift" => KeyModifiers::SHIFT,
+                    "super" | "cmd" => KeyModifiers::SUPER,
+                    other => {
+                        return Err(format!(
+                            "unknown modifier \"{other}\" in \"{trimmed}\" (expected ctrl, alt, shift or super)"
+                        ));
+                    }
+                };
+            }
+        }
+
+        let code = parse_key_name(key.trim()).ok_or_else(|| {
+            format!(
+                "unknown key \"{key}\" in \"{trimmed}\" (expected a single character, f1-f24, or a name like esc, enter, tab, pageup)"
+            )
+        })?;
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// The key event the built-in handlers expect for this chord.
+    pub fn to_event(self) -> KeyEvent {
+        let mut modifiers = self.modifiers;
+        if matches!(self.code, KeyCode::Char(c) if c.is_ascii_uppercase()) {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+        KeyEvent::new(self.code, modifiers)
+    }
+
+    /// True for chords that would otherwise type into the search box.
+    pub fn is_text_input(self) -> bool {
+        matches!(self.code, KeyCode::Char(_))
+            && !self
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER)
+            && !self.modifiers.contains(KeyModifiers::SHIFT)
+    }
+}
+
+fn parse_key_name(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let lower = name.to_ascii_lowercase();
+    Some(match lower.as_str() {
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "space" => KeyCode::Char(' '),
+        _ => {
+            let n: u8 = lower.strip_prefix('f')?.parse().ok()?;
+            if !(1..=24).contains(&n) {
+                return None;
+            }
+            KeyCode::F(n)
+        }
+    })
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SUPER) {
+            f.write_str("Super+")?;
+        }
+        let uppercase = matches!(self.code, KeyCode::Char(c) if c.is_ascii_uppercase());
+        if self.modifiers.contains(KeyModifiers::SHIFT) || uppercase {
+            f.write_str("Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("BackTab"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::Insert => f.write_str("Insert"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PageUp"),
+            KeyCode::PageDown => f.write_str("PageDown"),
+            KeyCode::Up => f.write_str("Up"),
+            KeyCode::Down => f.write_str("Down"),
+            KeyCode::Left => f.write_str("Left"),
+            KeyCode::Right => f.write_str("Right"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// Main-view actions handled in the `InputMode::Query` branch of the event loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ViewAction {
+    Help,
+    ToggleTheme,
+    FilterAgent,
+    ScopeAgent,
+    FilterWorkspace,
+    ClearAgentScope,
+    FilterFrom,
+    CycleTimePreset,
+    FilterTo,
+    ContextWindow,
+    OpenInEditor,
+    MatchMode,
+    Quit,
+    SourceFilter,
+    SourceFilterMenu,
+    RankingMode,
+    ToggleBorders,
+    ToggleDensity,
+    ClearFilters,
+    ResetState,
+    ToggleFocus,
+    SearchMode,
+    Granularity,
+    CommandPalette,
+    HistoryCycle,
+    Refresh,
+    Copy,
+    ToggleSelect,
+    SelectAll,
+    QueueItem,
+    OpenQueued,
+    BulkActions,
+    MoveDown,
+    MoveUp,
+    PaneLeft,
+    PaneRight,
+    PageDown,
+    PageUp,
+    OpenDetail,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    ToggleSplit,
+    OpenReader,
+}
+
+impl ViewAction {
+    pub const ALL: &'static [Self] = &[
+        Self::Help,
+        Self::ToggleTheme,
+        Self::FilterAgent,
+        Self::ScopeAgent,
+        Self::FilterWorkspace,
+        Self::ClearAgentScope,
+        Self::FilterFrom,
+        Self::CycleTimePreset,
+        Self::FilterTo,
+        Self::ContextWindow,
+        Self::OpenInEditor,
+        Self::MatchMode,
+        Self::Quit,
+        Self::SourceFilter,
+        Self::SourceFilterMenu,
+        Self::RankingMode,
+        Self::ToggleBorders,
+        Self::ToggleDensity,
+        Self::ClearFilters,
+        Self::ResetState,
+        Self::ToggleFocus,
+        Self::SearchMode,
+        Self::Granularity,
+        Self::CommandPalette,
+        Self::HistoryCycle,
+        Self::Refresh,
+        Self::Copy,
+        Self::ToggleSelect,
+        Self::SelectAll,
+        Self::QueueItem,
+        Self::OpenQueued,
+        Self::BulkActions,
+        Self::MoveDown,
+        Self::MoveUp,
+        Self::PaneLeft,
+        Self::PaneRight,
+        Self::PageDown,
+        Self::PageUp,
+        Self::OpenDetail,
+        Self::NewTab,
+        Self::CloseTab,
+        Self::NextTab,
+        Self::PrevTab,
+        Self::ToggleSplit,
+        Self::OpenReader,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Help => "help",
+            Self::ToggleTheme => "toggle_theme",
+            Self::FilterAgent => "filter_agent",
+            Self::ScopeAgent => "scope_agent",
+            Self::FilterWorkspace => "filter_workspace",
+            Self::ClearAgentScope => "clear_agent_scope",
+            Self::FilterFrom => "filter_from",
+            Self::CycleTimePreset => "cycle_time_preset",
+            Self::FilterTo => "filter_to",
+            Self::ContextWindow => "context_window",
+            Self::OpenInEditor => "open_in_editor",
+            Self::MatchMode => "match_mode",
+            Self::Quit => "quit",
+            Self::SourceFilter => "source_filter",
+            Self::SourceFilterMenu => "source_filter_menu",
+            Self::RankingMode => "ranking_mode",
+            Self::ToggleBorders => "toggle_borders",
+            Self::ToggleDensity => "toggle_density",
+            Self::ClearFilters => "clear_filters",
+            Self::ResetState => "reset_state",
+            Self::ToggleFocus => "toggle_focus",
+            Self::SearchMode => "search_mode",
+            Self::Granularity => "granularity",
+            Self::CommandPalette => "command_palette",
+            Self::HistoryCycle => "history_cycle",
+            Self::Refresh => "refresh",
+            Self::Copy => "copy",
+            Self::ToggleSelect => "toggle_select",
+            Self::SelectAll => "select_all",
+            Self::QueueItem => "queue_item",
+            Self::OpenQueued => "open_queued",
+            Self::BulkActions => "bulk_actions",
+            Self::MoveDown => "move_down",
+            Self::MoveUp => "move_up",
+            Self::PaneLeft => "pane_left",
+            Self::PaneRight => "pane_right",
+            Self::PageDown => "page_down",
+            Self::PageUp => "page_up",
+            Self::OpenDetail => "open_detail",
+            Self::NewTab => "new_tab",
+            Self::CloseTab => "close_tab",
+            Self::NextTab => "next_tab",
+            Self::PrevTab => "prev_tab",
+            Self::ToggleSplit => "toggle_split",
+            Self::OpenReader => "open_reader",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Help => "Toggle help",
+            Self::ToggleTheme => "Toggle light/dark theme",
+            Self::FilterAgent => "Filter by agent",
+            Self::ScopeAgent => "Scope to the selected hit's agent",
+            Self::FilterWorkspace => "Filter by workspace",
+            Self::ClearAgentScope => "Scope: all agents",
+            Self::FilterFrom => "Filter from date",
+            Self::CycleTimePreset => "Cycle time presets (24h/7d/30d/all)",
+            Self::FilterTo => "Filter to date",
+            Self::ContextWindow => "Cycle context window",
+            Self::OpenInEditor => "Open hit in editor",
+            Self::MatchMode => "Toggle match mode",
+            Self::Quit => "Clear selection, leave detail, or quit",
+            Self::SourceFilter => "Cycle source filter",
+            Self::SourceFilterMenu => "Source filter menu",
+            Self::RankingMode => "Cycle ranking mode",
+            Self::ToggleBorders => "Toggle border style",
+            Self::ToggleDensity => "Cycle density",
+            Self::ClearFilters => "Clear all filters",
+            Self::ResetState => "Reset persisted UI state",
+            Self::ToggleFocus => "Toggle results/detail focus",
+            Self::SearchMode => "Cycle search mode",
+            Self::Granularity => "Toggle message/session results",
+            Self::CommandPalette => "Open command palette",
+            Self::HistoryCycle => "Cycle query history",
+            Self::Refresh => "Refresh search / re-index",
+            Self::Copy => "Copy path or content",
+            Self::ToggleSelect => "Toggle selection",
+            Self::SelectAll => "Select all in pane",
+            Self::QueueItem => "Queue item for multi-open",
+            Self::OpenQueued => "Open queued items",
+            Self::BulkActions => "Bulk actions menu",
+            Self::MoveDown => "Move down / scroll detail",
+            Self::MoveUp => "Move up / scroll detail",
+            Self::PaneLeft => "Previous pane",
+            Self::PaneRight => "Next pane / focus detail",
+            Self::PageDown => "Next page",
+            Self::PageUp => "Previous page",
+            Self::OpenDetail => "Open detail / run query",
+            Self::NewTab => "Open a new search tab",
+            Self::CloseTab => "Close the current search tab",
+            Self::NextTab => "Next search tab",
+            Self::PrevTab => "Previous search tab",
+            Self::ToggleSplit => "Pin conversation side by side / close split",
+            Self::OpenReader => "Read the full conversation",
+        }
+    }
+
+    /// Built-in chords; the first one is what the event loop handlers match on.
+    pub fn defaults(self) -> &'static [KeyChord] {
+        match self {
+            Self::Help => const { &[chord(KeyCode::F(1), NONE), chord(KeyCode::Char('?'), NONE)] },
+            Self::ToggleTheme => const { &[chord(KeyCode::F(2), NONE)] },
+            Self::FilterAgent => const { &[chord(KeyCode::F(3), NONE)] },
+            Self::ScopeAgent => const { &[chord(KeyCode::F(3), SHIFT)] },
+            Self::FilterWorkspace => const { &[chord(KeyCode::F(4), NONE)] },
+            Self::ClearAgentScope => const { &[chord(KeyCode::F(4), SHIFT)] },
+            Self::FilterFrom => const { &[chord(KeyCode::F(5), NONE)] },
+            Self::CycleTimePreset => const { &[chord(KeyCode::F(5), SHIFT)] },
+            Self::FilterTo => const { &[chord(KeyCode::F(6), NONE)] },
+            Self::ContextWindow => const { &[chord(KeyCode::F(7), NONE)] },
+            Self::OpenInEditor => const { &[chord(KeyCode::F(8), NONE)] },
+            Self::MatchMode => const { &[chord(KeyCode::F(9), NONE)] },
+            Self::Quit => const { &[chord(KeyCode::Esc, NONE), chord(KeyCode::F(10), NONE)] },
+            Self::SourceFilter => const { &[chord(KeyCode::F(11), NONE)] },
+            Self::SourceFilterMenu => const { &[chord(KeyCode::F(11), SHIFT)] },
+            Self::RankingMode => const { &[chord(KeyCode::F(12), NONE)] },
+            Self::ToggleBorders => const { &[chord(KeyCode::Char('b'), CTRL)] },
+            Self::ToggleDensity => const { &[chord(KeyCode::Char('d'), CTRL)] },
+            Self::ClearFilters => const { &[chord(KeyCode::Delete, CTRL)] },
+            Self::ResetState => const { &[chord(KeyCode::Delete, CTRL_SHIFT)] },
+            Self::ToggleFocus => const { &[chord(KeyCode::Tab, NONE)] },
+            Self::SearchMode => const { &[chord(KeyCode::Char('s'), ALT)] },
+            Self::Granularity => const { &[chord(KeyCode::Char('g'), ALT)] },
+            Self::CommandPalette => {
+                const {
+                    &[
+                        chord(KeyCode::Char('p'), CTRL),
+                        chord(KeyCode::Char('p'), ALT),
+                    ]
+                }
+            }
+            Self::HistoryCycle => const { &[chord(KeyCode::Char('r'), CTRL)] },
+            Self::Refresh => const { &[chord(KeyCode::Char('R'), CTRL)] },
+            Self::Copy => const { &[chord(KeyCode::Char('y'), CTRL)] },
+            Self::ToggleSelect => const { &[chord(KeyCode::Char('x'), CTRL)] },
+            Self::SelectAll => const { &[chord(KeyCode::Char('a'), CTRL)] },
+            Self::QueueItem => const { &[chord(KeyCode::Enter, CTRL)] },
+            Self::OpenQueued => const { &[chord(KeyCode::Char('o'), CTRL)] },
+            Self::BulkActions => const { &[chord(KeyCode::Char('A'), NONE)] },
+            Self::MoveDown => {
+                const { &[chord(KeyCode::Down, NONE), chord(KeyCode::Char('j'), ALT)] }
+            }
+            Self::MoveUp => const { &[chord(KeyCode::Up, NONE), chord(KeyCode::Char('k'), ALT)] },
+            Self::PaneLeft => {
+                const { &[chord(KeyCode::Left, NONE), chord(KeyCode::Char('h'), ALT)] }
+            }
+            Self::PaneRight => {
+                const { &[chord(KeyCode::Right, NONE), chord(KeyCode::Char('l'), ALT)] }
+            }
+            Self::PageDown => const { &[chord(KeyCode::PageDown, NONE)] },
+            Self::PageUp => const { &[chord(KeyCode::PageUp, NONE)] },
+            Self::OpenDetail => const { &[chord(KeyCode::Enter, NONE)] },
+            Self::NewTab => const { &[chord(KeyCode::Char('t'), CTRL)] },
+            Self::CloseTab => const { &[chord(KeyCode::Char('w'), CTRL)] },
+            Self::NextTab => const { &[chord(KeyCode::PageDown, CTRL)] },
+            Self::PrevTab => const { &[chord(KeyCode::PageUp, CTRL)] },
+            Self::ToggleSplit => const { &[chord(KeyCode::Char('v'), ALT)] },
+            Self::OpenReader => const { &[chord(KeyCode::Char('r'), ALT)] },
+        }
+    }
+}
+
+/// Something a chord can be bound to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    View(ViewAction),
+    Palette(PaletteAction),
+}
+
+impl Target {
+    /// Look up a target by its keymap id (`quit`, `palette.filter_today`, ...).
+    pub fn from_id(id: &str) -> Option<Self> {
+        if let Some(palette_id) = id.strip_prefix(PALETTE_PREFIX) {
+            return default_actions()
+                .into_iter()
+                .find(|item| item.action.id() == palette_id)
+                .map(|item| Self::Palette(item.action));
+        }
+        ViewAction::ALL
+            .iter()
+            .find(|action| action.id() == id)
+            .map(|action| Self::View(*action))
+    }
+
+    pub fn id(&self) -> String {
+        match self {
+            Self::View(action) => action.id().to_string(),
+            Self::Palette(action) => format!("{PALETTE_PREFIX}{}", action.id()),
+        }
+    }
+
+    /// Built-in chords. Palette actions only have defaults where the main view
+    /// already handles them (saved-view slots).
+    pub fn defaults(&self) -> Vec<KeyChord> {
+        match self {
+            Self::View(action) => action.defaults().to_vec(),
+            Self::Palette(PaletteAction::SaveViewSlot(slot)) => {
+                vec![chord(slot_key(*slot), CTRL)]
+            }
+            Self::Palette(PaletteAction::LoadViewSlot(slot)) => {
+                vec![chord(slot_key(*slot), SHIFT)]
+            }
+            Self::Palette(_) => Vec::new(),
+        }
+    }
+}
+
+fn slot_key(slot: u8) -> KeyCode {
+    KeyCode::Char(char::from(b'0' + slot.min(9)))
+}
+
+/// True if `chord` is one of the built-in bindings.
+pub fn is_default_chord(chord: KeyChord) -> bool {
+    Keymap::default().lookup.contains_key(&chord)
+}
+
+/// One row of the effective keymap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding {
+    pub target: Target,
+    pub description: String,
+    pub chords: Vec<KeyChord>,
+}
+
+/// What the event loop should do with a key press.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    /// Not remapped; handle the key as-is.
+    Unchanged,
+    /// Handle this (built-in) key event instead.
+    Key(KeyEvent),
+    /// Run a command palette action directly.
+    Palette(PaletteAction),
+    /// A built-in chord that the user moved to another key; ignore it.
+    Unbound,
+}
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    lookup: HashMap<KeyChord, usize>,
+    released: HashSet<KeyChord>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings: Vec<Binding> = ViewAction::ALL
+            .iter()
+            .map(|action| Binding {
+                target: Target::View(*action),
+                description: action.description().to_string(),
+                chords: action.defaults().to_vec(),
+            })
+            .collect();
+        bindings.extend(default_actions().into_iter().map(|item| {
+            let target = Target::Palette(item.action);
+            Binding {
+                chords: target.defaults(),
+                target,
+                description: item.label,
+            }
+        }));
+        let mut keymap = Self {
+            bindings,
+            lookup: HashMap::new(),
+            released: HashSet::new(),
+        };
+        keymap.reindex();
+        keymap
+    }
+}
+
+impl Keymap {
+    /// Defaults with each listed target rebound to exactly the given chords. Chords claimed
+    /// by an override are removed from whichever built-in action held them.
+    pub fn with_overrides(overrides: Vec<(Target, Vec<KeyChord>)>) -> Self {
+        let mut keymap = Self::default();
+        let claimed: HashSet<KeyChord> = overrides
+            .iter()
+            .flat_map(|(_, chords)| chords.iter().copied())
+            .collect();
+        for binding in &mut keymap.bindings {
+            binding.chords.retain(|chord| !claimed.contains(chord));
+        }
+        for (target, chords) in overrides {
+            if let Some(binding) = keymap.bindings.iter_mut().find(|b| b.target == target) {
+                binding.chords = chords;
+            }
+        }
+        keymap.reindex();
+        keymap
+    }
+
+    fn reindex(&mut self) {
+        self.lookup.clear();
+        for (idx, binding) in self.bindings.iter().enumerate() {
+            for chord in &binding.chords {
+                self.lookup.entry(*chord).or_insert(idx);
+            }
+        }
+        self.released = self
+            .bindings
+            .iter()
+            .flat_map(|binding| binding.target.defaults())
+            .filter(|chord| !self.lookup.contains_key(chord))
+            .collect();
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    pub fn resolve(&self, key: &KeyEvent) -> Resolved {
+        let chord = KeyChord::from_event(key);
+        let Some(&idx) = self.lookup.get(&chord) else {
+            return if self.released.contains(&chord) {
+                Resolved::Unbound
+            } else {
+                Resolved::Unchanged
+            };
+        };
+        let target = &self.bindings[idx].target;
+        let defaults = target.defaults();
+        if defaults.contains(&chord) {
+            return Resolved::Unchanged;
+        }
+        match target {
+            Target::View(action) => Resolved::Key(action.defaults()[0].to_event()),
+            Target::Palette(action) => Resolved::Palette(action.clone()),
+        }
+    }
+
+    /// Render the effective bindings as a `[keys]` table that can be pasted into `tui.toml`.
+    pub fn to_toml(&self) -> String {
+        let rows: Vec<(String, &str)> = self
+            .bindings
+            .iter()
+            .map(|binding| {
+                let chords: Vec<String> = binding
+                    .chords
+                    .iter()
+                    .map(|chord| {
+                        let text = chord.to_string().replace('\\', "\\\\").replace('"', "\\\"");
+                        format!("\"{text}\"")
+                    })
+                    .collect();
+                (
+                    format!("{} = [{}]", binding.target.id(), chords.join(", ")),
+                    binding.description.as_str(),
+                )
+            })
+            .collect();
+        let width = rows.iter().map(|(line, _)| line.len()).max().unwrap_or(0);
+        let mut out = String::from("[keys]\n");
+        for (line, description) in rows {
+            out.push_str(&format!("{line:<width$}  # {description}\n"));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(spec: &str) -> KeyChord {
+        KeyChord::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn parse_normalizes_shift_and_case() {
+        assert_eq!(parse("ctrl+shift+r"), parse("Ctrl+R"));
+        assert_eq!(parse("shift+a"), chord(KeyCode::Char('A'), NONE));
+        assert_eq!(parse("alt+j"), chord(KeyCode::Char('j'), ALT));
+        assert_eq!(parse("shift+f3"), chord(KeyCode::F(3), SHIFT));
+        assert_eq!(parse("shift+1"), chord(KeyCode::Char('1'), SHIFT));
+        assert_eq!(parse("ctrl++"), chord(KeyCode::Char('+'), CTRL));
+        assert_eq!(parse("PgDn"), chord(KeyCode::PageDown, NONE));
+        assert_eq!(parse("space"), chord(KeyCode::Char(' '), NONE));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_parts() {
+        assert!(KeyChord::parse("hyper+x").unwrap_err().contains("hyper"));
+        assert!(KeyChord::parse("ctrl+nope").unwrap_err().contains("nope"));
+        assert!(KeyChord::parse("f25").is_err());
+        assert!(KeyChord::parse("ctrl+").is_err());
+        assert!(KeyChord::parse("  ").is_err());
+    }
+
+    #[test]
+    fn display_round_trips_through_parse() {
+        for binding in Keymap::default().bindings() {
+            for chord in &binding.chords {
+                assert_eq!(parse(&chord.to_string()), *chord, "{chord}");
+            }
+        }
+    }
+
+    #[test]
+    fn defaults_are_normalized_and_unique() {
+        let mut seen = HashSet::new();
+        for binding in Keymap::default().bindings() {
+            for chord in &binding.chords {
+                assert_eq!(KeyChord::new(chord.code, chord.modifiers), *chord);
+                assert!(seen.insert(*chord), "duplicate default {chord}");
+            }
+        }
+    }
+
+    #[test]
+    fn every_palette_action_is_bindable() {
+        for item in default_actions() {
+            let id = format!("{PALETTE_PREFIX}{}", item.action.id());
+            assert_eq!(Target::from_id(&id), Some(Target::Palette(item.action)));
+        }
+        assert_eq!(
+            Target::from_id("quit"),
+            Some(Target::View(ViewAction::Quit))
+        );
+        assert_eq!(Target::from_id("palette.quit"), None);
+    }
+
+    #[test]
+    fn default_keymap_leaves_keys_unchanged() {
+        let keymap = Keymap::default();
+        for key in [
+            KeyEvent::new(KeyCode::Esc, NONE),
+            KeyEvent::new(KeyCode::Char('j'), ALT),
+            KeyEvent::new(KeyCode::Char('1'), CTRL),
+            KeyEvent::new(KeyCode::Char('x'), NONE),
+        ] {
+            assert_eq!(keymap.resolve(&key), Resolved::Unchanged);
+        }
+    }
+
+    #[test]
+    fn rebinding_rewrites_and_releases_defaults() {
+        let keymap = Keymap::with_overrides(vec![(
+            Target::View(ViewAction::Quit),
+            vec![parse("ctrl+q")],
+        )]);
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Char('q'), CTRL)),
+            Resolved::Key(KeyEvent::new(KeyCode::Esc, NONE))
+        );
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::F(10), NONE)),
+            Resolved::Unbound
+        );
+    }
+
+    #[test]
+    fn extra_chords_keep_the_canonical_key() {
+        let keymap = Keymap::with_overrides(vec![(
+            Target::View(ViewAction::MoveDown),
+            vec![parse("down"), parse("ctrl+n")],
+        )]);
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Char('n'), CTRL)),
+            Resolved::Key(KeyEvent::new(KeyCode::Down, NONE))
+        );
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Down, NONE)),
+            Resolved::Unchanged
+        );
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Char('j'), ALT)),
+            Resolved::Unbound
+        );
+    }
+
+    #[test]
+    fn claiming_a_default_chord_removes_it_from_its_owner() {
+        let keymap = Keymap::with_overrides(vec![(
+            Target::View(ViewAction::PageDown),
+            vec![parse("ctrl+d")],
+        )]);
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Char('d'), CTRL)),
+            Resolved::Key(KeyEvent::new(KeyCode::PageDown, NONE))
+        );
+        let density = keymap
+            .bindings()
+            .iter()
+            .find(|b| b.target == Target::View(ViewAction::ToggleDensity))
+            .unwrap();
+        assert!(density.chords.is_empty());
+    }
+
+    #[test]
+    fn palette_bindings_dispatch_directly() {
+        let keymap = Keymap::with_overrides(vec![(
+            Target::Palette(PaletteAction::FilterToday),
+            vec![parse("alt+t")],
+        )]);
+        assert_eq!(
+            keymap.resolve(&KeyEvent::new(KeyCode::Char('t'), ALT)),
+            Resolved::Palette(PaletteAction::FilterToday)
+        );
+    }
+
+    #[test]
+    fn refresh_rewrite_keeps_shift_for_handlers() {
+        let keymap =
+            Keymap::with_overrides(vec![(Target::View(ViewAction::Refresh), vec![parse("f5")])]);
+        let Resolved::Key(event) = keymap.resolve(&KeyEvent::new(KeyCode::F(5), NONE)) else {
+            panic!("expected rewrite");
+        };
+        assert_eq!(event.code, KeyCode::Char('R'));
+        assert!(event.modifiers.contains(CTRL_SHIFT));
+    }
+
+    #[test]
+    fn to_toml_lists_every_target() {
+        let text = Keymap::default().to_toml();
+        assert!(text.starts_with("[keys]\n"));
+        assert!(text.contains("quit = [\"Esc\", \"F10\"]"));
+        assert!(text.contains("palette.filter_today = []"));
+        assert!(text.contains("palette.save_view_slot_3 = [\"Ctrl+3\"]"));
+        let parsed: toml::Table = toml::from_str(&text).unwrap();
+        assert!(parsed["keys"]["palette"]["reload_index"].is_array());
+    }
+}
@@ -1,6 +1,10 @@
 //! TUI entrypoint and layout.
 pub mod components;
+pub mod config;
 pub mod data;
+pub mod diff_view;
+pub mod keymap;
+pub mod reader;
 pub mod shortcuts;
 pub mod time_parser;
 pub mod tui;
@@ -0,0 +1,890 @@
+//! Full-conversation reader for the TUI.
+//!
+//! Opened with `Alt+R` from the main view or `r` from the detail modal. Shows every message
+//! of a session with syntax-highlighted code, collapsible tool calls and outputs, an outline
+//! of user prompts, and per-role filtering. `[`/`]` jump between user prompts, `n`/`N`
+//! between query hits, `Enter` folds the current message, `Esc` closes.
+
+use std::collections::HashSet;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph};
+use unicode_width::UnicodeWidthChar;
+
+use crate::model::types::{Message, MessageRole};
+use crate::ui::components::theme::ThemePalette;
+use crate::ui::data::ConversationView;
+use crate::ui::tui::{format_absolute_time, highlight_code_block, parse_message_content};
+
+/// Message roles the reader can show or hide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReaderRole {
+    User,
+    Assistant,
+    Tool,
+    System,
+}
+
+impl ReaderRole {
+    pub const ALL: [Self; 4] = [Self::User, Self::Assistant, Self::Tool, Self::System];
+
+    pub fn of(role: &MessageRole) -> Self {
+        match role {
+            MessageRole::User => Self::User,
+            MessageRole::Agent => Self::Assistant,
+            MessageRole::Tool => Self::Tool,
+            MessageRole::System | MessageRole::Other(_) => Self::System,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Assistant => "assistant",
+            Self::Tool => "tool",
+            Self::System => "system",
+        }
+    }
+
+    /// Digit key that toggles this role's visibility.
+    fn key(self) -> char {
+        match self {
+            Self::User => '1',
+            Self::Assistant => '2',
+            Self::Tool => '3',
+            Self::System => '4',
+        }
+    }
+}
+
+/// Tool outputs and agent turns that only invoke tools start out collapsed.
+fn is_tool_message(msg: &Message) -> bool {
+    matches!(msg.role, MessageRole::Tool) || msg.content.trim_start().starts_with("[Tool:")
+}
+
+/// Where to put the viewport once the next layout is built.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Anchor {
+    FirstHit,
+    Message(usize),
+}
+
+/// Rendered rows for one viewport width; rebuilt when folding or filters change.
+struct ReaderLayout {
+    width: u16,
+    theme_dark: bool,
+    rows: Vec<Line<'static>>,
+    /// Message index owning each row.
+    owners: Vec<usize>,
+    /// First row of every visible message.
+    starts: Vec<(usize, usize)>,
+    /// First row of every logical line containing the query.
+    hits: Vec<usize>,
+}
+
+pub struct ReaderState {
+    view: ConversationView,
+    query: String,
+    hidden: HashSet<ReaderRole>,
+    collapsed: HashSet<usize>,
+    show_outline: bool,
+    scroll: usize,
+    viewport: usize,
+    anchor: Option<Anchor>,
+    notice: Option<String>,
+    layout: Option<ReaderLayout>,
+}
+
+impl ReaderState {
+    pub fn new(view: ConversationView, query: &str) -> Self {
+        let collapsed = view
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| is_tool_message(m))
+            .map(|(idx, _)| idx)
+            .collect();
+        Self {
+            view,
+            query: query.trim().to_string(),
+            hidden: HashSet::new(),
+            collapsed,
+            show_outline: true,
+            scroll: 0,
+            viewport: 1,
+            anchor: Some(Anchor::FirstHit),
+            notice: None,
+            layout: None,
+        }
+    }
+
+    /// Handle a key press. Returns false when the reader should close.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        self.notice = None;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_by(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_by(self.viewport as isize),
+            KeyCode::PageUp => self.scroll_by(-(self.viewport as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll = self.max_scroll(),
+            KeyCode::Char(']') => self.jump_prompt(true),
+            KeyCode::Char('[') => self.jump_prompt(false),
+            KeyCode::Char('n') => self.jump_hit(true),
+            KeyCode::Char('N') => self.jump_hit(false),
+            KeyCode::Enter => self.toggle_current(),
+            KeyCode::Char('z') => self.toggle_tools(),
+            KeyCode::Char('o') => self.show_outline = !self.show_outline,
+            KeyCode::Char('0') => {
+                self.hidden.clear();
+                self.relayout();
+                self.notice = Some("Showing all roles".into());
+            }
+            KeyCode::Char(c) => {
+                if let Some(role) = ReaderRole::ALL.into_iter().find(|r| r.key() == c) {
+                    self.toggle_role(role);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    pub fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.layout
+            .as_ref()
+            .map_or(0, |l| l.rows.len().saturating_sub(self.viewport.max(1)))
+    }
+
+    /// Message at the top of the viewport.
+    fn current_message(&self) -> Option<usize> {
+        let layout = self.layout.as_ref()?;
+        layout
+            .owners
+            .get(self.scroll)
+            .or_else(|| layout.owners.last())
+            .copied()
+    }
+
+    fn relayout(&mut self) {
+        self.anchor = self.current_message().map(Anchor::Message);
+        self.layout = None;
+    }
+
+    fn toggle_role(&mut self, role: ReaderRole) {
+        if !self.hidden.remove(&role) {
+            self.hidden.insert(role);
+        }
+        self.notice = Some(format!(
+            "{} messages {}",
+            role.label(),
+            if self.hidden.contains(&role) {
+                "hidden"
+            } else {
+                "shown"
+            }
+        ));
+        self.relayout();
+    }
+
+    fn toggle_current(&mut self) {
+        let Some(idx) = self.current_message() else {
+            return;
+        };
+        if !self.collapsed.remove(&idx) {
+            self.collapsed.insert(idx);
+        }
+        self.relayout();
+    }
+
+    fn toggle_tools(&mut self) {
+        let tools: Vec<usize> = self
+            .view
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| is_tool_message(m))
+            .map(|(idx, _)| idx)
+            .collect();
+        if tools.is_empty() {
+            self.notice = Some("No tool calls in this session".into());
+            return;
+        }
+        if tools.iter().all(|idx| self.collapsed.contains(idx)) {
+            for idx in &tools {
+                self.collapsed.remove(idx);
+            }
+            self.notice = Some(format!("Expanded {} tool blocks", tools.len()));
+        } else {
+            self.collapsed.extend(tools.iter().copied());
+            self.notice = Some(format!("Collapsed {} tool blocks", tools.len()));
+        }
+        self.relayout();
+    }
+
+    fn jump_prompt(&mut self, forward: bool) {
+        let Some(layout) = &self.layout else {
+            return;
+        };
+        let prompts = layout
+            .starts
+            .iter()
+            .filter(|(idx, _)| matches!(self.view.messages[*idx].role, MessageRole::User))
+            .map(|(_, row)| *row);
+        let target = if forward {
+            prompts.into_iter().find(|row| *row > self.scroll)
+        } else {
+            prompts.into_iter().rev().find(|row| *row < self.scroll)
+        };
+        match target {
+            Some(row) => self.scroll = row.min(self.max_scroll()),
+            None => {
+                self.notice = Some(if forward {
+                    "No later user prompt".into()
+                } else {
+                    "No earlier user prompt".into()
+                });
+            }
+        }
+    }
+
+    fn jump_hit(&mut self, forward: bool) {
+        let Some(layout) = &self.layout else {
+            return;
+        };
+        if self.query.is_empty() || layout.hits.is_empty() {
+            self.notice = Some(if self.query.is_empty() {
+                "No search query to jump to".into()
+            } else {
+                format!("No matches for \"{}\"", self.query)
+            });
+            return;
+        }
+        // Hits are placed a couple of rows below the top edge for context
+        let top = |row: usize| row.saturating_sub(2);
+        let pos = if forward {
+            layout.hits.iter().position(|row| top(*row) > self.scroll)
+        } else {
+            layout.hits.iter().rposition(|row| top(*row) < self.scroll)
+        };
+        let total = layout.hits.len();
+        match pos {
+            Some(i) => {
+                self.scroll = top(layout.hits[i]).min(self.max_scroll());
+                self.notice = Some(format!("Hit {}/{total}", i + 1));
+            }
+            None => {
+                self.notice = Some(format!(
+                    "{} hit ({total} total)",
+                    if forward { "Last" } else { "First" }
+                ));
+            }
+        }
+    }
+
+    fn ensure_layout(&mut self, width: u16, palette: ThemePalette, theme_dark: bool) {
+        if self
+            .layout
+            .as_ref()
+            .is_some_and(|l| l.width == width && l.theme_dark == theme_dark)
+        {
+            return;
+        }
+        // Keep the same message on top across width or theme changes
+        if self.anchor.is_none() {
+            self.anchor = self.current_message().map(Anchor::Message);
+        }
+        let layout = build_layout(self, width, palette, theme_dark);
+        self.scroll = match self.anchor.take() {
+            Some(Anchor::FirstHit) => layout.hits.first().map_or(0, |row| row.saturating_sub(2)),
+            Some(Anchor::Message(idx)) => layout
+                .starts
+                .iter()
+                .find(|(m, _)| *m >= idx)
+                .or_else(|| layout.starts.last())
+                .map_or(0, |(_, row)| *row),
+            None => self.scroll,
+        };
+        self.layout = Some(layout);
+        self.scroll = self.scroll.min(self.max_scroll());
+    }
+
+    /// Draw the reader over `area`.
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, palette: ThemePalette, theme_dark: bool) {
+        frame.render_widget(Clear, area);
+
+        let title = self
+            .view
+            .convo
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .unwrap_or("Untitled session")
+            .to_string();
+        let hidden: Vec<&str> = ReaderRole::ALL
+            .iter()
+            .filter(|r| self.hidden.contains(r))
+            .map(|r| r.label())
+            .collect();
+        let filter_badge = if hidden.is_empty() {
+            String::new()
+        } else {
+            format!(" · hiding {}", hidden.join(", "))
+        };
+
+        let outline_width = if self.show_outline && area.width >= 80 {
+            (area.width / 4).clamp(24, 40)
+        } else {
+            0
+        };
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(outline_width), Constraint::Min(20)].as_ref())
+            .split(area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(palette.accent));
+        let inner = block.inner(cols[1]);
+        self.viewport = usize::from(inner.height).max(1);
+        self.ensure_layout(inner.width.max(1), palette, theme_dark);
+
+        let current = self.current_message();
+        let (rows, total_rows) = self.layout.as_ref().map_or((Vec::new(), 0), |l| {
+            let end = (self.scroll + self.viewport).min(l.rows.len());
+            (l.rows[self.scroll.min(end)..end].to_vec(), l.rows.len())
+        });
+        let position = current.map_or_else(String::new, |idx| {
+            format!(" · msg {}/{}", idx + 1, self.view.messages.len())
+        });
+        let hint = self.notice.clone().unwrap_or_else(|| {
+            "[ ] prompts · n/N hits · Enter fold · z tools · 1-4 roles · 0 all · o outline · Esc"
+                .to_string()
+        });
+        let block = block
+            .title(Span::styled(
+                format!(" Reader · {title}{position}{filter_badge} "),
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Line::from(Span::styled(
+                format!(" {hint} · row {}/{total_rows} ", self.scroll + 1),
+                Style::default().fg(palette.hint),
+            )));
+        let body = if rows.is_empty() {
+            Paragraph::new(Line::from(Span::styled(
+                "Nothing to show: every role is hidden (press 0 to show all)",
+                Style::default().fg(palette.hint),
+            )))
+        } else {
+            Paragraph::new(rows)
+        };
+        frame.render_widget(body.block(block), cols[1]);
+
+        if outline_width > 0 {
+            self.draw_outline(frame, cols[0], current, palette);
+        }
+    }
+
+    fn draw_outline(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        current: Option<usize>,
+        palette: ThemePalette,
+    ) {
+        let prompts = outline(&self.view);
+        let max = usize::from(area.width.saturating_sub(6)).max(4);
+        let items: Vec<ListItem> = prompts
+            .iter()
+            .map(|(idx, text)| {
+                let label = if text.chars().count() > max {
+                    format!("{}…", text.chars().take(max - 1).collect::<String>())
+                } else {
+                    text.clone()
+                };
+                let style = if self.hidden.contains(&ReaderRole::User) {
+                    Style::default()
+                        .fg(palette.hint)
+                        .add_modifier(Modifier::DIM)
+                } else {
+                    Style::default().fg(palette.user)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:>3} ", idx + 1),
+                        Style::default().fg(palette.hint),
+                    ),
+                    Span::styled(label, style),
+                ]))
+            })
+            .collect();
+        // Highlight the last prompt at or above the current message
+        let selected = current.and_then(|cur| prompts.iter().rposition(|(idx, _)| *idx <= cur));
+        let mut state = ListState::default();
+        state.select(selected);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        format!(" Prompts ({}) ", prompts.len()),
+                        Style::default().fg(palette.hint),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(palette.hint)),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol("▶");
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+}
+
+/// First line of every user prompt, with its message index.
+fn outline(view: &ConversationView) -> Vec<(usize, String)> {
+    view.messages
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| matches!(m.role, MessageRole::User))
+        .map(|(idx, m)| {
+            let first = m
+                .content
+                .lines()
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or("(empty prompt)");
+            (idx, first.to_string())
+        })
+        .collect()
+}
+
+fn build_layout(
+    state: &ReaderState,
+    width: u16,
+    palette: ThemePalette,
+    theme_dark: bool,
+) -> ReaderLayout {
+    let mut layout = ReaderLayout {
+        width,
+        theme_dark,
+        rows: Vec::new(),
+        owners: Vec::new(),
+        starts: Vec::new(),
+        hits: Vec::new(),
+    };
+    let needle = state.query.to_lowercase();
+    for (idx, msg) in state.view.messages.iter().enumerate() {
+        if state.hidden.contains(&ReaderRole::of(&msg.role)) {
+            continue;
+        }
+        layout.starts.push((idx, layout.rows.len()));
+        let collapsed = state.collapsed.contains(&idx);
+        let mut lines = vec![message_header(msg, idx, collapsed, palette)];
+        if collapsed {
+            lines.push(collapsed_summary(msg, palette));
+        } else {
+            lines.push(Line::from(""));
+            lines.extend(message_body(
+                &msg.content,
+                &state.query,
+                palette,
+                theme_dark,
+            ));
+        }
+        lines.push(Line::from(""));
+        for line in lines {
+            if !needle.is_empty() && line_text(&line).to_lowercase().contains(&needle) {
+                layout.hits.push(layout.rows.len());
+            }
+            for row in wrap_line(line, usize::from(width)) {
+                layout.rows.push(row);
+                layout.owners.push(idx);
+            }
+        }
+    }
+    layout
+}
+
+fn message_header(
+    msg: &Message,
+    idx: usize,
+    collapsed: bool,
+    palette: ThemePalette,
+) -> Line<'static> {
+    let (icon, label, color) = match &msg.role {
+        MessageRole::User => ("👤", "You".to_string(), palette.user),
+        MessageRole::Agent => ("🤖", "Assistant".to_string(), palette.agent),
+        MessageRole::Tool => ("🔧", "Tool".to_string(), palette.tool),
+        MessageRole::System => ("⚙️", "System".to_string(), palette.system),
+        MessageRole::Other(r) => ("📝", r.clone(), palette.hint),
+    };
+    let ts = msg
+        .created_at
+        .map(|t| format!(" · {}", format_absolute_time(t)))
+        .unwrap_or_default();
+    Line::from(vec![
+        Span::styled(
+            if collapsed { "▸ " } else { "▾ " },
+            Style::default().fg(palette.hint),
+        ),
+        Span::raw(format!("{icon} ")),
+        Span::styled(
+            label,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" #{}{ts}", idx + 1),
+            Style::default().fg(palette.hint),
+        ),
+    ])
+}
+
+fn collapsed_summary(msg: &Message, palette: ThemePalette) -> Line<'static> {
+    let first = msg
+        .content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    let first: String = first.chars().take(120).collect();
+    let count = msg.content.lines().count();
+    Line::from(vec![
+        Span::styled(format!("  {first}"), Style::default().fg(palette.tool)),
+        Span::styled(
+            format!("  ({count} lines, Enter to expand)"),
+            Style::default()
+                .fg(palette.hint)
+                .add_modifier(Modifier::DIM),
+        ),
+    ])
+}
+
+/// Message text through the shared markdown renderer, with fenced code highlighted by syntect.
+fn message_body(
+    content: &str,
+    query: &str,
+    palette: ThemePalette,
+    theme_dark: bool,
+) -> Vec<Line<'static>> {
+    let mut out = Vec::new();
+    let mut text = String::new();
+    let mut code: Option<(String, Vec<String>)> = None;
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            if let Some((lang, body)) = code.take() {
+                out.extend(code_block(&lang, &body, query, palette, theme_dark));
+            } else {
+                out.extend(parse_message_content(&text, query, palette));
+                text.clear();
+                let lang = line.trim_start().trim_start_matches('`');
+                code = Some((
+                    lang.split_whitespace().next().unwrap_or("").to_string(),
+                    Vec::new(),
+                ));
+            }
+            continue;
+        }
+        match code.as_mut() {
+            Some((_, body)) => body.push(line.to_string()),
+            None => {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    if let Some((lang, body)) = code {
+        out.extend(code_block(&lang, &body, query, palette, theme_dark));
+    }
+    out.extend(parse_message_content(&text, query, palette));
+    out
+}
+
+fn code_block(
+    lang: &str,
+    body: &[String],
+    query: &str,
+    palette: ThemePalette,
+    theme_dark: bool,
+) -> Vec<Line<'static>> {
+    let gutter = Style::default().fg(palette.hint);
+    let highlighted =
+        highlight_code_block(body, lang, query, palette, theme_dark).unwrap_or_else(|| {
+            body.iter()
+                .map(|l| Line::from(Span::styled(l.clone(), Style::default().fg(palette.fg))))
+                .collect()
+        });
+    let mut out = Vec::with_capacity(highlighted.len() + 2);
+    out.push(Line::from(vec![
+        Span::styled("┌──", gutter),
+        Span::styled(
+            if lang.is_empty() {
+                String::new()
+            } else {
+                format!(" {lang}")
+            },
+            Style::default()
+                .fg(palette.accent_alt)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+    for line in highlighted {
+        let mut spans = vec![Span::styled("│ ", gutter)];
+        spans.extend(line.spans);
+        out.push(Line::from(spans));
+    }
+    out.push(Line::from(Span::styled("└──", gutter)));
+    out
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans.iter().map(|s| s.content.as_ref()).collect()
+}
+
+/// Word-wrap a styled line to `width` columns so every row maps to one screen line.
+fn wrap_line(line: Line<'static>, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let line_style = line.style;
+    let chars: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+    let char_width = |c: char| UnicodeWidthChar::width(c).unwrap_or(0);
+    if chars.iter().map(|(c, _)| char_width(*c)).sum::<usize>() <= width {
+        return vec![line];
+    }
+
+    let mut rows: Vec<&[(char, Style)]> = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let mut used = 0;
+        let mut end = start;
+        let mut last_break = None;
+        while end < chars.len() {
+            let w = char_width(chars[end].0);
+            if used + w > width && end > start {
+                break;
+            }
+            used += w;
+            if chars[end].0 == ' ' {
+                last_break = Some(end + 1);
+            }
+            end += 1;
+        }
+        if end < chars.len()
+            && let Some(brk) = last_break.filter(|b| *b > start)
+        {
+            end = brk;
+        }
+        rows.push(&chars[start..end]);
+        start = end;
+        // Continuation rows do not start with the space we broke on
+        while start < chars.len() && chars[start].0 == ' ' {
+            start += 1;
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            let mut buf = String::new();
+            let mut style = row.first().map_or(Style::default(), |(_, s)| *s);
+            for (c, s) in row {
+                if *s != style && !buf.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut buf), style));
+                }
+                style = *s;
+                buf.push(*c);
+            }
+            if !buf.is_empty() {
+                spans.push(Span::styled(buf, style));
+            }
+            Line::from(spans).style(line_style)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::types::Conversation;
+    use crossterm::event::KeyModifiers;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn msg(idx: i64, role: MessageRole, content: &str) -> Message {
+        Message {
+            id: Some(idx),
+            idx,
+            role,
+            author: None,
+            created_at: None,
+            content: content.into(),
+            extra_json: json!({}),
+            snippets: vec![],
+        }
+    }
+
+    fn view() -> ConversationView {
+        ConversationView {
+            convo: Conversation {
+                id: Some(1),
+                agent_slug: "codex".into(),
+                workspace: None,
+                external_id: None,
+                title: Some("Reader test".into()),
+                source_path: PathBuf::from("/tmp/reader.jsonl"),
+                started_at: None,
+                ended_at: None,
+                approx_tokens: None,
+                metadata_json: json!({}),
+                messages: Vec::new(),
+                source_id: "local".to_string(),
+                origin_host: None,
+            },
+            messages: vec![
+                msg(0, MessageRole::User, "fix the flaky auth test"),
+                msg(1, MessageRole::Agent, "[Tool: shell - cargo test auth]"),
+                msg(
+                    2,
+                    MessageRole::Tool,
+                    "running 3 tests\ntest auth::login ... FAILED",
+                ),
+                msg(
+                    3,
+                    MessageRole::Agent,
+                    "The auth test races.\n```rust\nfn main() {}\n```",
+                ),
+                msg(4, MessageRole::User, "now document it"),
+                msg(5, MessageRole::Agent, "Added docs for auth."),
+            ],
+            workspace: None,
+            tags: Vec::new(),
+        }
+    }
+
+    fn laid_out(query: &str) -> ReaderState {
+        let mut state = ReaderState::new(view(), query);
+        state.viewport = 4;
+        state.ensure_layout(80, ThemePalette::dark(), true);
+        state
+    }
+
+    fn press(state: &mut ReaderState, code: KeyCode) -> bool {
+        let open = state.handle_key(&KeyEvent::new(code, KeyModifiers::NONE));
+        state.ensure_layout(80, ThemePalette::dark(), true);
+        open
+    }
+
+    fn text(state: &ReaderState) -> String {
+        let layout = state.layout.as_ref().expect("layout");
+        layout
+            .rows
+            .iter()
+            .map(line_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn tool_messages_start_collapsed_and_toggle() {
+        let mut state = laid_out("");
+        assert!(state.collapsed.contains(&1));
+        assert!(state.collapsed.contains(&2));
+        assert!(!text(&state).contains("test auth::login"));
+
+        press(&mut state, KeyCode::Char('z'));
+        assert!(state.collapsed.is_empty());
+        assert!(text(&state).contains("test auth::login ... FAILED"));
+
+        press(&mut state, KeyCode::Char('z'));
+        assert!(!text(&state).contains("test auth::login"));
+    }
+
+    #[test]
+    fn role_filter_hides_messages_and_zero_restores() {
+        let mut state = laid_out("");
+        press(&mut state, KeyCode::Char('2'));
+        assert!(state.hidden.contains(&ReaderRole::Assistant));
+        assert!(!text(&state).contains("Added docs"));
+        assert!(text(&state).contains("now document it"));
+
+        press(&mut state, KeyCode::Char('0'));
+        assert!(state.hidden.is_empty());
+        assert!(text(&state).contains("Added docs"));
+    }
+
+    #[test]
+    fn prompt_and_hit_jumps_move_the_viewport() {
+        let mut state = laid_out("auth");
+        // Opens on the first hit (the first prompt)
+        assert_eq!(state.current_message(), Some(0));
+
+        press(&mut state, KeyCode::Char(']'));
+        assert_eq!(state.current_message(), Some(4));
+        press(&mut state, KeyCode::Char('['));
+        assert_eq!(state.current_message(), Some(0));
+
+        press(&mut state, KeyCode::Char('n'));
+        let first = state.scroll;
+        press(&mut state, KeyCode::Char('n'));
+        assert!(state.scroll > first);
+        assert!(state.notice.as_deref().unwrap_or("").starts_with("Hit "));
+
+        assert!(!press(&mut state, KeyCode::Esc));
+    }
+
+    #[test]
+    fn code_blocks_keep_their_content() {
+        let state = laid_out("");
+        let body = text(&state);
+        assert!(body.contains("┌── rust"));
+        assert!(body.contains("│ fn main() {}"));
+    }
+
+    #[test]
+    fn wrap_line_breaks_on_spaces_and_keeps_styles() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![
+            Span::styled("alpha beta ", bold),
+            Span::raw("gamma delta"),
+        ]);
+        let rows = wrap_line(line, 11);
+        let texts: Vec<String> = rows.iter().map(line_text).collect();
+        assert_eq!(texts, vec!["alpha beta ", "gamma delta"]);
+        assert_eq!(rows[0].spans[0].style, bold);
+
+        let long = wrap_line(Line::from("abcdefghij"), 4);
+        let texts: Vec<String> = long.iter().map(line_text).collect();
+        assert_eq!(texts, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn outline_lists_user_prompts() {
+        let prompts = outline(&view());
+        assert_eq!(
+            prompts,
+            vec![
+                (0, "fix the flaky auth test".to_string()),
+                (4, "now document it".to_string())
+            ]
+        );
+    }
+}
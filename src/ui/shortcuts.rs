@@ -10,6 +10,7 @@ pub const CONTEXT_WINDOW: &str = "F7";
 pub const EDITOR: &str = "F8";
 pub const MATCH_MODE: &str = "F9";
 pub const SEARCH_MODE: &str = "Alt+S";
+pub const GRANULARITY: &str = "Alt+G";
 pub const QUIT: &str = "Esc/F10";
 pub const CLEAR_FILTERS: &str = "Ctrl+Del";
 pub const RESET_STATE: &str = "Ctrl+Shift+Del";
@@ -39,6 +40,14 @@ pub const VIM_NAV: &str = "Alt+h/j/k/l";
 pub const JUMP_TOP: &str = "Home";
 pub const JUMP_BOTTOM: &str = "End";
 
+// Tabs & split view
+pub const NEW_TAB: &str = "Ctrl+T";
+pub const CLOSE_TAB: &str = "Ctrl+W";
+pub const NEXT_TAB: &str = "Ctrl+PgDn";
+pub const PREV_TAB: &str = "Ctrl+PgUp";
+pub const SPLIT_VIEW: &str = "Alt+V";
+pub const READER: &str = "Alt+R";
+
 // =============================================================================
 // Unit Tests
 // =============================================================================
@@ -87,6 +96,7 @@ mod tests {
     #[test]
     fn modifier_shortcuts_are_not_empty() {
         assert!(!SEARCH_MODE.is_empty());
+        assert!(!GRANULARITY.is_empty());
         assert!(!CLEAR_FILTERS.is_empty());
         assert!(!RESET_STATE.is_empty());
         assert!(!REFRESH.is_empty());
@@ -99,6 +109,7 @@ mod tests {
     #[test]
     fn modifier_shortcuts_have_expected_values() {
         assert_eq!(SEARCH_MODE, "Alt+S");
+        assert_eq!(GRANULARITY, "Alt+G");
         assert_eq!(CLEAR_FILTERS, "Ctrl+Del");
         assert_eq!(RESET_STATE, "Ctrl+Shift+Del");
         assert_eq!(REFRESH, "Ctrl+Shift+R");
@@ -164,6 +175,20 @@ mod tests {
         assert_eq!(JUMP_BOTTOM, "End");
     }
 
+    // =========================================================================
+    // Tab & Split Constants Tests
+    // =========================================================================
+
+    #[test]
+    fn tab_shortcuts_have_expected_values() {
+        assert_eq!(NEW_TAB, "Ctrl+T");
+        assert_eq!(CLOSE_TAB, "Ctrl+W");
+        assert_eq!(NEXT_TAB, "Ctrl+PgDn");
+        assert_eq!(PREV_TAB, "Ctrl+PgUp");
+        assert_eq!(SPLIT_VIEW, "Alt+V");
+        assert_eq!(READER, "Alt+R");
+    }
+
     // =========================================================================
     // Detail View Constants Tests
     // =========================================================================
@@ -246,6 +271,10 @@ mod tests {
             HISTORY_PREV,
             HISTORY_CYCLE,
             TOGGLE_SELECT,
+            NEW_TAB,
+            CLOSE_TAB,
+            NEXT_TAB,
+            PREV_TAB,
         ];
 
         for key in &ctrl_keys {
@@ -299,6 +328,7 @@ mod tests {
     fn modifier_shortcuts_contain_plus_separator() {
         let modifier_keys = [
             SEARCH_MODE,
+            GRANULARITY,
             CLEAR_FILTERS,
             RESET_STATE,
             REFRESH,
@@ -37,7 +37,8 @@ use crate::search::model_manager::{
     SemanticAvailability, default_model_dir, load_hash_semantic_context, load_semantic_context,
 };
 use crate::search::query::{
-    CacheStats, QuerySuggestion, SearchClient, SearchFilters, SearchHit, SearchMode,
+    CacheStats, DEFAULT_SESSION_EVIDENCE, QuerySuggestion, SESSION_OVERFETCH, SearchClient,
+    SearchFilters, SearchGranularity, SearchHit, SearchMode, SessionHit, group_hits_by_session,
 };
 use crate::search::tantivy::index_dir;
 use crate::ui::components::export_modal::{
@@ -46,12 +47,15 @@ use crate::ui::components::export_modal::{
 use crate::ui::components::help_strip;
 use crate::ui::components::palette::{self, PaletteAction, PaletteState};
 use crate::ui::components::pills::{self, Pill};
-use crate::ui::components::theme::ThemePalette;
+use crate::ui::components::theme::{ThemeComponent, ThemePalette};
 use crate::ui::components::toast::{Toast, ToastManager, render_toasts};
 use crate::ui::components::widgets::{
     centered_rect, centered_rect_fixed, score_indicator, search_bar,
 };
+use crate::ui::config::TuiConfig;
 use crate::ui::data::{ConversationView, InputMode, load_conversation, role_style};
+use crate::ui::keymap::Resolved;
+use crate::ui::reader::ReaderState;
 use crate::ui::shortcuts;
 use crate::update_check::{
     UpdateInfo, open_in_browser, run_self_update, skip_version, spawn_update_check,
@@ -259,6 +263,15 @@ struct TuiStatePersisted {
     per_pane_limit: Option<usize>,
     /// Persisted ranking mode (bead 46t.1): "recent", "balanced", "relevance", etc.
     ranking_mode: Option<String>,
+    /// Result granularity: "message" or "session".
+    #[serde(default)]
+    granularity: Option<String>,
+    /// Open search tabs; only written when more than one tab is open.
+    #[serde(default)]
+    tabs: Option<Vec<SearchTabPersisted>>,
+    /// Index of the focused tab in `tabs`.
+    #[serde(default)]
+    active_tab: Option<usize>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -274,6 +287,37 @@ struct SavedViewPersisted {
     source_filter: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SearchTabPersisted {
+    query: String,
+    agents: Vec<String>,
+    workspaces: Vec<String>,
+    created_from: Option<i64>,
+    created_to: Option<i64>,
+    ranking: Option<String>,
+    #[serde(default)]
+    source_filter: Option<String>,
+}
+
+/// A search tab. The focused tab's state lives in the event loop's `query`, `filters`
+/// and `ranking_mode`; background tabs are parked here until they are switched to.
+#[derive(Clone, Debug)]
+struct SearchTab {
+    query: String,
+    filters: SearchFilters,
+    ranking: RankingMode,
+}
+
+impl SearchTab {
+    fn new(ranking: RankingMode) -> Self {
+        Self {
+            query: String::new(),
+            filters: SearchFilters::default(),
+            ranking,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct SavedView {
     slot: u8,
@@ -574,6 +618,26 @@ fn contextual_empty_state(
             ),
             Span::raw(" — filter by agent"),
         ]));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                "role:user tool:Bash",
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" — filter by message role or tool used"),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("  "),
+            Span::styled(
+                "tag:auth-bug",
+                Style::default()
+                    .fg(palette.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(" — filter by session tag (cass tag add)"),
+        ]));
         lines.push(Line::from(vec![
             Span::raw("  "),
             Span::styled(
@@ -815,7 +879,7 @@ fn format_relative_time(timestamp_ms: i64) -> String {
 }
 
 /// Formats a timestamp as an absolute string with date and time in UTC.
-fn format_absolute_time(timestamp_ms: i64) -> String {
+pub(crate) fn format_absolute_time(timestamp_ms: i64) -> String {
     DateTime::<Utc>::from_timestamp_millis(timestamp_ms).map_or_else(
         || "unknown".to_string(),
         |dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
@@ -881,6 +945,7 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
             "Index & state: ~/.local/share/coding-agent-search/".to_string(),
             "  agent_search.db - Full-text search index".to_string(),
             "  tui_state.json - UI preferences | watch_state.json - Watch timestamps".to_string(),
+            "  saved_searches.json - Saved searches (cass saved)".to_string(),
             "  remotes/ - Synced session data from remote sources".to_string(),
             "Config: ~/.config/cass/sources.toml (remote sources)".to_string(),
             "Agents: Claude, Codex, Gemini, Cline, OpenCode, Amp, Cursor, ChatGPT, Aider, Pi-Agent, Factory"
@@ -943,6 +1008,10 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
                 "{} search mode: Lexical → Semantic → Hybrid",
                 shortcuts::SEARCH_MODE
             ),
+            format!(
+                "{} results: per message ⇄ per session",
+                shortcuts::GRANULARITY
+            ),
             format!(
                 "{} match mode: prefix (default) ⇄ standard",
                 shortcuts::MATCH_MODE
@@ -990,6 +1059,37 @@ pub fn help_lines(palette: ThemePalette) -> Vec<Line<'static>> {
             "[ / ] cycle detail tabs (when results showing)".to_string(),
         ],
     ));
+    lines.extend(add_section(
+        "Tabs & Split View",
+        &[
+            format!(
+                "{} new tab | {} close tab | {}/{} next/prev tab",
+                shortcuts::NEW_TAB,
+                shortcuts::CLOSE_TAB,
+                shortcuts::NEXT_TAB,
+                shortcuts::PREV_TAB
+            ),
+            "Each tab keeps its own query, filters and ranking; tabs persist across runs"
+                .to_string(),
+            format!(
+                "{} pins the selected conversation beside the detail; both sides scroll together",
+                shortcuts::SPLIT_VIEW
+            ),
+        ],
+    ));
+    lines.extend(add_section(
+        "Reader",
+        &[
+            format!(
+                "{} (or r in the detail modal) reads the whole conversation",
+                shortcuts::READER
+            ),
+            "[ / ] prev/next user prompt; n/N next/prev hit; o toggles the prompt outline"
+                .to_string(),
+            "Enter folds the current message; z folds all tool calls and outputs".to_string(),
+            "1-4 toggle user/assistant/tool/system messages; 0 shows all; Esc closes".to_string(),
+        ],
+    ));
     lines.extend(add_section(
         "Mouse",
         &[
@@ -1163,7 +1263,11 @@ fn render_parsed_content(
 
 /// Parse message content and render with beautiful formatting.
 /// Handles code blocks, tool calls, JSON, and highlights search terms.
-fn parse_message_content(content: &str, query: &str, palette: ThemePalette) -> Vec<Line<'static>> {
+pub(crate) fn parse_message_content(
+    content: &str,
+    query: &str,
+    palette: ThemePalette,
+) -> Vec<Line<'static>> {
     let mut lines: Vec<Line<'static>> = Vec::new();
     let mut in_code_block = false;
     let mut code_lang: Option<String> = None;
@@ -1338,7 +1442,7 @@ fn render_detail_modal(
 
     // Build title with scroll position and hints
     let title_text = format!(
-        " {} · line {}/{} · Esc · o open · c copy · p path · s snip · n nano ",
+        " {} · line {}/{} · Esc · o open · c copy · p path · s snip · n nano · r reader ",
         hit.title, display_line, total_lines
     );
 
@@ -1584,6 +1688,10 @@ fn agent_color(agent: &str) -> Color {
         "chatgpt" => Color::Rgb(16, 163, 127), // ChatGPT Green
         "aider" => Color::Rgb(255, 165, 0), // Orange
         "pi_agent" => Color::Rgb(255, 140, 0), // Dark Orange (Pi)
+        "continue" => Color::Rgb(190, 190, 255), // Lavender (Continue.dev)
+        "windsurf" => Color::Rgb(9, 182, 162), // Sea Green (Windsurf)
+        "copilot" => Color::Rgb(110, 84, 148), // GitHub Purple
+        "roo_code" => Color::Rgb(255, 105, 180), // Hot Pink (Roo Code)
         _ => Color::Rgb(169, 169, 169),     // Dark Gray (fallback)
     }
 }
@@ -1867,6 +1975,45 @@ fn syntax_highlight_line(
     Some(Line::from(spans))
 }
 
+/// Highlight a fenced code block, keeping syntect's parse state across its lines.
+/// Returns `None` when `lang` names no known syntax so callers can render plain text.
+pub(crate) fn highlight_code_block(
+    code: &[String],
+    lang: &str,
+    highlight_term: &str,
+    palette: ThemePalette,
+    theme_dark: bool,
+) -> Option<Vec<Line<'static>>> {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        return None;
+    }
+    let assets = syntax_assets()?;
+    let syntax = assets.ps.find_syntax_by_token(lang)?;
+    let theme = if theme_dark {
+        &assets.theme_dark
+    } else {
+        &assets.theme_light
+    };
+    let mut h = HighlightLines::new(syntax, theme);
+    let mut out = Vec::with_capacity(code.len());
+    for line in code {
+        // The newline syntax set expects each line to carry its terminator
+        let ranges = h.highlight_line(&format!("{line}\n"), &assets.ps).ok()?;
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (style, text) in ranges {
+            let text = text.trim_end_matches('\n');
+            if text.is_empty() {
+                continue;
+            }
+            let base = Style::default().fg(syntect_color_to_ratatui(style.foreground));
+            spans.extend(highlight_spans_owned(text, highlight_term, palette, base));
+        }
+        out.push(Line::from(spans));
+    }
+    Some(out)
+}
+
 fn state_path_for(data_dir: &std::path::Path) -> std::path::PathBuf {
     // Persist lightweight, non-secret UI preferences (search/match mode, context window).
     data_dir.join("tui_state.json")
@@ -1883,6 +2030,17 @@ fn ranking_from_str(s: &str) -> RankingMode {
     }
 }
 
+fn ranking_to_str(mode: RankingMode) -> &'static str {
+    match mode {
+        RankingMode::RecentHeavy => "recent",
+        RankingMode::RelevanceHeavy => "relevance",
+        RankingMode::MatchQualityHeavy => "quality",
+        RankingMode::DateNewest => "newest",
+        RankingMode::DateOldest => "oldest",
+        RankingMode::Balanced => "balanced",
+    }
+}
+
 fn search_mode_from_str(s: &str) -> SearchMode {
     match s {
         "semantic" => SearchMode::Semantic,
@@ -2153,6 +2311,133 @@ fn load_view_slot(
     })
 }
 
+/// Maximum number of search tabs open at once.
+const MAX_TABS: usize = 9;
+
+/// Park the live search state in the active tab and load tab `to` into it.
+fn switch_tab(
+    tabs: &mut [SearchTab],
+    active_tab: &mut usize,
+    to: usize,
+    query: &mut String,
+    filters: &mut SearchFilters,
+    ranking: &mut RankingMode,
+) {
+    if let Some(current) = tabs.get_mut(*active_tab) {
+        current.query = std::mem::take(query);
+        current.filters = std::mem::take(filters);
+        current.ranking = *ranking;
+    }
+    *active_tab = to.min(tabs.len().saturating_sub(1));
+    if let Some(next) = tabs.get(*active_tab) {
+        query.clone_from(&next.query);
+        filters.clone_from(&next.filters);
+        *ranking = next.ranking;
+    }
+}
+
+/// Close the active tab and load its neighbour. Returns false when it is the last tab.
+fn close_tab(
+    tabs: &mut Vec<SearchTab>,
+    active_tab: &mut usize,
+    query: &mut String,
+    filters: &mut SearchFilters,
+    ranking: &mut RankingMode,
+) -> bool {
+    if tabs.len() <= 1 || *active_tab >= tabs.len() {
+        return false;
+    }
+    tabs.remove(*active_tab);
+    *active_tab = (*active_tab).min(tabs.len() - 1);
+    let next = &tabs[*active_tab];
+    query.clone_from(&next.query);
+    filters.clone_from(&next.filters);
+    *ranking = next.ranking;
+    true
+}
+
+/// Short label for the tab strip.
+fn tab_label(query: &str) -> String {
+    let query = query.trim();
+    if query.is_empty() {
+        "(all)".to_string()
+    } else if query.chars().count() > 18 {
+        format!("{}…", query.chars().take(17).collect::<String>())
+    } else {
+        query.to_string()
+    }
+}
+
+fn tab_to_persisted(tab: &SearchTab) -> SearchTabPersisted {
+    SearchTabPersisted {
+        query: tab.query.clone(),
+        agents: tab.filters.agents.iter().cloned().collect(),
+        workspaces: tab.filters.workspaces.iter().cloned().collect(),
+        created_from: tab.filters.created_from,
+        created_to: tab.filters.created_to,
+        ranking: Some(ranking_to_str(tab.ranking).into()),
+        source_filter: if tab.filters.source_filter.is_all() {
+            None
+        } else {
+            Some(tab.filters.source_filter.to_string())
+        },
+    }
+}
+
+fn tab_from_persisted(tab: &SearchTabPersisted) -> SearchTab {
+    SearchTab {
+        query: tab.query.clone(),
+        filters: SearchFilters {
+            agents: tab.agents.iter().cloned().collect(),
+            workspaces: tab.workspaces.iter().cloned().collect(),
+            created_from: tab.created_from,
+            created_to: tab.created_to,
+            source_filter: tab
+                .source_filter
+                .as_ref()
+                .map(|s| crate::sources::provenance::SourceFilter::parse(s))
+                .unwrap_or_default(),
+            ..SearchFilters::default()
+        },
+        ranking: tab
+            .ranking
+            .as_deref()
+            .map_or(RankingMode::Balanced, ranking_from_str),
+    }
+}
+
+/// Restore persisted tabs, falling back to a single empty tab.
+fn restore_tabs(
+    persisted: Option<&[SearchTabPersisted]>,
+    active_tab: Option<usize>,
+    ranking: RankingMode,
+) -> (Vec<SearchTab>, usize) {
+    let tabs: Vec<SearchTab> = persisted
+        .unwrap_or_default()
+        .iter()
+        .take(MAX_TABS)
+        .map(tab_from_persisted)
+        .collect();
+    if tabs.is_empty() {
+        return (vec![SearchTab::new(ranking)], 0);
+    }
+    let active = active_tab.unwrap_or(0).min(tabs.len() - 1);
+    (tabs, active)
+}
+
+/// Title for the pinned side of a split detail view.
+fn pinned_title(view: &ConversationView, source_path: &str) -> String {
+    view.convo
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map_or_else(
+            || truncate_path(source_path, 40),
+            |t| t.chars().take(40).collect(),
+        )
+}
+
 fn load_state(path: &std::path::Path) -> TuiStatePersisted {
     std::fs::read_to_string(path)
         .ok()
@@ -2464,6 +2749,37 @@ fn ensure_db_reader<'a>(
     db_reader.as_ref()
 }
 
+/// The conversation behind `hit`, reusing the detail pane's cached copy when it matches.
+fn hit_conversation(
+    hit: &SearchHit,
+    cached_detail: Option<&(String, ConversationView)>,
+    db_reader: &mut Option<crate::storage::sqlite::SqliteStorage>,
+    db_path: &Path,
+) -> Option<ConversationView> {
+    cached_detail
+        .filter(|(p, _)| p == &hit.source_path)
+        .map(|(_, d)| d.clone())
+        .or_else(|| {
+            ensure_db_reader(db_reader, db_path)
+                .and_then(|storage| load_conversation(storage, &hit.source_path).ok().flatten())
+        })
+}
+
+/// Term to highlight in detail views: detail-find > pane filter > last query.
+fn detail_highlight_term<'a>(
+    detail_find: Option<&'a DetailFindState>,
+    pane_filter: Option<&'a str>,
+    last_query: &'a str,
+) -> &'a str {
+    if let Some(df) = detail_find {
+        df.query.as_str()
+    } else if let Some(pf) = pane_filter.filter(|s| !s.trim().is_empty()) {
+        pf
+    } else {
+        last_query
+    }
+}
+
 fn ensure_known_workspaces(
     db_reader: &mut Option<crate::storage::sqlite::SqliteStorage>,
     db_path: &Path,
@@ -2553,6 +2869,13 @@ pub fn run_tui(
         let _ = std::fs::remove_file(&state_path);
     }
 
+    // Validate tui.toml before touching the terminal so errors print cleanly.
+    let TuiConfig {
+        keymap,
+        theme: user_theme,
+        ..
+    } = TuiConfig::load()?;
+
     if once
         && dotenvy::var("TUI_HEADLESS")
             .map(|v| v == "1")
@@ -2736,6 +3059,10 @@ pub fn run_tui(
         .with_max_visible(2)
         .with_position(crate::ui::components::toast::ToastPosition::TopRight);
     let mut cached_detail: Option<(String, ConversationView)> = None;
+    // Conversation pinned beside the live detail pane (Alt+V); shares `detail_scroll`
+    let mut split_detail: Option<(String, ConversationView)> = None;
+    // Full-screen conversation reader (Alt+R, or r in the detail modal)
+    let mut reader: Option<ReaderState> = None;
     let mut detail_find: Option<DetailFindState> = None;
     let mut last_query = String::new();
     let mut needs_draw = true;
@@ -2766,6 +3093,10 @@ pub fn run_tui(
         .ranking_mode
         .as_deref()
         .map_or(RankingMode::Balanced, ranking_from_str);
+    let mut granularity = match persisted.granularity.as_deref() {
+        Some("session") => SearchGranularity::Session,
+        _ => SearchGranularity::Message,
+    };
     let mut saved_views: Vec<SavedView> = persisted
         .saved_views
         .as_ref()
@@ -2796,6 +3127,17 @@ pub fn run_tui(
                 .collect()
         })
         .unwrap_or_default();
+    let (mut tabs, mut active_tab) = restore_tabs(
+        persisted.tabs.as_deref(),
+        persisted.active_tab,
+        ranking_mode,
+    );
+    if tabs.len() > 1 {
+        let current = &tabs[active_tab];
+        query.clone_from(&current.query);
+        filters.clone_from(&current.filters);
+        ranking_mode = current.ranking;
+    }
     let mut help_pinned = persisted.help_pinned.unwrap_or(false);
     let mut help_last_interaction = Instant::now();
     let mut fancy_borders = true; // Toggle with Ctrl+B for unicode vs ASCII borders
@@ -3040,11 +3382,19 @@ pub fn run_tui(
 
         if needs_draw {
             terminal.draw(|f| {
+                // User theme overrides apply to the default palette; F2 still flips to light.
                 let palette = if theme_dark {
-                    ThemePalette::dark()
+                    user_theme.palette()
                 } else {
                     ThemePalette::light()
                 };
+                let themed = |component: ThemeComponent| {
+                    if theme_dark {
+                        user_theme.component(component, palette)
+                    } else {
+                        palette
+                    }
+                };
 
                 let chunks = Layout::default()
                     .direction(Direction::Vertical)
@@ -3085,7 +3435,13 @@ pub fn run_tui(
                     .split(chunks[0]);
 
                 let chips = chips_for_filters(&filters, palette);
-                let sb = search_bar(&bar_text, palette, input_mode, mode_label, chips);
+                let sb = search_bar(
+                    &bar_text,
+                    themed(ThemeComponent::Widgets),
+                    input_mode,
+                    mode_label,
+                    chips,
+                );
                 f.render_widget(sb, search_split[0]);
 
                 let mut pill_vec: Vec<Pill> = Vec::new();
@@ -3127,7 +3483,8 @@ pub fn run_tui(
                     });
                 }
                 // Render pills and record their rects for click handling
-                let pill_rects = pills::draw_pills(f, search_split[1], &pill_vec, palette);
+                let pill_rects =
+                    pills::draw_pills(f, search_split[1], &pill_vec, themed(ThemeComponent::Pills));
                 last_pill_rects = pill_rects
                     .into_iter()
                     .zip(pill_vec.iter().cloned())
@@ -3139,10 +3496,47 @@ pub fn run_tui(
                     search_split[2],
                     &filters,
                     ranking_mode,
-                    palette,
+                    themed(ThemeComponent::Breadcrumbs),
                 );
                 last_breadcrumb_rects = bc_rects;
 
+                // Tab strip, shown once a second search tab is open
+                let body_area = if tabs.len() > 1 {
+                    let tab_split = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+                        .split(chunks[1]);
+                    let tab_titles: Vec<Line> = tabs
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, tab)| {
+                            let label = if idx == active_tab {
+                                tab_label(&query)
+                            } else {
+                                tab_label(&tab.query)
+                            };
+                            Line::from(Span::styled(
+                                format!("{} {label}", idx + 1),
+                                Style::default().fg(palette.hint),
+                            ))
+                        })
+                        .collect();
+                    f.render_widget(
+                        Tabs::new(tab_titles)
+                            .select(active_tab)
+                            .highlight_style(
+                                Style::default()
+                                    .fg(palette.accent)
+                                    .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                            )
+                            .divider(" │ "),
+                        tab_split[0],
+                    );
+                    tab_split[1]
+                } else {
+                    chunks[1]
+                };
+
                 // Responsive layout: detail pane expands when focused
                 let (results_pct, detail_pct) = match focus_region {
                     FocusRegion::Results => (70, 30),
@@ -3157,10 +3551,21 @@ pub fn run_tui(
                         ]
                         .as_ref(),
                     )
-                    .split(chunks[1]);
+                    .split(body_area);
 
                 let results_area = main_split[0];
-                let detail_area = main_split[1];
+                // Split view: live detail on the left, pinned conversation on the right
+                let (detail_area, pinned_area) = if split_detail.is_some() {
+                    let cols = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints(
+                            [Constraint::Percentage(50), Constraint::Percentage(50)].as_ref(),
+                        )
+                        .split(main_split[1]);
+                    (cols[0], Some(cols[1]))
+                } else {
+                    (main_split[1], None)
+                };
 
                 // Border style toggle: unicode rounded vs plain ASCII
                 let border_type = if fancy_borders {
@@ -3169,8 +3574,8 @@ pub fn run_tui(
                     BorderType::Plain
                 };
 
-                // Save layout for mouse hit testing
-                last_detail_area = Some(detail_area);
+                // Save layout for mouse hit testing (both halves of a split scroll together)
+                last_detail_area = Some(main_split[1]);
 
                 if panes.is_empty() {
                     // Clear pane rects when no panes (avoid stale click detection)
@@ -3405,7 +3810,10 @@ pub fn run_tui(
                                     format!("@{} ", pane.agent),
                                     Style::default().fg(palette.hint),
                                 ));
-                                header_spans.extend(score_indicator(hit.score, palette));
+                                header_spans.extend(score_indicator(
+                                    hit.score,
+                                    themed(ThemeComponent::Widgets),
+                                ));
                                 header_spans.push(Span::raw(" "));
                                 header_spans.push(Span::styled(
                                     title.to_string(),
@@ -3801,6 +4209,15 @@ pub fn run_tui(
                         Span::styled("Source: ", Style::default().fg(palette.hint)),
                         Span::raw(truncate_path(&hit.source_path, 60)),
                     ]));
+                    // User tags (`cass tag add`) as inline pills
+                    if let Some(ref d) = detail
+                        && !d.tags.is_empty()
+                    {
+                        let mut spans =
+                            vec![Span::styled("Tags: ", Style::default().fg(palette.hint))];
+                        spans.extend(pills::tag_spans(&d.tags, themed(ThemeComponent::Pills)));
+                        meta_lines.push(Line::from(spans));
+                    }
                     meta_lines.push(Line::from(vec![
                         Span::styled("Score: ", Style::default().fg(palette.hint)),
                         Span::raw(format!("{:.2}", hit.score)),
@@ -4019,6 +4436,34 @@ pub fn run_tui(
                     );
                 }
 
+                if let (Some(area), Some((source_path, pinned))) =
+                    (pinned_area, split_detail.as_ref())
+                {
+                    let scroll_badge = if detail_scroll > 0 {
+                        format!(" ↓{detail_scroll}")
+                    } else {
+                        String::new()
+                    };
+                    let pinned_para =
+                        Paragraph::new(render_parsed_content(pinned, &last_query, palette))
+                            .wrap(Wrap { trim: false })
+                            .scroll((detail_scroll, 0))
+                            .block(
+                                Block::default()
+                                    .title(Span::styled(
+                                        format!(
+                                            "Pinned: {}{scroll_badge} • Alt+V close",
+                                            pinned_title(pinned, source_path)
+                                        ),
+                                        Style::default().fg(palette.accent_alt),
+                                    ))
+                                    .borders(Borders::ALL)
+                                    .border_type(border_type)
+                                    .border_style(Style::default().fg(palette.hint)),
+                            );
+                    f.render_widget(pinned_para, area);
+                }
+
                 // Footer: status + modes + dense shortcut legend
                 let mut footer_parts: Vec<String> = vec![];
                 if dirty_since.is_some() {
@@ -4237,7 +4682,7 @@ pub fn run_tui(
                         f,
                         footer_split[2],
                         &shortcuts,
-                        palette,
+                        themed(ThemeComponent::HelpStrip),
                         help_pinned,
                     );
                 } else {
@@ -4374,6 +4819,11 @@ pub fn run_tui(
                     render_detail_modal(f, detail, hit, modal_highlight, palette, modal_scroll);
                 }
 
+                if let Some(r) = reader.as_mut() {
+                    let area = f.area();
+                    r.draw(f, area, palette, theme_dark);
+                }
+
                 // Bulk action modal
                 if show_bulk_modal {
                     let area = centered_rect(50, 30, f.area());
@@ -4420,7 +4870,7 @@ pub fn run_tui(
 
                 // HTML export modal
                 if show_export_modal && let Some(ref state) = export_modal_state {
-                    render_export_modal(f, state, palette);
+                    render_export_modal(f, state, themed(ThemeComponent::ExportModal));
                 }
 
                 // Model download consent dialog
@@ -4575,11 +5025,11 @@ pub fn run_tui(
 
                 if palette_state.open {
                     let area = centered_rect(70, 60, f.area());
-                    palette::draw_palette(f, area, &palette_state, palette);
+                    palette::draw_palette(f, area, &palette_state, themed(ThemeComponent::Palette));
                 }
 
                 // Render toast notifications (bead 2yg2)
-                render_toasts(f, &toast_manager, &palette);
+                render_toasts(f, &toast_manager, &themed(ThemeComponent::Toast));
             })?;
             needs_draw = false;
         }
@@ -4598,6 +5048,15 @@ pub fn run_tui(
 
             // Handle mouse events (skip when modal is open)
             if let Event::Mouse(mouse) = event {
+                if let Some(r) = reader.as_mut() {
+                    match mouse.kind {
+                        MouseEventKind::ScrollUp => r.scroll_by(-3),
+                        MouseEventKind::ScrollDown => r.scroll_by(3),
+                        _ => {}
+                    }
+                    needs_draw = true;
+                    continue;
+                }
                 // Ignore mouse events when help, detail, bulk, or source filter modal is open
                 if show_help || show_detail_modal || show_bulk_modal || source_filter_menu_open {
                     continue;
@@ -4745,7 +5204,7 @@ pub fn run_tui(
             }
 
             // Handle key events
-            let Event::Key(key) = event else {
+            let Event::Key(mut key) = event else {
                 continue;
             };
 
@@ -4762,6 +5221,14 @@ pub fn run_tui(
                 break;
             }
 
+            // The reader is full-screen and owns the keyboard while open
+            if let Some(r) = reader.as_mut() {
+                if !r.handle_key(&key) {
+                    reader = None;
+                }
+                continue;
+            }
+
             // Update banner keybindings (bead 018)
             // Only active when banner is visible and no modals are open
             if let Some(ref info) = update_info
@@ -4799,154 +5266,170 @@ pub fn run_tui(
                 }
             }
 
+            // User keymap (tui.toml) applies to the main view only; modals keep their keys.
+            let mut bound_palette_action = None;
+            if input_mode == InputMode::Query
+                && !palette_state.open
+                && !show_consent_dialog
+                && download_cancel.is_none()
+                && !show_bulk_modal
+                && !source_filter_menu_open
+                && !show_help
+                && !show_detail_modal
+                && !show_export_modal
+            {
+                match keymap.resolve(&key) {
+                    Resolved::Unchanged => {}
+                    Resolved::Key(mapped) => key = mapped,
+                    Resolved::Palette(action) => bound_palette_action = Some(action),
+                    Resolved::Unbound => continue,
+                }
+            }
+
             // Command palette handling takes precedence over help/detail.
-            if palette_state.open {
-                match key.code {
-                    KeyCode::Esc => {
-                        palette_state.open = false;
-                        palette_state.query.clear();
-                        palette_state.refilter();
-                    }
-                    KeyCode::Up => palette_state.move_selection(-1),
-                    KeyCode::Down => palette_state.move_selection(1),
-                    KeyCode::PageUp => palette_state.move_selection(-5),
-                    KeyCode::PageDown => palette_state.move_selection(5),
-                    KeyCode::Enter => {
-                        if let Some(item) = palette_state.filtered.get(palette_state.selected) {
-                            // UI metrics: log palette action (bead 020)
-                            if ui_metrics_enabled {
-                                tracing::info!(
-                                    target: "ui_metrics",
-                                    event = "palette_action",
-                                    action = item.label,
-                                    "Palette action executed"
-                                );
-                            }
-                            match item.action {
-                                PaletteAction::ToggleTheme => {
-                                    theme_dark = !theme_dark;
-                                }
-                                PaletteAction::ToggleDensity => {
-                                    density_mode = density_mode.next();
-                                    let height = terminal.size().map(|r| r.height).unwrap_or(24);
-                                    per_pane_limit = calculate_pane_limit(height, density_mode);
-                                    let prev_agent = active_hit(&panes, active_pane)
-                                        .map(|h| h.agent.clone())
-                                        .or_else(|| {
-                                            panes.get(active_pane).map(|p| p.agent.clone())
-                                        });
-                                    let prev_path = active_hit(&panes, active_pane)
-                                        .map(|h| h.source_path.clone());
-                                    panes = rebuild_panes_with_filter(
-                                        &results,
-                                        pane_filter.as_deref(),
-                                        per_pane_limit,
-                                        &mut active_pane,
-                                        &mut pane_scroll_offset,
-                                        prev_agent,
-                                        prev_path,
-                                        MAX_VISIBLE_PANES,
-                                    );
-                                    status = format!("Density: {}", density_mode.label());
-                                    needs_draw = true;
-                                }
-                                PaletteAction::ToggleHelpStrip => {
-                                    help_pinned = !help_pinned;
-                                }
-                                PaletteAction::OpenUpdateBanner => {
-                                    if let Some(ref info) = update_info {
-                                        if info.should_show() {
-                                            // Tear down TUI and run the installer
-                                            teardown_terminal().ok();
-                                            println!(
-                                                "Updating from v{} to v{}...\n",
-                                                info.current_version, info.latest_version
-                                            );
-                                            // This does not return - it execs the installer
-                                            run_self_update(&info.tag_name);
-                                        } else {
-                                            status = "You're on the latest version".to_string();
-                                        }
-                                    } else {
-                                        status = "No update information available yet".to_string();
-                                    }
-                                }
-                                PaletteAction::FilterAgent => {
-                                    input_mode = InputMode::Agent;
-                                    input_buffer.clear();
-                                }
-                                PaletteAction::FilterWorkspace => {
-                                    input_mode = InputMode::Workspace;
-                                    input_buffer.clear();
-                                    ensure_known_workspaces(
-                                        &mut db_reader,
-                                        &db_path,
-                                        &mut known_workspaces,
+            // Actions bound in the keymap run through the same dispatch.
+            if palette_state.open || bound_palette_action.is_some() {
+                let mut chosen = bound_palette_action;
+                if chosen.is_none() {
+                    match key.code {
+                        KeyCode::Esc => {
+                            palette_state.open = false;
+                            palette_state.query.clear();
+                            palette_state.refilter();
+                        }
+                        KeyCode::Up => palette_state.move_selection(-1),
+                        KeyCode::Down => palette_state.move_selection(1),
+                        KeyCode::PageUp => palette_state.move_selection(-5),
+                        KeyCode::PageDown => palette_state.move_selection(5),
+                        KeyCode::Enter => {
+                            if let Some(item) = palette_state.filtered.get(palette_state.selected) {
+                                // UI metrics: log palette action (bead 020)
+                                if ui_metrics_enabled {
+                                    tracing::info!(
+                                        target: "ui_metrics",
+                                        event = "palette_action",
+                                        action = item.label,
+                                        "Palette action executed"
                                     );
                                 }
-                                PaletteAction::FilterToday => {
-                                    if let Some((start, _)) = quick_date_range_today() {
-                                        filters.created_from = Some(start);
-                                        filters.created_to = None;
-                                        dirty_since = Some(Instant::now());
-                                    }
-                                }
-                                PaletteAction::FilterWeek => {
-                                    if let Some((start, _)) = quick_date_range_week() {
-                                        filters.created_from = Some(start);
-                                        filters.created_to = None;
-                                        dirty_since = Some(Instant::now());
-                                    }
-                                }
-                                PaletteAction::FilterCustomDate => {
-                                    input_mode = InputMode::CreatedFrom;
-                                    input_buffer.clear();
-                                    status = "Enter start date (YYYY-MM-DD)".to_string();
-                                }
-                                PaletteAction::OpenBulkActions => {
-                                    status = "Bulk actions: select with m, open with A".to_string();
-                                }
-                                PaletteAction::ReloadIndex => {
-                                    dirty_since = Some(Instant::now());
-                                }
-                                PaletteAction::OpenSavedViews => {
-                                    status =
-                                        "Saved views: Ctrl+<n> save, Shift+<n> load".to_string();
-                                }
-                                PaletteAction::SaveViewSlot(slot) => {
-                                    status = save_view_slot(
-                                        slot,
-                                        &filters,
-                                        ranking_mode,
-                                        &mut saved_views,
+                                chosen = Some(item.action.clone());
+                            }
+                        }
+                        KeyCode::Backspace => {
+                            palette_state.query.pop();
+                            palette_state.refilter();
+                        }
+                        KeyCode::Char(c) => {
+                            palette_state.query.push(c);
+                            palette_state.refilter();
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(action) = chosen {
+                    match action {
+                        PaletteAction::ToggleTheme => {
+                            theme_dark = !theme_dark;
+                        }
+                        PaletteAction::ToggleDensity => {
+                            density_mode = density_mode.next();
+                            let height = terminal.size().map(|r| r.height).unwrap_or(24);
+                            per_pane_limit = calculate_pane_limit(height, density_mode);
+                            let prev_agent = active_hit(&panes, active_pane)
+                                .map(|h| h.agent.clone())
+                                .or_else(|| panes.get(active_pane).map(|p| p.agent.clone()));
+                            let prev_path =
+                                active_hit(&panes, active_pane).map(|h| h.source_path.clone());
+                            panes = rebuild_panes_with_filter(
+                                &results,
+                                pane_filter.as_deref(),
+                                per_pane_limit,
+                                &mut active_pane,
+                                &mut pane_scroll_offset,
+                                prev_agent,
+                                prev_path,
+                                MAX_VISIBLE_PANES,
+                            );
+                            status = format!("Density: {}", density_mode.label());
+                            needs_draw = true;
+                        }
+                        PaletteAction::ToggleHelpStrip => {
+                            help_pinned = !help_pinned;
+                        }
+                        PaletteAction::OpenUpdateBanner => {
+                            if let Some(ref info) = update_info {
+                                if info.should_show() {
+                                    // Tear down TUI and run the installer
+                                    teardown_terminal().ok();
+                                    println!(
+                                        "Updating from v{} to v{}...\n",
+                                        info.current_version, info.latest_version
                                     );
+                                    // This does not return - it execs the installer
+                                    run_self_update(&info.tag_name);
+                                } else {
+                                    status = "You're on the latest version".to_string();
                                 }
-                                PaletteAction::LoadViewSlot(slot) => {
-                                    if let Some(msg) = load_view_slot(
-                                        slot,
-                                        &mut filters,
-                                        &mut ranking_mode,
-                                        &saved_views,
-                                    ) {
-                                        status = msg;
-                                        dirty_since = Some(Instant::now());
-                                    } else {
-                                        status = format!("No saved view in slot {slot}");
-                                    }
-                                }
+                            } else {
+                                status = "No update information available yet".to_string();
+                            }
+                        }
+                        PaletteAction::FilterAgent => {
+                            input_mode = InputMode::Agent;
+                            input_buffer.clear();
+                        }
+                        PaletteAction::FilterWorkspace => {
+                            input_mode = InputMode::Workspace;
+                            input_buffer.clear();
+                            ensure_known_workspaces(
+                                &mut db_reader,
+                                &db_path,
+                                &mut known_workspaces,
+                            );
+                        }
+                        PaletteAction::FilterToday => {
+                            if let Some((start, _)) = quick_date_range_today() {
+                                filters.created_from = Some(start);
+                                filters.created_to = None;
+                                dirty_since = Some(Instant::now());
+                            }
+                        }
+                        PaletteAction::FilterWeek => {
+                            if let Some((start, _)) = quick_date_range_week() {
+                                filters.created_from = Some(start);
+                                filters.created_to = None;
+                                dirty_since = Some(Instant::now());
+                            }
+                        }
+                        PaletteAction::FilterCustomDate => {
+                            input_mode = InputMode::CreatedFrom;
+                            input_buffer.clear();
+                            status = "Enter start date (YYYY-MM-DD)".to_string();
+                        }
+                        PaletteAction::OpenBulkActions => {
+                            status = "Bulk actions: select with m, open with A".to_string();
+                        }
+                        PaletteAction::ReloadIndex => {
+                            dirty_since = Some(Instant::now());
+                        }
+                        PaletteAction::OpenSavedViews => {
+                            status = "Saved views: Ctrl+<n> save, Shift+<n> load".to_string();
+                        }
+                        PaletteAction::SaveViewSlot(slot) => {
+                            status = save_view_slot(slot, &filters, ranking_mode, &mut saved_views);
+                        }
+                        PaletteAction::LoadViewSlot(slot) => {
+                            if let Some(msg) =
+                                load_view_slot(slot, &mut filters, &mut ranking_mode, &saved_views)
+                            {
+                                status = msg;
+                                dirty_since = Some(Instant::now());
+                            } else {
+                                status = format!("No saved view in slot {slot}");
                             }
-                            palette_state.open = false;
                         }
                     }
-                    KeyCode::Backspace => {
-                        palette_state.query.pop();
-                        palette_state.refilter();
-                    }
-                    KeyCode::Char(c) => {
-                        palette_state.query.push(c);
-                        palette_state.refilter();
-                    }
-                    _ => {}
+                    palette_state.open = false;
                 }
                 continue;
             }
@@ -5690,6 +6173,19 @@ pub fn run_tui(
                             status = "Quick export: Press Enter to confirm".to_string();
                         }
                     }
+                    KeyCode::Char('r') => {
+                        // Switch to the full conversation reader
+                        if let Some((_, ref detail)) = cached_detail {
+                            let term = detail_highlight_term(
+                                detail_find.as_ref(),
+                                pane_filter.as_deref(),
+                                &last_query,
+                            );
+                            reader = Some(ReaderState::new(detail.clone(), term));
+                            show_detail_modal = false;
+                            modal_scroll = 0;
+                        }
+                    }
                     KeyCode::Char('e') => {
                         // Open export modal
                         if let Some((_, ref detail)) = cached_detail
@@ -5992,9 +6488,143 @@ pub fn run_tui(
                 continue;
             }
 
+            // Toggle result granularity (Alt+G)
+            if matches!(key.code, KeyCode::Char('g' | 'G'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+            {
+                granularity = granularity.next();
+                page = 0;
+                status = match granularity {
+                    SearchGranularity::Message => "Results: one per message".to_string(),
+                    SearchGranularity::Session => {
+                        "Results: one per session (best match shown)".to_string()
+                    }
+                };
+                dirty_since = Some(Instant::now());
+                continue;
+            }
+
+            // Pin the selected conversation beside the detail pane, or close the split (Alt+V)
+            if matches!(key.code, KeyCode::Char('v' | 'V'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+                && matches!(input_mode, InputMode::Query)
+            {
+                if split_detail.take().is_some() {
+                    status = "Split view closed".to_string();
+                } else if let Some(hit) = active_hit(&panes, active_pane) {
+                    let view =
+                        hit_conversation(hit, cached_detail.as_ref(), &mut db_reader, &db_path);
+                    if let Some(view) = view {
+                        status = format!(
+                            "Pinned \"{}\" side by side; scrolling is synchronized (Alt+V to close)",
+                            pinned_title(&view, &hit.source_path)
+                        );
+                        split_detail = Some((hit.source_path.clone(), view));
+                    } else {
+                        status = "Conversation could not be loaded for split view".to_string();
+                    }
+                } else {
+                    status = "Select a result to pin it side by side".to_string();
+                }
+                continue;
+            }
+
+            // Open the full conversation reader (Alt+R)
+            if matches!(key.code, KeyCode::Char('r' | 'R'))
+                && key.modifiers.contains(KeyModifiers::ALT)
+                && matches!(input_mode, InputMode::Query)
+            {
+                if let Some(hit) = active_hit(&panes, active_pane) {
+                    if let Some(view) =
+                        hit_conversation(hit, cached_detail.as_ref(), &mut db_reader, &db_path)
+                    {
+                        let term = detail_highlight_term(
+                            detail_find.as_ref(),
+                            pane_filter.as_deref(),
+                            &last_query,
+                        );
+                        reader = Some(ReaderState::new(view, term));
+                    } else {
+                        status = "Conversation could not be loaded for the reader".to_string();
+                    }
+                } else {
+                    status = "Select a result to read the full conversation".to_string();
+                }
+                continue;
+            }
+
             match input_mode {
                 InputMode::Query => {
                     if key.modifiers.contains(KeyModifiers::CONTROL) {
+                        // Search tabs: Ctrl+T new, Ctrl+W close, Ctrl+PgDn/PgUp cycle
+                        let tab_changed = match key.code {
+                            KeyCode::Char('t' | 'T') => {
+                                if tabs.len() >= MAX_TABS {
+                                    status = format!("Tab limit reached ({MAX_TABS} tabs)");
+                                    continue;
+                                }
+                                tabs.push(SearchTab::new(ranking_mode));
+                                let to = tabs.len() - 1;
+                                switch_tab(
+                                    &mut tabs,
+                                    &mut active_tab,
+                                    to,
+                                    &mut query,
+                                    &mut filters,
+                                    &mut ranking_mode,
+                                );
+                                true
+                            }
+                            KeyCode::Char('w' | 'W') => {
+                                if !close_tab(
+                                    &mut tabs,
+                                    &mut active_tab,
+                                    &mut query,
+                                    &mut filters,
+                                    &mut ranking_mode,
+                                ) {
+                                    status = "Only one tab open".to_string();
+                                    continue;
+                                }
+                                true
+                            }
+                            KeyCode::PageDown | KeyCode::PageUp if tabs.len() > 1 => {
+                                let to = if key.code == KeyCode::PageDown {
+                                    (active_tab + 1) % tabs.len()
+                                } else {
+                                    (active_tab + tabs.len() - 1) % tabs.len()
+                                };
+                                switch_tab(
+                                    &mut tabs,
+                                    &mut active_tab,
+                                    to,
+                                    &mut query,
+                                    &mut filters,
+                                    &mut ranking_mode,
+                                );
+                                true
+                            }
+                            _ => false,
+                        };
+                        if tab_changed {
+                            page = 0;
+                            active_pane = 0;
+                            pane_scroll_offset = 0;
+                            selected.clear();
+                            open_confirm_armed = false;
+                            history_cursor = None;
+                            cached_detail = None;
+                            detail_scroll = 0;
+                            detail_find = None;
+                            dirty_since = Some(Instant::now());
+                            status = format!(
+                                "Tab {}/{}: {}",
+                                active_tab + 1,
+                                tabs.len(),
+                                tab_label(&query)
+                            );
+                            continue;
+                        }
                         if let KeyCode::Char(c) = key.code
                             && c.is_ascii_digit()
                             && c != '0'
@@ -7075,7 +7705,8 @@ pub fn run_tui(
                                 // Open full-screen detail modal for parsed viewing
                                 show_detail_modal = true;
                                 modal_scroll = 0;
-                                status = "Detail view · Esc close · c copy · n nano".to_string();
+                                status = "Detail view · Esc close · c copy · n nano · r reader"
+                                    .to_string();
                             } else if active_hit(&panes, active_pane).is_some() {
                                 // User committed to viewing a result - save query to history
                                 save_query_to_history(&query, &mut query_history, history_cap);
@@ -7469,14 +8100,23 @@ pub fn run_tui(
                     }
                     // Track effective search mode for ranking (bead vq8v)
                     let mut effective_search_mode = SearchMode::Lexical;
+                    // Session granularity pages over a deeper window of message hits
+                    // that is then collapsed to one row per conversation
+                    let (fetch_limit, fetch_offset) = match granularity {
+                        SearchGranularity::Message => (page_size, page * page_size),
+                        SearchGranularity::Session => (
+                            page_size * SESSION_OVERFETCH,
+                            page * page_size * SESSION_OVERFETCH,
+                        ),
+                    };
                     let search_result = match search_mode {
                         SearchMode::Hybrid if use_semantic => {
                             match client.search_hybrid(
                                 &lexical_query,
                                 &semantic_query,
                                 filters.clone(),
-                                page_size,
-                                page * page_size,
+                                fetch_limit,
+                                fetch_offset,
                                 SPARSE_THRESHOLD,
                                 crate::search::query::FieldMask::FULL,
                                 false,
@@ -7497,8 +8137,8 @@ pub fn run_tui(
                                     client.search_with_fallback(
                                         &lexical_query,
                                         filters.clone(),
-                                        page_size,
-                                        page * page_size,
+                                        fetch_limit,
+                                        fetch_offset,
                                         SPARSE_THRESHOLD,
                                         crate::search::query::FieldMask::FULL,
                                     )
@@ -7509,8 +8149,8 @@ pub fn run_tui(
                             match client.search_semantic(
                                 &semantic_query,
                                 filters.clone(),
-                                page_size,
-                                page * page_size,
+                                fetch_limit,
+                                fetch_offset,
                                 crate::search::query::FieldMask::FULL,
                                 false,
                             ) {
@@ -7537,8 +8177,8 @@ pub fn run_tui(
                                     client.search_with_fallback(
                                         &lexical_query,
                                         filters.clone(),
-                                        page_size,
-                                        page * page_size,
+                                        fetch_limit,
+                                        fetch_offset,
                                         SPARSE_THRESHOLD,
                                         crate::search::query::FieldMask::FULL,
                                     )
@@ -7548,8 +8188,8 @@ pub fn run_tui(
                         _ => client.search_with_fallback(
                             &lexical_query,
                             filters.clone(),
-                            page_size,
-                            page * page_size,
+                            fetch_limit,
+                            fetch_offset,
                             SPARSE_THRESHOLD,
                             crate::search::query::FieldMask::FULL,
                         ),
@@ -7569,7 +8209,17 @@ pub fn run_tui(
                                     "Search completed"
                                 );
                             }
-                            let hits = search_result.hits;
+                            let hits = match granularity {
+                                SearchGranularity::Message => search_result.hits,
+                                SearchGranularity::Session => group_hits_by_session(
+                                    search_result.hits,
+                                    DEFAULT_SESSION_EVIDENCE,
+                                    Utc::now().timestamp_millis(),
+                                )
+                                .iter()
+                                .filter_map(SessionHit::to_representative_hit)
+                                .collect(),
+                            };
                             cache_stats = if cache_debug {
                                 Some(search_result.cache_stats)
                             } else {
@@ -8029,6 +8679,11 @@ pub fn run_tui(
     if let Some(saved) = peek_window_saved.take() {
         context_window = saved;
     }
+    if let Some(current) = tabs.get_mut(active_tab) {
+        current.query = query;
+        current.filters = filters;
+        current.ranking = ranking_mode;
+    }
 
     let persisted_out = TuiStatePersisted {
         match_mode: Some(match match_mode {
@@ -8058,14 +8713,7 @@ pub fn run_tui(
                     workspaces: v.workspaces.iter().cloned().collect(),
                     created_from: v.created_from,
                     created_to: v.created_to,
-                    ranking: Some(match v.ranking {
-                        RankingMode::RecentHeavy => "recent".into(),
-                        RankingMode::RelevanceHeavy => "relevance".into(),
-                        RankingMode::MatchQualityHeavy => "quality".into(),
-                        RankingMode::DateNewest => "newest".into(),
-                        RankingMode::DateOldest => "oldest".into(),
-                        RankingMode::Balanced => "balanced".into(),
-                    }),
+                    ranking: Some(ranking_to_str(v.ranking).into()),
                     source_filter: if v.source_filter.is_all() {
                         None
                     } else {
@@ -8076,14 +8724,10 @@ pub fn run_tui(
         ),
         // Persist pane count & ranking mode (bead 46t.1)
         per_pane_limit: Some(per_pane_limit),
-        ranking_mode: Some(match ranking_mode {
-            RankingMode::RecentHeavy => "recent".into(),
-            RankingMode::RelevanceHeavy => "relevance".into(),
-            RankingMode::MatchQualityHeavy => "quality".into(),
-            RankingMode::DateNewest => "newest".into(),
-            RankingMode::DateOldest => "oldest".into(),
-            RankingMode::Balanced => "balanced".into(),
-        }),
+        ranking_mode: Some(ranking_to_str(ranking_mode).into()),
+        granularity: Some(granularity.as_str().into()),
+        tabs: (tabs.len() > 1).then(|| tabs.iter().map(tab_to_persisted).collect()),
+        active_tab: (tabs.len() > 1).then_some(active_tab),
     };
     save_state(&state_path, &persisted_out);
 
@@ -8150,12 +8794,27 @@ mod tests {
             }]),
             per_pane_limit: Some(12),
             ranking_mode: Some("balanced".into()),
+            granularity: Some("session".into()),
+            tabs: Some(vec![
+                tab_to_persisted(&SearchTab::new(RankingMode::Balanced)),
+                SearchTabPersisted {
+                    query: "auth bug".into(),
+                    agents: vec!["codex".into()],
+                    workspaces: vec![],
+                    created_from: None,
+                    created_to: None,
+                    ranking: Some("newest".into()),
+                    source_filter: None,
+                },
+            ]),
+            active_tab: Some(1),
         };
         save_state(&path, &state);
 
         let loaded = load_state(&path);
         assert_eq!(loaded.match_mode.as_deref(), Some("prefix"));
         assert_eq!(loaded.search_mode.as_deref(), Some("hybrid"));
+        assert_eq!(loaded.granularity.as_deref(), Some("session"));
         assert_eq!(loaded.context_window.as_deref(), Some("XL"));
         assert_eq!(loaded.has_seen_help, Some(true));
         assert_eq!(
@@ -8166,6 +8825,137 @@ mod tests {
         // Verify new fields (bead 46t.1)
         assert_eq!(loaded.per_pane_limit, Some(12));
         assert_eq!(loaded.ranking_mode.as_deref(), Some("balanced"));
+        assert_eq!(loaded.tabs.as_ref().map(std::vec::Vec::len), Some(2));
+        assert_eq!(loaded.active_tab, Some(1));
+    }
+
+    #[test]
+    fn switch_tab_parks_live_state_and_loads_target() {
+        let mut tabs = vec![
+            SearchTab::new(RankingMode::Balanced),
+            SearchTab {
+                query: "flaky test".into(),
+                filters: SearchFilters {
+                    agents: HashSet::from(["codex".to_string()]),
+                    ..SearchFilters::default()
+                },
+                ranking: RankingMode::DateNewest,
+            },
+        ];
+        let mut active = 0;
+        let mut query = "auth".to_string();
+        let mut filters = SearchFilters {
+            workspaces: HashSet::from(["/repo".to_string()]),
+            ..SearchFilters::default()
+        };
+        let mut ranking = RankingMode::RecentHeavy;
+
+        switch_tab(
+            &mut tabs,
+            &mut active,
+            1,
+            &mut query,
+            &mut filters,
+            &mut ranking,
+        );
+        assert_eq!(active, 1);
+        assert_eq!(query, "flaky test");
+        assert!(filters.agents.contains("codex"));
+        assert!(filters.workspaces.is_empty());
+        assert_eq!(ranking, RankingMode::DateNewest);
+        assert_eq!(tabs[0].query, "auth");
+        assert!(tabs[0].filters.workspaces.contains("/repo"));
+        assert_eq!(tabs[0].ranking, RankingMode::RecentHeavy);
+
+        switch_tab(
+            &mut tabs,
+            &mut active,
+            0,
+            &mut query,
+            &mut filters,
+            &mut ranking,
+        );
+        assert_eq!(query, "auth");
+        assert_eq!(ranking, RankingMode::RecentHeavy);
+    }
+
+    #[test]
+    fn close_tab_keeps_at_least_one_tab() {
+        let mut tabs = vec![
+            SearchTab::new(RankingMode::Balanced),
+            SearchTab {
+                query: "second".into(),
+                filters: SearchFilters::default(),
+                ranking: RankingMode::DateOldest,
+            },
+        ];
+        let mut active = 1;
+        let mut query = "second (edited)".to_string();
+        let mut filters = SearchFilters::default();
+        let mut ranking = RankingMode::DateOldest;
+
+        assert!(close_tab(
+            &mut tabs,
+            &mut active,
+            &mut query,
+            &mut filters,
+            &mut ranking
+        ));
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(active, 0);
+        assert_eq!(query, "");
+        assert_eq!(ranking, RankingMode::Balanced);
+
+        assert!(!close_tab(
+            &mut tabs,
+            &mut active,
+            &mut query,
+            &mut filters,
+            &mut ranking
+        ));
+        assert_eq!(tabs.len(), 1);
+    }
+
+    #[test]
+    fn restore_tabs_round_trips_and_clamps_active() {
+        use crate::sources::provenance::SourceFilter;
+
+        let tab = SearchTab {
+            query: "deploy".into(),
+            filters: SearchFilters {
+                agents: HashSet::from(["claude_code".to_string()]),
+                created_from: Some(10),
+                source_filter: SourceFilter::Local,
+                ..SearchFilters::default()
+            },
+            ranking: RankingMode::RelevanceHeavy,
+        };
+        let persisted = vec![tab_to_persisted(&tab), tab_to_persisted(&tab)];
+
+        let (tabs, active) =
+            restore_tabs(Some(persisted.as_slice()), Some(7), RankingMode::Balanced);
+        assert_eq!(tabs.len(), 2);
+        assert_eq!(active, 1);
+        assert_eq!(tabs[1].query, "deploy");
+        assert!(tabs[1].filters.agents.contains("claude_code"));
+        assert_eq!(tabs[1].filters.created_from, Some(10));
+        assert_eq!(tabs[1].filters.source_filter, SourceFilter::Local);
+        assert_eq!(tabs[1].ranking, RankingMode::RelevanceHeavy);
+
+        let (tabs, active) = restore_tabs(None, None, RankingMode::DateNewest);
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(active, 0);
+        assert_eq!(tabs[0].ranking, RankingMode::DateNewest);
+    }
+
+    #[test]
+    fn tab_label_truncates_long_queries() {
+        assert_eq!(tab_label("  "), "(all)");
+        assert_eq!(tab_label("auth"), "auth");
+        assert_eq!(
+            tab_label("a very long query about migrations"),
+            "a very long query…"
+        );
     }
 
     #[test]
@@ -8283,6 +9073,7 @@ mod tests {
             convo,
             messages: vec![message],
             workspace: None,
+            tags: Vec::new(),
         };
 
         let lines = render_parsed_content(&detail, "", palette);
@@ -4,6 +4,7 @@
 
 use coding_agent_search::bakeoff::{
     EvaluationConfig, EvaluationCorpus, EvaluationHarness, ModelMetadata, format_comparison_table,
+    format_quantization_table,
 };
 use coding_agent_search::search::embedder::{Embedder, EmbedderError, EmbedderResult};
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -394,3 +395,29 @@ fn test_ineligible_by_date() {
         "Model before cutoff should not be eligible"
     );
 }
+
+#[test]
+fn test_quantization_bakeoff() {
+    let harness = EvaluationHarness::new();
+    let corpus = EvaluationCorpus::code_search_sample();
+    let embedder = QualityMockEmbedder::new("quality", 64);
+
+    let reports = harness
+        .evaluate_quantization(&embedder, &corpus)
+        .expect("should succeed");
+    assert_eq!(reports.len(), 6);
+
+    let f32_report = reports
+        .iter()
+        .find(|r| r.quantization == "f32")
+        .expect("f32 baseline");
+    assert!((f32_report.recall_at_k - 1.0).abs() < 1e-9);
+    for report in &reports {
+        assert!(report.index_bytes <= f32_report.index_bytes);
+        assert_eq!(report.rescored, report.rescore_bytes > 0);
+    }
+
+    let table = format_quantization_table(&reports);
+    assert!(table.contains("int8"));
+    assert!(table.contains("binary"));
+}
@@ -748,10 +748,602 @@ fn parse_export_html_with_encrypt() {
             password_stdin,
             ..
         }) => {
-            assert_eq!(session.to_str().unwrap(), "/path/to/session.jsonl");
+            assert_eq!(
+                session.as_deref().and_then(|p| p.to_str()),
+                Some("/path/to/session.jsonl")
+            );
             assert!(encrypt);
             assert!(password_stdin);
         }
         other => panic!("expected export-html command, got {other:?}"),
     }
 }
+
+#[test]
+fn parse_export_html_multi_session_selection() {
+    let cli = Cli::try_parse_from([
+        "cass",
+        "export-html",
+        "--workspace",
+        "/work/app",
+        "--tag",
+        "auth",
+        "--tag",
+        "bug",
+        "--since",
+        "7d",
+        "--limit",
+        "10",
+    ])
+    .expect("parse multi-session export-html");
+    match cli.command {
+        Some(Commands::ExportHtml {
+            session,
+            workspace,
+            tag,
+            since,
+            limit,
+            ..
+        }) => {
+            assert!(session.is_none());
+            assert_eq!(workspace, vec!["/work/app".to_string()]);
+            assert_eq!(tag, vec!["auth".to_string(), "bug".to_string()]);
+            assert_eq!(since.as_deref(), Some("7d"));
+            assert_eq!(limit, 10);
+        }
+        other => panic!("expected export-html command, got {other:?}"),
+    }
+
+    // A selection is required when no session path is given, and the two
+    // modes are exclusive.
+    assert!(Cli::try_parse_from(["cass", "export-html"]).is_err());
+    assert!(
+        Cli::try_parse_from([
+            "cass",
+            "export-html",
+            "/path/to/session.jsonl",
+            "--query",
+            "auth"
+        ])
+        .is_err()
+    );
+}
+
+// =============================================================================
+// Bookmarks subcommand tests
+// =============================================================================
+
+#[test]
+fn bookmarks_list_json_on_empty_store() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["bookmarks", "list", "--json", "--data-dir"])
+        .arg(&data_dir);
+
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid bookmarks list json");
+    assert_eq!(json["count"], 0);
+    assert_eq!(json["stale"], 0);
+    assert!(json["bookmarks"].as_array().is_some_and(|b| b.is_empty()));
+    assert!(data_dir.join("bookmarks.db").exists());
+}
+
+#[test]
+fn bookmarks_add_without_index_reports_missing_db() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "bookmarks",
+        "add",
+        "/path/to/session.jsonl",
+        "--json",
+        "--data-dir",
+    ])
+    .arg(&data_dir);
+    cmd.assert().code(3);
+}
+
+#[test]
+fn bookmarks_resolve_without_index_reports_missing_db() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["bookmarks", "resolve", "--json", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().code(3);
+}
+
+#[test]
+fn bookmarks_export_honors_robot_format() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let out = tmp.path().join("bookmarks.json");
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["bookmarks", "export", "--robot-format", "jsonl", "-o"])
+        .arg(&out)
+        .arg("--data-dir")
+        .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim().lines().count(), 1, "{stdout}");
+    let json: Value = serde_json::from_str(stdout.trim()).expect("valid export summary");
+    assert_eq!(json["exported"], 0);
+    assert!(out.exists());
+}
+
+#[test]
+fn parse_bookmarks_add_with_line_and_tags() {
+    let cli = Cli::try_parse_from([
+        "cass",
+        "bookmarks",
+        "add",
+        "/path/to/session.jsonl",
+        "-n",
+        "42",
+        "--tags",
+        "auth,bug",
+        "--robot-format",
+        "jsonl",
+    ])
+    .expect("parse bookmarks add");
+    match cli.command {
+        Some(Commands::Bookmarks(coding_agent_search::BookmarksCommand::Add {
+            source_path,
+            line,
+            tags,
+            robot_format,
+            ..
+        })) => {
+            assert_eq!(source_path.to_str().unwrap(), "/path/to/session.jsonl");
+            assert_eq!(line, Some(42));
+            assert_eq!(tags.as_deref(), Some("auth,bug"));
+            assert_eq!(robot_format, Some(coding_agent_search::RobotFormat::Jsonl));
+        }
+        other => panic!("expected bookmarks add command, got {other:?}"),
+    }
+}
+
+// =============================================================================
+// Saved search subcommand tests
+// =============================================================================
+
+#[test]
+fn saved_add_list_remove_roundtrip() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "saved",
+        "add",
+        "drop-table",
+        "\"DROP TABLE\"",
+        "--agent",
+        "codex",
+        "--alert-jsonl",
+        "alerts.jsonl",
+        "--json",
+        "--data-dir",
+    ])
+    .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid saved add json");
+    assert_eq!(json["replaced"], false);
+    assert_eq!(json["saved_search"]["name"], "drop-table");
+    assert_eq!(json["saved_search"]["filters"]["agents"][0], "codex");
+    assert_eq!(json["saved_search"]["mode"], "lexical");
+    assert!(data_dir.join("saved_searches.json").exists());
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "list", "--json", "--data-dir"])
+        .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid saved list json");
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["saved_searches"][0]["query"], "\"DROP TABLE\"");
+    let alert_path = json["saved_searches"][0]["alert"]["jsonl"]
+        .as_str()
+        .expect("alert path");
+    assert!(Path::new(alert_path).is_absolute());
+    assert!(alert_path.ends_with("alerts.jsonl"));
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "remove", "drop-table", "--json", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().success();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "remove", "drop-table", "--json", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().code(4);
+}
+
+#[test]
+fn saved_add_rejects_invalid_name() {
+    let tmp = TempDir::new().unwrap();
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "add", "../oops", "query", "--json", "--data-dir"])
+        .arg(tmp.path().join("data"));
+    cmd.assert().code(2);
+}
+
+#[test]
+fn saved_run_without_index_reports_missing_index() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "add", "rate-limit", "rate limit", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().success();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "saved",
+        "run",
+        "rate-limit",
+        "--new",
+        "--json",
+        "--data-dir",
+    ])
+    .arg(&data_dir);
+    cmd.assert().code(3);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["saved", "run", "missing", "--json", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().code(4);
+}
+
+// =============================================================================
+// Tool event commands (files / commands)
+// =============================================================================
+
+/// Write a database with one session that edits a file and runs a failing command.
+fn seed_tool_events_db(data_dir: &Path) {
+    use coding_agent_search::model::types::{Agent, AgentKind, Conversation, Message, MessageRole};
+    use coding_agent_search::storage::sqlite::SqliteStorage;
+
+    let mut storage = SqliteStorage::open(&data_dir.join("agent_search.db")).unwrap();
+    let agent_id = storage
+        .ensure_agent(&Agent {
+            id: None,
+            slug: "claude_code".into(),
+            name: "Claude Code".into(),
+            version: None,
+            kind: AgentKind::Cli,
+        })
+        .unwrap();
+    let conv = Conversation {
+        id: None,
+        agent_slug: "claude_code".into(),
+        workspace: Some("/ws/demo".into()),
+        external_id: Some("tool-events".into()),
+        title: Some("Fix the build".into()),
+        source_path: "/logs/tool-events.jsonl".into(),
+        started_at: Some(1_700_000_000_000),
+        ended_at: None,
+        approx_tokens: None,
+        metadata_json: serde_json::json!({}),
+        messages: vec![Message {
+            id: None,
+            idx: 0,
+            role: MessageRole::Agent,
+            author: None,
+            created_at: Some(1_700_000_000_000),
+            content: "[Tool: Edit - /ws/demo/src/foo.rs]".into(),
+            extra_json: serde_json::json!({
+                "message": {"content": [
+                    {"type": "tool_use", "id": "t1", "name": "Edit", "input": {"file_path": "/ws/demo/src/foo.rs"}},
+                    {"type": "tool_use", "id": "t2", "name": "Bash", "input": {"command": "cargo build"}}
+                ]},
+                "cass_tool_results": [{"id": "t2", "is_error": true, "exit_code": 101}]
+            }),
+            snippets: vec![],
+        }],
+        source_id: "local".into(),
+        origin_host: None,
+    };
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+}
+
+#[test]
+fn files_touched_lists_sessions_that_edited_file() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["files", "--touched", "src/foo.rs", "--json", "--data-dir"])
+        .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid files json");
+    assert_eq!(json["total_sessions"], 1);
+    assert_eq!(
+        json["sessions"][0]["source_path"],
+        "/logs/tool-events.jsonl"
+    );
+    assert_eq!(json["sessions"][0]["edits"], 1);
+    assert_eq!(json["sessions"][0]["events"][0]["line_number"], 1);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["files", "--json", "--data-dir"]).arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid files json");
+    assert_eq!(json["files"][0]["path"], "/ws/demo/src/foo.rs");
+}
+
+#[test]
+fn commands_failed_reports_exit_codes() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["commands", "--failed", "--json", "--data-dir"])
+        .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid commands json");
+    assert_eq!(json["count"], 1);
+    assert_eq!(json["commands"][0]["command"], "cargo build");
+    assert_eq!(json["commands"][0]["exit_code"], 101);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["commands", "--grep", "npm", "--json", "--data-dir"])
+        .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid commands json");
+    assert_eq!(json["count"], 0);
+}
+
+#[test]
+fn search_rejects_unknown_roles_and_invalid_tags() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    for args in [&["cargo", "--role", "robot"][..], &["cargo role:robot"]] {
+        let mut cmd = base_cmd(tmp.path());
+        cmd.arg("search")
+            .args(args)
+            .args(["--json", "--data-dir"])
+            .arg(&data_dir);
+        let output = cmd.assert().failure().code(2).get_output().clone();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("unknown role 'robot'"), "{stderr}");
+    }
+
+    for args in [&["cargo", "--tag", "two words"][..], &["cargo tag:a:b"]] {
+        let mut cmd = base_cmd(tmp.path());
+        cmd.arg("search")
+            .args(args)
+            .args(["--json", "--data-dir"])
+            .arg(&data_dir);
+        let output = cmd.assert().failure().code(2).get_output().clone();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("invalid tag name"), "{stderr}");
+    }
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "search",
+        "cargo",
+        "--role",
+        "assistant",
+        "--json",
+        "--data-dir",
+    ])
+    .arg(&data_dir);
+    cmd.assert().success();
+}
+
+#[test]
+fn related_ranks_sessions_that_edited_file() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args([
+        "related",
+        "--file",
+        "src/foo.rs",
+        "--no-semantic",
+        "--json",
+        "--data-dir",
+    ])
+    .arg(&data_dir);
+    let output = cmd.assert().success().get_output().clone();
+    let json: Value = serde_json::from_slice(&output.stdout).expect("valid related json");
+    assert_eq!(json["count"], 1);
+    assert_eq!(
+        json["sessions"][0]["source_path"],
+        "/logs/tool-events.jsonl"
+    );
+    assert_eq!(json["sessions"][0]["edits"], 1);
+    assert_eq!(json["signals"]["semantic"], false);
+}
+
+#[test]
+fn commands_without_index_reports_missing_db() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["commands", "--json", "--data-dir"])
+        .arg(&data_dir);
+    cmd.assert().code(3);
+}
+
+#[test]
+fn mcp_serves_tools_over_stdio() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18"}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"stats","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"search","arguments":{"query":"cargo"}}}"#,
+    ];
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["mcp", "--data-dir"])
+        .arg(&data_dir)
+        .write_stdin(requests.join("\n"));
+    let output = cmd.assert().success().get_output().clone();
+    let responses: Vec<Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each stdout line is a JSON-RPC message"))
+        .collect();
+    assert_eq!(responses.len(), 4, "notifications get no response");
+
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    let tools = responses[1]["result"]["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), 6);
+
+    let stats = &responses[2]["result"];
+    assert_eq!(stats["isError"], false);
+    assert_eq!(stats["structuredContent"]["conversations"], 1);
+
+    // Without a Tantivy index, search falls back to the SQLite FTS table.
+    let search = &responses[3]["result"];
+    assert_eq!(search["isError"], false);
+    assert_eq!(search["structuredContent"]["query"], "cargo");
+    assert!(search["structuredContent"]["hits"].is_array());
+}
+
+#[test]
+fn search_routes_through_running_daemon() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+    let socket = tmp.path().join("daemon.sock");
+    let log_path = tmp.path().join("daemon.log");
+
+    let mut daemon = std::process::Command::new(assert_cmd::cargo::cargo_bin!("cass"))
+        .args(["daemon", "--socket"])
+        .arg(&socket)
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .env("HOME", tmp.path())
+        .env("NO_COLOR", "1")
+        .stderr(fs::File::create(&log_path).unwrap())
+        .spawn()
+        .expect("spawn cass daemon");
+    for _ in 0..100 {
+        if socket.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    let search = |extra: &[&str]| -> Value {
+        let mut cmd = base_cmd(tmp.path());
+        cmd.env("CASS_DAEMON_SOCKET", &socket)
+            .args(["search", "cargo", "--json", "--data-dir"])
+            .arg(&data_dir)
+            .args(extra);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        serde_json::from_slice(&output).expect("search --json output")
+    };
+    let routed = search(&[]);
+    let local = search(&["--no-daemon"]);
+    let _ = daemon.kill();
+    let _ = daemon.wait();
+
+    assert_eq!(routed["hits"], local["hits"]);
+    let log = fs::read_to_string(&log_path).unwrap();
+    assert!(
+        log.contains("Opened resident search index"),
+        "search should have been served by the daemon; log:\n{log}"
+    );
+}
+
+#[test]
+fn serve_answers_http_requests() {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    seed_tool_events_db(&data_dir);
+
+    let mut server = std::process::Command::new(assert_cmd::cargo::cargo_bin!("cass"))
+        .args([
+            "serve",
+            "--bind",
+            "127.0.0.1:0",
+            "--token",
+            "t0k",
+            "--data-dir",
+        ])
+        .arg(&data_dir)
+        .env("HOME", tmp.path())
+        .env("NO_COLOR", "1")
+        .env("CODING_AGENT_SEARCH_NO_UPDATE_PROMPT", "1")
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn cass serve");
+    let mut stderr = BufReader::new(server.stderr.take().unwrap());
+    let mut addr = None;
+    let mut line = String::new();
+    while addr.is_none() && stderr.read_line(&mut line).unwrap() > 0 {
+        addr = line
+            .split("http://")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(str::to_string);
+        line.clear();
+    }
+    let addr = addr.expect("listening address on stderr");
+
+    let request = |head: &str| -> (String, Value) {
+        let mut stream = std::net::TcpStream::connect(&addr).unwrap();
+        write!(stream, "{head}\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut raw = String::new();
+        stream.read_to_string(&mut raw).unwrap();
+        let (status, body) = raw.split_once("\r\n\r\n").unwrap();
+        (status.to_string(), serde_json::from_str(body).unwrap())
+    };
+    let unauthorized = request("GET /v1/stats HTTP/1.1");
+    let stats = request("GET /v1/stats HTTP/1.1\r\nAuthorization: Bearer t0k");
+    let search =
+        request("GET /v1/search?query=cargo&fields=minimal HTTP/1.1\r\nAuthorization: Bearer t0k");
+    let _ = server.kill();
+    let _ = server.wait();
+
+    assert!(unauthorized.0.starts_with("HTTP/1.1 401"));
+    assert_eq!(unauthorized.1["error"]["kind"], "unauthorized");
+    assert!(stats.0.starts_with("HTTP/1.1 200"));
+    assert_eq!(stats.1["conversations"], 1);
+    assert!(search.0.starts_with("HTTP/1.1 200"));
+    assert_eq!(search.1["query"], "cargo");
+    assert!(search.1["hits"].is_array());
+    assert!(
+        search.1["_meta"].is_object(),
+        "search responses carry _meta"
+    );
+}
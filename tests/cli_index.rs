@@ -1,6 +1,6 @@
 use assert_cmd::Command;
 use clap::Parser;
-use coding_agent_search::{Cli, Commands};
+use coding_agent_search::{Cli, Commands, IndexQuantization};
 use predicates::str::contains;
 use std::fs;
 use tempfile::TempDir;
@@ -59,6 +59,28 @@ fn index_default_embedder_is_fastembed() {
     }
 }
 
+#[test]
+fn index_parses_quantization() {
+    let cli = Cli::try_parse_from(["cass", "index", "--semantic", "--quantization", "binary"])
+        .expect("parse index flags");
+    match cli.command {
+        Some(Commands::Index { quantization, .. }) => {
+            assert_eq!(quantization, IndexQuantization::Binary);
+        }
+        other => panic!("expected index command, got {other:?}"),
+    }
+
+    let cli = Cli::try_parse_from(["cass", "index", "--semantic"]).expect("parse index flags");
+    match cli.command {
+        Some(Commands::Index { quantization, .. }) => {
+            assert_eq!(quantization, IndexQuantization::F32);
+        }
+        other => panic!("expected index command, got {other:?}"),
+    }
+
+    assert!(Cli::try_parse_from(["cass", "index", "--quantization", "int4"]).is_err());
+}
+
 #[test]
 fn index_creates_db_and_index() {
     let tmp = TempDir::new().unwrap();
@@ -214,3 +214,44 @@ fn search_combines_mode_hybrid_and_approximate() {
         other => panic!("expected search command, got {other:?}"),
     }
 }
+
+#[test]
+fn search_parses_granularity_and_evidence() {
+    use coding_agent_search::search::query::SearchGranularity;
+
+    let cli = Cli::try_parse_from([
+        "cass",
+        "search",
+        "query",
+        "--granularity",
+        "session",
+        "--evidence",
+        "5",
+    ])
+    .expect("parse search flags");
+
+    match cli.command {
+        Some(Commands::Search {
+            granularity,
+            evidence,
+            ..
+        }) => {
+            assert_eq!(granularity, SearchGranularity::Session);
+            assert_eq!(evidence, 5);
+        }
+        other => panic!("expected search command, got {other:?}"),
+    }
+
+    let cli = Cli::try_parse_from(["cass", "search", "query"]).expect("parse search flags");
+    match cli.command {
+        Some(Commands::Search {
+            granularity,
+            evidence,
+            ..
+        }) => {
+            assert_eq!(granularity, SearchGranularity::Message);
+            assert_eq!(evidence, 3);
+        }
+        other => panic!("expected search command, got {other:?}"),
+    }
+}
@@ -0,0 +1,86 @@
+use coding_agent_search::connectors::continue_dev::ContinueConnector;
+use coding_agent_search::connectors::{Connector, ScanContext, ScanRoot};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+// See tests/fixtures/connectors/MANIFEST.json for provenance tracking.
+
+const SESSION_ID: &str = "7b0c4f5e-2a1d-4e8b-9f36-0d5a2c1b8e77";
+
+/// Copy the Continue fixture sessions into `dest`.
+fn copy_fixture_sessions(dest: &Path) {
+    let src = PathBuf::from("tests/fixtures/continue/sessions");
+    fs::create_dir_all(dest).unwrap();
+    for entry in fs::read_dir(&src).unwrap().flatten() {
+        fs::copy(entry.path(), dest.join(entry.file_name())).expect("copy fixture");
+    }
+}
+
+#[test]
+fn continue_parses_session_fixture() {
+    let tmp = TempDir::new().unwrap();
+    let sessions = tmp.path().join("fixture-continue/sessions");
+    copy_fixture_sessions(&sessions);
+
+    let conn = ContinueConnector::new();
+    let ctx = ScanContext {
+        data_dir: sessions.clone(),
+        scan_roots: Vec::new(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1, "sessions.json index is not a session");
+
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "continue");
+    assert_eq!(c.external_id.as_deref(), Some(SESSION_ID));
+    assert_eq!(c.title.as_deref(), Some("Debounce search input"));
+    assert_eq!(
+        c.workspace,
+        Some(PathBuf::from("/home/dev/projects/storefront"))
+    );
+    assert_eq!(c.started_at, Some(1_729_500_000_000));
+    assert!(c.ended_at.is_some());
+    assert_eq!(c.source_path, sessions.join(format!("{SESSION_ID}.json")));
+
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(
+        roles,
+        vec!["user", "assistant", "assistant", "user", "assistant"]
+    );
+    assert_eq!(c.messages[1].author.as_deref(), Some("thinking"));
+    assert!(c.messages[0].content.contains("debounce it in React"));
+    assert!(c.messages[4].content.contains("clearTimeout"));
+    for (i, m) in c.messages.iter().enumerate() {
+        assert_eq!(m.idx, i as i64);
+    }
+}
+
+#[test]
+fn continue_scan_roots_find_nested_sessions_dir() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path().join("home");
+    copy_fixture_sessions(&home.join(".continue/sessions"));
+
+    let conn = ContinueConnector::new();
+    let ctx = ScanContext::with_roots(tmp.path().join("data"), vec![ScanRoot::local(home)], None);
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].title.as_deref(), Some("Debounce search input"));
+}
+
+#[test]
+fn continue_skips_sessions_older_than_since_ts() {
+    let tmp = TempDir::new().unwrap();
+    let sessions = tmp.path().join("fixture-continue/sessions");
+    copy_fixture_sessions(&sessions);
+
+    let conn = ContinueConnector::new();
+    let ctx = ScanContext {
+        data_dir: sessions,
+        scan_roots: Vec::new(),
+        since_ts: Some(i64::MAX / 2),
+    };
+    assert!(conn.scan(&ctx).expect("scan").is_empty());
+}
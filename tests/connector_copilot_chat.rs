@@ -0,0 +1,99 @@
+use coding_agent_search::connectors::copilot_chat::CopilotChatConnector;
+use coding_agent_search::connectors::{Connector, ScanContext, ScanRoot};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+// See tests/fixtures/connectors/MANIFEST.json for provenance tracking.
+
+const SESSION_ID: &str = "c4d2e8f1-9a3b-4c7d-8e5f-1a2b3c4d5e6f";
+
+/// Copy the Copilot Chat fixture `User` dir into `dest`.
+fn copy_fixture_user_dir(dest: &Path) {
+    let src = PathBuf::from("tests/fixtures/copilot_chat/User");
+    for entry in WalkDir::new(&src).into_iter().flatten() {
+        let target = dest.join(entry.path().strip_prefix(&src).unwrap());
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target).unwrap();
+        } else {
+            fs::copy(entry.path(), &target).expect("copy fixture");
+        }
+    }
+}
+
+#[test]
+fn copilot_chat_parses_workspace_session_fixture() {
+    let tmp = TempDir::new().unwrap();
+    let user = tmp.path().join("fixture-copilot/User");
+    copy_fixture_user_dir(&user);
+
+    let conn = CopilotChatConnector::new();
+    let ctx = ScanContext {
+        data_dir: user.clone(),
+        scan_roots: Vec::new(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1, "workspace.json is not a session");
+
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "copilot");
+    assert_eq!(c.external_id.as_deref(), Some(SESSION_ID));
+    assert_eq!(
+        c.title.as_deref(),
+        Some("Why does terraform plan want to recreate the bucket?")
+    );
+    assert_eq!(
+        c.workspace,
+        Some(PathBuf::from("/home/dev/projects/infra tools"))
+    );
+    assert_eq!(c.started_at, Some(1_729_600_000_000));
+    assert_eq!(c.ended_at, Some(1_729_600_125_000));
+    assert_eq!(c.metadata["location"], "panel");
+
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "user", "assistant"]);
+    // Inline references are dropped; the surrounding markdown is joined
+    assert_eq!(
+        c.messages[1].content,
+        "The `bucket` name is derived from `random_id`, which changes whenever its keepers change. Pin the keepers or import the existing bucket."
+    );
+    assert_eq!(c.messages[1].author.as_deref(), Some("copilot/gpt-4o"));
+    assert!(c.messages[3].content.contains("terraform import"));
+}
+
+#[test]
+fn copilot_chat_reads_empty_window_sessions_from_scan_root() {
+    let tmp = TempDir::new().unwrap();
+    let user = tmp.path().join("Code/User");
+    copy_fixture_user_dir(&user);
+    let empty = user.join("globalStorage/emptyWindowChatSessions");
+    fs::create_dir_all(&empty).unwrap();
+    fs::write(
+        empty.join("loose.json"),
+        serde_json::json!({
+            "sessionId": "loose",
+            "customTitle": "Regex help",
+            "requests": [{
+                "timestamp": 1_729_700_000_000i64,
+                "message": {"text": "Match ISO dates"},
+                "response": [{"value": "\\d{4}-\\d{2}-\\d{2}"}]
+            }]
+        })
+        .to_string(),
+    )
+    .unwrap();
+
+    let conn = CopilotChatConnector::new();
+    let ctx = ScanContext::with_roots(tmp.path().join("data"), vec![ScanRoot::local(user)], None);
+    let mut convs = conn.scan(&ctx).expect("scan");
+    convs.sort_by(|a, b| a.external_id.cmp(&b.external_id));
+    assert_eq!(convs.len(), 2);
+
+    let loose = &convs[1];
+    assert_eq!(loose.external_id.as_deref(), Some("loose"));
+    assert_eq!(loose.title.as_deref(), Some("Regex help"));
+    assert_eq!(loose.workspace, None, "empty-window chats have no folder");
+    assert_eq!(loose.started_at, Some(1_729_700_000_000));
+}
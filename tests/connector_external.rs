@@ -0,0 +1,209 @@
+#![cfg(unix)]
+
+use coding_agent_search::connectors::external::{
+    ExternalConnector, ExternalConnectorConfig, ExternalConnectors, ExternalConnectorsConfig,
+};
+use coding_agent_search::connectors::{Connector, ScanContext, ScanRoot};
+use std::collections::BTreeMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Write an executable connector script that records its stdin and prints `output`.
+fn write_script(dir: &Path, output: &str) -> PathBuf {
+    let script = dir.join("cass-acme");
+    let body = format!(
+        "#!/bin/sh\ncat > \"$(dirname \"$0\")/request.json\"\necho \"$CASS_SINCE_TS\" > \"$(dirname \"$0\")/since.txt\"\ncat <<'EOF'\n{output}\nEOF\n"
+    );
+    fs::write(&script, body).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+fn config(script: &Path, roots: Vec<String>) -> ExternalConnectorConfig {
+    ExternalConnectorConfig {
+        name: "acme".into(),
+        command: script.display().to_string(),
+        args: Vec::new(),
+        roots,
+        env: BTreeMap::new(),
+        timeout_secs: None,
+    }
+}
+
+fn conversation_line(source_path: &Path, agent: &str) -> String {
+    serde_json::json!({
+        "agent_slug": agent,
+        "external_id": "s1",
+        "title": "Fix the build",
+        "workspace": "/work/acme",
+        "source_path": source_path,
+        "started_at": 1_700_000_000_000i64,
+        "ended_at": 1_700_000_060_000i64,
+        "metadata": {},
+        "messages": [
+            {"idx": 5, "role": "user", "author": null, "created_at": 1_700_000_000_000i64,
+             "content": "build fails", "extra": {}, "snippets": []},
+            {"idx": 9, "role": "assistant", "author": null, "created_at": 1_700_000_060_000i64,
+             "content": "fixed the linker flags", "extra": {}, "snippets": []}
+        ]
+    })
+    .to_string()
+}
+
+#[test]
+fn external_connector_streams_conversations() {
+    let tmp = TempDir::new().unwrap();
+    let sessions = tmp.path().join("sessions");
+    fs::create_dir_all(&sessions).unwrap();
+    let session_file = sessions.join("s1.json");
+    fs::write(&session_file, "{}").unwrap();
+
+    let output = format!("{}\nnot json\n\n", conversation_line(&session_file, ""));
+    let script = write_script(tmp.path(), &output);
+    let conn = ExternalConnector::new(config(&script, vec![sessions.display().to_string()]));
+
+    let detection = conn.detect();
+    assert!(detection.detected);
+    assert_eq!(detection.root_paths, vec![sessions.clone()]);
+
+    let ctx = ScanContext::local_default(tmp.path().join("data"), None);
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1, "invalid lines are skipped");
+    let conv = &convs[0];
+    assert_eq!(conv.agent_slug, "acme", "empty agent slug defaults to name");
+    assert_eq!(conv.messages[0].idx, 0);
+    assert_eq!(conv.messages[1].idx, 1);
+
+    let request: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(tmp.path().join("request.json")).unwrap())
+            .unwrap();
+    assert_eq!(request["protocol"], 1);
+    assert_eq!(request["connector"], "acme");
+    assert!(request["since_ts"].is_null());
+    assert_eq!(
+        request["scan_roots"][0]["path"].as_str(),
+        Some(sessions.to_str().unwrap())
+    );
+    assert_eq!(request["scan_roots"][0]["origin"]["source_id"], "local");
+}
+
+#[test]
+fn external_connector_applies_since_ts_to_source_files() {
+    let tmp = TempDir::new().unwrap();
+    let session_file = tmp.path().join("s1.json");
+    fs::write(&session_file, "{}").unwrap();
+    let script = write_script(tmp.path(), &conversation_line(&session_file, "acme"));
+    let conn = ExternalConnector::new(config(&script, Vec::new()));
+
+    // A since_ts far in the future makes the (just written) source file stale.
+    let future = i64::MAX / 2;
+    let ctx = ScanContext::local_default(tmp.path().join("data"), Some(future));
+    let convs = conn.scan(&ctx).expect("scan");
+    assert!(convs.is_empty());
+    assert_eq!(
+        fs::read_to_string(tmp.path().join("since.txt"))
+            .unwrap()
+            .trim(),
+        future.to_string()
+    );
+}
+
+#[test]
+fn external_connector_reports_failing_command() {
+    let tmp = TempDir::new().unwrap();
+    let script = tmp.path().join("cass-broken");
+    fs::write(&script, "#!/bin/sh\necho 'db locked' >&2\nexit 3\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let conn = ExternalConnector::new(config(&script, Vec::new()));
+    let err = conn
+        .scan(&ScanContext::local_default(tmp.path().to_path_buf(), None))
+        .unwrap_err();
+    assert!(err.to_string().contains("db locked"), "{err}");
+}
+
+#[test]
+fn external_connectors_route_watch_roots() {
+    let tmp = TempDir::new().unwrap();
+    let sessions = tmp.path().join("sessions");
+    fs::create_dir_all(&sessions).unwrap();
+    let session_file = sessions.join("s1.json");
+    fs::write(&session_file, "{}").unwrap();
+    let script = write_script(tmp.path(), &conversation_line(&session_file, "acme"));
+
+    let conns = ExternalConnectors::from_config(ExternalConnectorsConfig {
+        connectors: vec![config(&script, vec![sessions.display().to_string()])],
+    });
+
+    let owned = ScanContext::with_roots(
+        sessions.clone(),
+        vec![ScanRoot::local(sessions.clone())],
+        None,
+    );
+    assert_eq!(conns.scan(&owned).unwrap().len(), 1);
+
+    let elsewhere = tmp.path().join("other");
+    let unrelated =
+        ScanContext::with_roots(elsewhere.clone(), vec![ScanRoot::local(elsewhere)], None);
+    assert!(conns.scan(&unrelated).unwrap().is_empty());
+}
+
+#[test]
+fn external_connector_times_out_without_blocking_others() {
+    let tmp = TempDir::new().unwrap();
+    let session_file = tmp.path().join("s1.json");
+    fs::write(&session_file, "{}").unwrap();
+    let good = write_script(tmp.path(), &conversation_line(&session_file, "acme"));
+
+    // Prints nothing and keeps stdout open (via the sleep grandchild) for far
+    // longer than its timeout.
+    let hung = tmp.path().join("cass-hung");
+    fs::write(&hung, "#!/bin/sh\nsleep 30\n").unwrap();
+    fs::set_permissions(&hung, fs::Permissions::from_mode(0o755)).unwrap();
+    let hung_config = ExternalConnectorConfig {
+        name: "hung".into(),
+        timeout_secs: Some(1),
+        ..config(&hung, Vec::new())
+    };
+
+    let ctx = ScanContext::local_default(tmp.path().join("data"), None);
+    let started = std::time::Instant::now();
+    let err = ExternalConnector::new(hung_config.clone())
+        .scan(&ctx)
+        .unwrap_err();
+    assert!(err.to_string().contains("timed out after 1s"), "{err}");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    let conns = ExternalConnectors::from_config(ExternalConnectorsConfig {
+        connectors: vec![hung_config, config(&good, Vec::new())],
+    });
+    let convs = conns.scan(&ctx).expect("the other connector still scans");
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].agent_slug, "acme");
+}
+
+#[test]
+fn external_connector_skips_non_utf8_lines() {
+    let tmp = TempDir::new().unwrap();
+    let session_file = tmp.path().join("s1.json");
+    fs::write(&session_file, "{}").unwrap();
+
+    let script = tmp.path().join("cass-latin1");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nprintf 'caf\\351\\n'\ncat <<'EOF'\n{}\nEOF\n",
+            conversation_line(&session_file, "acme")
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let conn = ExternalConnector::new(config(&script, Vec::new()));
+    let convs = conn
+        .scan(&ScanContext::local_default(tmp.path().join("data"), None))
+        .expect("a non-UTF-8 line is skipped, not fatal");
+    assert_eq!(convs.len(), 1);
+}
@@ -0,0 +1,94 @@
+use coding_agent_search::connectors::roo_code::RooCodeConnector;
+use coding_agent_search::connectors::{Connector, ScanContext, ScanRoot};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+// See tests/fixtures/connectors/MANIFEST.json for provenance tracking.
+
+const TASK_ID: &str = "1730000000000-roo-task";
+
+/// Copy the Roo Code fixture task into `root/tasks/` and return the task dir.
+fn copy_fixture_task(root: &Path) -> PathBuf {
+    let src = PathBuf::from("tests/fixtures/roo_code/tasks").join(TASK_ID);
+    let task = root.join("tasks").join(TASK_ID);
+    fs::create_dir_all(&task).unwrap();
+    for entry in fs::read_dir(&src).unwrap().flatten() {
+        fs::copy(entry.path(), task.join(entry.file_name())).expect("copy fixture");
+    }
+    task
+}
+
+#[test]
+fn roo_code_parses_task_fixture() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().join("fixture-roo_code");
+    let task = copy_fixture_task(&root);
+
+    let conn = RooCodeConnector::new();
+    let ctx = ScanContext {
+        data_dir: root,
+        scan_roots: Vec::new(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "roo_code");
+    assert_eq!(c.external_id.as_deref(), Some(TASK_ID));
+    assert_eq!(c.source_path, task);
+    assert_eq!(
+        c.title.as_deref(),
+        Some("Convert the config loader to use serde defaults")
+    );
+    assert_eq!(c.workspace, Some(PathBuf::from("/home/dev/projects/relay")));
+    assert_eq!(c.metadata["file"], "api_conversation_history");
+    assert_eq!(c.started_at, Some(1_730_000_000_000));
+    assert_eq!(c.ended_at, Some(1_730_000_030_000));
+
+    assert_eq!(c.messages[0].role, "user");
+    assert!(c.messages[1].content.contains("serde(default)"));
+    let last = c.messages.last().unwrap();
+    assert_eq!(last.role, "assistant");
+    assert!(last.content.contains("port defaults to 8080"));
+}
+
+#[test]
+fn roo_code_falls_back_to_ui_messages() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp
+        .path()
+        .join("Code/User/globalStorage/rooveterinaryinc.roo-cline");
+    let task = copy_fixture_task(&root);
+    fs::remove_file(task.join("api_conversation_history.json")).unwrap();
+
+    let conn = RooCodeConnector::new();
+    let ctx = ScanContext::with_roots(tmp.path().join("data"), vec![ScanRoot::local(root)], None);
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+
+    let c = &convs[0];
+    assert_eq!(c.metadata["file"], "ui_messages");
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "assistant"]);
+    assert_eq!(
+        c.title.as_deref(),
+        Some("Convert the config loader to use serde defaults")
+    );
+}
+
+#[test]
+fn roo_code_skips_tasks_older_than_since_ts() {
+    let tmp = TempDir::new().unwrap();
+    let root = tmp.path().join("fixture-roo_code");
+    copy_fixture_task(&root);
+
+    let conn = RooCodeConnector::new();
+    let ctx = ScanContext {
+        data_dir: root,
+        scan_roots: Vec::new(),
+        since_ts: Some(i64::MAX / 2),
+    };
+    assert!(conn.scan(&ctx).expect("scan").is_empty());
+}
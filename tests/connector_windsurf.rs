@@ -0,0 +1,90 @@
+use coding_agent_search::connectors::windsurf::WindsurfConnector;
+use coding_agent_search::connectors::{Connector, ScanContext, ScanRoot};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+// See tests/fixtures/connectors/MANIFEST.json for provenance tracking.
+
+const CASCADE_ID: &str = "3e9a7c21-5b4f-4d2e-8a10-6f2b9d0c4e15";
+
+/// Copy the Windsurf fixture into `home/.codeium/windsurf/cascade` and return that dir.
+fn copy_fixture_cascade(home: &Path) -> PathBuf {
+    let cascade = home.join(".codeium/windsurf/cascade");
+    fs::create_dir_all(&cascade).unwrap();
+    let name = format!("{CASCADE_ID}.json");
+    fs::copy(
+        PathBuf::from("tests/fixtures/windsurf/cascade").join(&name),
+        cascade.join(&name),
+    )
+    .expect("copy fixture");
+    cascade
+}
+
+#[test]
+fn windsurf_parses_trajectory_fixture() {
+    let tmp = TempDir::new().unwrap();
+    let cascade = copy_fixture_cascade(&tmp.path().join("fixture-windsurf"));
+    // The live protobuf store sits next to exports and must be ignored
+    fs::write(cascade.join(format!("{CASCADE_ID}.pb")), [0x0a, 0x24, 0x33]).unwrap();
+
+    let conn = WindsurfConnector::new();
+    let ctx = ScanContext {
+        data_dir: cascade.clone(),
+        scan_roots: Vec::new(),
+        since_ts: None,
+    };
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+
+    let c = &convs[0];
+    assert_eq!(c.agent_slug, "windsurf");
+    assert_eq!(c.external_id.as_deref(), Some(CASCADE_ID));
+    assert_eq!(
+        c.title.as_deref(),
+        Some("Add pagination to the /transactions endpoint")
+    );
+    assert_eq!(
+        c.workspace,
+        Some(PathBuf::from("/home/dev/projects/ledger-api"))
+    );
+    assert_eq!(c.source_path, cascade.join(format!("{CASCADE_ID}.json")));
+
+    // Tool steps (VIEW_FILE) are skipped
+    let roles: Vec<_> = c.messages.iter().map(|m| m.role.as_str()).collect();
+    assert_eq!(roles, vec!["user", "assistant", "user", "assistant"]);
+    assert_eq!(c.messages[2].content, "Cap limit at 500 please.");
+    assert!(c.messages[3].content.contains("clamped to 500"));
+    assert!(c.messages.iter().all(|m| m.created_at.is_some()));
+    assert_eq!(c.started_at, c.messages[0].created_at);
+    assert_eq!(c.ended_at, c.messages[3].created_at);
+}
+
+#[test]
+fn windsurf_scan_roots_find_nested_cascade_dir() {
+    let tmp = TempDir::new().unwrap();
+    let home = tmp.path().join("home");
+    copy_fixture_cascade(&home);
+
+    let conn = WindsurfConnector::new();
+    let ctx = ScanContext::with_roots(tmp.path().join("data"), vec![ScanRoot::local(home)], None);
+    let convs = conn.scan(&ctx).expect("scan");
+    assert_eq!(convs.len(), 1);
+    assert_eq!(convs[0].messages.len(), 4);
+}
+
+#[test]
+fn windsurf_ignores_unrelated_roots() {
+    let tmp = TempDir::new().unwrap();
+    let other = tmp.path().join("other/cascade");
+    fs::create_dir_all(&other).unwrap();
+    fs::copy(
+        PathBuf::from("tests/fixtures/windsurf/cascade").join(format!("{CASCADE_ID}.json")),
+        other.join("x.json"),
+    )
+    .unwrap();
+
+    let conn = WindsurfConnector::new();
+    let ctx = ScanContext::with_roots(tmp.path().join("data"), vec![ScanRoot::local(other)], None);
+    assert!(conn.scan(&ctx).expect("scan").is_empty());
+}
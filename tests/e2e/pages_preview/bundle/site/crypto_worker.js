@@ -261,8 +261,10 @@ async function handleDecryptDatabase(dekBase64, cfg, opfsEnabled) {
             }
             const encryptedChunk = await response.arrayBuffer();
 
-            // Derive chunk nonce: first 8 bytes from base_nonce, last 4 bytes are counter
-            const chunkNonce = deriveChunkNonce(baseNonce, i);
+            // Derive chunk nonce: first 8 bytes from base_nonce, last 4 bytes are counter.
+            // Chunks re-encrypted by `cass pages --update` carry a generation.
+            const generation = (payload.chunk_generations && payload.chunk_generations[i]) || 0;
+            const chunkNonce = deriveChunkNonce(baseNonce, i, generation);
 
             // Build chunk AAD: export_id || chunk_index (big-endian u32)
             const aad = buildChunkAad(exportId, i);
@@ -330,9 +332,10 @@ async function handleDecryptDatabase(dekBase64, cfg, opfsEnabled) {
 /**
  * Derive chunk nonce from base nonce and counter.
  * Uses deterministic counter mode: first 8 bytes from base_nonce,
- * last 4 bytes are the chunk index (big-endian).
+ * last 4 bytes are the chunk index (big-endian). Bytes 4..8 are XORed
+ * with the chunk generation so re-encrypted chunks never reuse a nonce.
  */
-function deriveChunkNonce(baseNonce, counter) {
+function deriveChunkNonce(baseNonce, counter, generation = 0) {
     const nonce = new Uint8Array(12);
     // Copy first 8 bytes from base nonce
     nonce.set(baseNonce.subarray(0, 8));
@@ -343,6 +346,15 @@ function deriveChunkNonce(baseNonce, counter) {
     const counterBytes = new Uint8Array(counterView.buffer);
     nonce.set(counterBytes, 8);
 
+    if (generation) {
+        const genView = new DataView(new ArrayBuffer(4));
+        genView.setUint32(0, generation >>> 0, false); // big-endian
+        const genBytes = new Uint8Array(genView.buffer);
+        for (let j = 0; j < 4; j++) {
+            nonce[4 + j] ^= genBytes[j];
+        }
+    }
+
     return nonce;
 }
 
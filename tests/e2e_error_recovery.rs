@@ -19,6 +19,7 @@ use coding_agent_search::indexer::{self, IndexOptions};
 use coding_agent_search::model::types::{Agent, AgentKind};
 use coding_agent_search::pages::encrypt::{DecryptionEngine, EncryptionEngine, load_config};
 use coding_agent_search::pages::export::{ExportEngine, ExportFilter, PathMode};
+use coding_agent_search::search::vector_index::Quantization;
 use coding_agent_search::storage::sqlite::SqliteStorage;
 use std::fs;
 use std::path::Path;
@@ -277,6 +278,7 @@ fn test_corrupted_index_triggers_rebuild() {
         semantic: false,
         build_hnsw: false,
         embedder: "fastembed".to_string(),
+        quantization: Quantization::F32,
         progress: None,
     };
     let result = indexer::run_index(opts, None);
@@ -306,6 +308,7 @@ fn test_corrupted_index_triggers_rebuild() {
             semantic: false,
             build_hnsw: false,
             embedder: "fastembed".to_string(),
+            quantization: Quantization::F32,
             progress: None,
         };
         // force_rebuild should handle corrupted index gracefully
@@ -441,6 +441,223 @@ fn search_hybrid_mode_combines_results() {
     tracker.complete();
 }
 
+/// Test: int8 and binary indexes save an f16 rescoring file and still serve semantic search.
+#[test]
+fn quantized_semantic_index_searches_with_rescoring() {
+    let tracker = tracker_for("quantized_semantic_index_searches_with_rescoring");
+    let _trace_guard = tracker.trace_env_guard();
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    let ps = tracker.start(
+        "create_fixtures",
+        Some("Create sessions for quantized index"),
+    );
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-1.jsonl",
+        "kubernetes pod eviction memory pressure",
+        1732118400000,
+    );
+    make_codex_session(
+        &codex_home,
+        "2024/11/21",
+        "rollout-2.jsonl",
+        "sourdough starter hydration schedule",
+        1732204800000,
+    );
+    tracker.end(
+        "create_fixtures",
+        Some("Create sessions for quantized index"),
+        ps,
+    );
+
+    for quantization in ["int8", "binary"] {
+        let ps = tracker.start("run_quantized_index", Some(quantization));
+        cargo_bin_cmd!("cass")
+            .args([
+                "index",
+                "--full",
+                "--semantic",
+                "--embedder",
+                "hash",
+                "--quantization",
+                quantization,
+                "--data-dir",
+            ])
+            .arg(&data_dir)
+            .env("CODEX_HOME", &codex_home)
+            .env("HOME", home)
+            .assert()
+            .success();
+        tracker.end("run_quantized_index", Some(quantization), ps);
+
+        let vector_files: Vec<String> = fs::read_dir(data_dir.join("vector_index"))
+            .expect("read vector_index dir")
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.ends_with(".cvvi"))
+            .collect();
+        assert!(
+            vector_files.iter().any(|name| name.ends_with(".f16.cvvi")),
+            "{quantization} index should save an f16 rescoring file: {vector_files:?}"
+        );
+
+        let ps = tracker.start("search_quantized", Some(quantization));
+        let output = cargo_bin_cmd!("cass")
+            .args(["search", "--robot", "--mode", "semantic", "--data-dir"])
+            .arg(&data_dir)
+            .arg("kubernetes pod eviction")
+            .env("CODEX_HOME", &codex_home)
+            .env("HOME", home)
+            .output()
+            .expect("search command");
+        tracker.end("search_quantized", Some(quantization), ps);
+
+        assert!(
+            output.status.success(),
+            "semantic search over {quantization} index failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("search output should be valid JSON");
+        let hits = json["hits"].as_array().expect("hits array");
+        assert!(!hits.is_empty(), "{quantization} index should return hits");
+        assert!(
+            hits[0]["content"]
+                .as_str()
+                .unwrap_or_default()
+                .contains("kubernetes"),
+            "rescored top hit should be the matching message: {}",
+            hits[0]
+        );
+    }
+
+    tracker.complete();
+}
+
+/// Test: re-running semantic indexing appends a delta segment for new messages.
+#[test]
+fn incremental_semantic_index_appends_segment() {
+    let tracker = tracker_for("incremental_semantic_index_appends_segment");
+    let _trace_guard = tracker.trace_env_guard();
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path();
+    let codex_home = home.join(".codex");
+    let data_dir = home.join("cass_data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let _guard_home = EnvGuard::set("HOME", home.to_string_lossy());
+    let _guard_codex = EnvGuard::set("CODEX_HOME", codex_home.to_string_lossy());
+
+    let run_index = |args: &[&str]| {
+        cargo_bin_cmd!("cass")
+            .arg("index")
+            .args(args)
+            .args(["--semantic", "--embedder", "hash", "--data-dir"])
+            .arg(&data_dir)
+            .env("CODEX_HOME", &codex_home)
+            .env("HOME", home)
+            .assert()
+            .success();
+    };
+
+    let ps = tracker.start("initial_index", Some("Index five sessions"));
+    let topics = [
+        "sourdough starter hydration schedule",
+        "bicycle chain lubrication interval",
+        "tomato seedling transplant timing",
+        "espresso grind size calibration",
+        "watercolor paper stretching method",
+    ];
+    for (i, topic) in topics.iter().enumerate() {
+        make_codex_session(
+            &codex_home,
+            &format!("2024/11/{:02}", 10 + i),
+            &format!("rollout-{i}.jsonl"),
+            topic,
+            1731196800000 + i as u64 * 86_400_000,
+        );
+    }
+    run_index(&["--full"]);
+    tracker.end("initial_index", Some("Index five sessions"), ps);
+
+    let vector_dir = data_dir.join("vector_index");
+    let list_files = || -> Vec<String> {
+        fs::read_dir(&vector_dir)
+            .expect("read vector_index dir")
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect()
+    };
+    assert!(
+        !list_files().iter().any(|name| name.contains(".seg-")),
+        "a full build should not leave delta segments"
+    );
+
+    let ps = tracker.start("incremental_index", Some("Add one session and reindex"));
+    make_codex_session(
+        &codex_home,
+        "2024/11/20",
+        "rollout-new.jsonl",
+        "kubernetes pod eviction memory pressure",
+        1732118400000,
+    );
+    run_index(&[]);
+    tracker.end("incremental_index", Some("Add one session and reindex"), ps);
+
+    let files = list_files();
+    assert!(
+        files.iter().any(|name| name.ends_with(".segments.json")),
+        "incremental run should write a segment manifest: {files:?}"
+    );
+    assert!(
+        files.iter().any(|name| name.ends_with(".seg-1.cvvi")),
+        "incremental run should append a delta segment: {files:?}"
+    );
+
+    let ps = tracker.start("search_new_content", Some("Search the appended segment"));
+    let output = cargo_bin_cmd!("cass")
+        .args(["search", "--robot", "--mode", "semantic", "--data-dir"])
+        .arg(&data_dir)
+        .arg("kubernetes pod eviction")
+        .env("CODEX_HOME", &codex_home)
+        .env("HOME", home)
+        .output()
+        .expect("search command");
+    tracker.end(
+        "search_new_content",
+        Some("Search the appended segment"),
+        ps,
+    );
+
+    assert!(
+        output.status.success(),
+        "semantic search over segmented index failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("search output should be valid JSON");
+    let hits = json["hits"].as_array().expect("hits array");
+    assert!(
+        hits.first()
+            .and_then(|hit| hit["content"].as_str())
+            .is_some_and(|content| content.contains("kubernetes")),
+        "new message should be found in the delta segment: {hits:?}"
+    );
+
+    tracker.complete();
+}
+
 // =============================================================================
 // HNSW Approximate Search Tests
 // =============================================================================
@@ -6,16 +6,20 @@
 //! - sources remove
 //! - sources doctor (limited without actual SSH)
 //! - sources sync (dry-run only)
+//! - sources daemon --once (failure recording and backoff, no SSH)
+//! - sources serve-stdio (native sync endpoint, driven over a pipe)
 //!
 //! Note: Tests that require actual SSH connectivity are marked #[ignore].
 
 use assert_cmd::cargo::cargo_bin_cmd;
+use coding_agent_search::model::types::{Agent, AgentKind};
+use coding_agent_search::storage::sqlite::SqliteStorage;
 use std::fs;
 use std::path::Path;
 
 mod util;
-use util::EnvGuard;
 use util::e2e_log::PhaseTracker;
+use util::{ConversationFixtureBuilder, EnvGuard};
 
 fn tracker_for(test_name: &str) -> PhaseTracker {
     PhaseTracker::new("e2e_sources", test_name)
@@ -444,6 +448,55 @@ fn sources_add_no_paths_error() {
     tracker.complete();
 }
 
+/// Test: sources add --native needs no paths and records the transport.
+#[test]
+fn sources_add_native_without_paths() {
+    let tracker = tracker_for("sources_add_native_without_paths");
+    let _trace_guard = tracker.trace_env_guard();
+
+    let start = tracker.start("setup", Some("Create temp config directory"));
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    fs::create_dir_all(&config_dir).unwrap();
+    let _guard_config = EnvGuard::set("XDG_CONFIG_HOME", config_dir.to_string_lossy());
+    tracker.end("setup", Some("Create temp config directory"), start);
+
+    let start = tracker.start("run_sources_add", Some("Run sources add --native"));
+    let output = cargo_bin_cmd!("cass")
+        .args([
+            "sources",
+            "add",
+            "user@buildbox.local",
+            "--name",
+            "buildbox",
+            "--native",
+            "--no-test",
+        ])
+        .env("XDG_CONFIG_HOME", &config_dir)
+        .output()
+        .expect("sources add command");
+    tracker.end("run_sources_add", Some("Run sources add --native"), start);
+
+    let start = tracker.start("verify_output", Some("Verify native transport saved"));
+    assert!(
+        output.status.success(),
+        "sources add failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config_content = read_sources_config(&config_dir);
+    assert!(
+        config_content.contains("transport = \"native\""),
+        "Transport not in config file: {config_content}"
+    );
+    tracker.end(
+        "verify_output",
+        Some("Verify native transport saved"),
+        start,
+    );
+
+    tracker.complete();
+}
+
 /// Test: sources add rejects duplicate source names.
 #[test]
 fn sources_add_duplicate_error() {
@@ -1270,6 +1323,198 @@ paths = ["~/.claude/projects"]
     tracker.complete();
 }
 
+// =============================================================================
+// sources daemon tests
+// =============================================================================
+
+/// Test: sources daemon --once records a failed scheduled sync and backs off.
+#[test]
+fn sources_daemon_once_records_failure_and_backs_off() {
+    let tracker = tracker_for("sources_daemon_once_records_failure_and_backs_off");
+    let _trace_guard = tracker.trace_env_guard();
+
+    let start = tracker.start("setup", Some("Create hourly source without paths"));
+    let tmp = tempfile::TempDir::new().unwrap();
+    let config_dir = tmp.path().join("config");
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::create_dir_all(&data_dir).unwrap();
+
+    // No paths: the sync fails before any SSH connection is attempted.
+    create_sources_config(
+        &config_dir,
+        r#"
+[[sources]]
+name = "laptop"
+type = "ssh"
+host = "user@laptop.local"
+paths = []
+sync_schedule = "hourly"
+
+[[sources]]
+name = "manual"
+type = "ssh"
+host = "user@manual.local"
+paths = ["~/.claude/projects"]
+"#,
+    );
+    tracker.end("setup", Some("Create hourly source without paths"), start);
+
+    let run_daemon = || {
+        cargo_bin_cmd!("cass")
+            .args(["sources", "daemon", "--once", "--json"])
+            .env("XDG_CONFIG_HOME", &config_dir)
+            .env("CASS_DATA_DIR", &data_dir)
+            .output()
+            .expect("sources daemon command")
+    };
+
+    let start = tracker.start("first_run", Some("Due source is synced and fails"));
+    let output = run_daemon();
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("JSON line"))
+        .collect();
+    assert_eq!(lines.len(), 1, "only the hourly source is due: {lines:?}");
+    assert_eq!(lines[0]["event"], "sync");
+    assert_eq!(lines[0]["source"], "laptop");
+    assert_eq!(lines[0]["status"], "failed");
+    assert!(lines[0]["next_sync"].is_string());
+
+    let status: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(data_dir.join("sync_status.json")).unwrap())
+            .unwrap();
+    assert_eq!(status["sources"]["laptop"]["consecutive_failures"], 1);
+    assert!(status["sources"].get("manual").is_none());
+    tracker.end("first_run", Some("Due source is synced and fails"), start);
+
+    let start = tracker.start("second_run", Some("Backoff skips the failed source"));
+    let output = run_daemon();
+    assert!(output.status.success());
+    assert!(
+        output.stdout.is_empty(),
+        "source should be backing off: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    tracker.end("second_run", Some("Backoff skips the failed source"), start);
+
+    tracker.complete();
+}
+
+// =============================================================================
+// sources serve-stdio tests
+// =============================================================================
+
+/// Test: serve-stdio answers a pull with conversations since the cursor.
+#[test]
+fn sources_serve_stdio_answers_pull() {
+    let tracker = tracker_for("sources_serve_stdio_answers_pull");
+    let _trace_guard = tracker.trace_env_guard();
+
+    let start = tracker.start("setup", Some("Seed a database with one session"));
+    let tmp = tempfile::TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+    let mut storage = SqliteStorage::open(&data_dir.join("agent_search.db")).expect("open db");
+    let agent_id = storage
+        .ensure_agent(&Agent {
+            id: None,
+            slug: "claude_code".to_string(),
+            name: "Claude Code".to_string(),
+            version: None,
+            kind: AgentKind::Cli,
+        })
+        .expect("ensure agent");
+    let conversation = ConversationFixtureBuilder::new("claude_code")
+        .external_id("session-1")
+        .title("Fix login flow")
+        .source_path("/home/user/.claude/projects/app/session-1.jsonl")
+        .messages(3)
+        .build_conversation();
+    storage
+        .insert_conversation_tree(agent_id, None, &conversation)
+        .expect("insert conversation");
+    drop(storage);
+    tracker.end("setup", Some("Seed a database with one session"), start);
+
+    let pull = |data_dir: &Path, since: i64, epoch: &serde_json::Value| {
+        let request = serde_json::json!({ "protocol": 1, "since": since, "epoch": epoch });
+        let output = cargo_bin_cmd!("cass")
+            .args(["sources", "serve-stdio", "--data-dir"])
+            .arg(data_dir)
+            .write_stdin(format!("{request}\n"))
+            .output()
+            .expect("sources serve-stdio command");
+        let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("JSON line"))
+            .collect();
+        (output.status.success(), lines)
+    };
+
+    let start = tracker.start("first_pull", Some("Everything is new"));
+    let (ok, lines) = pull(&data_dir, 0, &serde_json::Value::Null);
+    assert!(ok, "serve-stdio failed: {lines:?}");
+    assert_eq!(lines.len(), 3, "hello, one conversation, done: {lines:?}");
+    assert_eq!(lines[0]["type"], "hello");
+    assert_eq!(lines[0]["protocol"], 1);
+    assert_eq!(lines[1]["type"], "conversation");
+    let served = &lines[1]["conversation"];
+    assert_eq!(served["external_id"], "session-1");
+    assert_eq!(served["title"], "Fix login flow");
+    assert_eq!(served["messages"].as_array().unwrap().len(), 3);
+    assert_eq!(lines[2]["type"], "done");
+    assert_eq!(lines[2]["conversations"], 1);
+    assert_eq!(lines[2]["messages"], 3);
+    let cursor = lines[2]["cursor"].as_i64().unwrap();
+    assert!(cursor > 0);
+    let epoch = lines[2]["epoch"].clone();
+    assert!(epoch.as_str().is_some_and(|epoch| !epoch.is_empty()));
+    tracker.end("first_pull", Some("Everything is new"), start);
+
+    let start = tracker.start("second_pull", Some("Nothing new since the cursor"));
+    let (ok, lines) = pull(&data_dir, cursor, &epoch);
+    assert!(ok);
+    assert_eq!(lines.len(), 2, "hello and done only: {lines:?}");
+    assert_eq!(lines[1]["type"], "done");
+    assert_eq!(lines[1]["cursor"], cursor);
+    assert_eq!(lines[1]["conversations"], 0);
+    assert_eq!(lines[1]["reset"], false);
+    tracker.end("second_pull", Some("Nothing new since the cursor"), start);
+
+    let start = tracker.start("stale_epoch", Some("A cursor from another epoch restarts"));
+    let (ok, lines) = pull(&data_dir, cursor, &serde_json::json!("rebuilt"));
+    assert!(ok);
+    assert_eq!(lines.len(), 3, "conversation served again: {lines:?}");
+    assert_eq!(lines[2]["reset"], true);
+    assert_eq!(lines[2]["epoch"], epoch);
+    tracker.end(
+        "stale_epoch",
+        Some("A cursor from another epoch restarts"),
+        start,
+    );
+
+    let start = tracker.start("missing_db", Some("Errors are reported in-band"));
+    let (ok, lines) = pull(&tmp.path().join("empty"), 0, &serde_json::Value::Null);
+    assert!(!ok);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["type"], "error");
+    assert!(
+        lines[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Database not found")
+    );
+    tracker.end("missing_db", Some("Errors are reported in-band"), start);
+
+    tracker.complete();
+}
+
 // =============================================================================
 // Integration workflow tests
 // =============================================================================
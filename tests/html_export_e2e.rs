@@ -4,6 +4,8 @@
 //! CSS presence, JavaScript functionality, and accessibility.
 
 use assert_cmd::Command;
+use coding_agent_search::model::types::{Agent, AgentKind};
+use coding_agent_search::storage::sqlite::SqliteStorage;
 use regex::Regex;
 use serde_json::Value;
 use std::fs;
@@ -11,6 +13,9 @@ use std::path::Path;
 use tempfile::TempDir;
 use tracing::{debug, info};
 
+mod util;
+use util::ConversationFixtureBuilder;
+
 // ============================================================================
 // Test Helpers
 // ============================================================================
@@ -543,3 +548,155 @@ fn test_export_completes_quickly() {
         elapsed
     );
 }
+
+// ============================================================================
+// Multi-session export
+// ============================================================================
+
+/// Seed a DB with two sessions in one workspace and one in another.
+fn seed_multi_session_db(data_dir: &Path) {
+    fs::create_dir_all(data_dir).unwrap();
+    let mut storage = SqliteStorage::open(&data_dir.join("agent_search.db")).expect("open db");
+    let agent_id = storage
+        .ensure_agent(&Agent {
+            id: None,
+            slug: "claude_code".to_string(),
+            name: "Claude Code".to_string(),
+            version: None,
+            kind: AgentKind::Cli,
+        })
+        .expect("ensure agent");
+
+    let sessions = [
+        (
+            "Fix login flow",
+            "/home/user/projects/app",
+            1_700_000_000_000,
+        ),
+        (
+            "Add login tests",
+            "/home/user/projects/app",
+            1_700_000_500_000,
+        ),
+        ("Tune build", "/home/user/projects/lib", 1_700_001_000_000),
+    ];
+    for (i, (title, workspace, ts)) in sessions.into_iter().enumerate() {
+        let workspace_id = storage
+            .ensure_workspace(Path::new(workspace), None)
+            .expect("ensure workspace");
+        let conversation = ConversationFixtureBuilder::new("claude_code")
+            .title(title)
+            .workspace(workspace)
+            .source_path(format!("/home/user/.claude/projects/session-{i}.jsonl"))
+            .base_ts(ts)
+            .messages(4)
+            .build_conversation();
+        storage
+            .insert_conversation_tree(agent_id, Some(workspace_id), &conversation)
+            .expect("insert conversation");
+    }
+    storage
+        .add_session_tags(
+            "/home/user/.claude/projects/session-2.jsonl",
+            &["perf".to_string()],
+        )
+        .expect("tag session");
+}
+
+#[test]
+fn test_multi_session_export_by_workspace() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    let out_dir = tmp.path().join("site");
+    seed_multi_session_db(&data_dir);
+
+    let output = base_cmd()
+        .args([
+            "export-html",
+            "--workspace",
+            "/home/user/projects/app",
+            "--json",
+        ])
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .arg("--output-dir")
+        .arg(&out_dir)
+        .output()
+        .expect("run export-html");
+    assert!(
+        output.status.success(),
+        "multi-session export failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: Value = serde_json::from_slice(&output.stdout).expect("export JSON");
+    assert_eq!(json["exported"]["sessions_count"], 2);
+
+    let index = fs::read_to_string(out_dir.join("index.html")).expect("index.html");
+    assert!(index.contains(r#"id="site-table""#));
+    assert!(index.contains(r#"id="site-search-data""#));
+    assert!(!index.contains("Tune build"));
+
+    let pages = json["exported"]["sessions"].as_array().expect("sessions");
+    for page in pages {
+        let rel = page["path"].as_str().expect("page path");
+        assert!(index.contains(&format!(r#"href="{rel}""#)));
+        let html = fs::read_to_string(out_dir.join(rel)).expect("session page");
+        assert!(html.contains(r#"href="../index.html""#));
+        assert!(html.contains("1 other session in app"));
+    }
+}
+
+#[test]
+fn test_multi_session_export_by_tag_encrypted() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    let out_dir = tmp.path().join("site");
+    seed_multi_session_db(&data_dir);
+
+    let output = base_cmd()
+        .args([
+            "export-html",
+            "--tag",
+            "perf",
+            "--encrypt",
+            "--password",
+            "hunter2",
+            "--json",
+        ])
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .arg("--output-dir")
+        .arg(&out_dir)
+        .output()
+        .expect("run export-html");
+    assert!(
+        output.status.success(),
+        "encrypted export failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: Value = serde_json::from_slice(&output.stdout).expect("export JSON");
+    assert_eq!(json["exported"]["sessions_count"], 1);
+    assert_eq!(json["exported"]["encrypted"], true);
+
+    for rel in ["index.html", "sessions/session-001.html"] {
+        let html = fs::read_to_string(out_dir.join(rel)).expect("page");
+        assert!(html.contains(r#"id="encrypted-content""#), "{rel}");
+        assert!(!html.contains("Tune build"), "{rel} leaks the title");
+    }
+}
+
+#[test]
+fn test_multi_session_export_reports_empty_selection() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    seed_multi_session_db(&data_dir);
+
+    base_cmd()
+        .args(["export-html", "--workspace", "/nowhere", "--json"])
+        .arg("--data-dir")
+        .arg(&data_dir)
+        .arg("--output-dir")
+        .arg(tmp.path().join("site"))
+        .assert()
+        .code(3);
+}
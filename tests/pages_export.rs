@@ -420,4 +420,84 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_append_adds_new_messages_to_archived_conversations() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let source_path = temp_dir.path().join("source.db");
+        let output_path = temp_dir.path().join("export.db");
+
+        setup_source_db(&source_path)?;
+
+        let filter = ExportFilter {
+            agents: None,
+            workspaces: None,
+            since: None,
+            until: None,
+            path_mode: PathMode::Relative,
+        };
+        let engine = ExportEngine::new(&source_path, &output_path, filter);
+        engine.execute(|_, _| {}, None)?;
+
+        // Nothing new yet
+        let stats = engine.append(|_, _| {}, None)?;
+        assert_eq!(stats.conversations_processed, 0);
+        assert_eq!(stats.conversations_extended, 0);
+        assert_eq!(stats.messages_processed, 0);
+
+        // The older session continues, and an archived message is edited
+        let src = Connection::open(&source_path)?;
+        src.execute(
+            "INSERT INTO messages (conversation_id, idx, role, content, created_at)
+             VALUES (1, 2, 'user', 'follow-up question', 1600000009000)",
+            [],
+        )?;
+        src.execute(
+            "UPDATE messages SET content = 'hello again' WHERE conversation_id = 1 AND idx = 0",
+            [],
+        )?;
+        src.execute(
+            "UPDATE conversations SET ended_at = 1600000009000 WHERE id = 1",
+            [],
+        )?;
+        drop(src);
+
+        let stats = engine.append(|_, _| {}, None)?;
+        assert_eq!(stats.conversations_processed, 0);
+        assert_eq!(stats.conversations_extended, 1);
+        assert_eq!(stats.messages_processed, 1);
+
+        let conn = Connection::open(&output_path)?;
+        let (message_count, ended_at): (i64, Option<i64>) = conn.query_row(
+            "SELECT message_count, ended_at FROM conversations WHERE title = 'Test Conv 1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        assert_eq!(message_count, 3);
+        assert_eq!(ended_at, Some(1600000009000));
+
+        let contents: Vec<String> = conn
+            .prepare(
+                "SELECT m.content FROM messages m
+                 JOIN conversations c ON c.id = m.conversation_id
+                 WHERE c.title = 'Test Conv 1' ORDER BY m.idx",
+            )?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        // Only messages past the last archived one are added; edits are not
+        assert_eq!(contents, ["hello", "world", "follow-up question"]);
+
+        let fts_hits: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM messages_fts WHERE messages_fts MATCH 'follow'",
+            [],
+            |row| row.get(0),
+        )?;
+        assert_eq!(fts_hits, 1);
+
+        // A second run finds nothing new
+        let stats = engine.append(|_, _| {}, None)?;
+        assert_eq!(stats.messages_processed, 0);
+
+        Ok(())
+    }
 }
@@ -231,6 +231,153 @@ fn test_pages_bundle_key_add_revoke_cycle() {
     assert!(DecryptionEngine::unlock_with_password(config, TEST_PASSWORD_2).is_ok());
 }
 
+#[test]
+fn test_pages_keys_cli_add_list_revoke() {
+    let temp_dir = TempDir::new().unwrap();
+    let artifacts = build_pipeline(&temp_dir);
+    let site_dir = &artifacts.bundle.site_dir;
+    // The bundle root (parent of site/) is accepted as well as site/ itself
+    let bundle_root = site_dir.parent().unwrap();
+
+    let run_keys = |args: &[&str], password: &str| -> Value {
+        let output = cargo_bin_cmd!("cass")
+            .args(["pages", "keys"])
+            .args(args)
+            .arg("--archive")
+            .arg(bundle_root)
+            .arg("--json")
+            .env("CASS_PAGES_PASSWORD", password)
+            .env("CASS_PAGES_NEW_PASSWORD", TEST_PASSWORD_2)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "cass pages keys {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let added = run_keys(&["add"], TEST_PASSWORD);
+    assert_eq!(added["slot_id"].as_u64(), Some(2));
+    assert_eq!(added["slot_type"].as_str(), Some("password"));
+    assert!(added["deployment"].is_null());
+
+    let listed = run_keys(&["list"], TEST_PASSWORD);
+    assert_eq!(listed["active_slots"].as_u64(), Some(3));
+
+    let revoked = run_keys(&["revoke", "0"], TEST_PASSWORD_2);
+    assert_eq!(revoked["revoked_slot_id"].as_u64(), Some(0));
+    assert_eq!(revoked["remaining_slots"].as_u64(), Some(2));
+
+    let config = load_config(site_dir).unwrap();
+    assert!(DecryptionEngine::unlock_with_password(config, TEST_PASSWORD).is_err());
+    let config = load_config(site_dir).unwrap();
+    assert!(DecryptionEngine::unlock_with_password(config, TEST_PASSWORD_2).is_ok());
+
+    // config.json changed, and integrity.json was updated to match
+    let report = verify_bundle(site_dir, false).expect("verify after key changes");
+    assert_eq!(report.status, "valid");
+}
+
+#[test]
+fn test_pages_keys_cli_requires_password_non_interactive() {
+    let temp_dir = TempDir::new().unwrap();
+    let artifacts = build_pipeline(&temp_dir);
+
+    cargo_bin_cmd!("cass")
+        .args(["pages", "keys", "add", "--json", "--archive"])
+        .arg(&artifacts.bundle.site_dir)
+        .env_remove("CASS_PAGES_PASSWORD")
+        .env_remove("CASS_PAGES_NEW_PASSWORD")
+        .assert()
+        .failure()
+        .code(2);
+
+    assert_eq!(
+        key_list(&artifacts.bundle.site_dir).unwrap().active_slots,
+        2
+    );
+}
+
+#[test]
+fn test_pages_update_appends_only_new_conversations() {
+    let temp_dir = TempDir::new().unwrap();
+    let artifacts = build_pipeline(&temp_dir);
+    let site_dir = &artifacts.bundle.site_dir;
+    let data_dir = temp_dir.path().join("data");
+    let fingerprint_path = artifacts
+        .bundle
+        .private_dir
+        .join("integrity-fingerprint.txt");
+    let fingerprint_before = fs::read_to_string(&fingerprint_path).unwrap();
+
+    let run_update = || -> Value {
+        let output = cargo_bin_cmd!("cass")
+            .arg("--db")
+            .arg(data_dir.join("agent_search.db"))
+            .args(["pages", "--json", "--update"])
+            .arg(site_dir.parent().unwrap())
+            .env("CASS_PAGES_PASSWORD", TEST_PASSWORD)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "cass pages --update failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    // The archive already holds everything in the source database
+    let report = run_update();
+    assert_eq!(report["up_to_date"].as_bool(), Some(true));
+    assert_eq!(report["conversations_added"].as_u64(), Some(0));
+    assert_eq!(
+        fs::read_to_string(&fingerprint_path).unwrap(),
+        fingerprint_before
+    );
+
+    add_newer_conversation(&data_dir);
+
+    let report = run_update();
+    assert_eq!(report["up_to_date"].as_bool(), Some(false));
+    assert_eq!(report["conversations_added"].as_u64(), Some(1));
+    assert_eq!(report["messages_added"].as_u64(), Some(3));
+    assert_eq!(report["high_water_mark"].as_i64(), Some(1_800_000_000_000));
+    assert!(!report["chunks_rewritten"].as_array().unwrap().is_empty());
+    assert!(report["deployment"].is_null());
+    assert_ne!(
+        fs::read_to_string(&fingerprint_path).unwrap(),
+        fingerprint_before
+    );
+
+    let verify = verify_bundle(site_dir, false).expect("verify after update");
+    assert_eq!(verify.status, "valid");
+
+    // Re-encrypted chunks carry a new generation and still decrypt
+    let config = load_config(site_dir).unwrap();
+    assert!(config.payload.chunk_generations.iter().any(|g| *g > 0));
+    let engine = DecryptionEngine::unlock_with_password(config, TEST_PASSWORD).unwrap();
+    let decrypted = temp_dir.path().join("decrypted.db");
+    engine
+        .decrypt_to_file(site_dir, &decrypted, |_, _| {})
+        .expect("decrypt updated archive");
+    let conn = rusqlite::Connection::open(&decrypted).unwrap();
+    let titles: Vec<String> = conn
+        .prepare("SELECT title FROM conversations ORDER BY started_at")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(titles, ["Test Conversation", "Newer Conversation"]);
+
+    // A second run finds nothing new
+    let report = run_update();
+    assert_eq!(report["conversations_added"].as_u64(), Some(0));
+}
+
 #[test]
 fn test_pages_bundle_verify_detects_corruption() {
     let temp_dir = TempDir::new().unwrap();
@@ -318,6 +465,37 @@ fn setup_db(data_dir: &Path) {
     setup_db_internal(data_dir, false);
 }
 
+/// Add a conversation that started after the one from `setup_db`.
+fn add_newer_conversation(data_dir: &Path) {
+    let mut storage =
+        SqliteStorage::open(&data_dir.join("agent_search.db")).expect("Failed to open storage");
+    let agent = Agent {
+        id: None,
+        slug: "claude_code".to_string(),
+        name: "Claude Code".to_string(),
+        version: None,
+        kind: AgentKind::Cli,
+    };
+    let agent_id = storage.ensure_agent(&agent).expect("ensure agent");
+    let workspace_path = Path::new("/home/user/projects/test");
+    let workspace_id = Some(
+        storage
+            .ensure_workspace(workspace_path, None)
+            .expect("ensure workspace"),
+    );
+
+    let conversation = ConversationFixtureBuilder::new("claude_code")
+        .title("Newer Conversation")
+        .workspace(workspace_path)
+        .source_path("/home/user/.claude/projects/test/session-2.jsonl")
+        .base_ts(1_800_000_000_000)
+        .messages(3)
+        .build_conversation();
+    storage
+        .insert_conversation_tree(agent_id, workspace_id, &conversation)
+        .expect("Failed to insert conversation");
+}
+
 fn setup_db_with_secret(data_dir: &Path) {
     setup_db_internal(data_dir, true);
 }
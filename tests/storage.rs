@@ -1,8 +1,11 @@
 use std::path::PathBuf;
 
-use coding_agent_search::model::types::{Agent, AgentKind, Conversation, Message, MessageRole};
+use coding_agent_search::connectors::{FileCursor, ToolEventKind};
+use coding_agent_search::model::types::{
+    Agent, AgentKind, Conversation, Message, MessageRole, Snippet,
+};
 use coding_agent_search::sources::provenance::{LOCAL_SOURCE_ID, Source, SourceKind};
-use coding_agent_search::storage::sqlite::SqliteStorage;
+use coding_agent_search::storage::sqlite::{SqliteStorage, ToolEventFilter};
 
 fn sample_agent() -> Agent {
     Agent {
@@ -51,7 +54,7 @@ fn schema_version_created_on_open() {
     let db_path = tmp.path().join("store.db");
     let storage = SqliteStorage::open(&db_path).expect("open");
 
-    assert_eq!(storage.schema_version().unwrap(), 8);
+    assert_eq!(storage.schema_version().unwrap(), 13);
 
     // If meta row is removed, the getter surfaces an error.
     storage.raw().execute("DELETE FROM meta", []).unwrap();
@@ -106,6 +109,265 @@ fn rebuild_fts_repopulates_rows() {
     assert_eq!(fts_count, count_messages);
 }
 
+#[test]
+fn insert_records_message_tools() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("tools.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    let mut tool_msg = msg(1, 20);
+    tool_msg.role = MessageRole::Agent;
+    tool_msg.content = "[Tool: Bash - run tests]".into();
+    tool_msg.extra_json = serde_json::json!({"content": [{"type": "tool_use", "name": "Read"}]});
+    let conv = sample_conv(Some("ext-tools"), vec![msg(0, 10), tool_msg]);
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+
+    let mut stmt = storage
+        .raw()
+        .prepare(
+            "SELECT t.tool FROM message_tools t JOIN messages m ON m.id = t.message_id
+             WHERE m.idx = 1 ORDER BY t.tool",
+        )
+        .unwrap();
+    let tools: Vec<String> = stmt
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(tools, vec!["bash".to_string(), "read".to_string()]);
+}
+
+#[test]
+fn insert_records_tool_events_and_queries_them() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("events.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    let mut tool_msg = msg(1, 20);
+    tool_msg.role = MessageRole::Agent;
+    tool_msg.extra_json = serde_json::json!({
+        "message": {"content": [
+            {"type": "tool_use", "id": "t1", "name": "Edit", "input": {"file_path": "/workspace/demo/src/lib.rs"}},
+            {"type": "tool_use", "id": "t2", "name": "Bash", "input": {"command": "cargo test --all"}}
+        ]},
+        "cass_tool_results": [{"id": "t2", "is_error": true, "exit_code": 101}]
+    });
+    let conv = sample_conv(Some("ext-events"), vec![msg(0, 10), tool_msg]);
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+
+    let touched = storage
+        .tool_events(
+            &ToolEventFilter {
+                path: Some("src/lib.rs".into()),
+                ..Default::default()
+            },
+            10,
+        )
+        .unwrap();
+    assert_eq!(touched.len(), 1);
+    assert_eq!(touched[0].kind, "edit");
+    assert_eq!(touched[0].line_number, 2);
+    assert_eq!(touched[0].source_path, "/logs/demo.jsonl");
+
+    // Suffix matching is component-aligned
+    let partial = storage
+        .tool_events(
+            &ToolEventFilter {
+                path: Some("b.rs".into()),
+                ..Default::default()
+            },
+            10,
+        )
+        .unwrap();
+    assert!(partial.is_empty());
+
+    let failed = storage
+        .tool_events(
+            &ToolEventFilter {
+                kind: Some(ToolEventKind::Command),
+                failed: Some(true),
+                command_contains: Some("CARGO TEST".into()),
+                ..Default::default()
+            },
+            10,
+        )
+        .unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].command.as_deref(), Some("cargo test --all"));
+    assert_eq!(failed[0].exit_code, Some(101));
+    assert_eq!(failed[0].failed, Some(true));
+
+    let files = storage
+        .touched_files(&ToolEventFilter::default(), 10)
+        .unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].path, "/workspace/demo/src/lib.rs");
+    assert_eq!(
+        (files[0].sessions, files[0].edits, files[0].reads),
+        (1, 1, 0)
+    );
+}
+
+#[test]
+fn fetch_messages_with_snippets_attaches_file_paths() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("snippets.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    let mut edit = msg(1, 20);
+    edit.snippets = vec![Snippet {
+        id: None,
+        file_path: Some(PathBuf::from("src/lib.rs")),
+        start_line: Some(3),
+        end_line: Some(9),
+        language: Some("rust".into()),
+        snippet_text: Some("fn main() {}".into()),
+    }];
+    let conv = sample_conv(Some("ext-snippets"), vec![msg(0, 10), edit]);
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+
+    let session = coding_agent_search::diff::load_session(&storage, "/logs/demo.jsonl")
+        .unwrap()
+        .expect("session loaded by source path");
+    assert_eq!(session.messages.len(), 2);
+    assert!(session.messages[0].snippets.is_empty());
+    let snippets = &session.messages[1].snippets;
+    assert_eq!(snippets.len(), 1);
+    assert_eq!(snippets[0].file_path, Some(PathBuf::from("src/lib.rs")));
+    assert_eq!(snippets[0].start_line, Some(3));
+
+    assert!(
+        coding_agent_search::diff::load_session(&storage, "/logs/missing.jsonl")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn session_tags_survive_conversation_rebuild() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("tags.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    let conv = sample_conv(Some("ext-tags"), vec![msg(0, 10)]);
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+
+    let tags = vec!["auth-bug".to_string(), "infra".to_string()];
+    assert_eq!(
+        storage.add_session_tags("/logs/demo.jsonl", &tags).unwrap(),
+        1
+    );
+    assert_eq!(
+        storage
+            .add_session_tags("/logs/missing.jsonl", &tags)
+            .unwrap(),
+        0
+    );
+    assert_eq!(storage.session_tags("/logs/demo.jsonl").unwrap(), tags);
+
+    // Simulate `cass index --full`: derived rows are dropped, user tags are kept.
+    storage
+        .raw()
+        .execute_batch(
+            "DELETE FROM conversation_tags; DELETE FROM messages; DELETE FROM conversations;",
+        )
+        .unwrap();
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+
+    let reattached: i64 = storage
+        .raw()
+        .query_row("SELECT COUNT(*) FROM conversation_tags", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(reattached, 2);
+    assert_eq!(
+        storage.list_tags().unwrap(),
+        vec![("auth-bug".to_string(), 1), ("infra".to_string(), 1)]
+    );
+
+    storage
+        .remove_session_tags("/logs/demo.jsonl", &["infra".to_string()])
+        .unwrap();
+    assert_eq!(
+        storage.session_tags("/logs/demo.jsonl").unwrap(),
+        vec!["auth-bug".to_string()]
+    );
+    let tag_rows: i64 = storage
+        .raw()
+        .query_row("SELECT COUNT(*) FROM tags", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(tag_rows, 1, "unused tags are pruned");
+}
+
+#[test]
+fn scan_cursors_roundtrip_and_append_continues_indices() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("cursors.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let cursor = FileCursor {
+        inode: 42,
+        offset: 1024,
+        next_idx: 2,
+    };
+    let path = PathBuf::from("/logs/demo.jsonl");
+    storage
+        .save_scan_cursors(&[(path.clone(), cursor)])
+        .unwrap();
+    assert!(
+        storage.load_scan_cursors().unwrap().is_empty(),
+        "cursors without an indexed conversation are ignored"
+    );
+
+    let agent_id = storage.ensure_agent(&sample_agent()).unwrap();
+    let conv = sample_conv(Some("ext-cursor"), vec![msg(0, 10), msg(1, 20)]);
+    storage
+        .insert_conversation_tree(agent_id, None, &conv)
+        .unwrap();
+    assert_eq!(
+        storage.load_scan_cursors().unwrap().get(&path),
+        Some(&cursor)
+    );
+
+    // An appended tail (indices continuing at the cursor) only adds new messages
+    let tail = sample_conv(Some("ext-cursor"), vec![msg(2, 30)]);
+    let outcome = storage
+        .insert_conversation_tree(agent_id, None, &tail)
+        .unwrap();
+    assert_eq!(outcome.inserted_indices, vec![2]);
+    let count: i64 = storage
+        .raw()
+        .query_row("SELECT COUNT(*) FROM messages", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(count, 3);
+
+    let advanced = FileCursor {
+        offset: 2048,
+        next_idx: 3,
+        ..cursor
+    };
+    storage
+        .save_scan_cursors(&[(path.clone(), advanced)])
+        .unwrap();
+    assert_eq!(
+        storage.load_scan_cursors().unwrap().get(&path),
+        Some(&advanced)
+    );
+}
+
 #[test]
 fn transaction_rolls_back_on_duplicate_idx() {
     let tmp = tempfile::TempDir::new().unwrap();
@@ -591,7 +853,11 @@ fn migration_from_v1_applies_v2_and_v3() {
     let storage = SqliteStorage::open(&db_path).expect("open v1 db");
 
     // Verify migration completed
-    assert_eq!(storage.schema_version().unwrap(), 8, "should migrate to v8");
+    assert_eq!(
+        storage.schema_version().unwrap(),
+        13,
+        "should migrate to v13"
+    );
 
     // Verify FTS5 table was created
     let tables: Vec<String> = storage
@@ -707,7 +973,110 @@ fn migration_from_v2_applies_v3() {
     let storage = SqliteStorage::open(&db_path).expect("open v2 db");
 
     // Verify migration completed
-    assert_eq!(storage.schema_version().unwrap(), 8, "should migrate to v8");
+    assert_eq!(
+        storage.schema_version().unwrap(),
+        13,
+        "should migrate to v13"
+    );
+}
+
+#[test]
+fn migration_v13_moves_roo_sessions_off_cline() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let db_path = tmp.path().join("roo.db");
+    let mut storage = SqliteStorage::open(&db_path).expect("open");
+
+    let agent = |slug: &str| Agent {
+        id: None,
+        slug: slug.into(),
+        name: slug.into(),
+        version: None,
+        kind: AgentKind::Cli,
+    };
+    let cline = storage.ensure_agent(&agent("cline")).unwrap();
+    let roo = storage.ensure_agent(&agent("roo_code")).unwrap();
+    let roo_root = "/vscode/globalStorage/rooveterinaryinc.roo-cline/tasks";
+    let session = |slug: &str, task: &str, path: String| {
+        let mut conv = sample_conv(Some(task), vec![msg(0, 10), msg(1, 20)]);
+        conv.agent_slug = slug.into();
+        conv.source_path = PathBuf::from(path);
+        conv
+    };
+    for (agent_id, conv) in [
+        (
+            cline,
+            session("cline", "task-a", format!("{roo_root}/task-a")),
+        ),
+        (
+            cline,
+            session("cline", "task-b", format!("{roo_root}/task-b")),
+        ),
+        (
+            roo,
+            session("roo_code", "task-b", format!("{roo_root}/task-b")),
+        ),
+        (
+            cline,
+            session(
+                "cline",
+                "task-c",
+                "/vscode/globalStorage/saoudrizwan.claude-dev/tasks/task-c".into(),
+            ),
+        ),
+    ] {
+        storage
+            .insert_conversation_tree(agent_id, None, &conv)
+            .unwrap();
+    }
+    storage
+        .raw()
+        .execute(
+            "UPDATE meta SET value = '12' WHERE key = 'schema_version'",
+            [],
+        )
+        .unwrap();
+    drop(storage);
+
+    let mut storage = SqliteStorage::open(&db_path).expect("reopen");
+    assert_eq!(storage.schema_version().unwrap(), 13);
+
+    let sessions = |table_sql: &str| -> Vec<(String, String)> {
+        let mut stmt = storage.raw().prepare(table_sql).unwrap();
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    };
+    assert_eq!(
+        sessions(
+            "SELECT a.slug, c.external_id FROM conversations c
+             JOIN agents a ON a.id = c.agent_id ORDER BY c.external_id"
+        ),
+        vec![
+            ("roo_code".to_string(), "task-a".to_string()),
+            ("roo_code".to_string(), "task-b".to_string()),
+            ("cline".to_string(), "task-c".to_string()),
+        ]
+    );
+    assert_eq!(
+        sessions("SELECT DISTINCT agent, source_path FROM fts_messages ORDER BY source_path"),
+        vec![
+            ("roo_code".to_string(), format!("{roo_root}/task-a")),
+            ("roo_code".to_string(), format!("{roo_root}/task-b")),
+            (
+                "cline".to_string(),
+                "/vscode/globalStorage/saoudrizwan.claude-dev/tasks/task-c".to_string()
+            ),
+        ]
+    );
+    let messages: i64 = storage
+        .raw()
+        .query_row("SELECT COUNT(*) FROM messages", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(messages, 6, "the duplicate cline copy's messages are gone");
+
+    assert!(storage.take_rebuild_request().unwrap());
+    assert!(!storage.take_rebuild_request().unwrap());
 }
 
 #[test]
@@ -1068,7 +1437,11 @@ fn migration_from_v3_creates_sources_table() {
     let storage = SqliteStorage::open(&db_path).expect("open v3 db");
 
     // Verify migration completed
-    assert_eq!(storage.schema_version().unwrap(), 8, "should migrate to v8");
+    assert_eq!(
+        storage.schema_version().unwrap(),
+        13,
+        "should migrate to v13"
+    );
 
     // Verify sources table was created with local source
     let sources = storage.list_sources().expect("list_sources");
@@ -171,6 +171,68 @@ fn tui_headless_reset_state_flag() {
     cmd2.assert().success();
 }
 
+// ============================================================
+// Keymap / Theme Config Tests
+// ============================================================
+
+fn write_tui_config(temp_home: &Path, contents: &str) {
+    let dir = temp_home.join(".config/cass");
+    fs::create_dir_all(&dir).expect("create config dir");
+    fs::write(dir.join("tui.toml"), contents).expect("write tui.toml");
+}
+
+#[test]
+fn tui_print_keymap_lists_default_bindings() {
+    let tmp = TempDir::new().unwrap();
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["tui", "--print-keymap"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[keys]"))
+        .stdout(predicate::str::contains("quit = [\"Esc\", \"F10\"]"))
+        .stdout(predicate::str::contains("palette.reload_index = []"))
+        .stdout(predicate::str::contains("built-in defaults"));
+}
+
+#[test]
+fn tui_print_keymap_applies_config_overrides() {
+    let tmp = TempDir::new().unwrap();
+    write_tui_config(
+        tmp.path(),
+        "[keys]\nquit = \"ctrl+q\"\npalette.filter_today = \"alt+t\"\n\n[theme]\npreset = \"nord\"\n",
+    );
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["tui", "--print-keymap"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("quit = [\"Ctrl+q\"]"))
+        .stdout(predicate::str::contains(
+            "palette.filter_today = [\"Alt+t\"]",
+        ))
+        .stdout(predicate::str::contains("tui.toml"));
+}
+
+#[test]
+fn tui_rejects_invalid_config_on_startup() {
+    let tmp = TempDir::new().unwrap();
+    let data_dir = tmp.path().join("data");
+    setup_empty_data_dir(&data_dir);
+    write_tui_config(
+        tmp.path(),
+        "[keys]\nqiut = \"ctrl+q\"\n\n[theme]\naccent = \"not-a-color\"\n",
+    );
+
+    let mut cmd = base_cmd(tmp.path());
+    cmd.args(["tui", "--once", "--data-dir", data_dir.to_str().unwrap()]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid TUI config"))
+        .stderr(predicate::str::contains("keys.qiut: unknown action"))
+        .stderr(predicate::str::contains("theme.accent: invalid color"));
+}
+
 // ============================================================
 // Logging Tests
 // ============================================================